@@ -0,0 +1,42 @@
+//! Shared parts of drag-and-drop: only dropped files are accepted, sent by
+//! the source as a `text/uri-list`.
+
+use std::{os::unix::ffi::OsStringExt, path::PathBuf};
+
+/// Returns the local paths in a `text/uri-list`, skipping anything that
+/// isn't a `file://` URI.
+pub(crate) fn parse_uri_list(data: &[u8]) -> Vec<PathBuf> {
+    String::from_utf8_lossy(data)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|uri| uri.strip_prefix("file://"))
+        // Anything before the path is a host name, normally empty
+        .filter_map(|rest| rest.find('/').map(|i| &rest[i..]))
+        .map(|path| PathBuf::from(std::ffi::OsString::from_vec(percent_decode(path))))
+        .collect()
+}
+
+/// Decodes `%XX` escapes; malformed ones are kept as they are.
+fn percent_decode(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    out
+}
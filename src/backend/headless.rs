@@ -0,0 +1,397 @@
+//! Headless backend: windows drawn in memory, given scripted events.
+//!
+//! Dialogs shown inside [`render`], or `Headless::run` with the
+//! `test-backend` feature, get a window of this backend in place of one on
+//! the display, so they can be drawn, and what they do with input tested,
+//! where there is no display server.
+
+#[cfg(feature = "test-backend")]
+use std::path::PathBuf;
+use std::{cell::RefCell, collections::VecDeque, rc::Rc, time::Instant};
+
+#[cfg(feature = "test-backend")]
+use super::{CursorPos, KeyEvent, Modifiers, MouseButton, ScrollDirection};
+use super::{CursorShape, DisplayConnection, Monitor, Window, WindowEvent, WindowHandle};
+#[cfg(feature = "test-backend")]
+use crate::render::Rgba;
+use crate::{
+    error::Error,
+    render::{Canvas, Damage},
+};
+
+/// Input played to a headless dialog. Positions are in the dialog's
+/// pixels, at its scale.
+#[cfg(feature = "test-backend")]
+#[derive(Debug, Clone)]
+pub enum TestEvent {
+    /// Types the text, a character at a time.
+    Type(String),
+    /// Presses and releases a key, given as its X keysym.
+    Key(u32),
+    /// Moves the pointer.
+    MoveTo(i16, i16),
+    /// Moves the pointer and clicks the left button there.
+    Click(i16, i16),
+    ScrollUp,
+    ScrollDown,
+    /// Drops files onto the dialog.
+    Drop(Vec<PathBuf>),
+    /// Resizes the dialog, in pixels.
+    Resize(u32, u32),
+    /// Closes the window.
+    Close,
+}
+
+#[cfg(feature = "test-backend")]
+impl TestEvent {
+    pub const BACKSPACE: TestEvent = TestEvent::Key(0xff08);
+    pub const DOWN: TestEvent = TestEvent::Key(0xff54);
+    pub const END: TestEvent = TestEvent::Key(0xff57);
+    pub const ENTER: TestEvent = TestEvent::Key(0xff0d);
+    pub const ESCAPE: TestEvent = TestEvent::Key(0xff1b);
+    pub const HOME: TestEvent = TestEvent::Key(0xff50);
+    pub const LEFT: TestEvent = TestEvent::Key(0xff51);
+    pub const RIGHT: TestEvent = TestEvent::Key(0xff53);
+    pub const SPACE: TestEvent = TestEvent::Key(0x20);
+    pub const TAB: TestEvent = TestEvent::Key(0xff09);
+    pub const UP: TestEvent = TestEvent::Key(0xff52);
+
+    /// The window events the input comes in as.
+    fn window_events(self) -> Vec<WindowEvent> {
+        let key = |keysym| {
+            KeyEvent {
+                keysym,
+                modifiers: Modifiers::empty(),
+            }
+        };
+        match self {
+            TestEvent::Type(text) => text.chars().map(WindowEvent::TextInput).collect(),
+            // Printable keys come as text, as the display backends send them
+            TestEvent::Key(keysym @ 0x20..=0x7e) => {
+                vec![
+                    WindowEvent::TextInput(char::from(keysym as u8)),
+                    WindowEvent::KeyRelease(key(keysym)),
+                ]
+            }
+            TestEvent::Key(keysym) => {
+                vec![
+                    WindowEvent::KeyPress(key(keysym)),
+                    WindowEvent::KeyRelease(key(keysym)),
+                ]
+            }
+            TestEvent::MoveTo(x, y) => {
+                vec![WindowEvent::CursorMove(CursorPos {
+                    x,
+                    y,
+                })]
+            }
+            TestEvent::Click(x, y) => {
+                vec![
+                    WindowEvent::CursorMove(CursorPos {
+                        x,
+                        y,
+                    }),
+                    WindowEvent::ButtonPress(MouseButton::Left, Modifiers::empty()),
+                    WindowEvent::ButtonRelease(MouseButton::Left, Modifiers::empty()),
+                ]
+            }
+            TestEvent::ScrollUp => vec![WindowEvent::Scroll(ScrollDirection::Up)],
+            TestEvent::ScrollDown => vec![WindowEvent::Scroll(ScrollDirection::Down)],
+            TestEvent::Drop(paths) => vec![WindowEvent::Drop(paths)],
+            TestEvent::Resize(width, height) => vec![WindowEvent::Resized(width, height)],
+            TestEvent::Close => vec![WindowEvent::CloseRequested],
+        }
+    }
+}
+
+/// What a dialog last drew.
+#[cfg(feature = "test-backend")]
+#[derive(Clone)]
+pub struct Frame {
+    canvas: Canvas,
+}
+
+#[cfg(feature = "test-backend")]
+impl Frame {
+    pub fn width(&self) -> u32 {
+        self.canvas.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.canvas.height()
+    }
+
+    /// Returns the color at a pixel, or `None` outside the frame.
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Rgba> {
+        let color = self.canvas.pixmap.pixel(x, y)?.demultiply();
+        Some(Rgba::new(
+            color.red(),
+            color.green(),
+            color.blue(),
+            color.alpha(),
+        ))
+    }
+}
+
+/// Events left to play, and what was drawn, shared by the windows of a run
+struct Script {
+    events: VecDeque<WindowEvent>,
+    scale: f32,
+    frame: Option<Canvas>,
+}
+
+impl Script {
+    fn new(events: VecDeque<WindowEvent>, scale: f32) -> Rc<RefCell<Self>> {
+        Rc::new(RefCell::new(Self {
+            events,
+            scale,
+            frame: None,
+        }))
+    }
+}
+
+thread_local! {
+    static SCRIPT: RefCell<Option<Rc<RefCell<Script>>>> = const { RefCell::new(None) };
+}
+
+/// Returns whether dialogs on this thread are being shown headless.
+pub(crate) fn active() -> bool {
+    SCRIPT.with_borrow(Option::is_some)
+}
+
+/// Runs `show` with the dialogs it shows on this thread given `script`,
+/// putting back the one before afterwards.
+fn run_script<R>(script: &Rc<RefCell<Script>>, show: impl FnOnce() -> R) -> R {
+    let previous = SCRIPT.replace(Some(script.clone()));
+    let result = show();
+    SCRIPT.set(previous);
+    result
+}
+
+/// Runs `show` with the dialogs it shows drawn at `scale` in memory and
+/// closed as soon as they wait for input, returning what the last one
+/// drew along with what `show` returned.
+pub(crate) fn render<R>(scale: f32, show: impl FnOnce() -> R) -> (R, Option<Canvas>) {
+    let script = Script::new(VecDeque::new(), scale);
+    let result = run_script(&script, show);
+    let frame = script.borrow_mut().frame.take();
+    (result, frame)
+}
+
+/// Shows dialogs in memory instead of on the display, handing them a
+/// script of input, for testing what they do with it.
+///
+/// ```
+/// use zenity_rs::{EntryResult, Headless, TestEvent};
+///
+/// let mut headless = Headless::new([TestEvent::Type("Ada".into()), TestEvent::ENTER]);
+/// let result = headless.run(|| zenity_rs::entry().text("Name:").show())?;
+/// assert!(matches!(result, EntryResult::Text(name) if name == "Ada"));
+/// assert!(headless.last_frame().is_some());
+/// # Ok::<(), zenity_rs::Error>(())
+/// ```
+///
+/// A dialog is given the next input each time it waits for some, and is
+/// closed once the script runs out, so every run ends. Dialogs in the run
+/// are never drawn in the terminal.
+#[cfg(feature = "test-backend")]
+pub struct Headless {
+    script: Rc<RefCell<Script>>,
+}
+
+#[cfg(feature = "test-backend")]
+impl Headless {
+    pub fn new(events: impl IntoIterator<Item = TestEvent>) -> Self {
+        let events = events
+            .into_iter()
+            .flat_map(TestEvent::window_events)
+            .collect();
+        Self {
+            script: Script::new(events, 1.0),
+        }
+    }
+
+    /// Sets the scale the dialogs are drawn at (default: 1.0).
+    pub fn scale(self, scale: f32) -> Self {
+        self.script.borrow_mut().scale = scale;
+        self
+    }
+
+    /// Runs `show` on this thread with the dialogs it shows drawn in
+    /// memory and given the script's input.
+    pub fn run<R>(&mut self, show: impl FnOnce() -> R) -> R {
+        run_script(&self.script, show)
+    }
+
+    /// Returns what the last dialog drew last.
+    pub fn last_frame(&self) -> Option<Frame> {
+        let canvas = self.script.borrow().frame.clone()?;
+        Some(Frame {
+            canvas,
+        })
+    }
+}
+
+/// Connection to the script of the run on this thread.
+#[derive(Clone)]
+pub(crate) struct Connection {
+    script: Rc<RefCell<Script>>,
+}
+
+impl DisplayConnection for Connection {
+    type Window = HeadlessWindow;
+
+    fn connect() -> Result<Self, Error> {
+        let script = SCRIPT
+            .with_borrow(Clone::clone)
+            .ok_or(Error::BackendUnavailable {
+                tried: Vec::new(),
+            })?;
+        Ok(Self {
+            script,
+        })
+    }
+
+    fn create_window(
+        &self,
+        _width: u16,
+        _height: u16,
+        _monitor: Monitor,
+        _translucent: bool,
+    ) -> Result<HeadlessWindow, Error> {
+        Ok(HeadlessWindow {
+            conn: self.clone(),
+            waited: false,
+            closed: false,
+        })
+    }
+}
+
+pub(crate) struct HeadlessWindow {
+    conn: Connection,
+    /// Whether the dialog has waited for an event, and so is given the
+    /// close at the end of the script by waiting for it
+    waited: bool,
+    /// Whether polling has handed out the close at the end of the script
+    closed: bool,
+}
+
+impl HeadlessWindow {
+    /// Returns the connection the window was made on.
+    pub fn connection(&self) -> Connection {
+        self.conn.clone()
+    }
+}
+
+impl Window for HeadlessWindow {
+    fn set_title(&mut self, _title: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_contents_damaged(&mut self, canvas: &Canvas, _damage: &[Damage]) -> Result<(), Error> {
+        self.conn.script.borrow_mut().frame = Some(canvas.clone());
+        Ok(())
+    }
+
+    fn show(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn wait_for_event(&mut self) -> Result<WindowEvent, Error> {
+        self.waited = true;
+        Ok(self
+            .conn
+            .script
+            .borrow_mut()
+            .events
+            .pop_front()
+            .unwrap_or(WindowEvent::CloseRequested))
+    }
+
+    fn poll_for_event(&mut self) -> Result<Option<WindowEvent>, Error> {
+        // Input comes one event per wait, as a user's does with time in
+        // between, so what a dialog drains after waking is never swallowed
+        if self.waited {
+            return Ok(None);
+        }
+        if let Some(event) = self.conn.script.borrow_mut().events.pop_front() {
+            return Ok(Some(event));
+        }
+        // Dialogs which only poll are closed too, once, so they don't
+        // drain the queue forever
+        if self.closed {
+            return Ok(None);
+        }
+        self.closed = true;
+        Ok(Some(WindowEvent::CloseRequested))
+    }
+
+    fn wait_for_event_or(
+        &mut self,
+        _fds: &[libc::pollfd],
+        _deadline: Option<Instant>,
+    ) -> Result<Option<WindowEvent>, Error> {
+        // Scripted events never wait, so there is nothing to wait for
+        self.wait_for_event().map(Some)
+    }
+
+    fn start_drag(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn scale_factor(&self) -> f32 {
+        self.conn.script.borrow().scale
+    }
+
+    fn set_cursor(&mut self, _shape: CursorShape) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_resizable(&mut self, _min_width: u16, _min_height: u16) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_size(&mut self, _width: u16, _height: u16) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn needs_decorations(&self) -> bool {
+        false
+    }
+
+    fn set_position(&mut self, _x: i32, _y: i32) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn monitor_area(&self) -> Option<(i32, i32, u32, u32)> {
+        None
+    }
+
+    fn pointer_position(&self) -> Option<(i32, i32)> {
+        None
+    }
+
+    fn primary_selection(&mut self) -> Result<Option<String>, Error> {
+        Ok(None)
+    }
+
+    fn set_parent(&mut self, _parent: &WindowHandle) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_icon(&mut self, _icon: &Canvas) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_frame_margin(&mut self, _margin: u16) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn request_frame(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn set_opacity(&mut self, _opacity: f32) -> Result<(), Error> {
+        Ok(())
+    }
+}
@@ -1,11 +1,19 @@
+pub(crate) mod dnd;
+pub(crate) mod headless;
+mod touch;
 #[cfg(feature = "wayland")]
 pub(crate) mod wayland;
 #[cfg(feature = "x11")]
 pub(crate) mod x11;
 
+use std::{cell::RefCell, fmt, path::PathBuf, str::FromStr, time::Instant};
+
 use bitflags::bitflags;
 
-use crate::{error::Error, render::Canvas};
+use crate::{
+    error::Error,
+    render::{Canvas, Damage},
+};
 
 /// Default scale factor for rendering
 pub(crate) const DEFAULT_SCALE: f32 = 1.0;
@@ -15,7 +23,15 @@ pub(crate) trait DisplayConnection: Sized {
     type Window: Window;
 
     fn connect() -> Result<Self, Error>;
-    fn create_window(&self, width: u16, height: u16) -> Result<Self::Window, Error>;
+    /// Creates a window; a `translucent` one shows what is behind it
+    /// through its partly transparent pixels.
+    fn create_window(
+        &self,
+        width: u16,
+        height: u16,
+        monitor: Monitor,
+        translucent: bool,
+    ) -> Result<Self::Window, Error>;
 }
 
 /// Cursor shape types.
@@ -26,18 +42,157 @@ pub(crate) enum CursorShape {
     Default,
     /// Text input (I-beam) cursor.
     Text,
+    /// Pointing hand, over links.
+    Pointer,
+}
+
+/// What a dialog is shown through, as named in errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    X11,
+    Wayland,
+    /// Drawn as text in the terminal, when there is no display server.
+    Terminal,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Backend::X11 => "X11",
+            Backend::Wayland => "Wayland",
+            Backend::Terminal => "terminal",
+        })
+    }
+}
+
+/// The monitor a dialog is shown on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Monitor {
+    /// Whichever one the window manager picks.
+    #[default]
+    Default,
+    /// The monitor with the given index, counting from 0.
+    Index(usize),
+    /// The primary monitor.
+    Primary,
+    /// The monitor the mouse pointer is on.
+    Cursor,
+}
+
+impl FromStr for Monitor {
+    type Err = String;
+
+    /// Parses a monitor index, `primary` or `cursor`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "primary" => Ok(Monitor::Primary),
+            "cursor" | "mouse" => Ok(Monitor::Cursor),
+            _ => {
+                s.parse()
+                    .map(Monitor::Index)
+                    .map_err(|_| format!("invalid monitor '{s}'"))
+            }
+        }
+    }
+}
+
+/// A window of another program that a dialog can be attached to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WindowHandle {
+    /// An X11 window id.
+    X11(u32),
+    /// A handle exported through the Wayland xdg_foreign protocol.
+    Wayland(String),
+}
+
+impl FromStr for WindowHandle {
+    type Err = String;
+
+    /// Parses `x11:ID` or `wayland:HANDLE`, as used by desktop portals, or
+    /// a bare X11 window id in decimal or `0x` hex.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_id = |id: &str, radix| {
+            u32::from_str_radix(id, radix).map_err(|_| format!("invalid window '{s}'"))
+        };
+        if let Some(handle) = s.strip_prefix("wayland:") {
+            Ok(WindowHandle::Wayland(handle.to_string()))
+        } else if let Some(id) = s.strip_prefix("x11:") {
+            parse_id(id, 16).map(WindowHandle::X11)
+        } else if let Some(id) = s.strip_prefix("0x") {
+            parse_id(id, 16).map(WindowHandle::X11)
+        } else {
+            parse_id(s, 10).map(WindowHandle::X11)
+        }
+    }
+}
+
+impl fmt::Display for WindowHandle {
+    /// Formats the handle the way desktop portals take it.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowHandle::X11(id) => write!(f, "x11:{id:x}"),
+            WindowHandle::Wayland(handle) => write!(f, "wayland:{handle}"),
+        }
+    }
 }
 
 /// Trait for interacting with a window.
 pub(crate) trait Window {
     fn set_title(&mut self, title: &str) -> Result<(), Error>;
-    fn set_contents(&mut self, canvas: &Canvas) -> Result<(), Error>;
+    fn set_contents(&mut self, canvas: &Canvas) -> Result<(), Error> {
+        self.set_contents_damaged(canvas, &[Damage::whole(canvas)])
+    }
+    /// Shows `canvas`, of which only the `damage` areas changed since the
+    /// contents before, if they were the same size.
+    fn set_contents_damaged(&mut self, canvas: &Canvas, damage: &[Damage]) -> Result<(), Error>;
     fn show(&mut self) -> Result<(), Error>;
     fn wait_for_event(&mut self) -> Result<WindowEvent, Error>;
     fn poll_for_event(&mut self) -> Result<Option<WindowEvent>, Error>;
+    /// Waits for an event, or until one of `fds` is ready or `deadline`
+    /// has passed, returning `None` for either of those.
+    fn wait_for_event_or(
+        &mut self,
+        fds: &[libc::pollfd],
+        deadline: Option<Instant>,
+    ) -> Result<Option<WindowEvent>, Error>;
     fn start_drag(&mut self) -> Result<(), Error>;
     fn scale_factor(&self) -> f32;
     fn set_cursor(&mut self, shape: CursorShape) -> Result<(), Error>;
+    /// Lets the user resize the window down to `min_width`x`min_height`
+    /// (logical pixels). Windows are fixed-size until this is called.
+    fn set_resizable(&mut self, min_width: u16, min_height: u16) -> Result<(), Error>;
+    /// Changes the window's logical size.
+    fn set_size(&mut self, width: u16, height: u16) -> Result<(), Error>;
+    /// Returns true if nothing else draws a title bar for the window.
+    fn needs_decorations(&self) -> bool;
+    /// Moves the window's top-left corner to (`x`, `y`) on the screen.
+    /// Ignored where clients can't place their windows.
+    fn set_position(&mut self, x: i32, y: i32) -> Result<(), Error>;
+    /// Returns the bounds (x, y, width, height) of the monitor the window
+    /// was created for, if the backend knows them.
+    fn monitor_area(&self) -> Option<(i32, i32, u32, u32)>;
+    /// Returns the pointer position on the screen, if the backend knows it.
+    fn pointer_position(&self) -> Option<(i32, i32)>;
+    /// Returns the text of the primary selection, if there is any.
+    fn primary_selection(&mut self) -> Result<Option<String>, Error>;
+    /// Attaches the window to another program's window, keeping it above
+    /// and centered over it. Handles for the other display server are
+    /// ignored.
+    fn set_parent(&mut self, parent: &WindowHandle) -> Result<(), Error>;
+    /// Gives the window manager an icon for the window, drawn at the
+    /// window's scale. Ignored where it has no way to take one.
+    fn set_icon(&mut self, icon: &Canvas) -> Result<(), Error>;
+    /// Sets aside a border of `margin` logical pixels, such as a drop
+    /// shadow, that isn't part of the window: it takes no input and the
+    /// window is placed and snapped without it. Sizes still include it.
+    fn set_frame_margin(&mut self, margin: u16) -> Result<(), Error>;
+    /// Asks for a `RedrawRequested` once the display is ready for the next
+    /// frame of an animation. On Wayland the request goes out with the next
+    /// `set_contents`.
+    fn request_frame(&mut self) -> Result<(), Error>;
+    /// Sets how opaque the whole window is drawn, from 0.0 to 1.0. Takes
+    /// effect with the next `set_contents` where the window draws it.
+    fn set_opacity(&mut self, opacity: f32) -> Result<(), Error>;
 }
 
 /// Events that can be emitted by a window.
@@ -55,6 +210,14 @@ pub(crate) enum WindowEvent {
     KeyPress(KeyEvent),
     KeyRelease(KeyEvent),
     TextInput(char),
+    /// A middle click at the position asked to paste the primary selection.
+    PrimaryPaste(CursorPos, String),
+    /// Files were dropped onto the window.
+    Drop(Vec<PathBuf>),
+    /// The window was resized; carries the new physical width and height.
+    Resized(u32, u32),
+    /// New text for the dialog, set through its `DialogHandle`.
+    SetText(String),
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -101,6 +264,57 @@ pub(crate) enum AnyWindow {
     X11(Box<x11::X11Window>),
     #[cfg(feature = "wayland")]
     Wayland(Box<wayland::WaylandWindow>),
+    Headless(Box<headless::HeadlessWindow>),
+}
+
+/// Type-erased connection that can be either X11 or Wayland.
+#[derive(Clone)]
+pub(crate) enum AnyConnection {
+    #[cfg(feature = "x11")]
+    X11(x11::Connection),
+    #[cfg(feature = "wayland")]
+    Wayland(wayland::Connection),
+    Headless(headless::Connection),
+}
+
+impl AnyConnection {
+    fn create_window(
+        &self,
+        width: u16,
+        height: u16,
+        monitor: Monitor,
+        translucent: bool,
+    ) -> Result<AnyWindow, Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyConnection::X11(conn) => {
+                let w = conn.create_window(width, height, monitor, translucent)?;
+                Ok(AnyWindow::X11(Box::new(w)))
+            }
+            #[cfg(feature = "wayland")]
+            AnyConnection::Wayland(conn) => {
+                let w = conn.create_window(width, height, monitor, translucent)?;
+                Ok(AnyWindow::Wayland(Box::new(w)))
+            }
+            AnyConnection::Headless(conn) => {
+                let w = conn.create_window(width, height, monitor, translucent)?;
+                Ok(AnyWindow::Headless(Box::new(w)))
+            }
+        }
+    }
+}
+
+impl AnyWindow {
+    /// Returns the connection the window was made on.
+    fn connection(&self) -> AnyConnection {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => AnyConnection::X11(w.connection()),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => AnyConnection::Wayland(w.connection()),
+            AnyWindow::Headless(w) => AnyConnection::Headless(w.connection()),
+        }
+    }
 }
 
 impl Window for AnyWindow {
@@ -110,15 +324,17 @@ impl Window for AnyWindow {
             AnyWindow::X11(w) => w.set_title(title),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.set_title(title),
+            AnyWindow::Headless(w) => w.set_title(title),
         }
     }
 
-    fn set_contents(&mut self, canvas: &Canvas) -> Result<(), Error> {
+    fn set_contents_damaged(&mut self, canvas: &Canvas, damage: &[Damage]) -> Result<(), Error> {
         match self {
             #[cfg(feature = "x11")]
-            AnyWindow::X11(w) => w.set_contents(canvas),
+            AnyWindow::X11(w) => w.set_contents_damaged(canvas, damage),
             #[cfg(feature = "wayland")]
-            AnyWindow::Wayland(w) => w.set_contents(canvas),
+            AnyWindow::Wayland(w) => w.set_contents_damaged(canvas, damage),
+            AnyWindow::Headless(w) => w.set_contents_damaged(canvas, damage),
         }
     }
 
@@ -128,6 +344,7 @@ impl Window for AnyWindow {
             AnyWindow::X11(w) => w.show(),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.show(),
+            AnyWindow::Headless(w) => w.show(),
         }
     }
 
@@ -137,6 +354,7 @@ impl Window for AnyWindow {
             AnyWindow::X11(w) => w.wait_for_event(),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.wait_for_event(),
+            AnyWindow::Headless(w) => w.wait_for_event(),
         }
     }
 
@@ -146,6 +364,21 @@ impl Window for AnyWindow {
             AnyWindow::X11(w) => w.poll_for_event(),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.poll_for_event(),
+            AnyWindow::Headless(w) => w.poll_for_event(),
+        }
+    }
+
+    fn wait_for_event_or(
+        &mut self,
+        fds: &[libc::pollfd],
+        deadline: Option<Instant>,
+    ) -> Result<Option<WindowEvent>, Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.wait_for_event_or(fds, deadline),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.wait_for_event_or(fds, deadline),
+            AnyWindow::Headless(w) => w.wait_for_event_or(fds, deadline),
         }
     }
 
@@ -155,6 +388,7 @@ impl Window for AnyWindow {
             AnyWindow::X11(w) => w.start_drag(),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.start_drag(),
+            AnyWindow::Headless(w) => w.start_drag(),
         }
     }
 
@@ -164,6 +398,7 @@ impl Window for AnyWindow {
             AnyWindow::X11(w) => w.scale_factor(),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.scale_factor(),
+            AnyWindow::Headless(w) => w.scale_factor(),
         }
     }
 
@@ -173,45 +408,256 @@ impl Window for AnyWindow {
             AnyWindow::X11(w) => w.set_cursor(shape),
             #[cfg(feature = "wayland")]
             AnyWindow::Wayland(w) => w.set_cursor(shape),
+            AnyWindow::Headless(w) => w.set_cursor(shape),
+        }
+    }
+
+    fn set_resizable(&mut self, min_width: u16, min_height: u16) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.set_resizable(min_width, min_height),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.set_resizable(min_width, min_height),
+            AnyWindow::Headless(w) => w.set_resizable(min_width, min_height),
+        }
+    }
+
+    fn set_size(&mut self, width: u16, height: u16) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.set_size(width, height),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.set_size(width, height),
+            AnyWindow::Headless(w) => w.set_size(width, height),
+        }
+    }
+
+    fn needs_decorations(&self) -> bool {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.needs_decorations(),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.needs_decorations(),
+            AnyWindow::Headless(w) => w.needs_decorations(),
+        }
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.set_position(x, y),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.set_position(x, y),
+            AnyWindow::Headless(w) => w.set_position(x, y),
+        }
+    }
+
+    fn monitor_area(&self) -> Option<(i32, i32, u32, u32)> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.monitor_area(),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.monitor_area(),
+            AnyWindow::Headless(w) => w.monitor_area(),
+        }
+    }
+
+    fn pointer_position(&self) -> Option<(i32, i32)> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.pointer_position(),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.pointer_position(),
+            AnyWindow::Headless(w) => w.pointer_position(),
+        }
+    }
+
+    fn primary_selection(&mut self) -> Result<Option<String>, Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.primary_selection(),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.primary_selection(),
+            AnyWindow::Headless(w) => w.primary_selection(),
+        }
+    }
+
+    fn set_parent(&mut self, parent: &WindowHandle) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.set_parent(parent),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.set_parent(parent),
+            AnyWindow::Headless(w) => w.set_parent(parent),
+        }
+    }
+
+    fn set_icon(&mut self, icon: &Canvas) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.set_icon(icon),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.set_icon(icon),
+            AnyWindow::Headless(w) => w.set_icon(icon),
+        }
+    }
+
+    fn set_frame_margin(&mut self, margin: u16) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.set_frame_margin(margin),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.set_frame_margin(margin),
+            AnyWindow::Headless(w) => w.set_frame_margin(margin),
+        }
+    }
+
+    fn request_frame(&mut self) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.request_frame(),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.request_frame(),
+            AnyWindow::Headless(w) => w.request_frame(),
+        }
+    }
+
+    fn set_opacity(&mut self, opacity: f32) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "x11")]
+            AnyWindow::X11(w) => w.set_opacity(opacity),
+            #[cfg(feature = "wayland")]
+            AnyWindow::Wayland(w) => w.set_opacity(opacity),
+            AnyWindow::Headless(w) => w.set_opacity(opacity),
         }
     }
 }
 
+/// Waits until one of `fds` is ready or `deadline` has passed, for as long
+/// as it takes without one. Returns true if one of `fds` is ready.
+pub(crate) fn poll(fds: &mut [libc::pollfd], deadline: Option<Instant>) -> bool {
+    let timeout = deadline.map_or(-1, |deadline| {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        timeout
+            .as_micros()
+            .div_ceil(1000)
+            .try_into()
+            .unwrap_or(i32::MAX)
+    });
+    // SAFETY: `fds` is a valid slice of pollfd structures.
+    let ready = unsafe { libc::poll(fds.as_mut_ptr(), fds.len() as libc::nfds_t, timeout) };
+    ready > 0
+}
+
+thread_local! {
+    /// Connection kept open for the windows made on this thread: `None`
+    /// unless sharing, and `Some(None)` until the first window connects.
+    static SHARED: RefCell<Option<Option<AnyConnection>>> = const { RefCell::new(None) };
+}
+
+/// Starts or stops sharing one connection between the windows made on this
+/// thread, returning the sharing state before, to be put back later.
+pub(crate) fn share_connection(
+    shared: Option<Option<AnyConnection>>,
+) -> Option<Option<AnyConnection>> {
+    SHARED.replace(shared)
+}
+
 /// Creates a window using the best available backend.
 /// Prefers Wayland, falls back to X11.
-pub(crate) fn create_window(width: u16, height: u16) -> Result<AnyWindow, Error> {
+///
+/// While a connection is shared, windows after the first are made on the
+/// first one's connection.
+pub(crate) fn create_window(
+    width: u16,
+    height: u16,
+    monitor: Monitor,
+    translucent: bool,
+) -> Result<AnyWindow, Error> {
+    // Headless runs are kept apart from any connection being shared
+    if headless::active() {
+        return AnyConnection::Headless(headless::Connection::connect()?).create_window(
+            width,
+            height,
+            monitor,
+            translucent,
+        );
+    }
+
+    let shared = SHARED.with_borrow(Clone::clone);
+    if let Some(Some(conn)) = &shared {
+        return conn.create_window(width, height, monitor, translucent);
+    }
+    let window = connect_window(width, height, monitor, translucent)?;
+    if shared.is_some() {
+        SHARED.set(Some(Some(window.connection())));
+    }
+    Ok(window)
+}
+
+/// Connects to the best available display server for a window.
+fn connect_window(
+    width: u16,
+    height: u16,
+    monitor: Monitor,
+    translucent: bool,
+) -> Result<AnyWindow, Error> {
+    // Why each backend tried failed, for when none works
+    let mut tried = Vec::new();
+
     #[cfg(feature = "wayland")]
-    if let Some(window) = try_wayland(width, height) {
-        return Ok(window);
+    match try_wayland(width, height, monitor, translucent) {
+        Ok(Some(window)) => return Ok(window),
+        Ok(None) => {}
+        Err(e) => tried.push((Backend::Wayland, e)),
     }
 
     #[cfg(feature = "x11")]
-    return try_x11(width, height);
+    match try_x11(width, height, monitor, translucent) {
+        Ok(window) => return Ok(window),
+        Err(e) => tried.push((Backend::X11, e)),
+    }
 
     #[cfg(not(any(feature = "x11", feature = "wayland")))]
     compile_error!("At least one of 'x11' or 'wayland' features must be enabled");
+
+    Err(Error::BackendUnavailable {
+        tried,
+    })
 }
 
+/// Whether there is a display server for a compiled-in backend, found the
+/// way [`create_window`] looks for one.
+#[cfg(feature = "tui")]
+pub(crate) fn display_available() -> bool {
+    #[cfg(feature = "wayland")]
+    if find_wayland_socket().is_some() {
+        return true;
+    }
+    #[cfg(feature = "x11")]
+    if std::env::var_os("DISPLAY").is_some_and(|display| !display.is_empty()) {
+        return true;
+    }
+    false
+}
+
+/// Makes a window on the Wayland compositor, if there is one.
 #[cfg(feature = "wayland")]
-fn try_wayland(width: u16, height: u16) -> Option<AnyWindow> {
-    let socket_name = find_wayland_socket()?;
+fn try_wayland(
+    width: u16,
+    height: u16,
+    monitor: Monitor,
+    translucent: bool,
+) -> Result<Option<AnyWindow>, Error> {
+    let Some(socket_name) = find_wayland_socket() else {
+        return Ok(None);
+    };
 
     let _guard = SocketGuard::new(&socket_name);
 
-    match wayland::Connection::connect() {
-        Ok(conn) => {
-            match conn.create_window(width, height) {
-                Ok(w) => {
-                    std::mem::forget(conn);
-                    return Some(AnyWindow::Wayland(Box::new(w)));
-                }
-                Err(e) => eprintln!("Wayland window creation failed: {e}"),
-            }
-        }
-        Err(e) => eprintln!("Wayland connection failed: {e}"),
-    }
-
-    None
+    let conn = wayland::Connection::connect()?;
+    let w = conn.create_window(width, height, monitor, translucent)?;
+    Ok(Some(AnyWindow::Wayland(Box::new(w))))
 }
 
 #[cfg(feature = "wayland")]
@@ -255,16 +701,25 @@ fn find_wayland_socket() -> Option<String> {
     }
 
     if candidate_count > 1 {
-        eprintln!("zenity-rs: multiple wayland socket candidates found, using first");
+        // Looked for on every dialog, but only worth saying once
+        static WARNED: std::sync::Once = std::sync::Once::new();
+        WARNED.call_once(|| {
+            eprintln!("zenity-rs: multiple wayland socket candidates found, using first");
+        });
     }
 
     chosen
 }
 
 #[cfg(feature = "x11")]
-fn try_x11(width: u16, height: u16) -> Result<AnyWindow, Error> {
+fn try_x11(
+    width: u16,
+    height: u16,
+    monitor: Monitor,
+    translucent: bool,
+) -> Result<AnyWindow, Error> {
     let conn = x11::Connection::connect()?;
-    let w = conn.create_window(width, height)?;
+    let w = conn.create_window(width, height, monitor, translucent)?;
     Ok(AnyWindow::X11(Box::new(w)))
 }
 
@@ -0,0 +1,114 @@
+//! Touch input, turned into the pointer events dialogs already handle.
+//!
+//! A tap becomes a left click where the finger lifted; dragging a finger
+//! scrolls in steps, like a mouse wheel. Only the first finger down is
+//! followed.
+
+use std::collections::VecDeque;
+
+use super::{CursorPos, Modifiers, MouseButton, ScrollDirection, WindowEvent};
+
+/// How far a finger may move (logical pixels) and still count as a tap.
+const TAP_SLOP: f64 = 8.0;
+/// How far a finger moves (logical pixels) per scroll step.
+const SCROLL_STEP: f64 = 32.0;
+
+struct ActiveTouch {
+    id: i32,
+    start: (f64, f64),
+    /// Where the last scroll step was taken
+    anchor: (f64, f64),
+    dragging: bool,
+}
+
+/// Follows one touch and queues the pointer events it stands for.
+#[derive(Default)]
+pub(crate) struct TouchTracker {
+    active: Option<ActiveTouch>,
+}
+
+impl TouchTracker {
+    /// A finger touched the window at physical (`x`, `y`).
+    pub fn down(&mut self, id: i32, x: f64, y: f64, events: &mut VecDeque<WindowEvent>) {
+        if self.active.is_some() {
+            return;
+        }
+        self.active = Some(ActiveTouch {
+            id,
+            start: (x, y),
+            anchor: (x, y),
+            dragging: false,
+        });
+        events.push_back(WindowEvent::CursorEnter(cursor_pos(x, y)));
+    }
+
+    /// A finger moved to physical (`x`, `y`).
+    pub fn motion(
+        &mut self,
+        id: i32,
+        x: f64,
+        y: f64,
+        scale: f32,
+        events: &mut VecDeque<WindowEvent>,
+    ) {
+        let Some(touch) = self.active.as_mut().filter(|touch| touch.id == id) else {
+            return;
+        };
+        let scale = scale as f64;
+
+        if !touch.dragging {
+            let (dx, dy) = (x - touch.start.0, y - touch.start.1);
+            if dx.hypot(dy) < TAP_SLOP * scale {
+                return;
+            }
+            touch.dragging = true;
+        }
+
+        // Content follows the finger, so moving up scrolls down
+        let step = SCROLL_STEP * scale;
+        while y - touch.anchor.1 >= step {
+            touch.anchor.1 += step;
+            events.push_back(WindowEvent::Scroll(ScrollDirection::Up));
+        }
+        while touch.anchor.1 - y >= step {
+            touch.anchor.1 -= step;
+            events.push_back(WindowEvent::Scroll(ScrollDirection::Down));
+        }
+        while x - touch.anchor.0 >= step {
+            touch.anchor.0 += step;
+            events.push_back(WindowEvent::Scroll(ScrollDirection::Left));
+        }
+        while touch.anchor.0 - x >= step {
+            touch.anchor.0 -= step;
+            events.push_back(WindowEvent::Scroll(ScrollDirection::Right));
+        }
+    }
+
+    /// A finger lifted.
+    pub fn up(&mut self, id: i32, events: &mut VecDeque<WindowEvent>) {
+        let Some(touch) = self.active.take_if(|touch| touch.id == id) else {
+            return;
+        };
+        if !touch.dragging {
+            let modifiers = Modifiers::empty();
+            events.push_back(WindowEvent::ButtonPress(MouseButton::Left, modifiers));
+            events.push_back(WindowEvent::ButtonRelease(MouseButton::Left, modifiers));
+        }
+        events.push_back(WindowEvent::CursorLeave);
+    }
+
+    /// The compositor took the touches over, e.g. for a gesture.
+    #[cfg_attr(not(feature = "wayland"), allow(dead_code))]
+    pub fn cancel(&mut self, events: &mut VecDeque<WindowEvent>) {
+        if self.active.take().is_some() {
+            events.push_back(WindowEvent::CursorLeave);
+        }
+    }
+}
+
+fn cursor_pos(x: f64, y: f64) -> CursorPos {
+    CursorPos {
+        x: x as i16,
+        y: y as i16,
+    }
+}
@@ -4,43 +4,85 @@ mod shm;
 
 use std::{
     collections::VecDeque,
-    os::fd::{FromRawFd, IntoRawFd},
+    io::{self, ErrorKind, Read},
+    os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd},
+    sync::Mutex,
+    time::{Duration, Instant},
 };
 
 use kbvm::lookup::LookupTable;
 use wayland_client::{
-    Connection as WaylandConnection, Dispatch, EventQueue, QueueHandle, WEnum,
+    Connection as WaylandConnection, Dispatch, EventQueue, Proxy, QueueHandle, WEnum,
+    backend::WaylandError as BackendError,
+    event_created_child,
     protocol::{
         wl_buffer::{self, WlBuffer},
         wl_callback::{self, WlCallback},
         wl_compositor::WlCompositor,
+        wl_data_device::{self, WlDataDevice},
+        wl_data_device_manager::{DndAction, WlDataDeviceManager},
+        wl_data_offer::{self, WlDataOffer},
         wl_keyboard::{self, WlKeyboard},
         wl_output::{self, WlOutput},
         wl_pointer::{self, WlPointer},
+        wl_region::WlRegion,
         wl_registry::{self, WlRegistry},
         wl_seat::{self, WlSeat},
         wl_shm::WlShm,
         wl_shm_pool::WlShmPool,
         wl_surface::WlSurface,
+        wl_touch::{self, WlTouch},
     },
 };
-use wayland_protocols::xdg::shell::client::{
-    xdg_surface::{self, XdgSurface},
-    xdg_toplevel::{self, XdgToplevel},
-    xdg_wm_base::{self, XdgWmBase},
+use wayland_protocols::{
+    wp::{
+        fractional_scale::v1::client::{
+            wp_fractional_scale_manager_v1::WpFractionalScaleManagerV1,
+            wp_fractional_scale_v1::{self, WpFractionalScaleV1},
+        },
+        primary_selection::zv1::client::{
+            zwp_primary_selection_device_manager_v1::ZwpPrimarySelectionDeviceManagerV1,
+            zwp_primary_selection_device_v1::{self, ZwpPrimarySelectionDeviceV1},
+            zwp_primary_selection_offer_v1::{self, ZwpPrimarySelectionOfferV1},
+        },
+        viewporter::client::{wp_viewport::WpViewport, wp_viewporter::WpViewporter},
+    },
+    xdg::{
+        activation::v1::client::xdg_activation_v1::XdgActivationV1,
+        decoration::zv1::client::{
+            zxdg_decoration_manager_v1::ZxdgDecorationManagerV1,
+            zxdg_toplevel_decoration_v1::{self, ZxdgToplevelDecorationV1},
+        },
+        dialog::v1::client::{xdg_dialog_v1::XdgDialogV1, xdg_wm_dialog_v1::XdgWmDialogV1},
+        foreign::zv2::client::{
+            zxdg_imported_v2::{self, ZxdgImportedV2},
+            zxdg_importer_v2::ZxdgImporterV2,
+        },
+        shell::client::{
+            xdg_surface::{self, XdgSurface},
+            xdg_toplevel::{self, XdgToplevel},
+            xdg_wm_base::{self, XdgWmBase},
+        },
+        toplevel_icon::v1::client::{
+            xdg_toplevel_icon_manager_v1::XdgToplevelIconManagerV1,
+            xdg_toplevel_icon_v1::XdgToplevelIconV1,
+        },
+    },
 };
 
 use self::shm::ShmPool;
 use super::{
-    CursorPos, CursorShape, DEFAULT_SCALE, DisplayConnection, KeyEvent, Modifiers, MouseButton,
-    ScrollDirection, Window, WindowEvent,
+    CursorPos, CursorShape, DEFAULT_SCALE, DisplayConnection, KeyEvent, Modifiers, Monitor,
+    MouseButton, ScrollDirection, Window, WindowEvent, WindowHandle, dnd::parse_uri_list,
+    touch::TouchTracker,
 };
 use crate::{
     error::{Error, WaylandError},
-    render::Canvas,
+    render::{Canvas, Damage},
 };
 
 /// Wayland connection wrapper.
+#[derive(Clone)]
 pub(crate) struct Connection {
     conn: WaylandConnection,
 }
@@ -55,11 +97,67 @@ impl DisplayConnection for Connection {
         })
     }
 
-    fn create_window(&self, width: u16, height: u16) -> Result<Self::Window, Error> {
-        WaylandWindow::create(&self.conn, width, height)
+    fn create_window(
+        &self,
+        width: u16,
+        height: u16,
+        monitor: Monitor,
+        // Surfaces always blend with what is behind them
+        _translucent: bool,
+    ) -> Result<Self::Window, Error> {
+        WaylandWindow::create(&self.conn, width, height, monitor)
+    }
+}
+
+/// Key repeat rate and delay used until the compositor sends its own.
+const DEFAULT_REPEAT_RATE: i32 = 25;
+const DEFAULT_REPEAT_DELAY: i32 = 600;
+
+/// Text types accepted from the primary selection, most preferred first.
+const TEXT_MIME_TYPES: [&str; 3] = ["text/plain;charset=utf-8", "UTF8_STRING", "text/plain"];
+
+/// Type dropped files are accepted as.
+const URI_LIST_MIME: &str = "text/uri-list";
+
+/// How long a selection owner gets to send its data.
+const SELECTION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The most preferred accepted type an offer has been seen to support.
+type OfferMimeType = Mutex<Option<&'static str>>;
+
+/// Records an offered type if it is accepted and preferred over the
+/// offer's types seen so far.
+fn note_mime_type(best: &OfferMimeType, mime_type: &str, accepted: &[&'static str]) {
+    let Some(rank) = accepted.iter().position(|t| *t == mime_type) else {
+        return;
+    };
+    let mut best = best.lock().unwrap();
+    let best_rank = best.and_then(|b| accepted.iter().position(|t| *t == b));
+    if best_rank.is_none_or(|best_rank| rank < best_rank) {
+        *best = Some(accepted[rank]);
     }
 }
 
+/// Returns the type recorded for an offer.
+fn offer_mime_type(offer: &impl Proxy) -> Option<&'static str> {
+    offer
+        .data::<OfferMimeType>()
+        .and_then(|mime_type| *mime_type.lock().unwrap())
+}
+
+/// A held key that is being repeated.
+struct KeyRepeat {
+    key: kbvm::Keycode,
+    /// When the next repeat is due
+    next: Instant,
+}
+
+/// A bound output and its integer scale.
+struct Output {
+    output: WlOutput,
+    scale: i32,
+}
+
 /// State for Wayland protocol handling.
 pub(super) struct WaylandState {
     // Globals
@@ -67,25 +165,59 @@ pub(super) struct WaylandState {
     shm: Option<WlShm>,
     xdg_wm_base: Option<XdgWmBase>,
     seat: Option<WlSeat>,
-    output: Option<WlOutput>,
+    // Registry name of the seat, to notice it going away
+    seat_name: u32,
+    outputs: Vec<Output>,
+    fractional_scale_manager: Option<WpFractionalScaleManagerV1>,
+    viewporter: Option<WpViewporter>,
+    decoration_manager: Option<ZxdgDecorationManagerV1>,
+    primary_selection_manager: Option<ZwpPrimarySelectionDeviceManagerV1>,
+    data_device_manager: Option<WlDataDeviceManager>,
+    activation: Option<XdgActivationV1>,
+    importer: Option<ZxdgImporterV2>,
+    wm_dialog: Option<XdgWmDialogV1>,
+    icon_manager: Option<XdgToplevelIconManagerV1>,
 
     // Input devices
     pointer: Option<WlPointer>,
     keyboard: Option<WlKeyboard>,
+    touch: Option<WlTouch>,
+    primary_selection_device: Option<ZwpPrimarySelectionDeviceV1>,
+    // Offer for the current primary selection
+    primary_offer: Option<ZwpPrimarySelectionOfferV1>,
+    data_device: Option<WlDataDevice>,
+    // Offer for a drag over the window
+    drag_offer: Option<WlDataOffer>,
 
     // Window state
     surface: Option<WlSurface>,
     xdg_surface: Option<XdgSurface>,
     xdg_toplevel: Option<XdgToplevel>,
+    // Another program's window we are attached to
+    imported_parent: Option<ZxdgImportedV2>,
+    dialog: Option<XdgDialogV1>,
 
     // Configuration state
     configured: bool,
     closed: bool,
-
-    // Scale factor from output (integer scale from wl_output)
-    output_scale: i32,
+    // Whether the compositor draws the title bar and borders
+    server_decorations: bool,
+    // Whether the compositor's suggested size is followed
+    resizable: bool,
+    // Current logical size, and the size from the last toplevel configure
+    logical_size: (i32, i32),
+    pending_size: Option<(i32, i32)>,
+    // Logical border around the window geometry, such as a shadow, and the
+    // size the geometry was last set for
+    frame_margin: i32,
+    frame_size: Option<(i32, i32)>,
+
+    // Output whose scale is used until the compositor prefers another
+    monitor: Monitor,
+    // Preferred scale from wp_fractional_scale_v1, in 120ths
+    preferred_scale: Option<u32>,
     // Effective scale factor used for rendering (set when window is created)
-    effective_scale: i32,
+    effective_scale: f32,
 
     // Input state
     last_serial: u32,
@@ -94,6 +226,13 @@ pub(super) struct WaylandState {
 
     // Keyboard handling
     lookup_table: Option<LookupTable>,
+    // Repeats per second (0 disables repeat) and initial delay in ms
+    repeat_rate: i32,
+    repeat_delay: i32,
+    key_repeat: Option<KeyRepeat>,
+
+    // Touch handling
+    touch_tracker: TouchTracker,
 
     // Events
     pending_events: VecDeque<WindowEvent>,
@@ -106,32 +245,155 @@ impl WaylandState {
             shm: None,
             xdg_wm_base: None,
             seat: None,
-            output: None,
+            seat_name: 0,
+            outputs: Vec::new(),
+            fractional_scale_manager: None,
+            viewporter: None,
+            decoration_manager: None,
+            primary_selection_manager: None,
+            data_device_manager: None,
+            activation: None,
+            importer: None,
+            wm_dialog: None,
+            icon_manager: None,
             pointer: None,
             keyboard: None,
+            touch: None,
+            primary_selection_device: None,
+            primary_offer: None,
+            data_device: None,
+            drag_offer: None,
             surface: None,
             xdg_surface: None,
             xdg_toplevel: None,
+            imported_parent: None,
+            dialog: None,
             configured: false,
             closed: false,
-            output_scale: 1,
-            effective_scale: 1,
+            server_decorations: false,
+            resizable: false,
+            logical_size: (0, 0),
+            pending_size: None,
+            frame_margin: 0,
+            frame_size: None,
+            monitor: Monitor::Default,
+            preferred_scale: None,
+            effective_scale: 1.0,
             last_serial: 0,
             modifier_mask: kbvm::ModifierMask::NONE,
             keyboard_group: 0,
             lookup_table: None,
+            repeat_rate: DEFAULT_REPEAT_RATE,
+            repeat_delay: DEFAULT_REPEAT_DELAY,
+            key_repeat: None,
+            touch_tracker: TouchTracker::default(),
             pending_events: VecDeque::new(),
         }
     }
 
     /// Returns the effective scale factor to use for rendering.
-    /// Uses compositor scale if > 1, otherwise defaults to DEFAULT_SCALE.
+    /// Uses the fractional scale when the compositor offers one, then the
+    /// chosen output's scale if > 1, otherwise defaults to DEFAULT_SCALE.
     fn scale_factor(&self) -> f32 {
-        if self.output_scale > 1 {
-            self.output_scale as f32
+        if let Some(scale) = self.preferred_scale {
+            return scale as f32 / 120.0;
+        }
+        let output_scale = match self.monitor {
+            Monitor::Index(index) => self.outputs.get(index).map(|output| output.scale),
+            // Wayland has no primary output; compositors list it first
+            Monitor::Primary => self.outputs.first().map(|output| output.scale),
+            // Where the compositor maps the window isn't known yet, so be
+            // sharp on every output
+            Monitor::Default | Monitor::Cursor => {
+                self.outputs.iter().map(|output| output.scale).max()
+            }
+        };
+        match output_scale {
+            Some(scale) if scale > 1 => scale as f32,
+            _ => DEFAULT_SCALE,
+        }
+    }
+
+    /// Queues the events for a key press or release.
+    fn push_key(&mut self, keycode: kbvm::Keycode, pressed: bool) {
+        let Some(ref lookup_table) = self.lookup_table else {
+            return;
+        };
+        let modifiers = convert_wayland_modifiers(self.modifier_mask);
+        let group = kbvm::GroupIndex(self.keyboard_group);
+        let lookup = lookup_table.lookup(group, self.modifier_mask, keycode);
+
+        let keysym = lookup.into_iter().next().map(|p| p.keysym().0).unwrap_or(0);
+
+        if pressed {
+            // Emit TextInput for printable characters on key press
+            let ch: Option<char> = lookup.into_iter().flat_map(|p| p.char()).next();
+
+            if let Some(c) = ch {
+                if !c.is_control() && !modifiers.intersects(Modifiers::CTRL | Modifiers::ALT) {
+                    self.pending_events.push_back(WindowEvent::TextInput(c));
+                    return;
+                }
+            }
+
+            self.pending_events
+                .push_back(WindowEvent::KeyPress(KeyEvent {
+                    keysym,
+                    modifiers,
+                }));
         } else {
-            DEFAULT_SCALE
+            self.pending_events
+                .push_back(WindowEvent::KeyRelease(KeyEvent {
+                    keysym,
+                    modifiers,
+                }));
+        }
+    }
+
+    /// Repeats the held key if its next repeat is due.
+    fn repeat_key(&mut self) {
+        let Some(repeat) = &mut self.key_repeat else {
+            return;
+        };
+        let now = Instant::now();
+        if now < repeat.next {
+            return;
+        }
+
+        // Repeats missed while the dialog was busy are dropped rather than
+        // delivered in a burst
+        let interval = Duration::from_secs(1) / self.repeat_rate as u32;
+        repeat.next += interval;
+        if repeat.next < now {
+            repeat.next = now + interval;
+        }
+        let key = repeat.key;
+        self.push_key(key, true);
+    }
+
+    /// Lets go of the seat and its devices after it was unplugged.
+    fn release_seat(&mut self) {
+        if let Some(pointer) = self.pointer.take() {
+            pointer.release();
+        }
+        if let Some(keyboard) = self.keyboard.take() {
+            keyboard.release();
+        }
+        if let Some(touch) = self.touch.take() {
+            touch.release();
+        }
+        if let Some(device) = self.primary_selection_device.take() {
+            device.destroy();
+        }
+        if let Some(device) = self.data_device.take() {
+            device.release();
         }
+        if let Some(seat) = self.seat.take() {
+            seat.release();
+        }
+        self.primary_offer = None;
+        self.drag_offer = None;
+        self.key_repeat = None;
     }
 }
 
@@ -140,6 +402,7 @@ pub(crate) struct WaylandWindow {
     conn: WaylandConnection,
     event_queue: EventQueue<WaylandState>,
     state: WaylandState,
+    shm: WlShm,
     shm_pool: ShmPool,
     buffer: WlBuffer,
     /// Physical width (logical * scale)
@@ -147,22 +410,52 @@ pub(crate) struct WaylandWindow {
     /// Physical height (logical * scale)
     physical_height: i32,
     /// Scale factor for this window
-    scale: i32,
+    scale: f32,
+    /// Maps the buffer onto the logical surface size at fractional scales;
+    /// `None` when an integer buffer scale is used instead
+    viewport: Option<WpViewport>,
+    /// Reports the preferred fractional scale, along with the viewport
+    fractional_scale: Option<WpFractionalScaleV1>,
+    /// Asks the compositor for a title bar, where it draws them
+    decoration: Option<ZxdgToplevelDecorationV1>,
     /// Cursor theme
     cursor_theme: wayland_cursor::CursorTheme,
     /// Cursor surface for rendering cursor
     cursor_surface: WlSurface,
     /// Current cursor shape
     current_cursor: CursorShape,
+    /// Token from whoever launched us, used to take focus when shown
+    activation_token: Option<String>,
+    /// Pixels of the window icon, kept for as long as the compositor may
+    /// read them
+    icon_buffer: Option<(ShmPool, WlBuffer)>,
+    /// Whether to ask for a frame callback with the next commit
+    frame_requested: bool,
+    /// Opacity the contents are drawn with, and the one the buffer holds
+    opacity: f32,
+    buffer_opacity: f32,
 }
 
 impl WaylandWindow {
-    fn create(conn: &WaylandConnection, width: u16, height: u16) -> Result<Self, Error> {
+    /// Returns the connection the window was made on.
+    pub(crate) fn connection(&self) -> Connection {
+        Connection {
+            conn: self.conn.clone(),
+        }
+    }
+
+    fn create(
+        conn: &WaylandConnection,
+        width: u16,
+        height: u16,
+        monitor: Monitor,
+    ) -> Result<Self, Error> {
         let mut event_queue = conn.new_event_queue();
         let qh = event_queue.handle();
 
         let display = conn.display();
         let mut state = WaylandState::new();
+        state.monitor = monitor;
 
         // Get the registry and bind globals
         let _registry = display.get_registry(&qh, ());
@@ -174,15 +467,15 @@ impl WaylandWindow {
         let compositor = state
             .compositor
             .clone()
-            .ok_or(Error::Wayland(WaylandError::MissingGlobal("wl_compositor")))?;
+            .ok_or(WaylandError::MissingGlobal("wl_compositor"))?;
         let shm = state
             .shm
             .clone()
-            .ok_or(Error::Wayland(WaylandError::MissingGlobal("wl_shm")))?;
+            .ok_or(WaylandError::MissingGlobal("wl_shm"))?;
         let xdg_wm_base = state
             .xdg_wm_base
             .clone()
-            .ok_or(Error::Wayland(WaylandError::MissingGlobal("xdg_wm_base")))?;
+            .ok_or(WaylandError::MissingGlobal("xdg_wm_base"))?;
 
         // Create surface
         let surface = compositor.create_surface(&qh, ());
@@ -196,10 +489,37 @@ impl WaylandWindow {
         let xdg_toplevel = xdg_surface.get_toplevel(&qh, ());
         state.xdg_toplevel = Some(xdg_toplevel.clone());
 
+        // Ask for the preferred fractional scale; the buffer is then scaled
+        // down to the surface size through a viewport
+        let fractional_scale = state
+            .fractional_scale_manager
+            .as_ref()
+            .zip(state.viewporter.as_ref())
+            .map(|(manager, viewporter)| {
+                (
+                    manager.get_fractional_scale(&surface, &qh, ()),
+                    viewporter.get_viewport(&surface, &qh, ()),
+                )
+            });
+
         // Set up window properties
         xdg_toplevel.set_app_id("zenity".to_string());
+        // Marks the window as a dialog, so tiling compositors float it
+        state.dialog = state
+            .wm_dialog
+            .as_ref()
+            .map(|wm_dialog| wm_dialog.get_xdg_dialog(&xdg_toplevel, &qh, ()));
         xdg_toplevel.set_min_size(width as i32, height as i32);
         xdg_toplevel.set_max_size(width as i32, height as i32);
+        state.logical_size = (width as i32, height as i32);
+
+        // Prefer a title bar from the compositor. Without the protocol, or
+        // if the compositor insists on client-side mode, we go undecorated.
+        let decoration = state.decoration_manager.as_ref().map(|manager| {
+            let decoration = manager.get_toplevel_decoration(&xdg_toplevel, &qh, ());
+            decoration.set_mode(zxdg_toplevel_decoration_v1::Mode::ServerSide);
+            decoration
+        });
 
         // Commit to get configure event
         surface.commit();
@@ -212,16 +532,21 @@ impl WaylandWindow {
         // Do another roundtrip to ensure we have output scale
         event_queue.roundtrip(&mut state)?;
 
-        // Get the scale factor - use compositor scale if > 1, otherwise use our default
-        let scale = state.scale_factor().ceil() as i32;
+        // Get the scale factor. Without a viewport the buffer scale must be
+        // an integer, so round up and let the compositor scale down.
+        let (fractional_scale, viewport) = fractional_scale.unzip();
+        let scale = if viewport.is_some() {
+            state.scale_factor()
+        } else {
+            state.scale_factor().ceil()
+        };
         // Store the effective scale so pointer events can use the same value
         state.effective_scale = scale;
 
-        // Calculate physical dimensions (what we actually render)
-        let logical_width = width as i32;
-        let logical_height = height as i32;
-        let physical_width = logical_width * scale;
-        let physical_height = logical_height * scale;
+        // Calculate physical dimensions (what we actually render), rounded
+        // the same way dialogs size their canvas
+        let physical_width = (width as f32 * scale) as i32;
+        let physical_height = (height as f32 * scale) as i32;
 
         // Create shared memory pool and buffer at PHYSICAL size
         let stride = physical_width * 4; // 4 bytes per pixel (ARGB8888)
@@ -230,40 +555,72 @@ impl WaylandWindow {
         let shm_pool = ShmPool::new(&shm, size, &qh)?;
         let buffer = shm_pool.create_buffer(physical_width, physical_height, stride, &qh);
 
-        // Set buffer scale so compositor knows we're rendering at higher resolution
-        surface.set_buffer_scale(scale);
-
-        // Get input devices from seat
-        if let Some(seat) = &state.seat.clone() {
-            state.pointer = Some(seat.get_pointer(&qh, ()));
-            state.keyboard = Some(seat.get_keyboard(&qh, ()));
+        // Tell the compositor how the buffer maps to the surface
+        if let Some(viewport) = &viewport {
+            viewport.set_destination(width as i32, height as i32);
+        } else {
+            surface.set_buffer_scale(scale as i32);
         }
 
         // Create cursor theme and surface
         let cursor_theme = wayland_cursor::CursorTheme::load(conn, shm.clone(), 24)
-            .map_err(|_| Error::Wayland(WaylandError::MissingGlobal("cursor theme")))?;
+            .map_err(|_| WaylandError::MissingGlobal("cursor theme"))?;
         let cursor_surface = compositor.create_surface(&qh, ());
 
         Ok(Self {
             conn: conn.clone(),
             event_queue,
             state,
+            shm,
             shm_pool,
             buffer,
             physical_width,
             physical_height,
             scale,
+            viewport,
+            fractional_scale,
+            decoration,
             cursor_theme,
             cursor_surface,
             current_cursor: CursorShape::Default,
+            activation_token: take_activation_token(),
+            icon_buffer: None,
+            frame_requested: false,
+            opacity: 1.0,
+            buffer_opacity: 1.0,
         })
     }
 
+    /// Leaves the frame margin out of the window geometry and input region
+    /// once the window has a size, to go out with the next commit.
+    fn update_frame_geometry(&mut self) {
+        let state = &mut self.state;
+        let margin = state.frame_margin;
+        if margin == 0 || state.frame_size == Some(state.logical_size) {
+            return;
+        }
+        let (Some(surface), Some(xdg_surface), Some(compositor)) =
+            (&state.surface, &state.xdg_surface, &state.compositor)
+        else {
+            return;
+        };
+        let (width, height) = state.logical_size;
+        let (width, height) = ((width - 2 * margin).max(1), (height - 2 * margin).max(1));
+        xdg_surface.set_window_geometry(margin, margin, width, height);
+
+        let region = compositor.create_region(&self.event_queue.handle(), ());
+        region.add(margin, margin, width, height);
+        surface.set_input_region(Some(&region));
+        region.destroy();
+        state.frame_size = Some(state.logical_size);
+    }
+
     /// Updates the cursor on the pointer
     fn update_cursor(&mut self) {
         let cursor_name = match self.current_cursor {
             CursorShape::Default => "default",
             CursorShape::Text => "text",
+            CursorShape::Pointer => "pointer",
         };
 
         if let Some(cursor) = self.cursor_theme.get_cursor(cursor_name) {
@@ -286,6 +643,109 @@ impl WaylandWindow {
             }
         }
     }
+
+    /// Reads and dispatches events, waiting for them no later than
+    /// `deadline`.
+    fn dispatch_until(&mut self, deadline: Instant) -> Result<(), Error> {
+        self.dispatch_or(&[], Some(deadline)).map(drop)
+    }
+
+    /// Reads and dispatches events, waiting for them until one of `fds` is
+    /// ready or `deadline` has passed. Returns true if one of `fds` is.
+    fn dispatch_or(
+        &mut self,
+        fds: &[libc::pollfd],
+        deadline: Option<Instant>,
+    ) -> Result<bool, Error> {
+        let mut others_ready = false;
+        // Events already queued are dispatched without reading
+        if let Some(guard) = self.event_queue.prepare_read() {
+            let mut all = vec![libc::pollfd {
+                fd: guard.connection_fd().as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            }];
+            all.extend_from_slice(fds);
+            // On timeout or interruption the guard is dropped, cancelling
+            // the read
+            super::poll(&mut all, deadline);
+            others_ready = all[1..].iter().any(|fd| fd.revents != 0);
+            if all[0].revents != 0 {
+                match guard.read() {
+                    Ok(_) => {}
+                    Err(BackendError::Io(e)) if e.kind() == ErrorKind::WouldBlock => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
+        self.event_queue.dispatch_pending(&mut self.state)?;
+        Ok(others_ready)
+    }
+}
+
+impl Drop for WaylandWindow {
+    /// Takes the window down and lets go of the input devices, as the
+    /// connection may stay open for further dialogs.
+    fn drop(&mut self) {
+        let state = &mut self.state;
+        if let Some(dialog) = state.dialog.take() {
+            dialog.destroy();
+        }
+        if let Some(parent) = state.imported_parent.take() {
+            parent.destroy();
+        }
+        // The decoration must go before the toplevel it belongs to
+        if let Some(decoration) = self.decoration.take() {
+            decoration.destroy();
+        }
+        if let Some(toplevel) = state.xdg_toplevel.take() {
+            toplevel.destroy();
+        }
+        if let Some(xdg_surface) = state.xdg_surface.take() {
+            xdg_surface.destroy();
+        }
+        if let Some(viewport) = self.viewport.take() {
+            viewport.destroy();
+        }
+        if let Some(fractional_scale) = self.fractional_scale.take() {
+            fractional_scale.destroy();
+        }
+        if let Some(surface) = state.surface.take() {
+            surface.destroy();
+        }
+        self.buffer.destroy();
+        self.cursor_surface.destroy();
+        if let Some((_, buffer)) = self.icon_buffer.take() {
+            buffer.destroy();
+        }
+
+        // Input devices can be released from version 3 of the seat on
+        if let Some(pointer) = state.pointer.take()
+            && pointer.version() >= 3
+        {
+            pointer.release();
+        }
+        if let Some(keyboard) = state.keyboard.take()
+            && keyboard.version() >= 3
+        {
+            keyboard.release();
+        }
+        if let Some(touch) = state.touch.take()
+            && touch.version() >= 3
+        {
+            touch.release();
+        }
+        if let Some(device) = state.primary_selection_device.take() {
+            device.destroy();
+        }
+        if let Some(device) = state.data_device.take()
+            && device.version() >= 2
+        {
+            device.release();
+        }
+        let _ = self.conn.flush();
+    }
 }
 
 impl Window for WaylandWindow {
@@ -296,16 +756,73 @@ impl Window for WaylandWindow {
         Ok(())
     }
 
-    fn set_contents(&mut self, canvas: &Canvas) -> Result<(), Error> {
-        // Copy pixel data from Canvas to shared memory buffer
-        let src = canvas.as_argb();
-        let dst = self.shm_pool.data_mut();
-        dst[..src.len()].copy_from_slice(&src);
+    fn set_contents_damaged(&mut self, canvas: &Canvas, damage: &[Damage]) -> Result<(), Error> {
+        // The buffer keeps the last contents, so only changes are copied
+        // unless it is new or drawn at another opacity
+        let whole = [Damage::whole(canvas)];
+        let mut damage = damage;
+        if self.opacity != self.buffer_opacity {
+            damage = &whole;
+            self.buffer_opacity = self.opacity;
+        }
+
+        // Reallocate the buffer when the dialog has redrawn at a new size
+        let (width, height) = (canvas.width() as i32, canvas.height() as i32);
+        if (width, height) != (self.physical_width, self.physical_height) {
+            damage = &whole;
+            let qh = self.event_queue.handle();
+            let stride = width * 4;
+            self.shm_pool = ShmPool::new(&self.shm, (stride * height) as usize, &qh)?;
+            self.buffer.destroy();
+            self.buffer = self.shm_pool.create_buffer(width, height, stride, &qh);
+            self.physical_width = width;
+            self.physical_height = height;
+            if let Some(viewport) = &self.viewport {
+                let (logical_width, logical_height) = self.state.logical_size;
+                viewport.set_destination(logical_width, logical_height);
+            }
+        }
+
+        // Copy pixel data from Canvas to shared memory buffer. Pixels are
+        // premultiplied, so fading scales every channel alike
+        let stride = width as usize * 4;
+        for area in damage {
+            let mut src = canvas.argb_region(*area);
+            if self.opacity < 1.0 {
+                let opacity = self.opacity.max(0.0);
+                for byte in &mut src {
+                    *byte = (*byte as f32 * opacity) as u8;
+                }
+            }
+            let dst = self.shm_pool.data_mut();
+            let row_len = area.width as usize * 4;
+            for (row, y) in src.chunks_exact(row_len).zip(area.y as usize..) {
+                let start = y * stride + area.x as usize * 4;
+                dst[start..start + row_len].copy_from_slice(row);
+            }
+        }
 
         // Attach buffer and damage the surface (use physical dimensions)
         if let Some(surface) = &self.state.surface {
             surface.attach(Some(&self.buffer), 0, 0);
-            surface.damage_buffer(0, 0, self.physical_width, self.physical_height);
+            for area in damage {
+                surface.damage_buffer(
+                    area.x as i32,
+                    area.y as i32,
+                    area.width as i32,
+                    area.height as i32,
+                );
+            }
+            // Commits without damage may not get a frame callback
+            if damage.is_empty() && self.frame_requested {
+                surface.damage_buffer(0, 0, 1, 1);
+            }
+        }
+        self.update_frame_geometry();
+        if let Some(surface) = &self.state.surface {
+            if std::mem::take(&mut self.frame_requested) {
+                surface.frame(&self.event_queue.handle(), ());
+            }
             surface.commit();
         }
 
@@ -314,6 +831,13 @@ impl Window for WaylandWindow {
     }
 
     fn show(&mut self) -> Result<(), Error> {
+        // Without a token the compositor's focus stealing prevention may
+        // leave the dialog behind the window that launched it
+        if let Some(token) = self.activation_token.take()
+            && let (Some(activation), Some(surface)) = (&self.state.activation, &self.state.surface)
+        {
+            activation.activate(token, surface);
+        }
         self.conn.flush()?;
         Ok(())
     }
@@ -329,7 +853,13 @@ impl Window for WaylandWindow {
             }
 
             self.conn.flush()?;
-            self.event_queue.blocking_dispatch(&mut self.state)?;
+            match self.state.key_repeat.as_ref().map(|repeat| repeat.next) {
+                Some(deadline) => self.dispatch_until(deadline)?,
+                None => {
+                    self.event_queue.blocking_dispatch(&mut self.state)?;
+                }
+            }
+            self.state.repeat_key();
         }
     }
 
@@ -346,16 +876,44 @@ impl Window for WaylandWindow {
 
         // Try to prepare for reading new events
         if let Some(guard) = self.event_queue.prepare_read() {
-            // Try to read events - this may fail if no data is available
-            // The guard is consumed by read() call, so we don't need to cancel it
-            let _ = guard.read();
+            // Reading fails with WouldBlock when no data is available. The
+            // guard is consumed by read() call, so we don't need to cancel it
+            match guard.read() {
+                Ok(_) => {}
+                Err(BackendError::Io(e)) if e.kind() == ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e.into()),
+            }
         }
 
         self.event_queue.dispatch_pending(&mut self.state)?;
+        self.state.repeat_key();
 
         Ok(self.state.pending_events.pop_front())
     }
 
+    fn wait_for_event_or(
+        &mut self,
+        fds: &[libc::pollfd],
+        deadline: Option<Instant>,
+    ) -> Result<Option<WindowEvent>, Error> {
+        loop {
+            if let Some(event) = self.poll_for_event()? {
+                return Ok(Some(event));
+            }
+            // Wake for the next repeat of a held key too
+            let repeat = self.state.key_repeat.as_ref().map(|repeat| repeat.next);
+            let wake = match (deadline, repeat) {
+                (Some(deadline), Some(repeat)) => Some(deadline.min(repeat)),
+                (deadline, repeat) => deadline.or(repeat),
+            };
+            if self.dispatch_or(fds, wake)?
+                || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                return Ok(self.state.pending_events.pop_front());
+            }
+        }
+    }
+
     fn start_drag(&mut self) -> Result<(), Error> {
         if let (Some(toplevel), Some(seat)) = (&self.state.xdg_toplevel, &self.state.seat) {
             toplevel._move(seat, self.state.last_serial);
@@ -364,7 +922,7 @@ impl Window for WaylandWindow {
     }
 
     fn scale_factor(&self) -> f32 {
-        self.scale as f32
+        self.scale
     }
 
     fn set_cursor(&mut self, shape: CursorShape) -> Result<(), Error> {
@@ -376,6 +934,213 @@ impl Window for WaylandWindow {
         self.conn.flush()?;
         Ok(())
     }
+
+    fn set_resizable(&mut self, min_width: u16, min_height: u16) -> Result<(), Error> {
+        if let Some(toplevel) = &self.state.xdg_toplevel {
+            let margin = 2 * self.state.frame_margin;
+            toplevel.set_min_size(
+                (min_width as i32 - margin).max(1),
+                (min_height as i32 - margin).max(1),
+            );
+            // 0 means no maximum
+            toplevel.set_max_size(0, 0);
+        }
+        self.state.resizable = true;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    fn set_size(&mut self, width: u16, height: u16) -> Result<(), Error> {
+        // The new size takes effect with the next buffer
+        if !self.state.resizable
+            && let Some(toplevel) = &self.state.xdg_toplevel
+        {
+            let margin = 2 * self.state.frame_margin;
+            let (width, height) = (width as i32 - margin, height as i32 - margin);
+            toplevel.set_min_size(width.max(1), height.max(1));
+            toplevel.set_max_size(width.max(1), height.max(1));
+        }
+        self.state.logical_size = (width as i32, height as i32);
+        Ok(())
+    }
+
+    fn needs_decorations(&self) -> bool {
+        !self.state.server_decorations
+    }
+
+    fn set_position(&mut self, _x: i32, _y: i32) -> Result<(), Error> {
+        // Wayland leaves placement to the compositor
+        Ok(())
+    }
+
+    fn monitor_area(&self) -> Option<(i32, i32, u32, u32)> {
+        None
+    }
+
+    fn pointer_position(&self) -> Option<(i32, i32)> {
+        None
+    }
+
+    fn primary_selection(&mut self) -> Result<Option<String>, Error> {
+        let Some(offer) = &self.state.primary_offer else {
+            return Ok(None);
+        };
+        let Some(mime_type) = offer_mime_type(offer) else {
+            return Ok(None);
+        };
+
+        // An owner that doesn't answer in time is treated as having nothing
+        match receive_offer(&self.conn, |fd| offer.receive(mime_type.to_string(), fd)) {
+            Ok(data) => Ok(Some(String::from_utf8_lossy(&data).into_owned())),
+            Err(e) if e.kind() == ErrorKind::TimedOut => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn set_parent(&mut self, parent: &WindowHandle) -> Result<(), Error> {
+        let WindowHandle::Wayland(handle) = parent else {
+            return Ok(());
+        };
+        let (Some(importer), Some(surface)) = (&self.state.importer, &self.state.surface) else {
+            return Ok(());
+        };
+        let qh = self.event_queue.handle();
+
+        if let Some(previous) = self.state.imported_parent.take() {
+            previous.destroy();
+        }
+        let imported = importer.import_toplevel(handle.clone(), &qh, ());
+        imported.set_parent_of(surface);
+        self.state.imported_parent = Some(imported);
+
+        // Placement over the parent is up to the compositor, which may also
+        // dim or lock the parent for a modal dialog
+        if let Some(dialog) = &self.state.dialog {
+            dialog.set_modal();
+        }
+
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    fn set_icon(&mut self, icon: &Canvas) -> Result<(), Error> {
+        let (Some(manager), Some(toplevel)) = (&self.state.icon_manager, &self.state.xdg_toplevel)
+        else {
+            return Ok(());
+        };
+        let qh = self.event_queue.handle();
+
+        // Icons must be square; a non-square image is centered on one
+        let size = icon.width().max(icon.height());
+        let mut square = Canvas::new(size, size);
+        square.draw_canvas(
+            icon,
+            ((size - icon.width()) / 2) as i32,
+            ((size - icon.height()) / 2) as i32,
+        );
+
+        let stride = size as i32 * 4;
+        let mut pool = ShmPool::new(&self.shm, (stride * size as i32) as usize, &qh)?;
+        let src = square.as_argb();
+        pool.data_mut()[..src.len()].copy_from_slice(&src);
+        let buffer = pool.create_buffer(size as i32, size as i32, stride, &qh);
+
+        let xdg_icon = manager.create_icon(&qh, ());
+        xdg_icon.add_buffer(&buffer, (self.scale.ceil() as i32).max(1));
+        manager.set_icon(toplevel, Some(&xdg_icon));
+        xdg_icon.destroy();
+
+        if let Some((_, previous)) = self.icon_buffer.replace((pool, buffer)) {
+            previous.destroy();
+        }
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    fn set_frame_margin(&mut self, margin: u16) -> Result<(), Error> {
+        self.state.frame_margin = margin as i32;
+        self.state.frame_size = None;
+        Ok(())
+    }
+
+    fn request_frame(&mut self) -> Result<(), Error> {
+        self.frame_requested = true;
+        Ok(())
+    }
+
+    fn set_opacity(&mut self, opacity: f32) -> Result<(), Error> {
+        self.opacity = opacity;
+        Ok(())
+    }
+}
+
+/// Takes the activation token passed in the environment. Launchers
+/// written for X11 pass it as a startup notification ID instead. Both are
+/// removed so that they aren't handed on to anything we start; a token
+/// only works once anyway.
+fn take_activation_token() -> Option<String> {
+    let token = ["XDG_ACTIVATION_TOKEN", "DESKTOP_STARTUP_ID"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|token| !token.is_empty()));
+    unsafe {
+        std::env::remove_var("XDG_ACTIVATION_TOKEN");
+        std::env::remove_var("DESKTOP_STARTUP_ID");
+    }
+    token
+}
+
+/// Reads an offer's data: `receive` asks for it to be written into the
+/// given pipe.
+fn receive_offer(
+    conn: &WaylandConnection,
+    receive: impl FnOnce(BorrowedFd),
+) -> io::Result<Vec<u8>> {
+    let (read, write) = pipe()?;
+    receive(write.as_fd());
+    conn.flush().map_err(io::Error::other)?;
+    // Our end must be closed for the read to see the end of the data
+    drop(write);
+    read_to_end(read, Instant::now() + SELECTION_TIMEOUT)
+}
+
+/// Creates a pipe, returning its read and write ends.
+fn pipe() -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds = [0; 2];
+    if unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) })
+}
+
+/// Reads a pipe until the writer closes it, giving up at `deadline`.
+fn read_to_end(fd: OwnedFd, deadline: Instant) -> io::Result<Vec<u8>> {
+    let mut file = std::fs::File::from(fd);
+    let mut data = Vec::new();
+    let mut buf = [0; 4096];
+    loop {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let mut pollfd = libc::pollfd {
+            fd: file.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let timeout = timeout.as_millis().try_into().unwrap_or(i32::MAX);
+        match unsafe { libc::poll(&mut pollfd, 1, timeout) } {
+            0 => return Err(io::Error::from(ErrorKind::TimedOut)),
+            n if n < 0 => {
+                let err = io::Error::last_os_error();
+                if err.kind() != ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+            _ => {
+                match file.read(&mut buf)? {
+                    0 => return Ok(data),
+                    n => data.extend_from_slice(&buf[..n]),
+                }
+            }
+        }
+    }
 }
 
 // Registry handler - binds globals
@@ -404,17 +1169,57 @@ impl Dispatch<WlRegistry, ()> for WaylandState {
                 "xdg_wm_base" => {
                     state.xdg_wm_base = Some(registry.bind(name, version.min(6), qh, ()));
                 }
-                "wl_seat" => {
+                // Input devices come and go with the seat's capabilities.
+                // Only one seat is used; another is taken if it goes away.
+                "wl_seat" if state.seat.is_none() => {
                     state.seat = Some(registry.bind(name, version.min(9), qh, ()));
+                    state.seat_name = name;
                 }
-                "wl_output" => {
+                "wl_output"
                     // Bind wl_output version 2+ to get scale events
-                    if version >= 2 {
-                        state.output = Some(registry.bind(name, version.min(4), qh, ()));
+                    if version >= 2 => {
+                        state.outputs.push(Output {
+                            output: registry.bind(name, version.min(4), qh, ()),
+                            scale: 1,
+                        });
                     }
+                "wp_fractional_scale_manager_v1" => {
+                    state.fractional_scale_manager = Some(registry.bind(name, 1, qh, ()));
+                }
+                "wp_viewporter" => {
+                    state.viewporter = Some(registry.bind(name, 1, qh, ()));
+                }
+                "zxdg_decoration_manager_v1" => {
+                    state.decoration_manager = Some(registry.bind(name, 1, qh, ()));
+                }
+                "zwp_primary_selection_device_manager_v1" => {
+                    state.primary_selection_manager = Some(registry.bind(name, 1, qh, ()));
+                }
+                "wl_data_device_manager" => {
+                    state.data_device_manager =
+                        Some(registry.bind(name, version.min(3), qh, ()));
+                }
+                "xdg_activation_v1" => {
+                    state.activation = Some(registry.bind(name, 1, qh, ()));
+                }
+                "zxdg_importer_v2" => {
+                    state.importer = Some(registry.bind(name, 1, qh, ()));
+                }
+                "xdg_wm_dialog_v1" => {
+                    state.wm_dialog = Some(registry.bind(name, 1, qh, ()));
+                }
+                "xdg_toplevel_icon_manager_v1" => {
+                    state.icon_manager = Some(registry.bind(name, 1, qh, ()));
                 }
                 _ => {}
             }
+        } else if let wl_registry::Event::GlobalRemove {
+            name,
+        } = event
+            && state.seat.is_some()
+            && name == state.seat_name
+        {
+            state.release_seat();
         }
     }
 }
@@ -447,7 +1252,7 @@ impl Dispatch<WlShm, ()> for WaylandState {
 impl Dispatch<WlOutput, ()> for WaylandState {
     fn event(
         state: &mut Self,
-        _: &WlOutput,
+        output: &WlOutput,
         event: wl_output::Event,
         _: &(),
         _: &WaylandConnection,
@@ -455,9 +1260,337 @@ impl Dispatch<WlOutput, ()> for WaylandState {
     ) {
         if let wl_output::Event::Scale {
             factor,
+        } = event
+            && let Some(output) = state.outputs.iter_mut().find(|o| o.output == *output)
+        {
+            output.scale = factor;
+        }
+    }
+}
+
+impl Dispatch<WpFractionalScaleManagerV1, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &WpFractionalScaleManagerV1,
+        _: <WpFractionalScaleManagerV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WpFractionalScaleV1, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _: &WpFractionalScaleV1,
+        event: wp_fractional_scale_v1::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wp_fractional_scale_v1::Event::PreferredScale {
+            scale,
         } = event
         {
-            state.output_scale = factor;
+            state.preferred_scale = Some(scale);
+        }
+    }
+}
+
+impl Dispatch<WpViewporter, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &WpViewporter,
+        _: <WpViewporter as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WpViewport, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &WpViewport,
+        _: <WpViewport as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZxdgDecorationManagerV1, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &ZxdgDecorationManagerV1,
+        _: <ZxdgDecorationManagerV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZxdgToplevelDecorationV1, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _: &ZxdgToplevelDecorationV1,
+        event: zxdg_toplevel_decoration_v1::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zxdg_toplevel_decoration_v1::Event::Configure {
+            mode,
+        } = event
+        {
+            state.server_decorations =
+                mode == WEnum::Value(zxdg_toplevel_decoration_v1::Mode::ServerSide);
+        }
+    }
+}
+
+impl Dispatch<ZwpPrimarySelectionDeviceManagerV1, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &ZwpPrimarySelectionDeviceManagerV1,
+        _: <ZwpPrimarySelectionDeviceManagerV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwpPrimarySelectionDeviceV1, ()> for WaylandState {
+    event_created_child!(WaylandState, ZwpPrimarySelectionDeviceV1, [
+        zwp_primary_selection_device_v1::EVT_DATA_OFFER_OPCODE => (ZwpPrimarySelectionOfferV1, Mutex::new(None)),
+    ]);
+
+    fn event(
+        state: &mut Self,
+        _: &ZwpPrimarySelectionDeviceV1,
+        event: zwp_primary_selection_device_v1::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zwp_primary_selection_device_v1::Event::Selection {
+            id,
+        } = event
+        {
+            // The previous offer is no longer valid
+            if let Some(offer) = state.primary_offer.take()
+                && Some(&offer) != id.as_ref()
+            {
+                offer.destroy();
+            }
+            state.primary_offer = id;
+        }
+    }
+}
+
+impl Dispatch<ZwpPrimarySelectionOfferV1, OfferMimeType> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &ZwpPrimarySelectionOfferV1,
+        event: zwp_primary_selection_offer_v1::Event,
+        best: &OfferMimeType,
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zwp_primary_selection_offer_v1::Event::Offer {
+            mime_type,
+        } = event
+        {
+            note_mime_type(best, &mime_type, &TEXT_MIME_TYPES);
+        }
+    }
+}
+
+impl Dispatch<ZxdgImporterV2, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &ZxdgImporterV2,
+        _: <ZxdgImporterV2 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZxdgImportedV2, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _: &ZxdgImportedV2,
+        event: zxdg_imported_v2::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+        // The handle was invalid or the parent went away
+        if let zxdg_imported_v2::Event::Destroyed = event
+            && let Some(imported) = state.imported_parent.take()
+        {
+            imported.destroy();
+        }
+    }
+}
+
+impl Dispatch<XdgWmDialogV1, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &XdgWmDialogV1,
+        _: <XdgWmDialogV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<XdgToplevelIconManagerV1, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &XdgToplevelIconManagerV1,
+        _: <XdgToplevelIconManagerV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<XdgToplevelIconV1, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &XdgToplevelIconV1,
+        _: <XdgToplevelIconV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<XdgDialogV1, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &XdgDialogV1,
+        _: <XdgDialogV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<XdgActivationV1, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &XdgActivationV1,
+        _: <XdgActivationV1 as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlDataDeviceManager, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &WlDataDeviceManager,
+        _: <WlDataDeviceManager as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlDataDevice, ()> for WaylandState {
+    event_created_child!(WaylandState, WlDataDevice, [
+        wl_data_device::EVT_DATA_OFFER_OPCODE => (WlDataOffer, Mutex::new(None)),
+    ]);
+
+    fn event(
+        state: &mut Self,
+        _: &WlDataDevice,
+        event: wl_data_device::Event,
+        _: &(),
+        conn: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            wl_data_device::Event::Enter {
+                serial,
+                id,
+                ..
+            } => {
+                if let Some(offer) = state.drag_offer.take() {
+                    offer.destroy();
+                }
+                if let Some(offer) = &id {
+                    // Only files are accepted
+                    let mime_type = offer_mime_type(offer);
+                    offer.accept(serial, mime_type.map(str::to_string));
+                    if offer.version() >= 3 && mime_type.is_some() {
+                        offer.set_actions(DndAction::Copy, DndAction::Copy);
+                    }
+                }
+                state.drag_offer = id;
+            }
+            wl_data_device::Event::Leave => {
+                if let Some(offer) = state.drag_offer.take() {
+                    offer.destroy();
+                }
+            }
+            wl_data_device::Event::Drop => {
+                let Some(offer) = state.drag_offer.take() else {
+                    return;
+                };
+                if let Some(mime_type) = offer_mime_type(&offer)
+                    && let Ok(data) =
+                        receive_offer(conn, |fd| offer.receive(mime_type.to_string(), fd))
+                {
+                    let paths = parse_uri_list(&data);
+                    if !paths.is_empty() {
+                        state.pending_events.push_back(WindowEvent::Drop(paths));
+                    }
+                    if offer.version() >= 3 {
+                        offer.finish();
+                    }
+                }
+                offer.destroy();
+            }
+            // The clipboard isn't used
+            wl_data_device::Event::Selection {
+                id: Some(offer),
+            } => offer.destroy(),
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<WlDataOffer, OfferMimeType> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &WlDataOffer,
+        event: wl_data_offer::Event,
+        best: &OfferMimeType,
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wl_data_offer::Event::Offer {
+            mime_type,
+        } = event
+        {
+            note_mime_type(best, &mime_type, &[URI_LIST_MIME]);
         }
     }
 }
@@ -487,6 +1620,18 @@ impl Dispatch<WlBuffer, ()> for WaylandState {
     }
 }
 
+impl Dispatch<WlRegion, ()> for WaylandState {
+    fn event(
+        _: &mut Self,
+        _: &WlRegion,
+        _: <WlRegion as wayland_client::Proxy>::Event,
+        _: &(),
+        _: &WaylandConnection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
 impl Dispatch<WlSurface, ()> for WaylandState {
     fn event(
         _: &mut Self,
@@ -550,6 +1695,17 @@ impl Dispatch<XdgSurface, ()> for WaylandState {
         {
             xdg_surface.ack_configure(serial);
             state.configured = true;
+            if let Some(size) = state.pending_size.take()
+                && state.resizable
+                && size != state.logical_size
+            {
+                state.logical_size = size;
+                let scale = state.effective_scale;
+                state.pending_events.push_back(WindowEvent::Resized(
+                    (size.0 as f32 * scale) as u32,
+                    (size.1 as f32 * scale) as u32,
+                ));
+            }
             state.pending_events.push_back(WindowEvent::RedrawRequested);
         }
     }
@@ -564,22 +1720,133 @@ impl Dispatch<XdgToplevel, ()> for WaylandState {
         _: &WaylandConnection,
         _: &QueueHandle<Self>,
     ) {
-        if let xdg_toplevel::Event::Close = event {
-            state.closed = true;
-            state.pending_events.push_back(WindowEvent::CloseRequested);
+        match event {
+            xdg_toplevel::Event::Close => {
+                state.closed = true;
+                state.pending_events.push_back(WindowEvent::CloseRequested);
+            }
+            // A zero size leaves the choice to us
+            xdg_toplevel::Event::Configure {
+                width,
+                height,
+                ..
+            } if width > 0 && height > 0 => {
+                // Sizes given are of the window geometry, without the margin
+                let margin = 2 * state.frame_margin;
+                state.pending_size = Some((width + margin, height + margin));
+            }
+            _ => {}
         }
     }
 }
 
 impl Dispatch<WlSeat, ()> for WaylandState {
     fn event(
-        _: &mut Self,
-        _: &WlSeat,
-        _: wl_seat::Event,
+        state: &mut Self,
+        seat: &WlSeat,
+        event: wl_seat::Event,
+        _: &(),
+        _: &WaylandConnection,
+        qh: &QueueHandle<Self>,
+    ) {
+        if let wl_seat::Event::Capabilities {
+            capabilities: WEnum::Value(capabilities),
+        } = event
+        {
+            // Selections and drops belong to the seat rather than a device
+            if state.primary_selection_device.is_none() {
+                state.primary_selection_device = state
+                    .primary_selection_manager
+                    .as_ref()
+                    .map(|manager| manager.get_device(seat, qh, ()));
+            }
+            if state.data_device.is_none() {
+                state.data_device = state
+                    .data_device_manager
+                    .as_ref()
+                    .map(|manager| manager.get_data_device(seat, qh, ()));
+            }
+
+            let has_pointer = capabilities.contains(wl_seat::Capability::Pointer);
+            if has_pointer && state.pointer.is_none() {
+                state.pointer = Some(seat.get_pointer(qh, ()));
+            } else if !has_pointer && let Some(pointer) = state.pointer.take() {
+                pointer.release();
+            }
+
+            // A keyboard may be plugged in after the dialog opened
+            let has_keyboard = capabilities.contains(wl_seat::Capability::Keyboard);
+            if has_keyboard && state.keyboard.is_none() {
+                state.keyboard = Some(seat.get_keyboard(qh, ()));
+            } else if !has_keyboard && let Some(keyboard) = state.keyboard.take() {
+                keyboard.release();
+                state.key_repeat = None;
+            }
+
+            let has_touch = capabilities.contains(wl_seat::Capability::Touch);
+            if has_touch && state.touch.is_none() {
+                state.touch = Some(seat.get_touch(qh, ()));
+            } else if !has_touch && let Some(touch) = state.touch.take() {
+                touch.release();
+            }
+        }
+    }
+}
+
+impl Dispatch<WlTouch, ()> for WaylandState {
+    fn event(
+        state: &mut Self,
+        _: &WlTouch,
+        event: wl_touch::Event,
         _: &(),
         _: &WaylandConnection,
         _: &QueueHandle<Self>,
     ) {
+        let scale = state.effective_scale;
+
+        match event {
+            wl_touch::Event::Down {
+                serial,
+                id,
+                x,
+                y,
+                ..
+            } => {
+                state.last_serial = serial;
+                state.touch_tracker.down(
+                    id,
+                    x * scale as f64,
+                    y * scale as f64,
+                    &mut state.pending_events,
+                );
+            }
+            wl_touch::Event::Motion {
+                id,
+                x,
+                y,
+                ..
+            } => {
+                state.touch_tracker.motion(
+                    id,
+                    x * scale as f64,
+                    y * scale as f64,
+                    scale,
+                    &mut state.pending_events,
+                );
+            }
+            wl_touch::Event::Up {
+                serial,
+                id,
+                ..
+            } => {
+                state.last_serial = serial;
+                state.touch_tracker.up(id, &mut state.pending_events);
+            }
+            wl_touch::Event::Cancel => {
+                state.touch_tracker.cancel(&mut state.pending_events);
+            }
+            _ => {}
+        }
     }
 }
 
@@ -718,53 +1985,47 @@ impl Dispatch<WlKeyboard, ()> for WaylandState {
             } => {
                 state.last_serial = serial;
 
+                // ESC is delivered as a regular key press; dialogs decide
+                // whether it cancels or closes
                 let keycode = kbvm::Keycode::from_evdev(key);
-                let modifiers = convert_wayland_modifiers(state.modifier_mask);
-
-                // KEY_ESC = 1 in evdev codes
-                if key == 1
-                    && key_state == WEnum::Value(wl_keyboard::KeyState::Pressed)
-                    && state.modifier_mask == kbvm::ModifierMask::NONE
-                {
-                    state.pending_events.push_back(WindowEvent::CloseRequested);
-                    return;
-                }
-
-                if let Some(ref lookup_table) = state.lookup_table {
-                    let group = kbvm::GroupIndex(state.keyboard_group);
-                    let lookup = lookup_table.lookup(group, state.modifier_mask, keycode);
-
-                    let keysym = lookup.into_iter().next().map(|p| p.keysym().0).unwrap_or(0);
-
-                    match key_state {
-                        WEnum::Value(wl_keyboard::KeyState::Pressed) => {
-                            // Emit TextInput for printable characters on key press
-                            let ch: Option<char> = lookup.into_iter().flat_map(|p| p.char()).next();
-
-                            if let Some(c) = ch {
-                                if !c.is_control() && !modifiers.contains(Modifiers::CTRL) {
-                                    state.pending_events.push_back(WindowEvent::TextInput(c));
-                                    return;
-                                }
+                match key_state {
+                    WEnum::Value(wl_keyboard::KeyState::Pressed) => {
+                        state.push_key(keycode, true);
+
+                        // A new key takes over repeating from the previous one
+                        let repeats = state
+                            .lookup_table
+                            .as_ref()
+                            .is_some_and(|table| table.repeats(keycode));
+                        state.key_repeat = (repeats && state.repeat_rate > 0).then(|| {
+                            KeyRepeat {
+                                key: keycode,
+                                next: Instant::now()
+                                    + Duration::from_millis(state.repeat_delay.max(0) as u64),
                             }
-
-                            state
-                                .pending_events
-                                .push_back(WindowEvent::KeyPress(KeyEvent {
-                                    keysym,
-                                    modifiers,
-                                }));
-                        }
-                        WEnum::Value(wl_keyboard::KeyState::Released) => {
-                            state
-                                .pending_events
-                                .push_back(WindowEvent::KeyRelease(KeyEvent {
-                                    keysym,
-                                    modifiers,
-                                }));
+                        });
+                    }
+                    WEnum::Value(wl_keyboard::KeyState::Released) => {
+                        state.push_key(keycode, false);
+                        if state
+                            .key_repeat
+                            .as_ref()
+                            .is_some_and(|repeat| repeat.key == keycode)
+                        {
+                            state.key_repeat = None;
                         }
-                        _ => {}
                     }
+                    _ => {}
+                }
+            }
+            wl_keyboard::Event::RepeatInfo {
+                rate,
+                delay,
+            } => {
+                state.repeat_rate = rate;
+                state.repeat_delay = delay;
+                if rate <= 0 {
+                    state.key_repeat = None;
                 }
             }
             wl_keyboard::Event::Modifiers {
@@ -787,6 +2048,7 @@ impl Dispatch<WlKeyboard, ()> for WaylandState {
                 serial, ..
             } => {
                 state.last_serial = serial;
+                state.key_repeat = None;
             }
             _ => {}
         }
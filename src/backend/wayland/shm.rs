@@ -77,3 +77,10 @@ impl ShmPool {
         &mut self.data[..self.size]
     }
 }
+
+impl Drop for ShmPool {
+    fn drop(&mut self) {
+        // Buffers created from the pool stay valid after this
+        self.pool.destroy();
+    }
+}
@@ -1,13 +1,22 @@
 //! X11 backend implementation.
 
-use std::{ops::Deref, rc::Rc};
+use std::{
+    collections::VecDeque,
+    io,
+    ops::Deref,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 use kbvm::{lookup::LookupTable, xkb::x11::KbvmX11Ext};
 use x11rb::{
     connection::Connection as X11rbConnection,
-    properties::WmSizeHints,
+    properties::{WmSizeHints, WmSizeHintsSpecification},
     protocol::{
         Event,
+        xinerama::ConnectionExt as _,
+        xinput::{self, ConnectionExt as _, XIEventMask},
         xproto::{
             self, AtomEnum, ClientMessageEvent, ConfigureWindowAux, ConnectionExt as _,
             CreateWindowAux, EventMask, ImageFormat, KeyButMask, PropMode, StackMode, VisualClass,
@@ -19,12 +28,12 @@ use x11rb::{
 };
 
 use super::{
-    CursorPos, CursorShape, DisplayConnection, KeyEvent, Modifiers, MouseButton, ScrollDirection,
-    Window, WindowEvent,
+    CursorPos, CursorShape, DisplayConnection, KeyEvent, Modifiers, Monitor, MouseButton,
+    ScrollDirection, Window, WindowEvent, WindowHandle, dnd::parse_uri_list, touch::TouchTracker,
 };
 use crate::{
     error::{Error, X11Error},
-    render::Canvas,
+    render::{Canvas, Damage},
 };
 
 x11rb::atom_manager! {
@@ -35,10 +44,38 @@ x11rb::atom_manager! {
         WM_DELETE_WINDOW,
 
         _NET_WM_NAME,
+        _NET_WM_ICON,
+        _NET_WM_WINDOW_OPACITY,
         _NET_WM_WINDOW_TYPE,
         _NET_WM_WINDOW_TYPE_DIALOG,
+        _NET_WM_STATE,
+        _NET_WM_STATE_MODAL,
+        _NET_WM_STATE_SKIP_TASKBAR,
+        _NET_WM_STATE_SKIP_PAGER,
 
         _NET_WM_MOVERESIZE,
+
+        // KWin blurs what shows through windows with this property
+        _KDE_NET_WM_BLUR_BEHIND_REGION,
+
+        _NET_STARTUP_ID,
+        _NET_STARTUP_INFO_BEGIN,
+        _NET_STARTUP_INFO,
+
+        // Property selections are delivered into
+        _ZENITY_RS_SELECTION,
+
+        XdndAware,
+        XdndEnter,
+        XdndPosition,
+        XdndStatus,
+        XdndLeave,
+        XdndDrop,
+        XdndFinished,
+        XdndSelection,
+        XdndTypeList,
+        XdndActionCopy,
+        TEXT_URI_LIST: b"text/uri-list",
     }
 }
 
@@ -71,32 +108,140 @@ impl DisplayConnection for Connection {
         })
     }
 
-    fn create_window(&self, width: u16, height: u16) -> Result<Self::Window, Error> {
-        X11Window::create(self.clone(), width, height)
+    fn create_window(
+        &self,
+        width: u16,
+        height: u16,
+        monitor: Monitor,
+        translucent: bool,
+    ) -> Result<Self::Window, Error> {
+        X11Window::create(self.clone(), width, height, monitor, translucent)
+    }
+}
+
+impl Connection {
+    /// Returns whether a compositing manager runs on our screen, which it
+    /// shows by owning the `_NET_WM_CM_Sn` selection.
+    fn compositing(&self) -> Result<bool, Error> {
+        let name = format!("_NET_WM_CM_S{}", self.screen);
+        let atom = self.intern_atom(false, name.as_bytes())?.reply()?.atom;
+        Ok(self.get_selection_owner(atom)?.reply()?.owner != x11rb::NONE)
     }
 }
 
 const MOVERESIZE_MOVE: u32 = 8;
-const KEYCODE_ESC: u8 = 9;
+/// How long a selection owner gets to send its data.
+const SELECTION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Time between animation frames, as X11 has no signal for when the
+/// display is ready for the next one.
+const FRAME_INTERVAL: Duration = Duration::from_micros(16_667);
+/// XDND protocol version spoken.
+const XDND_VERSION: u32 = 5;
 const WM_CLASS: &[u8] = b"zenity\0Zenity\0";
 
 // X11 cursor font character constants
 const XC_LEFT_PTR: u16 = 68; // Default arrow
 const XC_XTERM: u16 = 152; // Text I-beam
+const XC_HAND2: u16 = 60; // Pointing hand
+
+/// Returns the bounds of a Xinerama screen, or `None` without Xinerama or
+/// when there is no such screen.
+fn find_monitor(
+    conn: &RustConnection,
+    root: xproto::Window,
+    monitor: Monitor,
+) -> Option<(i32, i32, u32, u32)> {
+    let screens = conn
+        .xinerama_query_screens()
+        .ok()?
+        .reply()
+        .ok()?
+        .screen_info;
+    let bounds = |screen: &x11rb::protocol::xinerama::ScreenInfo| {
+        (
+            i32::from(screen.x_org),
+            i32::from(screen.y_org),
+            u32::from(screen.width),
+            u32::from(screen.height),
+        )
+    };
+
+    match monitor {
+        Monitor::Index(index) => screens.get(index).map(bounds),
+        // The X server lists the RandR primary output first
+        Monitor::Primary => screens.first().map(bounds),
+        // Window managers usually place new windows where the pointer is
+        Monitor::Default | Monitor::Cursor => {
+            let pointer = conn.query_pointer(root).ok()?.reply().ok()?;
+            let (px, py) = (i32::from(pointer.root_x), i32::from(pointer.root_y));
+            screens
+                .iter()
+                .map(bounds)
+                .find(|&(x, y, w, h)| px >= x && px < x + w as i32 && py >= y && py < y + h as i32)
+                .or_else(|| screens.first().map(bounds))
+        }
+    }
+}
+
+/// A drag from another window that is over ours.
+struct XdndDrag {
+    source: xproto::Window,
+    /// Whether files are offered
+    accepted: bool,
+}
 
 pub(crate) struct X11Window {
     atoms: Atoms,
     conn: Connection,
     window: xproto::Window,
     gc: xproto::Gcontext,
+    /// Colormap made for the window's visual, or 0 if it uses the root's
+    colormap: xproto::Colormap,
+    /// Bits per pixel of the window's visual: 32 with alpha, else 24
+    depth: u8,
     lookup_table: LookupTable,
     xkb_group: u8,
     cursor_text: xproto::Cursor,
+    cursor_pointer: xproto::Cursor,
     current_cursor: CursorShape,
+    width: u16,
+    height: u16,
+    /// Smallest size the user may resize to; `None` while fixed-size
+    min_size: Option<(u16, u16)>,
+    /// Position asked for with `set_position`
+    position: Option<(i32, i32)>,
+    root: xproto::Window,
+    /// Bounds of the monitor the window is placed on
+    monitor_area: (i32, i32, u32, u32),
+    touch_tracker: TouchTracker,
+    /// Events produced by one X event beyond the first
+    pending_events: VecDeque<WindowEvent>,
+    xdnd: Option<XdndDrag>,
+    /// Drop whose data is yet to be read: source window and timestamp
+    pending_drop: Option<(xproto::Window, xproto::Timestamp)>,
+    /// Startup notification to complete once the window is shown
+    startup_id: Option<String>,
+    /// Goes off when the next animation frame is due
+    frame_timer: Option<OwnedFd>,
+    /// Whether part of the window lost its contents since they were last
+    /// sent
+    exposed: bool,
 }
 
 impl X11Window {
-    fn create(conn: Connection, width: u16, height: u16) -> Result<Self, Error> {
+    /// Returns the connection the window was made on.
+    pub(crate) fn connection(&self) -> Connection {
+        self.conn.clone()
+    }
+
+    fn create(
+        conn: Connection,
+        width: u16,
+        height: u16,
+        monitor: Monitor,
+        translucent: bool,
+    ) -> Result<Self, Error> {
         let atoms = Atoms::new(&conn.inner)?.reply()?;
 
         let screen = conn
@@ -104,28 +249,39 @@ impl X11Window {
             .setup()
             .roots
             .get(conn.screen)
-            .ok_or(Error::X11(X11Error::NoVisual))?;
-
-        // Find a 24-bit TrueColor visual
-        let visuals = screen
-            .allowed_depths
-            .iter()
-            .flat_map(|d| d.visuals.iter().map(move |vis| (vis, d.depth)));
-
-        let mut vid = None;
-        for (vty, depth) in visuals {
-            if depth == 24
-                && vty.class == VisualClass::TRUE_COLOR
-                && vty.red_mask == 0xff0000
-                && vty.green_mask == 0xff00
-                && vty.blue_mask == 0xff
-            {
-                vid = Some(vty.visual_id);
-                break;
-            }
-        }
+            .ok_or(X11Error::NoVisual)?;
+
+        // Find a TrueColor visual: a 32-bit one with alpha for translucent
+        // windows, which only a compositor can show, else a 24-bit one
+        let find_visual = |wanted: u8| {
+            screen
+                .allowed_depths
+                .iter()
+                .filter(|d| d.depth == wanted)
+                .flat_map(|d| d.visuals.iter())
+                .find(|vty| {
+                    vty.class == VisualClass::TRUE_COLOR
+                        && vty.red_mask == 0xff0000
+                        && vty.green_mask == 0xff00
+                        && vty.blue_mask == 0xff
+                })
+                .map(|vty| (vty.visual_id, wanted))
+        };
+        let argb = if translucent && conn.compositing()? {
+            find_visual(32)
+        } else {
+            None
+        };
+        let (vid, depth) = argb.or_else(|| find_visual(24)).ok_or(X11Error::NoVisual)?;
 
-        let vid = vid.ok_or(Error::X11(X11Error::NoVisual))?;
+        // Other visuals than the root's need a colormap of their own
+        let colormap = if vid == screen.root_visual {
+            0
+        } else {
+            let colormap = conn.generate_id()?;
+            conn.create_colormap(xproto::ColormapAlloc::NONE, colormap, screen.root, vid)?;
+            colormap
+        };
 
         let attrs = CreateWindowAux::new()
             .event_mask(
@@ -142,12 +298,12 @@ impl X11Window {
                     | EventMask::BUTTON_RELEASE,
             )
             .border_pixel(0)
-            .colormap(0);
+            .colormap(colormap);
 
         let window = conn.generate_id()?;
         conn.inner
             .create_window(
-                24,
+                depth,
                 window,
                 screen.root,
                 0,
@@ -177,26 +333,57 @@ impl X11Window {
             &[atoms.WM_DELETE_WINDOW],
         )?;
 
-        // Configure size hints to prevent resizing
-        WmSizeHints {
-            max_size: Some((width.into(), height.into())),
-            min_size: Some((width.into(), height.into())),
-            ..Default::default()
+        if argb.is_some() {
+            // An empty region blurs behind the whole window
+            conn.change_property32(
+                PropMode::REPLACE,
+                window,
+                atoms._KDE_NET_WM_BLUR_BEHIND_REGION,
+                AtomEnum::CARDINAL,
+                &[],
+            )?;
         }
-        .set_normal_hints(&conn.inner, window)?
-        .check()?;
+
+        // Accept files dropped from other windows
+        conn.change_property32(
+            PropMode::REPLACE,
+            window,
+            atoms.XdndAware,
+            AtomEnum::ATOM,
+            &[XDND_VERSION],
+        )?;
 
         // Initialize keyboard handling with kbvm
-        conn.setup_xkb_extension()
-            .map_err(|_| Error::X11(X11Error::NoVisual))?;
+        conn.setup_xkb_extension().map_err(|_| X11Error::NoVisual)?;
         let device_id = conn
             .get_xkb_core_device_id()
-            .map_err(|_| Error::X11(X11Error::NoVisual))?;
+            .map_err(|_| X11Error::NoVisual)?;
         let keymap = conn
             .get_xkb_keymap(device_id)
-            .map_err(|_| Error::X11(X11Error::NoVisual))?;
+            .map_err(|_| X11Error::NoVisual)?;
         let lookup_table = keymap.to_builder().build_lookup_table();
 
+        // Touches need XInput 2.2; without it the server sends them as
+        // emulated pointer events instead
+        let has_touch = conn
+            .xinput_xi_query_version(2, 2)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some_and(|version| (version.major_version, version.minor_version) >= (2, 2));
+        if has_touch {
+            conn.xinput_xi_select_events(
+                window,
+                &[xinput::EventMask {
+                    deviceid: xinput::Device::ALL_MASTER.into(),
+                    mask: vec![
+                        XIEventMask::TOUCH_BEGIN
+                            | XIEventMask::TOUCH_UPDATE
+                            | XIEventMask::TOUCH_END,
+                    ],
+                }],
+            )?;
+        }
+
         // Create cursors from the cursor font for the default arrow only.
         // IMPORTANT: do NOT set any window cursor during creation — letting the
         // compositor/WM choose the initial cursor allows it to follow themes.
@@ -205,6 +392,14 @@ impl X11Window {
         // when entering text fields. When leaving the text field we'll clear the
         // window cursor (set cursor to 0) so the compositor can restore the
         // themed default pointer.
+        let root = screen.root;
+        let monitor_area = find_monitor(&conn, root, monitor).unwrap_or((
+            0,
+            0,
+            screen.width_in_pixels.into(),
+            screen.height_in_pixels.into(),
+        ));
+
         let cursor_font = conn.generate_id()?;
         conn.open_font(cursor_font, b"cursor")?;
 
@@ -238,6 +433,21 @@ impl X11Window {
             0xffff,
         )?;
 
+        let cursor_pointer = conn.generate_id()?;
+        conn.create_glyph_cursor(
+            cursor_pointer,
+            cursor_font,
+            cursor_font,
+            XC_HAND2,
+            XC_HAND2 + 1,
+            0,
+            0,
+            0,
+            0xffff,
+            0xffff,
+            0xffff,
+        )?;
+
         conn.close_font(cursor_font)?;
 
         let win = X11Window {
@@ -245,17 +455,66 @@ impl X11Window {
             conn,
             window,
             gc,
+            colormap,
+            depth,
             lookup_table,
             xkb_group: 0,
             cursor_text,
+            cursor_pointer,
             current_cursor: CursorShape::Default,
+            width,
+            height,
+            min_size: None,
+            position: None,
+            root,
+            monitor_area,
+            touch_tracker: TouchTracker::default(),
+            pending_events: VecDeque::new(),
+            xdnd: None,
+            pending_drop: None,
+            startup_id: take_startup_id(),
+            frame_timer: None,
+            exposed: true,
         };
+        // Configure size hints to prevent resizing
+        win.update_size_hints()?;
         win.set_class(WM_CLASS)?;
         win.set_window_type(WindowType::Dialog)?;
+        if let Some(id) = &win.startup_id {
+            // Lets the window manager match the window to its launch for
+            // focus stealing prevention
+            win.conn
+                .change_property8(
+                    PropMode::REPLACE,
+                    win.window,
+                    win.atoms._NET_STARTUP_ID,
+                    win.atoms.UTF8_STRING,
+                    id.as_bytes(),
+                )?
+                .check()?;
+        }
 
         Ok(win)
     }
 
+    /// Writes the size hints from the current size, resize limit and
+    /// position.
+    fn update_size_hints(&self) -> Result<(), Error> {
+        let size = (self.width.into(), self.height.into());
+        WmSizeHints {
+            // Without a minimum the window stays at its current size
+            max_size: self.min_size.is_none().then_some(size),
+            min_size: Some(self.min_size.map_or(size, |(w, h)| (w.into(), h.into()))),
+            position: self
+                .position
+                .map(|(x, y)| (WmSizeHintsSpecification::UserSpecified, x, y)),
+            ..Default::default()
+        }
+        .set_normal_hints(&self.conn.inner, self.window)?
+        .check()?;
+        Ok(())
+    }
+
     fn set_class(&self, cls: &[u8]) -> Result<(), Error> {
         self.conn
             .change_property8(
@@ -285,21 +544,229 @@ impl X11Window {
         Ok(())
     }
 
+    /// Asks for a selection converted to `target` and waits for it,
+    /// keeping other events for later. Returns the data and its type, or
+    /// `None` if the owner had nothing to give.
+    fn read_selection(
+        &mut self,
+        selection: xproto::Atom,
+        target: xproto::Atom,
+        time: xproto::Timestamp,
+    ) -> Result<Option<(xproto::Atom, Vec<u8>)>, Error> {
+        self.conn.convert_selection(
+            self.window,
+            selection,
+            target,
+            self.atoms._ZENITY_RS_SELECTION,
+            time,
+        )?;
+        self.conn.flush()?;
+
+        let deadline = Instant::now() + SELECTION_TIMEOUT;
+        loop {
+            let Some(ev) = self.conn.poll_for_event()? else {
+                let timeout = deadline.saturating_duration_since(Instant::now());
+                if timeout.is_zero() {
+                    return Ok(None);
+                }
+                let mut fd = libc::pollfd {
+                    fd: self.conn.inner.stream().as_raw_fd(),
+                    events: libc::POLLIN,
+                    revents: 0,
+                };
+                let timeout = timeout.as_millis().try_into().unwrap_or(i32::MAX);
+                unsafe { libc::poll(&mut fd, 1, timeout) };
+                continue;
+            };
+
+            let Event::SelectionNotify(e) = ev else {
+                if let Some(ev) = self.cvt_event(ev) {
+                    self.pending_events.push_back(ev);
+                }
+                continue;
+            };
+            if e.requestor != self.window || e.selection != selection {
+                continue;
+            }
+            // No owner, or it couldn't convert the data
+            if e.property == x11rb::NONE {
+                return Ok(None);
+            }
+
+            let reply = self
+                .conn
+                .get_property(
+                    true,
+                    self.window,
+                    e.property,
+                    AtomEnum::ANY,
+                    0,
+                    u32::MAX / 4,
+                )?
+                .reply()?;
+            return Ok(Some((reply.type_, reply.value)));
+        }
+    }
+
+    /// Tracks a drag over the window from XDND client messages.
+    fn handle_xdnd(&mut self, msg: &ClientMessageEvent) -> Result<(), Error> {
+        let data = msg.data.as_data32();
+        let source = data[0];
+
+        if msg.type_ == self.atoms.XdndEnter {
+            // More than three types are listed on the source window
+            let types = if data[1] & 1 != 0 {
+                self.conn
+                    .get_property(
+                        false,
+                        source,
+                        self.atoms.XdndTypeList,
+                        AtomEnum::ATOM,
+                        0,
+                        1024,
+                    )?
+                    .reply()?
+                    .value32()
+                    .map(Iterator::collect)
+                    .unwrap_or_default()
+            } else {
+                data[2..5].to_vec()
+            };
+            self.xdnd = Some(XdndDrag {
+                source,
+                accepted: types.contains(&self.atoms.TEXT_URI_LIST),
+            });
+            return Ok(());
+        }
+
+        let Some(drag) = self.xdnd.as_ref().filter(|drag| drag.source == source) else {
+            return Ok(());
+        };
+        let accepted = drag.accepted;
+        let action = if accepted {
+            self.atoms.XdndActionCopy
+        } else {
+            x11rb::NONE
+        };
+
+        if msg.type_ == self.atoms.XdndPosition {
+            self.send_xdnd(
+                source,
+                self.atoms.XdndStatus,
+                [self.window, accepted as u32, 0, 0, action],
+            )?;
+        } else if msg.type_ == self.atoms.XdndLeave {
+            self.xdnd = None;
+        } else if msg.type_ == self.atoms.XdndDrop {
+            self.xdnd = None;
+            if accepted {
+                // The data is read once the event has been handled
+                self.pending_drop = Some((source, data[2]));
+            } else {
+                self.send_xdnd(source, self.atoms.XdndFinished, [self.window, 0, 0, 0, 0])?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if the frame timer has gone off since it was set.
+    fn frame_due(&mut self) -> bool {
+        let Some(timer) = &self.frame_timer else {
+            return false;
+        };
+        let mut expirations = 0u64;
+        let read = unsafe { libc::read(timer.as_raw_fd(), (&raw mut expirations).cast(), 8) };
+        read == 8
+    }
+
+    /// Reads the files of a drop and tells the source it is done.
+    fn finish_drop(&mut self) -> Result<(), Error> {
+        let Some((source, time)) = self.pending_drop.take() else {
+            return Ok(());
+        };
+
+        let paths = self
+            .read_selection(self.atoms.XdndSelection, self.atoms.TEXT_URI_LIST, time)?
+            .map(|(_, data)| parse_uri_list(&data))
+            .unwrap_or_default();
+        let (accepted, action) = if paths.is_empty() {
+            (0, x11rb::NONE)
+        } else {
+            (1, self.atoms.XdndActionCopy)
+        };
+        self.send_xdnd(
+            source,
+            self.atoms.XdndFinished,
+            [self.window, accepted, action, 0, 0],
+        )?;
+
+        if !paths.is_empty() {
+            self.pending_events.push_back(WindowEvent::Drop(paths));
+        }
+        Ok(())
+    }
+
+    fn send_xdnd(
+        &self,
+        target: xproto::Window,
+        message: xproto::Atom,
+        data: [u32; 5],
+    ) -> Result<(), Error> {
+        self.conn.send_event(
+            false,
+            target,
+            EventMask::NO_EVENT,
+            ClientMessageEvent::new(32, target, message, data),
+        )?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    /// Tells the launcher that started us that we're up, so it stops
+    /// showing a busy cursor. The message is broadcast on the root window
+    /// in pieces of 20 bytes.
+    fn complete_startup(&self, id: &str) -> Result<(), Error> {
+        let mut message = format!("remove: ID={}", quote_startup_value(id)).into_bytes();
+        message.push(0);
+        for (idx, chunk) in message.chunks(20).enumerate() {
+            let type_ = if idx == 0 {
+                self.atoms._NET_STARTUP_INFO_BEGIN
+            } else {
+                self.atoms._NET_STARTUP_INFO
+            };
+            let mut data = [0; 20];
+            data[..chunk.len()].copy_from_slice(chunk);
+            self.conn.send_event(
+                false,
+                self.root,
+                EventMask::PROPERTY_CHANGE,
+                ClientMessageEvent::new(8, self.window, type_, data),
+            )?;
+        }
+        Ok(())
+    }
+
     fn cvt_event(&mut self, ev: Event) -> Option<WindowEvent> {
         Some(match ev {
+            Event::ClientMessage(msg)
+                if [
+                    self.atoms.XdndEnter,
+                    self.atoms.XdndPosition,
+                    self.atoms.XdndLeave,
+                    self.atoms.XdndDrop,
+                ]
+                .contains(&msg.type_) =>
+            {
+                // A failure only leaves the drag unanswered
+                let _ = self.handle_xdnd(&msg);
+                return None;
+            }
             Event::ClientMessage(msg) if msg.data.as_data32()[0] == self.atoms.WM_DELETE_WINDOW => {
                 WindowEvent::CloseRequested
             }
             Event::KeyPress(press) if press.event == self.window => {
-                // ESC without modifiers closes the dialog
-                if press.detail == KEYCODE_ESC
-                    && !press
-                        .state
-                        .intersects(KeyButMask::CONTROL | KeyButMask::SHIFT | KeyButMask::MOD1)
-                {
-                    return Some(WindowEvent::CloseRequested);
-                }
-
+                // ESC is delivered as a regular key press; dialogs decide
+                // whether it cancels or closes
                 let modifiers = convert_modifiers(press.state);
                 let keycode = kbvm::Keycode::from_x11(press.detail.into());
                 let mods = convert_to_kbvm_mods(press.state);
@@ -312,7 +779,7 @@ impl X11Window {
                 // Get character from lookup and emit TextInput for printable characters
                 let ch: Option<char> = lookup.into_iter().flat_map(|p| p.char()).next();
                 if let Some(c) = ch {
-                    if !c.is_control() && !modifiers.contains(Modifiers::CTRL) {
+                    if !c.is_control() && !modifiers.intersects(Modifiers::CTRL | Modifiers::ALT) {
                         return Some(WindowEvent::TextInput(c));
                     }
                 }
@@ -341,7 +808,23 @@ impl X11Window {
                     modifiers,
                 })
             }
-            Event::Expose(ex) if ex.count == 0 => WindowEvent::RedrawRequested,
+            Event::Expose(ex) => {
+                // The server doesn't keep what was covered, so all of it
+                // goes out with the next contents
+                self.exposed = true;
+                if ex.count > 0 {
+                    return None;
+                }
+                WindowEvent::RedrawRequested
+            }
+            // Moves also arrive as ConfigureNotify; only report size changes
+            Event::ConfigureNotify(e)
+                if e.window == self.window && (e.width, e.height) != (self.width, self.height) =>
+            {
+                self.width = e.width;
+                self.height = e.height;
+                WindowEvent::Resized(e.width.into(), e.height.into())
+            }
             Event::EnterNotify(e) => {
                 WindowEvent::CursorEnter(CursorPos {
                     x: e.event_x,
@@ -374,11 +857,62 @@ impl X11Window {
                     }
                 }
             }
+            // Touch events are queued by the tracker rather than returned
+            Event::XinputTouchBegin(e) if e.event == self.window => {
+                self.touch_tracker.down(
+                    e.detail as i32,
+                    fp1616(e.event_x),
+                    fp1616(e.event_y),
+                    &mut self.pending_events,
+                );
+                return None;
+            }
+            Event::XinputTouchUpdate(e) if e.event == self.window => {
+                self.touch_tracker.motion(
+                    e.detail as i32,
+                    fp1616(e.event_x),
+                    fp1616(e.event_y),
+                    self.scale_factor(),
+                    &mut self.pending_events,
+                );
+                return None;
+            }
+            Event::XinputTouchEnd(e) if e.event == self.window => {
+                self.touch_tracker
+                    .up(e.detail as i32, &mut self.pending_events);
+                return None;
+            }
             _ => return None,
         })
     }
 }
 
+/// Takes the startup notification ID passed in the environment, removing
+/// it so that anything we start doesn't complete our launch.
+fn take_startup_id() -> Option<String> {
+    let id = std::env::var("DESKTOP_STARTUP_ID").ok();
+    unsafe { std::env::remove_var("DESKTOP_STARTUP_ID") };
+    id.filter(|id| !id.is_empty())
+}
+
+/// Quotes a startup notification value, escaping quotes and backslashes.
+fn quote_startup_value(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for ch in value.chars() {
+        if ch == '"' || ch == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(ch);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Converts an XInput 16.16 fixed-point coordinate.
+fn fp1616(value: xinput::Fp1616) -> f64 {
+    value as f64 / 65536.0
+}
+
 fn convert_modifiers(state: KeyButMask) -> Modifiers {
     let mut mods = Modifiers::empty();
     if state.contains(KeyButMask::SHIFT) {
@@ -416,6 +950,21 @@ fn convert_to_kbvm_mods(state: KeyButMask) -> kbvm::ModifierMask {
     mods
 }
 
+impl Drop for X11Window {
+    /// Takes the window down, as the connection may stay open for further
+    /// dialogs.
+    fn drop(&mut self) {
+        let _ = self.conn.destroy_window(self.window);
+        let _ = self.conn.free_gc(self.gc);
+        if self.colormap != 0 {
+            let _ = self.conn.free_colormap(self.colormap);
+        }
+        let _ = self.conn.free_cursor(self.cursor_text);
+        let _ = self.conn.free_cursor(self.cursor_pointer);
+        let _ = self.conn.flush();
+    }
+}
+
 impl Window for X11Window {
     fn set_title(&mut self, title: &str) -> Result<(), Error> {
         let title = if title.ends_with('\0') {
@@ -446,22 +995,31 @@ impl Window for X11Window {
         Ok(())
     }
 
-    fn set_contents(&mut self, canvas: &Canvas) -> Result<(), Error> {
-        let data = canvas.as_argb();
-        self.conn
-            .put_image(
-                ImageFormat::Z_PIXMAP,
-                self.window,
-                self.gc,
-                canvas.width().try_into().unwrap(),
-                canvas.height().try_into().unwrap(),
-                0,
-                0,
-                0,
-                24,
-                &data,
-            )?
-            .check()?;
+    fn set_contents_damaged(&mut self, canvas: &Canvas, damage: &[Damage]) -> Result<(), Error> {
+        // Only the changed areas are sent while the window keeps the rest
+        let whole = [Damage::whole(canvas)];
+        let damage = if std::mem::take(&mut self.exposed) {
+            &whole
+        } else {
+            damage
+        };
+        for area in damage {
+            let data = canvas.argb_region(*area);
+            self.conn
+                .put_image(
+                    ImageFormat::Z_PIXMAP,
+                    self.window,
+                    self.gc,
+                    area.width.try_into().unwrap(),
+                    area.height.try_into().unwrap(),
+                    area.x as i16,
+                    area.y as i16,
+                    0,
+                    self.depth,
+                    &data,
+                )?
+                .check()?;
+        }
         Ok(())
     }
 
@@ -471,32 +1029,102 @@ impl Window for X11Window {
             self.window,
             &ConfigureWindowAux::new().stack_mode(StackMode::ABOVE),
         )?;
+        if let Some(id) = self.startup_id.take() {
+            self.complete_startup(&id)?;
+        }
         self.conn.flush()?;
         Ok(())
     }
 
     fn wait_for_event(&mut self) -> Result<WindowEvent, Error> {
         loop {
-            let ev = self.conn.wait_for_event()?;
+            if let Some(ev) = self.pending_events.pop_front() {
+                return Ok(ev);
+            }
+            if self.frame_due() {
+                return Ok(WindowEvent::RedrawRequested);
+            }
+            let ev = match &self.frame_timer {
+                Some(timer) => {
+                    let Some(ev) = self.conn.poll_for_event()? else {
+                        // Wait for whichever comes first
+                        self.conn.flush()?;
+                        let mut fds = [self.conn.inner.stream().as_raw_fd(), timer.as_raw_fd()]
+                            .map(|fd| {
+                                libc::pollfd {
+                                    fd,
+                                    events: libc::POLLIN,
+                                    revents: 0,
+                                }
+                            });
+                        unsafe { libc::poll(fds.as_mut_ptr(), 2, -1) };
+                        continue;
+                    };
+                    ev
+                }
+                None => self.conn.wait_for_event()?,
+            };
             if let Some(ev) = self.cvt_event(ev) {
                 return Ok(ev);
             }
+            self.finish_drop()?;
         }
     }
 
     fn poll_for_event(&mut self) -> Result<Option<WindowEvent>, Error> {
         loop {
+            if let Some(ev) = self.pending_events.pop_front() {
+                return Ok(Some(ev));
+            }
+            if self.frame_due() {
+                return Ok(Some(WindowEvent::RedrawRequested));
+            }
             match self.conn.poll_for_event()? {
                 Some(ev) => {
                     if let Some(ev) = self.cvt_event(ev) {
                         return Ok(Some(ev));
                     }
+                    self.finish_drop()?;
                 }
                 None => return Ok(None),
             }
         }
     }
 
+    fn wait_for_event_or(
+        &mut self,
+        fds: &[libc::pollfd],
+        deadline: Option<Instant>,
+    ) -> Result<Option<WindowEvent>, Error> {
+        let watch = |fd| {
+            libc::pollfd {
+                fd,
+                events: libc::POLLIN,
+                revents: 0,
+            }
+        };
+        loop {
+            if let Some(ev) = self.poll_for_event()? {
+                return Ok(Some(ev));
+            }
+            self.conn.flush()?;
+            let mut all = vec![watch(self.conn.inner.stream().as_raw_fd())];
+            all.extend(
+                self.frame_timer
+                    .as_ref()
+                    .map(|timer| watch(timer.as_raw_fd())),
+            );
+            let own = all.len();
+            all.extend_from_slice(fds);
+            super::poll(&mut all, deadline);
+            if all[own..].iter().any(|fd| fd.revents != 0)
+                || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                return Ok(None);
+            }
+        }
+    }
+
     fn start_drag(&mut self) -> Result<(), Error> {
         let pointer = self.conn.query_pointer(self.window)?.reply()?;
 
@@ -539,6 +1167,7 @@ impl Window for X11Window {
         // (cursor = 0) so the compositor/WM can restore the themed default.
         let cursor_id: u32 = match shape {
             CursorShape::Text => self.cursor_text,
+            CursorShape::Pointer => self.cursor_pointer,
             CursorShape::Default => 0, // clear the cursor attribute
         };
 
@@ -551,6 +1180,194 @@ impl Window for X11Window {
         self.current_cursor = shape;
         Ok(())
     }
+
+    fn set_resizable(&mut self, min_width: u16, min_height: u16) -> Result<(), Error> {
+        self.min_size = Some((min_width, min_height));
+        self.update_size_hints()
+    }
+
+    fn set_size(&mut self, width: u16, height: u16) -> Result<(), Error> {
+        // The change was asked for, so don't report it back as a resize
+        self.width = width;
+        self.height = height;
+        self.update_size_hints()?;
+        self.conn.configure_window(
+            self.window,
+            &ConfigureWindowAux::new()
+                .width(u32::from(width))
+                .height(u32::from(height)),
+        )?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) -> Result<(), Error> {
+        // Marked as user-specified so window managers don't place it themselves
+        self.position = Some((x, y));
+        self.update_size_hints()?;
+        self.conn
+            .configure_window(self.window, &ConfigureWindowAux::new().x(x).y(y))?;
+        self.conn.flush()?;
+        Ok(())
+    }
+
+    fn monitor_area(&self) -> Option<(i32, i32, u32, u32)> {
+        Some(self.monitor_area)
+    }
+
+    fn pointer_position(&self) -> Option<(i32, i32)> {
+        let pointer = self.conn.query_pointer(self.root).ok()?.reply().ok()?;
+        Some((pointer.root_x.into(), pointer.root_y.into()))
+    }
+
+    fn primary_selection(&mut self) -> Result<Option<String>, Error> {
+        let Some((type_, data)) = self.read_selection(
+            AtomEnum::PRIMARY.into(),
+            self.atoms.UTF8_STRING,
+            x11rb::CURRENT_TIME,
+        )?
+        else {
+            return Ok(None);
+        };
+
+        // Large selections sent in INCR chunks aren't supported
+        Ok(if type_ == self.atoms.UTF8_STRING {
+            Some(String::from_utf8_lossy(&data).into_owned())
+        } else if type_ == u32::from(AtomEnum::STRING) {
+            // Latin-1
+            Some(data.iter().map(|&b| b as char).collect())
+        } else {
+            None
+        })
+    }
+
+    fn set_parent(&mut self, parent: &WindowHandle) -> Result<(), Error> {
+        let WindowHandle::X11(parent) = *parent else {
+            return Ok(());
+        };
+
+        // Window managers keep transient windows above their parent; the
+        // modal state asks them to treat the parent as blocked. The parent
+        // already stands for us in taskbars and pagers.
+        self.conn
+            .change_property32(
+                PropMode::REPLACE,
+                self.window,
+                AtomEnum::WM_TRANSIENT_FOR,
+                AtomEnum::WINDOW,
+                &[parent],
+            )?
+            .check()?;
+        self.conn
+            .change_property32(
+                PropMode::REPLACE,
+                self.window,
+                self.atoms._NET_WM_STATE,
+                AtomEnum::ATOM,
+                &[
+                    self.atoms._NET_WM_STATE_MODAL,
+                    self.atoms._NET_WM_STATE_SKIP_TASKBAR,
+                    self.atoms._NET_WM_STATE_SKIP_PAGER,
+                ],
+            )?
+            .check()?;
+
+        // A parent that is gone only loses the centering
+        let Ok(geometry) = self.conn.get_geometry(parent)?.reply() else {
+            return Ok(());
+        };
+        let Ok(origin) = self
+            .conn
+            .translate_coordinates(parent, self.root, 0, 0)?
+            .reply()
+        else {
+            return Ok(());
+        };
+        let x = origin.dst_x as i32 + (geometry.width as i32 - self.width as i32) / 2;
+        let y = origin.dst_y as i32 + (geometry.height as i32 - self.height as i32) / 2;
+        self.set_position(x, y)
+    }
+
+    fn set_icon(&mut self, icon: &Canvas) -> Result<(), Error> {
+        // Width and height, then straight (not premultiplied) ARGB pixels
+        let mut data = vec![icon.width(), icon.height()];
+        data.extend(icon.pixmap.pixels().iter().map(|pixel| {
+            let color = pixel.demultiply();
+            u32::from_be_bytes([color.alpha(), color.red(), color.green(), color.blue()])
+        }));
+        self.conn
+            .change_property32(
+                PropMode::REPLACE,
+                self.window,
+                self.atoms._NET_WM_ICON,
+                AtomEnum::CARDINAL,
+                &data,
+            )?
+            .check()?;
+        Ok(())
+    }
+
+    fn needs_decorations(&self) -> bool {
+        // The window manager decorates X11 windows
+        false
+    }
+
+    fn request_frame(&mut self) -> Result<(), Error> {
+        let timer = match &self.frame_timer {
+            Some(timer) => timer,
+            None => {
+                let fd = unsafe {
+                    libc::timerfd_create(
+                        libc::CLOCK_MONOTONIC,
+                        libc::TFD_CLOEXEC | libc::TFD_NONBLOCK,
+                    )
+                };
+                if fd < 0 {
+                    return Err(io::Error::last_os_error().into());
+                }
+                self.frame_timer.insert(unsafe { OwnedFd::from_raw_fd(fd) })
+            }
+        };
+
+        let spec = libc::itimerspec {
+            it_interval: libc::timespec {
+                tv_sec: 0,
+                tv_nsec: 0,
+            },
+            it_value: libc::timespec {
+                tv_sec: 0,
+                tv_nsec: FRAME_INTERVAL.as_nanos() as _,
+            },
+        };
+        if unsafe { libc::timerfd_settime(timer.as_raw_fd(), 0, &spec, std::ptr::null_mut()) } != 0
+        {
+            return Err(io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+
+    fn set_opacity(&mut self, opacity: f32) -> Result<(), Error> {
+        // Compositing managers blend the window by this property
+        if opacity >= 1.0 {
+            self.conn
+                .delete_property(self.window, self.atoms._NET_WM_WINDOW_OPACITY)?;
+        } else {
+            let value = (opacity.max(0.0) * u32::MAX as f32) as u32;
+            self.conn.change_property32(
+                PropMode::REPLACE,
+                self.window,
+                self.atoms._NET_WM_WINDOW_OPACITY,
+                AtomEnum::CARDINAL,
+                &[value],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn set_frame_margin(&mut self, _margin: u16) -> Result<(), Error> {
+        // Decorated by the window manager, whose compositor draws the shadow
+        Ok(())
+    }
 }
 
 fn mouse_button(detail: u8) -> Option<MouseButton> {
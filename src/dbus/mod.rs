@@ -0,0 +1,471 @@
+//! Minimal D-Bus client for the session bus.
+//!
+//! Enough to call methods, export a few objects and emit signals, speaking
+//! the wire protocol directly over the bus socket.
+
+pub(crate) mod portal;
+pub(crate) mod wire;
+
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Write},
+    os::{
+        fd::{AsRawFd, RawFd},
+        unix::net::UnixStream,
+    },
+};
+
+pub(crate) use wire::Value;
+use wire::{Reader, Writer};
+
+const BUS_NAME: &str = "org.freedesktop.DBus";
+const BUS_PATH: &str = "/org/freedesktop/DBus";
+
+/// Header flag asking the peer not to send a reply.
+const NO_REPLY_EXPECTED: u8 = 0x1;
+
+/// Header field codes.
+const FIELD_PATH: u8 = 1;
+const FIELD_INTERFACE: u8 = 2;
+const FIELD_MEMBER: u8 = 3;
+const FIELD_ERROR_NAME: u8 = 4;
+const FIELD_REPLY_SERIAL: u8 = 5;
+const FIELD_DESTINATION: u8 = 6;
+const FIELD_SENDER: u8 = 7;
+const FIELD_SIGNATURE: u8 = 8;
+
+/// Largest message the specification allows.
+const MAX_MESSAGE_SIZE: usize = 128 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MessageKind {
+    MethodCall = 1,
+    MethodReturn = 2,
+    Error = 3,
+    Signal = 4,
+}
+
+/// A D-Bus message.
+#[derive(Debug, Clone)]
+pub(crate) struct Message {
+    pub kind: MessageKind,
+    pub flags: u8,
+    pub serial: u32,
+    pub path: Option<String>,
+    pub interface: Option<String>,
+    pub member: Option<String>,
+    pub error_name: Option<String>,
+    pub reply_serial: Option<u32>,
+    pub destination: Option<String>,
+    pub sender: Option<String>,
+    pub body: Vec<Value>,
+}
+
+impl Message {
+    fn new(kind: MessageKind) -> Self {
+        Self {
+            kind,
+            flags: 0,
+            serial: 0,
+            path: None,
+            interface: None,
+            member: None,
+            error_name: None,
+            reply_serial: None,
+            destination: None,
+            sender: None,
+            body: Vec::new(),
+        }
+    }
+
+    pub fn method_call(
+        destination: &str,
+        path: &str,
+        interface: &str,
+        member: &str,
+        body: Vec<Value>,
+    ) -> Self {
+        Self {
+            path: Some(path.to_string()),
+            interface: Some(interface.to_string()),
+            member: Some(member.to_string()),
+            destination: Some(destination.to_string()),
+            body,
+            ..Self::new(MessageKind::MethodCall)
+        }
+    }
+
+    pub fn signal(path: &str, interface: &str, member: &str, body: Vec<Value>) -> Self {
+        Self {
+            path: Some(path.to_string()),
+            interface: Some(interface.to_string()),
+            member: Some(member.to_string()),
+            body,
+            ..Self::new(MessageKind::Signal)
+        }
+    }
+
+    /// A successful reply to `call`.
+    pub fn method_return(call: &Message, body: Vec<Value>) -> Self {
+        Self {
+            reply_serial: Some(call.serial),
+            destination: call.sender.clone(),
+            body,
+            ..Self::new(MessageKind::MethodReturn)
+        }
+    }
+
+    /// An error reply to `call`.
+    pub fn error(call: &Message, name: &str, text: &str) -> Self {
+        Self {
+            error_name: Some(name.to_string()),
+            reply_serial: Some(call.serial),
+            destination: call.sender.clone(),
+            body: vec![Value::str(text)],
+            ..Self::new(MessageKind::Error)
+        }
+    }
+
+    /// Whether the sender wants a reply to this call.
+    pub fn expects_reply(&self) -> bool {
+        self.kind == MessageKind::MethodCall && self.flags & NO_REPLY_EXPECTED == 0
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut body = Writer::new();
+        for value in &self.body {
+            body.write(value);
+        }
+        let signature: String = self.body.iter().map(Value::signature).collect();
+
+        let mut fields = Vec::new();
+        let mut field = |code: u8, value: Value| {
+            fields.push(Value::Struct(vec![
+                Value::Byte(code),
+                Value::variant(value),
+            ]));
+        };
+        if let Some(path) = &self.path {
+            field(FIELD_PATH, Value::ObjectPath(path.clone()));
+        }
+        if let Some(interface) = &self.interface {
+            field(FIELD_INTERFACE, Value::Str(interface.clone()));
+        }
+        if let Some(member) = &self.member {
+            field(FIELD_MEMBER, Value::Str(member.clone()));
+        }
+        if let Some(name) = &self.error_name {
+            field(FIELD_ERROR_NAME, Value::Str(name.clone()));
+        }
+        if let Some(serial) = self.reply_serial {
+            field(FIELD_REPLY_SERIAL, Value::Uint32(serial));
+        }
+        if let Some(destination) = &self.destination {
+            field(FIELD_DESTINATION, Value::Str(destination.clone()));
+        }
+        if !signature.is_empty() {
+            field(FIELD_SIGNATURE, Value::Signature(signature));
+        }
+
+        let mut out = Writer::new();
+        out.buf
+            .extend_from_slice(&[b'l', self.kind as u8, self.flags, 1]);
+        out.write_u32(body.buf.len() as u32);
+        out.write_u32(self.serial);
+        out.write(&Value::Array("(yv)".to_string(), fields));
+        out.align(8);
+        out.buf.extend_from_slice(&body.buf);
+        out.buf
+    }
+
+    fn decode(data: &[u8]) -> Option<Self> {
+        let big_endian = match data.first()? {
+            b'l' => false,
+            b'B' => true,
+            _ => return None,
+        };
+        let kind = match data.get(1)? {
+            1 => MessageKind::MethodCall,
+            2 => MessageKind::MethodReturn,
+            3 => MessageKind::Error,
+            4 => MessageKind::Signal,
+            _ => return None,
+        };
+        let mut message = Self::new(kind);
+        message.flags = *data.get(2)?;
+
+        let mut reader = Reader::new(data, 4, big_endian);
+        let body_len = reader.read_u32()? as usize;
+        message.serial = reader.read_u32()?;
+
+        let mut signature = String::new();
+        for field in reader.read("a(yv)")?.items() {
+            let Value::Struct(parts) = field else {
+                return None;
+            };
+            let (Some(Value::Byte(code)), Some(value)) = (parts.first(), parts.get(1)) else {
+                return None;
+            };
+            let text = value.as_str().map(str::to_string);
+            match *code {
+                FIELD_PATH => message.path = text,
+                FIELD_INTERFACE => message.interface = text,
+                FIELD_MEMBER => message.member = text,
+                FIELD_ERROR_NAME => message.error_name = text,
+                FIELD_REPLY_SERIAL => message.reply_serial = value.as_u32(),
+                FIELD_DESTINATION => message.destination = text,
+                FIELD_SENDER => message.sender = text,
+                FIELD_SIGNATURE => signature = text.unwrap_or_default(),
+                _ => {}
+            }
+        }
+
+        reader.pos = reader.pos.next_multiple_of(8);
+        if reader.pos + body_len != data.len() {
+            return None;
+        }
+        message.body = reader.read_all(&signature)?;
+        Some(message)
+    }
+}
+
+/// A connection to the session bus.
+pub(crate) struct Connection {
+    stream: UnixStream,
+    serial: u32,
+    /// Messages received while waiting for a reply.
+    pending: VecDeque<Message>,
+    /// Name the bus assigned us, like `:1.42`.
+    unique_name: String,
+}
+
+impl Connection {
+    /// Connects and authenticates to the session bus.
+    pub fn session() -> io::Result<Self> {
+        let stream = connect_session_socket()?;
+        let mut conn = Self {
+            stream,
+            serial: 0,
+            pending: VecDeque::new(),
+            unique_name: String::new(),
+        };
+        conn.authenticate()?;
+        let reply = conn.call(Message::method_call(
+            BUS_NAME,
+            BUS_PATH,
+            BUS_NAME,
+            "Hello",
+            vec![],
+        ))?;
+        conn.unique_name = reply
+            .body
+            .first()
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        Ok(conn)
+    }
+
+    pub fn unique_name(&self) -> &str {
+        &self.unique_name
+    }
+
+    fn authenticate(&mut self) -> io::Result<()> {
+        let uid = unsafe { libc::getuid() }.to_string();
+        let hex: String = uid.bytes().map(|b| format!("{b:02x}")).collect();
+        self.stream
+            .write_all(format!("\0AUTH EXTERNAL {hex}\r\n").as_bytes())?;
+
+        let mut line = Vec::new();
+        let mut byte = [0u8];
+        while !line.ends_with(b"\r\n") {
+            self.stream.read_exact(&mut byte)?;
+            line.push(byte[0]);
+            if line.len() > 512 {
+                break;
+            }
+        }
+        if !line.starts_with(b"OK ") {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "D-Bus authentication failed",
+            ));
+        }
+        self.stream.write_all(b"BEGIN\r\n")
+    }
+
+    pub fn fd(&self) -> RawFd {
+        self.stream.as_raw_fd()
+    }
+
+    /// Sends a message and returns its serial.
+    pub fn send(&mut self, mut message: Message) -> io::Result<u32> {
+        self.serial = self.serial.wrapping_add(1).max(1);
+        message.serial = self.serial;
+        self.stream.write_all(&message.encode())?;
+        Ok(message.serial)
+    }
+
+    /// Sends a method call and waits for its reply. Error replies become
+    /// errors; other messages that arrive meanwhile are kept for `read`.
+    pub fn call(&mut self, message: Message) -> io::Result<Message> {
+        let serial = self.send(message)?;
+        loop {
+            let reply = self.read_message()?;
+            if reply.reply_serial != Some(serial) {
+                self.pending.push_back(reply);
+                continue;
+            }
+            if reply.kind == MessageKind::Error {
+                let name = reply.error_name.unwrap_or_default();
+                let text = reply.body.first().and_then(Value::as_str).unwrap_or("");
+                return Err(io::Error::other(format!("{name}: {text}")));
+            }
+            return Ok(reply);
+        }
+    }
+
+    /// Calls a method on the bus daemon itself.
+    pub fn call_bus(&mut self, member: &str, body: Vec<Value>) -> io::Result<Message> {
+        self.call(Message::method_call(
+            BUS_NAME, BUS_PATH, BUS_NAME, member, body,
+        ))
+    }
+
+    /// Asks for a well-known name. Returns true if we now own it.
+    pub fn request_name(&mut self, name: &str) -> io::Result<bool> {
+        // Flags: do not queue if someone else owns it
+        let reply = self.call_bus("RequestName", vec![Value::str(name), Value::Uint32(4)])?;
+        // 1: primary owner, 4: already the owner
+        Ok(matches!(
+            reply.body.first().and_then(Value::as_u32),
+            Some(1 | 4)
+        ))
+    }
+
+    /// Subscribes to signals matching a match rule.
+    pub fn add_match(&mut self, rule: &str) -> io::Result<()> {
+        self.call_bus("AddMatch", vec![Value::str(rule)])
+            .map(|_| ())
+    }
+
+    /// Waits up to `timeout_ms` (-1 for no limit) for the next message.
+    pub fn read(&mut self, timeout_ms: i32) -> io::Result<Option<Message>> {
+        if let Some(message) = self.pending.pop_front() {
+            return Ok(Some(message));
+        }
+        let mut pfd = libc::pollfd {
+            fd: self.fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ready = unsafe { libc::poll(&mut pfd, 1, timeout_ms) };
+        if ready < 0 {
+            let err = io::Error::last_os_error();
+            return if err.kind() == io::ErrorKind::Interrupted {
+                Ok(None)
+            } else {
+                Err(err)
+            };
+        }
+        if ready == 0 {
+            return Ok(None);
+        }
+        self.read_message().map(Some)
+    }
+
+    fn read_message(&mut self) -> io::Result<Message> {
+        let malformed = || io::Error::new(io::ErrorKind::InvalidData, "malformed D-Bus message");
+
+        let mut fixed = [0u8; 16];
+        self.stream.read_exact(&mut fixed)?;
+        let big_endian = fixed[0] == b'B';
+        let number = |bytes: &[u8]| {
+            let bytes: [u8; 4] = bytes.try_into().unwrap_or_default();
+            if big_endian {
+                u32::from_be_bytes(bytes)
+            } else {
+                u32::from_le_bytes(bytes)
+            }
+        };
+        let body_len = number(&fixed[4..8]) as usize;
+        let fields_len = number(&fixed[12..16]) as usize;
+        let total = (16 + fields_len).next_multiple_of(8) + body_len;
+        if total > MAX_MESSAGE_SIZE {
+            return Err(malformed());
+        }
+
+        let mut data = fixed.to_vec();
+        data.resize(total, 0);
+        self.stream.read_exact(&mut data[16..])?;
+        Message::decode(&data).ok_or_else(malformed)
+    }
+}
+
+/// Opens the socket named by `DBUS_SESSION_BUS_ADDRESS`, falling back to
+/// `$XDG_RUNTIME_DIR/bus`.
+fn connect_session_socket() -> io::Result<UnixStream> {
+    let address = std::env::var("DBUS_SESSION_BUS_ADDRESS").unwrap_or_default();
+    for entry in address.split(';') {
+        let Some(params) = entry.strip_prefix("unix:") else {
+            continue;
+        };
+        for param in params.split(',') {
+            let Some((key, value)) = param.split_once('=') else {
+                continue;
+            };
+            let value = unescape_address(value);
+            let stream = match key {
+                "path" => UnixStream::connect(&value),
+                "abstract" => connect_abstract(&value),
+                _ => continue,
+            };
+            if let Ok(stream) = stream {
+                return Ok(stream);
+            }
+        }
+    }
+
+    if let Some(runtime) = dirs::runtime_dir() {
+        let path = runtime.join("bus");
+        if path.exists() {
+            return UnixStream::connect(path);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "no D-Bus session bus available",
+    ))
+}
+
+#[cfg(target_os = "linux")]
+fn connect_abstract(name: &str) -> io::Result<UnixStream> {
+    use std::os::{linux::net::SocketAddrExt, unix::net::SocketAddr};
+    UnixStream::connect_addr(&SocketAddr::from_abstract_name(name.as_bytes())?)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn connect_abstract(_name: &str) -> io::Result<UnixStream> {
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
+
+/// Decodes `%xx` escapes in a bus address value.
+fn unescape_address(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = value
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
@@ -0,0 +1,312 @@
+//! Requests to xdg-desktop-portal.
+//!
+//! Portal methods return a Request object at once and deliver their result
+//! later through its `Response` signal, usually after showing some UI.
+
+use std::{
+    io,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use super::{Connection, Message, MessageKind, Value};
+use crate::backend::dnd::parse_uri_list;
+
+const PORTAL_NAME: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+const REQUEST_INTERFACE: &str = "org.freedesktop.portal.Request";
+const SETTINGS_INTERFACE: &str = "org.freedesktop.portal.Settings";
+const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+
+/// `Response` codes.
+const RESPONSE_SUCCESS: u32 = 0;
+const RESPONSE_CANCELLED: u32 = 1;
+
+/// Returns true if the portal service is running or can be started.
+pub(crate) fn available(conn: &mut Connection) -> bool {
+    let running = conn
+        .call_bus("NameHasOwner", vec![Value::str(PORTAL_NAME)])
+        .ok()
+        .is_some_and(|reply| reply.body.first() == Some(&Value::Bool(true)));
+    running
+        || conn
+            .call_bus("ListActivatableNames", vec![])
+            .ok()
+            .is_some_and(|reply| {
+                reply.body.first().is_some_and(|names| {
+                    names
+                        .items()
+                        .iter()
+                        .any(|n| n.as_str() == Some(PORTAL_NAME))
+                })
+            })
+}
+
+/// Calls `interface.method` on the portal with `args` followed by an
+/// options dictionary, then waits for the response. Returns the results
+/// dictionary, or `None` if the user cancelled.
+pub(crate) fn request(
+    conn: &mut Connection,
+    interface: &str,
+    method: &str,
+    mut args: Vec<Value>,
+    mut options: Vec<(&str, Value)>,
+) -> io::Result<Option<Value>> {
+    static NEXT_TOKEN: AtomicU32 = AtomicU32::new(0);
+    let token = format!(
+        "zenity_rs_{}_{}",
+        std::process::id(),
+        NEXT_TOKEN.fetch_add(1, Ordering::Relaxed)
+    );
+
+    // Subscribe before calling so a quick response isn't missed. The
+    // handle path is predictable from our bus name and the token.
+    let sender = conn.unique_name().trim_start_matches(':').replace('.', "_");
+    let mut handle = format!("{PORTAL_PATH}/request/{sender}/{token}");
+    conn.add_match(&format!(
+        "type='signal',interface='{REQUEST_INTERFACE}',member='Response'"
+    ))?;
+
+    options.push(("handle_token", Value::str(&token)));
+    args.push(Value::dict(options));
+    let reply = conn.call(Message::method_call(
+        PORTAL_NAME,
+        PORTAL_PATH,
+        interface,
+        method,
+        args,
+    ))?;
+    // Older portals ignore the token and pick their own path
+    if let Some(path) = reply.body.first().and_then(Value::as_str) {
+        handle = path.to_string();
+    }
+
+    loop {
+        let Some(message) = conn.read(-1)? else {
+            continue;
+        };
+        if message.kind != MessageKind::Signal
+            || message.path.as_deref() != Some(handle.as_str())
+            || message.member.as_deref() != Some("Response")
+        {
+            continue;
+        }
+        return match message.body.first().and_then(Value::as_u32) {
+            Some(RESPONSE_SUCCESS) => Ok(message.body.into_iter().nth(1)),
+            Some(RESPONSE_CANCELLED) => Ok(None),
+            _ => Err(io::Error::other(format!("{interface}.{method} failed"))),
+        };
+    }
+}
+
+/// Lets the user pick a color from the screen. Returns red, green and blue
+/// in 0.0–1.0, or `None` if cancelled.
+pub(crate) fn pick_color() -> io::Result<Option<[f64; 3]>> {
+    let mut conn = Connection::session()?;
+    let Some(results) = request(
+        &mut conn,
+        "org.freedesktop.portal.Screenshot",
+        "PickColor",
+        vec![Value::str("")],
+        vec![],
+    )?
+    else {
+        return Ok(None);
+    };
+    match results.get("color") {
+        Some(Value::Struct(fields)) => {
+            let mut color = [0.0; 3];
+            for (out, field) in color.iter_mut().zip(fields) {
+                if let Value::Double(v) = field {
+                    *out = v.clamp(0.0, 1.0);
+                }
+            }
+            Ok(Some(color))
+        }
+        _ => {
+            Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "portal returned no color",
+            ))
+        }
+    }
+}
+
+/// The desktop's appearance preferences, as the settings portal reports
+/// them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Appearance {
+    /// 0: no preference, 1: prefers dark, 2: prefers light
+    pub color_scheme: u32,
+    /// Red, green and blue in 0.0–1.0, if the user chose an accent color
+    pub accent_color: Option<[f64; 3]>,
+    pub high_contrast: bool,
+}
+
+impl Appearance {
+    /// Takes in the value of one `org.freedesktop.appearance` setting.
+    fn set(&mut self, key: &str, value: &Value) {
+        let value = match value {
+            Value::Variant(inner) => inner.as_ref(),
+            other => other,
+        };
+        match key {
+            "color-scheme" => self.color_scheme = value.as_u32().unwrap_or_default(),
+            "accent-color" => {
+                self.accent_color = None;
+                let Value::Struct(fields) = value else {
+                    return;
+                };
+                let mut color = [0.0; 3];
+                for (out, field) in color.iter_mut().zip(fields) {
+                    match field {
+                        // Out of range means no accent color is set
+                        Value::Double(v) if (0.0..=1.0).contains(v) => *out = *v,
+                        _ => return,
+                    }
+                }
+                self.accent_color = Some(color);
+            }
+            "contrast" => self.high_contrast = value.as_u32() == Some(1),
+            _ => {}
+        }
+    }
+}
+
+/// Reads the desktop's appearance settings, then calls `on_change` with
+/// them and again each time the user changes one. Only returns on error.
+pub(crate) fn watch_appearance(
+    conn: &mut Connection,
+    mut on_change: impl FnMut(Appearance),
+) -> io::Result<()> {
+    // Subscribe before reading so a change in between isn't missed
+    conn.add_match(&format!(
+        "type='signal',interface='{SETTINGS_INTERFACE}',member='SettingChanged',\
+         arg0='{APPEARANCE_NAMESPACE}'"
+    ))?;
+    let reply = conn.call(Message::method_call(
+        PORTAL_NAME,
+        PORTAL_PATH,
+        SETTINGS_INTERFACE,
+        "ReadAll",
+        vec![Value::Array(
+            "s".to_string(),
+            vec![Value::str(APPEARANCE_NAMESPACE)],
+        )],
+    ))?;
+    let mut appearance = Appearance::default();
+    let namespaces = reply.body.first();
+    if let Some(settings) = namespaces.and_then(|n| n.get(APPEARANCE_NAMESPACE)) {
+        for entry in settings.items() {
+            if let Value::DictEntry(key, value) = entry
+                && let Some(key) = key.as_str()
+            {
+                appearance.set(key, value);
+            }
+        }
+    }
+    on_change(appearance);
+
+    loop {
+        let Some(message) = conn.read(-1)? else {
+            continue;
+        };
+        if message.kind != MessageKind::Signal
+            || message.member.as_deref() != Some("SettingChanged")
+        {
+            continue;
+        }
+        if let [namespace, key, value] = message.body.as_slice()
+            && namespace.as_str() == Some(APPEARANCE_NAMESPACE)
+            && let Some(key) = key.as_str()
+        {
+            appearance.set(key, value);
+            on_change(appearance);
+        }
+    }
+}
+
+/// What to ask the portal's file chooser for.
+pub(crate) struct FileChooser<'a> {
+    pub title: &'a str,
+    /// Window the chooser belongs to, as `x11:ID` or `wayland:HANDLE`
+    pub parent_window: String,
+    pub save: bool,
+    pub directory: bool,
+    pub multiple: bool,
+    pub current_folder: Option<&'a Path>,
+    /// File name suggested when saving
+    pub current_name: &'a str,
+    /// Filter names with their glob patterns
+    pub filters: Vec<(&'a str, &'a [String])>,
+}
+
+/// Shows the desktop's file chooser. Returns the chosen paths, or `None` if
+/// cancelled.
+pub(crate) fn choose_files(
+    conn: &mut Connection,
+    chooser: &FileChooser,
+) -> io::Result<Option<Vec<PathBuf>>> {
+    let mut options = vec![("modal", Value::Bool(true))];
+    if !chooser.save {
+        options.push(("multiple", Value::Bool(chooser.multiple)));
+        options.push(("directory", Value::Bool(chooser.directory)));
+    } else if !chooser.current_name.is_empty() {
+        options.push(("current_name", Value::str(chooser.current_name)));
+    }
+    if let Some(folder) = chooser.current_folder {
+        options.push(("current_folder", path_bytes(folder)));
+    }
+    if !chooser.filters.is_empty() {
+        // Each pattern is a glob (0), not a MIME type (1)
+        let filters = chooser
+            .filters
+            .iter()
+            .map(|(name, patterns)| {
+                let patterns = patterns
+                    .iter()
+                    .map(|pattern| Value::Struct(vec![Value::Uint32(0), Value::str(pattern)]))
+                    .collect();
+                Value::Struct(vec![
+                    Value::str(name),
+                    Value::Array("(us)".to_string(), patterns),
+                ])
+            })
+            .collect();
+        options.push(("filters", Value::Array("(sa(us))".to_string(), filters)));
+    }
+
+    let method = if chooser.save { "SaveFile" } else { "OpenFile" };
+    let Some(results) = request(
+        conn,
+        "org.freedesktop.portal.FileChooser",
+        method,
+        vec![
+            Value::str(&chooser.parent_window),
+            Value::str(chooser.title),
+        ],
+        options,
+    )?
+    else {
+        return Ok(None);
+    };
+
+    let uris: Vec<&str> = results
+        .get("uris")
+        .map(|uris| uris.items().iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    Ok(Some(parse_uri_list(uris.join("\n").as_bytes())))
+}
+
+/// A path as the portal takes it: NUL-terminated bytes.
+fn path_bytes(path: &Path) -> Value {
+    let bytes = path
+        .as_os_str()
+        .as_bytes()
+        .iter()
+        .chain([&0])
+        .map(|&byte| Value::Byte(byte))
+        .collect();
+    Value::Array("y".to_string(), bytes)
+}
@@ -0,0 +1,388 @@
+//! D-Bus wire format: typed values and their marshaling.
+//!
+//! Only what our services need is supported; unix fd passing is not.
+
+/// A D-Bus value with its type.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Byte(u8),
+    Bool(bool),
+    Int16(i16),
+    Uint16(u16),
+    Int32(i32),
+    Uint32(u32),
+    Int64(i64),
+    Uint64(u64),
+    Double(f64),
+    Str(String),
+    ObjectPath(String),
+    Signature(String),
+    Variant(Box<Value>),
+    /// Element signature and items.
+    Array(String, Vec<Value>),
+    Struct(Vec<Value>),
+    /// Key and value; only valid as an array element.
+    DictEntry(Box<Value>, Box<Value>),
+}
+
+impl Value {
+    pub fn str(s: &str) -> Value {
+        Value::Str(s.to_string())
+    }
+
+    pub fn variant(value: Value) -> Value {
+        Value::Variant(Box::new(value))
+    }
+
+    /// A dictionary with string keys and variant values (`a{sv}`).
+    pub fn dict(entries: Vec<(&str, Value)>) -> Value {
+        Value::Array(
+            "{sv}".to_string(),
+            entries
+                .into_iter()
+                .map(|(key, value)| {
+                    Value::DictEntry(Box::new(Value::str(key)), Box::new(Value::variant(value)))
+                })
+                .collect(),
+        )
+    }
+
+    pub fn signature(&self) -> String {
+        match self {
+            Value::Byte(_) => "y".to_string(),
+            Value::Bool(_) => "b".to_string(),
+            Value::Int16(_) => "n".to_string(),
+            Value::Uint16(_) => "q".to_string(),
+            Value::Int32(_) => "i".to_string(),
+            Value::Uint32(_) => "u".to_string(),
+            Value::Int64(_) => "x".to_string(),
+            Value::Uint64(_) => "t".to_string(),
+            Value::Double(_) => "d".to_string(),
+            Value::Str(_) => "s".to_string(),
+            Value::ObjectPath(_) => "o".to_string(),
+            Value::Signature(_) => "g".to_string(),
+            Value::Variant(_) => "v".to_string(),
+            Value::Array(element, _) => format!("a{element}"),
+            Value::Struct(fields) => {
+                let inner: String = fields.iter().map(Value::signature).collect();
+                format!("({inner})")
+            }
+            Value::DictEntry(key, value) => format!("{{{}{}}}", key.signature(), value.signature()),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) | Value::ObjectPath(s) | Value::Signature(s) => Some(s),
+            Value::Variant(inner) => inner.as_str(),
+            _ => None,
+        }
+    }
+
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            Value::Int32(v) => Some(*v),
+            Value::Variant(inner) => inner.as_i32(),
+            _ => None,
+        }
+    }
+
+    pub fn as_u32(&self) -> Option<u32> {
+        match self {
+            Value::Uint32(v) => Some(*v),
+            Value::Variant(inner) => inner.as_u32(),
+            _ => None,
+        }
+    }
+
+    /// Looks up `key` in a dictionary with string keys, unwrapping variant values.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.items().iter().find_map(|entry| {
+            match entry {
+                Value::DictEntry(k, v) if k.as_str() == Some(key) => {
+                    Some(match v.as_ref() {
+                        Value::Variant(inner) => inner.as_ref(),
+                        other => other,
+                    })
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Array items, or an empty slice for other values.
+    pub fn items(&self) -> &[Value] {
+        match self {
+            Value::Array(_, items) => items,
+            Value::Variant(inner) => inner.items(),
+            _ => &[],
+        }
+    }
+}
+
+/// Alignment of a type, given the first character of its signature.
+fn alignment(code: u8) -> usize {
+    match code {
+        b'n' | b'q' => 2,
+        b'b' | b'i' | b'u' | b'h' | b's' | b'o' | b'a' => 4,
+        b'x' | b't' | b'd' | b'(' | b'{' => 8,
+        _ => 1,
+    }
+}
+
+/// Serializes values in little-endian byte order.
+pub(crate) struct Writer {
+    pub buf: Vec<u8>,
+}
+
+impl Writer {
+    pub fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+        }
+    }
+
+    pub fn align(&mut self, n: usize) {
+        while self.buf.len() % n != 0 {
+            self.buf.push(0);
+        }
+    }
+
+    pub fn write_u32(&mut self, v: u32) {
+        self.align(4);
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn write_string(&mut self, s: &str) {
+        self.write_u32(s.len() as u32);
+        self.buf.extend_from_slice(s.as_bytes());
+        self.buf.push(0);
+    }
+
+    fn write_signature(&mut self, s: &str) {
+        self.buf.push(s.len() as u8);
+        self.buf.extend_from_slice(s.as_bytes());
+        self.buf.push(0);
+    }
+
+    pub fn write(&mut self, value: &Value) {
+        match value {
+            Value::Byte(v) => self.buf.push(*v),
+            Value::Bool(v) => self.write_u32(*v as u32),
+            Value::Int16(v) => {
+                self.align(2);
+                self.buf.extend_from_slice(&v.to_le_bytes());
+            }
+            Value::Uint16(v) => {
+                self.align(2);
+                self.buf.extend_from_slice(&v.to_le_bytes());
+            }
+            Value::Int32(v) => {
+                self.align(4);
+                self.buf.extend_from_slice(&v.to_le_bytes());
+            }
+            Value::Uint32(v) => self.write_u32(*v),
+            Value::Int64(v) => {
+                self.align(8);
+                self.buf.extend_from_slice(&v.to_le_bytes());
+            }
+            Value::Uint64(v) => {
+                self.align(8);
+                self.buf.extend_from_slice(&v.to_le_bytes());
+            }
+            Value::Double(v) => {
+                self.align(8);
+                self.buf.extend_from_slice(&v.to_le_bytes());
+            }
+            Value::Str(s) | Value::ObjectPath(s) => self.write_string(s),
+            Value::Signature(s) => self.write_signature(s),
+            Value::Variant(inner) => {
+                self.write_signature(&inner.signature());
+                self.write(inner);
+            }
+            Value::Array(element, items) => {
+                self.write_u32(0);
+                let len_at = self.buf.len() - 4;
+                // Padding before the first element is not counted in the length
+                self.align(alignment(
+                    element.as_bytes().first().copied().unwrap_or(b'y'),
+                ));
+                let start = self.buf.len();
+                for item in items {
+                    self.write(item);
+                }
+                let len = (self.buf.len() - start) as u32;
+                self.buf[len_at..len_at + 4].copy_from_slice(&len.to_le_bytes());
+            }
+            Value::Struct(fields) => {
+                self.align(8);
+                for field in fields {
+                    self.write(field);
+                }
+            }
+            Value::DictEntry(key, value) => {
+                self.align(8);
+                self.write(key);
+                self.write(value);
+            }
+        }
+    }
+}
+
+/// Deserializes values from a message, in either byte order. Offsets are
+/// relative to the start of the message, which alignment is based on.
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    pub pos: usize,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8], pos: usize, big_endian: bool) -> Self {
+        Self {
+            data,
+            pos,
+            big_endian,
+        }
+    }
+
+    fn align(&mut self, n: usize) -> Option<()> {
+        self.pos = self.pos.next_multiple_of(n);
+        (self.pos <= self.data.len()).then_some(())
+    }
+
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let bytes = self.data.get(self.pos..self.pos + N)?;
+        self.pos += N;
+        let mut out: [u8; N] = bytes.try_into().ok()?;
+        if self.big_endian {
+            out.reverse();
+        }
+        Some(out)
+    }
+
+    pub fn read_u32(&mut self) -> Option<u32> {
+        self.align(4)?;
+        self.take::<4>().map(u32::from_le_bytes)
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Option<String> {
+        let bytes = self.data.get(self.pos..self.pos + len)?;
+        // Skip the trailing nul
+        self.pos += len + 1;
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+
+    fn read_signature(&mut self) -> Option<String> {
+        let len = *self.data.get(self.pos)? as usize;
+        self.pos += 1;
+        self.read_bytes(len)
+    }
+
+    /// Reads one value of each complete type in `signature`.
+    pub fn read_all(&mut self, signature: &str) -> Option<Vec<Value>> {
+        let sig = signature.as_bytes();
+        let mut i = 0;
+        let mut values = Vec::new();
+        while i < sig.len() {
+            let end = type_end(sig, i)?;
+            values.push(self.read(&signature[i..end])?);
+            i = end;
+        }
+        Some(values)
+    }
+
+    /// Reads a value of the single complete type `signature`.
+    pub fn read(&mut self, signature: &str) -> Option<Value> {
+        let sig = signature.as_bytes();
+        Some(match sig.first()? {
+            b'y' => Value::Byte(self.take::<1>()?[0]),
+            b'b' => Value::Bool(self.read_u32()? != 0),
+            b'n' => {
+                self.align(2)?;
+                Value::Int16(i16::from_le_bytes(self.take::<2>()?))
+            }
+            b'q' => {
+                self.align(2)?;
+                Value::Uint16(u16::from_le_bytes(self.take::<2>()?))
+            }
+            b'i' => {
+                self.align(4)?;
+                Value::Int32(i32::from_le_bytes(self.take::<4>()?))
+            }
+            b'u' | b'h' => Value::Uint32(self.read_u32()?),
+            b'x' => {
+                self.align(8)?;
+                Value::Int64(i64::from_le_bytes(self.take::<8>()?))
+            }
+            b't' => {
+                self.align(8)?;
+                Value::Uint64(u64::from_le_bytes(self.take::<8>()?))
+            }
+            b'd' => {
+                self.align(8)?;
+                Value::Double(f64::from_le_bytes(self.take::<8>()?))
+            }
+            b's' => {
+                let len = self.read_u32()? as usize;
+                Value::Str(self.read_bytes(len)?)
+            }
+            b'o' => {
+                let len = self.read_u32()? as usize;
+                Value::ObjectPath(self.read_bytes(len)?)
+            }
+            b'g' => Value::Signature(self.read_signature()?),
+            b'v' => {
+                let inner = self.read_signature()?;
+                if type_end(inner.as_bytes(), 0)? != inner.len() {
+                    return None;
+                }
+                Value::variant(self.read(&inner)?)
+            }
+            b'a' => {
+                let element = &signature[1..type_end(sig, 1)?];
+                let len = self.read_u32()? as usize;
+                self.align(alignment(element.as_bytes()[0]))?;
+                let end = self.pos + len;
+                if end > self.data.len() {
+                    return None;
+                }
+                let mut items = Vec::new();
+                while self.pos < end {
+                    items.push(self.read(element)?);
+                }
+                Value::Array(element.to_string(), items)
+            }
+            b'(' => {
+                self.align(8)?;
+                let inner = &signature[1..type_end(sig, 0)? - 1];
+                Value::Struct(self.read_all(inner)?)
+            }
+            b'{' => {
+                self.align(8)?;
+                let key_end = type_end(sig, 1)?;
+                let value_end = type_end(sig, key_end)?;
+                let key = self.read(&signature[1..key_end])?;
+                let value = self.read(&signature[key_end..value_end])?;
+                Value::DictEntry(Box::new(key), Box::new(value))
+            }
+            _ => return None,
+        })
+    }
+}
+
+/// Returns the index just past the complete type starting at `sig[start]`.
+fn type_end(sig: &[u8], start: usize) -> Option<usize> {
+    match sig.get(start)? {
+        b'a' => type_end(sig, start + 1),
+        open @ (b'(' | b'{') => {
+            let close = if *open == b'(' { b')' } else { b'}' };
+            let mut i = start + 1;
+            while *sig.get(i)? != close {
+                i = type_end(sig, i)?;
+            }
+            Some(i + 1)
+        }
+        _ => Some(start + 1),
+    }
+}
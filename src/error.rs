@@ -1,13 +1,46 @@
 use std::fmt;
 
+pub use crate::backend::Backend;
+
+/// Why a dialog could not be shown.
+///
+/// A dialog being cancelled or closed is not an error: it is part of the
+/// result `show` returns.
 #[derive(Debug)]
 pub enum Error {
+    /// No display server could show the dialog. Each backend tried is
+    /// listed with the error it failed with; none are when no display
+    /// server was found at all.
+    BackendUnavailable {
+        tried: Vec<(Backend, Error)>,
+    },
+    /// The display server could not be set up with, refused a request, or
+    /// lacks something the dialog needs.
+    #[cfg(any(feature = "x11", feature = "wayland"))]
+    Protocol(ProtocolError),
+    /// The display server went away while a dialog was shown.
+    ConnectionLost(Backend),
+    Io(std::io::Error),
+    /// A theme file has lines that could not be used.
+    InvalidTheme {
+        path: std::path::PathBuf,
+        errors: Vec<crate::ui::ThemeError>,
+    },
+    /// Theme overrides that could not be used, one message each.
+    InvalidStyle(Vec<String>),
+    /// A builder was given options that don't make sense together, like a
+    /// scale whose minimum is above its maximum.
+    InvalidConfig(String),
+}
+
+/// What went wrong talking to a display server, by backend.
+#[cfg(any(feature = "x11", feature = "wayland"))]
+#[derive(Debug)]
+pub enum ProtocolError {
     #[cfg(feature = "x11")]
     X11(X11Error),
     #[cfg(feature = "wayland")]
     Wayland(WaylandError),
-    NoDisplay,
-    Io(std::io::Error),
 }
 
 #[cfg(feature = "x11")]
@@ -24,24 +57,123 @@ pub enum X11Error {
 pub enum WaylandError {
     Connect(wayland_client::ConnectError),
     Dispatch(wayland_client::DispatchError),
+    /// The compositor sent an error for a request.
+    Protocol(wayland_client::backend::protocol::ProtocolError),
+    /// A global the dialog needs, like `xdg_wm_base`, isn't offered.
     MissingGlobal(&'static str),
     NotConfigured,
 }
 
+impl Error {
+    /// Returns the backend the error came from, if it came from one.
+    pub fn backend(&self) -> Option<Backend> {
+        match self {
+            #[cfg(any(feature = "x11", feature = "wayland"))]
+            Error::Protocol(e) => Some(e.backend()),
+            Error::ConnectionLost(backend) => Some(*backend),
+            _ => None,
+        }
+    }
+
+    /// Whether no display server could show the dialog, so asking in some
+    /// other way may still work.
+    pub fn is_unavailable(&self) -> bool {
+        matches!(self, Error::BackendUnavailable { .. })
+    }
+
+    /// Whether the display server went away while the dialog was shown,
+    /// as when the session ends.
+    pub fn is_connection_lost(&self) -> bool {
+        matches!(self, Error::ConnectionLost(_))
+    }
+
+    /// Whether the dialog was given options, a theme or a style that could
+    /// not be used, rather than failing to be shown.
+    pub fn is_invalid(&self) -> bool {
+        matches!(
+            self,
+            Error::InvalidConfig(_) | Error::InvalidTheme { .. } | Error::InvalidStyle(_)
+        )
+    }
+}
+
+#[cfg(any(feature = "x11", feature = "wayland"))]
+impl ProtocolError {
+    /// Returns the backend the error came from.
+    pub fn backend(&self) -> Backend {
+        match *self {
+            #[cfg(feature = "x11")]
+            ProtocolError::X11(_) => Backend::X11,
+            #[cfg(feature = "wayland")]
+            ProtocolError::Wayland(_) => Backend::Wayland,
+        }
+    }
+}
+
 impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::BackendUnavailable {
+                tried,
+            } => {
+                write!(f, "no display server available")?;
+                for (backend, error) in tried {
+                    // Errors from a backend name it themselves
+                    if error.backend().is_some() {
+                        write!(f, "\n  {error}")?;
+                    } else {
+                        write!(f, "\n  {backend}: {error}")?;
+                    }
+                }
+                Ok(())
+            }
+            #[cfg(any(feature = "x11", feature = "wayland"))]
+            Error::Protocol(e) => write!(f, "{} error: {e}", e.backend()),
+            Error::ConnectionLost(backend) => {
+                write!(f, "connection to the {backend} display server was lost")
+            }
+            Error::Io(e) => write!(f, "IO error: {e}"),
+            Error::InvalidTheme {
+                path,
+                errors,
+            } => {
+                write!(f, "invalid theme {}:", path.display())?;
+                for error in errors {
+                    write!(f, "\n  {error}")?;
+                }
+                Ok(())
+            }
+            Error::InvalidStyle(errors) => write!(f, "invalid style: {}", errors.join("; ")),
+            Error::InvalidConfig(reason) => write!(f, "invalid configuration: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(any(feature = "x11", feature = "wayland"))]
+            Error::Protocol(e) => Some(e),
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(any(feature = "x11", feature = "wayland"))]
+impl fmt::Display for ProtocolError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             #[cfg(feature = "x11")]
-            Error::X11(e) => write!(f, "X11 error: {e}"),
+            ProtocolError::X11(e) => e.fmt(f),
             #[cfg(feature = "wayland")]
-            Error::Wayland(e) => write!(f, "Wayland error: {e}"),
-            Error::NoDisplay => write!(f, "no display server available"),
-            Error::Io(e) => write!(f, "IO error: {e}"),
+            ProtocolError::Wayland(e) => e.fmt(f),
         }
     }
 }
 
-impl std::error::Error for Error {}
+#[cfg(any(feature = "x11", feature = "wayland"))]
+impl std::error::Error for ProtocolError {}
 
 #[cfg(feature = "x11")]
 impl fmt::Display for X11Error {
@@ -61,6 +193,7 @@ impl fmt::Display for WaylandError {
         match self {
             WaylandError::Connect(e) => write!(f, "connect: {e}"),
             WaylandError::Dispatch(e) => write!(f, "dispatch: {e}"),
+            WaylandError::Protocol(e) => write!(f, "protocol: {e}"),
             WaylandError::MissingGlobal(name) => write!(f, "missing global: {name}"),
             WaylandError::NotConfigured => write!(f, "surface not configured"),
         }
@@ -73,24 +206,49 @@ impl From<std::io::Error> for Error {
     }
 }
 
+#[cfg(feature = "x11")]
+impl From<X11Error> for Error {
+    fn from(e: X11Error) -> Self {
+        Error::Protocol(ProtocolError::X11(e))
+    }
+}
+
+#[cfg(feature = "wayland")]
+impl From<WaylandError> for Error {
+    fn from(e: WaylandError) -> Self {
+        Error::Protocol(ProtocolError::Wayland(e))
+    }
+}
+
 #[cfg(feature = "x11")]
 impl From<x11rb::errors::ConnectError> for Error {
     fn from(e: x11rb::errors::ConnectError) -> Self {
-        Error::X11(X11Error::Connect(e))
+        match e {
+            // Such as the socket refusing us, which its kind tells apart
+            x11rb::errors::ConnectError::IoError(e) => Error::Io(e),
+            e => X11Error::Connect(e).into(),
+        }
     }
 }
 
 #[cfg(feature = "x11")]
 impl From<x11rb::errors::ConnectionError> for Error {
     fn from(e: x11rb::errors::ConnectionError) -> Self {
-        Error::X11(X11Error::Connection(e))
+        match e {
+            // Reading or writing fails once the server has closed the socket
+            x11rb::errors::ConnectionError::IoError(_) => Error::ConnectionLost(Backend::X11),
+            e => X11Error::Connection(e).into(),
+        }
     }
 }
 
 #[cfg(feature = "x11")]
 impl From<x11rb::errors::ReplyError> for Error {
     fn from(e: x11rb::errors::ReplyError) -> Self {
-        Error::X11(X11Error::Reply(e))
+        match e {
+            x11rb::errors::ReplyError::ConnectionError(e) => e.into(),
+            e => X11Error::Reply(e).into(),
+        }
     }
 }
 
@@ -98,11 +256,9 @@ impl From<x11rb::errors::ReplyError> for Error {
 impl From<x11rb::errors::ReplyOrIdError> for Error {
     fn from(e: x11rb::errors::ReplyOrIdError) -> Self {
         match e {
-            x11rb::errors::ReplyOrIdError::ConnectionError(e) => {
-                Error::X11(X11Error::Connection(e))
-            }
-            x11rb::errors::ReplyOrIdError::X11Error(e) => Error::X11(X11Error::Reply(e.into())),
-            x11rb::errors::ReplyOrIdError::IdsExhausted => Error::X11(X11Error::NoVisual),
+            x11rb::errors::ReplyOrIdError::ConnectionError(e) => e.into(),
+            x11rb::errors::ReplyOrIdError::X11Error(e) => X11Error::Reply(e.into()).into(),
+            x11rb::errors::ReplyOrIdError::IdsExhausted => X11Error::NoVisual.into(),
         }
     }
 }
@@ -110,21 +266,33 @@ impl From<x11rb::errors::ReplyOrIdError> for Error {
 #[cfg(feature = "wayland")]
 impl From<wayland_client::ConnectError> for Error {
     fn from(e: wayland_client::ConnectError) -> Self {
-        Error::Wayland(WaylandError::Connect(e))
+        WaylandError::Connect(e).into()
     }
 }
 
 #[cfg(feature = "wayland")]
 impl From<wayland_client::DispatchError> for Error {
     fn from(e: wayland_client::DispatchError) -> Self {
-        Error::Wayland(WaylandError::Dispatch(e))
+        match e {
+            wayland_client::DispatchError::Backend(e) => e.into(),
+            e => WaylandError::Dispatch(e).into(),
+        }
     }
 }
 
 #[cfg(feature = "wayland")]
 impl From<wayland_client::backend::WaylandError> for Error {
     fn from(e: wayland_client::backend::WaylandError) -> Self {
-        // Convert to IO error since WaylandError is usually an IO issue
-        Error::Io(std::io::Error::other(e.to_string()))
+        match e {
+            // The compositor closed the socket, or we can no longer write
+            // to it
+            wayland_client::backend::WaylandError::Io(e)
+                if e.kind() != std::io::ErrorKind::WouldBlock =>
+            {
+                Error::ConnectionLost(Backend::Wayland)
+            }
+            wayland_client::backend::WaylandError::Io(e) => Error::Io(e),
+            wayland_client::backend::WaylandError::Protocol(e) => WaylandError::Protocol(e).into(),
+        }
     }
 }
@@ -2,23 +2,56 @@
 //!
 //! This library provides simple GUI dialogs for shell scripts and command-line tools.
 
+// Dialogs are shown on X11 or Wayland only; there is no Win32 or AppKit
+// backend
+#[cfg(windows)]
+compile_error!("zenity-rs needs X11 or Wayland and does not support Windows");
+#[cfg(target_os = "macos")]
+compile_error!("zenity-rs needs X11 or Wayland and does not support macOS");
+
 pub(crate) mod backend;
+pub(crate) mod dbus;
 pub mod error;
 pub(crate) mod render;
+#[cfg(feature = "tui")]
+pub(crate) mod tui;
 pub mod ui;
 
-pub use error::Error;
+#[cfg(feature = "test-backend")]
+pub use backend::headless::{Frame, Headless, TestEvent};
+pub use error::{Backend, Error};
+pub use render::{Canvas, Font, Rgba, TextRenderer, rgb};
+#[cfg(feature = "tui")]
+pub use tui::force_tui;
 pub use ui::{
-    ButtonPreset, Colors, DialogResult, Icon, THEME_DARK, THEME_LIGHT,
+    ButtonPreset, Colors, DialogResult, Icon, Monitor, Placement, THEME_DARK, THEME_HIGH_CONTRAST,
+    THEME_LIGHT, Theme, ThemeError, WindowHandle,
     calendar::{CalendarBuilder, CalendarResult},
+    cancel::CancelToken,
+    chooser::{ChooserBuilder, ChooserResult},
+    color::{ColorBuilder, ColorResult},
+    countdown::{CountdownBuilder, CountdownResult},
+    custom, detect_accent, detect_theme,
+    dialog::{Dialog, DialogOutcome},
     entry::{EntryBuilder, EntryResult},
     file_select::{FileFilter, FileSelectBuilder, FileSelectResult},
+    font_select::{FontSelectBuilder, FontSelectResult},
     forms::{FormsBuilder, FormsResult},
+    handle::DialogHandle,
+    icon_select::{IconSelectBuilder, IconSelectResult},
+    image_view::{ImageBuilder, ImageResult},
     list::{ListBuilder, ListMode, ListResult},
+    load_theme,
+    login::{LoginBuilder, LoginResult},
     message::MessageBuilder,
+    on_output,
     progress::{ProgressBuilder, ProgressResult},
+    qr::{QrBuilder, QrResult},
     scale::{ScaleBuilder, ScaleResult},
+    session::Session,
+    set_animations, set_font, set_parent, set_placement, set_string, set_theme, set_window_icon,
     text_info::{TextInfoBuilder, TextInfoResult},
+    tray::{TrayBuilder, TrayEvent, TrayResult},
 };
 
 /// Creates a new message dialog builder.
@@ -92,11 +125,56 @@ pub fn file_select() -> FileSelectBuilder {
     FileSelectBuilder::new()
 }
 
+/// Creates a new font selection dialog builder.
+pub fn font_select() -> FontSelectBuilder {
+    FontSelectBuilder::new()
+}
+
+/// Creates a new icon picker dialog builder.
+pub fn icon_select() -> IconSelectBuilder {
+    IconSelectBuilder::new()
+}
+
+/// Creates a new image viewer dialog builder.
+pub fn image() -> ImageBuilder {
+    ImageBuilder::new()
+}
+
+/// Creates a QR code dialog showing `text` encoded.
+pub fn qr(text: &str) -> QrBuilder {
+    QrBuilder::new().text(text)
+}
+
+/// Creates a new system tray icon builder.
+pub fn tray() -> TrayBuilder {
+    TrayBuilder::new()
+}
+
 /// Creates a new list selection dialog builder.
 pub fn list() -> ListBuilder {
     ListBuilder::new()
 }
 
+/// Creates a new login dialog builder (username, password and optional "Remember me").
+pub fn login() -> LoginBuilder {
+    LoginBuilder::new()
+}
+
+/// Creates a new chooser dialog builder (pick and order rows between two lists).
+pub fn chooser() -> ChooserBuilder {
+    ChooserBuilder::new()
+}
+
+/// Creates a new countdown dialog builder that runs for `seconds`.
+pub fn countdown(seconds: u32) -> CountdownBuilder {
+    CountdownBuilder::new().seconds(seconds)
+}
+
+/// Creates a new color selection dialog builder.
+pub fn color_select() -> ColorBuilder {
+    ColorBuilder::new()
+}
+
 /// Creates a new calendar date picker dialog builder.
 pub fn calendar() -> CalendarBuilder {
     CalendarBuilder::new()
@@ -1,12 +1,15 @@
 //! zenity-rs - Display simple GUI dialogs from the command line.
 
-use std::{io::IsTerminal, process::ExitCode};
+use std::{io::IsTerminal, path::PathBuf, process::ExitCode};
 
 use lexopt::prelude::*;
 use zenity_rs::{
-    ButtonPreset, CalendarResult, EntryResult, FileSelectResult, FormsResult, Icon, ListResult,
-    ProgressResult, ScaleResult, TextInfoResult, calendar, entry, file_select, forms, list,
-    message, password, progress, scale, text_info,
+    ButtonPreset, CalendarResult, ChooserResult, ColorResult, EntryResult, FileSelectResult,
+    FontSelectResult, FormsResult, Icon, IconSelectResult, ListResult, LoginResult, Monitor,
+    Placement, ProgressResult, Rgba, ScaleResult, THEME_DARK, THEME_HIGH_CONTRAST, THEME_LIGHT,
+    TextInfoResult, Theme, TrayEvent, WindowHandle, calendar, chooser, color_select, countdown,
+    entry, file_select, font_select, forms, icon_select, image, list, login, message, password,
+    progress, qr, scale, text_info, tray,
 };
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -14,34 +17,97 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 fn handle_message_result(
     result: zenity_rs::DialogResult,
     extra_buttons: &[String],
-    default_cancel_index: Option<usize>,
+    preset_count: usize,
 ) -> i32 {
     match result {
         zenity_rs::DialogResult::Button(idx) => {
-            if idx < extra_buttons.len() {
-                // Extra button clicked - labels are reversed in positioning
-                // so we need to reverse the index to get the correct label
-                let reversed_idx = extra_buttons.len() - 1 - idx;
-                println!("{}", extra_buttons[reversed_idx]);
-                1
-            } else if let Some(cancel_idx) = default_cancel_index {
-                if idx == cancel_idx {
-                    // Default cancel button (or No button) clicked
-                    1
-                } else {
-                    // Default OK (or Yes) button clicked
-                    0
-                }
-            } else {
-                // No cancel button, so first button is OK
-                if idx == 0 { 0 } else { 1 }
+            handle_message_button(idx, extra_buttons, preset_count)
+        }
+        zenity_rs::DialogResult::ButtonWithCheckbox(idx, checked) => {
+            let code = handle_message_button(idx, extra_buttons, preset_count);
+            // Report the checkbox state so scripts can persist the preference
+            println!("{}", if checked { "TRUE" } else { "FALSE" });
+            code
+        }
+        zenity_rs::DialogResult::ButtonWithSwitches(idx, states) => {
+            let code = handle_message_button(idx, extra_buttons, preset_count);
+            // One line per switch, then the checkbox if shown
+            for on in states {
+                println!("{}", if on { "TRUE" } else { "FALSE" });
             }
+            code
         }
         zenity_rs::DialogResult::Closed => 255,
         zenity_rs::DialogResult::Timeout => 5,
     }
 }
 
+fn handle_message_button(idx: usize, extra_buttons: &[String], preset_count: usize) -> i32 {
+    if idx >= preset_count {
+        // Extra buttons follow the preset buttons
+        println!("{}", extra_buttons[idx - preset_count]);
+        1
+    } else {
+        // First preset button is OK/Yes, the rest are Cancel/No
+        if idx == 0 { 0 } else { 1 }
+    }
+}
+
+/// Expands `\n` and `\\` escapes in `--text`, like zenity does.
+fn unescape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// A size and a position, each optional.
+type Geometry = (Option<(u32, u32)>, Option<(i32, i32)>);
+
+/// Parses an X-style `WxH+X+Y` geometry; either part may be left out.
+fn parse_geometry(spec: &str) -> Result<Geometry, String> {
+    let invalid = || format!("invalid geometry '{spec}', expected WxH+X+Y");
+    let (size, position) = match spec.find('+') {
+        Some(i) => (&spec[..i], Some(&spec[i + 1..])),
+        None => (spec, None),
+    };
+
+    let size = if size.is_empty() {
+        None
+    } else {
+        let (w, h) = size.split_once('x').ok_or_else(invalid)?;
+        Some((
+            w.parse().map_err(|_| invalid())?,
+            h.parse().map_err(|_| invalid())?,
+        ))
+    };
+    let position = match position {
+        Some(position) => {
+            let (x, y) = position.split_once('+').ok_or_else(invalid)?;
+            Some((
+                x.parse().map_err(|_| invalid())?,
+                y.parse().map_err(|_| invalid())?,
+            ))
+        }
+        None => None,
+    };
+    Ok((size, position))
+}
+
 fn get_icon(icon_name: &Option<String>, default: Icon) -> Icon {
     match icon_name {
         None => default,
@@ -74,6 +140,7 @@ fn get_button_preset(
     default
 }
 
+#[allow(clippy::too_many_arguments)]
 fn apply_message_options(
     builder: zenity_rs::MessageBuilder,
     timeout: Option<u32>,
@@ -84,6 +151,9 @@ fn apply_message_options(
     ellipsize: bool,
     switch_mode: bool,
     _extra_buttons: &[String],
+    checkbox_text: &str,
+    switches: &[String],
+    links: &[String],
 ) -> zenity_rs::MessageBuilder {
     let mut builder = builder;
     if let Some(t) = timeout {
@@ -110,6 +180,19 @@ fn apply_message_options(
     for btn in _extra_buttons {
         builder = builder.extra_button(btn);
     }
+    if !checkbox_text.is_empty() {
+        builder = builder.suppress_checkbox(checkbox_text);
+    }
+    if !switches.is_empty() {
+        let switches: Vec<(&str, bool)> = switches
+            .iter()
+            .map(|label| (label.as_str(), false))
+            .collect();
+        builder = builder.switches(&switches);
+    }
+    for uri in links {
+        builder = builder.link("", uri);
+    }
     builder
 }
 
@@ -134,6 +217,14 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
     let mut width: Option<u32> = None;
     let mut height: Option<u32> = None;
     let mut no_wrap = false;
+    let mut placement = Placement::Default;
+    let mut monitor = Monitor::Default;
+    let mut attach: Option<WindowHandle> = None;
+    let mut window_icon: Option<PathBuf> = None;
+    let mut font: Option<String> = None;
+    let mut theme: Option<PathBuf> = None;
+    let mut accent: Option<Rgba> = None;
+    let mut style: Option<String> = None;
 
     // Shared options (for list, forms, file-selector)
     let mut separator = String::from("|");
@@ -150,7 +241,12 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
     // File selection options
     let mut directory_mode = false;
     let mut save_mode = false;
+    let mut confirm_overwrite = false;
+    let mut allow_manage = false;
+    let mut allow_directories = false;
+    let mut portal = false;
     let mut filename = String::new();
+    let mut class = String::new();
     let mut file_filters: Vec<zenity_rs::FileFilter> = Vec::new();
 
     // List options
@@ -165,11 +261,28 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
     let mut cal_month: Option<u32> = None;
     let mut cal_day: Option<u32> = None;
 
-    // Text info options
+    // Font selection options
+    let mut font_name = String::new();
+    let mut preview_text = String::new();
+    let mut caption = String::new();
+    let mut menu = String::new();
+    let mut listen = false;
+
+    // Color selection options
+    let mut initial_color = String::new();
+    let mut show_palette = false;
+    let mut palette_file = String::new();
+
+    // Countdown options
+    let mut countdown_secs: u32 = 0;
+
+    // Text info and message dialog options
     let mut checkbox_text = String::new();
+    let mut switches: Vec<String> = Vec::new();
+    let mut links: Vec<String> = Vec::new();
 
     // Scale options
-    let mut scale_value: i32 = 0;
+    let mut scale_value: Option<i32> = None;
     let mut scale_min: i32 = 0;
     let mut scale_max: i32 = 100;
     let mut scale_step: i32 = 1;
@@ -178,12 +291,18 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
     // Forms options
     let mut form_entries: Vec<String> = Vec::new();
     let mut form_passwords: Vec<String> = Vec::new();
+    let mut form_spins: Vec<String> = Vec::new();
+
+    // Login options
+    let mut username = String::new();
+    let mut remember_label: Option<String> = None;
 
     // Message dialog options
     let mut icon_name: Option<String> = None;
     let mut no_markup = false;
     let mut ellipsize = false;
     let mut switch_mode = false;
+    let mut default_cancel = false;
     let mut extra_buttons: Vec<String> = Vec::new();
     let mut ok_label = String::new();
     let mut cancel_label = String::new();
@@ -213,14 +332,26 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             Long("progress") => dialog_type = Some(DialogType::Progress),
             Long("file-selection") => dialog_type = Some(DialogType::FileSelection),
             Long("list") => dialog_type = Some(DialogType::List),
+            Long("chooser") => dialog_type = Some(DialogType::Chooser),
+            Long("color-selection") => dialog_type = Some(DialogType::ColorSelection),
+            Long("countdown") => {
+                dialog_type = Some(DialogType::Countdown);
+                countdown_secs = parser.value()?.string()?.parse()?;
+            }
             Long("calendar") => dialog_type = Some(DialogType::Calendar),
+            Long("font-selection") => dialog_type = Some(DialogType::FontSelection),
+            Long("icon-selection") => dialog_type = Some(DialogType::IconSelection),
+            Long("image") => dialog_type = Some(DialogType::Image),
+            Long("qr") => dialog_type = Some(DialogType::Qr),
+            Long("tray") => dialog_type = Some(DialogType::Tray),
             Long("text-info") => dialog_type = Some(DialogType::TextInfo),
             Long("scale") => dialog_type = Some(DialogType::Scale),
             Long("forms") => dialog_type = Some(DialogType::Forms),
+            Long("login") => dialog_type = Some(DialogType::Login),
 
             // Common options
             Long("title") => title = parser.value()?.string()?,
-            Long("text") => text = parser.value()?.string()?,
+            Long("text") => text = unescape_text(&parser.value()?.string()?),
             Long("entry-text") => entry_text = parser.value()?.string()?,
             Long("hide-text") => {
                 // If --hide-text is specified with --entry, treat as password mode
@@ -231,11 +362,42 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             Long("timeout") => timeout = Some(parser.value()?.string()?.parse()?),
             Long("width") => width = Some(parser.value()?.string()?.parse()?),
             Long("height") => height = Some(parser.value()?.string()?.parse()?),
+            Long("center") => placement = Placement::Center,
+            Long("mouse") => placement = Placement::Mouse,
+            Long("monitor") => monitor = parser.value()?.string()?.parse()?,
+            #[cfg(feature = "tui")]
+            Long("force-tui") => zenity_rs::force_tui(true),
+            Long("attach") => attach = Some(parser.value()?.string()?.parse()?),
+            Long("window-icon") => window_icon = Some(parser.value()?.into()),
+            Long("font") => font = Some(parser.value()?.string()?),
+            Long("theme") => theme = Some(parser.value()?.into()),
+            Long("style") => style = Some(parser.value()?.string()?),
+            Long("accent") => {
+                let value = parser.value()?.string()?;
+                accent = Some(
+                    Rgba::parse(&value).ok_or_else(|| format!("invalid accent color: {value}"))?,
+                );
+            }
+            Long("geometry") => {
+                let (size, position) = parse_geometry(&parser.value()?.string()?)?;
+                if let Some((w, h)) = size {
+                    width = Some(w);
+                    height = Some(h);
+                }
+                if let Some((x, y)) = position {
+                    placement = Placement::At {
+                        x,
+                        y,
+                    };
+                }
+            }
             Long("no-wrap") => no_wrap = true,
             Long("no-markup") => no_markup = true,
+            Long("no-animations") => zenity_rs::set_animations(false),
             Long("ellipsize") => ellipsize = true,
             Long("icon-name") | Long("icon") => icon_name = Some(parser.value()?.string()?),
             Long("switch") => switch_mode = true,
+            Long("default-cancel") => default_cancel = true,
             Long("extra-button") => extra_buttons.push(parser.value()?.string()?),
             Long("ok-label") => ok_label = parser.value()?.string()?,
             Long("cancel-label") => cancel_label = parser.value()?.string()?,
@@ -256,9 +418,11 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 multiple_mode = true;
             }
             Long("filename") => filename = parser.value()?.string()?,
-            Long("confirm-overwrite") => {
-                // Deprecated option, accepted for compatibility only
-            }
+            Long("class") => class = parser.value()?.string()?,
+            Long("confirm-overwrite") => confirm_overwrite = true,
+            Long("allow-manage") => allow_manage = true,
+            Long("allow-directories") => allow_directories = true,
+            Long("portal") => portal = true,
             Long("file-filter") => {
                 let filter_spec = parser.value()?.string()?;
                 // Parse "Name | Pattern1 Pattern2 Pattern3" format
@@ -294,11 +458,20 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             Long("month") => cal_month = Some(parser.value()?.string()?.parse()?),
             Long("day") => cal_day = Some(parser.value()?.string()?.parse()?),
 
-            // Text info options
+            // Font selection options
+            Long("font-name") => font_name = parser.value()?.string()?,
+            Long("preview-text") => preview_text = parser.value()?.string()?,
+            Long("caption") => caption = unescape_text(&parser.value()?.string()?),
+            Long("menu") => menu = parser.value()?.string()?,
+            Long("listen") => listen = true,
+
+            // Text info and message dialog options
             Long("checkbox") => checkbox_text = parser.value()?.string()?,
+            Long("add-switch") => switches.push(parser.value()?.string()?),
+            Long("add-link") => links.push(parser.value()?.string()?),
 
             // Scale options
-            Long("value") => scale_value = parser.value()?.string()?.parse()?,
+            Long("value") => scale_value = Some(parser.value()?.string()?.parse()?),
             Long("min-value") => scale_min = parser.value()?.string()?.parse()?,
             Long("max-value") => scale_max = parser.value()?.string()?.parse()?,
             Long("step") => scale_step = parser.value()?.string()?.parse()?,
@@ -307,13 +480,28 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             // Forms options
             Long("add-entry") => form_entries.push(parser.value()?.string()?),
             Long("add-password") => form_passwords.push(parser.value()?.string()?),
+            Long("add-spin") => form_spins.push(parser.value()?.string()?),
+
+            // Color selection options
+            Long("color") => initial_color = parser.value()?.string()?,
+            Long("show-palette") => show_palette = true,
+            Long("palette") => palette_file = parser.value()?.string()?,
+
+            // Login options
+            Long("username") => username = parser.value()?.string()?,
+            Long("remember") => {
+                remember_label = Some(match parser.optional_value() {
+                    Some(label) => label.string()?,
+                    None => "Remember me".to_string(),
+                });
+            }
 
             // Ignored options (for compatibility with zenity)
             Long("modal") => { /* Ignored */ }
 
             Value(val) => {
-                // Positional arguments - for list dialog these are row values
-                if dialog_type == Some(DialogType::List) {
+                // Positional arguments - for list dialogs these are row values
+                if matches!(dialog_type, Some(DialogType::List | DialogType::Chooser)) {
                     list_values.push(val.string()?);
                 } else if text.is_empty() {
                     text = val.string()?;
@@ -333,9 +521,28 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
         }
     };
 
+    zenity_rs::set_placement(placement);
+    zenity_rs::set_parent(attach);
+    zenity_rs::on_output(monitor);
+    zenity_rs::set_window_icon(window_icon);
+    zenity_rs::set_font(font.as_deref());
+    load_theme(theme, accent, style.as_deref())?;
+
+    // Dialogs that lead to another, such as a save dialog asking before
+    // replacing a file, show both over one connection
+    let _session = zenity_rs::Session::new();
+
     // Build and show the dialog
     match dialog_type {
         DialogType::Info => {
+            let preset = get_button_preset(
+                &ok_label,
+                &cancel_label,
+                &extra_buttons,
+                switch_mode,
+                ButtonPreset::Ok,
+            );
+            let preset_count = preset.labels().len();
             let builder = message()
                 .title(if title.is_empty() {
                     "Information"
@@ -344,13 +551,7 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 })
                 .text(&text)
                 .icon(get_icon(&icon_name, Icon::Info))
-                .buttons(get_button_preset(
-                    &ok_label,
-                    &cancel_label,
-                    &extra_buttons,
-                    switch_mode,
-                    ButtonPreset::Ok,
-                ));
+                .buttons(preset);
             let builder = apply_message_options(
                 builder,
                 timeout,
@@ -361,22 +562,27 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 ellipsize,
                 switch_mode,
                 &extra_buttons,
+                &checkbox_text,
+                &switches,
+                &links,
             );
             let result = builder.show()?;
-            Ok(handle_message_result(result, &extra_buttons, None))
+            Ok(handle_message_result(result, &extra_buttons, preset_count))
         }
         DialogType::Warning => {
+            let preset = get_button_preset(
+                &ok_label,
+                &cancel_label,
+                &extra_buttons,
+                switch_mode,
+                ButtonPreset::Ok,
+            );
+            let preset_count = preset.labels().len();
             let builder = message()
                 .title(if title.is_empty() { "Warning" } else { &title })
                 .text(&text)
                 .icon(get_icon(&icon_name, Icon::Warning))
-                .buttons(get_button_preset(
-                    &ok_label,
-                    &cancel_label,
-                    &extra_buttons,
-                    switch_mode,
-                    ButtonPreset::Ok,
-                ));
+                .buttons(preset);
             let builder = apply_message_options(
                 builder,
                 timeout,
@@ -387,22 +593,27 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 ellipsize,
                 switch_mode,
                 &extra_buttons,
+                &checkbox_text,
+                &switches,
+                &links,
             );
             let result = builder.show()?;
-            Ok(handle_message_result(result, &extra_buttons, None))
+            Ok(handle_message_result(result, &extra_buttons, preset_count))
         }
         DialogType::Error => {
+            let preset = get_button_preset(
+                &ok_label,
+                &cancel_label,
+                &extra_buttons,
+                switch_mode,
+                ButtonPreset::Ok,
+            );
+            let preset_count = preset.labels().len();
             let builder = message()
                 .title(if title.is_empty() { "Error" } else { &title })
                 .text(&text)
                 .icon(get_icon(&icon_name, Icon::Error))
-                .buttons(get_button_preset(
-                    &ok_label,
-                    &cancel_label,
-                    &extra_buttons,
-                    switch_mode,
-                    ButtonPreset::Ok,
-                ));
+                .buttons(preset);
             let builder = apply_message_options(
                 builder,
                 timeout,
@@ -413,22 +624,27 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 ellipsize,
                 switch_mode,
                 &extra_buttons,
+                &checkbox_text,
+                &switches,
+                &links,
             );
             let result = builder.show()?;
-            Ok(handle_message_result(result, &extra_buttons, None))
+            Ok(handle_message_result(result, &extra_buttons, preset_count))
         }
         DialogType::Question => {
+            let preset = get_button_preset(
+                &ok_label,
+                &cancel_label,
+                &extra_buttons,
+                switch_mode,
+                ButtonPreset::YesNo,
+            );
+            let preset_count = preset.labels().len();
             let builder = message()
                 .title(if title.is_empty() { "Question" } else { &title })
                 .text(&text)
                 .icon(get_icon(&icon_name, Icon::Question))
-                .buttons(get_button_preset(
-                    &ok_label,
-                    &cancel_label,
-                    &extra_buttons,
-                    switch_mode,
-                    ButtonPreset::YesNo,
-                ));
+                .buttons(preset);
             let builder = apply_message_options(
                 builder,
                 timeout,
@@ -439,13 +655,17 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
                 ellipsize,
                 switch_mode,
                 &extra_buttons,
+                &checkbox_text,
+                &switches,
+                &links,
             );
+            let builder = if default_cancel && !switch_mode {
+                builder.default_button(1)
+            } else {
+                builder
+            };
             let result = builder.show()?;
-            Ok(handle_message_result(
-                result,
-                &extra_buttons,
-                Some(1 + extra_buttons.len()),
-            ))
+            Ok(handle_message_result(result, &extra_buttons, preset_count))
         }
         DialogType::Entry => {
             let mut builder = entry()
@@ -501,11 +721,18 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             builder = builder
                 .directory(directory_mode)
                 .save(save_mode)
+                .confirm_overwrite(confirm_overwrite)
+                .allow_manage(allow_manage)
                 .multiple(multiple_mode)
+                .allow_directories(allow_directories)
+                .portal(portal)
                 .separator(&separator);
             if !filename.is_empty() {
                 builder = builder.filename(&filename);
             }
+            if !class.is_empty() {
+                builder = builder.class(&class);
+            }
             for filter in file_filters {
                 builder = builder.add_filter(filter);
             }
@@ -539,25 +766,10 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             for col in &hidden_columns {
                 builder = builder.hide_column(*col);
             }
+            builder = builder.no_markup(no_markup);
 
-            // Determine column count for rows
-            let num_columns = columns.len().max(1);
-
-            // Build rows from list_values based on column count
-            for chunk in list_values.chunks(num_columns) {
-                builder = builder.row(chunk.to_vec());
-            }
-
-            // Read additional rows from stdin if data is being piped
-            // Zenity format: each line is one column value, multiple lines form one row
-            if !std::io::stdin().is_terminal() {
-                use std::io::{self, BufRead};
-                let stdin = io::stdin();
-                let lines: Vec<String> = stdin.lock().lines().map_while(Result::ok).collect();
-                // Group lines by num_columns to form rows
-                for chunk in lines.chunks(num_columns) {
-                    builder = builder.row(chunk.to_vec());
-                }
+            for row in list_rows(&list_values, columns.len()) {
+                builder = builder.row(row);
             }
 
             if let Some(w) = width {
@@ -569,6 +781,36 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             let result = builder.show()?;
             handle_list_result(result, &separator)
         }
+        DialogType::Chooser => {
+            let mut builder = chooser();
+            if !title.is_empty() {
+                builder = builder.title(&title);
+            }
+            if !text.is_empty() {
+                builder = builder.text(&text);
+            }
+            // With --checklist the first column says whether a row starts selected
+            for row in list_rows(&list_values, columns.len()) {
+                builder = match row.split_first() {
+                    Some((first, rest)) if checklist => {
+                        if first.eq_ignore_ascii_case("true") {
+                            builder.selected_row(rest.to_vec())
+                        } else {
+                            builder.row(rest.to_vec())
+                        }
+                    }
+                    _ => builder.row(row),
+                };
+            }
+            if let Some(w) = width {
+                builder = builder.width(w);
+            }
+            if let Some(h) = height {
+                builder = builder.height(h);
+            }
+            let result = builder.show()?;
+            handle_chooser_result(result, &separator)
+        }
         DialogType::Calendar => {
             let mut builder = calendar();
             if !title.is_empty() {
@@ -595,6 +837,161 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             let result = builder.show()?;
             handle_calendar_result(result)
         }
+        DialogType::FontSelection => {
+            let mut builder = font_select();
+            if !title.is_empty() {
+                builder = builder.title(&title);
+            }
+            if !text.is_empty() {
+                builder = builder.text(&text);
+            }
+            if !font_name.is_empty() {
+                builder = builder.font_name(&font_name);
+            }
+            if !preview_text.is_empty() {
+                builder = builder.preview_text(&preview_text);
+            }
+            if let Some(w) = width {
+                builder = builder.width(w);
+            }
+            if let Some(h) = height {
+                builder = builder.height(h);
+            }
+            let result = builder.show()?;
+            handle_font_select_result(result)
+        }
+        DialogType::IconSelection => {
+            let mut builder = icon_select();
+            if !title.is_empty() {
+                builder = builder.title(&title);
+            }
+            if !text.is_empty() {
+                builder = builder.text(&text);
+            }
+            if let Some(name) = &icon_name {
+                builder = builder.icon_name(name);
+            }
+            if let Some(w) = width {
+                builder = builder.width(w);
+            }
+            if let Some(h) = height {
+                builder = builder.height(h);
+            }
+            let result = builder.show()?;
+            handle_icon_select_result(result)
+        }
+        DialogType::Image => {
+            let mut builder = image().filename(&filename);
+            if !title.is_empty() {
+                builder = builder.title(&title);
+            }
+            if !text.is_empty() {
+                builder = builder.text(&text);
+            }
+            if let Some(w) = width {
+                builder = builder.width(w);
+            }
+            if let Some(h) = height {
+                builder = builder.height(h);
+            }
+            let result = builder.show()?;
+            Ok(result.exit_code())
+        }
+        DialogType::ColorSelection => {
+            let mut builder = color_select().show_palette(show_palette);
+            if !title.is_empty() {
+                builder = builder.title(&title);
+            }
+            if !text.is_empty() {
+                builder = builder.text(&text);
+            }
+            if !initial_color.is_empty() {
+                builder = builder.color(&initial_color);
+            }
+            if !palette_file.is_empty() {
+                builder = builder.palette_file(&palette_file);
+            }
+            if let Some(w) = width {
+                builder = builder.width(w);
+            }
+            if let Some(h) = height {
+                builder = builder.height(h);
+            }
+            match builder.show()? {
+                ColorResult::Selected {
+                    red,
+                    green,
+                    blue,
+                } => {
+                    println!("rgb({red},{green},{blue})");
+                    Ok(0)
+                }
+                result => Ok(result.exit_code()),
+            }
+        }
+        DialogType::Countdown => {
+            let mut builder = countdown(countdown_secs);
+            if !title.is_empty() {
+                builder = builder.title(&title);
+            }
+            if !text.is_empty() {
+                builder = builder.text(&text);
+            }
+            if let Some(w) = width {
+                builder = builder.width(w);
+            }
+            if let Some(h) = height {
+                builder = builder.height(h);
+            }
+            let result = builder.show()?;
+            Ok(result.exit_code())
+        }
+        DialogType::Qr => {
+            // Without --text, encode whatever is piped in
+            let mut data = text.clone();
+            if data.is_empty() && !std::io::stdin().is_terminal() {
+                use std::io::Read;
+                std::io::stdin().read_to_string(&mut data)?;
+                data.truncate(data.trim_end_matches('\n').len());
+            }
+            let mut builder = qr(&data);
+            if !title.is_empty() {
+                builder = builder.title(&title);
+            }
+            if !caption.is_empty() {
+                builder = builder.caption(&caption);
+            }
+            if let Some(w) = width {
+                builder = builder.width(w);
+            }
+            if let Some(h) = height {
+                builder = builder.height(h);
+            }
+            let result = builder.show()?;
+            Ok(result.exit_code())
+        }
+        DialogType::Tray => {
+            let mut builder = tray().listen(listen);
+            if !title.is_empty() {
+                builder = builder.title(&title);
+            }
+            if !text.is_empty() {
+                builder = builder.tooltip(&text);
+            }
+            if let Some(name) = &icon_name {
+                builder = builder.icon(name);
+            }
+            if !menu.is_empty() {
+                builder = builder.menu(&menu);
+            }
+            let result = builder.show(|event| {
+                match event {
+                    TrayEvent::Activate => println!("activate"),
+                    TrayEvent::Menu(action) => println!("{action}"),
+                }
+            })?;
+            Ok(result.exit_code())
+        }
         DialogType::TextInfo => {
             let mut builder = text_info();
             if !title.is_empty() {
@@ -607,6 +1004,9 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             if has_checkbox {
                 builder = builder.checkbox(&checkbox_text);
             }
+            if let Some(uri) = links.first() {
+                builder = builder.link("", uri);
+            }
             if let Some(w) = width {
                 builder = builder.width(w);
             }
@@ -624,8 +1024,10 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             if !text.is_empty() {
                 builder = builder.text(&text);
             }
+            if let Some(value) = scale_value {
+                builder = builder.value(value);
+            }
             builder = builder
-                .value(scale_value)
                 .min_value(scale_min)
                 .max_value(scale_max)
                 .step(scale_step)
@@ -654,6 +1056,9 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             for label in &form_passwords {
                 builder = builder.add_password(label);
             }
+            for label in &form_spins {
+                builder = builder.add_spin(label, scale_min, scale_max, scale_step);
+            }
             builder = builder.separator(&separator);
             if let Some(w) = width {
                 builder = builder.width(w);
@@ -664,6 +1069,29 @@ fn run() -> Result<i32, Box<dyn std::error::Error>> {
             let result = builder.show()?;
             handle_forms_result(result, &separator)
         }
+        DialogType::Login => {
+            let mut builder = login();
+            if !title.is_empty() {
+                builder = builder.title(&title);
+            }
+            if !text.is_empty() {
+                builder = builder.text(&text);
+            }
+            if !username.is_empty() {
+                builder = builder.username(&username);
+            }
+            if let Some(label) = &remember_label {
+                builder = builder.remember(label);
+            }
+            if let Some(w) = width {
+                builder = builder.width(w);
+            }
+            if let Some(h) = height {
+                builder = builder.height(h);
+            }
+            let result = builder.show()?;
+            handle_login_result(result, remember_label.is_some())
+        }
     }
 }
 
@@ -681,6 +1109,36 @@ fn handle_list_result(
     }
 }
 
+fn handle_chooser_result(
+    result: ChooserResult,
+    separator: &str,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    match result {
+        ChooserResult::Selected(items) => {
+            println!("{}", items.join(separator));
+            Ok(0)
+        }
+        ChooserResult::Cancelled => Ok(1),
+        ChooserResult::Closed => Ok(255),
+    }
+}
+
+/// Groups list values into rows of `columns` values each: first the
+/// positional arguments, then lines piped on stdin.
+fn list_rows(values: &[String], columns: usize) -> Vec<Vec<String>> {
+    let num_columns = columns.max(1);
+    let mut rows: Vec<Vec<String>> = values.chunks(num_columns).map(<[String]>::to_vec).collect();
+
+    // Zenity format: each line is one column value, multiple lines form one row
+    if !std::io::stdin().is_terminal() {
+        use std::io::{self, BufRead};
+        let stdin = io::stdin();
+        let lines: Vec<String> = stdin.lock().lines().map_while(Result::ok).collect();
+        rows.extend(lines.chunks(num_columns).map(<[String]>::to_vec));
+    }
+    rows
+}
+
 fn handle_calendar_result(result: CalendarResult) -> Result<i32, Box<dyn std::error::Error>> {
     match result {
         CalendarResult::Selected {
@@ -696,6 +1154,20 @@ fn handle_calendar_result(result: CalendarResult) -> Result<i32, Box<dyn std::er
     }
 }
 
+fn handle_font_select_result(result: FontSelectResult) -> Result<i32, Box<dyn std::error::Error>> {
+    if let Some(font) = result.to_string() {
+        println!("{font}");
+    }
+    Ok(result.exit_code())
+}
+
+fn handle_icon_select_result(result: IconSelectResult) -> Result<i32, Box<dyn std::error::Error>> {
+    if let IconSelectResult::Selected(name) = &result {
+        println!("{name}");
+    }
+    Ok(result.exit_code())
+}
+
 fn handle_file_select_result(
     result: FileSelectResult,
     separator: &str,
@@ -782,6 +1254,28 @@ fn handle_forms_result(
     }
 }
 
+fn handle_login_result(
+    result: LoginResult,
+    has_remember: bool,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    match result {
+        LoginResult::Login {
+            username,
+            password,
+            remember,
+        } => {
+            println!("{username}");
+            println!("{password}");
+            if has_remember {
+                println!("{}", if remember { "TRUE" } else { "FALSE" });
+            }
+            Ok(0)
+        }
+        LoginResult::Cancelled => Ok(1),
+        LoginResult::Closed => Ok(255),
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum DialogType {
     Info,
@@ -793,10 +1287,97 @@ enum DialogType {
     Progress,
     FileSelection,
     List,
+    Chooser,
+    ColorSelection,
+    Countdown,
     Calendar,
+    FontSelection,
+    IconSelection,
+    Image,
+    Qr,
+    Tray,
     TextInfo,
     Scale,
     Forms,
+    Login,
+}
+
+/// Picks the theme `--theme` (or else `ZENITY_RS_THEME`) names: a
+/// preset, the detected theme, or a theme file, failing if that can't be
+/// read. Without either, the user's `theme.toml` is used if there is one,
+/// warning about what is wrong with it. `--accent` then recolors the
+/// theme, and `--style` overrides single fields.
+fn load_theme(
+    spec: Option<PathBuf>,
+    accent: Option<Rgba>,
+    style: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let spec = spec.or_else(|| {
+        std::env::var_os("ZENITY_RS_THEME")
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    });
+    let theme = match spec {
+        Some(spec) => {
+            match spec.to_str() {
+                Some("light") => Some(in_desktop_accent(THEME_LIGHT)),
+                Some("dark") => Some(in_desktop_accent(THEME_DARK)),
+                Some("high-contrast") => Some(THEME_HIGH_CONTRAST),
+                Some("auto") => None,
+                _ => {
+                    Some(zenity_rs::load_theme(&spec).map_err(|e| {
+                        match e {
+                            zenity_rs::Error::Io(e) => format!("{}: {e}", spec.display()).into(),
+                            e => Box::new(e) as Box<dyn std::error::Error>,
+                        }
+                    })?)
+                }
+            }
+        }
+        None => user_theme(),
+    };
+    let theme = match accent {
+        Some(accent) => {
+            Some(
+                theme
+                    .unwrap_or_else(zenity_rs::detect_theme)
+                    .with_accent(accent),
+            )
+        }
+        None => theme,
+    };
+    let theme = match style {
+        Some(style) => {
+            Some(
+                theme
+                    .unwrap_or_else(zenity_rs::detect_theme)
+                    .with_style(style)?,
+            )
+        }
+        None => theme,
+    };
+    zenity_rs::set_theme(theme);
+    Ok(())
+}
+
+/// Returns `preset` in the desktop's accent color, if it has one.
+fn in_desktop_accent(preset: Theme) -> Theme {
+    match zenity_rs::detect_accent() {
+        Some(accent) => preset.with_accent(accent),
+        None => preset,
+    }
+}
+
+/// Reads `~/.config/zenity-rs/theme.toml`, if there is one and it is
+/// valid.
+fn user_theme() -> Option<Theme> {
+    let path = dirs::config_dir()?.join("zenity-rs/theme.toml");
+    if !path.exists() {
+        return None;
+    }
+    zenity_rs::load_theme(&path)
+        .inspect_err(|e| eprintln!("zenity-rs: {e}"))
+        .ok()
 }
 
 fn print_help() {
@@ -811,13 +1392,28 @@ USAGE:
     --text=TEXT           Set the dialog text/prompt
     --width=N             Set the dialog width (minimum when --no-wrap is used)
     --height=N            Set the dialog height
+    --geometry=WxH+X+Y    Set the dialog size and position (either part optional)
+    --center              Center the dialog on the screen
+    --mouse               Center the dialog on the mouse pointer
+    --monitor=MONITOR     Show the dialog on a monitor (index from 0, primary or cursor)
+    --force-tui           Show message, entry, list and progress dialogs in the terminal
+    --attach=WINDOW       Attach the dialog to a window (X11 id or wayland:HANDLE)
+    --window-icon=PATH    Set the window's icon from an image file (e.g., SVG or PNG)
+    --font=FAMILY         Draw text in a font family (default: fontconfig's sans-serif)
+    --theme=THEME         Use the light, dark, high-contrast or auto(-detected) theme,
+                          or read colors, radii and sizes from a theme file
+                          (default: $ZENITY_RS_THEME, or else ~/.config/zenity-rs/theme.toml)
+    --accent=#RRGGBB      Use a color for focus, selections and progress
+    --style=KEY:VALUE,... Override theme fields for this dialog
+                          (e.g. button_text:#fff,window_bg:#202020,control_radius:0)
     --no-wrap             Do not wrap text (width becomes minimum, content can expand)
-    --icon=ICON           Set the icon name (e.g., dialog-information, dialog-warning)
+    --icon=ICON           Set the icon name (e.g., dialog-information) or image file
     --ok-label=TEXT       Set the label of the OK button
     --cancel-label=TEXT   Set the label of the Cancel button
     --extra-button=TEXT   Add an extra button (outputs label text, exit code 1+)
     --switch              Suppress OK/Cancel buttons, only show extra buttons
-    --no-markup           Do not enable pango markup (for compatibility)
+    --no-markup           Show text as is instead of Pango markup
+    --no-animations       Do not fade dialogs in or animate hover effects
     --ellipsize           Enable ellipsizing in dialog text (for compatibility)
     -h, --help            Print this help message
     --version             Print version information
@@ -833,9 +1429,14 @@ USAGE:
       --no-wrap           Do not wrap text (width becomes minimum, content can expand)
       --icon=ICON         Set the icon name (also accepts --icon-name for compatibility)
       --switch            Only show extra buttons (suppress OK/Cancel)
-      --extra-button=TEXT Add extra buttons
-      --no-markup         Do not enable pango markup (for compatibility)
+      --extra-button=TEXT Add extra buttons ("_Retry" underlines R, activated by Alt+R)
+      --no-markup         Show text as is instead of Pango markup
       --ellipsize         Enable ellipsizing in dialog text (for compatibility)
+      --checkbox=TEXT     Add a checkbox (prints TRUE/FALSE with its state)
+      --add-switch=TEXT   Add an on/off switch (can be repeated; prints TRUE/FALSE
+                          for each, before the checkbox's state)
+      --add-link=URI      Add a link that opens URI (can be repeated)
+      --default-cancel    Make Cancel/No the default button (question dialogs)
 
   --entry                 Display a text entry dialog
     --entry-text=TEXT     Set default text
@@ -857,8 +1458,12 @@ USAGE:
     --multiple        Allow multiple file selection
     --separator=TEXT  Output separator for multiple files (default: space)
     --filename=TEXT   Default filename/path
+    --class=TEXT      Remember the last directory under this name (default: the title)
     --file-filter=SPEC Add file filter (e.g., "*.rs" or "Video | *.mkv *.mp4")
-    --confirm-overwrite Deprecated, accepted for compatibility
+    --confirm-overwrite Ask before replacing an existing file in save mode
+    --allow-manage    Allow renaming and deleting files from a right-click menu
+    --allow-directories Return selected directories too in --multiple mode
+    --portal          Use the desktop's file chooser via xdg-desktop-portal
 
   --list                Display a list selection dialog
     --column=TEXT     Add a column header (can be repeated)
@@ -868,14 +1473,55 @@ USAGE:
     --hide-column=N   Hide column N (1-based, can be repeated)
     [VALUES...]       Row values (number must match column count)
 
+  --chooser               Pick and order rows from an "Available" list (prints them in order)
+    --column=TEXT         Add a column header (can be repeated)
+    --checklist           First column is TRUE/FALSE: whether the row starts selected
+    --separator=TEXT      Output separator (default: |)
+    [VALUES...]           Row values, as for --list
+
+  --color-selection       Pick a color (prints "rgb(R,G,B)")
+    --color=VALUE         Initial color (#rrggbb, #rgb or rgb(R,G,B))
+    --show-palette        Show a palette of swatches
+    --palette=FILE        Palette file: one color per line, or a GIMP .gpl palette
+                          "Pick from Screen" uses the desktop portal when available
+
+  --countdown=SECONDS     Count down with Pause/Cancel buttons (exit 0 when time is up)
+    --text=TEXT           Text shown above the remaining time
+
   --calendar              Display a calendar date picker
     --year=N              Initial year
     --month=N             Initial month (1-12)
     --day=N               Initial day (1-31)
 
+  --font-selection        Display a font picker (prints "Family Style Size")
+    --font-name=TEXT      Initially selected font (e.g. "DejaVu Sans Bold 12")
+    --preview-text=TEXT   Sample text for the preview
+
+  --icon-selection        Display an icon picker for the current icon theme
+    --icon=NAME           Initially selected icon name
+
+  --image                 Display an image (PNG, JPEG, WebP or SVG) for confirmation
+    --filename=PATH       Image file to show
+    --text=TEXT           Caption shown under the image
+                          Scroll to zoom, drag to pan, 0 to fit, 1 for actual size
+
+  --qr                    Display text as a QR code
+    --text=TEXT           Text to encode (otherwise reads stdin)
+    --caption=TEXT        Caption shown under the code
+
+  --tray                  Show a system tray icon until quit
+    --text=TEXT           Tooltip text
+    --icon=ICON           Icon name or image file
+    --menu=SPEC           Menu items as "Label!action|Label!action" (empty item: separator)
+                          Chosen actions are printed; clicking the icon prints "activate";
+                          the action "quit" removes the icon
+    --listen              Read commands from stdin: "icon: NAME", "tooltip: TEXT",
+                          "visible: true|false", "menu: SPEC", "quit"
+
   --text-info             Display scrollable text from file or stdin
     --filename=TEXT       Read text from file (otherwise reads stdin)
     --checkbox=TEXT       Add checkbox with label (for agreements)
+    --add-link=URI        Add a link left of the buttons that opens URI
 
   --scale                 Display a slider to select a numeric value
     --value=N             Initial value (default: 0)
@@ -887,8 +1533,14 @@ USAGE:
   --forms                 Display a form with multiple input fields
     --add-entry=LABEL     Add a text entry field (can be repeated)
     --add-password=LABEL  Add a password field (can be repeated)
+    --add-spin=LABEL      Add a number field from --min-value to --max-value,
+                          stepped by --step (can be repeated)
     --separator=CHAR      Output separator (default: |)
 
+  --login                 Ask for a username and password (printed on separate lines)
+    --username=TEXT       Prefilled username
+    --remember[=LABEL]    Add a "Remember me" checkbox (prints TRUE/FALSE as a third line)
+
  EXAMPLES:
     zenity-rs --info --text="Operation completed"
     zenity-rs --question --text="Continue?" --timeout=10
@@ -900,16 +1552,25 @@ USAGE:
     zenity-rs --file-selection --multiple --separator="|" file1.rs file2.txt file3.rs
     zenity-rs --file-selection --file-filter="Video | *.mkv *.mp4 *.avi" --file-filter="Image | *.jpg *.png *.gif"
     zenity-rs --list --column="Name" --column="Size" file1 10KB file2 20KB
+    zenity-rs --chooser --column="Name" --column="Size" file1 10KB file2 20KB
+    zenity-rs --color-selection --color="rgb(53,132,228)" --show-palette
+    zenity-rs --countdown=60 --text="Rebooting in"
     zenity-rs --calendar --text="Select date:" --year=2024 --month=12
+    zenity-rs --font-selection --font-name="Cantarell 11"
+    zenity-rs --icon-selection --icon=folder-music
+    zenity-rs --image --filename=screenshot.png --text="Upload this screenshot?"
+    zenity-rs --qr --text="WIFI:T:WPA;S:home;P:secret;;" --caption="Scan to join"
+    zenity-rs --tray --icon=mail-unread --text="New mail" --menu="Open!open|Quit!quit"
     zenity-rs --text-info --filename=LICENSE --checkbox="I accept"
     zenity-rs --scale --text="Volume:" --value=50 --max-value=100
     zenity-rs --forms --add-entry="Name" --add-password="Password"
+    zenity-rs --login --text="Sign in to the server" --remember
 
 EXIT CODES:
     0   OK/Yes clicked, or value selected
     1   Cancel/No clicked, or checkbox unchecked
     5   Timeout reached
-    255 Dialog was closed (ESC or window close)
+    255 Dialog was closed (window close, or ESC without a Cancel button)
     100 Error occurred
 "#
     );
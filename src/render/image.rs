@@ -0,0 +1,104 @@
+//! Raster image decoding (PNG, JPEG, WebP) into canvases.
+
+use std::{
+    fs::File,
+    io::{BufReader, Cursor},
+    path::Path,
+};
+
+use tiny_skia::{IntSize, Pixmap};
+
+use super::Canvas;
+
+/// Returns true if the file name has an extension we can decode.
+pub(crate) fn is_supported_image(name: &str) -> bool {
+    let ext = name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase());
+    matches!(ext.as_deref(), Some("png" | "jpg" | "jpeg" | "webp"))
+}
+
+/// Decodes an image file, choosing the decoder from its extension.
+pub(crate) fn load_image(path: &Path) -> Option<Canvas> {
+    let name = path.file_name()?.to_str()?;
+    let ext = name.rsplit_once('.')?.1.to_ascii_lowercase();
+
+    let (width, height, rgba) = match ext.as_str() {
+        "png" => decode_png(path)?,
+        "jpg" | "jpeg" => decode_jpeg(path)?,
+        "webp" => decode_webp(path)?,
+        _ => return None,
+    };
+    canvas_from_rgba(width, height, rgba)
+}
+
+fn decode_png(path: &Path) -> Option<(u32, u32, Vec<u8>)> {
+    let mut decoder = png::Decoder::new(BufReader::new(File::open(path).ok()?));
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().ok()?;
+    let mut buf = vec![0; reader.output_buffer_size()?];
+    let info = reader.next_frame(&mut buf).ok()?;
+    buf.truncate(info.buffer_size());
+
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => {
+            buf.chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], 255])
+                .collect()
+        }
+        png::ColorType::GrayscaleAlpha => {
+            buf.chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect()
+        }
+        png::ColorType::Grayscale => buf.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+        png::ColorType::Indexed => return None,
+    };
+    Some((info.width, info.height, rgba))
+}
+
+fn decode_jpeg(path: &Path) -> Option<(u32, u32, Vec<u8>)> {
+    use zune_jpeg::zune_core::{colorspace::ColorSpace, options::DecoderOptions};
+
+    let data = std::fs::read(path).ok()?;
+    let options = DecoderOptions::default().jpeg_set_out_colorspace(ColorSpace::RGBA);
+    let mut decoder = zune_jpeg::JpegDecoder::new_with_options(Cursor::new(data), options);
+    let rgba = decoder.decode().ok()?;
+    let info = decoder.info()?;
+    Some((info.width as u32, info.height as u32, rgba))
+}
+
+fn decode_webp(path: &Path) -> Option<(u32, u32, Vec<u8>)> {
+    let file = BufReader::new(File::open(path).ok()?);
+    let mut decoder = image_webp::WebPDecoder::new(file).ok()?;
+    let (width, height) = decoder.dimensions();
+    let mut buf = vec![0; decoder.output_buffer_size()?];
+    decoder.read_image(&mut buf).ok()?;
+
+    let rgba = if decoder.has_alpha() {
+        buf
+    } else {
+        buf.chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect()
+    };
+    Some((width, height, rgba))
+}
+
+/// Builds a canvas from straight (non-premultiplied) RGBA pixels.
+fn canvas_from_rgba(width: u32, height: u32, mut rgba: Vec<u8>) -> Option<Canvas> {
+    if rgba.len() != (width as usize) * (height as usize) * 4 {
+        return None;
+    }
+    for px in rgba.chunks_exact_mut(4) {
+        let a = px[3] as u32;
+        for c in &mut px[..3] {
+            *c = (*c as u32 * a / 255) as u8;
+        }
+    }
+    let pixmap = Pixmap::from_vec(rgba, IntSize::from_wh(width, height)?)?;
+    Some(Canvas {
+        pixmap,
+    })
+}
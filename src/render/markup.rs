@@ -0,0 +1,273 @@
+//! Pango-style markup: `<b>`, `<i>`, `<u>`, `<s>`, `<tt>`, `<big>`,
+//! `<small>` and `<span>` with entity escapes, parsed into styled runs.
+
+use std::ops::Range;
+
+use super::{Rgba, rgb};
+use crate::ui::color::parse_color;
+
+/// Size steps between the named sizes, as in Pango.
+const SIZE_STEP: f32 = 1.2;
+
+/// Points per logical pixel at the base font size, to read sizes given in
+/// points.
+const POINTS_PER_PX: f32 = 0.75;
+
+/// How text in a run is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Style {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub monospace: bool,
+    /// Size relative to the font's
+    pub scale: f32,
+    /// Color replacing the text color
+    pub color: Option<Rgba>,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            bold: false,
+            italic: false,
+            underline: false,
+            strikethrough: false,
+            monospace: false,
+            scale: 1.0,
+            color: None,
+        }
+    }
+}
+
+/// A byte range of text drawn in one style.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Run {
+    pub range: Range<usize>,
+    pub style: Style,
+}
+
+/// Text with markup removed, and the runs styling it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StyledText {
+    pub text: String,
+    pub runs: Vec<Run>,
+}
+
+impl StyledText {
+    /// Returns the runs covering `range` of the text, relative to its start.
+    pub fn runs_in(&self, range: Range<usize>) -> Vec<Run> {
+        self.runs
+            .iter()
+            .filter(|run| run.range.start < range.end && run.range.end > range.start)
+            .map(|run| {
+                Run {
+                    range: run.range.start.max(range.start) - range.start
+                        ..run.range.end.min(range.end) - range.start,
+                    style: run.style,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Parses `markup`, or returns `None` if it isn't well-formed, in which
+/// case it is best shown as is.
+pub(crate) fn parse(markup: &str) -> Option<StyledText> {
+    let mut styled = StyledText::default();
+    // Open tags and the style inside each
+    let mut stack: Vec<(&str, Style)> = Vec::new();
+    let mut rest = markup;
+
+    while !rest.is_empty() {
+        let style = stack
+            .last()
+            .map_or_else(Style::default, |(_, style)| *style);
+        if let Some(tag) = rest.strip_prefix('<') {
+            let end = tag.find('>')?;
+            let (tag, after) = (&tag[..end], &tag[end + 1..]);
+            rest = after;
+
+            if let Some(name) = tag.strip_prefix('/') {
+                let (open, _) = stack.pop()?;
+                if open != name.trim() {
+                    return None;
+                }
+            } else {
+                let (name, attrs) = tag
+                    .trim()
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((tag.trim(), ""));
+                let style = apply_tag(style, name, attrs)?;
+                stack.push((name, style));
+            }
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let start = styled.text.len();
+            unescape(&rest[..end], &mut styled.text)?;
+            rest = &rest[end..];
+
+            let range = start..styled.text.len();
+            match styled.runs.last_mut() {
+                Some(last) if last.style == style && last.range.end == range.start => {
+                    last.range.end = range.end;
+                }
+                _ => {
+                    styled.runs.push(Run {
+                        range,
+                        style,
+                    })
+                }
+            }
+        }
+    }
+
+    stack.is_empty().then_some(styled)
+}
+
+/// Returns `style` changed by an opening tag, or `None` for tags and
+/// attributes we don't know.
+fn apply_tag(mut style: Style, name: &str, attrs: &str) -> Option<Style> {
+    match name {
+        "b" => style.bold = true,
+        "i" => style.italic = true,
+        "u" => style.underline = true,
+        "s" => style.strikethrough = true,
+        "tt" => style.monospace = true,
+        "big" => style.scale *= SIZE_STEP,
+        "small" => style.scale /= SIZE_STEP,
+        "span" => {
+            for (attr, value) in parse_attributes(attrs)? {
+                apply_attribute(&mut style, attr, &value)?;
+            }
+        }
+        _ => return None,
+    }
+    Some(style)
+}
+
+/// Splits `name="value"` pairs, with single or double quotes.
+fn parse_attributes(mut attrs: &str) -> Option<Vec<(&str, String)>> {
+    let mut parsed = Vec::new();
+    loop {
+        attrs = attrs.trim_start();
+        if attrs.is_empty() {
+            return Some(parsed);
+        }
+        let (name, rest) = attrs.split_once('=')?;
+        let rest = rest.trim_start();
+        let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let (value, rest) = rest[1..].split_once(quote)?;
+        let mut unescaped = String::new();
+        unescape(value, &mut unescaped)?;
+        parsed.push((name.trim(), unescaped));
+        attrs = rest;
+    }
+}
+
+fn apply_attribute(style: &mut Style, attr: &str, value: &str) -> Option<()> {
+    match attr {
+        "foreground" | "fgcolor" | "color" => style.color = Some(parse_markup_color(value)?),
+        "size" | "font_size" => style.scale = parse_size(value, style.scale)?,
+        "weight" | "font_weight" => {
+            style.bold = match value {
+                "bold" | "ultrabold" | "heavy" | "ultraheavy" | "semibold" => true,
+                "normal" | "book" | "medium" | "light" | "ultralight" | "thin" => false,
+                _ => value.parse::<u32>().ok()? >= 600,
+            }
+        }
+        "style" | "font_style" => style.italic = matches!(value, "italic" | "oblique"),
+        "underline" => style.underline = !matches!(value, "none" | "false"),
+        "strikethrough" => style.strikethrough = value == "true",
+        "font_family" | "face" => {
+            style.monospace = matches!(value.to_lowercase().as_str(), "monospace" | "mono")
+        }
+        // Attributes that don't change how we draw are accepted and ignored
+        "background" | "bgcolor" | "font_desc" | "font" | "lang" | "letter_spacing" => {}
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Reads a size: a name, `smaller`/`larger`, points as `12pt` or in
+/// 1024ths, or a percentage. Returns it relative to the font's size.
+fn parse_size(value: &str, current: f32) -> Option<f32> {
+    let named = |steps: i32| SIZE_STEP.powi(steps);
+    Some(match value {
+        "xx-small" => named(-3),
+        "x-small" => named(-2),
+        "small" => named(-1),
+        "medium" => 1.0,
+        "large" => named(1),
+        "x-large" => named(2),
+        "xx-large" => named(3),
+        "smaller" => current / SIZE_STEP,
+        "larger" => current * SIZE_STEP,
+        _ => {
+            let points = if let Some(percent) = value.strip_suffix('%') {
+                return Some(percent.parse::<f32>().ok()? / 100.0);
+            } else if let Some(points) = value.strip_suffix("pt") {
+                points.parse::<f32>().ok()?
+            } else {
+                value.parse::<f32>().ok()? / 1024.0
+            };
+            points / (super::text::BASE_FONT_SIZE * POINTS_PER_PX)
+        }
+    })
+}
+
+fn parse_markup_color(value: &str) -> Option<Rgba> {
+    if let Some([r, g, b]) = parse_color(value) {
+        return Some(rgb(r, g, b));
+    }
+    let [r, g, b] = match value.to_lowercase().as_str() {
+        "black" => [0, 0, 0],
+        "white" => [255, 255, 255],
+        "gray" | "grey" => [190, 190, 190],
+        "red" => [255, 0, 0],
+        "green" => [0, 255, 0],
+        "blue" => [0, 0, 255],
+        "yellow" => [255, 255, 0],
+        "cyan" => [0, 255, 255],
+        "magenta" => [255, 0, 255],
+        "orange" => [255, 165, 0],
+        "purple" => [160, 32, 240],
+        "brown" => [165, 42, 42],
+        "pink" => [255, 192, 203],
+        "darkred" => [139, 0, 0],
+        "darkgreen" => [0, 100, 0],
+        "darkblue" => [0, 0, 139],
+        "darkgray" | "darkgrey" => [169, 169, 169],
+        _ => return None,
+    };
+    Some(rgb(r, g, b))
+}
+
+/// Appends `text` to `out` with entities such as `&amp;` replaced.
+fn unescape(mut text: &str, out: &mut String) -> Option<()> {
+    while let Some(amp) = text.find('&') {
+        out.push_str(&text[..amp]);
+        let end = text[amp..].find(';')? + amp;
+        let entity = &text[amp + 1..end];
+        let ch = match entity {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = entity.strip_prefix('#')?;
+                let code = match code.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => code.parse().ok()?,
+                };
+                char::from_u32(code)?
+            }
+        };
+        out.push(ch);
+        text = &text[end + 1..];
+    }
+    out.push_str(text);
+    Some(())
+}
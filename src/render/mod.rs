@@ -1,15 +1,62 @@
+#[cfg(feature = "images")]
+pub(crate) mod image;
+pub(crate) mod markup;
+#[cfg(feature = "svg")]
+pub(crate) mod svg;
 mod text;
 
-pub(crate) use text::Font;
+use std::path::Path;
+
+pub(crate) use text::{Align, set_family};
+pub use text::{Font, TextRenderer};
 use tiny_skia::{Color, Paint, PathBuilder, Pixmap, PixmapRef, Rect, Transform};
 
-/// A canvas backed by a tiny-skia Pixmap.
-/// Stores pixels in RGBA format internally, but can convert to ARGB for X11/Wayland.
+use crate::ui::color::parse_color;
+
+/// An image being drawn, in premultiplied RGBA pixels. Dialogs are drawn
+/// on canvases, and [`MessageBuilder::custom_content`] hands one to draw
+/// on beside a message.
+///
+/// Coordinates are in pixels from the top-left corner, and shapes are
+/// antialiased.
+///
+/// ```
+/// use zenity_rs::{Canvas, Font, rgb};
+///
+/// let mut canvas = Canvas::new(120, 40);
+/// canvas.fill_rounded_rect(0.0, 0.0, 120.0, 40.0, 8.0, rgb(0x35, 0x84, 0xe4));
+/// let label = Font::load(1.0).render("Ready").with_color(rgb(255, 255, 255)).finish();
+/// canvas.draw_canvas(&label, 10, 10);
+/// ```
+///
+/// [`MessageBuilder::custom_content`]: crate::ui::message::MessageBuilder::custom_content
 pub struct Canvas {
     pub(crate) pixmap: Pixmap,
 }
 
+impl Clone for Canvas {
+    fn clone(&self) -> Self {
+        Self {
+            pixmap: self.pixmap.clone(),
+        }
+    }
+
+    /// Copies the pixels over without allocating if the sizes match.
+    fn clone_from(&mut self, source: &Self) {
+        if (self.width(), self.height()) == (source.width(), source.height()) {
+            self.pixmap.data_mut().copy_from_slice(source.pixmap.data());
+        } else {
+            *self = source.clone();
+        }
+    }
+}
+
 impl Canvas {
+    /// Creates a transparent canvas.
+    ///
+    /// # Panics
+    ///
+    /// If `width` or `height` is zero.
     pub fn new(width: u32, height: u32) -> Self {
         Self {
             pixmap: Pixmap::new(width, height).expect("invalid canvas dimensions"),
@@ -81,13 +128,226 @@ impl Canvas {
             .stroke_path(&path, &paint, &stroke, Transform::identity(), None);
     }
 
+    /// Fills a circle centered on (`cx`, `cy`).
+    pub fn fill_circle(&mut self, cx: f32, cy: f32, radius: f32, color: Rgba) {
+        let Some(path) = PathBuilder::from_circle(cx, cy, radius) else {
+            return;
+        };
+        let mut paint = Paint::default();
+        paint.set_color(color.into());
+        paint.anti_alias = true;
+        self.pixmap.fill_path(
+            &path,
+            &paint,
+            tiny_skia::FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+
+    /// Strokes the outline of a circle centered on (`cx`, `cy`).
+    pub fn stroke_circle(&mut self, cx: f32, cy: f32, radius: f32, color: Rgba, width: f32) {
+        let Some(path) = PathBuilder::from_circle(cx, cy, radius) else {
+            return;
+        };
+        let mut paint = Paint::default();
+        paint.set_color(color.into());
+        paint.anti_alias = true;
+        let stroke = tiny_skia::Stroke {
+            width,
+            ..Default::default()
+        };
+        self.pixmap
+            .stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    }
+
+    /// Fills a band `width` pixels wide with rounded ends along a circle
+    /// centered on (`cx`, `cy`), from `start` radians round through
+    /// `sweep` radians. Angles go clockwise from three o'clock.
+    #[allow(clippy::too_many_arguments)]
+    pub fn fill_arc(
+        &mut self,
+        cx: f32,
+        cy: f32,
+        radius: f32,
+        width: f32,
+        start: f32,
+        sweep: f32,
+        color: Rgba,
+    ) {
+        let Some(path) = arc_path(cx, cy, radius, start, sweep) else {
+            return;
+        };
+        let mut paint = Paint::default();
+        paint.set_color(color.into());
+        paint.anti_alias = true;
+        let stroke = tiny_skia::Stroke {
+            width,
+            line_cap: tiny_skia::LineCap::Round,
+            ..Default::default()
+        };
+        self.pixmap
+            .stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    }
+
+    /// Strokes a straight line from (`x1`, `y1`) to (`x2`, `y2`).
+    #[allow(clippy::too_many_arguments)]
+    pub fn stroke_line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, color: Rgba, width: f32) {
+        let mut pb = PathBuilder::new();
+        pb.move_to(x1, y1);
+        pb.line_to(x2, y2);
+        let Some(path) = pb.finish() else {
+            return;
+        };
+        let mut paint = Paint::default();
+        paint.set_color(color.into());
+        paint.anti_alias = true;
+        let stroke = tiny_skia::Stroke {
+            width,
+            line_cap: tiny_skia::LineCap::Round,
+            ..Default::default()
+        };
+        self.pixmap
+            .stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+    }
+
     /// Draws another canvas onto this one at the given position.
     pub fn draw_canvas(&mut self, other: &Canvas, x: i32, y: i32) {
         self.draw_pixmap(other.pixmap.as_ref(), x, y);
     }
 
+    /// Draws another canvas onto this one at the given position, replacing
+    /// rather than blending with what is inside its rounded rectangle of
+    /// corner `radius`, so a translucent canvas keeps its own opacity.
+    pub(crate) fn replace_rounded(&mut self, other: &Canvas, x: i32, y: i32, radius: f32) {
+        let Some(mut mask) = tiny_skia::Mask::new(other.width(), other.height()) else {
+            return;
+        };
+        let path = rounded_rect_path(
+            0.0,
+            0.0,
+            other.width() as f32,
+            other.height() as f32,
+            radius,
+        );
+        mask.fill_path(
+            &path,
+            tiny_skia::FillRule::Winding,
+            true,
+            Transform::identity(),
+        );
+
+        // Each pixel goes from ours to theirs as far as the mask covers it
+        let (width, other_width) = (self.width() as i32, other.width() as i32);
+        let columns = x.max(0)..(x + other_width).min(width);
+        let rows = y.max(0)..(y + other.height() as i32).min(self.height() as i32);
+        let (dst, src) = (self.pixmap.data_mut(), other.pixmap.data());
+        for row in rows {
+            for column in columns.clone() {
+                let i = (row * width + column) as usize;
+                let j = ((row - y) * other_width + column - x) as usize;
+                let m = mask.data()[j] as u32;
+                for c in 0..4 {
+                    let (d, s) = (dst[i * 4 + c] as u32, src[j * 4 + c] as u32);
+                    dst[i * 4 + c] = ((d * (255 - m) + s * m + 127) / 255) as u8;
+                }
+            }
+        }
+    }
+
+    /// Draws another canvas enlarged by `scale` with its top-left corner at
+    /// (`x`, `y`). Pixels are kept sharp rather than smoothed.
+    pub fn draw_canvas_scaled(&mut self, other: &Canvas, x: f32, y: f32, scale: f32) {
+        let paint = tiny_skia::PixmapPaint {
+            quality: tiny_skia::FilterQuality::Nearest,
+            ..Default::default()
+        };
+        self.pixmap.draw_pixmap(
+            0,
+            0,
+            other.pixmap.as_ref(),
+            &paint,
+            Transform::from_row(scale, 0.0, 0.0, scale, x, y),
+            None,
+        );
+    }
+
+    /// Returns a copy scaled down (never up) to fit within `max_w` x `max_h`,
+    /// averaging source pixels so large images stay smooth.
+    pub fn scaled_to_fit(&self, max_w: u32, max_h: u32) -> Canvas {
+        let (sw, sh) = (self.width(), self.height());
+        let ratio = (max_w as f32 / sw as f32)
+            .min(max_h as f32 / sh as f32)
+            .min(1.0);
+        let dw = ((sw as f32 * ratio).round() as u32).max(1);
+        let dh = ((sh as f32 * ratio).round() as u32).max(1);
+
+        let src = self.pixmap.data();
+        let mut out = Canvas::new(dw, dh);
+        let dst = out.pixmap.data_mut();
+
+        for dy in 0..dh {
+            let y0 = dy * sh / dh;
+            let y1 = ((dy + 1) * sh / dh).max(y0 + 1);
+            for dx in 0..dw {
+                let x0 = dx * sw / dw;
+                let x1 = ((dx + 1) * sw / dw).max(x0 + 1);
+
+                let mut sum = [0u32; 4];
+                for y in y0..y1 {
+                    for x in x0..x1 {
+                        let i = ((y * sw + x) * 4) as usize;
+                        for c in 0..4 {
+                            sum[c] += src[i + c] as u32;
+                        }
+                    }
+                }
+                let count = (x1 - x0) * (y1 - y0);
+                let o = ((dy * dw + dx) * 4) as usize;
+                for c in 0..4 {
+                    dst[o + c] = (sum[c] / count) as u8;
+                }
+            }
+        }
+        out
+    }
+
+    /// Loads an image file scaled to fit within `max_w` x `max_h`. SVG
+    /// images are rendered at that size, so they are sharp at any scale;
+    /// others are only ever scaled down.
+    #[cfg_attr(not(any(feature = "images", feature = "svg")), allow(unused_variables))]
+    pub fn load_image(path: &Path, max_w: u32, max_h: u32) -> Option<Canvas> {
+        #[cfg(feature = "svg")]
+        if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(is_vector_image)
+        {
+            return svg::Svg::load(path)?.rasterize(max_w, max_h);
+        }
+        #[cfg(feature = "images")]
+        if let Some(image) = image::load_image(path) {
+            return Some(image.scaled_to_fit(max_w, max_h));
+        }
+        None
+    }
+
+    /// Draws an image file fitted and centered in the `w` x `h` box at
+    /// (`x`, `y`). Returns false if the file couldn't be loaded.
+    pub fn draw_image(&mut self, path: &Path, x: i32, y: i32, w: u32, h: u32) -> bool {
+        let Some(image) = Canvas::load_image(path, w, h) else {
+            return false;
+        };
+        self.draw_canvas(
+            &image,
+            x + (w - image.width()) as i32 / 2,
+            y + (h - image.height()) as i32 / 2,
+        );
+        true
+    }
+
     /// Draws a pixmap onto this canvas at the given position.
-    pub fn draw_pixmap(&mut self, src: PixmapRef, x: i32, y: i32) {
+    pub(crate) fn draw_pixmap(&mut self, src: PixmapRef, x: i32, y: i32) {
         self.pixmap.draw_pixmap(
             x,
             y,
@@ -100,50 +360,99 @@ impl Canvas {
 
     /// Returns the pixel data as ARGB (for X11/Wayland compatibility).
     /// The returned Vec has premultiplied alpha in ARGB format.
-    pub fn as_argb(&self) -> Vec<u8> {
+    #[cfg_attr(not(feature = "wayland"), allow(dead_code))]
+    pub(crate) fn as_argb(&self) -> Vec<u8> {
+        self.argb_region(Damage::whole(self))
+    }
+
+    /// Returns the pixels of `area` as [`Canvas::as_argb`] does, row after
+    /// row.
+    pub(crate) fn argb_region(&self, area: Damage) -> Vec<u8> {
         let data = self.pixmap.data();
-        let mut argb = Vec::with_capacity(data.len());
+        let stride = self.width() as usize * 4;
+        let mut argb = Vec::with_capacity(area.width as usize * area.height as usize * 4);
 
-        // Convert RGBA to ARGB (premultiplied)
-        for chunk in data.chunks_exact(4) {
-            let r = chunk[0];
-            let g = chunk[1];
-            let b = chunk[2];
-            let a = chunk[3];
-            // ARGB order: B, G, R, A (little-endian u32)
-            argb.push(b);
-            argb.push(g);
-            argb.push(r);
-            argb.push(a);
+        for y in area.y..area.y + area.height {
+            let start = y as usize * stride + area.x as usize * 4;
+            let row = &data[start..start + area.width as usize * 4];
+            // Convert RGBA to ARGB (premultiplied)
+            for chunk in row.chunks_exact(4) {
+                let r = chunk[0];
+                let g = chunk[1];
+                let b = chunk[2];
+                let a = chunk[3];
+                // ARGB order: B, G, R, A (little-endian u32)
+                argb.push(b);
+                argb.push(g);
+                argb.push(r);
+                argb.push(a);
+            }
         }
 
         argb
     }
 
-    /// Fills a dialog background with subtle shadow and border.
-    pub fn fill_dialog_bg(
+    /// Returns the areas where the canvas differs from `previous`: bands
+    /// of changed rows, each narrowed to the columns that changed. A
+    /// canvas of another size differs everywhere.
+    pub(crate) fn damage_since(&self, previous: &Canvas) -> Vec<Damage> {
+        if (self.width(), self.height()) != (previous.width(), previous.height()) {
+            return vec![Damage::whole(self)];
+        }
+
+        let stride = self.width() as usize * 4;
+        let rows = self.pixmap.data().chunks_exact(stride);
+        let old_rows = previous.pixmap.data().chunks_exact(stride);
+        let mut damage: Vec<Damage> = Vec::new();
+        // Whether the last row looked at changed, so extends the last band
+        let mut in_band = false;
+
+        for (y, (row, old)) in rows.zip(old_rows).enumerate() {
+            if row == old {
+                in_band = false;
+                continue;
+            }
+            let pixels = || row.chunks_exact(4).zip(old.chunks_exact(4));
+            let first = pixels().position(|(new, old)| new != old).unwrap_or(0) as u32;
+            let last = pixels().rposition(|(new, old)| new != old).unwrap_or(0) as u32;
+
+            match damage.last_mut() {
+                Some(band) if in_band => {
+                    let end = (band.x + band.width).max(last + 1);
+                    band.x = band.x.min(first);
+                    band.width = end - band.x;
+                    band.height += 1;
+                }
+                _ => {
+                    damage.push(Damage {
+                        x: first,
+                        y: y as u32,
+                        width: last + 1 - first,
+                        height: 1,
+                    })
+                }
+            }
+            in_band = true;
+        }
+
+        // Many small areas cost more to send than one covering them
+        if damage.len() > MAX_DAMAGE_AREAS {
+            let bounds = damage.iter().skip(1).fold(damage[0], |a, b| a.union(*b));
+            damage = vec![bounds];
+        }
+        damage
+    }
+
+    /// Fills a dialog background with a border.
+    pub(crate) fn fill_dialog_bg(
         &mut self,
         width: f32,
         height: f32,
         bg_color: Rgba,
         border_color: Rgba,
-        shadow_color: Rgba,
         radius: f32,
+        border_width: f32,
     ) {
-        let shadow_offset = 3.0;
-        let border_width = 1.0;
-
-        // Draw shadow (slightly smaller to be fully covered by background)
-        self.fill_rounded_rect(
-            shadow_offset,
-            shadow_offset,
-            width - shadow_offset,
-            height - shadow_offset,
-            radius,
-            shadow_color,
-        );
-
-        // Draw main background (covers shadow completely)
         self.fill_rounded_rect(0.0, 0.0, width, height, radius, bg_color);
 
         // Draw border (inset by half border width)
@@ -158,6 +467,244 @@ impl Canvas {
             border_width,
         );
     }
+
+    /// Draws the soft shadow of a rounded rectangle: its shape in `color`,
+    /// blurred by about `blur` pixels to each side.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn fill_shadow(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        radius: f32,
+        blur: f32,
+        color: Rgba,
+    ) {
+        // The blur spreads the shape by up to three box widths
+        let pad = 3 * blur_radius(blur) as i32 + 1;
+        let (left, top) = (x.floor() as i32 - pad, y.floor() as i32 - pad);
+        let Some(mut mask) = tiny_skia::Mask::new(
+            (w.ceil() as i32 + 2 * pad + 1).max(1) as u32,
+            (h.ceil() as i32 + 2 * pad + 1).max(1) as u32,
+        ) else {
+            return;
+        };
+        let path = rounded_rect_path(x, y, w, h, radius);
+        mask.fill_path(
+            &path,
+            tiny_skia::FillRule::Winding,
+            true,
+            Transform::from_translate(-left as f32, -top as f32),
+        );
+        blur_mask(&mut mask, blur);
+        self.fill_mask(&mask, left, top, color);
+    }
+
+    /// Fills a rounded rectangle with a vertical gradient from `top` to
+    /// `bottom`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn fill_rounded_rect_gradient(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        radius: f32,
+        top: Rgba,
+        bottom: Rgba,
+    ) {
+        let Some(shader) = tiny_skia::LinearGradient::new(
+            tiny_skia::Point::from_xy(x, y),
+            tiny_skia::Point::from_xy(x, y + h),
+            vec![
+                tiny_skia::GradientStop::new(0.0, top.into()),
+                tiny_skia::GradientStop::new(1.0, bottom.into()),
+            ],
+            tiny_skia::SpreadMode::Pad,
+            Transform::identity(),
+        ) else {
+            // A zero-height gradient has nothing to show
+            return;
+        };
+        let path = rounded_rect_path(x, y, w, h, radius);
+        let paint = Paint {
+            shader,
+            anti_alias: true,
+            ..Default::default()
+        };
+        self.pixmap.fill_path(
+            &path,
+            &paint,
+            tiny_skia::FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+
+    /// Shades the inside edges of a rounded rectangle as if it were sunk
+    /// into the surface, lit from above: a shadow of `color` falling
+    /// `offset_y` pixels down and blurred by about `blur` pixels.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn fill_inner_shadow(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        radius: f32,
+        blur: f32,
+        offset_y: f32,
+        color: Rgba,
+    ) {
+        // Enough room around the shape for the blur to pull in the
+        // shadow from outside it
+        let pad = 3 * blur_radius(blur) as i32 + offset_y.abs().ceil() as i32 + 1;
+        let (left, top) = (x.floor() as i32 - pad, y.floor() as i32 - pad);
+        let Some(mut mask) = tiny_skia::Mask::new(
+            (w.ceil() as i32 + 2 * pad + 1).max(1) as u32,
+            (h.ceil() as i32 + 2 * pad + 1).max(1) as u32,
+        ) else {
+            return;
+        };
+        let to_mask = Transform::from_translate(-left as f32, -top as f32);
+        let path = rounded_rect_path(x, y, w, h, radius);
+
+        // Everything but the shape moved down casts the shadow...
+        mask.fill_path(
+            &path,
+            tiny_skia::FillRule::Winding,
+            true,
+            to_mask.pre_translate(0.0, offset_y),
+        );
+        mask.invert();
+        blur_mask(&mut mask, blur);
+        // ...which only shows inside the shape
+        mask.intersect_path(&path, tiny_skia::FillRule::Winding, true, to_mask);
+        self.fill_mask(&mask, left, top, color);
+    }
+
+    /// Strokes a ring of `width` pixels just outside a rounded rectangle,
+    /// to show it has keyboard focus.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn stroke_focus_ring(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        radius: f32,
+        width: f32,
+        color: Rgba,
+    ) {
+        let outset = width / 2.0;
+        self.stroke_rounded_rect(
+            x - outset,
+            y - outset,
+            w + width,
+            h + width,
+            radius + outset,
+            color,
+            width,
+        );
+    }
+
+    /// Fills `color` through a mask whose top-left corner lies at (`x`,
+    /// `y`).
+    fn fill_mask(&mut self, mask: &tiny_skia::Mask, x: i32, y: i32, color: Rgba) {
+        let Some(mut layer) = Pixmap::new(mask.width(), mask.height()) else {
+            return;
+        };
+        let mut paint = Paint::default();
+        paint.set_color(color.into());
+        if let Some(rect) = Rect::from_xywh(0.0, 0.0, mask.width() as f32, mask.height() as f32) {
+            layer.fill_rect(rect, &paint, Transform::identity(), Some(mask));
+        }
+        self.draw_pixmap(layer.as_ref(), x, y);
+    }
+}
+
+/// Returns the radius of the box blurs that, applied three times, come
+/// close to a gaussian blur with a standard deviation of half of `blur`.
+fn blur_radius(blur: f32) -> usize {
+    let sigma = blur / 2.0;
+    (((4.0 * sigma * sigma + 1.0).sqrt() - 1.0) / 2.0).round() as usize
+}
+
+/// Blurs a mask by about `blur` pixels in every direction.
+fn blur_mask(mask: &mut tiny_skia::Mask, blur: f32) {
+    let box_radius = blur_radius(blur);
+    if box_radius == 0 {
+        return;
+    }
+    let (width, height) = (mask.width() as usize, mask.height() as usize);
+    let data = mask.data_mut();
+    for _ in 0..3 {
+        box_blur(data, width, height, 1, width, box_radius);
+        box_blur(data, height, width, width, 1, box_radius);
+    }
+}
+
+/// Averages each of `lines` lines of `len` values over a window of
+/// `radius` values to each side. Values in a line are `step` apart, and
+/// lines start `line_step` apart.
+fn box_blur(
+    data: &mut [u8],
+    len: usize,
+    lines: usize,
+    step: usize,
+    line_step: usize,
+    radius: usize,
+) {
+    let window = (2 * radius + 1) as u32;
+    let mut line = vec![0u8; len];
+    for start in (0..lines).map(|n| n * line_step) {
+        for (i, value) in line.iter_mut().enumerate() {
+            *value = data[start + i * step];
+        }
+        // Values past the ends count as 0
+        let mut sum: u32 = line[..radius.min(len)].iter().map(|&v| v as u32).sum();
+        for i in 0..len {
+            if let Some(&entering) = line.get(i + radius) {
+                sum += entering as u32;
+            }
+            data[start + i * step] = ((sum + window / 2) / window) as u8;
+            if i >= radius {
+                sum -= line[i - radius] as u32;
+            }
+        }
+    }
+}
+
+/// Creates the path of an arc, as cubic curves of at most a quarter turn
+/// each.
+fn arc_path(cx: f32, cy: f32, r: f32, start: f32, sweep: f32) -> Option<tiny_skia::Path> {
+    let sweep = sweep.clamp(-std::f32::consts::TAU, std::f32::consts::TAU);
+    let segments = (sweep.abs() / std::f32::consts::FRAC_PI_2).ceil().max(1.0);
+    let step = sweep / segments;
+    // Control points this far along the tangents keep each curve on the
+    // circle
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let point = |angle: f32| (cx + r * angle.cos(), cy + r * angle.sin());
+
+    let mut pb = PathBuilder::new();
+    let (x, y) = point(start);
+    pb.move_to(x, y);
+    for i in 0..segments as u32 {
+        let a0 = start + step * i as f32;
+        let a1 = a0 + step;
+        let (x0, y0) = point(a0);
+        let (x1, y1) = point(a1);
+        pb.cubic_to(
+            x0 - k * r * a0.sin(),
+            y0 + k * r * a0.cos(),
+            x1 + k * r * a1.sin(),
+            y1 - k * r * a1.cos(),
+            x1,
+            y1,
+        );
+    }
+    pb.finish()
 }
 
 /// Creates a rounded rectangle path.
@@ -190,6 +737,66 @@ fn rounded_rect_path(x: f32, y: f32, w: f32, h: f32, r: f32) -> tiny_skia::Path
     pb.finish().unwrap()
 }
 
+/// Returns true if the file name is an image [`Canvas::load_image`] can
+/// load with the enabled features.
+pub(crate) fn is_image(name: &str) -> bool {
+    #[cfg(feature = "images")]
+    if image::is_supported_image(name) {
+        return true;
+    }
+    is_vector_image(name)
+}
+
+/// Returns true if the file name is an SVG image and SVG rendering is
+/// enabled.
+pub(crate) fn is_vector_image(name: &str) -> bool {
+    #[cfg(feature = "svg")]
+    {
+        svg::Svg::is_svg(name)
+    }
+    #[cfg(not(feature = "svg"))]
+    {
+        let _ = name;
+        false
+    }
+}
+
+/// Changed areas beyond which they are sent as one.
+const MAX_DAMAGE_AREAS: usize = 16;
+
+/// A rectangle of a canvas that changed, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Damage {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Damage {
+    /// Covers all of `canvas`.
+    pub fn whole(canvas: &Canvas) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            width: canvas.width(),
+            height: canvas.height(),
+        }
+    }
+
+    /// Returns the smallest rectangle covering both.
+    fn union(self, other: Damage) -> Self {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Self {
+            x,
+            y,
+            width: (self.x + self.width).max(other.x + other.width) - x,
+            height: (self.y + self.height).max(other.y + other.height) - y,
+        }
+    }
+}
+
 /// RGBA color with 8-bit components.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct Rgba {
@@ -218,12 +825,39 @@ impl Rgba {
         }
     }
 
+    /// Parses `#rrggbbaa`, or `#rrggbb`, `#rgb` and `rgb(r,g,b)` as
+    /// opaque colors.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if let Some(hex) = text.strip_prefix('#')
+            && hex.len() == 8
+        {
+            let [r, g, b] = parse_color(text.get(..7)?)?;
+            let a = u8::from_str_radix(hex.get(6..)?, 16).ok()?;
+            return Some(Self::new(r, g, b, a));
+        }
+        let [r, g, b] = parse_color(text)?;
+        Some(Self::rgb(r, g, b))
+    }
+
     pub const fn with_alpha(self, a: u8) -> Self {
         Self {
             a,
             ..self
         }
     }
+
+    /// Returns the color `t` of the way from this one to `other`.
+    pub fn mix(self, other: Rgba, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Self {
+            r: mix(self.r, other.r),
+            g: mix(self.g, other.g),
+            b: mix(self.b, other.b),
+            a: mix(self.a, other.a),
+        }
+    }
 }
 
 impl From<Rgba> for Color {
@@ -0,0 +1,57 @@
+//! SVG rendering through resvg.
+
+use std::path::Path;
+
+use resvg::usvg;
+use tiny_skia::Transform;
+
+use super::Canvas;
+
+/// A parsed SVG document, rendered on demand at any scale.
+pub(crate) struct Svg {
+    tree: usvg::Tree,
+}
+
+impl Svg {
+    /// Returns true if the file name has an SVG extension.
+    pub fn is_svg(name: &str) -> bool {
+        name.rsplit_once('.')
+            .is_some_and(|(_, ext)| ext.eq_ignore_ascii_case("svg"))
+    }
+
+    pub fn load(path: &Path) -> Option<Self> {
+        let data = std::fs::read(path).ok()?;
+        let tree = usvg::Tree::from_data(&data, &usvg::Options::default()).ok()?;
+        Some(Self {
+            tree,
+        })
+    }
+
+    /// The document's natural size in pixels.
+    pub fn size(&self) -> (f32, f32) {
+        let size = self.tree.size();
+        (size.width(), size.height())
+    }
+
+    /// Renders the document scaled by `zoom` with its top-left corner at
+    /// (`x`, `y`). Only the part inside the canvas is rasterized.
+    pub fn render_to(&self, canvas: &mut Canvas, x: f32, y: f32, zoom: f32) {
+        let transform = Transform::from_row(zoom, 0.0, 0.0, zoom, x, y);
+        resvg::render(&self.tree, transform, &mut canvas.pixmap.as_mut());
+    }
+
+    /// Renders the document into a new canvas of at most `width` x
+    /// `height` pixels, keeping its aspect ratio.
+    pub fn rasterize(&self, width: u32, height: u32) -> Option<Canvas> {
+        let (svg_width, svg_height) = self.size();
+        let zoom = (width as f32 / svg_width).min(height as f32 / svg_height);
+        let canvas_width = (svg_width * zoom).round() as u32;
+        let canvas_height = (svg_height * zoom).round() as u32;
+        if canvas_width == 0 || canvas_height == 0 {
+            return None;
+        }
+        let mut canvas = Canvas::new(canvas_width, canvas_height);
+        self.render_to(&mut canvas, 0.0, 0.0, zoom);
+        Some(canvas)
+    }
+}
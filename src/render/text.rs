@@ -1,30 +1,136 @@
-use ab_glyph::{Font as _, Glyph, OutlinedGlyph, PxScaleFont, ScaleFont, point};
-use tiny_skia::Pixmap;
-
-use super::{Canvas, Rgba, rgb};
-
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    cmp::Ordering,
+    collections::HashMap,
+    ops::{Range, RangeInclusive},
+    path::{Path, PathBuf},
+    process::Command,
+    rc::Rc,
+    sync::{Arc, LazyLock, Mutex, OnceLock, PoisonError},
+};
+
+use ab_glyph::{
+    Font as _, FontArc, FontVec, Glyph, GlyphId, PxScale, PxScaleFont, Rect, ScaleFont, point,
+};
+use tiny_skia::{Pixmap, PremultipliedColorU8};
+
+use super::{
+    Canvas, Rgba,
+    markup::{self, Run, Style},
+    rgb,
+};
+
+/// Used when fontconfig can't find a font.
 const FALLBACK_FONT: &[u8] = include_bytes!("../../assets/Cantarell-Regular.ttf");
 
+/// Family chosen with [`set_family`]; `None` uses fontconfig's sans-serif.
+static FAMILY: Mutex<Option<String>> = Mutex::new(None);
+
+/// A font at a size, drawing text through [`Font::render`]. Fonts keep
+/// what they shaped and laid out, so drawing the same text again is cheap.
 pub struct Font {
-    font: PxScaleFont<ab_glyph::FontRef<'static>>,
+    font: PxScaleFont<FontArc>,
+    /// Face index of `font` in its file
+    index: u32,
+    /// Shaped text by font, size and text, as shaping is slow
+    shaped: RefCell<ShapeCache>,
+    /// Laid out short plain text by text and maximum width, as dialogs
+    /// draw the same labels every frame
+    laid: RefCell<LayoutCache>,
+}
+
+type ShapeCache = HashMap<(usize, u32, String), Rc<[ShapedGlyph]>>;
+type LayoutCache = HashMap<(String, u32, Align), Rc<[LaidGlyph]>>;
+
+/// Where lines of text sit across the width of the widest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub(crate) enum Align {
+    #[default]
+    Start,
+    Center,
+    #[allow(dead_code)]
+    End,
+}
+
+impl Align {
+    /// Share of spare width left before the text.
+    pub(crate) fn factor(self) -> f32 {
+        match self {
+            Align::Start => 0.0,
+            Align::Center => 0.5,
+            Align::End => 1.0,
+        }
+    }
+}
+
+/// A parsed font and the index of its face in the file, which shaping
+/// needs to read the file again.
+#[derive(Clone)]
+struct FontFace {
+    font: FontArc,
+    index: u32,
 }
 
-const BASE_FONT_SIZE: f32 = 18.0;
+/// The font picked for a character, scaled for its style.
+struct StyledFace {
+    /// Tells apart the fonts a [`Font`] draws with
+    id: usize,
+    index: u32,
+    scaled: PxScaleFont<FontArc>,
+}
+
+/// A glyph positioned by shaping, in pixels.
+#[derive(Debug, Clone, Copy)]
+struct ShapedGlyph {
+    id: GlyphId,
+    /// Byte offset of the text the glyph shows
+    cluster: usize,
+    x_advance: f32,
+    x_offset: f32,
+    /// Upward shift from the baseline
+    y_offset: f32,
+}
+
+/// Shaped texts kept by a [`Font`] before the cache is emptied.
+const SHAPE_CACHE_SIZE: usize = 512;
+
+/// Laid out texts kept by a [`Font`] before the cache is emptied, and the
+/// longest text kept.
+const LAYOUT_CACHE_SIZE: usize = 256;
+const LAYOUT_CACHE_TEXT_LEN: usize = 128;
+
+/// Glyphs kept rasterized before the cache is emptied.
+const GLYPH_CACHE_SIZE: usize = 4096;
+
+pub(super) const BASE_FONT_SIZE: f32 = 18.0;
+
+/// Slant of synthesized italics, as horizontal shift per pixel of height.
+const ITALIC_SLANT: f32 = 0.2;
 
 impl Font {
-    /// Loads the font with the given scale factor for crisp rendering.
+    /// Loads the UI font with the given scale factor for crisp rendering.
     pub fn load(scale: f32) -> Self {
-        let inner = ab_glyph::FontRef::try_from_slice(FALLBACK_FONT).unwrap();
-        Self {
-            font: inner.into_scaled(BASE_FONT_SIZE * scale),
-        }
+        Self::load_with_size(BASE_FONT_SIZE * scale)
     }
 
-    /// Loads the font with a specific size in pixels (already scaled).
+    /// Loads the UI font with a specific size in pixels (already scaled).
     pub fn load_with_size(size: f32) -> Self {
-        let inner = ab_glyph::FontRef::try_from_slice(FALLBACK_FONT).unwrap();
+        Self::new(ui_font(), size)
+    }
+
+    /// Loads face `index` of a font file with a specific size in pixels.
+    /// Returns `None` if the file can't be read or parsed.
+    pub fn load_file(path: &Path, index: u32, size: f32) -> Option<Self> {
+        Some(Self::new(load_font(path, index)?, size))
+    }
+
+    fn new(face: FontFace, size: f32) -> Self {
         Self {
-            font: inner.into_scaled(size),
+            font: face.font.into_scaled(size),
+            index: face.index,
+            shaped: RefCell::new(HashMap::new()),
+            laid: RefCell::new(HashMap::new()),
         }
     }
 
@@ -32,21 +138,347 @@ impl Font {
     pub fn render<'a>(&'a self, text: &'a str) -> TextRenderer<'a> {
         TextRenderer {
             font: self,
-            text,
+            text: Cow::Borrowed(text),
+            runs: Cow::Borrowed(&[]),
             color: rgb(255, 255, 255),
             max_width: f32::MAX,
+            align: Align::Start,
+        }
+    }
+
+    /// Returns the font for `c` in `style`. Characters the font lacks come
+    /// from a fallback font.
+    fn face(&self, style: &Style, c: char) -> StyledFace {
+        let (mut id, mut font, mut index) = match monospace_font() {
+            Some(mono) if style.monospace => (1, &mono.font, mono.index),
+            _ => (0, &self.font.font, self.index),
+        };
+        if font.glyph_id(c).0 == 0
+            && !c.is_whitespace()
+            && !c.is_control()
+            && c != ZWSP
+            && let Some((position, fallback)) = fallback_font(c)
+        {
+            (id, font, index) = (2 + position, &fallback.font, fallback.index);
+        }
+        StyledFace {
+            id,
+            index,
+            scaled: font.clone().into_scaled(PxScale {
+                x: self.font.scale.x * style.scale,
+                y: self.font.scale.y * style.scale,
+            }),
+        }
+    }
+
+    /// Shapes `text`, which must be in one font and style.
+    fn shape(&self, face: &StyledFace, text: &str) -> Rc<[ShapedGlyph]> {
+        let key = (face.id, face.scaled.scale.y.to_bits(), text.to_string());
+        if let Some(shaped) = self.shaped.borrow().get(&key) {
+            return shaped.clone();
+        }
+        let shaped: Rc<[ShapedGlyph]> = shape(face, text).into();
+        let mut cache = self.shaped.borrow_mut();
+        if cache.len() >= SHAPE_CACHE_SIZE {
+            cache.clear();
         }
+        cache.insert(key, shaped.clone());
+        shaped
     }
 }
 
+/// Shapes `text` with HarfBuzz's rules, so ligatures form and complex
+/// scripts are drawn right.
+#[cfg(feature = "shaping")]
+fn shape(face: &StyledFace, text: &str) -> Vec<ShapedGlyph> {
+    let font = &face.scaled.font;
+    let Some(shaper) = rustybuzz::Face::from_slice(font.font_data(), face.index) else {
+        return shape_simple(face, text);
+    };
+    let mut buffer = rustybuzz::UnicodeBuffer::new();
+    buffer.push_str(text);
+    buffer.guess_segment_properties();
+    let shaped = rustybuzz::shape(&shaper, &[], buffer);
+
+    let (h_scale, v_scale) = (face.scaled.h_scale_factor(), face.scaled.v_scale_factor());
+    shaped
+        .glyph_infos()
+        .iter()
+        .zip(shaped.glyph_positions())
+        .map(|(info, position)| {
+            ShapedGlyph {
+                id: GlyphId(info.glyph_id as u16),
+                cluster: info.cluster as usize,
+                x_advance: position.x_advance as f32 * h_scale,
+                x_offset: position.x_offset as f32 * h_scale,
+                y_offset: position.y_offset as f32 * v_scale,
+            }
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "shaping"))]
+fn shape(face: &StyledFace, text: &str) -> Vec<ShapedGlyph> {
+    shape_simple(face, text)
+}
+
+/// Places a glyph for each character, with kerning.
+fn shape_simple(face: &StyledFace, text: &str) -> Vec<ShapedGlyph> {
+    let mut glyphs: Vec<ShapedGlyph> = Vec::new();
+    for (cluster, c) in text.char_indices() {
+        let id = face.scaled.glyph_id(c);
+        if let Some(last) = glyphs.last_mut() {
+            last.x_advance += face.scaled.kern(last.id, id);
+        }
+        glyphs.push(ShapedGlyph {
+            id,
+            cluster,
+            x_advance: face.scaled.h_advance(id),
+            x_offset: 0.0,
+            y_offset: 0.0,
+        });
+    }
+    glyphs
+}
+
+/// Sets the font family text is drawn in from now on, or goes back to
+/// fontconfig's sans-serif font with `None`.
+pub(crate) fn set_family(family: Option<String>) {
+    *FAMILY.lock().unwrap_or_else(PoisonError::into_inner) = family;
+}
+
+/// Returns the font for the chosen family, loading it when the family
+/// changes. Without fontconfig the bundled font is used.
+fn ui_font() -> FontFace {
+    static LOADED: Mutex<Option<(Option<String>, FontFace)>> = Mutex::new(None);
+    let family = FAMILY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    let mut loaded = LOADED.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((loaded_family, font)) = &*loaded
+        && *loaded_family == family
+    {
+        return font.clone();
+    }
+    let font = match_font(family.as_deref().unwrap_or("sans-serif")).unwrap_or_else(|| {
+        FontFace {
+            font: FontArc::try_from_slice(FALLBACK_FONT).unwrap(),
+            index: 0,
+        }
+    });
+    *loaded = Some((family, font.clone()));
+    font
+}
+
+/// Returns fontconfig's monospace font, loaded on first use.
+fn monospace_font() -> Option<&'static FontFace> {
+    static MONOSPACE: OnceLock<Option<FontFace>> = OnceLock::new();
+    MONOSPACE.get_or_init(|| match_font("monospace")).as_ref()
+}
+
+/// Loads the font fontconfig picks for `pattern`.
+fn match_font(pattern: &str) -> Option<FontFace> {
+    let output = Command::new("fc-match")
+        .args(["--format", "%{file}\n%{index}", pattern])
+        .output()
+        .ok()?;
+    let output = String::from_utf8(output.stdout).ok()?;
+    let (file, index) = output.split_once('\n')?;
+    load_font(Path::new(file), index.parse().unwrap_or(0))
+}
+
+fn load_font(path: &Path, index: u32) -> Option<FontFace> {
+    let data = std::fs::read(path).ok()?;
+    let font = FontVec::try_from_vec_and_index(data, index).ok()?;
+    Some(FontFace {
+        font: FontArc::new(font),
+        index,
+    })
+}
+
+/// A font fontconfig suggests for characters the main font lacks.
+struct Fallback {
+    path: PathBuf,
+    index: u32,
+    /// Characters the font has
+    charset: Vec<RangeInclusive<u32>>,
+    /// The font, loaded when first needed
+    font: OnceLock<Option<FontFace>>,
+}
+
+/// Returns the first fallback font that has `c`, and its position in the
+/// fallback chain.
+fn fallback_font(c: char) -> Option<(usize, &'static FontFace)> {
+    static FALLBACKS: OnceLock<Vec<Fallback>> = OnceLock::new();
+    let fallbacks = FALLBACKS.get_or_init(|| {
+        // fontconfig's fonts for sans-serif, best first, with the
+        // characters each covers; covering scripts such as CJK, Cyrillic
+        // and Arabic is what the list is sorted for
+        let Ok(output) = Command::new("fc-match")
+            .args([
+                "-s",
+                "--format",
+                "%{file}|%{index}|%{charset}\n",
+                "sans-serif",
+            ])
+            .output()
+        else {
+            return Vec::new();
+        };
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, '|');
+                let path = PathBuf::from(fields.next()?);
+                let index = fields.next()?.parse().ok()?;
+                let charset = fields
+                    .next()?
+                    .split_whitespace()
+                    .filter_map(|range| {
+                        let (start, end) = range.split_once('-').unwrap_or((range, range));
+                        Some(
+                            u32::from_str_radix(start, 16).ok()?
+                                ..=u32::from_str_radix(end, 16).ok()?,
+                        )
+                    })
+                    .collect();
+                Some(Fallback {
+                    path,
+                    index,
+                    charset,
+                    font: OnceLock::new(),
+                })
+            })
+            .collect()
+    });
+
+    let c = c as u32;
+    fallbacks.iter().enumerate().find_map(|(i, fallback)| {
+        let covered = fallback
+            .charset
+            .binary_search_by(|range| {
+                if *range.end() < c {
+                    Ordering::Less
+                } else if *range.start() > c {
+                    Ordering::Greater
+                } else {
+                    Ordering::Equal
+                }
+            })
+            .is_ok();
+        if !covered {
+            return None;
+        }
+        let font = fallback
+            .font
+            .get_or_init(|| load_font(&fallback.path, fallback.index))
+            .as_ref()?;
+        Some((i, font))
+    })
+}
+
+/// Text being set up for drawing with a [`Font`], drawn by
+/// [`finish`](Self::finish).
 pub struct TextRenderer<'a> {
     font: &'a Font,
-    text: &'a str,
+    text: Cow<'a, str>,
+    /// Styles for parts of the text; the rest is drawn plainly
+    runs: Cow<'a, [Run]>,
     color: Rgba,
     max_width: f32,
+    align: Align,
+}
+
+/// A glyph placed by [`TextRenderer::layout`], with its style.
+struct LaidGlyph {
+    glyph: Glyph,
+    /// The font the glyph is from, scaled, and its face index
+    face: PxScaleFont<FontArc>,
+    index: u32,
+    /// `None` for glyphs with nothing to draw, such as spaces
+    bitmap: Option<Arc<GlyphBitmap>>,
+    style: Style,
+    x: f32,
+    /// Shift up from the baseline, for marks placed by shaping
+    rise: f32,
+    advance: f32,
+    baseline: f32,
+    /// Font size in pixels, for synthesized styles
+    size: f32,
+    /// Whether the next glyph is on the same line, to underline spaces
+    /// between words but not at the end of a line
+    joined: bool,
+}
+
+impl LaidGlyph {
+    /// Horizontal shift for synthesized bold.
+    fn bold_offset(&self) -> f32 {
+        if self.style.bold {
+            bold_offset(self.size)
+        } else {
+            0.0
+        }
+    }
+
+    /// Areas of the underline and strikethrough.
+    fn decorations(&self) -> impl Iterator<Item = Rect> {
+        let thickness = (self.size / 16.0).max(1.0).round();
+        let underline = self.style.underline.then_some(self.baseline + thickness);
+        let strikethrough = self
+            .style
+            .strikethrough
+            .then_some(self.baseline - (self.size * 0.3).round());
+        [underline, strikethrough]
+            .into_iter()
+            .flatten()
+            .filter(|_| self.bitmap.is_some() || self.joined)
+            .map(move |y| {
+                Rect {
+                    min: point(self.x, y),
+                    max: point(self.x + self.advance, y + thickness),
+                }
+            })
+    }
+
+    /// Returns the area the glyph draws on, if any.
+    fn bounds(&self) -> Option<Rect> {
+        let mut bounds = self.bitmap.as_ref().map(|bitmap| {
+            let x = self.glyph.position.x + bitmap.left as f32;
+            let y = self.glyph.position.y + bitmap.top as f32;
+            let mut bounds = Rect {
+                min: point(x, y),
+                max: point(x + bitmap.width as f32, y + bitmap.height as f32),
+            };
+            bounds.max.x += self.bold_offset();
+            if self.style.italic {
+                bounds.max.x += ((self.baseline - bounds.min.y) * ITALIC_SLANT).max(0.0);
+                bounds.min.x += ((self.baseline - bounds.max.y) * ITALIC_SLANT).min(0.0);
+            }
+            bounds
+        });
+        for decoration in self.decorations() {
+            bounds = Some(bounds.map_or(decoration, |bounds| union(bounds, decoration)));
+        }
+        bounds
+    }
+}
+
+fn union(mut sum: Rect, next: Rect) -> Rect {
+    sum.min.x = f32::min(sum.min.x, next.min.x);
+    sum.min.y = f32::min(sum.min.y, next.min.y);
+    sum.max.x = f32::max(sum.max.x, next.max.x);
+    sum.max.y = f32::max(sum.max.y, next.max.y);
+    sum
+}
+
+/// Returns the area covered by all glyphs.
+fn bounds(glyphs: &[LaidGlyph]) -> Option<Rect> {
+    glyphs.iter().filter_map(LaidGlyph::bounds).reduce(union)
 }
 
 impl<'a> TextRenderer<'a> {
+    /// Draws the text in `color`, white if not given.
     pub fn with_color(self, color: Rgba) -> Self {
         Self {
             color,
@@ -54,6 +486,7 @@ impl<'a> TextRenderer<'a> {
         }
     }
 
+    /// Wraps the text onto lines no wider than `max_width` pixels.
     pub fn with_max_width(self, max_width: f32) -> Self {
         Self {
             max_width,
@@ -61,25 +494,47 @@ impl<'a> TextRenderer<'a> {
         }
     }
 
+    /// Lines up the lines of the text with each other.
+    pub(crate) fn with_align(self, align: Align) -> Self {
+        Self {
+            align,
+            ..self
+        }
+    }
+
+    /// Reads the text as Pango-style markup. Text that isn't valid markup
+    /// is shown as is.
+    pub fn with_markup(self, markup: bool) -> Self {
+        if !markup || !self.text.contains(['<', '&']) {
+            return self;
+        }
+        match markup::parse(&self.text) {
+            Some(styled) => {
+                Self {
+                    text: Cow::Owned(styled.text),
+                    runs: Cow::Owned(styled.runs),
+                    ..self
+                }
+            }
+            None => self,
+        }
+    }
+
+    /// Styles the text with runs from already parsed markup.
+    pub(crate) fn with_runs(self, runs: &'a [Run]) -> Self {
+        Self {
+            runs: Cow::Borrowed(runs),
+            ..self
+        }
+    }
+
     /// Renders the text and returns a Canvas containing it.
     pub fn finish(self) -> Canvas {
         let glyphs = self.layout();
 
-        if glyphs.is_empty() {
+        let Some(bounds) = bounds(&glyphs) else {
             return Canvas::new(1, 1);
-        }
-
-        let bounds = glyphs
-            .iter()
-            .map(|g| g.px_bounds())
-            .reduce(|mut sum, next| {
-                sum.min.x = f32::min(sum.min.x, next.min.x);
-                sum.min.y = f32::min(sum.min.y, next.min.y);
-                sum.max.x = f32::max(sum.max.x, next.max.x);
-                sum.max.y = f32::max(sum.max.y, next.max.y);
-                sum
-            })
-            .unwrap_or_default();
+        };
 
         // Add padding to avoid clipping
         let width = (bounds.width().ceil() as u32 + 2).max(1);
@@ -92,53 +547,61 @@ impl<'a> TextRenderer<'a> {
         let base_x = -bounds.min.x.floor() as i32 + 1;
         let base_y = -bounds.min.y.floor() as i32 + 1;
 
-        for g in glyphs {
-            let glyph_bounds = g.px_bounds();
-            // Use floor for proper pixel alignment
-            let gx = glyph_bounds.min.x.floor() as i32 + base_x;
-            let gy = glyph_bounds.min.y.floor() as i32 + base_y;
-
-            g.draw(|x, y, c| {
-                let px = gx + x as i32;
-                let py = gy + y as i32;
-
-                if px >= 0 && py >= 0 && (px as u32) < width && (py as u32) < height {
-                    let idx = (py as u32 * width + px as u32) as usize;
-                    if let Some(pix) = pixels.get_mut(idx) {
-                        // Premultiplied alpha blending
-                        let a = (c * 255.0).round() as u8;
-                        if a > 0 {
-                            let r = (self.color.r as u32 * a as u32 / 255) as u8;
-                            let g = (self.color.g as u32 * a as u32 / 255) as u8;
-                            let b = (self.color.b as u32 * a as u32 / 255) as u8;
-
-                            // Blend with existing pixel (SrcOver)
-                            let existing = *pix;
-                            if existing.alpha() == 0 {
-                                *pix =
-                                    tiny_skia::PremultipliedColorU8::from_rgba(r, g, b, a).unwrap();
-                            } else {
-                                // Alpha composite
-                                let ea = existing.alpha() as u32;
-                                let er = existing.red() as u32;
-                                let eg = existing.green() as u32;
-                                let eb = existing.blue() as u32;
-
-                                let inv_a = 255 - a as u32;
-                                let out_a = (a as u32 + ea * inv_a / 255).min(255) as u8;
-                                let out_r = (r as u32 + er * inv_a / 255).min(255) as u8;
-                                let out_g = (g as u32 + eg * inv_a / 255).min(255) as u8;
-                                let out_b = (b as u32 + eb * inv_a / 255).min(255) as u8;
-
-                                *pix = tiny_skia::PremultipliedColorU8::from_rgba(
-                                    out_r, out_g, out_b, out_a,
-                                )
-                                .unwrap();
-                            }
+        let mut plot = |px: i32, py: i32, coverage: f32, color: Rgba| {
+            if px < 0 || py < 0 || px as u32 >= width || py as u32 >= height {
+                return;
+            }
+            let idx = (py as u32 * width + px as u32) as usize;
+            if let Some(pix) = pixels.get_mut(idx) {
+                blend(pix, coverage, color);
+            }
+        };
+
+        for g in glyphs.iter() {
+            let color = g.style.color.unwrap_or(self.color);
+
+            for decoration in g.decorations() {
+                let x0 = decoration.min.x.round() as i32 + base_x;
+                let x1 = decoration.max.x.round() as i32 + base_x;
+                let y0 = decoration.min.y.round() as i32 + base_y;
+                let y1 = decoration.max.y.round() as i32 + base_y;
+                for py in y0..y1 {
+                    for px in x0..x1 {
+                        plot(px, py, 1.0, color);
+                    }
+                }
+            }
+
+            let Some(bitmap) = &g.bitmap else {
+                continue;
+            };
+            // Glyphs sit on whole pixels
+            let gx = g.glyph.position.x as i32 + bitmap.left + base_x;
+            let gy = g.glyph.position.y as i32 + bitmap.top + base_y;
+            let baseline = g.baseline.round() as i32 + base_y;
+
+            // Bold is synthesized by drawing the glyph again a little to
+            // the right, italics by slanting rows above the baseline
+            let offsets: &[i32] = if g.style.bold {
+                &[0, g.bold_offset() as i32]
+            } else {
+                &[0]
+            };
+            for &offset in offsets {
+                let rows = bitmap.coverage.chunks_exact(bitmap.width as usize);
+                for (py, row) in (gy..).zip(rows) {
+                    let slant = if g.style.italic {
+                        ((baseline - py) as f32 * ITALIC_SLANT).round() as i32
+                    } else {
+                        0
+                    };
+                    for (px, &c) in (gx + offset + slant..).zip(row) {
+                        if c > 0 {
+                            plot(px, py, c as f32 / 255.0, color);
                         }
                     }
                 }
-            });
+            }
         }
 
         Canvas {
@@ -148,71 +611,326 @@ impl<'a> TextRenderer<'a> {
 
     /// Computes the size of the rendered text without actually rendering it.
     pub fn measure(&self) -> (f32, f32) {
-        let glyphs = self.layout();
+        let bounds = bounds(&self.layout()).unwrap_or_default();
+        (bounds.width(), bounds.height())
+    }
+
+    /// Returns the x offset, advance width and baseline of the `n`th character
+    /// on the first line, in the coordinates of the canvas produced by `finish`.
+    pub(crate) fn char_span(&self, n: usize) -> Option<(f32, f32, f32)> {
+        let bounds = bounds(&self.layout())?;
+
+        let line = self.text.lines().next()?;
+        let (index, _) = line.char_indices().nth(n)?;
+
+        // Advance of the glyphs for each cluster of characters
+        let mut clusters: Vec<(usize, f32)> = Vec::new();
+        for (range, _, face) in self.segments(line, 0) {
+            for shaped in self.font.shape(&face, &line[range.clone()]).iter() {
+                clusters.push((range.start + shaped.cluster, shaped.x_advance));
+            }
+        }
+        clusters.sort_by_key(|&(start, _)| start);
+        clusters.dedup_by(|next, first| {
+            let same = next.0 == first.0;
+            if same {
+                first.1 += next.1;
+            }
+            same
+        });
+
+        // Characters drawn as one glyph, such as a ligature, share it evenly
+        let origin_x = -bounds.min.x.floor() + 1.0;
+        let origin_y = -bounds.min.y.floor() + 1.0;
+        let mut x: f32 = 0.0;
+        for (i, &(start, advance)) in clusters.iter().enumerate() {
+            let end = clusters.get(i + 1).map_or(line.len(), |&(next, _)| next);
+            let chars = &line[start..end];
+            let share = advance / chars.chars().count().max(1) as f32;
+            for (offset, _) in chars.char_indices() {
+                if start + offset == index {
+                    return Some((x.round() + origin_x, share, origin_y));
+                }
+                x += share;
+            }
+        }
+        None
+    }
 
-        let bounds = glyphs
+    /// Splits a line starting at byte `line_start` of the text into parts
+    /// drawn in one font and style.
+    fn segments(&self, line: &str, line_start: usize) -> Vec<(Range<usize>, Style, StyledFace)> {
+        let mut segments: Vec<(Range<usize>, Style, StyledFace)> = Vec::new();
+        for (i, c) in line.char_indices() {
+            let style = self.style_at(line_start + i);
+            let face = self.font.face(&style, c);
+            let end = i + c.len_utf8();
+            match segments.last_mut() {
+                Some((range, last_style, last_face))
+                    if *last_style == style && last_face.id == face.id =>
+                {
+                    range.end = end;
+                }
+                _ => segments.push((i..end, style, face)),
+            }
+        }
+        segments
+    }
+
+    /// Returns the style of the text at byte `index`.
+    fn style_at(&self, index: usize) -> Style {
+        self.runs
             .iter()
-            .map(|g| g.px_bounds())
-            .reduce(|mut sum, next| {
-                sum.min.x = f32::min(sum.min.x, next.min.x);
-                sum.min.y = f32::min(sum.min.y, next.min.y);
-                sum.max.x = f32::max(sum.max.x, next.max.x);
-                sum.max.y = f32::max(sum.max.y, next.max.y);
-                sum
-            })
-            .unwrap_or_default();
+            .find(|run| run.range.contains(&index))
+            .map_or_else(Style::default, |run| run.style)
+    }
 
-        (bounds.width(), bounds.height())
+    /// Lays out the text, reusing the layout of short plain text drawn
+    /// before.
+    fn layout(&self) -> Rc<[LaidGlyph]> {
+        if !self.runs.is_empty() || self.text.len() > LAYOUT_CACHE_TEXT_LEN {
+            return self.lay_out().into();
+        }
+        let key = (self.text.to_string(), self.max_width.to_bits(), self.align);
+        if let Some(laid) = self.font.laid.borrow().get(&key) {
+            return laid.clone();
+        }
+        let laid: Rc<[LaidGlyph]> = self.lay_out().into();
+        let mut cache = self.font.laid.borrow_mut();
+        if cache.len() >= LAYOUT_CACHE_SIZE {
+            cache.clear();
+        }
+        cache.insert(key, laid.clone());
+        laid
     }
 
     /// Performs text layout with soft wrapping.
-    fn layout(&self) -> Vec<OutlinedGlyph> {
-        let mut glyphs: Vec<Glyph> = Vec::new();
+    fn lay_out(&self) -> Vec<LaidGlyph> {
+        // Glyphs are first placed on lines, then the lines are stacked once
+        // their tallest text is known
+        let mut glyphs: Vec<LaidGlyph> = Vec::new();
+        let mut glyph_lines: Vec<usize> = Vec::new();
+        let base = &self.font.font;
+        let base_metrics = (base.ascent(), base.descent(), base.line_gap());
+        // Ascent, descent and line gap of each line
+        let mut lines: Vec<(f32, f32, f32)> = Vec::new();
 
-        let mut y: f32 = 0.0;
         for line in self.text.lines() {
+            let line_start = line.as_ptr() as usize - self.text.as_ptr() as usize;
+            lines.push(base_metrics);
             let mut x: f32 = 0.0;
             let mut last_softbreak: Option<usize> = None;
-            let mut last = None;
-
-            for c in line.chars() {
-                let mut glyph = self.font.font.scaled_glyph(c);
-                if let Some(last) = last {
-                    x += self.font.font.kern(last, glyph.id);
-                }
-                // Round positions to pixel boundaries for crisp text
-                glyph.position = point(x.round(), y.round());
-                last = Some(glyph.id);
 
-                x += self.font.font.h_advance(glyph.id);
-
-                if c == ' ' || c == ZWSP {
-                    last_softbreak = Some(glyphs.len());
-                } else {
-                    glyphs.push(glyph);
-
-                    if x > self.max_width {
-                        if let Some(i) = last_softbreak {
-                            // Soft line break
-                            y += self.font.font.height() + self.font.font.line_gap();
-                            let x_diff = glyphs.get(i).map(|g| g.position.x).unwrap_or(0.0);
-                            for glyph in &mut glyphs[i..] {
-                                glyph.position.x -= x_diff;
-                                glyph.position.y = y;
-                            }
-                            x -= x_diff;
-                            last_softbreak = None;
+            // Shaping also kerns, within each part
+            for (range, style, face) in self.segments(line, line_start) {
+                let segment = &line[range.clone()];
+                let size = face.scaled.scale.y;
+                for shaped in self.font.shape(&face, segment).iter() {
+                    let c = segment[shaped.cluster..].chars().next().unwrap_or(' ');
+                    // Round positions to pixel boundaries for crisp text
+                    let glyph = shaped.id.with_scale_and_position(
+                        face.scaled.scale,
+                        point((x + shaped.x_offset).round(), 0.0),
+                    );
+                    let advance =
+                        shaped.x_advance + if style.bold { bold_offset(size) } else { 0.0 };
+                    x += advance;
+
+                    let line_idx = lines.len() - 1;
+                    let metrics = &mut lines[line_idx];
+                    metrics.0 = metrics.0.max(face.scaled.ascent());
+                    metrics.1 = metrics.1.min(face.scaled.descent());
+                    metrics.2 = metrics.2.max(face.scaled.line_gap());
+
+                    if c == ZWSP {
+                        last_softbreak = Some(glyphs.len());
+                        continue;
+                    }
+                    glyphs.push(LaidGlyph {
+                        face: face.scaled.clone(),
+                        index: face.index,
+                        // Looked up once the baseline is known
+                        bitmap: None,
+                        style,
+                        x: glyph.position.x,
+                        rise: shaped.y_offset.round(),
+                        advance,
+                        baseline: 0.0,
+                        size,
+                        joined: false,
+                        glyph,
+                    });
+                    glyph_lines.push(line_idx);
+
+                    if c == ' ' {
+                        last_softbreak = Some(glyphs.len());
+                    } else if x > self.max_width
+                        && let Some(i) = last_softbreak
+                    {
+                        // Soft line break
+                        lines.push(base_metrics);
+                        let x_diff = glyphs.get(i).map(|g| g.x).unwrap_or(0.0);
+                        for glyph in &mut glyphs[i..] {
+                            glyph.x -= x_diff;
                         }
+                        for line in &mut glyph_lines[i..] {
+                            *line = lines.len() - 1;
+                        }
+                        x -= x_diff;
+                        last_softbreak = None;
                     }
                 }
             }
-            y += self.font.font.height() + self.font.font.line_gap();
         }
 
+        // Baselines follow each other by the lower line's ascent and the
+        // upper line's descent
+        let mut baselines = Vec::with_capacity(lines.len());
+        let mut y: f32 = 0.0;
+        for (i, &(ascent, _, line_gap)) in lines.iter().enumerate() {
+            if let Some(&(_, prev_descent, prev_gap)) = i.checked_sub(1).map(|i| &lines[i]) {
+                y += -prev_descent + prev_gap.max(line_gap) + ascent;
+            }
+            baselines.push(y.round());
+        }
+
+        for (i, glyph) in glyphs.iter_mut().enumerate() {
+            let line = glyph_lines[i];
+            glyph.baseline = baselines[line];
+            glyph.joined = glyph_lines.get(i + 1) == Some(&line);
+            glyph.glyph.position = point(glyph.x, glyph.baseline - glyph.rise);
+            glyph.bitmap = glyph_bitmap(&glyph.face, glyph.index, glyph.glyph.id);
+        }
+        if self.align != Align::Start {
+            align_lines(&mut glyphs, &glyph_lines, lines.len(), self.align);
+        }
         glyphs
-            .into_iter()
-            .filter_map(|g| self.font.font.outline_glyph(g))
-            .collect()
+    }
+}
+
+/// Moves the glyphs on each line across by a share of the space the line
+/// leaves beside the widest one. Spaces at the end of a line don't count.
+fn align_lines(glyphs: &mut [LaidGlyph], glyph_lines: &[usize], line_count: usize, align: Align) {
+    let mut widths = vec![0.0f32; line_count];
+    for (glyph, &line) in glyphs.iter().zip(glyph_lines) {
+        if glyph.bitmap.is_some() {
+            widths[line] = widths[line].max(glyph.x + glyph.advance);
+        }
+    }
+    let widest = widths.iter().copied().fold(0.0, f32::max);
+    for (glyph, &line) in glyphs.iter_mut().zip(glyph_lines) {
+        let shift = ((widest - widths[line]) * align.factor()).round();
+        glyph.x += shift;
+        glyph.glyph.position.x += shift;
+    }
+}
+
+/// A glyph's coverage, from 0 to 255 per pixel.
+struct GlyphBitmap {
+    /// Offset of the top-left pixel from the glyph's origin
+    left: i32,
+    top: i32,
+    width: u32,
+    height: u32,
+    coverage: Vec<u8>,
+}
+
+type GlyphKey = (usize, u32, GlyphId, u32, u32);
+
+/// Glyphs rasterized by font data address, face index, glyph and size.
+/// Fonts with glyphs in the cache are kept, so their addresses aren't
+/// reused by others.
+#[derive(Default)]
+struct GlyphCache {
+    glyphs: HashMap<GlyphKey, Option<Arc<GlyphBitmap>>>,
+    fonts: HashMap<usize, FontArc>,
+}
+
+/// Returns glyph `id` of a face rasterized, or `None` if it has no
+/// outline. Glyphs are drawn once for all text in the process, as that is
+/// the slow part of rendering it.
+fn glyph_bitmap(face: &PxScaleFont<FontArc>, index: u32, id: GlyphId) -> Option<Arc<GlyphBitmap>> {
+    static CACHE: LazyLock<Mutex<GlyphCache>> = LazyLock::new(Mutex::default);
+
+    let font = face.font.font_data().as_ptr() as usize;
+    let key = (
+        font,
+        index,
+        id,
+        face.scale.x.to_bits(),
+        face.scale.y.to_bits(),
+    );
+    let mut cache = CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(bitmap) = cache.glyphs.get(&key) {
+        return bitmap.clone();
+    }
+    if cache.glyphs.len() >= GLYPH_CACHE_SIZE {
+        *cache = GlyphCache::default();
+    }
+    cache.fonts.entry(font).or_insert_with(|| face.font.clone());
+
+    let bitmap = rasterize(face, id).map(Arc::new);
+    cache.glyphs.insert(key, bitmap.clone());
+    bitmap
+}
+
+/// Draws the outline of glyph `id` placed at the origin.
+fn rasterize(face: &PxScaleFont<FontArc>, id: GlyphId) -> Option<GlyphBitmap> {
+    let outline = face.outline_glyph(id.with_scale(face.scale))?;
+    let bounds = outline.px_bounds();
+    let (width, height) = (bounds.width() as u32, bounds.height() as u32);
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let mut coverage = vec![0; (width * height) as usize];
+    outline.draw(|x, y, c| {
+        if let Some(pixel) = coverage.get_mut((y * width + x) as usize) {
+            *pixel = (c * 255.0).round() as u8;
+        }
+    });
+    Some(GlyphBitmap {
+        left: bounds.min.x as i32,
+        top: bounds.min.y as i32,
+        width,
+        height,
+        coverage,
+    })
+}
+
+/// Horizontal shift for synthesized bold at a font size.
+fn bold_offset(size: f32) -> f32 {
+    (size / 24.0).max(1.0).round()
+}
+
+/// Blends `color` at `coverage` over a pixel.
+fn blend(pix: &mut PremultipliedColorU8, coverage: f32, color: Rgba) {
+    // Premultiplied alpha blending
+    let a = (coverage * 255.0).round() as u8;
+    if a == 0 {
+        return;
+    }
+    let r = (color.r as u32 * a as u32 / 255) as u8;
+    let g = (color.g as u32 * a as u32 / 255) as u8;
+    let b = (color.b as u32 * a as u32 / 255) as u8;
+
+    // Blend with existing pixel (SrcOver)
+    let existing = *pix;
+    if existing.alpha() == 0 {
+        *pix = PremultipliedColorU8::from_rgba(r, g, b, a).unwrap();
+    } else {
+        // Alpha composite
+        let ea = existing.alpha() as u32;
+        let er = existing.red() as u32;
+        let eg = existing.green() as u32;
+        let eb = existing.blue() as u32;
+
+        let inv_a = 255 - a as u32;
+        let out_a = (a as u32 + ea * inv_a / 255).min(255) as u8;
+        let out_r = (r as u32 + er * inv_a / 255).min(255) as u8;
+        let out_g = (g as u32 + eg * inv_a / 255).min(255) as u8;
+        let out_b = (b as u32 + eb * inv_a / 255).min(255) as u8;
+
+        *pix = PremultipliedColorU8::from_rgba(out_r, out_g, out_b, out_a).unwrap();
     }
 }
 
@@ -0,0 +1,321 @@
+//! Terminal fallback for when there is no display server.
+//!
+//! Message, entry, list and progress dialogs can draw themselves as text on
+//! the controlling terminal, so scripts keep working over SSH. The terminal
+//! is opened directly, leaving stdin and stdout to the script.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read, Write},
+    mem::MaybeUninit,
+    os::fd::AsRawFd,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use crate::error::{Backend, Error};
+
+pub(crate) const BOLD: &str = "\x1b[1m";
+pub(crate) const REVERSE: &str = "\x1b[7m";
+pub(crate) const RESET: &str = "\x1b[0m";
+
+static FORCE: AtomicBool = AtomicBool::new(false);
+
+/// Shows supported dialogs in the terminal even when a display server is
+/// available.
+pub fn force_tui(force: bool) {
+    FORCE.store(force, Ordering::Relaxed);
+}
+
+/// Whether dialogs should use the terminal: when forced, or when the
+/// backends find no display server, unless they are shown headless.
+pub(crate) fn enabled() -> bool {
+    if crate::backend::headless::active() {
+        return false;
+    }
+    FORCE.load(Ordering::Relaxed) || !crate::backend::display_available()
+}
+
+/// A key read from the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Key {
+    Enter,
+    Escape,
+    /// Ctrl+C or Ctrl+D
+    Interrupt,
+    Tab,
+    BackTab,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Backspace,
+    Delete,
+    Char(char),
+}
+
+/// The error for a terminal that can't be used, as the last resort
+/// for showing a dialog.
+fn unavailable(e: io::Error) -> Error {
+    Error::BackendUnavailable {
+        tried: vec![(Backend::Terminal, e.into())],
+    }
+}
+
+/// The controlling terminal in raw mode, showing the alternate screen.
+/// Both are restored when dropped.
+pub(crate) struct Terminal {
+    tty: File,
+    saved: libc::termios,
+    /// Bytes read but not yet parsed into keys
+    input: Vec<u8>,
+}
+
+impl Terminal {
+    /// Opens the controlling terminal. Without one there is nothing to show
+    /// a dialog on.
+    pub fn open() -> Result<Self, Error> {
+        let tty = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")
+            .map_err(unavailable)?;
+        let fd = tty.as_raw_fd();
+
+        let mut saved = MaybeUninit::uninit();
+        if unsafe { libc::tcgetattr(fd, saved.as_mut_ptr()) } != 0 {
+            return Err(unavailable(io::Error::last_os_error()));
+        }
+        let saved = unsafe { saved.assume_init() };
+
+        // Keys arrive one by one and unechoed; Ctrl+C is read as a key so
+        // the terminal is always restored
+        let mut raw = saved;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG | libc::IEXTEN);
+        raw.c_iflag &= !(libc::IXON | libc::ICRNL);
+        raw.c_cc[libc::VMIN] = 1;
+        raw.c_cc[libc::VTIME] = 0;
+        if unsafe { libc::tcsetattr(fd, libc::TCSAFLUSH, &raw) } != 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let mut terminal = Self {
+            tty,
+            saved,
+            input: Vec::new(),
+        };
+        terminal.tty.write_all(b"\x1b[?1049h\x1b[?25l")?;
+        Ok(terminal)
+    }
+
+    /// Returns the space for dialog lines, in columns and rows: the whole
+    /// terminal less a margin column on each side.
+    pub fn size(&self) -> (usize, usize) {
+        let mut size = MaybeUninit::<libc::winsize>::zeroed();
+        let ok = unsafe { libc::ioctl(self.tty.as_raw_fd(), libc::TIOCGWINSZ, size.as_mut_ptr()) };
+        let size = unsafe { size.assume_init() };
+        let (cols, rows) = if ok == 0 && size.ws_col > 0 && size.ws_row > 0 {
+            (size.ws_col.into(), size.ws_row.into())
+        } else {
+            (80, 24)
+        };
+        (usize::max(cols, 4) - 2, rows)
+    }
+
+    /// Replaces the screen contents with the given lines, which must already
+    /// fit the width from [`Terminal::size`].
+    pub fn present(&mut self, lines: &[String]) -> Result<(), Error> {
+        let mut out = String::from("\x1b[H");
+        for line in lines {
+            out.push(' ');
+            out.push_str(line);
+            out.push_str(RESET);
+            out.push_str("\x1b[K\n");
+        }
+        out.push_str("\x1b[J");
+        self.tty.write_all(out.as_bytes())?;
+        self.tty.flush()?;
+        Ok(())
+    }
+
+    /// Waits for a key, up to `timeout` if given.
+    pub fn read_key(&mut self, timeout: Option<Duration>) -> Result<Option<Key>, Error> {
+        loop {
+            if let Some(key) = self.next_key() {
+                return Ok(Some(key));
+            }
+
+            let mut fds = [libc::pollfd {
+                fd: self.tty.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            }];
+            let timeout_ms = timeout.map_or(-1, |timeout| timeout.as_millis() as i32);
+            match unsafe { libc::poll(fds.as_mut_ptr(), 1, timeout_ms) } {
+                0 => return Ok(None),
+                n if n < 0 => {
+                    let err = io::Error::last_os_error();
+                    if err.kind() == io::ErrorKind::Interrupted {
+                        continue;
+                    }
+                    return Err(err.into());
+                }
+                _ => {}
+            }
+
+            let mut buf = [0; 64];
+            let n = self.tty.read(&mut buf)?;
+            if n == 0 {
+                return Ok(Some(Key::Interrupt));
+            }
+            self.input.extend_from_slice(&buf[..n]);
+        }
+    }
+
+    /// Takes the next key from the read bytes, skipping any we don't know.
+    fn next_key(&mut self) -> Option<Key> {
+        while !self.input.is_empty() {
+            let (key, len) = parse_key(&self.input);
+            self.input.drain(..len);
+            if key.is_some() {
+                return key;
+            }
+        }
+        None
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = self.tty.write_all(b"\x1b[?25h\x1b[?1049l");
+        let _ = self.tty.flush();
+        unsafe { libc::tcsetattr(self.tty.as_raw_fd(), libc::TCSAFLUSH, &self.saved) };
+    }
+}
+
+/// Parses the key at the start of `input`, returning it (if known) and the
+/// number of bytes it took.
+fn parse_key(input: &[u8]) -> (Option<Key>, usize) {
+    match input[0] {
+        0x1b => {
+            match input.get(1) {
+                Some(b'[' | b'O') => parse_escape_sequence(input),
+                // A lone Escape, or Alt with a key, which is taken as Escape
+                _ => (Some(Key::Escape), 1),
+            }
+        }
+        b'\r' | b'\n' => (Some(Key::Enter), 1),
+        b'\t' => (Some(Key::Tab), 1),
+        0x7f | 0x08 => (Some(Key::Backspace), 1),
+        0x03 | 0x04 => (Some(Key::Interrupt), 1),
+        byte if byte < 0x20 => (None, 1),
+        byte => {
+            let len = match byte {
+                0xf0.. => 4,
+                0xe0.. => 3,
+                0xc0.. => 2,
+                _ => 1,
+            }
+            .min(input.len());
+            let key = std::str::from_utf8(&input[..len])
+                .ok()
+                .and_then(|s| s.chars().next())
+                .map(Key::Char);
+            (key, len)
+        }
+    }
+}
+
+/// Parses a `CSI` or `SS3` sequence such as `ESC [ A` or `ESC [ 5 ~`.
+fn parse_escape_sequence(input: &[u8]) -> (Option<Key>, usize) {
+    let Some(end) = input[2..]
+        .iter()
+        .position(|byte| (0x40..=0x7e).contains(byte))
+    else {
+        return (None, input.len());
+    };
+    let len = end + 3;
+    let params = std::str::from_utf8(&input[2..len - 1]).unwrap_or("");
+    let key = match (input[len - 1], params.split(';').next().unwrap_or("")) {
+        (b'A', _) => Some(Key::Up),
+        (b'B', _) => Some(Key::Down),
+        (b'C', _) => Some(Key::Right),
+        (b'D', _) => Some(Key::Left),
+        (b'H', _) | (b'~', "1" | "7") => Some(Key::Home),
+        (b'F', _) | (b'~', "4" | "8") => Some(Key::End),
+        (b'Z', _) => Some(Key::BackTab),
+        (b'~', "3") => Some(Key::Delete),
+        (b'~', "5") => Some(Key::PageUp),
+        (b'~', "6") => Some(Key::PageDown),
+        _ => None,
+    };
+    (key, len)
+}
+
+/// Cuts `text` to at most `width` characters, ending with an ellipsis when
+/// shortened.
+pub(crate) fn fit(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut fitted: String = text.chars().take(width.saturating_sub(1)).collect();
+    if width > 0 {
+        fitted.push('…');
+    }
+    fitted
+}
+
+/// Wraps `text` at word boundaries to lines of at most `width` characters.
+pub(crate) fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let line_len = line.chars().count();
+            if line_len > 0 && line_len + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+            // Words longer than a line are broken up
+            while line.chars().count() > width {
+                let rest = line.chars().skip(width).collect();
+                line = line.chars().take(width).collect();
+                lines.push(std::mem::replace(&mut line, rest));
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Returns the lines starting a dialog: the title, bold, over a rule.
+pub(crate) fn header(title: &str, width: usize) -> Vec<String> {
+    if title.is_empty() {
+        return Vec::new();
+    }
+    vec![format!("{BOLD}{}", fit(title, width)), "─".repeat(width)]
+}
+
+/// Returns a row of buttons, the focused one highlighted.
+pub(crate) fn buttons(labels: &[String], focused: Option<usize>) -> String {
+    labels
+        .iter()
+        .enumerate()
+        .map(|(idx, label)| {
+            if Some(idx) == focused {
+                format!("{REVERSE}< {label} >{RESET}")
+            } else {
+                format!("< {label} >")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("  ")
+}
@@ -0,0 +1,68 @@
+//! The accent color chosen for the desktop.
+//!
+//! The settings portal knows it on any desktop that implements it; GNOME's
+//! and KDE's own settings are read when it doesn't.
+
+use std::{fs, process::Command};
+
+use crate::{
+    render::{Rgba, rgb},
+    ui::appearance,
+};
+
+/// Colors of GNOME's named accents, as Adwaita draws them.
+const GNOME_ACCENTS: [(&str, Rgba); 9] = [
+    ("blue", rgb(0x35, 0x84, 0xe4)),
+    ("teal", rgb(0x21, 0x90, 0xa4)),
+    ("green", rgb(0x3a, 0x94, 0x4a)),
+    ("yellow", rgb(0xc8, 0x88, 0x00)),
+    ("orange", rgb(0xed, 0x5b, 0x00)),
+    ("red", rgb(0xe6, 0x2d, 0x42)),
+    ("pink", rgb(0xd5, 0x61, 0x99)),
+    ("purple", rgb(0x91, 0x41, 0xac)),
+    ("slate", rgb(0x6f, 0x83, 0x96)),
+];
+
+/// Returns the accent color the settings portal reports, as it is now.
+pub(crate) fn from_portal() -> Option<Rgba> {
+    let [r, g, b] = appearance::current()?.accent_color?;
+    let channel = |v: f64| (v * 255.0).round() as u8;
+    Some(rgb(channel(r), channel(g), channel(b)))
+}
+
+/// Returns the accent color set in GNOME's or KDE's configuration, for
+/// when there is no portal to ask.
+pub(crate) fn from_config() -> Option<Rgba> {
+    from_gsettings().or_else(from_kdeglobals)
+}
+
+/// GNOME 47 and later store one of a few named accents.
+fn from_gsettings() -> Option<Rgba> {
+    let output = Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "accent-color"])
+        .output()
+        .ok()?;
+    let name = String::from_utf8_lossy(&output.stdout);
+    let name = name.trim().trim_matches('\'');
+    GNOME_ACCENTS
+        .iter()
+        .find(|(accent, _)| *accent == name)
+        .map(|&(_, color)| color)
+}
+
+/// KDE keeps the accent as `AccentColor=r,g,b` in the `[General]` group
+/// of `kdeglobals`.
+fn from_kdeglobals() -> Option<Rgba> {
+    let text = fs::read_to_string(dirs::config_dir()?.join("kdeglobals")).ok()?;
+    let mut in_general = false;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_general = line == "[General]";
+        } else if in_general && let Some(value) = line.strip_prefix("AccentColor=") {
+            let mut channels = value.split(',').map(|c| c.trim().parse::<u8>().ok());
+            let (r, g, b) = (channels.next()??, channels.next()??, channels.next()??);
+            return Some(rgb(r, g, b));
+        }
+    }
+    None
+}
@@ -0,0 +1,96 @@
+//! Time-based transitions for fades, hover effects and other motion.
+//!
+//! Widgets sample a [`Transition`] while drawing; one still under way asks
+//! for another frame, which the window turns into a `RedrawRequested` when
+//! the display is ready for it. Dialogs thus animate by redrawing as they
+//! always do, without keeping timers of their own.
+
+use std::{
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// How long a transition takes.
+pub(crate) const DURATION: Duration = Duration::from_millis(150);
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+static FRAME_WANTED: AtomicBool = AtomicBool::new(false);
+
+/// Turns transitions on or off. Even when on, they are skipped if the
+/// desktop asks for reduced motion.
+pub(crate) fn set_enabled(enabled: bool) {
+    DISABLED.store(!enabled, Ordering::Relaxed);
+}
+
+/// Whether transitions play rather than jumping to their end.
+pub(crate) fn enabled() -> bool {
+    !DISABLED.load(Ordering::Relaxed) && !reduced_motion()
+}
+
+/// Whether the desktop has animations turned off. GNOME's setting is the
+/// one other desktops' settings daemons keep in sync with.
+fn reduced_motion() -> bool {
+    static REDUCED: OnceLock<bool> = OnceLock::new();
+    *REDUCED.get_or_init(|| {
+        std::process::Command::new("gsettings")
+            .args(["get", "org.gnome.desktop.interface", "enable-animations"])
+            .output()
+            .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "false")
+    })
+}
+
+/// Asks for another frame after the one being drawn.
+pub(crate) fn request_frame() {
+    FRAME_WANTED.store(true, Ordering::Relaxed);
+}
+
+/// Returns whether something drawn since the last call wants another
+/// frame.
+pub(crate) fn take_frame_request() -> bool {
+    FRAME_WANTED.swap(false, Ordering::Relaxed)
+}
+
+/// A value easing towards a target over [`DURATION`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Transition {
+    from: f32,
+    to: f32,
+    start: Instant,
+}
+
+impl Transition {
+    /// Creates a transition resting at `value`.
+    pub fn new(value: f32) -> Self {
+        Self {
+            from: value,
+            to: value,
+            start: Instant::now() - DURATION,
+        }
+    }
+
+    /// Starts easing from the current value towards `target`.
+    pub fn set(&mut self, target: f32) {
+        if target == self.to {
+            return;
+        }
+        self.from = if enabled() { self.value() } else { target };
+        self.to = target;
+        self.start = Instant::now();
+    }
+
+    /// Returns the current value, asking for another frame if it is still
+    /// changing.
+    pub fn value(&self) -> f32 {
+        let t = self.start.elapsed().as_secs_f32() / DURATION.as_secs_f32();
+        if t >= 1.0 || self.from == self.to {
+            return self.to;
+        }
+        request_frame();
+        // Ease out: fast at first, settling gently
+        let eased = 1.0 - (1.0 - t).powi(3);
+        self.from + (self.to - self.from) * eased
+    }
+}
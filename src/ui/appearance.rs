@@ -0,0 +1,51 @@
+//! The desktop's appearance preferences, kept current as the user changes
+//! them.
+//!
+//! A thread reads them from the settings portal and then follows its
+//! `SettingChanged` signal, so dialogs shown later in a long-running
+//! program pick up a switch between light and dark. The first read is
+//! waited for only briefly: a portal that is slow to start must not hold
+//! up the dialog.
+
+use std::{
+    sync::{Mutex, OnceLock, PoisonError, mpsc},
+    thread,
+    time::Duration,
+};
+
+use crate::dbus::{
+    Connection,
+    portal::{self, Appearance},
+};
+
+/// How long to wait for the portal's first answer.
+const TIMEOUT: Duration = Duration::from_millis(300);
+
+static CURRENT: Mutex<Option<Appearance>> = Mutex::new(None);
+
+/// Returns the latest preferences, or `None` without a settings portal.
+pub(crate) fn current() -> Option<Appearance> {
+    static STARTED: OnceLock<()> = OnceLock::new();
+    STARTED.get_or_init(|| {
+        let (ready, first) = mpsc::channel();
+        let watcher = thread::Builder::new()
+            .name("appearance".into())
+            .spawn(move || watch(ready));
+        if watcher.is_ok() {
+            let _ = first.recv_timeout(TIMEOUT);
+        }
+    });
+    *CURRENT.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Follows the portal's settings until the connection fails, signalling
+/// `ready` once the first read is in or has failed.
+fn watch(ready: mpsc::Sender<()>) {
+    if let Ok(mut conn) = Connection::session() {
+        let _ = portal::watch_appearance(&mut conn, |appearance| {
+            *CURRENT.lock().unwrap_or_else(PoisonError::into_inner) = Some(appearance);
+            let _ = ready.send(());
+        });
+    }
+    let _ = ready.send(());
+}
@@ -0,0 +1,131 @@
+//! User bookmarks for the file dialog's PLACES section.
+//!
+//! Bookmarks added from the dialog are stored in `~/.config/zenity-rs/bookmarks`.
+//! GTK's `~/.config/gtk-3.0/bookmarks` is read as well but never written. Both
+//! files use GTK's format: one `file://` URI per line, optionally followed by
+//! a space and a label.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A bookmarked directory.
+pub(crate) struct Bookmark {
+    pub path: PathBuf,
+    pub label: Option<String>,
+    /// True for our own bookmarks; GTK's are read-only.
+    pub removable: bool,
+}
+
+/// Loads our bookmarks followed by GTK's, skipping duplicates and
+/// directories that no longer exist.
+pub(crate) fn load() -> Vec<Bookmark> {
+    let own = own_file().map(|f| read_file(&f)).unwrap_or_default();
+    let gtk = dirs::config_dir()
+        .map(|d| read_file(&d.join("gtk-3.0/bookmarks")))
+        .unwrap_or_default();
+
+    let mut bookmarks: Vec<Bookmark> = Vec::new();
+    let sources = own
+        .into_iter()
+        .map(|b| (b, true))
+        .chain(gtk.into_iter().map(|b| (b, false)));
+    for ((path, label), removable) in sources {
+        if path.is_dir() && !bookmarks.iter().any(|b| b.path == path) {
+            bookmarks.push(Bookmark {
+                path,
+                label,
+                removable,
+            });
+        }
+    }
+    bookmarks
+}
+
+/// Appends a directory to our bookmarks file.
+pub(crate) fn add(path: &Path) -> io::Result<()> {
+    let file = own_file().ok_or_else(|| io::Error::other("no config directory"))?;
+    let mut lines = read_lines(&file);
+    lines.push(path_to_uri(path));
+    write_lines(&file, &lines)
+}
+
+/// Removes a directory from our bookmarks file.
+pub(crate) fn remove(path: &Path) -> io::Result<()> {
+    let file = own_file().ok_or_else(|| io::Error::other("no config directory"))?;
+    let lines: Vec<String> = read_lines(&file)
+        .into_iter()
+        .filter(|line| parse_line(line).is_none_or(|(p, _)| p != path))
+        .collect();
+    write_lines(&file, &lines)
+}
+
+/// Converts a `file://` URI to a path, decoding percent escapes.
+pub(crate) fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = encoded.get(i + 1..i + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            decoded.push(byte);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Some(PathBuf::from(String::from_utf8(decoded).ok()?))
+}
+
+/// Converts a path to a `file://` URI, escaping everything but unreserved
+/// characters and slashes.
+pub(crate) fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for &byte in path.to_string_lossy().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+fn own_file() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("zenity-rs/bookmarks"))
+}
+
+fn read_file(file: &Path) -> Vec<(PathBuf, Option<String>)> {
+    read_lines(file)
+        .iter()
+        .filter_map(|line| parse_line(line))
+        .collect()
+}
+
+fn parse_line(line: &str) -> Option<(PathBuf, Option<String>)> {
+    let (uri, label) = match line.split_once(' ') {
+        Some((uri, label)) => (uri, Some(label.trim().to_string())),
+        None => (line.trim(), None),
+    };
+    Some((uri_to_path(uri)?, label.filter(|l| !l.is_empty())))
+}
+
+fn read_lines(file: &Path) -> Vec<String> {
+    fs::read_to_string(file)
+        .map(|text| text.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn write_lines(file: &Path, lines: &[String]) -> io::Result<()> {
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    fs::write(file, text)
+}
@@ -1,23 +1,28 @@
 //! Calendar date picker dialog implementation.
 
 use crate::{
-    backend::{MouseButton, Window, WindowEvent, create_window},
+    backend::{MouseButton, Window, WindowEvent},
     error::Error,
     render::{Canvas, Font, Rgba, rgb},
     ui::{
-        Colors,
-        widgets::{Widget, button::Button},
+        Theme, WindowPlacement, create_window,
+        strings::tr,
+        widgets::{
+            Widget, button::Button, combo::Combo, focus::FocusManager, spin_button::SpinButton,
+        },
     },
 };
 
 const BASE_PADDING: u32 = 16;
-const BASE_CELL_SIZE: u32 = 36;
+const BASE_CELL_SIZE: u32 = 40;
 const BASE_HEADER_HEIGHT: u32 = 40;
 const BASE_DAY_HEADER_HEIGHT: u32 = 28;
-const BASE_DROPDOWN_ITEM_HEIGHT: u32 = 24;
+/// Last year the year field goes up to
+const MAX_YEAR: i32 = 9999;
 
 /// Calendar dialog result.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CalendarResult {
     /// User selected a date.
     Selected { year: u32, month: u32, day: u32 },
@@ -51,13 +56,6 @@ impl CalendarResult {
     }
 }
 
-#[derive(Clone, Copy, PartialEq)]
-enum DropdownState {
-    None,
-    Month,
-    Year,
-}
-
 /// Calendar dialog builder.
 pub struct CalendarBuilder {
     title: String,
@@ -67,7 +65,8 @@ pub struct CalendarBuilder {
     day: Option<u32>,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    colors: Option<Theme>,
+    pub(super) placing: WindowPlacement,
 }
 
 impl CalendarBuilder {
@@ -81,6 +80,7 @@ impl CalendarBuilder {
             width: None,
             height: None,
             colors: None,
+            placing: WindowPlacement::default(),
         }
     }
 
@@ -102,18 +102,18 @@ impl CalendarBuilder {
 
     /// Set initial month (1-12).
     pub fn month(mut self, month: u32) -> Self {
-        self.month = Some(month.clamp(1, 12));
+        self.month = Some(month);
         self
     }
 
-    /// Set initial day (1-31).
+    /// Set initial day, from 1 to the last of the month.
     pub fn day(mut self, day: u32) -> Self {
-        self.day = Some(day.clamp(1, 31));
+        self.day = Some(day);
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
         self
     }
 
@@ -127,14 +127,43 @@ impl CalendarBuilder {
         self
     }
 
+    /// Checks the date given, as `show` does before showing the dialog.
+    /// The year and month not given are the current ones.
+    pub fn validate(&self) -> Result<(), Error> {
+        let now = current_date();
+        let year = self.year.unwrap_or(now.0);
+        let month = self.month.unwrap_or(now.1);
+        if !(1..=MAX_YEAR as u32).contains(&year) {
+            return Err(Error::InvalidConfig(format!(
+                "year {year} is not between 1 and {MAX_YEAR}"
+            )));
+        }
+        if !(1..=12).contains(&month) {
+            return Err(Error::InvalidConfig(format!(
+                "month {month} is not between 1 and 12"
+            )));
+        }
+        if let Some(day) = self.day {
+            let last = days_in_month(year, month);
+            if !(1..=last).contains(&day) {
+                return Err(Error::InvalidConfig(format!(
+                    "day {day} is not between 1 and {last}, the last of {} {year}",
+                    month_name(month)
+                )));
+            }
+        }
+        Ok(())
+    }
+
     pub fn show(self) -> Result<CalendarResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+        self.validate()?;
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
 
         // Calculate logical dimensions at scale 1.0
         let logical_grid_width = BASE_CELL_SIZE * 7;
         let logical_text_height = if self.text.is_empty() { 0 } else { 24 };
-        let calc_width = logical_grid_width + BASE_PADDING * 2;
-        let calc_height = BASE_PADDING * 2
+        let calc_width = logical_grid_width + colors.space(BASE_PADDING) * 2;
+        let calc_height = colors.space(BASE_PADDING) * 2
             + logical_text_height
             + BASE_HEADER_HEIGHT
             + BASE_DAY_HEADER_HEIGHT
@@ -146,21 +175,26 @@ impl CalendarBuilder {
         let logical_height = self.height.unwrap_or(calc_height);
 
         // Create window with LOGICAL dimensions
-        let mut window = create_window(logical_width as u16, logical_height as u16)?;
-        window.set_title(if self.title.is_empty() {
-            "Calendar selection"
+        let mut window = create_window(
+            logical_width as u16,
+            logical_height as u16,
+            colors,
+            &self.placing,
+        )?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Calendar selection")
         } else {
-            &self.title
+            self.title.as_str().into()
         })?;
 
         // Get the actual scale factor from the window (compositor scale)
         let scale = window.scale_factor();
 
         // Now create everything at PHYSICAL scale
-        let font = Font::load(scale);
+        let font = colors.font(scale);
 
         // Scale dimensions for physical rendering
-        let padding = (BASE_PADDING as f32 * scale) as u32;
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
         let cell_size = (BASE_CELL_SIZE as f32 * scale) as u32;
         let header_height = (BASE_HEADER_HEIGHT as f32 * scale) as u32;
         let day_header_height = (BASE_DAY_HEADER_HEIGHT as f32 * scale) as u32;
@@ -187,8 +221,8 @@ impl CalendarBuilder {
         let mut selected_day = self.day.unwrap_or(now.2);
 
         // Create buttons at physical scale
-        let mut ok_button = Button::new("OK", &font, scale);
-        let mut cancel_button = Button::new("Cancel", &font, scale);
+        let mut ok_button = Button::new(&tr("OK"), &font, colors, scale);
+        let mut cancel_button = Button::new(&tr("Cancel"), &font, colors, scale);
 
         // Layout in physical coordinates
         let mut y = padding as i32;
@@ -200,7 +234,7 @@ impl CalendarBuilder {
         let calendar_x = padding as i32;
         let calendar_y = y;
 
-        let button_y = (height - padding - (32.0 * scale) as u32) as i32;
+        let button_y = (height - padding - (colors.control_height as f32 * scale) as u32) as i32;
         let mut bx = width as i32 - padding as i32;
         bx -= cancel_button.width() as i32;
         cancel_button.set_position(bx, button_y);
@@ -212,9 +246,21 @@ impl CalendarBuilder {
         let mut mouse_x = 0i32;
         let mut mouse_y = 0i32;
         let mut hovered_day: Option<u32> = None;
-        let mut dropdown = DropdownState::None;
-        let mut dropdown_hover: Option<usize> = None;
-        let mut year_scroll_offset: i32 = 0;
+
+        // Month picker and year field in the header
+        let months = (1..=12).map(|m| tr(month_name(m)).to_string()).collect();
+        let mut month_combo = Combo::new(months, (month - 1) as usize, &font, colors, scale)
+            .with_flat()
+            .with_visible_rows(12);
+        let mut year_spin = SpinButton::new(1, MAX_YEAR, year as i32, &font, scale);
+        layout_header(
+            &mut month_combo,
+            &mut year_spin,
+            calendar_x,
+            calendar_y,
+            header_height,
+            scale,
+        );
 
         // Initial draw
         draw_calendar(
@@ -230,9 +276,8 @@ impl CalendarBuilder {
             month,
             selected_day,
             hovered_day,
-            dropdown,
-            dropdown_hover,
-            year_scroll_offset,
+            &month_combo,
+            &year_spin,
             &ok_button,
             &cancel_button,
             scale,
@@ -242,92 +287,74 @@ impl CalendarBuilder {
 
         let grid_y = calendar_y + header_height as i32 + day_header_height as i32;
 
+        let mut focus = FocusManager::new();
         loop {
-            let event = window.wait_for_event()?;
+            let mut event = window.wait_for_event()?;
             let mut needs_redraw = false;
 
+            // An open picker takes every event until it closes
+            let picking = month_combo.is_open();
+            if !picking {
+                event = focus.process_event(
+                    event,
+                    &mut [
+                        &mut month_combo,
+                        &mut year_spin,
+                        &mut ok_button,
+                        &mut cancel_button,
+                    ],
+                );
+                needs_redraw |= year_spin.process_event(&event);
+            }
+            needs_redraw |= month_combo.process_event(&event);
+            let picking = picking || month_combo.is_open();
+            // Keys edit the year while its field has focus
+            let typing = year_spin.has_focus();
+
             match &event {
                 WindowEvent::CloseRequested => return Ok(CalendarResult::Closed),
                 WindowEvent::RedrawRequested => needs_redraw = true,
+                _ if picking => {}
                 WindowEvent::CursorMove(pos) => {
                     mouse_x = pos.x as i32;
                     mouse_y = pos.y as i32;
 
-                    // Handle dropdown hover
-                    if dropdown != DropdownState::None {
-                        let old_hover = dropdown_hover;
-                        dropdown_hover = get_dropdown_hover(
-                            dropdown, mouse_x, mouse_y, calendar_x, calendar_y, scale,
-                        );
-                        if old_hover != dropdown_hover {
-                            needs_redraw = true;
-                        }
-                    } else {
-                        // Handle day hover
-                        let old_hovered = hovered_day;
-                        hovered_day = None;
-
-                        if mouse_x >= calendar_x
-                            && mouse_x < calendar_x + grid_width as i32
-                            && mouse_y >= grid_y
-                            && mouse_y < grid_y + (cell_size * 6) as i32
-                        {
-                            let col = (mouse_x - calendar_x) / cell_size as i32;
-                            let row = (mouse_y - grid_y) / cell_size as i32;
-                            let cell_idx = row * 7 + col;
-
-                            let first_day = first_day_of_month(year, month);
-                            let days_in = days_in_month(year, month);
-
-                            let day = cell_idx - first_day as i32 + 1;
-                            if day >= 1 && day <= days_in as i32 {
-                                hovered_day = Some(day as u32);
-                            }
+                    // Handle day hover
+                    let old_hovered = hovered_day;
+                    hovered_day = None;
+
+                    if mouse_x >= calendar_x
+                        && mouse_x < calendar_x + grid_width as i32
+                        && mouse_y >= grid_y
+                        && mouse_y < grid_y + (cell_size * 6) as i32
+                    {
+                        let col = (mouse_x - calendar_x) / cell_size as i32;
+                        let row = (mouse_y - grid_y) / cell_size as i32;
+                        let cell_idx = row * 7 + col;
+
+                        let first_day = first_day_of_month(year, month);
+                        let days_in = days_in_month(year, month);
+
+                        let day = cell_idx - first_day as i32 + 1;
+                        if day >= 1 && day <= days_in as i32 {
+                            hovered_day = Some(day as u32);
                         }
+                    }
 
-                        if old_hovered != hovered_day {
-                            needs_redraw = true;
-                        }
+                    if old_hovered != hovered_day {
+                        needs_redraw = true;
                     }
                 }
                 WindowEvent::ButtonPress(MouseButton::Left, _) => {
                     let header_y = calendar_y;
 
-                    // Handle dropdown selection
-                    if dropdown != DropdownState::None {
-                        if let Some(idx) = dropdown_hover {
-                            match dropdown {
-                                DropdownState::Month => {
-                                    month = idx as u32 + 1;
-                                    selected_day = selected_day.min(days_in_month(year, month));
-                                }
-                                DropdownState::Year => {
-                                    let base_year = year as i32 - 5 + year_scroll_offset;
-                                    year = (base_year + idx as i32).max(1) as u32;
-                                    selected_day = selected_day.min(days_in_month(year, month));
-                                }
-                                DropdownState::None => {}
-                            }
-                        }
-                        dropdown = DropdownState::None;
-                        dropdown_hover = None;
-                        needs_redraw = true;
-                    }
                     // Check header clicks
-                    else if mouse_y >= header_y && mouse_y < header_y + header_height as i32 {
-                        // Calculate actual positions based on text widths
-                        let month_name = month_name(month);
-                        let month_text_width = font.render(month_name).finish().width() as i32;
-                        let year_str = year.to_string();
-                        let year_text_width = font.render(&year_str).finish().width() as i32;
-
-                        let prev_arrow_end = calendar_x + 28;
-                        let month_x = calendar_x + 35;
-                        let month_end = month_x + month_text_width;
-                        let year_x = month_x + month_text_width + 8;
-                        let year_end = year_x + year_text_width;
-                        let today_x = calendar_x + grid_width as i32 - 70;
-                        let next_arrow_start = calendar_x + grid_width as i32 - 24;
+                    if mouse_y >= header_y && mouse_y < header_y + header_height as i32 {
+                        let today_width = font.render(&tr("Today")).measure().0 as i32;
+                        let prev_arrow_end = calendar_x + (28.0 * scale) as i32;
+                        let next_arrow_start =
+                            calendar_x + grid_width as i32 - (24.0 * scale) as i32;
+                        let today_x = next_arrow_start - today_width - (8.0 * scale) as i32;
 
                         // Check in order from left to right
                         if mouse_x < prev_arrow_end {
@@ -340,17 +367,6 @@ impl CalendarBuilder {
                             }
                             selected_day = selected_day.min(days_in_month(year, month));
                             needs_redraw = true;
-                        } else if mouse_x >= month_x && mouse_x < month_end + 5 {
-                            // Month click
-                            dropdown = DropdownState::Month;
-                            dropdown_hover = Some((month - 1) as usize);
-                            needs_redraw = true;
-                        } else if mouse_x >= year_x && mouse_x < year_end + 5 {
-                            // Year click
-                            dropdown = DropdownState::Year;
-                            dropdown_hover = Some(5); // Current year is at index 5
-                            year_scroll_offset = 0;
-                            needs_redraw = true;
                         } else if mouse_x >= today_x && mouse_x < next_arrow_start {
                             // Today click
                             let today = current_date();
@@ -376,21 +392,6 @@ impl CalendarBuilder {
                         needs_redraw = true;
                     }
                 }
-                WindowEvent::Scroll(dir) => {
-                    if dropdown == DropdownState::Year {
-                        match dir {
-                            crate::backend::ScrollDirection::Up => {
-                                year_scroll_offset -= 1;
-                                needs_redraw = true;
-                            }
-                            crate::backend::ScrollDirection::Down => {
-                                year_scroll_offset += 1;
-                                needs_redraw = true;
-                            }
-                            _ => {}
-                        }
-                    }
-                }
                 WindowEvent::KeyPress(key_event) => {
                     const KEY_LEFT: u32 = 0xff51;
                     const KEY_RIGHT: u32 = 0xff53;
@@ -399,143 +400,86 @@ impl CalendarBuilder {
                     const KEY_RETURN: u32 = 0xff0d;
                     const KEY_ESCAPE: u32 = 0xff1b;
 
-                    // Handle dropdown keyboard navigation
-                    if dropdown != DropdownState::None {
-                        let max_items = match dropdown {
-                            DropdownState::Month => 12,
-                            DropdownState::Year => 11,
-                            DropdownState::None => 0,
-                        };
-
-                        match key_event.keysym {
-                            KEY_ESCAPE => {
-                                dropdown = DropdownState::None;
-                                dropdown_hover = None;
-                                needs_redraw = true;
-                            }
-                            KEY_UP => {
-                                let current = dropdown_hover.unwrap_or(0);
-                                if current > 0 {
-                                    dropdown_hover = Some(current - 1);
-                                } else if dropdown == DropdownState::Year {
-                                    // Scroll up for year dropdown
-                                    year_scroll_offset -= 1;
-                                }
-                                needs_redraw = true;
-                            }
-                            KEY_DOWN => {
-                                let current = dropdown_hover.unwrap_or(0);
-                                if current + 1 < max_items {
-                                    dropdown_hover = Some(current + 1);
-                                } else if dropdown == DropdownState::Year {
-                                    // Scroll down for year dropdown
-                                    year_scroll_offset += 1;
-                                }
-                                needs_redraw = true;
-                            }
-                            KEY_RETURN => {
-                                if let Some(idx) = dropdown_hover {
-                                    match dropdown {
-                                        DropdownState::Month => {
-                                            month = idx as u32 + 1;
-                                            selected_day =
-                                                selected_day.min(days_in_month(year, month));
-                                        }
-                                        DropdownState::Year => {
-                                            let base_year = year as i32 - 5 + year_scroll_offset;
-                                            year = (base_year + idx as i32).max(1) as u32;
-                                            selected_day =
-                                                selected_day.min(days_in_month(year, month));
-                                        }
-                                        DropdownState::None => {}
-                                    }
-                                }
-                                dropdown = DropdownState::None;
-                                dropdown_hover = None;
-                                needs_redraw = true;
-                            }
-                            _ => {}
-                        }
-                    } else {
-                        match key_event.keysym {
-                            KEY_LEFT => {
-                                if selected_day > 1 {
-                                    selected_day -= 1;
+                    match key_event.keysym {
+                        KEY_LEFT if !typing => {
+                            if selected_day > 1 {
+                                selected_day -= 1;
+                            } else {
+                                if month == 1 {
+                                    month = 12;
+                                    year -= 1;
                                 } else {
-                                    if month == 1 {
-                                        month = 12;
-                                        year -= 1;
-                                    } else {
-                                        month -= 1;
-                                    }
-                                    selected_day = days_in_month(year, month);
+                                    month -= 1;
                                 }
-                                needs_redraw = true;
+                                selected_day = days_in_month(year, month);
                             }
-                            KEY_RIGHT => {
-                                if selected_day < days_in_month(year, month) {
-                                    selected_day += 1;
+                            needs_redraw = true;
+                        }
+                        KEY_RIGHT if !typing => {
+                            if selected_day < days_in_month(year, month) {
+                                selected_day += 1;
+                            } else {
+                                if month == 12 {
+                                    month = 1;
+                                    year += 1;
                                 } else {
-                                    if month == 12 {
-                                        month = 1;
-                                        year += 1;
-                                    } else {
-                                        month += 1;
-                                    }
-                                    selected_day = 1;
+                                    month += 1;
                                 }
-                                needs_redraw = true;
+                                selected_day = 1;
                             }
-                            KEY_UP => {
-                                if selected_day > 7 {
-                                    selected_day -= 7;
+                            needs_redraw = true;
+                        }
+                        KEY_UP if !typing => {
+                            if selected_day > 7 {
+                                selected_day -= 7;
+                            } else {
+                                if month == 1 {
+                                    month = 12;
+                                    year -= 1;
                                 } else {
-                                    if month == 1 {
-                                        month = 12;
-                                        year -= 1;
-                                    } else {
-                                        month -= 1;
-                                    }
-                                    let days_prev = days_in_month(year, month);
-                                    selected_day = days_prev - (7 - selected_day);
+                                    month -= 1;
                                 }
-                                needs_redraw = true;
+                                let days_prev = days_in_month(year, month);
+                                selected_day = days_prev - (7 - selected_day);
                             }
-                            KEY_DOWN => {
-                                let days_in = days_in_month(year, month);
-                                if selected_day + 7 <= days_in {
-                                    selected_day += 7;
+                            needs_redraw = true;
+                        }
+                        KEY_DOWN if !typing => {
+                            let days_in = days_in_month(year, month);
+                            if selected_day + 7 <= days_in {
+                                selected_day += 7;
+                            } else {
+                                let overflow = selected_day + 7 - days_in;
+                                if month == 12 {
+                                    month = 1;
+                                    year += 1;
                                 } else {
-                                    let overflow = selected_day + 7 - days_in;
-                                    if month == 12 {
-                                        month = 1;
-                                        year += 1;
-                                    } else {
-                                        month += 1;
-                                    }
-                                    selected_day = overflow;
+                                    month += 1;
                                 }
-                                needs_redraw = true;
+                                selected_day = overflow;
                             }
-                            KEY_RETURN => {
-                                return Ok(CalendarResult::Selected {
-                                    year,
-                                    month,
-                                    day: selected_day,
-                                });
-                            }
-                            KEY_ESCAPE => {
-                                return Ok(CalendarResult::Cancelled);
-                            }
-                            _ => {}
+                            needs_redraw = true;
                         }
+                        KEY_RETURN => {
+                            return Ok(CalendarResult::Selected {
+                                year,
+                                month,
+                                day: selected_day,
+                            });
+                        }
+                        KEY_ESCAPE => {
+                            return Ok(CalendarResult::Cancelled);
+                        }
+                        _ => {}
                     }
                 }
                 _ => {}
             }
 
-            needs_redraw |= ok_button.process_event(&event);
-            needs_redraw |= cancel_button.process_event(&event);
+            if !picking {
+                needs_redraw |= ok_button.process_event(&event);
+                needs_redraw |= cancel_button.process_event(&event);
+            }
 
             if ok_button.was_clicked() {
                 return Ok(CalendarResult::Selected {
@@ -548,19 +492,59 @@ impl CalendarBuilder {
                 return Ok(CalendarResult::Cancelled);
             }
 
-            while let Some(ev) = window.poll_for_event()? {
+            while let Some(mut ev) = window.poll_for_event()? {
                 if let WindowEvent::CloseRequested = ev {
                     return Ok(CalendarResult::Closed);
                 }
+                if !month_combo.is_open() {
+                    ev = focus.process_event(
+                        ev,
+                        &mut [
+                            &mut month_combo,
+                            &mut year_spin,
+                            &mut ok_button,
+                            &mut cancel_button,
+                        ],
+                    );
+                    needs_redraw |= matches!(ev, WindowEvent::RedrawRequested);
+                    needs_redraw |= year_spin.process_event(&ev);
+                }
                 if let WindowEvent::CursorMove(pos) = ev {
                     mouse_x = pos.x as i32;
                     mouse_y = pos.y as i32;
                 }
+                needs_redraw |= month_combo.process_event(&ev);
                 needs_redraw |= ok_button.process_event(&ev);
                 needs_redraw |= cancel_button.process_event(&ev);
             }
 
+            // Take the month chosen from the picker or the year typed
+            if month_combo.was_changed() {
+                month = month_combo.selected() as u32 + 1;
+                selected_day = selected_day.min(days_in_month(year, month));
+                needs_redraw = true;
+            }
+            if year_spin.was_changed() {
+                year = year_spin.value() as u32;
+                selected_day = selected_day.min(days_in_month(year, month));
+                needs_redraw = true;
+            }
+
             if needs_redraw {
+                // Keep the header showing the date however it changed
+                month_combo.set_selected((month - 1) as usize);
+                if year_spin.value() != year as i32 {
+                    year_spin.set_value(year as i32);
+                }
+                layout_header(
+                    &mut month_combo,
+                    &mut year_spin,
+                    calendar_x,
+                    calendar_y,
+                    header_height,
+                    scale,
+                );
+
                 draw_calendar(
                     &mut canvas,
                     colors,
@@ -574,9 +558,8 @@ impl CalendarBuilder {
                     month,
                     selected_day,
                     hovered_day,
-                    dropdown,
-                    dropdown_hover,
-                    year_scroll_offset,
+                    &month_combo,
+                    &year_spin,
                     &ok_button,
                     &cancel_button,
                     scale,
@@ -587,10 +570,29 @@ impl CalendarBuilder {
     }
 }
 
+/// Places the month picker after the previous arrow and the year field
+/// after it, centered in the header.
+fn layout_header(
+    month_combo: &mut Combo,
+    year_spin: &mut SpinButton,
+    calendar_x: i32,
+    calendar_y: i32,
+    header_height: u32,
+    scale: f32,
+) {
+    let centered = |height: u32| calendar_y + (header_height as i32 - height as i32) / 2;
+    let month_x = calendar_x + (30.0 * scale) as i32;
+    month_combo.set_position(month_x, centered(month_combo.height()));
+    year_spin.set_position(
+        month_x + month_combo.width() as i32 + (2.0 * scale) as i32,
+        centered(year_spin.height()),
+    );
+}
+
 #[allow(clippy::too_many_arguments)]
 fn draw_calendar(
     canvas: &mut Canvas,
-    colors: &Colors,
+    colors: &Theme,
     font: &Font,
     text: &str,
     text_y: i32,
@@ -601,29 +603,27 @@ fn draw_calendar(
     month: u32,
     selected_day: u32,
     hovered_day: Option<u32>,
-    dropdown: DropdownState,
-    dropdown_hover: Option<usize>,
-    year_scroll_offset: i32,
+    month_combo: &Combo,
+    year_spin: &SpinButton,
     ok_button: &Button,
     cancel_button: &Button,
     scale: f32,
 ) {
     // Scale dimensions
-    let padding = (BASE_PADDING as f32 * scale) as u32;
+    let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
     let cell_size = (BASE_CELL_SIZE as f32 * scale) as u32;
     let header_height = (BASE_HEADER_HEIGHT as f32 * scale) as u32;
     let day_header_height = (BASE_DAY_HEADER_HEIGHT as f32 * scale) as u32;
     let width = canvas.width() as f32;
     let height = canvas.height() as f32;
-    let radius = 8.0 * scale;
 
     canvas.fill_dialog_bg(
         width,
         height,
         colors.window_bg,
         colors.window_border,
-        colors.window_shadow,
-        radius,
+        colors.window_radius * scale,
+        colors.border_width,
     );
 
     // Draw text prompt
@@ -682,21 +682,13 @@ fn draw_calendar(
         header_y + (12.0 * scale) as i32,
     );
 
-    // Month name (clickable)
-    let month_name_str = month_name(month);
-    let month_text = font.render(month_name_str).with_color(colors.text).finish();
-    let month_x = calendar_x + (35.0 * scale) as i32;
-    canvas.draw_canvas(&month_text, month_x, header_y + (12.0 * scale) as i32);
-
-    // Year (clickable)
-    let year_str = year.to_string();
-    let year_text = font.render(&year_str).with_color(colors.text).finish();
-    let year_x = month_x + month_text.width() as i32 + (8.0 * scale) as i32;
-    canvas.draw_canvas(&year_text, year_x, header_y + (12.0 * scale) as i32);
+    // Month picker and year field
+    month_combo.draw_to(canvas, colors, font);
+    year_spin.draw_to(canvas, colors, font);
 
     // "Today" link (right side) - green color for action
     let today_color = rgb(80, 160, 100);
-    let today_text = font.render("Today").with_color(today_color).finish();
+    let today_text = font.render(&tr("Today")).with_color(today_color).finish();
     let today_x = calendar_x + grid_width as i32
         - (24.0 * scale) as i32
         - today_text.width() as i32
@@ -708,7 +700,10 @@ fn draw_calendar(
     let days = ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"];
     for (i, day) in days.iter().enumerate() {
         let dx = calendar_x + (i as u32 * cell_size) as i32;
-        let dt = font.render(day).with_color(rgb(140, 140, 140)).finish();
+        let dt = font
+            .render(&tr(day))
+            .with_color(rgb(140, 140, 140))
+            .finish();
         let dtx = dx + (cell_size as i32 - dt.width() as i32) / 2;
         canvas.draw_canvas(&dt, dtx, day_header_y + (6.0 * scale) as i32);
     }
@@ -768,7 +763,7 @@ fn draw_calendar(
         // Day number
         let day_str = day.to_string();
         let text_color = if is_selected {
-            rgb(255, 255, 255)
+            colors.selection_text
         } else if col == 0 {
             rgb(200, 100, 100) // Sunday in red-ish
         } else {
@@ -791,267 +786,11 @@ fn draw_calendar(
         1.0,
     );
 
-    // Buttons (draw before dropdowns so dropdowns appear on top)
+    // Buttons (draw before the picker's list so it appears on top)
     ok_button.draw_to(canvas, colors, font);
     cancel_button.draw_to(canvas, colors, font);
 
-    // Draw dropdowns on top of everything
-    if dropdown == DropdownState::Month {
-        draw_month_dropdown(
-            canvas,
-            colors,
-            font,
-            calendar_x,
-            calendar_y,
-            month,
-            dropdown_hover,
-            scale,
-        );
-    } else if dropdown == DropdownState::Year {
-        draw_year_dropdown(
-            canvas,
-            colors,
-            font,
-            calendar_x,
-            calendar_y,
-            year,
-            year_scroll_offset,
-            dropdown_hover,
-            scale,
-        );
-    }
-}
-
-#[allow(clippy::too_many_arguments)]
-fn draw_month_dropdown(
-    canvas: &mut Canvas,
-    colors: &Colors,
-    font: &Font,
-    calendar_x: i32,
-    calendar_y: i32,
-    current_month: u32,
-    hover: Option<usize>,
-    scale: f32,
-) {
-    let header_height = (BASE_HEADER_HEIGHT as f32 * scale) as u32;
-    let dropdown_item_height = (BASE_DROPDOWN_ITEM_HEIGHT as f32 * scale) as u32;
-
-    let dropdown_x = calendar_x + (30.0 * scale) as i32;
-    let dropdown_y = calendar_y + header_height as i32;
-    let dropdown_w = (100.0 * scale) as u32;
-    let dropdown_h = 6 * dropdown_item_height; // Show 6 items at a time
-
-    // Background with shadow effect
-    canvas.fill_rounded_rect(
-        (dropdown_x + (3.0 * scale) as i32) as f32,
-        (dropdown_y + (3.0 * scale) as i32) as f32,
-        dropdown_w as f32,
-        (dropdown_h * 2) as f32,
-        6.0 * scale,
-        rgb(0, 0, 0),
-    );
-    canvas.fill_rounded_rect(
-        dropdown_x as f32,
-        dropdown_y as f32,
-        dropdown_w as f32,
-        (dropdown_h * 2) as f32,
-        6.0 * scale,
-        colors.window_bg,
-    );
-
-    // Items
-    for i in 0..12usize {
-        let item_y = dropdown_y + (i as u32 * dropdown_item_height) as i32;
-        let is_current = i + 1 == current_month as usize;
-        let is_hovered = hover == Some(i);
-
-        // Hover background - subtle gray
-        if is_hovered {
-            canvas.fill_rounded_rect(
-                (dropdown_x + (4.0 * scale) as i32) as f32,
-                (item_y + (2.0 * scale) as i32) as f32,
-                (dropdown_w - (8.0 * scale) as u32) as f32,
-                (dropdown_item_height - (4.0 * scale) as u32) as f32,
-                4.0 * scale,
-                rgb(70, 130, 180), // Steel blue for hover
-            );
-        }
-
-        // Current month gets a checkmark
-        let name = month_name(i as u32 + 1);
-        let display_name = if is_current {
-            format!("{} *", name)
-        } else {
-            name.to_string()
-        };
-
-        let text_color = if is_hovered {
-            rgb(255, 255, 255)
-        } else if is_current {
-            rgb(70, 180, 130) // Teal for current
-        } else {
-            colors.text
-        };
-        let tc = font.render(&display_name).with_color(text_color).finish();
-        canvas.draw_canvas(
-            &tc,
-            dropdown_x + (10.0 * scale) as i32,
-            item_y + (4.0 * scale) as i32,
-        );
-    }
-
-    // Border
-    canvas.stroke_rounded_rect(
-        dropdown_x as f32,
-        dropdown_y as f32,
-        dropdown_w as f32,
-        (dropdown_h * 2) as f32,
-        6.0 * scale,
-        colors.input_border,
-        1.0,
-    );
-}
-
-#[allow(clippy::too_many_arguments)]
-fn draw_year_dropdown(
-    canvas: &mut Canvas,
-    colors: &Colors,
-    font: &Font,
-    calendar_x: i32,
-    calendar_y: i32,
-    current_year: u32,
-    scroll_offset: i32,
-    hover: Option<usize>,
-    scale: f32,
-) {
-    let header_height = (BASE_HEADER_HEIGHT as f32 * scale) as u32;
-    let dropdown_item_height = (BASE_DROPDOWN_ITEM_HEIGHT as f32 * scale) as u32;
-
-    let dropdown_x = calendar_x + (100.0 * scale) as i32;
-    let dropdown_y = calendar_y + header_height as i32;
-    let dropdown_w = (70.0 * scale) as u32;
-    let visible_years = 11usize;
-    let dropdown_h = visible_years as u32 * dropdown_item_height;
-
-    // Background with shadow
-    canvas.fill_rounded_rect(
-        (dropdown_x + (3.0 * scale) as i32) as f32,
-        (dropdown_y + (3.0 * scale) as i32) as f32,
-        dropdown_w as f32,
-        dropdown_h as f32,
-        6.0 * scale,
-        rgb(0, 0, 0),
-    );
-    canvas.fill_rounded_rect(
-        dropdown_x as f32,
-        dropdown_y as f32,
-        dropdown_w as f32,
-        dropdown_h as f32,
-        6.0 * scale,
-        colors.window_bg,
-    );
-
-    // Years centered around current year
-    let base_year = current_year as i32 - 5 + scroll_offset;
-
-    for i in 0..visible_years {
-        let yr = base_year + i as i32;
-        if yr < 1 {
-            continue;
-        }
-
-        let item_y = dropdown_y + (i as u32 * dropdown_item_height) as i32;
-        let is_current = yr == current_year as i32;
-        let is_hovered = hover == Some(i);
-
-        // Hover background
-        if is_hovered {
-            canvas.fill_rounded_rect(
-                (dropdown_x + (4.0 * scale) as i32) as f32,
-                (item_y + (2.0 * scale) as i32) as f32,
-                (dropdown_w - (8.0 * scale) as u32) as f32,
-                (dropdown_item_height - (4.0 * scale) as u32) as f32,
-                4.0 * scale,
-                rgb(70, 130, 180), // Steel blue for hover
-            );
-        }
-
-        let yr_str = if is_current {
-            format!("* {} *", yr)
-        } else {
-            yr.to_string()
-        };
-
-        let text_color = if is_hovered {
-            rgb(255, 255, 255)
-        } else if is_current {
-            rgb(70, 180, 130) // Teal for current
-        } else {
-            colors.text
-        };
-        let tc = font.render(&yr_str).with_color(text_color).finish();
-        let tx = dropdown_x + (dropdown_w as i32 - tc.width() as i32) / 2;
-        canvas.draw_canvas(&tc, tx, item_y + (4.0 * scale) as i32);
-    }
-
-    // Border
-    canvas.stroke_rounded_rect(
-        dropdown_x as f32,
-        dropdown_y as f32,
-        dropdown_w as f32,
-        dropdown_h as f32,
-        6.0 * scale,
-        colors.input_border,
-        1.0,
-    );
-}
-
-fn get_dropdown_hover(
-    dropdown: DropdownState,
-    mouse_x: i32,
-    mouse_y: i32,
-    calendar_x: i32,
-    calendar_y: i32,
-    scale: f32,
-) -> Option<usize> {
-    let header_height = (BASE_HEADER_HEIGHT as f32 * scale) as u32;
-    let dropdown_item_height = (BASE_DROPDOWN_ITEM_HEIGHT as f32 * scale) as u32;
-
-    let dropdown_y = calendar_y + header_height as i32;
-
-    match dropdown {
-        DropdownState::Month => {
-            let dropdown_x = calendar_x + (30.0 * scale) as i32;
-            let dropdown_w = (100.0 * scale) as i32;
-            let dropdown_h = (12 * dropdown_item_height) as i32;
-
-            if mouse_x >= dropdown_x
-                && mouse_x < dropdown_x + dropdown_w
-                && mouse_y >= dropdown_y
-                && mouse_y < dropdown_y + dropdown_h
-            {
-                let idx = (mouse_y - dropdown_y) / dropdown_item_height as i32;
-                return Some(idx as usize);
-            }
-        }
-        DropdownState::Year => {
-            let dropdown_x = calendar_x + (100.0 * scale) as i32;
-            let dropdown_w = (70.0 * scale) as i32;
-            let visible_years = 11u32;
-            let dropdown_h = (visible_years * dropdown_item_height) as i32;
-
-            if mouse_x >= dropdown_x
-                && mouse_x < dropdown_x + dropdown_w
-                && mouse_y >= dropdown_y
-                && mouse_y < dropdown_y + dropdown_h
-            {
-                let idx = (mouse_y - dropdown_y) / dropdown_item_height as i32;
-                return Some(idx as usize);
-            }
-        }
-        DropdownState::None => {}
-    }
-    None
+    month_combo.draw_popup(canvas, colors, font);
 }
 
 impl Default for CalendarBuilder {
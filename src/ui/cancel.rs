@@ -0,0 +1,52 @@
+//! Closing a dialog from another thread once it is no longer needed.
+
+use std::sync::Arc;
+
+use crate::ui::handle::{self, Remote};
+
+/// Closes the dialogs shown under it from any thread it is cloned to, for
+/// when what the user is asked about stops mattering, like a device being
+/// unplugged while asking what to do with it.
+///
+/// ```no_run
+/// let token = zenity_rs::CancelToken::new();
+/// let unplugged = token.clone();
+/// std::thread::spawn(move || {
+///     // ... wait for the device to go away ...
+///     unplugged.cancel();
+/// });
+///
+/// let result = token.run(|| zenity_rs::question("Format the new drive?").show())?;
+/// # Ok::<(), zenity_rs::Error>(())
+/// ```
+///
+/// A cancelled dialog returns as if its window was closed, with the
+/// `Closed` result of its kind. Dialogs drawn in the terminal, and file
+/// dialogs shown by the desktop portal, can't be cancelled.
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    remote: Arc<Remote>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Runs `f` on this thread, letting the token close the dialogs it
+    /// shows. Once cancelled, dialogs run under it close as soon as they
+    /// are shown.
+    pub fn run<R>(&self, f: impl FnOnce() -> R) -> R {
+        handle::with_remote(self.remote.clone(), f)
+    }
+
+    /// Closes the dialog showing under the token, and any shown under it
+    /// afterwards.
+    pub fn cancel(&self) {
+        self.remote.close();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.remote.is_closed()
+    }
+}
@@ -0,0 +1,529 @@
+//! Chooser dialog implementation.
+//!
+//! Two lists side by side: rows move from "Available" to "Selected" and can be
+//! reordered there. The selected rows are returned in their final order.
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    backend::{Modifiers, MouseButton, ScrollDirection, Window, WindowEvent},
+    error::Error,
+    render::Canvas,
+    ui::{
+        Theme, WindowPlacement, create_window,
+        strings::tr,
+        widgets::{Widget, button::Button, focus::FocusManager, label::Label, list_pane::ListPane},
+    },
+};
+
+const BASE_WINDOW_WIDTH: u32 = 560;
+const BASE_WINDOW_HEIGHT: u32 = 400;
+const BASE_PADDING: u32 = 16;
+const BASE_GAP: u32 = 10;
+const BASE_ROW_HEIGHT: u32 = 26;
+const BASE_COLUMN_GAP: u32 = 16;
+
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+const KEY_ESCAPE: u32 = 0xff1b;
+const KEY_RETURN: u32 = 0xff0d;
+const KEY_KP_ENTER: u32 = 0xff8d;
+const KEY_LEFT: u32 = 0xff51;
+const KEY_UP: u32 = 0xff52;
+const KEY_RIGHT: u32 = 0xff53;
+const KEY_DOWN: u32 = 0xff54;
+const KEY_PAGE_UP: u32 = 0xff55;
+const KEY_PAGE_DOWN: u32 = 0xff56;
+
+/// Chooser dialog result.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChooserResult {
+    /// User confirmed. Holds the first column of each selected row, in order.
+    Selected(Vec<String>),
+    /// User cancelled.
+    Cancelled,
+    /// Dialog was closed.
+    Closed,
+}
+
+impl ChooserResult {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ChooserResult::Selected(_) => 0,
+            ChooserResult::Cancelled => 1,
+            ChooserResult::Closed => 255,
+        }
+    }
+}
+
+/// Chooser dialog builder.
+pub struct ChooserBuilder {
+    title: String,
+    text: String,
+    rows: Vec<Vec<String>>,
+    /// Indices into `rows` that start in the selected list, in order.
+    initially_selected: Vec<usize>,
+    width: Option<u32>,
+    height: Option<u32>,
+    colors: Option<Theme>,
+    pub(super) placing: WindowPlacement,
+}
+
+impl ChooserBuilder {
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            text: String::new(),
+            rows: Vec::new(),
+            initially_selected: Vec::new(),
+            width: None,
+            height: None,
+            colors: None,
+            placing: WindowPlacement::default(),
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    /// Adds a row to the available list.
+    pub fn row(mut self, values: Vec<String>) -> Self {
+        self.rows.push(values);
+        self
+    }
+
+    /// Adds a row that starts in the selected list.
+    pub fn selected_row(mut self, values: Vec<String>) -> Self {
+        self.initially_selected.push(self.rows.len());
+        self.rows.push(values);
+        self
+    }
+
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
+        self
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn show(self) -> Result<ChooserResult, Error> {
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
+
+        let logical_width = self.width.unwrap_or(BASE_WINDOW_WIDTH);
+        let logical_height = self.height.unwrap_or(BASE_WINDOW_HEIGHT);
+
+        let mut window = create_window(
+            logical_width as u16,
+            logical_height as u16,
+            colors,
+            &self.placing,
+        )?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Choose Items")
+        } else {
+            self.title.as_str().into()
+        })?;
+
+        // Get the actual scale factor from the window (compositor scale)
+        let scale = window.scale_factor();
+        let physical_width = (logical_width as f32 * scale) as u32;
+        let physical_height = (logical_height as f32 * scale) as u32;
+
+        // Now create everything at PHYSICAL scale
+        let font = colors.font(scale);
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
+        let gap = (colors.space(BASE_GAP) as f32 * scale) as u32;
+        let row_height = (BASE_ROW_HEIGHT as f32 * scale) as u32;
+        let column_gap = (colors.space(BASE_COLUMN_GAP) as f32 * scale) as u32;
+        let content_w = physical_width - padding * 2;
+
+        // Layout in physical coordinates, top to bottom
+        let mut y = padding as i32;
+        let mut prompt = Label::new(&self.text).with_wrap(true);
+        prompt.layout(&font, colors.text, Some(content_w));
+        prompt.set_position(padding as i32, y);
+        if !self.text.is_empty() {
+            y += prompt.height() as i32 + gap as i32;
+        }
+
+        let [mut available_label, mut selected_label] = ["Available", "Selected"].map(|text| {
+            let mut label = Label::new(&tr(text));
+            label.layout(&font, colors.text, None);
+            label
+        });
+        available_label.set_position(padding as i32, y);
+        let labels_y = y;
+        y += available_label.height() as i32 + (gap / 2) as i32;
+
+        let mut ok_button = Button::new(&tr("OK"), &font, colors, scale);
+        let mut cancel_button = Button::new(&tr("Cancel"), &font, colors, scale);
+        let button_y = (physical_height - padding) as i32 - ok_button.height() as i32;
+        let mut bx = physical_width as i32 - padding as i32;
+        bx -= cancel_button.width() as i32;
+        cancel_button.set_position(bx, button_y);
+        bx -= gap as i32 + ok_button.width() as i32;
+        ok_button.set_position(bx, button_y);
+        ok_button.set_default(true);
+
+        // Transfer and reorder buttons share one width between the lists
+        let mut add_button = Button::new(&tr("_Add"), &font, colors, scale);
+        let mut remove_button = Button::new(&tr("_Remove"), &font, colors, scale);
+        let mut up_button = Button::new(&tr("Move _Up"), &font, colors, scale);
+        let mut down_button = Button::new(&tr("Move _Down"), &font, colors, scale);
+        let middle_w = [&add_button, &remove_button, &up_button, &down_button]
+            .iter()
+            .map(|b| b.width())
+            .max()
+            .unwrap_or(0);
+
+        let panes_h = (button_y - gap as i32 - y).max(row_height as i32) as u32;
+        let pane_w = (content_w - middle_w - gap * 2) / 2;
+        let middle_x = padding as i32 + (pane_w + gap) as i32;
+        let selected_x = middle_x + (middle_w + gap) as i32;
+        selected_label.set_position(selected_x, labels_y);
+        let mut available_pane = ListPane::new(padding as i32, y, pane_w, panes_h, row_height);
+        let mut selected_pane = ListPane::new(selected_x, y, pane_w, panes_h, row_height);
+
+        let middle_h = add_button.height() * 4 + gap * 3;
+        let mut my = y + (panes_h as i32 - middle_h as i32).max(0) / 2;
+        for button in [
+            &mut add_button,
+            &mut remove_button,
+            &mut up_button,
+            &mut down_button,
+        ] {
+            button.set_width(middle_w);
+            button.set_position(middle_x, my);
+            my += (button.height() + gap) as i32;
+        }
+
+        // Rows are shown with their columns aligned across both lists
+        let column_count = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut tab_stops = Vec::new();
+        let mut stop = 0;
+        for column in 0..column_count.saturating_sub(1) {
+            let widest = self
+                .rows
+                .iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| font.render(cell).measure().0 as u32)
+                .max()
+                .unwrap_or(0);
+            stop += widest + column_gap;
+            tab_stops.push(stop);
+        }
+        available_pane.set_tab_stops(tab_stops.clone());
+        selected_pane.set_tab_stops(tab_stops);
+        let labels: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| row.join("\t").replace('\n', " "))
+            .collect();
+
+        // Both lists hold indices into `rows`; available rows keep their input order
+        let mut chosen: Vec<usize> = Vec::new();
+        for &row in &self.initially_selected {
+            if !chosen.contains(&row) {
+                chosen.push(row);
+            }
+        }
+        let mut available: Vec<usize> = (0..self.rows.len())
+            .filter(|row| !chosen.contains(row))
+            .collect();
+        let items = |list: &[usize]| list.iter().map(|&i| labels[i].clone()).collect();
+        available_pane.set_items(items(&available), None);
+        selected_pane.set_items(items(&chosen), None);
+        let mut selected_active = false; // Arrow keys move through the selected list
+
+        let mut canvas = Canvas::new(physical_width, physical_height);
+
+        let draw = |canvas: &mut Canvas,
+                    available_pane: &ListPane,
+                    selected_pane: &ListPane,
+                    selected_active: bool,
+                    buttons: [&Button; 6]| {
+            let width = canvas.width() as f32;
+            let height = canvas.height() as f32;
+            canvas.fill_dialog_bg(
+                width,
+                height,
+                colors.window_bg,
+                colors.window_border,
+                colors.window_radius * scale,
+                colors.border_width,
+            );
+
+            prompt.draw(canvas, colors);
+            available_label.draw(canvas, colors);
+            selected_label.draw(canvas, colors);
+            available_pane.draw(canvas, &font, colors, !selected_active, scale);
+            selected_pane.draw(canvas, &font, colors, selected_active, scale);
+
+            for button in buttons {
+                button.draw_to(canvas, colors, &font);
+            }
+        };
+
+        draw(
+            &mut canvas,
+            &available_pane,
+            &selected_pane,
+            selected_active,
+            [
+                &add_button,
+                &remove_button,
+                &up_button,
+                &down_button,
+                &ok_button,
+                &cancel_button,
+            ],
+        );
+        window.set_contents(&canvas)?;
+        window.show()?;
+
+        let mut mouse_x = 0i32;
+        let mut mouse_y = 0i32;
+        let mut last_click: Option<(bool, usize, Instant)> = None;
+        let mut focus = FocusManager::new();
+
+        // Event loop; each pass handles every pending event, then redraws once
+        loop {
+            let mut next = Some(window.wait_for_event()?);
+            let mut needs_redraw = false;
+            let mut accept = false;
+
+            while let Some(event) = next.take() {
+                let event = focus.process_event(
+                    event,
+                    &mut [
+                        &mut add_button,
+                        &mut remove_button,
+                        &mut up_button,
+                        &mut down_button,
+                        &mut ok_button,
+                        &mut cancel_button,
+                    ],
+                );
+                let mut action = Action::None;
+
+                match &event {
+                    WindowEvent::CloseRequested => return Ok(ChooserResult::Closed),
+                    WindowEvent::RedrawRequested => needs_redraw = true,
+                    WindowEvent::KeyPress(key) => {
+                        let ctrl = key.modifiers.contains(Modifiers::CTRL);
+                        match key.keysym {
+                            KEY_ESCAPE => return Ok(ChooserResult::Cancelled),
+                            KEY_RETURN | KEY_KP_ENTER => accept = true,
+                            KEY_RIGHT => action = Action::Add,
+                            KEY_LEFT => action = Action::Remove,
+                            KEY_UP if ctrl => action = Action::Move(-1),
+                            KEY_DOWN if ctrl => action = Action::Move(1),
+                            KEY_UP | KEY_DOWN | KEY_PAGE_UP | KEY_PAGE_DOWN => {
+                                let pane = if selected_active {
+                                    &mut selected_pane
+                                } else {
+                                    &mut available_pane
+                                };
+                                let rows = pane.visible_rows() as isize;
+                                pane.step(match key.keysym {
+                                    KEY_UP => -1,
+                                    KEY_DOWN => 1,
+                                    KEY_PAGE_UP => -rows,
+                                    _ => rows,
+                                });
+                                needs_redraw = true;
+                            }
+                            _ => {}
+                        }
+                    }
+                    WindowEvent::CursorMove(pos) => {
+                        mouse_x = pos.x as i32;
+                        mouse_y = pos.y as i32;
+                        needs_redraw |= available_pane.hover(mouse_x, mouse_y);
+                        needs_redraw |= selected_pane.hover(mouse_x, mouse_y);
+                    }
+                    WindowEvent::CursorLeave => {
+                        needs_redraw |= available_pane.hover(-1, -1);
+                        needs_redraw |= selected_pane.hover(-1, -1);
+                    }
+                    WindowEvent::ButtonPress(MouseButton::Left, _) => {
+                        let clicked = if let Some(row) = available_pane.row_at(mouse_x, mouse_y) {
+                            Some((false, row))
+                        } else {
+                            selected_pane
+                                .row_at(mouse_x, mouse_y)
+                                .map(|row| (true, row))
+                        };
+                        if let Some((in_selected, row)) = clicked {
+                            let pane = if in_selected {
+                                &mut selected_pane
+                            } else {
+                                &mut available_pane
+                            };
+                            pane.select(Some(row));
+                            selected_active = in_selected;
+
+                            // Double-clicking a row moves it to the other list
+                            let double_click = last_click.is_some_and(|(side, prev, time)| {
+                                side == in_selected
+                                    && prev == row
+                                    && time.elapsed() <= DOUBLE_CLICK_TIME
+                            });
+                            last_click =
+                                (!double_click).then(|| (in_selected, row, Instant::now()));
+                            if double_click {
+                                action = if in_selected {
+                                    Action::Remove
+                                } else {
+                                    Action::Add
+                                };
+                            }
+                            needs_redraw = true;
+                        }
+                    }
+                    WindowEvent::Scroll(direction) => {
+                        let rows = match direction {
+                            ScrollDirection::Up => -3,
+                            ScrollDirection::Down => 3,
+                            _ => 0,
+                        };
+                        for pane in [&mut available_pane, &mut selected_pane] {
+                            if pane.contains(mouse_x, mouse_y) {
+                                pane.scroll(rows);
+                                needs_redraw = true;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+
+                for button in [
+                    &mut add_button,
+                    &mut remove_button,
+                    &mut up_button,
+                    &mut down_button,
+                    &mut ok_button,
+                    &mut cancel_button,
+                ] {
+                    needs_redraw |= button.process_event(&event);
+                }
+
+                if add_button.was_clicked() {
+                    action = Action::Add;
+                }
+                if remove_button.was_clicked() {
+                    action = Action::Remove;
+                }
+                if up_button.was_clicked() {
+                    action = Action::Move(-1);
+                }
+                if down_button.was_clicked() {
+                    action = Action::Move(1);
+                }
+
+                match action {
+                    Action::None => {}
+                    Action::Add => {
+                        if let Some(pos) = available_pane.selected {
+                            let row = available.remove(pos);
+                            chosen.push(row);
+                            let next = pos.min(available.len().saturating_sub(1));
+                            available_pane.set_items(items(&available), Some(next));
+                            selected_pane.set_items(items(&chosen), Some(chosen.len() - 1));
+                            last_click = None;
+                            needs_redraw = true;
+                        }
+                    }
+                    Action::Remove => {
+                        if let Some(pos) = selected_pane.selected {
+                            let row = chosen.remove(pos);
+                            // Back to its original place among the available rows
+                            let back = available.partition_point(|&i| i < row);
+                            available.insert(back, row);
+                            let next = pos.min(chosen.len().saturating_sub(1));
+                            selected_pane.set_items(items(&chosen), Some(next));
+                            available_pane.set_items(items(&available), Some(back));
+                            last_click = None;
+                            needs_redraw = true;
+                        }
+                    }
+                    Action::Move(delta) => {
+                        if let Some(pos) = selected_pane.selected
+                            && let Some(target) = pos.checked_add_signed(delta)
+                            && target < chosen.len()
+                        {
+                            chosen.swap(pos, target);
+                            selected_pane.set_items(items(&chosen), Some(target));
+                            selected_active = true;
+                            last_click = None;
+                            needs_redraw = true;
+                        }
+                    }
+                }
+
+                if cancel_button.was_clicked() {
+                    return Ok(ChooserResult::Cancelled);
+                }
+                accept |= ok_button.was_clicked();
+
+                next = window.poll_for_event()?;
+            }
+
+            if accept {
+                let values = chosen
+                    .iter()
+                    .map(|&i| self.rows[i].first().cloned().unwrap_or_default())
+                    .collect();
+                return Ok(ChooserResult::Selected(values));
+            }
+
+            if needs_redraw {
+                draw(
+                    &mut canvas,
+                    &available_pane,
+                    &selected_pane,
+                    selected_active,
+                    [
+                        &add_button,
+                        &remove_button,
+                        &up_button,
+                        &down_button,
+                        &ok_button,
+                        &cancel_button,
+                    ],
+                );
+                window.set_contents(&canvas)?;
+            }
+        }
+    }
+}
+
+impl Default for ChooserBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What an input asked to do with the lists.
+enum Action {
+    None,
+    Add,
+    Remove,
+    Move(isize),
+}
@@ -0,0 +1,194 @@
+//! Ordering names the way people read them, for sorting file listings.
+//!
+//! Runs of digits compare by value, so "file2" sorts before "file10" and
+//! "1.9" before "1.10". The text between them compares by the user's
+//! collation order (`LC_COLLATE`), which in most locales ignores case and
+//! accents unless nothing else tells names apart. Without a locale, as in
+//! `C` or `POSIX`, text compares case-insensitively with accents folded
+//! from common Latin letters.
+
+use std::{cmp::Ordering, env, ffi::CString, sync::OnceLock};
+
+/// Compares two names naturally. Names equal under the rules above fall
+/// back to a plain comparison so the order stays stable.
+pub(crate) fn natural_cmp(a: &str, b: &str) -> Ordering {
+    match collator() {
+        Some(collator) => natural_cmp_by(a, b, |x, y| collator.cmp(x, y)),
+        None => natural_cmp_by(a, b, folded_cmp),
+    }
+}
+
+/// Compares names by runs: digit runs by value, other text by `cmp_text`.
+fn natural_cmp_by(a: &str, b: &str, cmp_text: impl Fn(&str, &str) -> Ordering) -> Ordering {
+    let (mut x, mut y) = (runs(a), runs(b));
+    loop {
+        let ord = match (x.next(), y.next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(rx), Some(ry)) if is_digits(rx) && is_digits(ry) => {
+                let (nx, ny) = (rx.trim_start_matches('0'), ry.trim_start_matches('0'));
+                nx.len().cmp(&ny.len()).then_with(|| nx.cmp(ny))
+            }
+            (Some(rx), Some(ry)) => cmp_text(rx, ry),
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+}
+
+/// Splits `s` into alternating runs of ASCII digits and other text.
+fn runs(s: &str) -> impl Iterator<Item = &str> {
+    let mut rest = s;
+    std::iter::from_fn(move || {
+        let first = rest.chars().next()?;
+        let len = rest
+            .find(|c: char| c.is_ascii_digit() != first.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (run, tail) = rest.split_at(len);
+        rest = tail;
+        Some(run)
+    })
+}
+
+fn is_digits(run: &str) -> bool {
+    run.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Compares text case-insensitively, with accents folded.
+fn folded_cmp(a: &str, b: &str) -> Ordering {
+    a.chars().map(fold).cmp(b.chars().map(fold))
+}
+
+/// Lowercases a character and strips accents from common Latin letters.
+fn fold(c: char) -> char {
+    match c.to_lowercase().next().unwrap_or(c) {
+        'à'..='å' | 'ā' | 'ă' | 'ą' => 'a',
+        'ç' | 'ć' | 'č' => 'c',
+        'ď' => 'd',
+        'è'..='ë' | 'ē' | 'ę' | 'ě' => 'e',
+        'ì'..='ï' | 'ī' | 'į' => 'i',
+        'ł' | 'ľ' => 'l',
+        'ñ' | 'ń' | 'ň' => 'n',
+        'ò'..='ö' | 'ø' | 'ō' | 'ő' => 'o',
+        'ř' => 'r',
+        'ß' | 'ś' | 'š' | 'ş' => 's',
+        'ť' | 'ţ' => 't',
+        'ù'..='ü' | 'ū' | 'ů' | 'ű' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ź' | 'ż' | 'ž' => 'z',
+        lower => lower,
+    }
+}
+
+/// The user's collation locale, loaded without changing the program's.
+struct Collator(libc::locale_t);
+
+// SAFETY: the locale is never changed or freed once made, and POSIX allows
+// a locale object to be used from several threads at once.
+unsafe impl Send for Collator {}
+unsafe impl Sync for Collator {}
+
+impl Collator {
+    fn cmp(&self, a: &str, b: &str) -> Ordering {
+        // Names can't hold NUL, but compare plainly rather than panic
+        let (Ok(ca), Ok(cb)) = (CString::new(a), CString::new(b)) else {
+            return a.cmp(b);
+        };
+        // SAFETY: the locale is valid for the life of the program, and is
+        // only made this thread's for the one call.
+        let result = unsafe {
+            let previous = libc::uselocale(self.0);
+            let result = libc::strcoll(ca.as_ptr(), cb.as_ptr());
+            libc::uselocale(previous);
+            result
+        };
+        result.cmp(&0)
+    }
+}
+
+/// Returns the collation locale the environment names, or `None` where it
+/// names none, or only `C` or `POSIX`.
+fn collator() -> Option<&'static Collator> {
+    static COLLATOR: OnceLock<Option<Collator>> = OnceLock::new();
+    COLLATOR
+        .get_or_init(|| {
+            let var = |name| env::var(name).ok().filter(|value| !value.is_empty());
+            let locale = var("LC_ALL")
+                .or_else(|| var("LC_COLLATE"))
+                .or_else(|| var("LANG"))?;
+            let name = locale.split(['.', '@']).next().unwrap_or_default();
+            if matches!(name, "C" | "POSIX") {
+                return None;
+            }
+            let locale = CString::new(locale).ok()?;
+            // SAFETY: `locale` is a valid C string, and no base locale is
+            // given to be taken over.
+            let handle = unsafe {
+                libc::newlocale(libc::LC_COLLATE_MASK, locale.as_ptr(), std::ptr::null_mut())
+            };
+            (!handle.is_null()).then_some(Collator(handle))
+        })
+        .as_ref()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering::{Equal, Greater, Less};
+
+    use super::*;
+
+    fn cmp(a: &str, b: &str) -> Ordering {
+        natural_cmp_by(a, b, folded_cmp)
+    }
+
+    #[test]
+    fn numbers_compare_by_value() {
+        assert_eq!(cmp("file2", "file10"), Less);
+        assert_eq!(cmp("file10", "file2"), Greater);
+        assert_eq!(cmp("1.9", "1.10"), Less);
+        assert_eq!(cmp("v2.0.1", "v2.0.10"), Less);
+        assert_eq!(natural_cmp("file2", "file10"), Less);
+    }
+
+    #[test]
+    fn case_is_ignored_until_names_tie() {
+        assert_eq!(cmp("apple", "Banana"), Less);
+        assert_eq!(cmp("Banana", "cherry"), Less);
+        assert_eq!(cmp("README", "notes"), Greater);
+        // Stable order for names differing only in case
+        assert_eq!(cmp("A", "a"), Less);
+        assert_eq!(cmp("a", "A"), Greater);
+    }
+
+    #[test]
+    fn accents_are_folded() {
+        assert_eq!(cmp("école", "eu"), Less);
+        assert_eq!(cmp("Émile", "elephant"), Greater);
+        assert_eq!(cmp("Émile", "zebra"), Less);
+        assert_eq!(cmp("Ångström", "b"), Less);
+    }
+
+    #[test]
+    fn leading_zeros_count_only_to_break_ties() {
+        assert_eq!(cmp("file007", "file10"), Less);
+        assert_eq!(cmp("file02", "file1"), Greater);
+        assert_eq!(cmp("file01", "file1"), Less);
+        assert_eq!(cmp("file1", "file01"), Greater);
+    }
+
+    #[test]
+    fn empty_names_sort_first() {
+        assert_eq!(cmp("", ""), Equal);
+        assert_eq!(cmp("", "a"), Less);
+        assert_eq!(cmp("0", ""), Greater);
+    }
+
+    #[test]
+    fn digits_and_text_split_into_runs() {
+        assert_eq!(runs("ab12cd3").collect::<Vec<_>>(), ["ab", "12", "cd", "3"]);
+        assert_eq!(runs("42").collect::<Vec<_>>(), ["42"]);
+        assert_eq!(runs("").count(), 0);
+    }
+}
@@ -0,0 +1,823 @@
+//! Color selection dialog implementation.
+
+use std::{
+    path::PathBuf,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    backend::{MouseButton, Window, WindowEvent},
+    dbus::{Connection, portal},
+    error::Error,
+    render::{Canvas, Rgba, rgb},
+    ui::{
+        Theme, WindowPlacement, create_window, recent_colors,
+        strings::tr,
+        widgets::{
+            Widget, button::Button, focus::FocusManager, label::Label, point_in_rect,
+            spinner::Spinner, text_input::TextInput,
+        },
+    },
+};
+
+const BASE_PADDING: u32 = 20;
+const BASE_GAP: u32 = 12;
+const BASE_SV_SIZE: u32 = 200;
+const BASE_HUE_WIDTH: u32 = 20;
+const BASE_SIDE_WIDTH: u32 = 140;
+const BASE_PREVIEW_HEIGHT: u32 = 60;
+const BASE_SWATCH_SIZE: u32 = 24;
+const BASE_SWATCH_GAP: u32 = 4;
+const BASE_LABEL_GAP: u32 = 4;
+const BASE_BUTTON_SPACING: u32 = 10;
+/// Swatches per row in the palette and recent colors.
+const SWATCH_COLUMNS: usize = 9;
+/// How often the window is polled while the eyedropper is active.
+const PICK_POLL: Duration = Duration::from_millis(50);
+
+const KEY_ESCAPE: u32 = 0xff1b;
+
+/// The GNOME palette: a row per shade, a column per hue.
+const DEFAULT_PALETTE: [[u8; 3]; 45] = [
+    [0x99, 0xc1, 0xf1],
+    [0x8f, 0xf0, 0xa4],
+    [0xf9, 0xf0, 0x6b],
+    [0xff, 0xbe, 0x6f],
+    [0xf6, 0x61, 0x51],
+    [0xdc, 0x8a, 0xdd],
+    [0xcd, 0xab, 0x8f],
+    [0xff, 0xff, 0xff],
+    [0x77, 0x76, 0x7b],
+    [0x62, 0xa0, 0xea],
+    [0x57, 0xe3, 0x89],
+    [0xf8, 0xe4, 0x5c],
+    [0xff, 0xa3, 0x48],
+    [0xed, 0x33, 0x3b],
+    [0xc0, 0x61, 0xcb],
+    [0xb5, 0x83, 0x5a],
+    [0xf6, 0xf5, 0xf4],
+    [0x5e, 0x5c, 0x64],
+    [0x35, 0x84, 0xe4],
+    [0x33, 0xd1, 0x7a],
+    [0xf6, 0xd3, 0x2d],
+    [0xff, 0x78, 0x00],
+    [0xe0, 0x1b, 0x24],
+    [0x91, 0x41, 0xac],
+    [0x98, 0x6a, 0x44],
+    [0xde, 0xdd, 0xda],
+    [0x3d, 0x38, 0x46],
+    [0x1c, 0x71, 0xd8],
+    [0x2e, 0xc2, 0x7e],
+    [0xf5, 0xc2, 0x11],
+    [0xe6, 0x61, 0x00],
+    [0xc0, 0x1c, 0x28],
+    [0x81, 0x3d, 0x9c],
+    [0x86, 0x5e, 0x3c],
+    [0xc0, 0xbf, 0xbc],
+    [0x24, 0x1f, 0x31],
+    [0x1a, 0x5f, 0xb4],
+    [0x26, 0xa2, 0x69],
+    [0xe5, 0xa5, 0x0a],
+    [0xc6, 0x46, 0x00],
+    [0xa5, 0x1d, 0x2d],
+    [0x61, 0x35, 0x83],
+    [0x63, 0x45, 0x2c],
+    [0x9a, 0x99, 0x96],
+    [0x00, 0x00, 0x00],
+];
+
+/// Color selection dialog result.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorResult {
+    /// User selected a color.
+    Selected { red: u8, green: u8, blue: u8 },
+    /// User cancelled the dialog.
+    Cancelled,
+    /// Dialog was closed.
+    Closed,
+}
+
+impl ColorResult {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ColorResult::Selected {
+                ..
+            } => 0,
+            ColorResult::Cancelled => 1,
+            ColorResult::Closed => 255,
+        }
+    }
+}
+
+/// Color selection dialog builder.
+pub struct ColorBuilder {
+    title: String,
+    text: String,
+    color: [u8; 3],
+    show_palette: bool,
+    palette_file: Option<PathBuf>,
+    remember: bool,
+    width: Option<u32>,
+    height: Option<u32>,
+    colors: Option<Theme>,
+    pub(super) placing: WindowPlacement,
+}
+
+impl ColorBuilder {
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            text: String::new(),
+            color: [0x35, 0x84, 0xe4],
+            show_palette: false,
+            palette_file: None,
+            remember: true,
+            width: None,
+            height: None,
+            colors: None,
+            placing: WindowPlacement::default(),
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    /// Sets the initial color as `#rrggbb`, `#rgb` or `rgb(r,g,b)`.
+    /// Unrecognized values are ignored.
+    pub fn color(mut self, color: &str) -> Self {
+        if let Some(color) = parse_color(color) {
+            self.color = color;
+        }
+        self
+    }
+
+    /// Shows a palette of swatches to pick from.
+    pub fn show_palette(mut self, show: bool) -> Self {
+        self.show_palette = show;
+        self
+    }
+
+    /// Reads the palette from a file instead of using the built-in one.
+    /// Lines hold a color as for [`color`](Self::color), or `R G B name`
+    /// as in GIMP palettes; other lines are skipped.
+    pub fn palette_file(mut self, path: &str) -> Self {
+        self.palette_file = Some(PathBuf::from(path));
+        self.show_palette = true;
+        self
+    }
+
+    /// Whether to show and update the recently chosen colors (default: true).
+    pub fn remember(mut self, remember: bool) -> Self {
+        self.remember = remember;
+        self
+    }
+
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
+        self
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn show(self) -> Result<ColorResult, Error> {
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
+
+        let palette: Vec<[u8; 3]> = match (&self.palette_file, self.show_palette) {
+            (Some(path), _) => {
+                std::fs::read_to_string(path)?
+                    .lines()
+                    .filter_map(parse_palette_line)
+                    .collect()
+            }
+            (None, true) => DEFAULT_PALETTE.to_vec(),
+            (None, false) => Vec::new(),
+        };
+        let recent = if self.remember {
+            recent_colors::load()
+        } else {
+            Vec::new()
+        };
+        let can_pick = Connection::session()
+            .map(|mut conn| portal::available(&mut conn))
+            .unwrap_or(false);
+
+        // Measure at logical scale to size the window
+        let temp_font = colors.font(1.0);
+        let content_w = BASE_SV_SIZE
+            + colors.space(BASE_GAP)
+            + BASE_HUE_WIDTH
+            + colors.space(BASE_GAP)
+            + BASE_SIDE_WIDTH;
+        let line_h = temp_font.render(&tr("Palette")).measure().1 as u32;
+        let mut temp_prompt = Label::new(&self.text).with_wrap(true);
+        temp_prompt.layout(&temp_font, colors.text, Some(content_w));
+        let prompt_h = if self.text.is_empty() {
+            0
+        } else {
+            temp_prompt.height() + colors.space(BASE_GAP)
+        };
+        let temp_ok = Button::new(&tr("OK"), &temp_font, colors, 1.0);
+        drop(temp_font);
+        let grid_h = |count: usize| {
+            let rows = count.div_ceil(SWATCH_COLUMNS) as u32;
+            colors.space(BASE_GAP)
+                + line_h
+                + colors.space(BASE_LABEL_GAP)
+                + rows * (BASE_SWATCH_SIZE + colors.space(BASE_SWATCH_GAP))
+                - colors.space(BASE_SWATCH_GAP)
+        };
+        let mut swatches_h = 0;
+        if !palette.is_empty() {
+            swatches_h += grid_h(palette.len());
+        }
+        if !recent.is_empty() {
+            swatches_h += grid_h(recent.len());
+        }
+
+        let logical_width = self
+            .width
+            .unwrap_or(content_w + colors.space(BASE_PADDING) * 2);
+        let logical_height = self.height.unwrap_or(
+            colors.space(BASE_PADDING) * 2
+                + prompt_h
+                + BASE_SV_SIZE
+                + swatches_h
+                + colors.space(BASE_GAP)
+                + temp_ok.height(),
+        );
+
+        let mut window = create_window(
+            logical_width as u16,
+            logical_height as u16,
+            colors,
+            &self.placing,
+        )?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Select Color")
+        } else {
+            self.title.as_str().into()
+        })?;
+
+        // Get the actual scale factor from the window (compositor scale)
+        let scale = window.scale_factor();
+        let physical_width = (logical_width as f32 * scale) as u32;
+        let physical_height = (logical_height as f32 * scale) as u32;
+
+        // Now create everything at PHYSICAL scale
+        let font = colors.font(scale);
+        let px = |v: u32| (v as f32 * scale) as u32;
+        let padding = px(colors.space(BASE_PADDING));
+        let gap = px(colors.space(BASE_GAP));
+        let swatch = px(BASE_SWATCH_SIZE);
+        let swatch_gap = px(colors.space(BASE_SWATCH_GAP));
+
+        let mut prompt = Label::new(&self.text).with_wrap(true);
+        prompt.layout(&font, colors.text, Some(physical_width - padding * 2));
+        let mut y = padding as i32;
+        prompt.set_position(padding as i32, y);
+        if !self.text.is_empty() {
+            y += (prompt.height() + gap) as i32;
+        }
+
+        // Saturation/value square, hue bar, then preview, hex entry and eyedropper
+        let sv_size = px(BASE_SV_SIZE);
+        let sv_rect = (padding as i32, y, sv_size, sv_size);
+        let hue_rect = (
+            sv_rect.0 + (sv_size + gap) as i32,
+            y,
+            px(BASE_HUE_WIDTH),
+            sv_size,
+        );
+        let side_x = hue_rect.0 + (hue_rect.2 + gap) as i32;
+        let side_w = (physical_width as i32 - padding as i32 - side_x).max(1) as u32;
+        let preview_rect = (side_x, y, side_w, px(BASE_PREVIEW_HEIGHT));
+
+        let mut hex_input = TextInput::new(side_w);
+        hex_input.set_position(side_x, y + (preview_rect.3 + gap) as i32);
+
+        let mut pick_button = Button::new(&tr("_Pick from Screen"), &font, colors, scale);
+        pick_button.set_width(side_w);
+        pick_button.set_position(side_x, hex_input.y() + (hex_input.height() + gap) as i32);
+        // Left out of the Tab order while there is no portal to pick with
+        pick_button.set_enabled(can_pick);
+
+        // Shown in place of the button while the portal waits for a pick
+        let mut spinner = Spinner::new(pick_button.height() * 3 / 4, scale);
+        spinner.set_position(
+            side_x + (side_w as i32 - spinner.size() as i32) / 2,
+            pick_button.y() + (pick_button.height() as i32 - spinner.size() as i32) / 2,
+        );
+        y += sv_size as i32;
+
+        // Swatch grids, each under a label
+        let mut grids: Vec<Grid> = Vec::new();
+        for (label, items) in [("Palette", &palette), ("Recent", &recent)] {
+            if items.is_empty() {
+                continue;
+            }
+            let mut label = Label::new(&tr(label));
+            label.layout(&font, colors.text, None);
+            label.set_position(padding as i32, y + gap as i32);
+            let top = label.y() + (label.height() + px(colors.space(BASE_LABEL_GAP))) as i32;
+            let rows = items.len().div_ceil(SWATCH_COLUMNS) as u32;
+            y = top + (rows * (swatch + swatch_gap) - swatch_gap) as i32;
+            grids.push(Grid {
+                label,
+                top,
+                colors: items.clone(),
+            });
+        }
+        let swatch_at = |grids: &[Grid], px_: i32, py: i32| {
+            grids.iter().find_map(|grid| {
+                grid.colors.iter().enumerate().find_map(|(i, color)| {
+                    let (sx, sy) = grid.swatch_pos(i, padding as i32, swatch, swatch_gap);
+                    point_in_rect(px_, py, sx, sy, swatch, swatch).then_some(*color)
+                })
+            })
+        };
+
+        let mut ok_button = Button::new(&tr("OK"), &font, colors, scale);
+        let mut cancel_button = Button::new(&tr("Cancel"), &font, colors, scale);
+        let button_y = (physical_height - padding) as i32 - ok_button.height() as i32;
+        let mut button_x = physical_width as i32 - padding as i32;
+        button_x -= cancel_button.width() as i32;
+        cancel_button.set_position(button_x, button_y);
+        button_x -= px(colors.space(BASE_BUTTON_SPACING)) as i32 + ok_button.width() as i32;
+        ok_button.set_position(button_x, button_y);
+        ok_button.set_default(true);
+
+        let hue_canvas = hue_bar(hue_rect.2, hue_rect.3);
+        let mut hsv = rgb_to_hsv(self.color);
+        let mut sv_canvas = sv_square(hsv.0, sv_size);
+        let mut sv_hue = hsv.0;
+        hex_input.set_text(&to_hex(self.color));
+
+        let mut canvas = Canvas::new(physical_width, physical_height);
+
+        let draw = |canvas: &mut Canvas,
+                    sv_canvas: &Canvas,
+                    hsv: (f32, f32, f32),
+                    hex_input: &TextInput,
+                    pick_button: &Button,
+                    picking: bool,
+                    ok_button: &Button,
+                    cancel_button: &Button| {
+            let width = canvas.width() as f32;
+            let height = canvas.height() as f32;
+            canvas.fill_dialog_bg(
+                width,
+                height,
+                colors.window_bg,
+                colors.window_border,
+                colors.window_radius * scale,
+                colors.border_width,
+            );
+
+            prompt.draw(canvas, colors);
+
+            let current = hsv_to_rgb(hsv);
+            let ring = (4.0 * scale).max(3.0);
+
+            // Saturation/value square with a ring at the current color
+            canvas.draw_canvas(sv_canvas, sv_rect.0, sv_rect.1);
+            stroke_rect(canvas, sv_rect, colors.input_border);
+            let cx = sv_rect.0 as f32 + hsv.1 * (sv_size - 1) as f32;
+            let cy = sv_rect.1 as f32 + (1.0 - hsv.2) * (sv_size - 1) as f32;
+            let ring_color = if hsv.2 > 0.5 && hsv.1 < 0.5 {
+                rgb(0, 0, 0)
+            } else {
+                rgb(255, 255, 255)
+            };
+            canvas.stroke_circle(cx, cy, ring, ring_color, scale.max(1.0) * 1.5);
+
+            // Hue bar with a marker at the current hue
+            canvas.draw_canvas(&hue_canvas, hue_rect.0, hue_rect.1);
+            stroke_rect(canvas, hue_rect, colors.input_border);
+            let hy = hue_rect.1 as f32 + hsv.0 / 360.0 * (hue_rect.3 - 1) as f32;
+            canvas.stroke_rounded_rect(
+                hue_rect.0 as f32 - 1.0,
+                hy - 2.0 * scale,
+                hue_rect.2 as f32 + 2.0,
+                4.0 * scale,
+                1.0 * scale,
+                colors.text,
+                scale.max(1.0) * 1.5,
+            );
+
+            canvas.fill_rounded_rect(
+                preview_rect.0 as f32,
+                preview_rect.1 as f32,
+                preview_rect.2 as f32,
+                preview_rect.3 as f32,
+                4.0 * scale,
+                to_rgba(current),
+            );
+            canvas.stroke_rounded_rect(
+                preview_rect.0 as f32,
+                preview_rect.1 as f32,
+                preview_rect.2 as f32,
+                preview_rect.3 as f32,
+                4.0 * scale,
+                colors.input_border,
+                1.0,
+            );
+            hex_input.draw_to(canvas, colors, &font);
+            if picking {
+                spinner.draw(canvas, colors);
+            } else if can_pick {
+                pick_button.draw_to(canvas, colors, &font);
+            }
+
+            for grid in &grids {
+                grid.label.draw(canvas, colors);
+                for (i, color) in grid.colors.iter().enumerate() {
+                    let (sx, sy) = grid.swatch_pos(i, padding as i32, swatch, swatch_gap);
+                    canvas.fill_rounded_rect(
+                        sx as f32,
+                        sy as f32,
+                        swatch as f32,
+                        swatch as f32,
+                        3.0 * scale,
+                        to_rgba(*color),
+                    );
+                    let (outline, outline_w) = if *color == current {
+                        (colors.input_border_focused, 2.0)
+                    } else {
+                        (colors.input_border, 1.0)
+                    };
+                    canvas.stroke_rounded_rect(
+                        sx as f32,
+                        sy as f32,
+                        swatch as f32,
+                        swatch as f32,
+                        3.0 * scale,
+                        outline,
+                        outline_w,
+                    );
+                }
+            }
+
+            ok_button.draw_to(canvas, colors, &font);
+            cancel_button.draw_to(canvas, colors, &font);
+        };
+
+        let mut focus = FocusManager::new();
+        focus.focus(
+            Some(0),
+            &mut [
+                &mut hex_input,
+                &mut pick_button,
+                &mut ok_button,
+                &mut cancel_button,
+            ],
+        );
+
+        draw(
+            &mut canvas,
+            &sv_canvas,
+            hsv,
+            &hex_input,
+            &pick_button,
+            false,
+            &ok_button,
+            &cancel_button,
+        );
+        window.set_contents(&canvas)?;
+        window.show()?;
+
+        let mut dragging = Drag::None;
+        let mut cursor = (0, 0);
+        // The eyedropper runs on its own thread while the window keeps redrawing
+        let mut picker: Option<Receiver<Option<[u8; 3]>>> = None;
+
+        loop {
+            let first = if picker.is_some() {
+                match window.poll_for_event()? {
+                    Some(event) => Some(event),
+                    None => {
+                        thread::sleep(PICK_POLL);
+                        None
+                    }
+                }
+            } else {
+                Some(window.wait_for_event()?)
+            };
+            let mut next = first;
+            let mut needs_redraw = false;
+            let mut submit = false;
+            let mut picked: Option<[u8; 3]> = None;
+
+            if let Some(rx) = &picker
+                && let Ok(result) = rx.try_recv()
+            {
+                picker = None;
+                picked = result;
+                pick_button.set_enabled(true);
+                needs_redraw = true;
+            }
+
+            while let Some(event) = next.take() {
+                let event = focus.process_event(
+                    event,
+                    &mut [
+                        &mut hex_input,
+                        &mut pick_button,
+                        &mut ok_button,
+                        &mut cancel_button,
+                    ],
+                );
+                match &event {
+                    WindowEvent::CloseRequested => return Ok(ColorResult::Closed),
+                    WindowEvent::RedrawRequested => needs_redraw = true,
+                    WindowEvent::CursorMove(pos) | WindowEvent::CursorEnter(pos) => {
+                        cursor = (pos.x as i32, pos.y as i32);
+                        match dragging {
+                            Drag::Sv => {
+                                hsv.1 = fraction(cursor.0 - sv_rect.0, sv_size);
+                                hsv.2 = 1.0 - fraction(cursor.1 - sv_rect.1, sv_size);
+                            }
+                            Drag::Hue => {
+                                hsv.0 = fraction(cursor.1 - hue_rect.1, hue_rect.3) * 360.0;
+                            }
+                            Drag::None => {}
+                        }
+                        if dragging != Drag::None {
+                            hex_input.set_text(&to_hex(hsv_to_rgb(hsv)));
+                            needs_redraw = true;
+                        }
+                    }
+                    WindowEvent::ButtonPress(MouseButton::Left, _) => {
+                        let (x, y) = cursor;
+                        if in_rect(x, y, sv_rect) {
+                            dragging = Drag::Sv;
+                            hsv.1 = fraction(x - sv_rect.0, sv_size);
+                            hsv.2 = 1.0 - fraction(y - sv_rect.1, sv_size);
+                        } else if in_rect(x, y, hue_rect) {
+                            dragging = Drag::Hue;
+                            hsv.0 = fraction(y - hue_rect.1, hue_rect.3) * 360.0;
+                        } else if let Some(color) = swatch_at(&grids, x, y) {
+                            picked = Some(color);
+                        }
+                        if dragging != Drag::None {
+                            hex_input.set_text(&to_hex(hsv_to_rgb(hsv)));
+                            needs_redraw = true;
+                        }
+                    }
+                    WindowEvent::ButtonRelease(MouseButton::Left, _) => dragging = Drag::None,
+                    WindowEvent::KeyPress(key) if key.keysym == KEY_ESCAPE => {
+                        return Ok(ColorResult::Cancelled);
+                    }
+                    _ => {}
+                }
+
+                // Typing a valid color updates the rest of the dialog
+                let before = hex_input.text().to_string();
+                needs_redraw |= hex_input.process_event(&event);
+                submit |= hex_input.was_submitted();
+                if hex_input.text() != before
+                    && let Some(color) = parse_color(hex_input.text())
+                {
+                    hsv = rgb_to_hsv(color);
+                }
+
+                if can_pick && picker.is_none() {
+                    needs_redraw |= pick_button.process_event(&event);
+                    if pick_button.was_clicked() {
+                        let (tx, rx) = mpsc::channel();
+                        thread::spawn(move || {
+                            let color = portal::pick_color()
+                                .ok()
+                                .flatten()
+                                .map(|c| c.map(|channel| (channel * 255.0).round() as u8));
+                            let _ = tx.send(color);
+                        });
+                        picker = Some(rx);
+                        pick_button.set_enabled(false);
+                        needs_redraw = true;
+                    }
+                }
+
+                needs_redraw |= ok_button.process_event(&event);
+                submit |= ok_button.was_clicked();
+                needs_redraw |= cancel_button.process_event(&event);
+                if cancel_button.was_clicked() {
+                    return Ok(ColorResult::Cancelled);
+                }
+
+                next = window.poll_for_event()?;
+            }
+
+            if let Some(color) = picked {
+                hsv = rgb_to_hsv(color);
+                hex_input.set_text(&to_hex(color));
+                needs_redraw = true;
+            }
+
+            if submit {
+                let [red, green, blue] = hsv_to_rgb(hsv);
+                if self.remember {
+                    let _ = recent_colors::save([red, green, blue]);
+                }
+                return Ok(ColorResult::Selected {
+                    red,
+                    green,
+                    blue,
+                });
+            }
+
+            if needs_redraw {
+                if hsv.0 != sv_hue {
+                    sv_canvas = sv_square(hsv.0, sv_size);
+                    sv_hue = hsv.0;
+                }
+                draw(
+                    &mut canvas,
+                    &sv_canvas,
+                    hsv,
+                    &hex_input,
+                    &pick_button,
+                    picker.is_some(),
+                    &ok_button,
+                    &cancel_button,
+                );
+                window.set_contents(&canvas)?;
+            }
+        }
+    }
+}
+
+impl Default for ColorBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What the pointer is dragging.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Drag {
+    None,
+    Sv,
+    Hue,
+}
+
+/// A labelled grid of swatches.
+struct Grid {
+    label: Label,
+    /// Top of the first row of swatches.
+    top: i32,
+    colors: Vec<[u8; 3]>,
+}
+
+impl Grid {
+    fn swatch_pos(&self, index: usize, left: i32, size: u32, gap: u32) -> (i32, i32) {
+        let step = (size + gap) as i32;
+        let col = (index % SWATCH_COLUMNS) as i32;
+        let row = (index / SWATCH_COLUMNS) as i32;
+        (left + col * step, self.top + row * step)
+    }
+}
+
+/// Parses `#rrggbb`, `#rgb` or `rgb(r,g,b)`.
+pub(crate) fn parse_color(text: &str) -> Option<[u8; 3]> {
+    let text = text.trim();
+    if let Some(hex) = text.strip_prefix('#') {
+        if !hex.is_ascii() {
+            return None;
+        }
+        let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+        return match hex.len() {
+            6 => {
+                Some([
+                    channel(&hex[0..2])?,
+                    channel(&hex[2..4])?,
+                    channel(&hex[4..6])?,
+                ])
+            }
+            3 => {
+                let mut out = [0; 3];
+                for (i, o) in out.iter_mut().enumerate() {
+                    *o = channel(&hex[i..i + 1])? * 17;
+                }
+                Some(out)
+            }
+            _ => None,
+        };
+    }
+
+    let inner = text
+        .strip_prefix("rgb(")
+        .or_else(|| text.strip_prefix("rgba("))?
+        .strip_suffix(')')?;
+    let mut parts = inner.split(',').map(|p| p.trim().parse::<u8>().ok());
+    Some([parts.next()??, parts.next()??, parts.next()??])
+}
+
+/// Parses a palette file line: a color as for [`parse_color`], or the
+/// `R G B name` lines of GIMP palettes.
+fn parse_palette_line(line: &str) -> Option<[u8; 3]> {
+    parse_color(line).or_else(|| {
+        let mut parts = line.split_whitespace().map(|p| p.parse::<u8>().ok());
+        Some([parts.next()??, parts.next()??, parts.next()??])
+    })
+}
+
+fn to_hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn to_rgba([r, g, b]: [u8; 3]) -> Rgba {
+    rgb(r, g, b)
+}
+
+/// Position within `len` pixels as 0.0–1.0, clamped.
+fn fraction(offset: i32, len: u32) -> f32 {
+    (offset as f32 / (len.max(2) - 1) as f32).clamp(0.0, 1.0)
+}
+
+fn in_rect(x: i32, y: i32, (rx, ry, w, h): (i32, i32, u32, u32)) -> bool {
+    point_in_rect(x, y, rx, ry, w, h)
+}
+
+fn stroke_rect(canvas: &mut Canvas, (x, y, w, h): (i32, i32, u32, u32), color: Rgba) {
+    canvas.stroke_rounded_rect(x as f32, y as f32, w as f32, h as f32, 0.0, color, 1.0);
+}
+
+/// Hue in degrees, saturation and value in 0.0–1.0.
+fn rgb_to_hsv([r, g, b]: [u8; 3]) -> (f32, f32, f32) {
+    let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    (hue, saturation, max)
+}
+
+fn hsv_to_rgb((h, s, v): (f32, f32, f32)) -> [u8; 3] {
+    let c = v * s;
+    let h = (h % 360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    [r, g, b].map(|channel| ((channel + m) * 255.0).round() as u8)
+}
+
+/// Renders the saturation (left to right) and value (top to bottom) square
+/// for a hue.
+fn sv_square(hue: f32, size: u32) -> Canvas {
+    let mut canvas = Canvas::new(size, size);
+    let data = canvas.pixmap.data_mut();
+    for y in 0..size {
+        let v = 1.0 - fraction(y as i32, size);
+        for x in 0..size {
+            let s = fraction(x as i32, size);
+            let [r, g, b] = hsv_to_rgb((hue, s, v));
+            let o = ((y * size + x) * 4) as usize;
+            data[o..o + 4].copy_from_slice(&[r, g, b, 255]);
+        }
+    }
+    canvas
+}
+
+/// Renders the hue bar, red at the top through the spectrum back to red.
+fn hue_bar(width: u32, height: u32) -> Canvas {
+    let mut canvas = Canvas::new(width, height);
+    for y in 0..height {
+        let color = hsv_to_rgb((fraction(y as i32, height) * 360.0, 1.0, 1.0));
+        canvas.fill_rect(0.0, y as f32, width as f32, 1.0, to_rgba(color));
+    }
+    canvas
+}
@@ -0,0 +1,362 @@
+//! Countdown dialog implementation.
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    backend::{Window, WindowEvent},
+    error::Error,
+    render::{Canvas, Font},
+    ui::{
+        Theme, WindowPlacement, create_window,
+        strings::tr,
+        widgets::{
+            Widget, button::Button, focus::FocusManager, label::Label, progress_bar::ProgressBar,
+        },
+    },
+};
+
+const BASE_PADDING: u32 = 20;
+const BASE_GAP: u32 = 12;
+const BASE_TIME_FONT_SIZE: f32 = 56.0;
+const BASE_CONTENT_WIDTH: u32 = 340;
+const BASE_BAR_HEIGHT: u32 = 20;
+/// How often the display is refreshed while counting down.
+const TICK: Duration = Duration::from_millis(50);
+
+const KEY_ESCAPE: u32 = 0xff1b;
+const KEY_SPACE: u32 = 0x0020;
+
+/// Countdown dialog result.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CountdownResult {
+    /// The time elapsed.
+    Elapsed,
+    /// User clicked Cancel or pressed Escape.
+    Cancelled,
+    /// Dialog was closed.
+    Closed,
+}
+
+impl CountdownResult {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CountdownResult::Elapsed => 0,
+            CountdownResult::Cancelled => 1,
+            CountdownResult::Closed => 255,
+        }
+    }
+}
+
+/// Countdown dialog builder.
+pub struct CountdownBuilder {
+    title: String,
+    text: String,
+    seconds: u32,
+    width: Option<u32>,
+    height: Option<u32>,
+    colors: Option<Theme>,
+    pub(super) placing: WindowPlacement,
+}
+
+impl CountdownBuilder {
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            text: String::new(),
+            seconds: 60,
+            width: None,
+            height: None,
+            colors: None,
+            placing: WindowPlacement::default(),
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Text shown above the remaining time (e.g. "Rebooting in").
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    /// Length of the countdown in seconds.
+    pub fn seconds(mut self, seconds: u32) -> Self {
+        self.seconds = seconds;
+        self
+    }
+
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
+        self
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn show(self) -> Result<CountdownResult, Error> {
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
+        let total = Duration::from_secs(self.seconds as u64);
+
+        // Measure the text at logical scale to size the window
+        let temp_font = colors.font(1.0);
+        let logical_content_w = self.width.map_or(BASE_CONTENT_WIDTH, |w| {
+            w.saturating_sub(colors.space(BASE_PADDING) * 2)
+        });
+        let mut temp_text = Label::new(&self.text).with_wrap(true);
+        temp_text.layout(&temp_font, colors.text, Some(logical_content_w));
+        let text_h = if self.text.is_empty() {
+            0
+        } else {
+            temp_text.height() + colors.space(BASE_GAP)
+        };
+        let time_h = Font::load_with_size(BASE_TIME_FONT_SIZE)
+            .render("0:00")
+            .measure()
+            .1 as u32;
+        drop(temp_font);
+
+        let logical_width = self
+            .width
+            .unwrap_or(BASE_CONTENT_WIDTH + colors.space(BASE_PADDING) * 2);
+        let logical_height = self.height.unwrap_or(
+            colors.space(BASE_PADDING) * 2
+                + text_h
+                + time_h
+                + colors.space(BASE_GAP) * 3
+                + BASE_BAR_HEIGHT
+                + colors.control_height,
+        );
+
+        let mut window = create_window(
+            logical_width as u16,
+            logical_height as u16,
+            colors,
+            &self.placing,
+        )?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Countdown")
+        } else {
+            self.title.as_str().into()
+        })?;
+
+        // Get the actual scale factor from the window (compositor scale)
+        let scale = window.scale_factor();
+        let physical_width = (logical_width as f32 * scale) as u32;
+        let physical_height = (logical_height as f32 * scale) as u32;
+
+        // Now create everything at PHYSICAL scale
+        let font = colors.font(scale);
+        let time_font = Font::load_with_size(BASE_TIME_FONT_SIZE * scale);
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
+        let gap = (colors.space(BASE_GAP) as f32 * scale) as u32;
+        let content_w = physical_width.saturating_sub(padding * 2);
+
+        let mut text = Label::new(&self.text).with_wrap(true);
+        text.layout(&font, colors.text, Some(content_w));
+        text.set_position(padding as i32, padding as i32);
+
+        let mut cancel_button = Button::new(&tr("_Cancel"), &font, colors, scale);
+        let mut pause_button = Button::new(&tr("_Pause"), &font, colors, scale);
+        let mut resume_button = Button::new(&tr("_Resume"), &font, colors, scale);
+        // Pause and Resume swap places, so give them the same width
+        let toggle_w = pause_button.width().max(resume_button.width());
+        pause_button.set_width(toggle_w);
+        resume_button.set_width(toggle_w);
+
+        let button_y = (physical_height - padding) as i32 - cancel_button.height() as i32;
+        let mut button_x = physical_width as i32 - padding as i32;
+        button_x -= cancel_button.width() as i32;
+        cancel_button.set_position(button_x, button_y);
+        button_x -= (gap + toggle_w) as i32;
+        pause_button.set_position(button_x, button_y);
+        resume_button.set_position(button_x, button_y);
+
+        let mut progress_bar = ProgressBar::new(content_w, scale);
+        let bar_y = button_y - (gap * 2) as i32 - progress_bar.height() as i32;
+        progress_bar.set_position(padding as i32, bar_y);
+        progress_bar.set_progress(1.0);
+
+        let time_top = if self.text.is_empty() {
+            padding as i32
+        } else {
+            padding as i32 + (text.height() + gap) as i32
+        };
+        let time_bottom = bar_y - gap as i32;
+
+        let mut canvas = Canvas::new(physical_width, physical_height);
+
+        let draw = |canvas: &mut Canvas,
+                    remaining: Duration,
+                    paused: bool,
+                    progress_bar: &ProgressBar,
+                    pause_button: &Button,
+                    resume_button: &Button,
+                    cancel_button: &Button| {
+            let width = canvas.width() as f32;
+            let height = canvas.height() as f32;
+            canvas.fill_dialog_bg(
+                width,
+                height,
+                colors.window_bg,
+                colors.window_border,
+                colors.window_radius * scale,
+                colors.border_width,
+            );
+
+            text.draw(canvas, colors);
+
+            // Dim the time while paused
+            let time_color = if paused {
+                colors.input_placeholder
+            } else {
+                colors.text
+            };
+            let time = time_font
+                .render(&format_remaining(remaining))
+                .with_color(time_color)
+                .finish();
+            let time_x = (physical_width as i32 - time.width() as i32) / 2;
+            let time_y = time_top + (time_bottom - time_top - time.height() as i32).max(0) / 2;
+            canvas.draw_canvas(&time, time_x, time_y);
+
+            progress_bar.draw(canvas, colors);
+            if paused {
+                resume_button.draw_to(canvas, colors, &font);
+            } else {
+                pause_button.draw_to(canvas, colors, &font);
+            }
+            cancel_button.draw_to(canvas, colors, &font);
+        };
+
+        let mut deadline = Instant::now() + total;
+        // Time left when paused; the deadline is moved on resume
+        let mut paused_at: Option<Duration> = None;
+        let mut shown_secs = total.as_secs();
+        let mut focus = FocusManager::new();
+
+        draw(
+            &mut canvas,
+            total,
+            false,
+            &progress_bar,
+            &pause_button,
+            &resume_button,
+            &cancel_button,
+        );
+        window.set_contents(&canvas)?;
+        window.show()?;
+
+        loop {
+            let remaining =
+                paused_at.unwrap_or_else(|| deadline.saturating_duration_since(Instant::now()));
+            if remaining.is_zero() {
+                return Ok(CountdownResult::Elapsed);
+            }
+
+            let mut needs_redraw = false;
+            let mut toggle = false;
+
+            while let Some(event) = window.poll_for_event()? {
+                let toggle_button = if paused_at.is_some() {
+                    &mut resume_button
+                } else {
+                    &mut pause_button
+                };
+                let event = focus.process_event(event, &mut [toggle_button, &mut cancel_button]);
+                match &event {
+                    WindowEvent::CloseRequested => return Ok(CountdownResult::Closed),
+                    WindowEvent::RedrawRequested => needs_redraw = true,
+                    WindowEvent::KeyPress(key) => {
+                        match key.keysym {
+                            KEY_ESCAPE => return Ok(CountdownResult::Cancelled),
+                            KEY_SPACE => toggle = true,
+                            _ => {}
+                        }
+                    }
+                    _ => {}
+                }
+
+                let toggle_button = if paused_at.is_some() {
+                    &mut resume_button
+                } else {
+                    &mut pause_button
+                };
+                needs_redraw |= toggle_button.process_event(&event);
+                toggle |= toggle_button.was_clicked();
+
+                needs_redraw |= cancel_button.process_event(&event);
+                if cancel_button.was_clicked() {
+                    return Ok(CountdownResult::Cancelled);
+                }
+            }
+
+            if toggle {
+                paused_at = match paused_at {
+                    Some(left) => {
+                        deadline = Instant::now() + left;
+                        None
+                    }
+                    None => Some(remaining),
+                };
+                // Focus passes to the button taking the toggle's place
+                let toggle_focused = focus.focused() == Some(0);
+                pause_button.set_focus(toggle_focused && paused_at.is_none());
+                resume_button.set_focus(toggle_focused && paused_at.is_some());
+                needs_redraw = true;
+            }
+
+            let remaining =
+                paused_at.unwrap_or_else(|| deadline.saturating_duration_since(Instant::now()));
+            // Redraw when the seconds change or the bar has moved a pixel
+            let fraction = remaining.as_secs_f32() / total.as_secs_f32();
+            let secs = remaining.as_secs_f64().ceil() as u64;
+            needs_redraw |= secs != shown_secs
+                || (progress_bar.progress() - fraction).abs() * content_w as f32 >= 1.0;
+
+            if needs_redraw {
+                progress_bar.set_progress(fraction);
+                shown_secs = secs;
+                draw(
+                    &mut canvas,
+                    remaining,
+                    paused_at.is_some(),
+                    &progress_bar,
+                    &pause_button,
+                    &resume_button,
+                    &cancel_button,
+                );
+                window.set_contents(&canvas)?;
+            }
+
+            std::thread::sleep(TICK);
+        }
+    }
+}
+
+impl Default for CountdownBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Formats whole seconds left, rounded up, as `M:SS` or `H:MM:SS`.
+fn format_remaining(remaining: Duration) -> String {
+    let secs = remaining.as_secs_f64().ceil() as u64;
+    let (hours, mins, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}:{mins:02}:{secs:02}")
+    } else {
+        format!("{mins}:{secs:02}")
+    }
+}
@@ -0,0 +1,790 @@
+//! Custom dialogs, put together from widgets for what none of the other
+//! dialogs ask.
+//!
+//! Widgets are stacked top to bottom in the order they are added, filling
+//! the dialog's width, with a row of buttons under them.
+
+use std::collections::HashSet;
+
+use crate::{
+    backend::{CursorShape, MouseButton, ScrollDirection, Window, WindowEvent},
+    error::Error,
+    render::{Canvas, Font},
+    ui::{
+        Theme, WindowPlacement, create_window,
+        hooks::Hooks,
+        strings::tr,
+        widgets::{
+            Widget, button::Button, focus::FocusManager, label::Label, list_pane::ListPane,
+            point_in_rect, text_input::TextInput,
+        },
+    },
+};
+
+const BASE_PADDING: u32 = 20;
+const BASE_SPACING: u32 = 10;
+const BASE_CONTENT_WIDTH: u32 = 300;
+const BASE_ROW_HEIGHT: u32 = 26;
+/// Rows a list shows before it scrolls
+const LIST_ROWS: u32 = 6;
+
+const KEY_ESCAPE: u32 = 0xff1b;
+const KEY_RETURN: u32 = 0xff0d;
+const KEY_KP_ENTER: u32 = 0xff8d;
+const KEY_HOME: u32 = 0xff50;
+const KEY_UP: u32 = 0xff52;
+const KEY_DOWN: u32 = 0xff54;
+const KEY_PAGE_UP: u32 = 0xff55;
+const KEY_PAGE_DOWN: u32 = 0xff56;
+const KEY_END: u32 = 0xff57;
+
+/// What a widget holds: an entry's text, or the row selected in a list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Value {
+    Text(String),
+    Selection(Option<usize>),
+}
+
+/// The values of a dialog's entries and lists, by their ids.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Values(Vec<(String, Value)>);
+
+impl Values {
+    pub fn get(&self, id: &str) -> Option<&Value> {
+        self.0
+            .iter()
+            .find(|(key, _)| key == id)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns the text of the entry `id`.
+    pub fn text(&self, id: &str) -> Option<&str> {
+        match self.get(id)? {
+            Value::Text(text) => Some(text),
+            Value::Selection(_) => None,
+        }
+    }
+
+    /// Returns the row selected in the list `id`, if one is.
+    pub fn selection(&self, id: &str) -> Option<usize> {
+        match self.get(id)? {
+            Value::Selection(row) => *row,
+            Value::Text(_) => None,
+        }
+    }
+
+    /// Iterates over the values in the order their widgets were added.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.0.iter().map(|(id, value)| (id.as_str(), value))
+    }
+}
+
+/// A change the user made to an entry or list, as passed to
+/// [`DialogBuilder::on_change`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub id: String,
+    pub value: Value,
+}
+
+/// Custom dialog result.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CustomResult {
+    /// A button was clicked, or Enter pressed for the first one. Carries
+    /// the button's index and the values of the entries and lists.
+    Button(usize, Values),
+    /// User pressed Escape.
+    Cancelled,
+    /// Dialog was closed.
+    Closed,
+}
+
+impl CustomResult {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            // As for messages: 0 to 2 for the first buttons, 3 for the rest
+            CustomResult::Button(idx, _) => (*idx).min(3) as i32,
+            CustomResult::Cancelled => 1,
+            CustomResult::Closed => 255,
+        }
+    }
+}
+
+/// A widget as added to the builder.
+enum Item {
+    Label(String),
+    Entry {
+        id: String,
+        text: String,
+        hidden: bool,
+    },
+    List {
+        id: String,
+        rows: Vec<String>,
+    },
+}
+
+impl Item {
+    fn id(&self) -> Option<&str> {
+        match self {
+            Item::Label(_) => None,
+            Item::Entry {
+                id, ..
+            }
+            | Item::List {
+                id, ..
+            } => Some(id),
+        }
+    }
+}
+
+/// Custom dialog builder.
+///
+/// ```no_run
+/// use zenity_rs::custom::{CustomResult, DialogBuilder};
+///
+/// let result = DialogBuilder::new()
+///     .title("Connect")
+///     .label("Server:")
+///     .entry("host", "example.org")
+///     .label("Protocol:")
+///     .list("protocol", &["SSH", "SFTP", "FTP"])
+///     .button("_Connect")
+///     .button("Cancel")
+///     .show()?;
+/// if let CustomResult::Button(0, values) = result {
+///     println!("{:?} over {:?}", values.text("host"), values.selection("protocol"));
+/// }
+/// # Ok::<(), zenity_rs::Error>(())
+/// ```
+pub struct DialogBuilder {
+    title: String,
+    items: Vec<Item>,
+    buttons: Vec<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    colors: Option<Theme>,
+    hooks: Hooks<Change>,
+    pub(super) placing: WindowPlacement,
+}
+
+impl DialogBuilder {
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            items: Vec::new(),
+            buttons: Vec::new(),
+            width: None,
+            height: None,
+            colors: None,
+            hooks: Hooks::default(),
+            placing: WindowPlacement::default(),
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Adds text, wrapped to the dialog's width.
+    pub fn label(mut self, text: &str) -> Self {
+        self.items.push(Item::Label(text.to_string()));
+        self
+    }
+
+    /// Adds a text field starting with `text`, whose text is returned as
+    /// `id`.
+    pub fn entry(mut self, id: &str, text: &str) -> Self {
+        self.items.push(Item::Entry {
+            id: id.to_string(),
+            text: text.to_string(),
+            hidden: false,
+        });
+        self
+    }
+
+    /// Adds a text field whose text is hidden, returned as `id`.
+    pub fn password(mut self, id: &str) -> Self {
+        self.items.push(Item::Entry {
+            id: id.to_string(),
+            text: String::new(),
+            hidden: true,
+        });
+        self
+    }
+
+    /// Adds a list of rows to pick one of, whose selected row is returned
+    /// as `id`. Nothing is selected at first.
+    pub fn list(mut self, id: &str, rows: &[&str]) -> Self {
+        self.items.push(Item::List {
+            id: id.to_string(),
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+        });
+        self
+    }
+
+    /// Adds a button to the row under the widgets, after those added
+    /// before it. The first is the default, clicked by Enter. Without any,
+    /// the dialog has an OK button.
+    pub fn button(mut self, label: &str) -> Self {
+        self.buttons.push(label.to_string());
+        self
+    }
+
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
+        self
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Calls `f` each time the user edits an entry or selects a row in a
+    /// list.
+    pub fn on_change(mut self, f: impl FnMut(&Change) + Send + 'static) -> Self {
+        self.hooks.change = Some(Box::new(f));
+        self
+    }
+
+    /// Calls `f` with the index of the button the dialog is dismissed
+    /// with.
+    pub fn on_button(mut self, f: impl FnMut(usize) + Send + 'static) -> Self {
+        self.hooks.button = Some(Box::new(f));
+        self
+    }
+
+    /// Calls `f` when the dialog is cancelled or closed.
+    pub fn on_cancel(mut self, f: impl FnMut() + Send + 'static) -> Self {
+        self.hooks.cancel = Some(Box::new(f));
+        self
+    }
+
+    /// Checks that no two entries or lists share an id, as `show` does
+    /// before showing the dialog.
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut ids = HashSet::new();
+        for id in self.items.iter().filter_map(Item::id) {
+            if !ids.insert(id) {
+                return Err(Error::InvalidConfig(format!(
+                    "two widgets have the id \"{id}\""
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn show(mut self) -> Result<CustomResult, Error> {
+        self.validate()?;
+        let mut hooks = std::mem::take(&mut self.hooks);
+        let result = self.run(&mut hooks)?;
+        let button = match result {
+            CustomResult::Button(idx, _) => Some(idx),
+            CustomResult::Cancelled | CustomResult::Closed => None,
+        };
+        hooks.dismissed(button, button.is_none());
+        Ok(result)
+    }
+
+    fn run(&self, hooks: &mut Hooks<Change>) -> Result<CustomResult, Error> {
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
+
+        // First pass: calculate LOGICAL dimensions using scale 1.0
+        let temp_font = colors.font(1.0);
+        let padding = colors.space(BASE_PADDING);
+        let spacing = colors.space(BASE_SPACING);
+        let temp_buttons = self.make_buttons(&temp_font, colors, 1.0);
+        let buttons_width = temp_buttons
+            .iter()
+            .map(|b| b.width() + spacing)
+            .sum::<u32>()
+            - spacing;
+        let logical_width = self
+            .width
+            .unwrap_or(BASE_CONTENT_WIDTH.max(buttons_width) + padding * 2);
+        let temp_controls = self.make_controls(
+            &temp_font,
+            colors,
+            1.0,
+            logical_width.saturating_sub(padding * 2),
+        );
+        let calc_height = padding * 2
+            + temp_controls
+                .iter()
+                .map(|c| c.height() + spacing)
+                .sum::<u32>()
+            + colors.control_height;
+        let logical_height = self.height.unwrap_or(calc_height);
+
+        drop(temp_font);
+        drop(temp_buttons);
+        drop(temp_controls);
+
+        // Create window with LOGICAL dimensions
+        let mut window = create_window(
+            logical_width as u16,
+            logical_height as u16,
+            colors,
+            &self.placing,
+        )?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Dialog")
+        } else {
+            self.title.as_str().into()
+        })?;
+
+        // Get the actual scale factor from the window (compositor scale)
+        let scale = window.scale_factor();
+        let physical_width = (logical_width as f32 * scale) as u32;
+        let physical_height = (logical_height as f32 * scale) as u32;
+
+        // Now create everything at PHYSICAL scale
+        let font = colors.font(scale);
+        let padding = (padding as f32 * scale) as u32;
+        let spacing = (spacing as f32 * scale) as u32;
+
+        // Widgets top to bottom, buttons right-aligned at the bottom
+        let mut controls = self.make_controls(
+            &font,
+            colors,
+            scale,
+            physical_width.saturating_sub(padding * 2),
+        );
+        let mut y = padding as i32;
+        for control in &mut controls {
+            control.set_position(padding as i32, y);
+            y += (control.height() + spacing) as i32;
+        }
+        let mut buttons = self.make_buttons(&font, colors, scale);
+        let button_y = (physical_height - padding) as i32 - buttons[0].height() as i32;
+        let mut bx = physical_width as i32 - padding as i32;
+        for button in buttons.iter_mut().rev() {
+            bx -= button.width() as i32;
+            button.set_position(bx, button_y);
+            bx -= spacing as i32;
+        }
+        buttons[0].set_default(true);
+
+        let mut focus = FocusManager::new();
+        focus.focus(
+            controls.iter().position(Widget::is_focusable),
+            &mut focus_order(&mut controls, &mut buttons),
+        );
+
+        let mut canvas = Canvas::new(physical_width, physical_height);
+
+        let draw = |canvas: &mut Canvas, controls: &[Control], buttons: &[Button]| {
+            let width = canvas.width() as f32;
+            let height = canvas.height() as f32;
+            canvas.fill_dialog_bg(
+                width,
+                height,
+                colors.window_bg,
+                colors.window_border,
+                colors.window_radius * scale,
+                colors.border_width,
+            );
+
+            for control in controls {
+                control.draw_to(canvas, colors, &font);
+            }
+            for button in buttons {
+                button.draw_to(canvas, colors, &font);
+            }
+        };
+
+        draw(&mut canvas, &controls, &buttons);
+        window.set_contents(&canvas)?;
+        window.show()?;
+
+        // Event loop; each pass handles every pending event, then redraws once
+        let mut last_values = values(&controls);
+        loop {
+            let mut next = Some(window.wait_for_event()?);
+            let mut needs_redraw = false;
+            let mut clicked = None;
+
+            while let Some(event) = next.take() {
+                let event =
+                    focus.process_event(event, &mut focus_order(&mut controls, &mut buttons));
+
+                match &event {
+                    WindowEvent::CloseRequested => return Ok(CustomResult::Closed),
+                    WindowEvent::RedrawRequested => needs_redraw = true,
+                    WindowEvent::KeyPress(key) => {
+                        match key.keysym {
+                            KEY_ESCAPE => return Ok(CustomResult::Cancelled),
+                            KEY_RETURN | KEY_KP_ENTER => clicked = Some(0),
+                            _ => {}
+                        }
+                    }
+                    WindowEvent::CursorMove(pos) => {
+                        let (x, y) = (pos.x as i32, pos.y as i32);
+                        let over_input = controls.iter().any(|control| {
+                            matches!(control, Control::Entry { .. })
+                                && point_in_rect(
+                                    x,
+                                    y,
+                                    control.x(),
+                                    control.y(),
+                                    control.width(),
+                                    control.height(),
+                                )
+                        });
+                        let _ = window.set_cursor(if over_input {
+                            CursorShape::Text
+                        } else {
+                            CursorShape::Default
+                        });
+                    }
+                    _ => {}
+                }
+
+                for control in &mut controls {
+                    needs_redraw |= control.process_event(&event);
+                    if control.was_submitted() {
+                        clicked = Some(0);
+                    }
+                }
+                for (idx, button) in buttons.iter_mut().enumerate() {
+                    needs_redraw |= button.process_event(&event);
+                    if button.was_clicked() {
+                        clicked = Some(idx);
+                    }
+                }
+
+                next = window.poll_for_event()?;
+            }
+
+            let values = values(&controls);
+            for ((id, value), (_, last)) in values.0.iter().zip(&last_values.0) {
+                if value != last {
+                    hooks.changed(&Change {
+                        id: id.clone(),
+                        value: value.clone(),
+                    });
+                }
+            }
+            if let Some(idx) = clicked {
+                return Ok(CustomResult::Button(idx, values));
+            }
+            last_values = values;
+
+            if needs_redraw {
+                draw(&mut canvas, &controls, &buttons);
+                window.set_contents(&canvas)?;
+            }
+        }
+    }
+
+    /// Creates the widgets at `scale`, `width` wide, all at the origin.
+    fn make_controls(&self, font: &Font, colors: &Theme, scale: f32, width: u32) -> Vec<Control> {
+        let row_height = (BASE_ROW_HEIGHT as f32 * scale) as u32;
+        self.items
+            .iter()
+            .map(|item| {
+                match item {
+                    Item::Label(text) => {
+                        let mut label = Label::new(text).with_wrap(true);
+                        label.layout(font, colors.text, Some(width));
+                        Control::Label(label)
+                    }
+                    Item::Entry {
+                        id,
+                        text,
+                        hidden,
+                    } => {
+                        Control::Entry {
+                            id: id.clone(),
+                            input: TextInput::new(width)
+                                .with_password(*hidden)
+                                .with_default_text(text),
+                        }
+                    }
+                    Item::List {
+                        id,
+                        rows,
+                    } => {
+                        let mut list =
+                            ListBox::new(width, row_height * LIST_ROWS, row_height, scale);
+                        list.pane.set_items(rows.clone(), None);
+                        Control::List {
+                            id: id.clone(),
+                            list,
+                        }
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn make_buttons(&self, font: &Font, colors: &Theme, scale: f32) -> Vec<Button> {
+        if self.buttons.is_empty() {
+            return vec![Button::new(&tr("OK"), font, colors, scale)];
+        }
+        self.buttons
+            .iter()
+            .map(|label| Button::new(label, font, colors, scale))
+            .collect()
+    }
+}
+
+impl Default for DialogBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A widget of the dialog, as laid out.
+enum Control {
+    Label(Label),
+    Entry { id: String, input: TextInput },
+    List { id: String, list: ListBox },
+}
+
+impl Control {
+    /// The widget's id and value, if it has them.
+    fn value(&self) -> Option<(String, Value)> {
+        match self {
+            Control::Label(_) => None,
+            Control::Entry {
+                id,
+                input,
+            } => Some((id.clone(), Value::Text(input.text().to_string()))),
+            Control::List {
+                id,
+                list,
+            } => Some((id.clone(), Value::Selection(list.pane.selected))),
+        }
+    }
+
+    /// Returns true if Enter was pressed in a text field.
+    fn was_submitted(&mut self) -> bool {
+        match self {
+            Control::Entry {
+                input, ..
+            } => input.was_submitted(),
+            Control::Label(_)
+            | Control::List {
+                ..
+            } => false,
+        }
+    }
+
+    fn draw_to(&self, canvas: &mut Canvas, colors: &Theme, font: &Font) {
+        match self {
+            Control::Label(label) => label.draw(canvas, colors),
+            Control::Entry {
+                input, ..
+            } => input.draw_to(canvas, colors, font),
+            Control::List {
+                list, ..
+            } => list.draw_to(canvas, colors, font),
+        }
+    }
+
+    fn widget(&self) -> &dyn Widget {
+        match self {
+            Control::Label(label) => label,
+            Control::Entry {
+                input, ..
+            } => input,
+            Control::List {
+                list, ..
+            } => list,
+        }
+    }
+
+    fn widget_mut(&mut self) -> &mut dyn Widget {
+        match self {
+            Control::Label(label) => label,
+            Control::Entry {
+                input, ..
+            } => input,
+            Control::List {
+                list, ..
+            } => list,
+        }
+    }
+}
+
+impl Widget for Control {
+    fn width(&self) -> u32 {
+        self.widget().width()
+    }
+
+    fn height(&self) -> u32 {
+        self.widget().height()
+    }
+
+    fn x(&self) -> i32 {
+        self.widget().x()
+    }
+
+    fn y(&self) -> i32 {
+        self.widget().y()
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.widget_mut().set_position(x, y);
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        self.widget_mut().process_event(event)
+    }
+
+    fn draw(&self, _canvas: &mut Canvas, _colors: &Theme) {
+        // Use draw_to instead for font access
+    }
+
+    fn is_focusable(&self) -> bool {
+        self.widget().is_focusable()
+    }
+
+    fn set_focus(&mut self, focused: bool) {
+        self.widget_mut().set_focus(focused);
+    }
+}
+
+/// A [`ListPane`] taking keyboard focus like the other widgets, moved
+/// through with the arrow keys while it has it.
+struct ListBox {
+    pane: ListPane,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    pointer: (i32, i32),
+    focused: bool,
+    scale: f32,
+}
+
+impl ListBox {
+    fn new(width: u32, height: u32, row_height: u32, scale: f32) -> Self {
+        Self {
+            pane: ListPane::new(0, 0, width, height, row_height),
+            x: 0,
+            y: 0,
+            width,
+            height,
+            pointer: (-1, -1),
+            focused: false,
+            scale,
+        }
+    }
+
+    fn draw_to(&self, canvas: &mut Canvas, colors: &Theme, font: &Font) {
+        self.pane
+            .draw(canvas, font, colors, self.focused, self.scale);
+    }
+}
+
+impl Widget for ListBox {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+        self.pane.set_position(x, y);
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        let (px, py) = self.pointer;
+        match event {
+            WindowEvent::CursorMove(pos) | WindowEvent::CursorEnter(pos) => {
+                self.pointer = (pos.x as i32, pos.y as i32);
+                self.pane.hover(self.pointer.0, self.pointer.1)
+            }
+            WindowEvent::CursorLeave => {
+                self.pointer = (-1, -1);
+                self.pane.hover(-1, -1)
+            }
+            WindowEvent::ButtonPress(MouseButton::Left, _) => {
+                match self.pane.row_at(px, py) {
+                    Some(row) => {
+                        self.pane.select(Some(row));
+                        true
+                    }
+                    None => false,
+                }
+            }
+            WindowEvent::Scroll(direction) if self.pane.contains(px, py) => {
+                match direction {
+                    ScrollDirection::Up => self.pane.scroll(-3),
+                    ScrollDirection::Down => self.pane.scroll(3),
+                    _ => return false,
+                }
+                true
+            }
+            WindowEvent::KeyPress(key) if self.focused => {
+                let page = self.pane.visible_rows() as isize;
+                // Home and End step as far as the list goes
+                let rows = match key.keysym {
+                    KEY_UP => -1,
+                    KEY_DOWN => 1,
+                    KEY_PAGE_UP => -page,
+                    KEY_PAGE_DOWN => page,
+                    KEY_HOME => isize::MIN,
+                    KEY_END => isize::MAX,
+                    _ => return false,
+                };
+                self.pane.step(rows);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn draw(&self, _canvas: &mut Canvas, _colors: &Theme) {
+        // Use draw_to instead for font access
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focus(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+}
+
+/// The values of the widgets that have them, in order.
+fn values(controls: &[Control]) -> Values {
+    Values(controls.iter().filter_map(Control::value).collect())
+}
+
+/// The dialog's widgets in Tab order: the ones added, then the buttons.
+fn focus_order<'a>(
+    controls: &'a mut [Control],
+    buttons: &'a mut [Button],
+) -> Vec<&'a mut dyn Widget> {
+    controls
+        .iter_mut()
+        .map(|control| control as &mut dyn Widget)
+        .chain(buttons.iter_mut().map(|button| button as &mut dyn Widget))
+        .collect()
+}
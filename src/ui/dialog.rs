@@ -0,0 +1,167 @@
+//! Dialogs of any kind, shown through one trait.
+
+use crate::{
+    error::Error,
+    ui::{
+        DialogResult, Placement, WindowHandle,
+        calendar::{CalendarBuilder, CalendarResult},
+        chooser::{ChooserBuilder, ChooserResult},
+        color::{ColorBuilder, ColorResult},
+        countdown::{CountdownBuilder, CountdownResult},
+        custom::{CustomResult, DialogBuilder},
+        entry::{EntryBuilder, EntryResult},
+        file_select::{FileSelectBuilder, FileSelectResult},
+        font_select::{FontSelectBuilder, FontSelectResult},
+        forms::{FormsBuilder, FormsResult},
+        handle::DialogHandle,
+        icon_select::{IconSelectBuilder, IconSelectResult},
+        image_view::{ImageBuilder, ImageResult},
+        list::{ListBuilder, ListResult},
+        login::{LoginBuilder, LoginResult},
+        message::MessageBuilder,
+        progress::{ProgressBuilder, ProgressResult},
+        qr::{QrBuilder, QrResult},
+        scale::{ScaleBuilder, ScaleResult},
+        text_info::{TextInfoBuilder, TextInfoResult},
+    },
+};
+
+/// A dialog builder shown without knowing which kind of dialog it is, so
+/// that dialogs of different kinds can be queued and shown in turn.
+///
+/// ```no_run
+/// use zenity_rs::Dialog;
+///
+/// let steps: Vec<Box<dyn Dialog>> = vec![
+///     Box::new(zenity_rs::info("This will set up the printer.")),
+///     Box::new(zenity_rs::entry().text("Printer name:")),
+///     Box::new(zenity_rs::question("Print a test page?")),
+/// ];
+/// for step in steps {
+///     if step.show_boxed()?.exit_code() != 0 {
+///         break;
+///     }
+/// }
+/// # Ok::<(), zenity_rs::Error>(())
+/// ```
+///
+/// The tray icon isn't a dialog, and has no implementation.
+pub trait Dialog: Send {
+    /// Shows the dialog as the builder's `show` does, returning its result
+    /// as a [`DialogOutcome`].
+    fn show_boxed(self: Box<Self>) -> Result<DialogOutcome, Error>;
+}
+
+/// Generates [`DialogOutcome`] with a variant for each builder's result,
+/// along with the conversions to it, the builders' [`Dialog`]
+/// implementations and their placement methods, `spawn`, `show_async` and `render_to_image`.
+macro_rules! outcomes {
+    ($($variant:ident($builder:ty => $result:ty),)*) => {
+        /// The result of a dialog shown through [`Dialog`]: the result its
+        /// builder's `show` returns, in the variant for its kind.
+        #[derive(Debug, Clone)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        pub enum DialogOutcome {
+            $($variant($result),)*
+        }
+
+        impl DialogOutcome {
+            pub fn exit_code(&self) -> i32 {
+                match self {
+                    $(DialogOutcome::$variant(result) => result.exit_code(),)*
+                }
+            }
+        }
+
+        $(
+            impl From<$result> for DialogOutcome {
+                fn from(result: $result) -> Self {
+                    DialogOutcome::$variant(result)
+                }
+            }
+
+            impl Dialog for $builder {
+                fn show_boxed(self: Box<Self>) -> Result<DialogOutcome, Error> {
+                    self.show().map(DialogOutcome::from)
+                }
+            }
+
+            impl $builder {
+                /// Places the dialog with its top-left corner at `x`, `y` on
+                /// the screen. Takes precedence over [`crate::set_placement`].
+                pub fn position(mut self, x: i32, y: i32) -> Self {
+                    self.placing.placement = Some(Placement::At {
+                        x,
+                        y,
+                    });
+                    self
+                }
+
+                /// Centers the dialog on the screen. Takes precedence over
+                /// [`crate::set_placement`].
+                pub fn center_on_screen(mut self) -> Self {
+                    self.placing.placement = Some(Placement::Center);
+                    self
+                }
+
+                /// Attaches the dialog to another window, keeping it modal and
+                /// on top of it, and centers it there. Takes precedence over
+                /// [`crate::set_parent`].
+                pub fn center_on_parent(mut self, parent: WindowHandle) -> Self {
+                    self.placing.parent = Some(parent);
+                    self
+                }
+
+                /// Shows the dialog on a thread of its own, returning a handle
+                /// to get its result with, or to close it.
+                pub fn spawn(self) -> DialogHandle<$result> {
+                    DialogHandle::spawn(move || self.show())
+                }
+
+                /// Like [`show`](Self::show), but waits for the dialog without
+                /// blocking the async runtime.
+                #[cfg(any(feature = "tokio", feature = "async-std"))]
+                pub async fn show_async(self) -> Result<$result, Error> {
+                    crate::ui::show_blocking(move || self.show()).await
+                }
+
+                /// Draws the dialog as `show` first would, given `width` and
+                /// `height` as by the builder's own methods and drawn at
+                /// `scale`, but without a window, returning the drawing as
+                /// PNG. Fails with [`Error::BackendUnavailable`] if the dialog
+                /// shows no window.
+                pub fn render_to_image(
+                    self,
+                    width: u32,
+                    height: u32,
+                    scale: f32,
+                ) -> Result<Vec<u8>, Error> {
+                    crate::ui::render_to_image(scale, move || {
+                        self.width(width).height(height).show()
+                    })
+                }
+            }
+        )*
+    };
+}
+
+outcomes! {
+    Message(MessageBuilder => DialogResult),
+    Entry(EntryBuilder => EntryResult),
+    List(ListBuilder => ListResult),
+    Forms(FormsBuilder => FormsResult),
+    Scale(ScaleBuilder => ScaleResult),
+    Calendar(CalendarBuilder => CalendarResult),
+    Color(ColorBuilder => ColorResult),
+    FileSelect(FileSelectBuilder => FileSelectResult),
+    FontSelect(FontSelectBuilder => FontSelectResult),
+    IconSelect(IconSelectBuilder => IconSelectResult),
+    Chooser(ChooserBuilder => ChooserResult),
+    Countdown(CountdownBuilder => CountdownResult),
+    Progress(ProgressBuilder => ProgressResult),
+    TextInfo(TextInfoBuilder => TextInfoResult),
+    Image(ImageBuilder => ImageResult),
+    Qr(QrBuilder => QrResult),
+    Login(LoginBuilder => LoginResult),
+    Custom(DialogBuilder => CustomResult),
+}
@@ -1,12 +1,18 @@
 //! Entry dialog implementation for text input.
 
+use std::path::PathBuf;
+
 use crate::{
-    backend::{CursorShape, Window, WindowEvent, create_window},
+    backend::{CursorShape, Window, WindowEvent},
     error::Error,
     render::{Canvas, Font},
     ui::{
-        Colors,
-        widgets::{Widget, button::Button, text_input::TextInput},
+        Theme, WindowPlacement, create_window,
+        hooks::Hooks,
+        strings::tr,
+        widgets::{
+            Widget, button::Button, focus::FocusManager, label::Label, text_input::TextInput,
+        },
     },
 };
 
@@ -14,8 +20,11 @@ const BASE_PADDING: u32 = 20;
 const BASE_BUTTON_SPACING: u32 = 10;
 const BASE_INPUT_WIDTH: u32 = 300;
 
+const KEY_ESCAPE: u32 = 0xff1b;
+
 /// Entry dialog result.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EntryResult {
     /// User entered text and clicked OK.
     Text(String),
@@ -43,7 +52,9 @@ pub struct EntryBuilder {
     hide_text: bool,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    colors: Option<Theme>,
+    hooks: Hooks<str>,
+    pub(super) placing: WindowPlacement,
 }
 
 impl EntryBuilder {
@@ -56,6 +67,8 @@ impl EntryBuilder {
             width: None,
             height: None,
             colors: None,
+            hooks: Hooks::default(),
+            placing: WindowPlacement::default(),
         }
     }
 
@@ -79,8 +92,8 @@ impl EntryBuilder {
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
         self
     }
 
@@ -94,33 +107,65 @@ impl EntryBuilder {
         self
     }
 
-    pub fn show(self) -> Result<EntryResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+    /// Calls `f` with the text each time the user edits it, to check it
+    /// as it is typed for instance.
+    pub fn on_change(mut self, f: impl FnMut(&str) + Send + 'static) -> Self {
+        self.hooks.change = Some(Box::new(f));
+        self
+    }
 
-        // First pass: calculate LOGICAL dimensions using scale 1.0
-        let temp_font = Font::load(1.0);
-        let temp_ok = Button::new("OK", &temp_font, 1.0);
-        let temp_cancel = Button::new("Cancel", &temp_font, 1.0);
-        let temp_prompt_height = if !self.text.is_empty() {
-            temp_font
-                .render(&self.text)
-                .with_max_width(BASE_INPUT_WIDTH as f32)
-                .finish()
-                .height()
-        } else {
-            0
+    /// Calls `f` with the button the dialog is dismissed with: 0 for OK or
+    /// Enter, 1 for Cancel or Escape.
+    pub fn on_button(mut self, f: impl FnMut(usize) + Send + 'static) -> Self {
+        self.hooks.button = Some(Box::new(f));
+        self
+    }
+
+    /// Calls `f` when the dialog is cancelled or closed.
+    pub fn on_cancel(mut self, f: impl FnMut() + Send + 'static) -> Self {
+        self.hooks.cancel = Some(Box::new(f));
+        self
+    }
+
+    pub fn show(mut self) -> Result<EntryResult, Error> {
+        let mut hooks = std::mem::take(&mut self.hooks);
+        let result = self.run(&mut hooks)?;
+        let button = match result {
+            EntryResult::Text(_) => Some(0),
+            EntryResult::Cancelled => Some(1),
+            EntryResult::Closed => None,
         };
+        hooks.dismissed(button, button != Some(0));
+        Ok(result)
+    }
+
+    fn run(&self, hooks: &mut Hooks<str>) -> Result<EntryResult, Error> {
+        #[cfg(feature = "tui")]
+        if crate::tui::enabled() {
+            return self.show_tui(hooks);
+        }
+
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
+
+        // First pass: calculate LOGICAL dimensions using scale 1.0
+        let temp_font = colors.font(1.0);
+        let temp_ok = Button::new(&tr("OK"), &temp_font, colors, 1.0);
+        let temp_cancel = Button::new(&tr("Cancel"), &temp_font, colors, 1.0);
+        let mut temp_prompt = Label::new(&self.text).with_wrap(true);
+        temp_prompt.layout(&temp_font, colors.text, Some(BASE_INPUT_WIDTH));
+        let temp_prompt_height = temp_prompt.height();
         let temp_input = TextInput::new(BASE_INPUT_WIDTH);
 
-        let logical_buttons_width = temp_ok.width() + temp_cancel.width() + BASE_BUTTON_SPACING;
+        let logical_buttons_width =
+            temp_ok.width() + temp_cancel.width() + colors.space(BASE_BUTTON_SPACING);
         let logical_content_width = BASE_INPUT_WIDTH.max(logical_buttons_width);
-        let calc_width = logical_content_width + BASE_PADDING * 2;
-        let calc_height = BASE_PADDING * 3
+        let calc_width = logical_content_width + colors.space(BASE_PADDING) * 2;
+        let calc_height = colors.space(BASE_PADDING) * 3
             + temp_prompt_height
             + (if temp_prompt_height > 0 { 10 } else { 0 })
             + temp_input.height()
             + 10
-            + 32;
+            + colors.control_height;
 
         drop(temp_font);
         drop(temp_ok);
@@ -132,11 +177,11 @@ impl EntryBuilder {
         let logical_height = self.height.unwrap_or(calc_height) as u16;
 
         // Create window with LOGICAL dimensions
-        let mut window = create_window(logical_width, logical_height)?;
-        window.set_title(if self.title.is_empty() {
-            "Entry"
+        let mut window = create_window(logical_width, logical_height, colors, &self.placing)?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Entry")
         } else {
-            &self.title
+            self.title.as_str().into()
         })?;
 
         // Get the actual scale factor from the window (compositor scale)
@@ -147,43 +192,38 @@ impl EntryBuilder {
         let physical_height = (logical_height as f32 * scale) as u32;
 
         // Now create everything at PHYSICAL scale
-        let font = Font::load(scale);
+        let font = colors.font(scale);
 
         // Scale dimensions for physical rendering
-        let padding = (BASE_PADDING as f32 * scale) as u32;
-        let button_spacing = (BASE_BUTTON_SPACING as f32 * scale) as u32;
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
+        let button_spacing = (colors.space(BASE_BUTTON_SPACING) as f32 * scale) as u32;
 
         // Input should fill available width
         let input_width = physical_width - (padding * 2);
 
         // Create buttons at physical scale
-        let mut ok_button = Button::new("OK", &font, scale);
-        let mut cancel_button = Button::new("Cancel", &font, scale);
+        let mut ok_button = Button::new(&tr("OK"), &font, colors, scale);
+        let mut cancel_button = Button::new(&tr("Cancel"), &font, colors, scale);
 
         // Create text input at physical scale
         let mut input = TextInput::new(input_width)
             .with_password(self.hide_text)
             .with_default_text(&self.entry_text);
-        input.set_focus(true);
+        let mut focus = FocusManager::new();
+        focus.focus(
+            Some(0),
+            &mut [&mut input, &mut ok_button, &mut cancel_button],
+        );
 
         // Render prompt text at physical scale (wrapped to fit)
-        let prompt_canvas = if !self.text.is_empty() {
-            Some(
-                font.render(&self.text)
-                    .with_color(colors.text)
-                    .with_max_width((physical_width - padding * 2) as f32)
-                    .finish(),
-            )
-        } else {
-            None
-        };
-        let prompt_height = prompt_canvas.as_ref().map(|c| c.height()).unwrap_or(0);
+        let mut prompt = Label::new(&self.text).with_wrap(true);
+        prompt.layout(&font, colors.text, Some(physical_width - padding * 2));
 
         // Position elements in physical coordinates
         let mut y = padding as i32;
-        let prompt_y = y;
-        if prompt_height > 0 {
-            y += prompt_height as i32 + (10.0 * scale) as i32;
+        prompt.set_position(padding as i32, y);
+        if prompt.height() > 0 {
+            y += prompt.height() as i32 + (10.0 * scale) as i32;
         }
 
         // Input position
@@ -202,32 +242,27 @@ impl EntryBuilder {
 
         // Draw function
         let draw = |canvas: &mut Canvas,
-                    colors: &Colors,
+                    colors: &Theme,
                     font: &Font,
-                    prompt_canvas: &Option<Canvas>,
+                    prompt: &Label,
                     input: &TextInput,
                     ok_button: &Button,
                     cancel_button: &Button,
-                    padding: u32,
-                    prompt_y: i32,
                     scale: f32| {
             let width = canvas.width() as f32;
             let height = canvas.height() as f32;
-            let radius = 8.0 * scale;
 
             canvas.fill_dialog_bg(
                 width,
                 height,
                 colors.window_bg,
                 colors.window_border,
-                colors.window_shadow,
-                radius,
+                colors.window_radius * scale,
+                colors.border_width,
             );
 
             // Draw prompt
-            if let Some(prompt) = prompt_canvas {
-                canvas.draw_canvas(prompt, padding as i32, prompt_y);
-            }
+            prompt.draw(canvas, colors);
 
             // Draw input
             input.draw_to(canvas, colors, font);
@@ -242,36 +277,39 @@ impl EntryBuilder {
             &mut canvas,
             colors,
             &font,
-            &prompt_canvas,
+            &prompt,
             &input,
             &ok_button,
             &cancel_button,
-            padding,
-            prompt_y,
             scale,
         );
         window.set_contents(&canvas)?;
         window.show()?;
 
         // Event loop
+        let mut last_text = input.text().to_string();
         loop {
-            let event = window.wait_for_event()?;
+            let event = focus.process_event(
+                window.wait_for_event()?,
+                &mut [&mut input, &mut ok_button, &mut cancel_button],
+            );
 
             match &event {
                 WindowEvent::CloseRequested => {
                     return Ok(EntryResult::Closed);
                 }
+                WindowEvent::KeyPress(key_event) if key_event.keysym == KEY_ESCAPE => {
+                    return Ok(EntryResult::Cancelled);
+                }
                 WindowEvent::RedrawRequested => {
                     draw(
                         &mut canvas,
                         colors,
                         &font,
-                        &prompt_canvas,
+                        &prompt,
                         &input,
                         &ok_button,
                         &cancel_button,
-                        padding,
-                        prompt_y,
                         scale,
                     );
                     window.set_contents(&canvas)?;
@@ -303,6 +341,12 @@ impl EntryBuilder {
             // Process input events
             let mut needs_redraw = input.process_event(&event);
 
+            // Dropped files are typed in as their paths
+            if let WindowEvent::Drop(paths) = &event {
+                input.insert_text(&paths_text(paths));
+                needs_redraw = true;
+            }
+
             // Check for Enter key submission
             if input.was_submitted() {
                 return Ok(EntryResult::Text(input.text().to_string()));
@@ -325,11 +369,21 @@ impl EntryBuilder {
 
             // Batch process pending events
             while let Some(event) = window.poll_for_event()? {
+                let event = focus
+                    .process_event(event, &mut [&mut input, &mut ok_button, &mut cancel_button]);
                 match &event {
                     WindowEvent::CloseRequested => {
                         return Ok(EntryResult::Closed);
                     }
+                    WindowEvent::KeyPress(key_event) if key_event.keysym == KEY_ESCAPE => {
+                        return Ok(EntryResult::Cancelled);
+                    }
+                    WindowEvent::Drop(paths) => {
+                        input.insert_text(&paths_text(paths));
+                        needs_redraw = true;
+                    }
                     _ => {
+                        needs_redraw |= matches!(event, WindowEvent::RedrawRequested);
                         if input.process_event(&event) {
                             needs_redraw = true;
                         }
@@ -352,23 +406,92 @@ impl EntryBuilder {
                 }
             }
 
+            if input.text() != last_text {
+                last_text = input.text().to_string();
+                hooks.changed(&last_text);
+            }
+
             if needs_redraw {
                 draw(
                     &mut canvas,
                     colors,
                     &font,
-                    &prompt_canvas,
+                    &prompt,
                     &input,
                     &ok_button,
                     &cancel_button,
-                    padding,
-                    prompt_y,
                     scale,
                 );
                 window.set_contents(&canvas)?;
             }
         }
     }
+
+    /// Shows the dialog on the terminal instead of in a window.
+    #[cfg(feature = "tui")]
+    fn show_tui(&self, hooks: &mut Hooks<str>) -> Result<EntryResult, Error> {
+        use crate::tui::{self, Key, RESET, REVERSE, Terminal};
+
+        let mut terminal = Terminal::open()?;
+        let mut text: Vec<char> = self.entry_text.chars().collect();
+        let mut cursor = text.len();
+
+        loop {
+            let (width, _) = terminal.size();
+            let mut lines = tui::header(&self.title, width);
+            if !self.text.is_empty() {
+                lines.extend(tui::wrap(&self.text, width));
+                lines.push(String::new());
+            }
+
+            // Scrolled to keep the cursor in view, which is drawn as a
+            // highlighted cell
+            let field_width = width.saturating_sub(2).max(1);
+            let start = (cursor + 1).saturating_sub(field_width);
+            let mut field = String::from("> ");
+            for idx in start..(start + field_width).min(text.len() + 1) {
+                let c = match text.get(idx) {
+                    Some(_) if self.hide_text => '*',
+                    Some(&c) => c,
+                    None => ' ',
+                };
+                if idx == cursor {
+                    field.push_str(&format!("{REVERSE}{c}{RESET}"));
+                } else {
+                    field.push(c);
+                }
+            }
+            lines.push(field);
+            lines.push(String::new());
+            lines.push(tui::fit(&tr("Enter: OK   Esc: Cancel"), width));
+            terminal.present(&lines)?;
+
+            match terminal.read_key(None)? {
+                Some(Key::Char(c)) => {
+                    text.insert(cursor, c);
+                    cursor += 1;
+                    hooks.changed(&text.iter().collect::<String>());
+                }
+                Some(Key::Backspace) if cursor > 0 => {
+                    cursor -= 1;
+                    text.remove(cursor);
+                    hooks.changed(&text.iter().collect::<String>());
+                }
+                Some(Key::Delete) if cursor < text.len() => {
+                    text.remove(cursor);
+                    hooks.changed(&text.iter().collect::<String>());
+                }
+                Some(Key::Left) => cursor = cursor.saturating_sub(1),
+                Some(Key::Right) => cursor = (cursor + 1).min(text.len()),
+                Some(Key::Home) => cursor = 0,
+                Some(Key::End) => cursor = text.len(),
+                Some(Key::Enter) => return Ok(EntryResult::Text(text.into_iter().collect())),
+                Some(Key::Escape) => return Ok(EntryResult::Cancelled),
+                Some(Key::Interrupt) => return Ok(EntryResult::Closed),
+                _ => {}
+            }
+        }
+    }
 }
 
 impl Default for EntryBuilder {
@@ -376,3 +499,12 @@ impl Default for EntryBuilder {
         Self::new()
     }
 }
+
+/// Joins dropped paths into text for the input.
+fn paths_text(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
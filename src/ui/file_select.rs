@@ -1,25 +1,52 @@
 //! File selection dialog implementation with enhanced UI.
 
 use std::{
+    borrow::Cow,
     collections::HashSet,
     fs::{self, Metadata},
     path::{Path, PathBuf},
-    time::SystemTime,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
 };
 
 use crate::{
-    backend::{MouseButton, Window, WindowEvent, create_window},
+    backend::{Modifiers, MouseButton, Window, WindowEvent},
+    dbus::{Connection, portal},
     error::Error,
     render::{Canvas, Font, Rgba, rgb},
     ui::{
-        Colors,
-        widgets::{Widget, button::Button, text_input::TextInput},
+        ButtonPreset, DialogResult, Icon, Theme, WindowPlacement, bookmarks,
+        collate::natural_cmp,
+        create_window,
+        entry::{EntryBuilder, EntryResult},
+        last_dir,
+        message::MessageBuilder,
+        recent,
+        strings::{tr, tr_args},
+        thumbnails::Thumbnails,
+        widgets::{
+            Widget,
+            button::Button,
+            focus::FocusManager,
+            label::{Ellipsize, Label},
+            point_in_rect,
+            scroll_area::ScrollArea,
+            spinner::Spinner,
+            text_input::TextInput,
+            tooltip::Tooltip,
+        },
     },
 };
 
 // Layout constants (logical, at scale 1.0)
 const BASE_WINDOW_WIDTH: u32 = 700;
 const BASE_WINDOW_HEIGHT: u32 = 500;
+const BASE_MIN_WIDTH: u32 = 600;
+const BASE_MIN_HEIGHT: u32 = 360;
 const BASE_PADDING: u32 = 12;
 const BASE_SIDEBAR_WIDTH: u32 = 160;
 const BASE_TOOLBAR_HEIGHT: u32 = 36;
@@ -27,14 +54,53 @@ const BASE_PATH_BAR_HEIGHT: u32 = 32;
 const BASE_SEARCH_WIDTH: u32 = 200;
 const BASE_ITEM_HEIGHT: u32 = 28;
 const BASE_ICON_SIZE: u32 = 20;
+const THUMBNAIL_CACHE_SIZE: usize = 256;
+const BASE_SPINNER_SIZE: u32 = 32;
+
+// Icon view cells (logical)
+const BASE_GRID_CELL_WIDTH: u32 = 96;
+const BASE_GRID_CELL_HEIGHT: u32 = 84;
+const BASE_GRID_ICON_SIZE: u32 = 48;
 const BASE_SECTION_HEADER_HEIGHT: u32 = 22;
 
 // Column widths (logical)
 const BASE_NAME_COL_WIDTH: u32 = 280;
 const BASE_SIZE_COL_WIDTH: u32 = 80;
+const BASE_DATE_COL_WIDTH: u32 = 120;
+
+// Preview pane (logical)
+const BASE_PREVIEW_WIDTH: u32 = 200;
+const BASE_PREVIEW_GAP: u32 = 12;
+
+const BASE_MENU_WIDTH: u32 = 120;
+const CONTEXT_MENU_ITEMS: [&str; 2] = ["Rename", "Delete"];
+const BOOKMARK_MENU_ITEMS: [&str; 1] = ["Remove"];
+
+const KEY_ESCAPE: u32 = 0xff1b;
+const KEY_TAB: u32 = 0xff09;
+
+/// Longest gap between the clicks of a double click.
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+/// How long to wait for a directory before showing it as loading.
+const LOAD_WAIT: Duration = Duration::from_millis(100);
+
+/// How often to check on thumbnails and the listing while they load.
+const BUSY_POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Pause after which type-ahead starts a new prefix.
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Whether the last file dialog was left in icon view; later dialogs in the
+/// same process start in the same mode.
+static GRID_VIEW: AtomicBool = AtomicBool::new(false);
+
+/// Hidden files setting, likewise carried over to later dialogs.
+static SHOW_HIDDEN: AtomicBool = AtomicBool::new(false);
 
 /// File selection dialog result.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FileSelectResult {
     Selected(PathBuf),
     SelectedMultiple(Vec<PathBuf>),
@@ -55,9 +121,11 @@ impl FileSelectResult {
 /// Quick access location.
 #[derive(Clone)]
 struct QuickAccess {
-    name: &'static str,
+    name: String,
     path: PathBuf,
     icon: QuickAccessIcon,
+    /// User bookmark that can be removed from the right-click menu.
+    removable: bool,
 }
 
 #[derive(Clone, Copy)]
@@ -69,6 +137,8 @@ enum QuickAccessIcon {
     Pictures,
     Music,
     Videos,
+    Bookmark,
+    Recent,
 }
 
 /// Represents a mounted drive
@@ -98,24 +168,35 @@ pub struct FileFilter {
 /// File selection dialog builder.
 pub struct FileSelectBuilder {
     title: String,
+    class: String,
     directory: bool,
     save: bool,
+    confirm_overwrite: bool,
+    allow_manage: bool,
     filename: String,
     start_path: Option<PathBuf>,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    colors: Option<Theme>,
     filters: Vec<FileFilter>,
     multiple: bool,
+    allow_directories: bool,
     separator: String,
+    follow_symlinks: bool,
+    preview: bool,
+    portal: bool,
+    pub(super) placing: WindowPlacement,
 }
 
 impl FileSelectBuilder {
     pub fn new() -> Self {
         Self {
             title: String::new(),
+            class: String::new(),
             directory: false,
             save: false,
+            confirm_overwrite: false,
+            allow_manage: false,
             filename: String::new(),
             start_path: None,
             width: None,
@@ -123,7 +204,12 @@ impl FileSelectBuilder {
             colors: None,
             filters: Vec::new(),
             multiple: false,
+            allow_directories: false,
             separator: String::from(" "),
+            follow_symlinks: false,
+            preview: false,
+            portal: false,
+            placing: WindowPlacement::default(),
         }
     }
 
@@ -132,6 +218,13 @@ impl FileSelectBuilder {
         self
     }
 
+    /// Application key for remembering the last directory. Defaults to the
+    /// title.
+    pub fn class(mut self, class: &str) -> Self {
+        self.class = class.to_string();
+        self
+    }
+
     pub fn directory(mut self, directory: bool) -> Self {
         self.directory = directory;
         self
@@ -142,6 +235,18 @@ impl FileSelectBuilder {
         self
     }
 
+    /// Ask before replacing an existing file in save mode.
+    pub fn confirm_overwrite(mut self, confirm_overwrite: bool) -> Self {
+        self.confirm_overwrite = confirm_overwrite;
+        self
+    }
+
+    /// Allow renaming and deleting files from a right-click menu.
+    pub fn allow_manage(mut self, allow_manage: bool) -> Self {
+        self.allow_manage = allow_manage;
+        self
+    }
+
     pub fn filename(mut self, filename: &str) -> Self {
         self.filename = filename.to_string();
         self
@@ -152,8 +257,8 @@ impl FileSelectBuilder {
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
         self
     }
 
@@ -177,43 +282,161 @@ impl FileSelectBuilder {
         self
     }
 
+    /// In multiple mode, return selected directories along with files instead
+    /// of dropping them. Always on in directory mode.
+    pub fn allow_directories(mut self, allow_directories: bool) -> Self {
+        self.allow_directories = allow_directories;
+        self
+    }
+
     pub fn separator(mut self, separator: &str) -> Self {
         self.separator = separator.to_string();
         self
     }
 
+    /// Show the preview pane for the selected entry when the dialog opens.
+    /// It can also be toggled from the toolbar.
+    pub fn preview(mut self, preview: bool) -> Self {
+        self.preview = preview;
+        self
+    }
+
+    /// Resolve symlinked directories to their target when entering them,
+    /// instead of keeping the link in the path.
+    pub fn follow_symlinks(mut self, follow_symlinks: bool) -> Self {
+        self.follow_symlinks = follow_symlinks;
+        self
+    }
+
+    /// Use the desktop's own file chooser through xdg-desktop-portal, as
+    /// sandboxed apps must, falling back to ours when there is no portal.
+    /// Also turned on by setting `ZENITY_RS_PORTAL=1`.
+    pub fn portal(mut self, portal: bool) -> Self {
+        self.portal = portal;
+        self
+    }
+
     pub fn show(self) -> Result<FileSelectResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+        // The last directory is remembered per application, like GTK choosers do
+        let key = if self.class.is_empty() {
+            self.title.clone()
+        } else {
+            self.class.clone()
+        };
+        let result = match self.show_portal(&key) {
+            Some(result) => result,
+            None => self.run(&key)?,
+        };
+
+        let picked = match &result {
+            FileSelectResult::Selected(path) => Some(path),
+            FileSelectResult::SelectedMultiple(paths) => paths.first(),
+            _ => None,
+        };
+        if let Some(dir) = picked.and_then(|p| p.parent()) {
+            let _ = last_dir::save(&key, dir);
+        }
+        Ok(result)
+    }
+
+    fn display_title(&self) -> Cow<'_, str> {
+        if !self.title.is_empty() {
+            self.title.as_str().into()
+        } else if self.directory {
+            tr("Select Directory")
+        } else if self.save {
+            tr("Save File")
+        } else {
+            tr("Open File")
+        }
+    }
+
+    /// Shows the portal's file chooser if it was asked for. Returns `None`
+    /// to fall back to our own dialog.
+    fn show_portal(&self, key: &str) -> Option<FileSelectResult> {
+        let enabled =
+            self.portal || std::env::var_os("ZENITY_RS_PORTAL").is_some_and(|value| value == "1");
+        if !enabled {
+            return None;
+        }
+        let mut conn = Connection::session().ok()?;
+        if !portal::available(&mut conn) {
+            return None;
+        }
+
+        let (filename_dir, filename_name) = split_filename(&self.filename);
+        let current_folder = self
+            .start_path
+            .clone()
+            .or(filename_dir)
+            .or_else(|| last_dir::load(key));
+        let chooser = portal::FileChooser {
+            title: &self.display_title(),
+            parent_window: self
+                .placing
+                .parent
+                .clone()
+                .or_else(crate::ui::parent)
+                .map(|parent| parent.to_string())
+                .unwrap_or_default(),
+            save: self.save,
+            directory: self.directory,
+            multiple: self.multiple,
+            current_folder: current_folder.as_deref(),
+            current_name: filename_name,
+            filters: self
+                .filters
+                .iter()
+                .map(|filter| (filter.name.as_str(), filter.patterns.as_slice()))
+                .collect(),
+        };
+
+        let mut paths = match portal::choose_files(&mut conn, &chooser) {
+            Ok(Some(paths)) => paths,
+            Ok(None) => return Some(FileSelectResult::Cancelled),
+            // A portal that can't show the chooser is as good as none
+            Err(_) => return None,
+        };
+        Some(if paths.is_empty() {
+            FileSelectResult::Cancelled
+        } else if self.multiple {
+            FileSelectResult::SelectedMultiple(paths)
+        } else {
+            FileSelectResult::Selected(paths.swap_remove(0))
+        })
+    }
+
+    fn run(self, key: &str) -> Result<FileSelectResult, Error> {
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
 
         // Use custom dimensions if provided, otherwise use defaults
-        let logical_width = self.width.unwrap_or(BASE_WINDOW_WIDTH);
+        // The default width grows to make room for the preview pane
+        let logical_width = self.width.unwrap_or(if self.preview {
+            BASE_WINDOW_WIDTH + BASE_PREVIEW_WIDTH + colors.space(BASE_PREVIEW_GAP)
+        } else {
+            BASE_WINDOW_WIDTH
+        });
         let logical_height = self.height.unwrap_or(BASE_WINDOW_HEIGHT);
 
         // Create window with LOGICAL dimensions first
-        let mut window = create_window(logical_width as u16, logical_height as u16)?;
-        let title = if self.title.is_empty() {
-            if self.directory {
-                "Select Directory"
-            } else if self.save {
-                "Save File"
-            } else {
-                "Open File"
-            }
-        } else {
-            &self.title
-        };
-        window.set_title(title)?;
+        let mut window = create_window(
+            logical_width as u16,
+            logical_height as u16,
+            colors,
+            &self.placing,
+        )?;
+        window.set_title(&self.display_title())?;
 
         // Get the actual scale factor from the window (compositor scale)
         let scale = window.scale_factor();
 
         // Now create everything at PHYSICAL scale
-        let font = Font::load(scale);
+        let font = colors.font(scale);
 
         // Scale dimensions for physical rendering
-        let window_width = (logical_width as f32 * scale) as u32;
-        let window_height = (logical_height as f32 * scale) as u32;
-        let padding = (BASE_PADDING as f32 * scale) as u32;
+        let mut window_width = (logical_width as f32 * scale) as u32;
+        let mut window_height = (logical_height as f32 * scale) as u32;
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
         let sidebar_width = (BASE_SIDEBAR_WIDTH as f32 * scale) as u32;
         let toolbar_height = (BASE_TOOLBAR_HEIGHT as f32 * scale) as u32;
         let path_bar_height = (BASE_PATH_BAR_HEIGHT as f32 * scale) as u32;
@@ -221,19 +444,39 @@ impl FileSelectBuilder {
         let item_height = (BASE_ITEM_HEIGHT as f32 * scale) as u32;
         let name_col_width = (BASE_NAME_COL_WIDTH as f32 * scale) as u32;
         let size_col_width = (BASE_SIZE_COL_WIDTH as f32 * scale) as u32;
+        let date_col_width = (BASE_DATE_COL_WIDTH as f32 * scale) as u32;
+        let preview_w = (BASE_PREVIEW_WIDTH as f32 * scale) as u32;
+        let preview_gap = (colors.space(BASE_PREVIEW_GAP) as f32 * scale) as u32;
+
+        // Build quick access locations and bookmarks
+        let mut quick_access = build_places(!self.directory);
 
-        // Build quick access locations
-        let quick_access = build_quick_access();
+        // Directory mode lists only directories, so always return them
+        let allow_directories = self.allow_directories || self.directory;
 
-        // Load mounted drives
-        let mounted_drives = get_mounted_drives();
+        // Load mounted drives, refreshed as things are mounted and unmounted
+        let mut mounted_drives = get_mounted_drives();
+        let mut mount_watcher = MountWatcher::new();
+
+        // Files created or removed in the listed directory; the reload waits
+        // until no rename, menu or drag selection holds entry indices
+        let mut dir_watcher = DirWatcher::new();
+        let mut dir_changed = false;
 
         // Create UI elements at physical scale
-        let mut ok_button = Button::new(if self.save { "Save" } else { "Open" }, &font, scale);
-        let mut cancel_button = Button::new("Cancel", &font, scale);
+        let mut ok_button = Button::new(
+            &tr(if self.save { "Save" } else { "Open" }),
+            &font,
+            colors,
+            scale,
+        );
+        let mut cancel_button = Button::new(&tr("Cancel"), &font, colors, scale);
+        let mut new_folder_button = Button::new(&tr("New Folder"), &font, colors, scale);
 
         // Search input
-        let mut search_input = TextInput::new(search_width).with_placeholder("Search...");
+        let mut search_input = TextInput::new(search_width).with_placeholder(&tr("Search..."));
+
+        let (filename_dir, filename_name) = split_filename(&self.filename);
 
         // Navigation history
         let mut history: Vec<PathBuf> = Vec::new();
@@ -242,26 +485,64 @@ impl FileSelectBuilder {
         // Current state
         let mut current_dir = self
             .start_path
+            .or(filename_dir)
+            .or_else(|| last_dir::load(key))
             .unwrap_or_else(|| dirs::home_dir().unwrap_or_else(|| PathBuf::from("/")));
         history.push(current_dir.clone());
 
         let mut all_entries: Vec<DirEntry> = Vec::new();
         let mut filtered_entries: Vec<usize> = Vec::new(); // Indices into all_entries
         let mut selected_indices: HashSet<usize> = HashSet::new();
-        let mut scroll_offset: usize = 0;
-        let mut show_hidden = false;
+        let mut show_hidden = SHOW_HIDDEN.load(Ordering::Relaxed);
+        let mut recent = false; // Listing recent files instead of current_dir
+
+        // Last file list click, to detect double clicks
+        let mut last_click: Option<(usize, Instant)> = None;
+
+        // Multiple mode: end of a Shift+click range, and drag selection
+        let mut selection_anchor: Option<usize> = None;
+        let mut rubber_band: Option<RubberBand> = None;
+
+        // Type-ahead: prefix typed while the list has focus, and when
+        let mut type_ahead = String::new();
+        let mut last_type_ahead = Instant::now();
+        let mut sort = SortOrder {
+            column: SortColumn::Name,
+            ascending: true,
+        };
         let mut search_text = String::new();
         let mut hovered_quick_access: Option<usize> = None;
         let mut hovered_entry: Option<usize> = None;
         let mut hovered_drive: Option<usize> = None;
 
-        // Scrollbar thumb dragging state
-        let mut thumb_drag = false;
-        let mut thumb_drag_offset: Option<i32> = None;
-        let mut scrollbar_hovered = false;
+        // Image thumbnails, decoded in the background
+        let icon_size = (BASE_ICON_SIZE as f32 * scale) as u32;
+        let grid_icon_size = (BASE_GRID_ICON_SIZE as f32 * scale) as u32;
+        let mut thumbnails = Thumbnails::new(icon_size, grid_icon_size, THUMBNAIL_CACHE_SIZE);
+
+        // Preview pane contents for the selected entry
+        let preview_image_size = preview_w - (16.0 * scale) as u32;
+        let mut previews = Thumbnails::new(preview_image_size, preview_image_size, 16);
+        let mut preview: Option<Preview> = None;
+
+        // File management state (see `allow_manage`)
+        let mut context_menu: Option<ContextMenu> = None;
+        let mut renaming: Option<(usize, TextInput)> = None;
+
+        // Editable location bar, shown in place of the breadcrumbs (Ctrl+L)
+        let mut location_input: Option<TextInput> = None;
 
-        // Load initial directory
-        load_directory(&current_dir, &mut all_entries, self.directory, show_hidden);
+        // Load initial directory, with a spinner if it takes a while
+        let mut loader = DirLoader::new();
+        let mut spinner = Spinner::new((BASE_SPINNER_SIZE as f32 * scale) as u32, scale);
+        let mut tooltip = Tooltip::new(scale);
+        loader.load(
+            &current_dir,
+            &mut all_entries,
+            self.directory,
+            show_hidden,
+            sort,
+        );
         update_filtered(
             &all_entries,
             &search_text,
@@ -272,78 +553,171 @@ impl FileSelectBuilder {
         // Calculate layout in physical coordinates
         let sidebar_x = padding as i32;
         let sidebar_y = (padding + toolbar_height + (8.0 * scale) as u32) as i32;
-        let sidebar_h = window_height
-            - padding * 2
-            - toolbar_height
-            - (8.0 * scale) as u32
-            - (44.0 * scale) as u32;
+        let name_row_height = if self.save { (40.0 * scale) as u32 } else { 0 };
+        let sidebar_height = |window_height: u32| {
+            window_height
+                - padding * 2
+                - toolbar_height
+                - (8.0 * scale) as u32
+                - (44.0 * scale) as u32
+                - name_row_height
+        };
+        let mut sidebar_h = sidebar_height(window_height);
 
         let main_x = (padding + sidebar_width + (12.0 * scale) as u32) as i32;
         let main_y = sidebar_y;
-        let main_w = window_width - padding * 2 - sidebar_width - (12.0 * scale) as u32;
-        let main_h = sidebar_h;
+        let mut main_h = sidebar_h;
+
+        // The preview pane, when shown, takes space from the right of the main area
+        let mut show_preview = self.preview;
+        let main_width = |window_width: u32, show_preview: bool| {
+            let full_main_w = window_width - padding * 2 - sidebar_width - (12.0 * scale) as u32;
+            if show_preview {
+                full_main_w.saturating_sub(preview_w + preview_gap)
+            } else {
+                full_main_w
+            }
+        };
+        let mut main_w = main_width(window_width, show_preview);
 
         let header_offset = (28.0 * scale) as u32; // Column headers
         let list_y = main_y + path_bar_height as i32 + header_offset as i32;
-        let list_h = main_h - path_bar_height - header_offset;
-        let visible_items = (list_h / item_height) as usize;
+        let mut list_h = main_h - path_bar_height - header_offset;
+
+        // Leave room for the scrollbar beside the last grid column
+        let mut list_w = main_w - (12.0 * scale) as u32;
+        let mut layout = ListLayout::new(GRID_VIEW.load(Ordering::Relaxed), list_w, list_h, scale);
+        let mut scroll = ScrollArea::new(main_w, list_h, scale);
+        scroll.set_position(main_x, list_y);
+        layout.fit(&mut scroll, filtered_entries.len());
 
         // Calculate section heights
         let section_header_height = (BASE_SECTION_HEADER_HEIGHT as f32 * scale) as u32;
         let item_height_scaled = item_height;
         let gap_between_sections = (12.0 * scale) as u32;
 
-        // Position buttons
-        let button_y = (window_height - padding - (32.0 * scale) as u32) as i32;
-        let mut bx = window_width as i32 - padding as i32;
-        bx -= cancel_button.width() as i32;
-        cancel_button.set_position(bx, button_y);
-        bx -= (10.0 * scale) as i32 + ok_button.width() as i32;
-        ok_button.set_position(bx, button_y);
+        // Filename input (save mode only), in its own row above the buttons
+        let mut name_input = self
+            .save
+            .then(|| TextInput::new(0).with_default_text(filename_name));
+
+        // Save dialogs start out typing the name
+        let mut focus = FocusManager::new();
+        if name_input.is_some() {
+            focus.focus(
+                Some(2),
+                &mut focus_order(
+                    &mut new_folder_button,
+                    &mut search_input,
+                    name_input.as_mut(),
+                    &mut ok_button,
+                    &mut cancel_button,
+                ),
+            );
+        }
 
-        // Position search input
-        let search_x = window_width as i32 - padding as i32 - search_width as i32;
+        // Position the controls along the window's right and bottom edges,
+        // returning the button row's y
         let search_y = padding as i32 + (2.0 * scale) as i32;
-        search_input.set_position(search_x, search_y);
+        let place_controls = |ok_button: &mut Button,
+                              cancel_button: &mut Button,
+                              search_input: &mut TextInput,
+                              name_input: Option<&mut TextInput>,
+                              window_width: u32,
+                              window_height: u32| {
+            let button_y = (window_height - padding - (32.0 * scale) as u32) as i32;
+            let mut bx = window_width as i32 - padding as i32;
+            bx -= cancel_button.width() as i32;
+            cancel_button.set_position(bx, button_y);
+            bx -= (10.0 * scale) as i32 + ok_button.width() as i32;
+            ok_button.set_position(bx, button_y);
+
+            if let Some(input) = name_input {
+                input.set_width(window_width - padding - main_x as u32);
+                input.set_position(main_x, button_y - name_row_height as i32);
+            }
+
+            let search_x = window_width as i32 - padding as i32 - search_width as i32;
+            search_input.set_position(search_x, search_y);
+            button_y
+        };
+        let mut button_y = place_controls(
+            &mut ok_button,
+            &mut cancel_button,
+            &mut search_input,
+            name_input.as_mut(),
+            window_width,
+            window_height,
+        );
+
+        // Toggles follow the navigation buttons, and New Folder the toggles
+        let toggle_size = (28.0 * scale) as i32;
+        let toggle_gap = (8.0 * scale) as i32;
+        let hidden_toggle_x = (padding as f32 + 150.0 * scale) as i32;
+        let view_toggle_x = hidden_toggle_x + toggle_size + toggle_gap;
+        let preview_toggle_x = view_toggle_x + toggle_size + toggle_gap;
+        new_folder_button.set_position(
+            preview_toggle_x + toggle_size + (12.0 * scale) as i32,
+            search_y,
+        );
 
         // Create canvas at PHYSICAL dimensions
         let mut canvas = Canvas::new(window_width, window_height);
+        // Size from the latest resize, applied before the next redraw
+        let mut new_size: Option<(u32, u32)> = None;
         let mut mouse_x = 0i32;
         let mut mouse_y = 0i32;
 
         // Draw function - captures scaled variables from enclosing scope
         let draw = |canvas: &mut Canvas,
-                    colors: &Colors,
+                    colors: &Theme,
                     font: &Font,
                     current_dir: &Path,
+                    recent: bool,
                     quick_access: &[QuickAccess],
                     all_entries: &[DirEntry],
                     filtered_entries: &[usize],
                     selected_indices: &HashSet<usize>,
-                    scroll_offset: usize,
+                    scroll: &ScrollArea,
+                    sidebar_h: u32,
+                    main_w: u32,
+                    main_h: u32,
+                    list_w: u32,
+                    layout: ListLayout,
                     hovered_quick_access: Option<usize>,
                     hovered_entry: Option<usize>,
                     show_hidden: bool,
                     search_input: &TextInput,
+                    name_input: Option<&TextInput>,
+                    new_folder_button: &Button,
+                    sort: SortOrder,
+                    thumbnails: &Thumbnails,
+                    renaming: Option<&TextInput>,
+                    show_preview: bool,
+                    preview: Option<&Preview>,
+                    previews: &Thumbnails,
+                    rubber_band: Option<&RubberBand>,
+                    location_input: Option<&TextInput>,
+                    context_menu: Option<&ContextMenu>,
                     ok_button: &Button,
                     cancel_button: &Button,
                     history: &[PathBuf],
                     history_index: usize,
                     mounted_drives: &[MountPoint],
                     hovered_drive: Option<usize>,
+                    button_y: i32,
                     scale: f32,
-                    scrollbar_hovered: bool| {
+                    spinner: Option<&Spinner>| {
             let width = canvas.width() as f32;
             let height = canvas.height() as f32;
-            let radius = 8.0 * scale;
 
             canvas.fill_dialog_bg(
                 width,
                 height,
                 colors.window_bg,
                 colors.window_border,
-                colors.window_shadow,
-                radius,
+                colors.window_radius * scale,
+                colors.border_width,
             );
 
             // Toolbar background
@@ -351,7 +725,7 @@ impl FileSelectBuilder {
             canvas.fill_rect(
                 0.0,
                 0.0,
-                window_width as f32,
+                width,
                 (toolbar_height + padding) as f32,
                 toolbar_bg,
             );
@@ -407,10 +781,9 @@ impl FileSelectBuilder {
                 scale,
             );
             // Hidden files toggle
-            let toggle_x = (padding as f32 + 150.0 * scale) as i32;
             draw_toggle(
                 canvas,
-                toggle_x,
+                hidden_toggle_x,
                 nav_y,
                 ".*",
                 show_hidden,
@@ -418,6 +791,13 @@ impl FileSelectBuilder {
                 font,
                 scale,
             );
+            // List/icon view toggle
+            draw_view_toggle(canvas, view_toggle_x, nav_y, layout.grid, colors, scale);
+            // Preview pane toggle
+            draw_preview_toggle(canvas, preview_toggle_x, nav_y, show_preview, colors, scale);
+
+            // New Folder button
+            new_folder_button.draw_to(canvas, colors, font);
 
             // Search input
             search_input.draw_to(canvas, colors, font);
@@ -438,18 +818,32 @@ impl FileSelectBuilder {
                 canvas,
                 sidebar_x,
                 sidebar_y + (8.0 * scale) as i32,
-                "PLACES",
+                &tr("PLACES"),
                 colors,
                 font,
                 scale,
             );
 
+            // "+" to bookmark the current directory
+            if !recent && !quick_access.iter().any(|qa| qa.path == current_dir) {
+                let plus = font.render("+").with_color(rgb(140, 140, 140)).finish();
+                canvas.draw_canvas(
+                    &plus,
+                    sidebar_x + sidebar_width as i32 - (18.0 * scale) as i32,
+                    sidebar_y + (8.0 * scale) as i32,
+                );
+            }
+
             let places_items_start_y =
                 sidebar_y + (8.0 * scale) as i32 + section_header_height as i32;
             for (i, qa) in quick_access.iter().enumerate() {
                 let y = places_items_start_y + (i as i32 * item_height_scaled as i32);
                 let is_hovered = hovered_quick_access == Some(i);
-                let is_current = qa.path == current_dir;
+                let is_current = if matches!(qa.icon, QuickAccessIcon::Recent) {
+                    recent
+                } else {
+                    !recent && qa.path == current_dir
+                };
 
                 if is_current {
                     canvas.fill_rounded_rect(
@@ -481,11 +875,14 @@ impl FileSelectBuilder {
                 );
 
                 let text_color = if is_current {
-                    rgb(255, 255, 255)
+                    colors.selection_text
                 } else {
                     colors.text
                 };
-                let name_canvas = font.render(qa.name).with_color(text_color).finish();
+                let name_canvas = font
+                    .render(&truncate_name(&qa.name, 18))
+                    .with_color(text_color)
+                    .finish();
                 canvas.draw_canvas(
                     &name_canvas,
                     sidebar_x + (36.0 * scale) as i32,
@@ -503,7 +900,7 @@ impl FileSelectBuilder {
                     canvas,
                     sidebar_x,
                     drives_section_y,
-                    "DRIVES",
+                    &tr("DRIVES"),
                     colors,
                     font,
                     scale,
@@ -513,7 +910,7 @@ impl FileSelectBuilder {
                 for (i, drive) in mounted_drives.iter().enumerate() {
                     let y = drives_items_start_y + (i as i32 * item_height_scaled as i32);
                     let is_hovered = hovered_drive == Some(i);
-                    let is_current = drive.mount_point == current_dir;
+                    let is_current = !recent && drive.mount_point == current_dir;
 
                     if is_current {
                         canvas.fill_rounded_rect(
@@ -555,7 +952,7 @@ impl FileSelectBuilder {
                     let truncated_name = truncate_name(display_name, 18);
 
                     let text_color = if is_current {
-                        rgb(255, 255, 255)
+                        colors.selection_text
                     } else {
                         colors.text
                     };
@@ -578,16 +975,27 @@ impl FileSelectBuilder {
                 colors.input_bg,
             );
 
-            // Path bar (breadcrumbs)
-            draw_breadcrumbs(
-                canvas,
-                main_x + (8.0 * scale) as i32,
-                main_y + (6.0 * scale) as i32,
-                main_w - (16.0 * scale) as u32,
-                current_dir,
-                colors,
-                font,
-            );
+            // Path bar (breadcrumbs, or the location input while editing)
+            if let Some(input) = location_input {
+                input.draw_to(canvas, colors, font);
+            } else if recent {
+                let label = font.render(&tr("Recent")).with_color(colors.text).finish();
+                canvas.draw_canvas(
+                    &label,
+                    main_x + (8.0 * scale) as i32,
+                    main_y + (6.0 * scale) as i32,
+                );
+            } else {
+                draw_breadcrumbs(
+                    canvas,
+                    main_x + (8.0 * scale) as i32,
+                    main_y + (6.0 * scale) as i32,
+                    main_w - (16.0 * scale) as u32,
+                    current_dir,
+                    colors,
+                    font,
+                );
+            }
 
             // Column headers
             let header_y = main_y + path_bar_height as i32;
@@ -600,25 +1008,39 @@ impl FileSelectBuilder {
                 header_bg,
             );
 
+            // Size and Modified only when there is room beside the preview pane
+            let show_details = main_w >= name_col_width + size_col_width + date_col_width;
             let header_text = rgb(150, 150, 150);
-            let name_header = font.render("Name").with_color(header_text).finish();
-            canvas.draw_canvas(
-                &name_header,
-                main_x + (32.0 * scale) as i32,
-                header_y + (5.0 * scale) as i32,
-            );
-            let size_header = font.render("Size").with_color(header_text).finish();
-            canvas.draw_canvas(
-                &size_header,
-                main_x + name_col_width as i32 + (8.0 * scale) as i32,
-                header_y + (5.0 * scale) as i32,
-            );
-            let date_header = font.render("Modified").with_color(header_text).finish();
-            canvas.draw_canvas(
-                &date_header,
-                main_x + name_col_width as i32 + size_col_width as i32 + (16.0 * scale) as i32,
-                header_y + (5.0 * scale) as i32,
-            );
+            let headers = [
+                ("Name", SortColumn::Name, main_x + (32.0 * scale) as i32),
+                (
+                    "Size",
+                    SortColumn::Size,
+                    main_x + name_col_width as i32 + (8.0 * scale) as i32,
+                ),
+                (
+                    "Modified",
+                    SortColumn::Modified,
+                    main_x + name_col_width as i32 + size_col_width as i32 + (16.0 * scale) as i32,
+                ),
+            ];
+            let header_count = if show_details { headers.len() } else { 1 };
+            for (label, column, x) in headers.into_iter().take(header_count) {
+                let header = font.render(&tr(label)).with_color(header_text).finish();
+                canvas.draw_canvas(&header, x, header_y + (5.0 * scale) as i32);
+
+                // Arrow next to the sorted column
+                if sort.column == column {
+                    draw_sort_arrow(
+                        canvas,
+                        x + header.width() as i32 + (6.0 * scale) as i32,
+                        header_y + (10.0 * scale) as i32,
+                        sort.ascending,
+                        header_text,
+                        scale,
+                    );
+                }
+            }
 
             // Separator line
             canvas.fill_rect(
@@ -633,15 +1055,83 @@ impl FileSelectBuilder {
             let list_x = main_x;
             for (vi, &ei) in filtered_entries
                 .iter()
-                .skip(scroll_offset)
-                .take(visible_items)
+                .skip(scroll.offset())
+                .take(layout.visible_items)
                 .enumerate()
             {
                 let entry = &all_entries[ei];
-                let y = list_y + (vi as u32 * item_height) as i32;
                 let is_selected = selected_indices.contains(&ei);
                 let is_hovered = hovered_entry == Some(ei);
 
+                if layout.grid {
+                    let (cell_x, cell_y) = layout.cell_origin(vi);
+                    let x = list_x + cell_x;
+                    let y = list_y + cell_y;
+                    let inset = 3.0 * scale;
+
+                    // Selection/hover highlight
+                    let highlight = if is_selected {
+                        Some(colors.input_border_focused)
+                    } else if is_hovered {
+                        Some(darken(colors.input_bg, 0.06))
+                    } else {
+                        None
+                    };
+                    if let Some(color) = highlight {
+                        canvas.fill_rounded_rect(
+                            x as f32 + inset,
+                            y as f32 + inset,
+                            layout.cell_width as f32 - inset * 2.0,
+                            layout.row_height as f32 - inset * 2.0,
+                            4.0 * scale,
+                            color,
+                        );
+                    }
+
+                    // Large icon, centered at the top of the cell
+                    let icon_x = x + (layout.cell_width - grid_icon_size) as i32 / 2;
+                    let icon_y = y + (8.0 * scale) as i32;
+                    let icon_scale = scale * BASE_GRID_ICON_SIZE as f32 / BASE_ICON_SIZE as f32;
+                    if entry.is_dir {
+                        draw_folder_icon(canvas, icon_x, icon_y, colors, icon_scale);
+                    } else if let Some(thumb) = thumbnails.get(&entry.path) {
+                        canvas.draw_canvas(
+                            &thumb.large,
+                            icon_x + (grid_icon_size as i32 - thumb.large.width() as i32) / 2,
+                            icon_y + (grid_icon_size as i32 - thumb.large.height() as i32) / 2,
+                        );
+                    } else {
+                        draw_file_icon(canvas, icon_x, icon_y, &entry.name, colors, icon_scale);
+                    }
+                    if entry.link_target.is_some() {
+                        draw_link_emblem(
+                            canvas,
+                            icon_x,
+                            icon_y + grid_icon_size as i32,
+                            16.0 * scale,
+                            colors,
+                        );
+                    }
+
+                    // Name underneath
+                    let text_color = if is_selected {
+                        colors.selection_text
+                    } else {
+                        colors.text
+                    };
+                    let max_w = layout.cell_width - (8.0 * scale) as u32;
+                    let mut name =
+                        fit_text(font, &entry.name, max_w, text_color, Ellipsize::Middle);
+                    name.set_position(
+                        x + (layout.cell_width as i32 - name.width() as i32) / 2,
+                        icon_y + grid_icon_size as i32 + (6.0 * scale) as i32,
+                    );
+                    name.draw(canvas, colors);
+                    continue;
+                }
+
+                let y = list_y + (vi as u32 * item_height) as i32;
+
                 // Alternating background
                 let row_bg = if vi % 2 == 1 {
                     darken(colors.input_bg, 0.02)
@@ -681,23 +1171,44 @@ impl FileSelectBuilder {
                 let icon_y = y + (4.0 * scale) as i32;
                 if entry.is_dir {
                     draw_folder_icon(canvas, icon_x, icon_y, colors, scale);
+                } else if let Some(thumb) = thumbnails.get(&entry.path) {
+                    // Center the thumbnail in the icon box
+                    canvas.draw_canvas(
+                        &thumb.small,
+                        icon_x + (icon_size as i32 - thumb.small.width() as i32) / 2,
+                        icon_y + (icon_size as i32 - thumb.small.height() as i32) / 2,
+                    );
                 } else {
                     draw_file_icon(canvas, icon_x, icon_y, &entry.name, colors, scale);
                 }
+                if entry.link_target.is_some() {
+                    draw_link_emblem(
+                        canvas,
+                        icon_x - (2.0 * scale) as i32,
+                        icon_y + icon_size as i32,
+                        10.0 * scale,
+                        colors,
+                    );
+                }
 
                 // Name
                 let text_color = if is_selected {
-                    rgb(255, 255, 255)
+                    colors.selection_text
                 } else {
                     colors.text
                 };
-                let display_name = truncate_name(&entry.name, 35);
-                let name_canvas = font.render(&display_name).with_color(text_color).finish();
-                canvas.draw_canvas(
-                    &name_canvas,
-                    list_x + (32.0 * scale) as i32,
-                    y + (6.0 * scale) as i32,
-                );
+                let mut name = if show_details {
+                    let display_name = truncate_name(&entry.name, 35);
+                    fit_text(font, &display_name, u32::MAX, text_color, Ellipsize::None)
+                } else {
+                    let max_w = list_w.saturating_sub((36.0 * scale) as u32);
+                    fit_text(font, &entry.name, max_w, text_color, Ellipsize::Middle)
+                };
+                name.set_position(list_x + (32.0 * scale) as i32, y + (6.0 * scale) as i32);
+                name.draw(canvas, colors);
+                if !show_details {
+                    continue;
+                }
 
                 // Size (for files)
                 if !entry.is_dir {
@@ -715,58 +1226,57 @@ impl FileSelectBuilder {
                     );
                 }
 
-                // Date
-                let date_str = format_date(entry.modified);
+                // Date, or a hovered symlink's target
+                let date_x =
+                    list_x + name_col_width as i32 + size_col_width as i32 + (16.0 * scale) as i32;
+                let date_str = match &entry.link_target {
+                    Some(target) if is_hovered => tr_args("Link to {}", &[&target.display()]),
+                    _ => format_date(entry.modified),
+                };
                 let date_color = if is_selected {
                     rgb(220, 220, 220)
                 } else {
                     rgb(140, 140, 140)
                 };
-                let date_canvas = font.render(&date_str).with_color(date_color).finish();
-                canvas.draw_canvas(
-                    &date_canvas,
-                    list_x + name_col_width as i32 + size_col_width as i32 + (16.0 * scale) as i32,
-                    y + (6.0 * scale) as i32,
-                );
+                let date_w = (main_x + list_w as i32 - date_x).max(0) as u32;
+                let mut date = fit_text(font, &date_str, date_w, date_color, Ellipsize::End);
+                date.set_position(date_x, y + (6.0 * scale) as i32);
+                date.draw(canvas, colors);
             }
 
-            // Scrollbar
-            if filtered_entries.len() > visible_items {
-                let scrollbar_width = if scrollbar_hovered {
-                    12.0 * scale
-                } else {
-                    8.0 * scale
-                };
-                let scrollbar_x = main_x + main_w as i32 - scrollbar_width as i32;
-                let scrollbar_h = list_h as f32;
-                let thumb_h = (visible_items as f32 / filtered_entries.len() as f32 * scrollbar_h)
-                    .max(20.0 * scale);
-                let thumb_y = scroll_offset as f32 / filtered_entries.len() as f32 * scrollbar_h;
-
-                // Track
-                canvas.fill_rounded_rect(
-                    scrollbar_x as f32,
-                    list_y as f32,
-                    scrollbar_width - 2.0 * scale,
-                    scrollbar_h,
-                    3.0 * scale,
-                    darken(colors.input_bg, 0.05),
+            // Drag selection rectangle
+            if let Some(band) = rubber_band.filter(|b| b.active) {
+                let (x, y, w, h) = band.rect();
+                canvas.fill_rect(
+                    x as f32,
+                    y as f32,
+                    w as f32,
+                    h as f32,
+                    colors.input_border_focused.with_alpha(60),
                 );
-                // Thumb
-                canvas.fill_rounded_rect(
-                    scrollbar_x as f32,
-                    list_y as f32 + thumb_y,
-                    scrollbar_width - 2.0 * scale,
-                    thumb_h,
-                    3.0 * scale,
-                    if scrollbar_hovered {
-                        colors.input_border_focused
-                    } else {
-                        colors.input_border
-                    },
+                canvas.stroke_rounded_rect(
+                    x as f32,
+                    y as f32,
+                    w as f32,
+                    h as f32,
+                    0.0,
+                    colors.input_border_focused,
+                    1.0,
                 );
             }
 
+            // Spinner over the list while the directory is read
+            if let Some(spinner) = spinner {
+                spinner.draw(canvas, colors);
+            }
+
+            // Inline rename field
+            if let Some(input) = renaming {
+                input.draw_to(canvas, colors, font);
+            }
+
+            scroll.draw(canvas, colors);
+
             // Border
             canvas.stroke_rounded_rect(
                 main_x as f32,
@@ -778,93 +1288,449 @@ impl FileSelectBuilder {
                 1.0,
             );
 
+            // Preview pane
+            if show_preview {
+                draw_preview_pane(
+                    canvas,
+                    main_x + (main_w + preview_gap) as i32,
+                    main_y,
+                    preview_w,
+                    main_h,
+                    preview,
+                    previews,
+                    colors,
+                    font,
+                    scale,
+                );
+            }
+
+            // Filename row
+            if let Some(name_input) = name_input {
+                let label = font.render(&tr("Name:")).with_color(colors.text).finish();
+                let label_y =
+                    name_input.y() + (name_input.height() as i32 - label.height() as i32) / 2;
+                canvas.draw_canvas(&label, sidebar_x, label_y);
+                name_input.draw_to(canvas, colors, font);
+            }
+
             // Buttons
             ok_button.draw_to(canvas, colors, font);
             cancel_button.draw_to(canvas, colors, font);
 
             // Status bar
-            let status = format!("{} items", filtered_entries.len());
+            let status = tr_args("{} items", &[&filtered_entries.len()]);
             let status_canvas = font.render(&status).with_color(rgb(120, 120, 120)).finish();
             canvas.draw_canvas(&status_canvas, main_x, button_y + (8.0 * scale) as i32);
+
+            // Context menu, drawn on top of everything else
+            if let Some(menu) = context_menu {
+                let menu_h = menu.item_height * menu.items.len() as u32;
+                canvas.fill_rounded_rect(
+                    menu.x as f32,
+                    menu.y as f32,
+                    menu.width as f32,
+                    menu_h as f32,
+                    4.0 * scale,
+                    colors.input_bg,
+                );
+                for (i, label) in menu.items.iter().enumerate() {
+                    let item_y = menu.y + (i as u32 * menu.item_height) as i32;
+                    let is_hovered = menu.hovered == Some(i);
+                    if is_hovered {
+                        canvas.fill_rect(
+                            menu.x as f32,
+                            item_y as f32,
+                            menu.width as f32,
+                            menu.item_height as f32,
+                            colors.input_border_focused,
+                        );
+                    }
+                    let text_color = if is_hovered {
+                        colors.selection_text
+                    } else {
+                        colors.text
+                    };
+                    let tc = font.render(&tr(label)).with_color(text_color).finish();
+                    canvas.draw_canvas(
+                        &tc,
+                        menu.x + (12.0 * scale) as i32,
+                        item_y + (6.0 * scale) as i32,
+                    );
+                }
+                canvas.stroke_rounded_rect(
+                    menu.x as f32,
+                    menu.y as f32,
+                    menu.width as f32,
+                    menu_h as f32,
+                    4.0 * scale,
+                    colors.input_border,
+                    1.0,
+                );
+            }
+        };
+
+        // Says why OK has nothing to accept yet, or returns `None` once it
+        // has: a selection, a typed name, or the current directory
+        let ok_blocked_reason = |selected_indices: &HashSet<usize>,
+                                 name_input: Option<&TextInput>| {
+            let typed = name_input.is_some_and(|input| !input.text().trim().is_empty());
+            if self.directory || typed || !selected_indices.is_empty() {
+                None
+            } else if self.save {
+                Some(tr("Type a name for the file"))
+            } else if self.multiple {
+                Some(tr("Select one or more files"))
+            } else {
+                Some(tr("Select a file"))
+            }
         };
+        let blocked = ok_blocked_reason(&selected_indices, name_input.as_ref());
+        ok_button.set_enabled(blocked.is_none());
+        ok_button.set_tooltip(blocked.as_deref());
 
         // Initial draw
+        if show_preview {
+            update_preview(&mut preview, &mut previews, &all_entries, &selected_indices);
+        }
+        request_thumbnails(
+            &mut thumbnails,
+            &all_entries,
+            &filtered_entries,
+            scroll.offset(),
+            layout.visible_items,
+        );
+        spinner.set_position(
+            main_x + (list_w as i32 - spinner.size() as i32) / 2,
+            list_y + (list_h as i32 - spinner.size() as i32) / 2,
+        );
         draw(
             &mut canvas,
             colors,
             &font,
             &current_dir,
+            recent,
             &quick_access,
             &all_entries,
             &filtered_entries,
             &selected_indices,
-            scroll_offset,
+            &scroll,
+            sidebar_h,
+            main_w,
+            main_h,
+            list_w,
+            layout,
             hovered_quick_access,
             hovered_entry,
             show_hidden,
             &search_input,
+            name_input.as_ref(),
+            &new_folder_button,
+            sort,
+            &thumbnails,
+            renaming.as_ref().map(|(_, input)| input),
+            show_preview,
+            preview.as_ref(),
+            &previews,
+            rubber_band.as_ref(),
+            location_input.as_ref(),
+            context_menu.as_ref(),
             &ok_button,
             &cancel_button,
             &history,
             history_index,
             &mounted_drives,
             hovered_drive,
+            button_y,
             scale,
-            scrollbar_hovered,
+            loader.is_loading().then_some(&spinner),
         );
         window.set_contents(&canvas)?;
+        window.set_resizable(
+            BASE_MIN_WIDTH.min(logical_width) as u16,
+            BASE_MIN_HEIGHT.min(logical_height) as u16,
+        )?;
         window.show()?;
 
+        // Returns whether a save path may be used, asking first if it would be replaced
+        let confirm_save = |path: &Path| -> Result<bool, Error> {
+            if self.save && self.confirm_overwrite && path.exists() {
+                confirm_replace(path, colors)
+            } else {
+                Ok(true)
+            }
+        };
+
         // Event loop
         loop {
-            let event = window.wait_for_event()?;
-            let mut needs_redraw = false;
-
-            match &event {
-                WindowEvent::CloseRequested => return Ok(FileSelectResult::Closed),
-                WindowEvent::RedrawRequested => needs_redraw = true,
-                WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
-                    mouse_x = pos.x as i32;
-                    mouse_y = pos.y as i32;
-
-                    // Handle scrollbar thumb dragging
-                    if thumb_drag && !filtered_entries.is_empty() {
-                        let scrollbar_y = list_y;
+            // Wake for changes on disk as well as input, and to check on
+            // thumbnails and the listing while they load
+            dir_watcher.watch((!recent).then_some(current_dir.as_path()));
+            let fds: Vec<_> = [dir_watcher.pollfd(), mount_watcher.pollfd()]
+                .into_iter()
+                .flatten()
+                .collect();
+            let busy = thumbnails.is_busy() || previews.is_busy() || loader.is_loading();
+            let deadline = busy.then(|| Instant::now() + BUSY_POLL_INTERVAL);
+            let event = match window.wait_for_event_or(&fds, deadline)? {
+                Some(event) => event,
+                None => {
+                    let dir_event = dir_watcher.changed();
+                    dir_changed |= dir_event;
+
+                    let mounts_changed = mount_watcher.changed();
+                    if mounts_changed {
+                        mounted_drives = get_mounted_drives();
+                        hovered_drive = None;
+                    }
 
-                        if mouse_x >= main_x
-                            && mouse_x < main_x + main_w as i32
-                            && mouse_y >= list_y
-                            && mouse_y < list_y + list_h as i32
-                        {
-                            let visible_items = (list_h / item_height) as usize;
-                            let total_items = filtered_entries.len();
-                            let max_scroll = total_items.saturating_sub(visible_items);
-
-                            if max_scroll > 0 {
-                                let scrollbar_h_f32 = list_h as f32 - 8.0 * scale;
-                                let thumb_h_f32 = (visible_items as f32 / total_items as f32
-                                    * scrollbar_h_f32)
-                                    .max(20.0 * scale);
-                                let thumb_h = thumb_h_f32 as i32;
-                                let max_thumb_y = scrollbar_h_f32 as i32 - thumb_h;
-
-                                let offset = thumb_drag_offset.unwrap_or(thumb_h / 2);
-                                let thumb_y =
-                                    (mouse_y - scrollbar_y - offset).clamp(0, max_thumb_y);
-                                let scroll_ratio = if max_thumb_y > 0 {
-                                    thumb_y as f32 / max_thumb_y as f32
-                                } else {
-                                    0.0
-                                };
-                                scroll_offset = ((scroll_ratio * max_scroll as f32) as usize)
-                                    .clamp(0, max_scroll);
-                                needs_redraw = true;
+                    if !(thumbnails.receive()
+                        | previews.receive()
+                        | dir_event
+                        | mounts_changed
+                        | loader.is_loading())
+                    {
+                        continue;
+                    }
+                    WindowEvent::RedrawRequested
+                }
+            };
+            let mut needs_redraw = false;
+            let mut consumed = false;
+            let mut rename_submitted = false;
+            let mut location_submitted = false;
+            let mut dropped: Vec<PathBuf> = Vec::new();
+            let mut toggle_hidden = false;
+
+            // Context menu captures the pointer while open
+            if let Some(ref mut menu) = context_menu {
+                match &event {
+                    WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
+                        mouse_x = pos.x as i32;
+                        mouse_y = pos.y as i32;
+                        let hovered = menu.item_at(mouse_x, mouse_y);
+                        needs_redraw |= hovered != menu.hovered;
+                        menu.hovered = hovered;
+                        consumed = true;
+                    }
+                    WindowEvent::ButtonPress(..) => {
+                        let action = menu.item_at(mouse_x, mouse_y);
+                        let target = menu.target;
+                        context_menu = None;
+                        consumed = true;
+
+                        match (target, action) {
+                            // Rename
+                            (MenuTarget::Entry(ei), Some(0)) => {
+                                if let Some(pos) = filtered_entries.iter().position(|&e| e == ei) {
+                                    let (cell_x, cell_y) =
+                                        layout.cell_origin(pos.saturating_sub(scroll.offset()));
+                                    let mut input = if layout.grid {
+                                        // Over the name, at the bottom of the cell
+                                        let mut input = TextInput::new(layout.cell_width)
+                                            .with_default_text(&all_entries[ei].name);
+                                        input.set_position(
+                                            main_x + cell_x,
+                                            list_y + cell_y + layout.row_height as i32
+                                                - input.height() as i32,
+                                        );
+                                        input
+                                    } else {
+                                        let mut input = TextInput::new(name_col_width)
+                                            .with_default_text(&all_entries[ei].name);
+                                        input.set_position(
+                                            main_x + (28.0 * scale) as i32,
+                                            list_y + cell_y,
+                                        );
+                                        input
+                                    };
+                                    input.set_focus(true);
+                                    focus.focus(
+                                        None,
+                                        &mut focus_order(
+                                            &mut new_folder_button,
+                                            &mut search_input,
+                                            name_input.as_mut(),
+                                            &mut ok_button,
+                                            &mut cancel_button,
+                                        ),
+                                    );
+                                    renaming = Some((ei, input));
+                                }
+                            }
+                            // Delete
+                            (MenuTarget::Entry(ei), Some(_)) => {
+                                let deleted = delete_entry(&current_dir, &all_entries[ei], colors)?;
+                                if deleted {
+                                    reload_entries(
+                                        recent,
+                                        &current_dir,
+                                        &mut all_entries,
+                                        &mut loader,
+                                        self.directory,
+                                        show_hidden,
+                                        sort,
+                                    );
+                                    update_filtered(
+                                        &all_entries,
+                                        &search_text,
+                                        &mut filtered_entries,
+                                        &self.filters,
+                                    );
+                                    selected_indices.clear();
+                                    layout.fit(&mut scroll, filtered_entries.len());
+                                }
                             }
+                            // Remove bookmark
+                            (MenuTarget::Bookmark(i), Some(_)) => {
+                                if let Err(e) = bookmarks::remove(&quick_access[i].path) {
+                                    show_file_error(
+                                        &tr_args("Could not remove bookmark: {}", &[&e]),
+                                        colors,
+                                    )?;
+                                }
+                                quick_access = build_places(!self.directory);
+                                hovered_quick_access = None;
+                            }
+                            (_, None) => {}
                         }
                     }
-
-                    // Update hover states (only when not dragging)
-                    if !thumb_drag {
+                    WindowEvent::KeyPress(key_event) if key_event.keysym == KEY_ESCAPE => {
+                        context_menu = None;
+                        consumed = true;
+                    }
+                    _ => {}
+                }
+            }
+
+            // Inline rename captures the keyboard while active
+            if let Some((_, ref mut input)) = renaming {
+                match &event {
+                    WindowEvent::KeyPress(key_event) if key_event.keysym == KEY_ESCAPE => {
+                        renaming = None;
+                        consumed = true;
+                    }
+                    WindowEvent::ButtonPress(..) | WindowEvent::PrimaryPaste(..)
+                        if !point_in_rect(
+                            mouse_x,
+                            mouse_y,
+                            input.x(),
+                            input.y(),
+                            input.width(),
+                            input.height(),
+                        ) =>
+                    {
+                        renaming = None;
+                    }
+                    WindowEvent::Scroll(_) => renaming = None,
+                    WindowEvent::KeyPress(_)
+                    | WindowEvent::TextInput(_)
+                    | WindowEvent::PrimaryPaste(..) => {
+                        input.process_event(&event);
+                        rename_submitted = input.was_submitted();
+                        consumed = true;
+                    }
+                    _ => {}
+                }
+            }
+
+            // Location bar captures the keyboard while open
+            if let Some(ref mut input) = location_input {
+                match &event {
+                    WindowEvent::KeyPress(key_event) if key_event.keysym == KEY_ESCAPE => {
+                        location_input = None;
+                        consumed = true;
+                    }
+                    WindowEvent::KeyPress(key_event) if key_event.keysym == KEY_TAB => {
+                        if let Some(completed) = complete_directory(input.text(), show_hidden) {
+                            input.set_text(&completed);
+                        }
+                        consumed = true;
+                    }
+                    WindowEvent::ButtonPress(..) | WindowEvent::PrimaryPaste(..)
+                        if !point_in_rect(
+                            mouse_x,
+                            mouse_y,
+                            input.x(),
+                            input.y(),
+                            input.width(),
+                            input.height(),
+                        ) =>
+                    {
+                        location_input = None;
+                    }
+                    WindowEvent::KeyPress(_)
+                    | WindowEvent::TextInput(_)
+                    | WindowEvent::PrimaryPaste(..) => {
+                        input.process_event(&event);
+                        location_submitted = input.was_submitted();
+                        consumed = true;
+                    }
+                    _ => {}
+                }
+            }
+
+            // Consumed events only trigger a redraw
+            let event = if consumed {
+                WindowEvent::RedrawRequested
+            } else {
+                focus.process_event(
+                    event,
+                    &mut focus_order(
+                        &mut new_folder_button,
+                        &mut search_input,
+                        name_input.as_mut(),
+                        &mut ok_button,
+                        &mut cancel_button,
+                    ),
+                )
+            };
+
+            match &event {
+                WindowEvent::CloseRequested => return Ok(FileSelectResult::Closed),
+                WindowEvent::RedrawRequested => needs_redraw = true,
+                WindowEvent::Resized(w, h) => new_size = Some((*w, *h)),
+                WindowEvent::Drop(paths) => dropped = paths.clone(),
+                WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
+                    mouse_x = pos.x as i32;
+                    mouse_y = pos.y as i32;
+
+                    // Drag selection: select every visible entry the rectangle touches
+                    if let Some(ref mut band) = rubber_band {
+                        band.current = (
+                            mouse_x.clamp(main_x, main_x + main_w as i32),
+                            mouse_y.clamp(list_y, list_y + list_h as i32),
+                        );
+                        let (dx, dy) = (
+                            band.current.0 - band.origin.0,
+                            band.current.1 - band.origin.1,
+                        );
+                        band.active |= dx.abs().max(dy.abs()) as f32 > 4.0 * scale;
+
+                        if band.active {
+                            let (x, y, w, h) = band.rect();
+                            selected_indices = band.base.clone();
+                            for (vi, &ei) in filtered_entries
+                                .iter()
+                                .skip(scroll.offset())
+                                .take(layout.visible_items)
+                                .enumerate()
+                            {
+                                let (cell_x, cell_y) = layout.cell_origin(vi);
+                                let (cell_x, cell_y) = (main_x + cell_x, list_y + cell_y);
+                                if cell_x < x + w as i32
+                                    && x < cell_x + layout.cell_width as i32
+                                    && cell_y < y + h as i32
+                                    && y < cell_y + layout.row_height as i32
+                                {
+                                    selected_indices.insert(ei);
+                                }
+                            }
+                            needs_redraw = true;
+                        }
+                    }
+
+                    // Update hover states (only when not dragging)
+                    if !scroll.is_dragging() {
                         let old_qa = hovered_quick_access;
                         let old_entry = hovered_entry;
                         let old_drive = hovered_drive;
@@ -904,30 +1770,12 @@ impl FileSelectBuilder {
                         }
 
                         // Check file list hover (only if not over scrollbar)
-                        let scrollbar_width = if scrollbar_hovered {
-                            12.0 * scale
-                        } else {
-                            8.0 * scale
-                        };
-                        let scrollbar_x = main_x + main_w as i32 - scrollbar_width as i32;
-
-                        // Update scrollbar hover state
-                        scrollbar_hovered = mouse_x >= scrollbar_x
-                            && mouse_x < main_x + main_w as i32
-                            && mouse_y >= list_y
-                            && mouse_y < list_y + list_h as i32
-                            && !filtered_entries.is_empty();
-
-                        if mouse_x >= main_x
-                            && mouse_x < scrollbar_x
-                            && mouse_y >= list_y
-                            && mouse_y < list_y + list_h as i32
+                        if point_in_rect(mouse_x, mouse_y, main_x, list_y, main_w, list_h)
+                            && !scroll.over_bar(mouse_x, mouse_y)
+                            && let Some(vi) = layout.entry_at(mouse_x - main_x, mouse_y - list_y)
+                            && let Some(&ei) = filtered_entries.get(scroll.offset() + vi)
                         {
-                            let rel_y = (mouse_y - list_y) as usize;
-                            let idx = scroll_offset + rel_y / item_height as usize;
-                            if idx < filtered_entries.len() {
-                                hovered_entry = Some(filtered_entries[idx]);
-                            }
+                            hovered_entry = Some(ei);
                         }
 
                         if old_qa != hovered_quick_access
@@ -938,60 +1786,10 @@ impl FileSelectBuilder {
                         }
                     }
                 }
-                WindowEvent::ButtonPress(MouseButton::Left, _) => {
-                    let mut clicking_scrollbar = false;
-
-                    // Check if clicking anywhere in scrollbar area (thumb OR track)
-                    if !filtered_entries.is_empty() {
-                        let scrollbar_width = if scrollbar_hovered {
-                            12.0 * scale
-                        } else {
-                            8.0 * scale
-                        };
-                        let scrollbar_x = main_x + main_w as i32 - scrollbar_width as i32;
-
-                        // Block all clicks in scrollbar area
-                        if mouse_x >= scrollbar_x
-                            && mouse_x < main_x + main_w as i32
-                            && mouse_y >= list_y
-                            && mouse_y < list_y + list_h as i32
-                        {
-                            clicking_scrollbar = true;
-
-                            // Now check if clicking specifically on the thumb for dragging
-                            let scrollbar_y = list_y;
-                            let visible_items = (list_h / item_height) as usize;
-                            let total_items = filtered_entries.len();
-
-                            if visible_items < total_items {
-                                let scrollbar_h_f32 = list_h as f32 - 8.0 * scale;
-                                let thumb_h_f32 = (visible_items as f32 / total_items as f32
-                                    * scrollbar_h_f32)
-                                    .max(20.0 * scale);
-                                let thumb_h = thumb_h_f32 as i32;
-
-                                let max_scroll = total_items - visible_items;
-                                let max_thumb_y = scrollbar_h_f32 as i32 - thumb_h;
-                                let thumb_y = if max_thumb_y > 0 {
-                                    ((scroll_offset as f32 / max_scroll as f32)
-                                        * max_thumb_y as f32)
-                                        as i32
-                                } else {
-                                    0
-                                };
-
-                                let rel_y = mouse_y - scrollbar_y;
-                                if mouse_x >= scrollbar_x
-                                    && mouse_x < scrollbar_x + scrollbar_width as i32
-                                    && rel_y >= scrollbar_y as i32 + thumb_y
-                                    && rel_y < scrollbar_y as i32 + thumb_y + thumb_h
-                                {
-                                    thumb_drag = true;
-                                    thumb_drag_offset = Some(mouse_y - (scrollbar_y + thumb_y));
-                                }
-                            }
-                        }
-                    }
+                WindowEvent::ButtonPress(MouseButton::Left, modifiers) => {
+                    let clicking_scrollbar = scroll.over_bar(mouse_x, mouse_y);
+                    let ctrl = modifiers.contains(Modifiers::CTRL);
+                    let previous_selection = selected_indices.clone();
 
                     // Toolbar buttons
                     let nav_y = padding as i32 + (4.0 * scale) as i32;
@@ -1004,15 +1802,18 @@ impl FileSelectBuilder {
                                 navigate_to_directory(
                                     history[history_index].clone(),
                                     &mut current_dir,
+                                    &mut recent,
                                     &mut history,
                                     &mut history_index,
                                     &mut all_entries,
+                                    &mut loader,
                                     self.directory,
                                     show_hidden,
+                                    sort,
                                     &search_text,
                                     &mut filtered_entries,
                                     &mut selected_indices,
-                                    &mut scroll_offset,
+                                    &mut scroll,
                                     &self.filters,
                                 );
                                 needs_redraw = true;
@@ -1027,15 +1828,18 @@ impl FileSelectBuilder {
                                 navigate_to_directory(
                                     history[history_index].clone(),
                                     &mut current_dir,
+                                    &mut recent,
                                     &mut history,
                                     &mut history_index,
                                     &mut all_entries,
+                                    &mut loader,
                                     self.directory,
                                     show_hidden,
+                                    sort,
                                     &search_text,
                                     &mut filtered_entries,
                                     &mut selected_indices,
-                                    &mut scroll_offset,
+                                    &mut scroll,
                                     &self.filters,
                                 );
                                 needs_redraw = true;
@@ -1049,15 +1853,18 @@ impl FileSelectBuilder {
                                 navigate_to_directory(
                                     parent.to_path_buf(),
                                     &mut current_dir,
+                                    &mut recent,
                                     &mut history,
                                     &mut history_index,
                                     &mut all_entries,
+                                    &mut loader,
                                     self.directory,
                                     show_hidden,
+                                    sort,
                                     &search_text,
                                     &mut filtered_entries,
                                     &mut selected_indices,
-                                    &mut scroll_offset,
+                                    &mut scroll,
                                     &self.filters,
                                 );
                                 needs_redraw = true;
@@ -1071,31 +1878,117 @@ impl FileSelectBuilder {
                                 navigate_to_directory(
                                     home,
                                     &mut current_dir,
+                                    &mut recent,
                                     &mut history,
                                     &mut history_index,
                                     &mut all_entries,
+                                    &mut loader,
                                     self.directory,
                                     show_hidden,
+                                    sort,
                                     &search_text,
                                     &mut filtered_entries,
                                     &mut selected_indices,
-                                    &mut scroll_offset,
+                                    &mut scroll,
                                     &self.filters,
                                 );
                                 needs_redraw = true;
                             }
                         }
                         // Hidden toggle
-                        else if mouse_x >= (padding as f32 + 150.0 * scale) as i32
-                            && mouse_x < (padding as f32 + 178.0 * scale) as i32
+                        else if mouse_x >= hidden_toggle_x
+                            && mouse_x < hidden_toggle_x + toggle_size
                         {
-                            show_hidden = !show_hidden;
-                            load_directory(
-                                &current_dir,
-                                &mut all_entries,
-                                self.directory,
-                                show_hidden,
-                            );
+                            toggle_hidden = true;
+                        }
+                        // List/icon view toggle
+                        else if mouse_x >= view_toggle_x && mouse_x < view_toggle_x + toggle_size
+                        {
+                            layout = ListLayout::new(!layout.grid, list_w, list_h, scale);
+                            GRID_VIEW.store(layout.grid, Ordering::Relaxed);
+                            layout.fit(&mut scroll, filtered_entries.len());
+                            renaming = None;
+                            needs_redraw = true;
+                        }
+                        // Preview pane toggle
+                        else if mouse_x >= preview_toggle_x
+                            && mouse_x < preview_toggle_x + toggle_size
+                        {
+                            show_preview = !show_preview;
+                            main_w = main_width(window_width, show_preview);
+                            list_w = main_w - (12.0 * scale) as u32;
+                            layout = ListLayout::new(layout.grid, list_w, list_h, scale);
+                            layout.fit(&mut scroll, filtered_entries.len());
+                            renaming = None;
+                            location_input = None;
+                            needs_redraw = true;
+                        }
+                    }
+
+                    // "+" in the PLACES header: bookmark the current directory
+                    let plus_x = sidebar_x + sidebar_width as i32 - (24.0 * scale) as i32;
+                    let plus_size = (22.0 * scale) as u32;
+                    if point_in_rect(mouse_x, mouse_y, plus_x, sidebar_y, plus_size, plus_size)
+                        && !recent
+                        && !quick_access.iter().any(|qa| qa.path == current_dir)
+                    {
+                        if let Err(e) = bookmarks::add(&current_dir) {
+                            show_file_error(&tr_args("Could not add bookmark: {}", &[&e]), colors)?;
+                        }
+                        quick_access = build_places(!self.directory);
+                        needs_redraw = true;
+                    }
+
+                    // Path bar click: edit the location
+                    if location_input.is_none()
+                        && point_in_rect(mouse_x, mouse_y, main_x, main_y, main_w, path_bar_height)
+                    {
+                        location_input =
+                            Some(location_bar_input(&current_dir, main_x, main_y, main_w));
+                        renaming = None;
+                        needs_redraw = true;
+                    }
+
+                    // Column header click: sort by that column, toggling direction
+                    let header_y = main_y + path_bar_height as i32;
+                    if mouse_x >= main_x
+                        && mouse_x < main_x + main_w as i32
+                        && mouse_y >= header_y
+                        && mouse_y < header_y + (26.0 * scale) as i32
+                    {
+                        let rel_x = mouse_x - main_x;
+                        let show_details =
+                            main_w >= name_col_width + size_col_width + date_col_width;
+                        let column = if rel_x < name_col_width as i32 || !show_details {
+                            SortColumn::Name
+                        } else if rel_x < (name_col_width + size_col_width) as i32 {
+                            SortColumn::Size
+                        } else {
+                            SortColumn::Modified
+                        };
+                        sort = SortOrder {
+                            column,
+                            ascending: sort.column != column || !sort.ascending,
+                        };
+                        sort_entries(&mut all_entries, sort);
+                        update_filtered(
+                            &all_entries,
+                            &search_text,
+                            &mut filtered_entries,
+                            &self.filters,
+                        );
+                        selected_indices.clear();
+                        needs_redraw = true;
+                    }
+
+                    // Quick access click
+                    if !clicking_scrollbar {
+                        if let Some(idx) = hovered_quick_access
+                            && matches!(quick_access[idx].icon, QuickAccessIcon::Recent)
+                        {
+                            recent = true;
+                            loader.cancel();
+                            load_recent(&mut all_entries, show_hidden, sort);
                             update_filtered(
                                 &all_entries,
                                 &search_text,
@@ -1103,27 +1996,25 @@ impl FileSelectBuilder {
                                 &self.filters,
                             );
                             selected_indices.clear();
-                            scroll_offset = 0;
+                            scroll.scroll_to(0);
                             needs_redraw = true;
-                        }
-                    }
-
-                    // Quick access click
-                    if !clicking_scrollbar {
-                        if let Some(idx) = hovered_quick_access {
+                        } else if let Some(idx) = hovered_quick_access {
                             let qa = &quick_access[idx];
                             navigate_to_directory(
                                 qa.path.clone(),
                                 &mut current_dir,
+                                &mut recent,
                                 &mut history,
                                 &mut history_index,
                                 &mut all_entries,
+                                &mut loader,
                                 self.directory,
                                 show_hidden,
+                                sort,
                                 &search_text,
                                 &mut filtered_entries,
                                 &mut selected_indices,
-                                &mut scroll_offset,
+                                &mut scroll,
                                 &self.filters,
                             );
                             needs_redraw = true;
@@ -1135,195 +2026,325 @@ impl FileSelectBuilder {
                             navigate_to_directory(
                                 drive.mount_point.clone(),
                                 &mut current_dir,
+                                &mut recent,
                                 &mut history,
                                 &mut history_index,
                                 &mut all_entries,
+                                &mut loader,
                                 self.directory,
                                 show_hidden,
+                                sort,
                                 &search_text,
                                 &mut filtered_entries,
                                 &mut selected_indices,
-                                &mut scroll_offset,
+                                &mut scroll,
                                 &self.filters,
                             );
                             needs_redraw = true;
                         }
 
-                        // File list click
+                        // File list click: select, or activate on double click
                         if let Some(ei) = hovered_entry {
-                            if self.multiple {
-                                // Toggle selection in multiple mode
+                            let double_click = last_click.is_some_and(|(prev, time)| {
+                                prev == ei && time.elapsed() <= DOUBLE_CLICK_TIME
+                            });
+                            last_click = if double_click {
+                                None
+                            } else {
+                                Some((ei, Instant::now()))
+                            };
+
+                            let entry = &all_entries[ei];
+                            if double_click && entry.is_dir {
+                                navigate_to_directory(
+                                    entry_destination(entry, self.follow_symlinks),
+                                    &mut current_dir,
+                                    &mut recent,
+                                    &mut history,
+                                    &mut history_index,
+                                    &mut all_entries,
+                                    &mut loader,
+                                    self.directory,
+                                    show_hidden,
+                                    sort,
+                                    &search_text,
+                                    &mut filtered_entries,
+                                    &mut selected_indices,
+                                    &mut scroll,
+                                    &self.filters,
+                                );
+                            } else if double_click && self.multiple {
+                                // Accept the selection, including the clicked file
+                                selected_indices.insert(ei);
+                                let selected_files = selected_paths(
+                                    &all_entries,
+                                    &selected_indices,
+                                    allow_directories,
+                                );
+                                return Ok(FileSelectResult::SelectedMultiple(selected_files));
+                            } else if double_click {
+                                if confirm_save(&entry.path)? {
+                                    return Ok(FileSelectResult::Selected(entry.path.clone()));
+                                }
+                            } else if self.multiple && modifiers.contains(Modifiers::SHIFT) {
+                                // Shift+click: select the range from the anchor
+                                let pos = filtered_entries.iter().position(|&e| e == ei);
+                                let anchor = selection_anchor
+                                    .and_then(|a| filtered_entries.iter().position(|&e| e == a))
+                                    .or(pos);
+                                if let (Some(anchor), Some(pos)) = (anchor, pos) {
+                                    if !ctrl {
+                                        selected_indices.clear();
+                                    }
+                                    let range = anchor.min(pos)..=anchor.max(pos);
+                                    selected_indices.extend(&filtered_entries[range]);
+                                }
+                            } else if self.multiple && ctrl {
+                                // Ctrl+click: toggle
                                 if selected_indices.contains(&ei) {
                                     selected_indices.remove(&ei);
                                 } else {
                                     selected_indices.insert(ei);
                                 }
+                                selection_anchor = Some(ei);
                             } else {
-                                // Single click - activate if already selected (double click behavior)
-                                if selected_indices.contains(&ei) {
-                                    let entry = &all_entries[ei];
-                                    if entry.is_dir {
-                                        navigate_to(
-                                            entry.path.clone(),
-                                            &mut current_dir,
-                                            &mut history,
-                                            &mut history_index,
-                                        );
-                                        load_directory(
-                                            &current_dir,
-                                            &mut all_entries,
-                                            self.directory,
-                                            show_hidden,
-                                        );
-                                        update_filtered(
-                                            &all_entries,
-                                            &search_text,
-                                            &mut filtered_entries,
-                                            &self.filters,
-                                        );
-                                        selected_indices.clear();
-                                        scroll_offset = 0;
-                                    } else if !self.directory {
-                                        return Ok(FileSelectResult::Selected(entry.path.clone()));
-                                    }
-                                } else {
-                                    selected_indices.clear();
-                                    selected_indices.insert(ei);
+                                selected_indices.clear();
+                                selected_indices.insert(ei);
+                                selection_anchor = Some(ei);
+
+                                // Picking a file in save mode fills in its name
+                                if let Some(ref mut name_input) = name_input
+                                    && !entry.is_dir
+                                {
+                                    name_input.set_text(&entry.name);
                                 }
                             }
                             needs_redraw = true;
                         }
                     }
 
-                    // Search input focus
-                    let in_search = mouse_x >= search_x
-                        && mouse_x < search_x + search_width as i32
+                    // Start a drag selection over the list
+                    if self.multiple
+                        && !clicking_scrollbar
+                        && point_in_rect(mouse_x, mouse_y, main_x, list_y, main_w, list_h)
+                    {
+                        rubber_band = Some(RubberBand {
+                            origin: (mouse_x, mouse_y),
+                            current: (mouse_x, mouse_y),
+                            base: if ctrl {
+                                previous_selection
+                            } else {
+                                HashSet::new()
+                            },
+                            active: false,
+                        });
+                    }
+
+                    // A click away from the widgets leaves the keys to the
+                    // file list
+                    let mut widgets = focus_order(
+                        &mut new_folder_button,
+                        &mut search_input,
+                        name_input.as_mut(),
+                        &mut ok_button,
+                        &mut cancel_button,
+                    );
+                    if location_input.is_some()
+                        || !widgets.iter().any(|w| {
+                            point_in_rect(mouse_x, mouse_y, w.x(), w.y(), w.width(), w.height())
+                        })
+                    {
+                        focus.focus(None, &mut widgets);
+                    }
+                }
+                // Pasting focuses the input the text lands in
+                WindowEvent::PrimaryPaste(..) if location_input.is_none() => {
+                    let in_search = mouse_x >= search_input.x()
+                        && mouse_x < search_input.x() + search_width as i32
                         && mouse_y >= search_y
                         && mouse_y < search_y + (32.0 * scale) as i32;
-                    search_input.set_focus(in_search);
+                    let in_name = name_input.as_ref().is_some_and(|name_input| {
+                        point_in_rect(
+                            mouse_x,
+                            mouse_y,
+                            name_input.x(),
+                            name_input.y(),
+                            name_input.width(),
+                            name_input.height(),
+                        )
+                    });
+                    if in_search || in_name {
+                        focus.focus(
+                            Some(if in_search { 1 } else { 2 }),
+                            &mut focus_order(
+                                &mut new_folder_button,
+                                &mut search_input,
+                                name_input.as_mut(),
+                                &mut ok_button,
+                                &mut cancel_button,
+                            ),
+                        );
+                    }
                 }
-                WindowEvent::ButtonRelease(_, _) => {
-                    thumb_drag = false;
-                    thumb_drag_offset = None;
+                WindowEvent::ButtonPress(MouseButton::Right, _) => {
+                    let menu = match (hovered_quick_access, hovered_entry) {
+                        (Some(i), _) if quick_access[i].removable => {
+                            Some((MenuTarget::Bookmark(i), &BOOKMARK_MENU_ITEMS[..]))
+                        }
+                        (_, Some(ei))
+                            if self.allow_manage
+                                && is_child_of(&all_entries[ei].path, &current_dir) =>
+                        {
+                            selected_indices.clear();
+                            selected_indices.insert(ei);
+                            Some((MenuTarget::Entry(ei), &CONTEXT_MENU_ITEMS[..]))
+                        }
+                        _ => None,
+                    };
+
+                    if let Some((target, items)) = menu {
+                        // Keep the menu inside the window
+                        let width = (BASE_MENU_WIDTH as f32 * scale) as u32;
+                        let menu_h = item_height * items.len() as u32;
+                        context_menu = Some(ContextMenu {
+                            target,
+                            items,
+                            x: mouse_x.min(window_width as i32 - width as i32),
+                            y: mouse_y.min(window_height as i32 - menu_h as i32),
+                            width,
+                            item_height,
+                            hovered: None,
+                        });
+                        needs_redraw = true;
+                    }
                 }
-                WindowEvent::Scroll(direction) => {
-                    match direction {
-                        crate::backend::ScrollDirection::Up => {
-                            if scroll_offset > 0 {
-                                scroll_offset = scroll_offset.saturating_sub(3);
-                                needs_redraw = true;
-                            }
+                WindowEvent::TextInput(c) => {
+                    // Type-ahead: jump to the first entry starting with what was typed
+                    let name_focused = name_input.as_ref().is_some_and(|i| i.has_focus());
+                    if !search_input.has_focus() && !name_focused {
+                        if last_type_ahead.elapsed() > TYPE_AHEAD_TIMEOUT {
+                            type_ahead.clear();
                         }
-                        crate::backend::ScrollDirection::Down => {
-                            if scroll_offset + visible_items < filtered_entries.len() {
-                                scroll_offset = (scroll_offset + 3)
-                                    .min(filtered_entries.len().saturating_sub(visible_items));
-                                needs_redraw = true;
+                        last_type_ahead = Instant::now();
+                        type_ahead.extend(c.to_lowercase());
+
+                        if let Some(pos) = filtered_entries.iter().position(|&ei| {
+                            let name = &all_entries[ei].name;
+                            name != ".." && name.to_lowercase().starts_with(&type_ahead)
+                        }) {
+                            let ei = filtered_entries[pos];
+                            selected_indices.clear();
+                            selected_indices.insert(ei);
+                            scroll.scroll_into_view(pos);
+                            if let Some(ref mut name_input) = name_input
+                                && !all_entries[ei].is_dir
+                            {
+                                name_input.set_text(&all_entries[ei].name);
                             }
+                            needs_redraw = true;
                         }
-                        _ => {}
+                    }
+                }
+                WindowEvent::ButtonRelease(_, _) => {
+                    if rubber_band.take().is_some_and(|b| b.active) {
+                        needs_redraw = true;
                     }
                 }
                 WindowEvent::KeyPress(key_event) => {
                     const KEY_UP: u32 = 0xff52;
                     const KEY_DOWN: u32 = 0xff54;
+                    const KEY_LEFT: u32 = 0xff51;
+                    const KEY_RIGHT: u32 = 0xff53;
                     const KEY_RETURN: u32 = 0xff0d;
-                    const KEY_ESCAPE: u32 = 0xff1b;
                     const KEY_BACKSPACE: u32 = 0xff08;
+                    const KEY_H: u32 = 0x68;
+                    const KEY_L: u32 = 0x6c;
 
-                    if !search_input.has_focus() {
-                        match key_event.keysym {
-                            KEY_UP => {
-                                if !filtered_entries.is_empty() {
-                                    let new_index =
-                                        if let Some(&sel) = selected_indices.iter().next() {
-                                            if let Some(pos) =
-                                                filtered_entries.iter().position(|&e| e == sel)
-                                            {
-                                                if pos > 0 {
-                                                    Some(filtered_entries[pos - 1])
-                                                } else {
-                                                    Some(sel)
-                                                }
-                                            } else {
-                                                Some(filtered_entries[0])
-                                            }
-                                        } else {
-                                            Some(filtered_entries[0])
-                                        };
+                    if key_event.keysym == KEY_ESCAPE {
+                        return Ok(FileSelectResult::Cancelled);
+                    }
 
-                                    if let Some(idx) = new_index {
-                                        if self.multiple {
-                                            if selected_indices.contains(&idx) {
-                                                selected_indices.remove(&idx);
-                                            } else {
-                                                selected_indices.insert(idx);
-                                            }
-                                        } else {
-                                            selected_indices.clear();
-                                            selected_indices.insert(idx);
-                                        }
+                    // Ctrl+H: show or hide hidden files
+                    if key_event.keysym == KEY_H && key_event.modifiers.contains(Modifiers::CTRL) {
+                        toggle_hidden = true;
+                    }
 
+                    // Ctrl+L: type a location
+                    if key_event.keysym == KEY_L && key_event.modifiers.contains(Modifiers::CTRL) {
+                        location_input =
+                            Some(location_bar_input(&current_dir, main_x, main_y, main_w));
+                        focus.focus(
+                            None,
+                            &mut focus_order(
+                                &mut new_folder_button,
+                                &mut search_input,
+                                name_input.as_mut(),
+                                &mut ok_button,
+                                &mut cancel_button,
+                            ),
+                        );
+                        renaming = None;
+                        needs_redraw = true;
+                    }
+
+                    let name_focused = name_input.as_ref().is_some_and(|i| i.has_focus());
+                    if !search_input.has_focus() && !name_focused {
+                        match key_event.keysym {
+                            KEY_UP | KEY_DOWN | KEY_LEFT | KEY_RIGHT => {
+                                // Up/Down move a whole row; Left/Right only apply in icon view
+                                let step = match key_event.keysym {
+                                    KEY_UP => -(layout.columns as isize),
+                                    KEY_DOWN => layout.columns as isize,
+                                    KEY_LEFT if layout.grid => -1,
+                                    KEY_RIGHT if layout.grid => 1,
+                                    _ => 0,
+                                };
+                                if step != 0 && !filtered_entries.is_empty() {
+                                    let idx = if let Some(&sel) = selected_indices.iter().next() {
                                         if let Some(pos) =
-                                            filtered_entries.iter().position(|&e| e == idx)
+                                            filtered_entries.iter().position(|&e| e == sel)
                                         {
-                                            if pos < scroll_offset {
-                                                scroll_offset = pos;
-                                            }
-                                        }
-                                        needs_redraw = true;
-                                    }
-                                }
-                            }
-                            KEY_DOWN => {
-                                if !filtered_entries.is_empty() {
-                                    let new_index =
-                                        if let Some(&sel) = selected_indices.iter().next() {
-                                            if let Some(pos) =
-                                                filtered_entries.iter().position(|&e| e == sel)
+                                            let target = pos as isize + step;
+                                            if (0..filtered_entries.len() as isize)
+                                                .contains(&target)
                                             {
-                                                if pos + 1 < filtered_entries.len() {
-                                                    Some(filtered_entries[pos + 1])
-                                                } else {
-                                                    Some(sel)
-                                                }
+                                                filtered_entries[target as usize]
                                             } else {
-                                                Some(filtered_entries[0])
+                                                sel
                                             }
                                         } else {
-                                            Some(filtered_entries[0])
-                                        };
+                                            filtered_entries[0]
+                                        }
+                                    } else {
+                                        filtered_entries[0]
+                                    };
 
-                                    if let Some(idx) = new_index {
-                                        if self.multiple {
-                                            if selected_indices.contains(&idx) {
-                                                selected_indices.remove(&idx);
-                                            } else {
-                                                selected_indices.insert(idx);
-                                            }
+                                    if self.multiple {
+                                        if selected_indices.contains(&idx) {
+                                            selected_indices.remove(&idx);
                                         } else {
-                                            selected_indices.clear();
                                             selected_indices.insert(idx);
                                         }
+                                    } else {
+                                        selected_indices.clear();
+                                        selected_indices.insert(idx);
+                                    }
 
-                                        if let Some(pos) =
-                                            filtered_entries.iter().position(|&e| e == idx)
-                                        {
-                                            if pos + 1 >= scroll_offset + visible_items {
-                                                scroll_offset = pos + 1 - visible_items + 1;
-                                            }
-                                        }
-                                        needs_redraw = true;
+                                    if let Some(pos) =
+                                        filtered_entries.iter().position(|&e| e == idx)
+                                    {
+                                        scroll.scroll_into_view(pos);
                                     }
+                                    needs_redraw = true;
                                 }
                             }
                             KEY_RETURN => {
                                 if self.multiple && !selected_indices.is_empty() {
-                                    let selected_files: Vec<PathBuf> = selected_indices
-                                        .iter()
-                                        .filter(|&ei| !all_entries[*ei].is_dir)
-                                        .map(|&ei| all_entries[ei].path.clone())
-                                        .collect();
+                                    let selected_files = selected_paths(
+                                        &all_entries,
+                                        &selected_indices,
+                                        allow_directories,
+                                    );
                                     if !selected_files.is_empty() {
                                         return Ok(FileSelectResult::SelectedMultiple(
                                             selected_files,
@@ -1333,22 +2354,30 @@ impl FileSelectBuilder {
                                     let entry = &all_entries[sel];
                                     if entry.is_dir {
                                         navigate_to_directory(
-                                            entry.path.clone(),
+                                            entry_destination(entry, self.follow_symlinks),
                                             &mut current_dir,
+                                            &mut recent,
                                             &mut history,
                                             &mut history_index,
                                             &mut all_entries,
+                                            &mut loader,
                                             self.directory,
                                             show_hidden,
+                                            sort,
                                             &search_text,
                                             &mut filtered_entries,
                                             &mut selected_indices,
-                                            &mut scroll_offset,
+                                            &mut scroll,
                                             &self.filters,
                                         );
                                         needs_redraw = true;
                                     } else if !self.directory {
-                                        return Ok(FileSelectResult::Selected(entry.path.clone()));
+                                        if confirm_save(&entry.path)? {
+                                            return Ok(FileSelectResult::Selected(
+                                                entry.path.clone(),
+                                            ));
+                                        }
+                                        needs_redraw = true;
                                     }
                                 }
                             }
@@ -1357,23 +2386,23 @@ impl FileSelectBuilder {
                                     navigate_to_directory(
                                         parent.to_path_buf(),
                                         &mut current_dir,
+                                        &mut recent,
                                         &mut history,
                                         &mut history_index,
                                         &mut all_entries,
+                                        &mut loader,
                                         self.directory,
                                         show_hidden,
+                                        sort,
                                         &search_text,
                                         &mut filtered_entries,
                                         &mut selected_indices,
-                                        &mut scroll_offset,
+                                        &mut scroll,
                                         &self.filters,
                                     );
                                     needs_redraw = true;
                                 }
                             }
-                            KEY_ESCAPE => {
-                                return Ok(FileSelectResult::Cancelled);
-                            }
                             _ => {}
                         }
                     }
@@ -1381,6 +2410,74 @@ impl FileSelectBuilder {
                 _ => {}
             }
 
+            // Hidden files toggle (toolbar button or Ctrl+H)
+            if toggle_hidden {
+                show_hidden = !show_hidden;
+                SHOW_HIDDEN.store(show_hidden, Ordering::Relaxed);
+                reload_entries(
+                    recent,
+                    &current_dir,
+                    &mut all_entries,
+                    &mut loader,
+                    self.directory,
+                    show_hidden,
+                    sort,
+                );
+                update_filtered(
+                    &all_entries,
+                    &search_text,
+                    &mut filtered_entries,
+                    &self.filters,
+                );
+                selected_indices.clear();
+                scroll.scroll_to(0);
+                needs_redraw = true;
+            }
+
+            // Reload after changes on disk, keeping the selection
+            if dir_changed && renaming.is_none() && context_menu.is_none() && rubber_band.is_none()
+            {
+                dir_changed = false;
+                refresh_entries(
+                    recent,
+                    &current_dir,
+                    &mut all_entries,
+                    &mut loader,
+                    self.directory,
+                    show_hidden,
+                    sort,
+                    &search_text,
+                    &mut filtered_entries,
+                    &mut selected_indices,
+                    &mut selection_anchor,
+                    &self.filters,
+                );
+                layout.fit(&mut scroll, filtered_entries.len());
+                hovered_entry = None;
+                last_click = None;
+                needs_redraw = true;
+            }
+
+            // Show a directory read in the background once it is in
+            if renaming.is_none()
+                && context_menu.is_none()
+                && rubber_band.is_none()
+                && let Some(entries) = loader.receive()
+            {
+                all_entries = entries;
+                update_filtered(
+                    &all_entries,
+                    &search_text,
+                    &mut filtered_entries,
+                    &self.filters,
+                );
+                selected_indices.clear();
+                selection_anchor = None;
+                hovered_entry = None;
+                last_click = None;
+                needs_redraw = true;
+            }
+
             // Process search input
             if search_input.process_event(&event) {
                 let new_search = search_input.text().to_lowercase();
@@ -1393,28 +2490,258 @@ impl FileSelectBuilder {
                         &self.filters,
                     );
                     selected_indices.clear();
-                    scroll_offset = 0;
+                    scroll.scroll_to(0);
                 }
                 needs_redraw = true;
             }
 
+            // Process filename input
+            let mut name_submitted = false;
+            if let Some(ref mut name_input) = name_input {
+                needs_redraw |= name_input.process_event(&event);
+                name_submitted = name_input.was_submitted();
+            }
+
+            // Process the list's scrollbars and paging keys
+            needs_redraw |= scroll.process_event(&event);
+
             // Process buttons
             needs_redraw |= ok_button.process_event(&event);
             needs_redraw |= cancel_button.process_event(&event);
+            needs_redraw |= new_folder_button.process_event(&event);
+            needs_redraw |= tooltip.process_event(&event);
 
-            if ok_button.was_clicked() {
-                if self.multiple && !selected_indices.is_empty() {
-                    let selected_files: Vec<PathBuf> = selected_indices
+            // Batch pending events
+            while let Some(ev) = window.poll_for_event()? {
+                // The location bar and an inline rename keep Tab and Space
+                let ev = if location_input.is_none() && renaming.is_none() {
+                    focus.process_event(
+                        ev,
+                        &mut focus_order(
+                            &mut new_folder_button,
+                            &mut search_input,
+                            name_input.as_mut(),
+                            &mut ok_button,
+                            &mut cancel_button,
+                        ),
+                    )
+                } else {
+                    ev
+                };
+                match &ev {
+                    WindowEvent::CloseRequested => {
+                        return Ok(FileSelectResult::Closed);
+                    }
+                    WindowEvent::RedrawRequested => needs_redraw = true,
+                    WindowEvent::Resized(w, h) => new_size = Some((*w, *h)),
+                    WindowEvent::Drop(paths) => dropped = paths.clone(),
+                    WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
+                        mouse_x = pos.x as i32;
+                        mouse_y = pos.y as i32;
+                    }
+                    WindowEvent::ButtonRelease(_, _) => {
+                        needs_redraw |= rubber_band.take().is_some_and(|b| b.active);
+                    }
+                    _ => {}
+                }
+
+                if location_input.is_none() && renaming.is_none() && context_menu.is_none() {
+                    needs_redraw |= scroll.process_event(&ev);
+                }
+                if let Some(ref mut name_input) = name_input {
+                    needs_redraw |= name_input.process_event(&ev);
+                    name_submitted |= name_input.was_submitted();
+                }
+                needs_redraw |= ok_button.process_event(&ev);
+                needs_redraw |= cancel_button.process_event(&ev);
+                needs_redraw |= new_folder_button.process_event(&ev);
+                needs_redraw |= tooltip.process_event(&ev);
+                if let Some((_, ref mut input)) = renaming {
+                    needs_redraw |= input.process_event(&ev);
+                    rename_submitted |= input.was_submitted();
+                }
+                if let Some(ref mut input) = location_input {
+                    needs_redraw |= input.process_event(&ev);
+                    location_submitted |= input.was_submitted();
+                }
+            }
+
+            // Go to a typed location; a file is selected in its directory
+            if location_submitted && let Some(input) = location_input.take() {
+                let target = current_dir.join(expand_tilde(input.text().trim()));
+                let dir = if target.is_dir() {
+                    Some(target.clone())
+                } else if target.is_file() {
+                    target.parent().map(Path::to_path_buf)
+                } else {
+                    None
+                };
+
+                if let Some(dir) = dir {
+                    navigate_to_directory(
+                        dir,
+                        &mut current_dir,
+                        &mut recent,
+                        &mut history,
+                        &mut history_index,
+                        &mut all_entries,
+                        &mut loader,
+                        self.directory,
+                        show_hidden,
+                        sort,
+                        &search_text,
+                        &mut filtered_entries,
+                        &mut selected_indices,
+                        &mut scroll,
+                        &self.filters,
+                    );
+                    layout.fit(&mut scroll, filtered_entries.len());
+                    if let Some(pos) = filtered_entries
                         .iter()
-                        .filter(|&ei| !all_entries[*ei].is_dir)
-                        .map(|&ei| all_entries[ei].path.clone())
-                        .collect();
+                        .position(|&ei| all_entries[ei].path == target)
+                    {
+                        let ei = filtered_entries[pos];
+                        selected_indices.insert(ei);
+                        scroll.scroll_into_view(pos);
+                        if let Some(ref mut name_input) = name_input {
+                            name_input.set_text(&all_entries[ei].name);
+                        }
+                    }
+                } else {
+                    show_file_error(
+                        &tr_args("\"{}\" does not exist.", &[&target.display()]),
+                        colors,
+                    )?;
+                    location_input = Some(input);
+                }
+                needs_redraw = true;
+            }
+
+            // A dropped folder is opened; dropped files are selected in theirs
+            let drop_dir = dropped.first().and_then(|path| {
+                if path.is_dir() {
+                    Some(path.clone())
+                } else {
+                    path.parent().map(Path::to_path_buf)
+                }
+            });
+            if let Some(dir) = drop_dir {
+                renaming = None;
+                location_input = None;
+                context_menu = None;
+                rubber_band = None;
+                navigate_to_directory(
+                    dir,
+                    &mut current_dir,
+                    &mut recent,
+                    &mut history,
+                    &mut history_index,
+                    &mut all_entries,
+                    &mut loader,
+                    self.directory,
+                    show_hidden,
+                    sort,
+                    &search_text,
+                    &mut filtered_entries,
+                    &mut selected_indices,
+                    &mut scroll,
+                    &self.filters,
+                );
+                layout.fit(&mut scroll, filtered_entries.len());
+                let limit = if self.multiple { dropped.len() } else { 1 };
+                for path in dropped.iter().take(limit) {
+                    if let Some(pos) = filtered_entries
+                        .iter()
+                        .position(|&ei| all_entries[ei].path == *path)
+                    {
+                        let ei = filtered_entries[pos];
+                        selected_indices.insert(ei);
+                        scroll.scroll_into_view(pos);
+                        if let Some(ref mut name_input) = name_input {
+                            name_input.set_text(&all_entries[ei].name);
+                        }
+                    }
+                }
+                needs_redraw = true;
+            }
+
+            // Commit an inline rename
+            if rename_submitted && let Some((ei, input)) = renaming.take() {
+                let new_name = input.text().trim();
+                if new_name != all_entries[ei].name
+                    && rename_entry(&current_dir, &all_entries[ei].path, new_name, colors)?
+                {
+                    reload_entries(
+                        recent,
+                        &current_dir,
+                        &mut all_entries,
+                        &mut loader,
+                        self.directory,
+                        show_hidden,
+                        sort,
+                    );
+                    update_filtered(
+                        &all_entries,
+                        &search_text,
+                        &mut filtered_entries,
+                        &self.filters,
+                    );
+                    selected_indices.clear();
+                }
+                needs_redraw = true;
+            }
+
+            // Handle button activation (including clicks seen in the batch above)
+            let ok_clicked = ok_button.was_clicked();
+            let typed_name = name_input
+                .as_ref()
+                .map(|i| i.text().trim().to_string())
+                .filter(|name| !name.is_empty());
+
+            if let Some(name) = typed_name.filter(|_| ok_clicked || name_submitted) {
+                // Save to the typed name, which need not exist yet; a typed
+                // directory name is entered instead
+                let path = current_dir.join(name);
+                if path.is_dir() {
+                    navigate_to_directory(
+                        path,
+                        &mut current_dir,
+                        &mut recent,
+                        &mut history,
+                        &mut history_index,
+                        &mut all_entries,
+                        &mut loader,
+                        self.directory,
+                        show_hidden,
+                        sort,
+                        &search_text,
+                        &mut filtered_entries,
+                        &mut selected_indices,
+                        &mut scroll,
+                        &self.filters,
+                    );
+                    if let Some(ref mut name_input) = name_input {
+                        name_input.set_text("");
+                    }
+                    needs_redraw = true;
+                } else if confirm_save(&path)? {
+                    return Ok(FileSelectResult::Selected(path));
+                } else {
+                    needs_redraw = true;
+                }
+            } else if ok_clicked {
+                if self.multiple && !selected_indices.is_empty() {
+                    let selected_files =
+                        selected_paths(&all_entries, &selected_indices, allow_directories);
                     if !selected_files.is_empty() {
                         return Ok(FileSelectResult::SelectedMultiple(selected_files));
                     }
                 } else if let Some(&sel) = selected_indices.iter().next() {
                     let entry = &all_entries[sel];
-                    return Ok(FileSelectResult::Selected(entry.path.clone()));
+                    if confirm_save(&entry.path)? {
+                        return Ok(FileSelectResult::Selected(entry.path.clone()));
+                    }
+                    needs_redraw = true;
                 } else if self.directory {
                     return Ok(FileSelectResult::Selected(current_dir.clone()));
                 }
@@ -1424,109 +2751,623 @@ impl FileSelectBuilder {
                 return Ok(FileSelectResult::Cancelled);
             }
 
-            // Batch pending events
-            while let Some(ev) = window.poll_for_event()? {
-                match &ev {
-                    WindowEvent::CloseRequested => {
-                        return Ok(FileSelectResult::Closed);
-                    }
-                    WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
-                        mouse_x = pos.x as i32;
-                        mouse_y = pos.y as i32;
-                    }
-                    WindowEvent::ButtonPress(button, _modifiers)
-                        if *button == MouseButton::Left =>
-                    {
-                        if !filtered_entries.is_empty() {
-                            let scrollbar_x = main_x + main_w as i32 - (8.0 * scale) as i32;
-                            let scrollbar_y = list_y;
-
-                            if mouse_x >= main_x
-                                && mouse_x < main_x + main_w as i32
-                                && mouse_y >= list_y
-                                && mouse_y < list_y + list_h as i32
-                            {
-                                let visible_items = (list_h / item_height) as usize;
-                                let total_items = filtered_entries.len();
-
-                                if visible_items < total_items {
-                                    let scrollbar_h_f32 = list_h as f32 - 8.0 * scale;
-                                    let thumb_h_f32 = (visible_items as f32 / total_items as f32
-                                        * scrollbar_h_f32)
-                                        .max(20.0 * scale);
-                                    let thumb_h = thumb_h_f32 as i32;
-
-                                    let max_scroll = total_items - visible_items;
-                                    let max_thumb_y = scrollbar_h_f32 as i32 - thumb_h;
-                                    let thumb_y = if max_thumb_y > 0 {
-                                        ((scroll_offset as f32 / max_scroll as f32)
-                                            * max_thumb_y as f32)
-                                            as i32
-                                    } else {
-                                        0
-                                    };
+            if new_folder_button.was_clicked() {
+                if let Some(path) = create_new_folder(&current_dir, colors)? {
+                    navigate_to_directory(
+                        path,
+                        &mut current_dir,
+                        &mut recent,
+                        &mut history,
+                        &mut history_index,
+                        &mut all_entries,
+                        &mut loader,
+                        self.directory,
+                        show_hidden,
+                        sort,
+                        &search_text,
+                        &mut filtered_entries,
+                        &mut selected_indices,
+                        &mut scroll,
+                        &self.filters,
+                    );
+                }
+                needs_redraw = true;
+            }
 
-                                    let rel_y = mouse_y - scrollbar_y;
-                                    if mouse_x >= scrollbar_x
-                                        && mouse_x < scrollbar_x + (6.0 * scale) as i32
-                                        && rel_y >= thumb_y
-                                        && rel_y < thumb_y + thumb_h
-                                    {
-                                        thumb_drag = true;
-                                        thumb_drag_offset = Some(mouse_y - (scrollbar_y + thumb_y));
-                                    }
-                                }
-                            }
+            // Grow the sidebar, list and preview pane to the new size
+            if let Some((width, height)) = new_size.take() {
+                window_width = width;
+                window_height = height;
+                button_y = place_controls(
+                    &mut ok_button,
+                    &mut cancel_button,
+                    &mut search_input,
+                    name_input.as_mut(),
+                    width,
+                    height,
+                );
+                sidebar_h = sidebar_height(height);
+                main_h = sidebar_h;
+                main_w = main_width(width, show_preview);
+                list_h = main_h - path_bar_height - header_offset;
+                list_w = main_w - (12.0 * scale) as u32;
+                layout = ListLayout::new(layout.grid, list_w, list_h, scale);
+                scroll.set_size(main_w, list_h);
+                layout.fit(&mut scroll, filtered_entries.len());
+                // These were placed for the old layout
+                renaming = None;
+                location_input = None;
+                context_menu = None;
+                canvas = Canvas::new(width, height);
+                needs_redraw = true;
+            }
+
+            // OK is disabled, saying why, until there is something to accept
+            let blocked = ok_blocked_reason(&selected_indices, name_input.as_ref());
+            if blocked.is_none() != ok_button.is_enabled() {
+                ok_button.set_enabled(blocked.is_none());
+                ok_button.set_tooltip(blocked.as_deref());
+                needs_redraw = true;
+            }
+
+            // Tooltip for what the pointer rests on: a toolbar button, the
+            // disabled OK button, or a name cut short to fit
+            let tip = if context_menu.is_some() || rubber_band.is_some() || scroll.is_dragging() {
+                None
+            } else {
+                let nav_y = padding as i32 + (4.0 * scale) as i32;
+                let in_toolbar = mouse_y >= nav_y && mouse_y < nav_y + (28.0 * scale) as i32;
+                let shortened_entry = hovered_entry
+                    .map(|ei| all_entries[ei].name.as_str())
+                    .filter(|name| {
+                        if !layout.grid
+                            && main_w >= name_col_width + size_col_width + date_col_width
+                        {
+                            name.chars().count() > 35
+                        } else {
+                            let max_w = if layout.grid {
+                                layout.cell_width - (8.0 * scale) as u32
+                            } else {
+                                list_w.saturating_sub((36.0 * scale) as u32)
+                            };
+                            font.render(name).measure().0 > max_w as f32
                         }
-                    }
-                    WindowEvent::ButtonRelease(_, _) => {
-                        thumb_drag = false;
-                        thumb_drag_offset = None;
-                    }
-                    _ => {}
+                    });
+                let shortened_place = hovered_quick_access
+                    .map(|i| quick_access[i].name.as_str())
+                    .filter(|name| name.chars().count() > 18);
+                in_toolbar
+                    .then(|| {
+                        toolbar_tooltip(
+                            mouse_x - padding as i32,
+                            show_hidden,
+                            layout.grid,
+                            show_preview,
+                            scale,
+                        )
+                    })
+                    .flatten()
+                    .or_else(|| {
+                        ok_button
+                            .tooltip()
+                            .or(shortened_entry)
+                            .or(shortened_place)
+                            .map(Cow::Borrowed)
+                    })
+            };
+            needs_redraw |= tooltip.set(tip.as_deref(), mouse_x, mouse_y);
+
+            if needs_redraw {
+                spinner.set_position(
+                    main_x + (list_w as i32 - spinner.size() as i32) / 2,
+                    list_y + (list_h as i32 - spinner.size() as i32) / 2,
+                );
+                thumbnails.receive();
+                previews.receive();
+                if show_preview {
+                    update_preview(&mut preview, &mut previews, &all_entries, &selected_indices);
                 }
+                // The entries may have changed since the scrollbar was laid out
+                layout.fit(&mut scroll, filtered_entries.len());
+                request_thumbnails(
+                    &mut thumbnails,
+                    &all_entries,
+                    &filtered_entries,
+                    scroll.offset(),
+                    layout.visible_items,
+                );
+                draw(
+                    &mut canvas,
+                    colors,
+                    &font,
+                    &current_dir,
+                    recent,
+                    &quick_access,
+                    &all_entries,
+                    &filtered_entries,
+                    &selected_indices,
+                    &scroll,
+                    sidebar_h,
+                    main_w,
+                    main_h,
+                    list_w,
+                    layout,
+                    hovered_quick_access,
+                    hovered_entry,
+                    show_hidden,
+                    &search_input,
+                    name_input.as_ref(),
+                    &new_folder_button,
+                    sort,
+                    &thumbnails,
+                    renaming.as_ref().map(|(_, input)| input),
+                    show_preview,
+                    preview.as_ref(),
+                    &previews,
+                    rubber_band.as_ref(),
+                    location_input.as_ref(),
+                    context_menu.as_ref(),
+                    &ok_button,
+                    &cancel_button,
+                    &history,
+                    history_index,
+                    &mounted_drives,
+                    hovered_drive,
+                    button_y,
+                    scale,
+                    loader.is_loading().then_some(&spinner),
+                );
+                tooltip.draw_to(&mut canvas, colors, &font);
+                window.set_contents(&canvas)?;
+            }
+        }
+    }
+}
+
+impl Default for FileSelectBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Splits `--filename` into a directory to start in and, in save mode, a
+/// file name; either may be missing.
+fn split_filename(filename: &str) -> (Option<PathBuf>, &str) {
+    let path = Path::new(filename);
+    if filename.is_empty() {
+        (None, "")
+    } else if path.is_dir() {
+        (Some(path.to_path_buf()), "")
+    } else {
+        (
+            path.parent().filter(|p| p.is_dir()).map(Path::to_path_buf),
+            path.file_name().and_then(|n| n.to_str()).unwrap_or(""),
+        )
+    }
+}
+
+// Helper types and functions
+
+/// Prompts for a folder name and creates it in `dir`.
+/// Returns the new folder's path, or None if cancelled or creation failed.
+fn create_new_folder(dir: &Path, colors: &Theme) -> Result<Option<PathBuf>, Error> {
+    let name = match EntryBuilder::new()
+        .title(&tr("New Folder"))
+        .text(&tr("Folder name:"))
+        .entry_text(&tr("New Folder"))
+        .colors(colors)
+        .show()?
+    {
+        EntryResult::Text(name) if !name.trim().is_empty() => name.trim().to_string(),
+        _ => return Ok(None),
+    };
+
+    if !is_valid_name(&name) {
+        show_file_error(&tr_args("\"{}\" is not a valid name.", &[&name]), colors)?;
+        return Ok(None);
+    }
+    let path = dir.join(name);
+    match fs::create_dir(&path) {
+        Ok(()) => Ok(Some(path)),
+        Err(e) => {
+            show_file_error(&tr_args("Could not create folder: {}", &[&e]), colors)?;
+            Ok(None)
+        }
+    }
+}
+
+/// The dialog's widgets in Tab order, the name field only when saving.
+fn focus_order<'a>(
+    new_folder_button: &'a mut Button,
+    search_input: &'a mut TextInput,
+    name_input: Option<&'a mut TextInput>,
+    ok_button: &'a mut Button,
+    cancel_button: &'a mut Button,
+) -> Vec<&'a mut dyn Widget> {
+    let mut widgets: Vec<&mut dyn Widget> = vec![new_folder_button, search_input];
+    if let Some(name_input) = name_input {
+        widgets.push(name_input);
+    }
+    widgets.push(ok_button);
+    widgets.push(cancel_button);
+    widgets
+}
+
+/// Location bar input spanning the path bar, prefilled with `dir`.
+fn location_bar_input(dir: &Path, x: i32, y: i32, width: u32) -> TextInput {
+    let mut text = dir.display().to_string();
+    if !text.ends_with('/') {
+        text.push('/');
+    }
+    let mut input = TextInput::new(width).with_default_text(&text);
+    input.set_position(x, y);
+    input.set_focus(true);
+    input
+}
+
+/// Expands a leading `~` to the home directory.
+fn expand_tilde(text: &str) -> PathBuf {
+    match (text.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(text),
+    }
+}
+
+/// Tab completion for the location bar: completes the last path component
+/// to the directory names it prefixes, as far as they agree.
+fn complete_directory(text: &str, show_hidden: bool) -> Option<String> {
+    let (head, prefix) = text.split_at(text.rfind('/')? + 1);
+
+    let mut matches: Vec<String> = fs::read_dir(expand_tilde(head))
+        .ok()?
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|name| name.starts_with(prefix))
+        .filter(|name| show_hidden || prefix.starts_with('.') || !name.starts_with('.'))
+        .collect();
+    matches.sort();
+
+    let mut common = matches.first()?.as_str();
+    for name in &matches[1..] {
+        let len = common
+            .chars()
+            .zip(name.chars())
+            .take_while(|(a, b)| a == b)
+            .map(|(c, _)| c.len_utf8())
+            .sum();
+        common = &common[..len];
+    }
+
+    let mut completed = format!("{head}{common}");
+    if matches.len() == 1 {
+        completed.push('/');
+    }
+    (completed != text).then_some(completed)
+}
+
+/// Details shown in the preview pane for the selected entry.
+struct Preview {
+    path: PathBuf,
+    name: String,
+    /// "Label: value" lines of basic metadata.
+    details: Vec<String>,
+}
+
+impl Preview {
+    fn new(entry: &DirEntry) -> Self {
+        let mut details = Vec::new();
+        details.push(match (&entry.link_target, entry.is_dir) {
+            (Some(target), _) => tr_args("Link to {}", &[&target.display()]),
+            (None, true) => tr("Folder").to_string(),
+            (None, false) => tr("File").to_string(),
+        });
+        if !entry.is_dir {
+            details.push(tr_args("Size: {}", &[&format_size(entry.size)]));
+        }
+        if entry.modified.is_some() {
+            details.push(tr_args("Modified: {}", &[&format_date(entry.modified)]));
+        }
+
+        Self {
+            path: entry.path.clone(),
+            name: entry.name.clone(),
+            details,
+        }
+    }
+}
+
+/// Refreshes the preview when the selection has changed.
+fn update_preview(
+    preview: &mut Option<Preview>,
+    previews: &mut Thumbnails,
+    all_entries: &[DirEntry],
+    selected_indices: &HashSet<usize>,
+) {
+    let entry = selected_indices.iter().next().map(|&ei| &all_entries[ei]);
+    if preview.as_ref().map(|p| &p.path) == entry.map(|e| &e.path) {
+        return;
+    }
+    *preview = entry.map(Preview::new);
+    // Text is read off the event loop, as it may be on a slow disk
+    match entry {
+        Some(entry) if entry.is_dir => {}
+        Some(entry) if Thumbnails::is_image(&entry.name) => previews.request(&entry.path),
+        Some(entry) => previews.request_text(&entry.path),
+        None => {}
+    }
+}
+
+/// Queues thumbnails for the image files currently scrolled into view.
+fn request_thumbnails(
+    thumbnails: &mut Thumbnails,
+    all_entries: &[DirEntry],
+    filtered_entries: &[usize],
+    scroll_offset: usize,
+    visible_items: usize,
+) {
+    for &ei in filtered_entries
+        .iter()
+        .skip(scroll_offset)
+        .take(visible_items)
+    {
+        let entry = &all_entries[ei];
+        if !entry.is_dir && Thumbnails::is_image(&entry.name) {
+            thumbnails.request(&entry.path);
+        }
+    }
+}
+
+/// Renames a file or directory of `dir`. Returns true on success.
+fn rename_entry(dir: &Path, path: &Path, new_name: &str, colors: &Theme) -> Result<bool, Error> {
+    if !is_child_of(path, dir) {
+        return Ok(false);
+    }
+    if !is_valid_name(new_name) {
+        show_file_error(
+            &tr_args("\"{}\" is not a valid name.", &[&new_name]),
+            colors,
+        )?;
+        return Ok(false);
+    }
+    let target = path.with_file_name(new_name);
+    if target.exists() {
+        show_file_error(&tr_args("\"{}\" already exists.", &[&new_name]), colors)?;
+        return Ok(false);
+    }
+    match fs::rename(path, &target) {
+        Ok(()) => Ok(true),
+        Err(e) => {
+            show_file_error(&tr_args("Could not rename: {}", &[&e]), colors)?;
+            Ok(false)
+        }
+    }
+}
+
+/// Whether `path` is directly inside `dir`. The ".." entry, whose path is
+/// the parent directory, is not, so it can never be renamed or deleted.
+fn is_child_of(path: &Path, dir: &Path) -> bool {
+    path.parent() == Some(dir)
+}
+
+/// Whether `name` names an entry of its own directory, rather than being
+/// empty, `.`, `..` or a path into another.
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains('/')
+}
+
+/// Deletes a file or directory of `dir` after confirmation. Returns true if
+/// it was deleted.
+fn delete_entry(dir: &Path, entry: &DirEntry, colors: &Theme) -> Result<bool, Error> {
+    if !is_child_of(&entry.path, dir) {
+        return Ok(false);
+    }
+    let text = if entry.is_dir {
+        tr_args(
+            "Delete the folder \"{}\" and everything in it? This cannot be undone.",
+            &[&entry.name],
+        )
+    } else {
+        tr_args("Delete \"{}\"? This cannot be undone.", &[&entry.name])
+    };
+    let result = MessageBuilder::new()
+        .title(&tr("Delete"))
+        .text(&text)
+        .icon(Icon::Warning)
+        .buttons(ButtonPreset::Custom(vec![
+            tr("_Delete").to_string(),
+            tr("_Cancel").to_string(),
+        ]))
+        .default_button(1)
+        .colors(colors)
+        .show()?;
+    if result != DialogResult::Button(0) {
+        return Ok(false);
+    }
+
+    let removed = if entry.is_dir {
+        fs::remove_dir_all(&entry.path)
+    } else {
+        fs::remove_file(&entry.path)
+    };
+    match removed {
+        Ok(()) => Ok(true),
+        Err(e) => {
+            show_file_error(&tr_args("Could not delete: {}", &[&e]), colors)?;
+            Ok(false)
+        }
+    }
+}
+
+fn show_file_error(text: &str, colors: &Theme) -> Result<(), Error> {
+    MessageBuilder::new()
+        .title(&tr("Error"))
+        .text(text)
+        .icon(Icon::Error)
+        .colors(colors)
+        .show()?;
+    Ok(())
+}
+
+/// Asks whether an existing file should be replaced. Returns true to proceed.
+fn confirm_replace(path: &Path, colors: &Theme) -> Result<bool, Error> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    let result = MessageBuilder::new()
+        .title(&tr("Replace existing file?"))
+        .text(&tr_args(
+            "A file named \"{}\" already exists. Do you want to replace it?",
+            &[&name],
+        ))
+        .icon(Icon::Warning)
+        .buttons(ButtonPreset::Custom(vec![
+            tr("_Replace").to_string(),
+            tr("_Cancel").to_string(),
+        ]))
+        .default_button(1)
+        .colors(colors)
+        .show()?;
+    Ok(result == DialogResult::Button(0))
+}
+
+/// Click-drag selection rectangle over the file list, in multiple mode.
+struct RubberBand {
+    origin: (i32, i32),
+    current: (i32, i32),
+    /// Selection the rectangle adds to (kept when Ctrl was held).
+    base: HashSet<usize>,
+    /// Set once the pointer has moved far enough to count as a drag.
+    active: bool,
+}
+
+impl RubberBand {
+    /// Rectangle spanned so far, as (x, y, width, height).
+    fn rect(&self) -> (i32, i32, u32, u32) {
+        let x = self.origin.0.min(self.current.0);
+        let y = self.origin.1.min(self.current.1);
+        (
+            x,
+            y,
+            self.origin.0.abs_diff(self.current.0),
+            self.origin.1.abs_diff(self.current.1),
+        )
+    }
+}
+
+/// What a context menu acts on.
+#[derive(Clone, Copy)]
+enum MenuTarget {
+    /// A file list entry (index into all entries).
+    Entry(usize),
+    /// A bookmark in PLACES (index into the places list).
+    Bookmark(usize),
+}
+
+/// Right-click menu over a file row or bookmark.
+struct ContextMenu {
+    target: MenuTarget,
+    items: &'static [&'static str],
+    x: i32,
+    y: i32,
+    width: u32,
+    item_height: u32,
+    hovered: Option<usize>,
+}
+
+impl ContextMenu {
+    fn item_at(&self, px: i32, py: i32) -> Option<usize> {
+        let height = self.item_height * self.items.len() as u32;
+        if !point_in_rect(px, py, self.x, self.y, self.width, height) {
+            return None;
+        }
+        Some(((py - self.y) as u32 / self.item_height) as usize)
+    }
+}
+
+/// Arrangement of the file list: one entry per row, or an icon grid.
+#[derive(Clone, Copy)]
+struct ListLayout {
+    grid: bool,
+    /// Entries per row (1 in list view).
+    columns: usize,
+    cell_width: u32,
+    row_height: u32,
+    /// Entries that fit in the list area, in whole rows.
+    visible_items: usize,
+}
 
-                needs_redraw |= ok_button.process_event(&ev);
-                needs_redraw |= cancel_button.process_event(&ev);
-            }
+impl ListLayout {
+    fn new(grid: bool, width: u32, height: u32, scale: f32) -> Self {
+        let (columns, row_height) = if grid {
+            let min_cell = (BASE_GRID_CELL_WIDTH as f32 * scale) as u32;
+            (
+                (width / min_cell).max(1) as usize,
+                (BASE_GRID_CELL_HEIGHT as f32 * scale) as u32,
+            )
+        } else {
+            (1, (BASE_ITEM_HEIGHT as f32 * scale) as u32)
+        };
+        let visible_rows = (height / row_height).max(1) as usize;
+        Self {
+            grid,
+            columns,
+            // Spread spare width evenly over the columns
+            cell_width: width / columns as u32,
+            row_height,
+            visible_items: visible_rows * columns,
+        }
+    }
 
-            if needs_redraw {
-                draw(
-                    &mut canvas,
-                    colors,
-                    &font,
-                    &current_dir,
-                    &quick_access,
-                    &all_entries,
-                    &filtered_entries,
-                    &selected_indices,
-                    scroll_offset,
-                    hovered_quick_access,
-                    hovered_entry,
-                    show_hidden,
-                    &search_input,
-                    &ok_button,
-                    &cancel_button,
-                    &history,
-                    history_index,
-                    &mounted_drives,
-                    hovered_drive,
-                    scale,
-                    scrollbar_hovered,
-                );
-                window.set_contents(&canvas)?;
-            }
+    /// Position of the `vi`-th visible entry relative to the list origin.
+    fn cell_origin(&self, vi: usize) -> (i32, i32) {
+        let col = (vi % self.columns) as u32;
+        let row = (vi / self.columns) as u32;
+        (
+            (col * self.cell_width) as i32,
+            (row * self.row_height) as i32,
+        )
+    }
+
+    /// Visible entry under a point relative to the list origin.
+    fn entry_at(&self, rel_x: i32, rel_y: i32) -> Option<usize> {
+        if rel_x < 0 || rel_y < 0 {
+            return None;
         }
+        let col = if self.grid {
+            rel_x as usize / self.cell_width as usize
+        } else {
+            0
+        };
+        let vi = rel_y as usize / self.row_height as usize * self.columns + col;
+        (col < self.columns && vi < self.visible_items).then_some(vi)
     }
-}
 
-impl Default for FileSelectBuilder {
-    fn default() -> Self {
-        Self::new()
+    /// Lays `scroll` out for this layout showing `count` entries: one
+    /// row per wheel step in the grid, three in the list.
+    fn fit(&self, scroll: &mut ScrollArea, count: usize) {
+        scroll.set_columns(self.columns);
+        scroll.set_step(if self.grid { 1 } else { 3 });
+        scroll.set_content(count, self.visible_items);
     }
 }
 
-// Helper types and functions
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SortColumn {
+    Name,
+    Size,
+    Modified,
+}
+
+/// Listing order; directories always come before files.
+#[derive(Clone, Copy)]
+struct SortOrder {
+    column: SortColumn,
+    ascending: bool,
+}
 
 struct DirEntry {
     name: String,
@@ -1534,6 +3375,8 @@ struct DirEntry {
     is_dir: bool,
     size: u64,
     modified: Option<SystemTime>,
+    /// Where the entry points, if it is a symlink.
+    link_target: Option<PathBuf>,
 }
 
 fn build_quick_access() -> Vec<QuickAccess> {
@@ -1541,102 +3384,389 @@ fn build_quick_access() -> Vec<QuickAccess> {
 
     if let Some(home) = dirs::home_dir() {
         items.push(QuickAccess {
-            name: "Home",
+            name: tr("Home").to_string(),
             path: home,
             icon: QuickAccessIcon::Home,
+            removable: false,
         });
     }
     if let Some(desktop) = dirs::desktop_dir() {
         items.push(QuickAccess {
-            name: "Desktop",
+            name: tr("Desktop").to_string(),
             path: desktop,
             icon: QuickAccessIcon::Desktop,
+            removable: false,
         });
     }
     if let Some(docs) = dirs::document_dir() {
         items.push(QuickAccess {
-            name: "Documents",
+            name: tr("Documents").to_string(),
             path: docs,
             icon: QuickAccessIcon::Documents,
+            removable: false,
         });
     }
     if let Some(dl) = dirs::download_dir() {
         items.push(QuickAccess {
-            name: "Downloads",
+            name: tr("Downloads").to_string(),
             path: dl,
             icon: QuickAccessIcon::Downloads,
+            removable: false,
         });
     }
     if let Some(pics) = dirs::picture_dir() {
         items.push(QuickAccess {
-            name: "Pictures",
+            name: tr("Pictures").to_string(),
             path: pics,
             icon: QuickAccessIcon::Pictures,
+            removable: false,
         });
     }
     if let Some(music) = dirs::audio_dir() {
         items.push(QuickAccess {
-            name: "Music",
+            name: tr("Music").to_string(),
             path: music,
             icon: QuickAccessIcon::Music,
+            removable: false,
         });
     }
     if let Some(videos) = dirs::video_dir() {
         items.push(QuickAccess {
-            name: "Videos",
+            name: tr("Videos").to_string(),
             path: videos,
             icon: QuickAccessIcon::Videos,
+            removable: false,
         });
     }
 
     items
 }
 
+/// Standard places followed by bookmarks not already among them. With
+/// `with_recent`, a "Recent" item (which has no path) comes first.
+fn build_places(with_recent: bool) -> Vec<QuickAccess> {
+    let mut places = Vec::new();
+    if with_recent {
+        places.push(QuickAccess {
+            name: tr("Recent").to_string(),
+            path: PathBuf::new(),
+            icon: QuickAccessIcon::Recent,
+            removable: false,
+        });
+    }
+    places.extend(build_quick_access());
+    for bookmark in bookmarks::load() {
+        if places.iter().any(|qa| qa.path == bookmark.path) {
+            continue;
+        }
+        let name = bookmark.label.unwrap_or_else(|| {
+            bookmark
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| bookmark.path.display().to_string())
+        });
+        places.push(QuickAccess {
+            name,
+            path: bookmark.path,
+            icon: QuickAccessIcon::Bookmark,
+            removable: bookmark.removable,
+        });
+    }
+    places
+}
+
+/// Paths of the selected entries for a multiple selection. Directories are
+/// left out unless `allow_directories` is set; ".." never counts.
+fn selected_paths(
+    entries: &[DirEntry],
+    selected: &HashSet<usize>,
+    allow_directories: bool,
+) -> Vec<PathBuf> {
+    selected
+        .iter()
+        .map(|&ei| &entries[ei])
+        .filter(|e| e.name != ".." && (allow_directories || !e.is_dir))
+        .map(|e| e.path.clone())
+        .collect()
+}
+
+/// Filesystem types that never hold user files.
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "autofs",
+    "binfmt_misc",
+    "bpf",
+    "cgroup",
+    "cgroup2",
+    "configfs",
+    "debugfs",
+    "devpts",
+    "devtmpfs",
+    "efivarfs",
+    "fuse.gvfsd-fuse",
+    "fuse.lxcfs",
+    "fuse.portal",
+    "fusectl",
+    "hugetlbfs",
+    "mqueue",
+    "nsfs",
+    "overlay",
+    "proc",
+    "pstore",
+    "ramfs",
+    "rpc_pipefs",
+    "securityfs",
+    "selinuxfs",
+    "squashfs",
+    "sysfs",
+    "tmpfs",
+    "tracefs",
+];
+
+/// Network filesystems, listed whatever their source looks like.
+const NETWORK_FILESYSTEMS: &[&str] = &[
+    "9p",
+    "afs",
+    "ceph",
+    "cifs",
+    "davfs",
+    "fuse.rclone",
+    "fuse.sshfs",
+    "nfs",
+    "nfs4",
+    "smb3",
+    "smbfs",
+];
+
+/// Mount point prefixes for system internals, never listed.
+const SYSTEM_MOUNT_PREFIXES: &[&str] = &[
+    "/boot", "/dev", "/efi", "/proc", "/run", "/snap", "/sys", "/tmp", "/var/lib",
+];
+
+/// Lists mounted block devices and network shares from `/proc/self/mountinfo`,
+/// skipping the root filesystem, pseudo-filesystems and system mounts.
 fn get_mounted_drives() -> Vec<MountPoint> {
-    let mut drives = Vec::new();
-
-    // Parse /run/mount/utab for user-mounted drives (much cleaner than /proc/mounts)
-    if let Ok(content) = std::fs::read_to_string("/run/mount/utab") {
-        for line in content.lines() {
-            let mut device: Option<String> = None;
-            let mut mount_point: Option<PathBuf> = None;
-
-            // Parse KEY=VALUE pairs
-            for pair in line.split_whitespace() {
-                let mut kv = pair.split('=');
-                if let Some(key) = kv.next() {
-                    let value = kv.next();
-                    match key {
-                        "SRC" => {
-                            device = value.map(|v| v.to_string());
-                        }
-                        "TARGET" => {
-                            mount_point = value.map(PathBuf::from);
-                        }
-                        _ => {}
-                    }
-                }
-            }
+    let mut drives: Vec<MountPoint> = Vec::new();
+    let Ok(content) = fs::read_to_string("/proc/self/mountinfo") else {
+        return drives;
+    };
 
-            // We have both source and target, create a mount point entry
-            if let (Some(dev), Some(mp)) = (device, mount_point) {
-                // Skip root filesystem
-                if mp.as_os_str() == "/" {
-                    continue;
-                }
+    for line in content.lines() {
+        // "id parent major:minor root mount_point options [optional...] - fstype source super_options"
+        let Some((mount, fs_info)) = line.split_once(" - ") else {
+            continue;
+        };
+        let mount: Vec<&str> = mount.split(' ').collect();
+        let mut fs_info = fs_info.split(' ');
+        let (Some(root), Some(target), Some(fs_type), Some(source)) =
+            (mount.get(3), mount.get(4), fs_info.next(), fs_info.next())
+        else {
+            continue;
+        };
+
+        let mount_point = PathBuf::from(unescape_mount_field(target));
+        let device = unescape_mount_field(source);
+        let user_media = mount_point.starts_with("/media") || mount_point.starts_with("/run/media");
+
+        let network = NETWORK_FILESYSTEMS.contains(&fs_type);
+        let block_device = device.starts_with("/dev/")
+            // Loop devices are only interesting when udisks mounted them
+            && (!device.starts_with("/dev/loop") || user_media);
+        let system = mount_point.as_os_str() == "/"
+            || (!user_media
+                && SYSTEM_MOUNT_PREFIXES
+                    .iter()
+                    .any(|prefix| mount_point.starts_with(prefix)));
+
+        // Bind mounts of a subdirectory repeat a device that is already listed
+        if PSEUDO_FILESYSTEMS.contains(&fs_type)
+            || system
+            || *root != "/"
+            || !(network || block_device)
+        {
+            continue;
+        }
+
+        // A later mount on the same point hides the earlier one
+        drives.retain(|d| d.mount_point != mount_point);
+        let label = if block_device {
+            get_volume_label(&device)
+        } else {
+            None
+        };
+        drives.push(MountPoint {
+            device,
+            mount_point,
+            label,
+        });
+    }
+
+    drives
+}
+
+/// Decodes the octal escapes (`\040` for space, ...) used in mountinfo fields.
+fn unescape_mount_field(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\'
+            && let Some(octal) = field.get(i + 1..i + 4)
+            && let Ok(byte) = u8::from_str_radix(octal, 8)
+        {
+            out.push(byte);
+            i += 4;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Reports files created, removed, renamed or rewritten in one directory,
+/// using inotify.
+struct DirWatcher {
+    inotify: Option<fs::File>,
+    /// Watched directory and its watch descriptor (negative if it failed).
+    watch: Option<(PathBuf, i32)>,
+}
+
+impl DirWatcher {
+    fn new() -> Self {
+        use std::os::fd::{FromRawFd, OwnedFd};
+
+        // SAFETY: plain syscall; on success we take ownership of the new fd.
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        Self {
+            inotify: (fd >= 0).then(|| fs::File::from(unsafe { OwnedFd::from_raw_fd(fd) })),
+            watch: None,
+        }
+    }
+
+    /// Moves the watch to `dir`, or stops watching for `None`.
+    fn watch(&mut self, dir: Option<&Path>) {
+        use std::os::{fd::AsRawFd, unix::ffi::OsStrExt};
+
+        let Some(inotify) = &self.inotify else {
+            return;
+        };
+        if self.watch.as_ref().map(|(path, _)| path.as_path()) == dir {
+            return;
+        }
+        if let Some((_, wd)) = self.watch.take()
+            && wd >= 0
+        {
+            // SAFETY: removing a watch we added on our own descriptor.
+            unsafe { libc::inotify_rm_watch(inotify.as_raw_fd(), wd) };
+        }
+
+        let Some(dir) = dir else {
+            return;
+        };
+        let Ok(c_path) = std::ffi::CString::new(dir.as_os_str().as_bytes()) else {
+            return;
+        };
+        let mask = libc::IN_CREATE
+            | libc::IN_DELETE
+            | libc::IN_MOVED_FROM
+            | libc::IN_MOVED_TO
+            | libc::IN_CLOSE_WRITE;
+        // SAFETY: `c_path` is a valid NUL-terminated string.
+        let wd = unsafe { libc::inotify_add_watch(inotify.as_raw_fd(), c_path.as_ptr(), mask) };
+        self.watch = Some((dir.to_path_buf(), wd));
+    }
+
+    /// Returns the inotify descriptor to wait on while a directory is
+    /// watched.
+    fn pollfd(&self) -> Option<libc::pollfd> {
+        use std::os::fd::AsRawFd;
+
+        self.watch.as_ref()?;
+        Some(libc::pollfd {
+            fd: self.inotify.as_ref()?.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        })
+    }
 
-                let label = get_volume_label(&dev);
+    /// Returns true if the watched directory changed since the last call.
+    /// Never blocks.
+    fn changed(&mut self) -> bool {
+        use std::io::Read;
 
-                drives.push(MountPoint {
-                    device: dev,
-                    mount_point: mp,
-                    label,
-                });
+        let (Some(inotify), Some((_, wd))) = (&mut self.inotify, &self.watch) else {
+            return false;
+        };
+        let mut changed = false;
+        let mut buf = [0u8; 4096];
+        // Drain everything queued; events of earlier watches are ignored
+        while let Ok(len) = inotify.read(&mut buf)
+            && len > 0
+        {
+            let word =
+                |at: usize| u32::from_ne_bytes([buf[at], buf[at + 1], buf[at + 2], buf[at + 3]]);
+            let mut at = 0;
+            // struct inotify_event { wd, mask, cookie, len, name[len] }
+            while at + 16 <= len {
+                changed |= word(at) as i32 == *wd && word(at + 4) & libc::IN_IGNORED == 0;
+                at += 16 + word(at + 12) as usize;
             }
         }
+        changed
     }
+}
 
-    drives
+/// Reports mount table changes, which the kernel signals as an exceptional
+/// condition on `/proc/self/mountinfo`.
+struct MountWatcher {
+    file: Option<fs::File>,
+}
+
+impl MountWatcher {
+    fn new() -> Self {
+        Self {
+            file: fs::File::open("/proc/self/mountinfo").ok(),
+        }
+    }
+
+    /// Returns the mount table's descriptor to wait on for changes.
+    fn pollfd(&self) -> Option<libc::pollfd> {
+        use std::os::fd::AsRawFd;
+
+        Some(libc::pollfd {
+            fd: self.file.as_ref()?.as_raw_fd(),
+            events: libc::POLLPRI,
+            revents: 0,
+        })
+    }
+
+    /// Returns true if mounts changed since the last call. Never blocks.
+    fn changed(&mut self) -> bool {
+        use std::{
+            io::{Read, Seek, SeekFrom},
+            os::fd::AsRawFd,
+        };
+
+        let Some(file) = self.file.as_mut() else {
+            return false;
+        };
+        let mut pfd = libc::pollfd {
+            fd: file.as_raw_fd(),
+            events: libc::POLLPRI,
+            revents: 0,
+        };
+        // SAFETY: `pfd` is a valid pollfd for an open descriptor.
+        let ready = unsafe { libc::poll(&mut pfd, 1, 0) };
+        if ready <= 0 || pfd.revents & (libc::POLLPRI | libc::POLLERR) == 0 {
+            return false;
+        }
+
+        // Reading the table again acknowledges the change
+        let _ = file.seek(SeekFrom::Start(0));
+        let _ = file.read_to_end(&mut Vec::new());
+        true
+    }
 }
 
 fn get_volume_label(device: &str) -> Option<String> {
@@ -1692,19 +3822,86 @@ fn get_mount_icon(device: &str) -> MountIcon {
     MountIcon::Generic
 }
 
-fn load_directory(path: &Path, entries: &mut Vec<DirEntry>, dirs_only: bool, show_hidden: bool) {
-    entries.clear();
+/// Reads directories on a thread, so that one on a slow disk or with a
+/// great many files doesn't freeze the dialog. A read taking longer than
+/// [`LOAD_WAIT`] leaves the listing with just ".." and a spinner until it
+/// is in.
+struct DirLoader {
+    /// Entries of the read under way
+    pending: Option<Receiver<Vec<DirEntry>>>,
+}
 
-    if let Some(parent) = path.parent() {
-        entries.push(DirEntry {
-            name: "..".to_string(),
-            path: parent.to_path_buf(),
-            is_dir: true,
-            size: 0,
-            modified: None,
+impl DirLoader {
+    fn new() -> Self {
+        Self {
+            pending: None,
+        }
+    }
+
+    /// Lists `path` in `entries`, or starts to, dropping any read under way.
+    fn load(
+        &mut self,
+        path: &Path,
+        entries: &mut Vec<DirEntry>,
+        dirs_only: bool,
+        show_hidden: bool,
+        sort: SortOrder,
+    ) {
+        let (tx, rx) = mpsc::channel();
+        let dir = path.to_path_buf();
+        thread::spawn(move || {
+            let _ = tx.send(read_directory(&dir, dirs_only, show_hidden, sort));
         });
+        match rx.recv_timeout(LOAD_WAIT) {
+            Ok(read) => {
+                *entries = read;
+                self.pending = None;
+            }
+            Err(_) => {
+                *entries = path.parent().map(parent_entry).into_iter().collect();
+                self.pending = Some(rx);
+            }
+        }
+    }
+
+    /// Drops the read under way, for a listing made some other way.
+    fn cancel(&mut self) {
+        self.pending = None;
+    }
+
+    /// Returns true while a read is under way.
+    fn is_loading(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Returns the entries of the read under way once it is done. Never
+    /// blocks.
+    fn receive(&mut self) -> Option<Vec<DirEntry>> {
+        let read = self.pending.as_ref()?.try_recv().ok()?;
+        self.pending = None;
+        Some(read)
+    }
+}
+
+/// The ".." entry leading to `parent`.
+fn parent_entry(parent: &Path) -> DirEntry {
+    DirEntry {
+        name: "..".to_string(),
+        path: parent.to_path_buf(),
+        is_dir: true,
+        size: 0,
+        modified: None,
+        link_target: None,
     }
+}
 
+/// Lists a directory, ".." first, in `sort` order.
+fn read_directory(
+    path: &Path,
+    dirs_only: bool,
+    show_hidden: bool,
+    sort: SortOrder,
+) -> Vec<DirEntry> {
     let mut dirs: Vec<DirEntry> = Vec::new();
     let mut files: Vec<DirEntry> = Vec::new();
 
@@ -1726,12 +3923,19 @@ fn load_directory(path: &Path, entries: &mut Vec<DirEntry>, dirs_only: bool, sho
             let size = metadata.as_ref().map(Metadata::len).unwrap_or(0);
             let modified = metadata.as_ref().and_then(|m| m.modified().ok());
 
+            let link_target = entry
+                .file_type()
+                .is_ok_and(|t| t.is_symlink())
+                .then(|| fs::read_link(entry.path()).ok())
+                .flatten();
+
             let de = DirEntry {
                 name,
                 path: entry.path(),
                 is_dir,
                 size,
                 modified,
+                link_target,
             };
 
             if is_dir {
@@ -1742,11 +3946,117 @@ fn load_directory(path: &Path, entries: &mut Vec<DirEntry>, dirs_only: bool, sho
         }
     }
 
-    dirs.sort_by_key(|a| a.name.to_lowercase());
-    files.sort_by_key(|a| a.name.to_lowercase());
-
+    let mut entries: Vec<DirEntry> = path.parent().map(parent_entry).into_iter().collect();
     entries.extend(dirs);
     entries.extend(files);
+    sort_entries(&mut entries, sort);
+    entries
+}
+
+/// Directory to enter for an entry, resolving symlinks if asked to.
+fn entry_destination(entry: &DirEntry, follow_symlinks: bool) -> PathBuf {
+    if follow_symlinks && entry.link_target.is_some() {
+        fs::canonicalize(&entry.path).unwrap_or_else(|_| entry.path.clone())
+    } else {
+        entry.path.clone()
+    }
+}
+
+/// Reloads the listing after a change: the recent files, or `dir`.
+fn reload_entries(
+    recent: bool,
+    dir: &Path,
+    entries: &mut Vec<DirEntry>,
+    loader: &mut DirLoader,
+    dirs_only: bool,
+    show_hidden: bool,
+    sort: SortOrder,
+) {
+    if recent {
+        loader.cancel();
+        load_recent(entries, show_hidden, sort);
+    } else {
+        loader.load(dir, entries, dirs_only, show_hidden, sort);
+    }
+}
+
+/// Lists recently used files, like a directory without "..".
+fn load_recent(entries: &mut Vec<DirEntry>, show_hidden: bool, sort: SortOrder) {
+    entries.clear();
+    for path in recent::load() {
+        let name = match path.file_name() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => continue,
+        };
+        if !show_hidden && name.starts_with('.') {
+            continue;
+        }
+        let metadata = path.metadata().ok();
+        entries.push(DirEntry {
+            name,
+            size: metadata.as_ref().map(Metadata::len).unwrap_or(0),
+            modified: metadata.as_ref().and_then(|m| m.modified().ok()),
+            link_target: fs::read_link(&path).ok(),
+            path,
+            is_dir: false,
+        });
+    }
+    sort_entries(entries, sort);
+}
+
+/// Sorts entries in place, keeping ".." first and directories before files.
+fn sort_entries(entries: &mut [DirEntry], sort: SortOrder) {
+    entries.sort_by(|a, b| {
+        let rank = |e: &DirEntry| (e.name != "..", !e.is_dir);
+        rank(a).cmp(&rank(b)).then_with(|| {
+            let by_name = || natural_cmp(&a.name, &b.name);
+            let ord = match sort.column {
+                SortColumn::Name => by_name(),
+                SortColumn::Size => a.size.cmp(&b.size).then_with(by_name),
+                SortColumn::Modified => a.modified.cmp(&b.modified).then_with(by_name),
+            };
+            if sort.ascending { ord } else { ord.reverse() }
+        })
+    });
+}
+
+/// Reloads the listing after a change on disk, keeping selected entries that
+/// still exist selected.
+#[allow(clippy::too_many_arguments)]
+fn refresh_entries(
+    recent: bool,
+    dir: &Path,
+    all_entries: &mut Vec<DirEntry>,
+    loader: &mut DirLoader,
+    dirs_only: bool,
+    show_hidden: bool,
+    sort: SortOrder,
+    search_text: &str,
+    filtered_entries: &mut Vec<usize>,
+    selected_indices: &mut HashSet<usize>,
+    selection_anchor: &mut Option<usize>,
+    filters: &[FileFilter],
+) {
+    let selected: Vec<PathBuf> = selected_indices
+        .iter()
+        .map(|&ei| all_entries[ei].path.clone())
+        .collect();
+    let anchor = selection_anchor.map(|ei| all_entries[ei].path.clone());
+
+    reload_entries(
+        recent,
+        dir,
+        all_entries,
+        loader,
+        dirs_only,
+        show_hidden,
+        sort,
+    );
+    update_filtered(all_entries, search_text, filtered_entries, filters);
+
+    let index_of = |path: &Path| all_entries.iter().position(|e| e.path == path);
+    *selected_indices = selected.iter().filter_map(|p| index_of(p)).collect();
+    *selection_anchor = anchor.and_then(|p| index_of(&p));
 }
 
 fn update_filtered(
@@ -1817,23 +4127,27 @@ fn navigate_to(
 fn navigate_to_directory(
     dest: PathBuf,
     current_dir: &mut PathBuf,
+    recent: &mut bool,
     history: &mut Vec<PathBuf>,
     history_index: &mut usize,
     all_entries: &mut Vec<DirEntry>,
+    loader: &mut DirLoader,
     directory_mode: bool,
     show_hidden: bool,
+    sort: SortOrder,
     search_text: &str,
     filtered_entries: &mut Vec<usize>,
     selected_indices: &mut HashSet<usize>,
-    scroll_offset: &mut usize,
+    scroll: &mut ScrollArea,
     filters: &[FileFilter],
 ) {
     if dest.exists() {
+        *recent = false;
         navigate_to(dest, current_dir, history, history_index);
-        load_directory(current_dir, all_entries, directory_mode, show_hidden);
+        loader.load(current_dir, all_entries, directory_mode, show_hidden, sort);
         update_filtered(all_entries, search_text, filtered_entries, filters);
         selected_indices.clear();
-        *scroll_offset = 0;
+        scroll.scroll_to(0);
     }
 }
 
@@ -1878,13 +4192,13 @@ fn format_date(time: Option<SystemTime>) -> String {
             let diff = now.saturating_sub(secs);
 
             if diff < 60 {
-                "Just now".to_string()
+                tr("Just now").to_string()
             } else if diff < 3600 {
-                format!("{} min ago", diff / 60)
+                tr_args("{} min ago", &[&(diff / 60)])
             } else if diff < 86400 {
-                format!("{} hrs ago", diff / 3600)
+                tr_args("{} hrs ago", &[&(diff / 3600)])
             } else if diff < 86400 * 7 {
-                format!("{} days ago", diff / 86400)
+                tr_args("{} days ago", &[&(diff / 86400)])
             } else {
                 // Convert to date-ish
                 let days_since_epoch = secs / 86400;
@@ -1896,6 +4210,59 @@ fn format_date(time: Option<SystemTime>) -> String {
     }
 }
 
+/// Draws a small triangle pointing up (ascending) or down.
+fn draw_sort_arrow(canvas: &mut Canvas, x: i32, y: i32, ascending: bool, color: Rgba, scale: f32) {
+    let rows = (4.0 * scale).round().max(2.0) as i32;
+    for row in 0..rows {
+        // Row width grows by 2px per row from the tip
+        let half = if ascending { row } else { rows - 1 - row };
+        canvas.fill_rect(
+            (x + rows - 1 - half) as f32,
+            (y + row) as f32,
+            (half * 2 + 1) as f32,
+            1.0,
+            color,
+        );
+    }
+}
+
+/// Tooltip for the toolbar button `x` pixels from the toolbar's left edge.
+fn toolbar_tooltip(
+    x: i32,
+    show_hidden: bool,
+    grid: bool,
+    show_preview: bool,
+    scale: f32,
+) -> Option<Cow<'static, str>> {
+    let buttons = [
+        (0.0, "Back"),
+        (32.0, "Forward"),
+        (68.0, "Parent folder (Backspace)"),
+        (104.0, "Home folder"),
+        (
+            150.0,
+            if show_hidden {
+                "Hide hidden files (Ctrl+H)"
+            } else {
+                "Show hidden files (Ctrl+H)"
+            },
+        ),
+        (186.0, if grid { "List view" } else { "Icon view" }),
+        (
+            222.0,
+            if show_preview {
+                "Hide preview"
+            } else {
+                "Show preview"
+            },
+        ),
+    ];
+    buttons
+        .into_iter()
+        .find(|&(left, _)| x >= (left * scale) as i32 && x < ((left + 28.0) * scale) as i32)
+        .map(|(_, text)| tr(text))
+}
+
 #[allow(clippy::too_many_arguments)]
 fn draw_nav_button(
     canvas: &mut Canvas,
@@ -1903,7 +4270,7 @@ fn draw_nav_button(
     y: i32,
     label: &str,
     enabled: bool,
-    colors: &Colors,
+    colors: &Theme,
     font: &Font,
     scale: f32,
 ) {
@@ -1931,7 +4298,7 @@ fn draw_toggle(
     y: i32,
     label: &str,
     active: bool,
-    colors: &Colors,
+    colors: &Theme,
     font: &Font,
     scale: f32,
 ) {
@@ -1944,7 +4311,7 @@ fn draw_toggle(
     canvas.fill_rounded_rect(x as f32, y as f32, size, size, 4.0 * scale, bg);
 
     let text_color = if active {
-        rgb(255, 255, 255)
+        colors.selection_text
     } else {
         colors.button_text
     };
@@ -1952,13 +4319,47 @@ fn draw_toggle(
     canvas.draw_canvas(&tc, x + (6.0 * scale) as i32, y + (6.0 * scale) as i32);
 }
 
+/// Toolbar toggle for icon view, drawn as a 2x2 grid of squares.
+fn draw_view_toggle(canvas: &mut Canvas, x: i32, y: i32, grid: bool, colors: &Theme, scale: f32) {
+    let (bg, fg) = if grid {
+        (colors.input_border_focused, colors.selection_text)
+    } else {
+        (colors.button, colors.button_text)
+    };
+    let size = 28.0 * scale;
+    canvas.fill_rounded_rect(x as f32, y as f32, size, size, 4.0 * scale, bg);
+
+    let square = 5.0 * scale;
+    let gap = 2.0 * scale;
+    let origin = (size - square * 2.0 - gap) / 2.0;
+    for row in 0..2 {
+        for col in 0..2 {
+            canvas.fill_rect(
+                x as f32 + origin + col as f32 * (square + gap),
+                y as f32 + origin + row as f32 * (square + gap),
+                square,
+                square,
+                fg,
+            );
+        }
+    }
+}
+
+/// Lays `text` out on one line in `color`, shortened with "..." where
+/// `ellipsize` says until it fits within `max_w`.
+fn fit_text(font: &Font, text: &str, max_w: u32, color: Rgba, ellipsize: Ellipsize) -> Label {
+    let mut label = Label::new(text).with_ellipsize(ellipsize);
+    label.layout(font, color, Some(max_w));
+    label
+}
+
 fn draw_breadcrumbs(
     canvas: &mut Canvas,
     x: i32,
     y: i32,
     max_w: u32,
     path: &Path,
-    colors: &Colors,
+    colors: &Theme,
     font: &Font,
 ) {
     let components: Vec<_> = path.components().collect();
@@ -2090,7 +4491,7 @@ fn draw_breadcrumbs(
     }
 }
 
-fn draw_folder_icon(canvas: &mut Canvas, x: i32, y: i32, colors: &Colors, scale: f32) {
+fn draw_folder_icon(canvas: &mut Canvas, x: i32, y: i32, colors: &Theme, scale: f32) {
     let folder_color = rgb(240, 180, 70); // Golden folder
     let icon_size = BASE_ICON_SIZE as f32 * scale;
     // Folder body
@@ -2114,7 +4515,7 @@ fn draw_folder_icon(canvas: &mut Canvas, x: i32, y: i32, colors: &Colors, scale:
     let _ = colors;
 }
 
-fn draw_file_icon(canvas: &mut Canvas, x: i32, y: i32, name: &str, colors: &Colors, scale: f32) {
+fn draw_file_icon(canvas: &mut Canvas, x: i32, y: i32, name: &str, colors: &Theme, scale: f32) {
     let ext = name.rsplit('.').next().unwrap_or("").to_lowercase();
     let icon_size = BASE_ICON_SIZE as f32 * scale;
 
@@ -2150,12 +4551,148 @@ fn draw_file_icon(canvas: &mut Canvas, x: i32, y: i32, name: &str, colors: &Colo
     let _ = colors;
 }
 
+/// Toolbar toggle for the preview pane, drawn as a window with a side panel.
+fn draw_preview_toggle(
+    canvas: &mut Canvas,
+    x: i32,
+    y: i32,
+    active: bool,
+    colors: &Theme,
+    scale: f32,
+) {
+    let (bg, fg) = if active {
+        (colors.input_border_focused, colors.selection_text)
+    } else {
+        (colors.button, colors.button_text)
+    };
+    let size = 28.0 * scale;
+    canvas.fill_rounded_rect(x as f32, y as f32, size, size, 4.0 * scale, bg);
+
+    let (ix, iy) = (x as f32 + 7.0 * scale, y as f32 + 8.0 * scale);
+    let (iw, ih) = (14.0 * scale, 12.0 * scale);
+    canvas.stroke_rounded_rect(ix, iy, iw, ih, 1.0 * scale, fg, 1.5 * scale);
+    canvas.fill_rect(ix + iw * 0.6, iy, iw * 0.4, ih, fg);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_preview_pane(
+    canvas: &mut Canvas,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    preview: Option<&Preview>,
+    previews: &Thumbnails,
+    colors: &Theme,
+    font: &Font,
+    scale: f32,
+) {
+    canvas.fill_rounded_rect(
+        x as f32,
+        y as f32,
+        width as f32,
+        height as f32,
+        6.0 * scale,
+        colors.input_bg,
+    );
+    canvas.stroke_rounded_rect(
+        x as f32,
+        y as f32,
+        width as f32,
+        height as f32,
+        6.0 * scale,
+        colors.input_border,
+        1.0,
+    );
+
+    let Some(preview) = preview else {
+        let hint = font
+            .render(&tr("No selection"))
+            .with_color(rgb(140, 140, 140))
+            .finish();
+        canvas.draw_canvas(
+            &hint,
+            x + (width as i32 - hint.width() as i32) / 2,
+            y + (height as i32 - hint.height() as i32) / 2,
+        );
+        return;
+    };
+
+    let inset = (8.0 * scale) as i32;
+    let inner_w = width - 2 * inset as u32;
+    let bottom = y + height as i32 - inset;
+    let line_h = font.render("Ag").finish().height() as i32;
+    let mut cy = y + inset;
+
+    // Image
+    if let Some(thumb) = previews.get(&preview.path) {
+        canvas.draw_canvas(
+            &thumb.large,
+            x + (width as i32 - thumb.large.width() as i32) / 2,
+            cy,
+        );
+        cy += thumb.large.height() as i32 + inset;
+    }
+
+    // Name and details
+    let mut name = fit_text(font, &preview.name, inner_w, colors.text, Ellipsize::Middle);
+    name.set_position(x + inset, cy);
+    name.draw(canvas, colors);
+    cy += line_h + (4.0 * scale) as i32;
+    for detail in &preview.details {
+        let mut line = fit_text(font, detail, inner_w, rgb(140, 140, 140), Ellipsize::End);
+        line.set_position(x + inset, cy);
+        line.draw(canvas, colors);
+        cy += line_h;
+    }
+
+    // Text file contents, as many lines as fit
+    let lines = previews.text(&preview.path);
+    if !lines.is_empty() {
+        cy += inset / 2;
+        canvas.fill_rect(
+            (x + inset) as f32,
+            cy as f32,
+            inner_w as f32,
+            1.0,
+            colors.input_border,
+        );
+        cy += inset / 2;
+        for text in lines {
+            if cy + line_h > bottom {
+                break;
+            }
+            let mut line = fit_text(font, text, inner_w, colors.text, Ellipsize::End);
+            line.set_position(x + inset, cy);
+            line.draw(canvas, colors);
+            cy += line_h;
+        }
+    }
+}
+
+/// Small arrow badge marking a symlink; `(x, y)` is its bottom-left corner.
+fn draw_link_emblem(canvas: &mut Canvas, x: i32, y: i32, size: f32, colors: &Theme) {
+    let (x, y) = (x as f32, y as f32 - size);
+    let u = size / 8.0;
+    canvas.fill_rounded_rect(x, y, size, size, 2.0 * u, rgb(255, 255, 255));
+    canvas.stroke_rounded_rect(x, y, size, size, 2.0 * u, colors.input_border, 1.0);
+
+    // Arrow pointing up and to the right
+    let arrow = rgb(60, 60, 60);
+    canvas.fill_rect(x + 3.0 * u, y + 2.0 * u, 3.0 * u, u, arrow);
+    canvas.fill_rect(x + 5.0 * u, y + 2.0 * u, u, 3.0 * u, arrow);
+    for i in 0..3 {
+        let i = i as f32;
+        canvas.fill_rect(x + (2.0 + i) * u, y + (5.0 - i) * u, u, u, arrow);
+    }
+}
+
 fn draw_quick_access_icon(
     canvas: &mut Canvas,
     x: i32,
     y: i32,
     icon: QuickAccessIcon,
-    colors: &Colors,
+    colors: &Theme,
     scale: f32,
 ) {
     let color = match icon {
@@ -2166,6 +4703,8 @@ fn draw_quick_access_icon(
         QuickAccessIcon::Pictures => rgb(180, 120, 180),
         QuickAccessIcon::Music => rgb(220, 120, 120),
         QuickAccessIcon::Videos => rgb(180, 100, 200),
+        QuickAccessIcon::Bookmark => rgb(150, 150, 160),
+        QuickAccessIcon::Recent => rgb(90, 170, 170),
     };
 
     canvas.fill_rounded_rect(
@@ -2184,7 +4723,7 @@ fn draw_section_header(
     x: i32,
     y: i32,
     label: &str,
-    colors: &Colors,
+    colors: &Theme,
     font: &Font,
     scale: f32,
 ) {
@@ -2206,7 +4745,7 @@ fn draw_mount_icon(
     x: i32,
     y: i32,
     icon: MountIcon,
-    colors: &Colors,
+    colors: &Theme,
     scale: f32,
 ) {
     let icon_size = 16.0 * scale;
@@ -2244,3 +4783,29 @@ fn draw_mount_icon(
 
     let _ = colors;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parent_entry_is_not_managed() {
+        let dir = Path::new("/home/user/docs");
+        assert!(!is_child_of(
+            &parent_entry(Path::new("/home/user")).path,
+            dir
+        ));
+        assert!(!is_child_of(Path::new("/home/user/other/a.txt"), dir));
+        assert!(is_child_of(Path::new("/home/user/docs/a.txt"), dir));
+    }
+
+    #[test]
+    fn names_stay_in_their_directory() {
+        assert!(is_valid_name("notes.txt"));
+        assert!(is_valid_name(".hidden"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("."));
+        assert!(!is_valid_name(".."));
+        assert!(!is_valid_name("../escape"));
+    }
+}
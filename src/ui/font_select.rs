@@ -0,0 +1,771 @@
+//! Font selection dialog implementation.
+//!
+//! System fonts are listed with fontconfig's `fc-list`; the preview renders the
+//! chosen face directly from its font file.
+
+use std::{path::PathBuf, process::Command};
+
+use crate::{
+    backend::{CursorShape, MouseButton, ScrollDirection, Window, WindowEvent},
+    error::Error,
+    render::{Canvas, Font},
+    ui::{
+        Theme, WindowPlacement, create_window,
+        strings::tr,
+        widgets::{
+            Widget, button::Button, focus::FocusManager, label::Label, list_pane::ListPane,
+            point_in_rect, text_input::TextInput,
+        },
+    },
+};
+
+const BASE_WINDOW_WIDTH: u32 = 600;
+const BASE_WINDOW_HEIGHT: u32 = 480;
+const BASE_PADDING: u32 = 16;
+const BASE_GAP: u32 = 10;
+const BASE_ROW_HEIGHT: u32 = 26;
+const BASE_STYLE_WIDTH: u32 = 160;
+const BASE_SIZE_WIDTH: u32 = 90;
+const BASE_PREVIEW_HEIGHT: u32 = 80;
+
+const DEFAULT_SIZE: u32 = 11;
+const MIN_SIZE: u32 = 4;
+const MAX_SIZE: u32 = 144;
+const DEFAULT_PREVIEW_TEXT: &str = "The quick brown fox jumps over the lazy dog";
+
+const KEY_ESCAPE: u32 = 0xff1b;
+const KEY_RETURN: u32 = 0xff0d;
+const KEY_KP_ENTER: u32 = 0xff8d;
+const KEY_UP: u32 = 0xff52;
+const KEY_DOWN: u32 = 0xff54;
+const KEY_PAGE_UP: u32 = 0xff55;
+const KEY_PAGE_DOWN: u32 = 0xff56;
+
+/// Font selection dialog result.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FontSelectResult {
+    /// User picked a font. `size` is in points.
+    Selected {
+        family: String,
+        style: String,
+        size: u32,
+    },
+    /// User cancelled.
+    Cancelled,
+    /// Dialog was closed.
+    Closed,
+}
+
+impl FontSelectResult {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            FontSelectResult::Selected {
+                ..
+            } => 0,
+            FontSelectResult::Cancelled => 1,
+            FontSelectResult::Closed => 255,
+        }
+    }
+
+    /// Returns the font as "Family Style Size". A regular style is left out,
+    /// as Pango font descriptions do.
+    pub fn to_string(&self) -> Option<String> {
+        match self {
+            FontSelectResult::Selected {
+                family,
+                style,
+                size,
+            } => {
+                Some(if style_rank(style) == 0 {
+                    format!("{family} {size}")
+                } else {
+                    format!("{family} {style} {size}")
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Font selection dialog builder.
+pub struct FontSelectBuilder {
+    title: String,
+    text: String,
+    font_name: String,
+    preview_text: String,
+    width: Option<u32>,
+    height: Option<u32>,
+    colors: Option<Theme>,
+    pub(super) placing: WindowPlacement,
+}
+
+impl FontSelectBuilder {
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            text: String::new(),
+            font_name: String::new(),
+            preview_text: String::new(),
+            width: None,
+            height: None,
+            colors: None,
+            placing: WindowPlacement::default(),
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    /// Initially selected font, as "Family Style Size" (e.g. "DejaVu Sans Bold 12").
+    /// Defaults to fontconfig's sans-serif font.
+    pub fn font_name(mut self, font_name: &str) -> Self {
+        self.font_name = font_name.to_string();
+        self
+    }
+
+    /// Sample text shown in the preview.
+    pub fn preview_text(mut self, preview_text: &str) -> Self {
+        self.preview_text = preview_text.to_string();
+        self
+    }
+
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
+        self
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn show(self) -> Result<FontSelectResult, Error> {
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
+        let families = list_system_fonts();
+
+        let logical_width = self.width.unwrap_or(BASE_WINDOW_WIDTH);
+        let logical_height = self.height.unwrap_or(BASE_WINDOW_HEIGHT);
+
+        let mut window = create_window(
+            logical_width as u16,
+            logical_height as u16,
+            colors,
+            &self.placing,
+        )?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Select Font")
+        } else {
+            self.title.as_str().into()
+        })?;
+
+        // Get the actual scale factor from the window (compositor scale)
+        let scale = window.scale_factor();
+        let physical_width = (logical_width as f32 * scale) as u32;
+        let physical_height = (logical_height as f32 * scale) as u32;
+
+        // Now create everything at PHYSICAL scale
+        let font = colors.font(scale);
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
+        let gap = (colors.space(BASE_GAP) as f32 * scale) as u32;
+        let row_height = (BASE_ROW_HEIGHT as f32 * scale) as u32;
+        let style_w = (BASE_STYLE_WIDTH as f32 * scale) as u32;
+        let size_w = (BASE_SIZE_WIDTH as f32 * scale) as u32;
+        let preview_h = (BASE_PREVIEW_HEIGHT as f32 * scale) as u32;
+        let content_w = physical_width - padding * 2;
+
+        // Initial selection: the requested font, else the default sans-serif
+        let requested = if self.font_name.is_empty() {
+            default_font_name()
+        } else {
+            self.font_name.clone()
+        };
+        let (mut family, mut style, mut size) = parse_font_name(&requested, &families);
+
+        let preview_text = if self.preview_text.is_empty() {
+            DEFAULT_PREVIEW_TEXT
+        } else {
+            &self.preview_text
+        };
+
+        // Layout in physical coordinates, top to bottom
+        let mut y = padding as i32;
+        let mut prompt = Label::new(&self.text).with_wrap(true);
+        prompt.layout(&font, colors.text, Some(content_w));
+        prompt.set_position(padding as i32, y);
+        if !self.text.is_empty() {
+            y += prompt.height() as i32 + gap as i32;
+        }
+
+        let mut search_input = TextInput::new(content_w).with_placeholder(&tr("Search fonts..."));
+        search_input.set_position(padding as i32, y);
+        y += search_input.height() as i32 + gap as i32;
+
+        let mut ok_button = Button::new(&tr("OK"), &font, colors, scale);
+        let mut cancel_button = Button::new(&tr("Cancel"), &font, colors, scale);
+        let button_y = (physical_height - padding) as i32 - ok_button.height() as i32;
+        let mut bx = physical_width as i32 - padding as i32;
+        bx -= cancel_button.width() as i32;
+        cancel_button.set_position(bx, button_y);
+        bx -= gap as i32 + ok_button.width() as i32;
+        ok_button.set_position(bx, button_y);
+        ok_button.set_default(true);
+
+        let preview_y = button_y - gap as i32 - preview_h as i32;
+        let panes_h = (preview_y - gap as i32 - y).max(row_height as i32) as u32;
+
+        // Family and style columns, then the size spinner
+        let family_w = content_w - style_w - size_w - gap * 2;
+        let mut family_pane = ListPane::new(padding as i32, y, family_w, panes_h, row_height);
+        let style_x = padding as i32 + (family_w + gap) as i32;
+        let mut style_pane = ListPane::new(style_x, y, style_w, panes_h, row_height);
+
+        let size_x = style_x + (style_w + gap) as i32;
+        let mut size_input = TextInput::new(size_w).with_default_text(&size.to_string());
+        size_input.set_position(size_x, y);
+        let step_w = (size_w - gap / 2) / 2;
+        let mut smaller_button = Button::new(&tr("-"), &font, colors, scale);
+        let mut larger_button = Button::new(&tr("+"), &font, colors, scale);
+        smaller_button.set_width(step_w);
+        larger_button.set_width(step_w);
+        let step_y = y + size_input.height() as i32 + (gap / 2) as i32;
+        smaller_button.set_position(size_x, step_y);
+        larger_button.set_position(size_x + (size_w - step_w) as i32, step_y);
+
+        // Families matching the search, as indices into `families`
+        let mut shown: Vec<usize> = (0..families.len()).collect();
+        family_pane.set_items(
+            families.iter().map(|f| f.name.clone()).collect(),
+            Some(family),
+        );
+        style_pane.set_items(style_names(&families, family), Some(style));
+        let mut styles_active = false; // Arrow keys move through styles rather than families
+
+        let mut preview_font = load_face(&families, family, style, size, scale);
+        let mut canvas = Canvas::new(physical_width, physical_height);
+
+        let draw = |canvas: &mut Canvas,
+                    family_pane: &ListPane,
+                    style_pane: &ListPane,
+                    styles_active: bool,
+                    search_input: &TextInput,
+                    size_input: &TextInput,
+                    buttons: [&Button; 4],
+                    preview_font: &Font| {
+            let width = canvas.width() as f32;
+            let height = canvas.height() as f32;
+            canvas.fill_dialog_bg(
+                width,
+                height,
+                colors.window_bg,
+                colors.window_border,
+                colors.window_radius * scale,
+                colors.border_width,
+            );
+
+            prompt.draw(canvas, colors);
+            search_input.draw_to(canvas, colors, &font);
+            family_pane.draw(canvas, &font, colors, !styles_active, scale);
+            style_pane.draw(canvas, &font, colors, styles_active, scale);
+            size_input.draw_to(canvas, colors, &font);
+
+            // Preview, clipped to its box
+            let mut preview = Canvas::new(content_w, preview_h);
+            preview.fill_rounded_rect(
+                0.0,
+                0.0,
+                content_w as f32,
+                preview_h as f32,
+                4.0 * scale,
+                colors.input_bg,
+            );
+            let sample = preview_font
+                .render(preview_text)
+                .with_color(colors.text)
+                .finish();
+            let sample_y = ((preview_h as i32 - sample.height() as i32) / 2).max(0);
+            preview.draw_canvas(&sample, (10.0 * scale) as i32, sample_y);
+            canvas.draw_canvas(&preview, padding as i32, preview_y);
+            canvas.stroke_rounded_rect(
+                padding as f32,
+                preview_y as f32,
+                content_w as f32,
+                preview_h as f32,
+                4.0 * scale,
+                colors.input_border,
+                1.0,
+            );
+
+            for button in buttons {
+                button.draw_to(canvas, colors, &font);
+            }
+        };
+
+        draw(
+            &mut canvas,
+            &family_pane,
+            &style_pane,
+            styles_active,
+            &search_input,
+            &size_input,
+            [&smaller_button, &larger_button, &ok_button, &cancel_button],
+            &preview_font,
+        );
+        window.set_contents(&canvas)?;
+        window.show()?;
+
+        let mut mouse_x = 0i32;
+        let mut mouse_y = 0i32;
+        let mut focus = FocusManager::new();
+
+        // Event loop; each pass handles every pending event, then redraws once
+        loop {
+            let mut next = Some(window.wait_for_event()?);
+            let mut needs_redraw = false;
+            let mut reload_preview = false;
+            let mut accept = false;
+
+            while let Some(event) = next.take() {
+                let old_search = search_input.text().to_string();
+                let old_size = size_input.text().to_string();
+                let size_was_focused = size_input.has_focus();
+                let event = focus.process_event(
+                    event,
+                    &mut focus_order(
+                        &mut search_input,
+                        &mut size_input,
+                        &mut smaller_button,
+                        &mut larger_button,
+                        &mut ok_button,
+                        &mut cancel_button,
+                    ),
+                );
+
+                match &event {
+                    WindowEvent::CloseRequested => return Ok(FontSelectResult::Closed),
+                    WindowEvent::RedrawRequested => needs_redraw = true,
+                    WindowEvent::KeyPress(key) => {
+                        match key.keysym {
+                            KEY_ESCAPE => return Ok(FontSelectResult::Cancelled),
+                            KEY_RETURN | KEY_KP_ENTER => accept = true,
+                            KEY_UP | KEY_DOWN if size_input.has_focus() => {
+                                let step = if key.keysym == KEY_UP { 1 } else { -1 };
+                                size = size.saturating_add_signed(step).clamp(MIN_SIZE, MAX_SIZE);
+                                size_input.set_text(&size.to_string());
+                                reload_preview = true;
+                            }
+                            KEY_UP | KEY_DOWN | KEY_PAGE_UP | KEY_PAGE_DOWN => {
+                                let pane = if styles_active {
+                                    &mut style_pane
+                                } else {
+                                    &mut family_pane
+                                };
+                                let rows = pane.visible_rows() as isize;
+                                pane.step(match key.keysym {
+                                    KEY_UP => -1,
+                                    KEY_DOWN => 1,
+                                    KEY_PAGE_UP => -rows,
+                                    _ => rows,
+                                });
+                                reload_preview = true;
+                            }
+                            _ => {}
+                        }
+                    }
+                    WindowEvent::CursorMove(pos) => {
+                        mouse_x = pos.x as i32;
+                        mouse_y = pos.y as i32;
+                        needs_redraw |= family_pane.hover(mouse_x, mouse_y);
+                        needs_redraw |= style_pane.hover(mouse_x, mouse_y);
+
+                        let over_input = [&search_input, &size_input].iter().any(|input| {
+                            point_in_rect(
+                                mouse_x,
+                                mouse_y,
+                                input.x(),
+                                input.y(),
+                                input.width(),
+                                input.height(),
+                            )
+                        });
+                        let _ = window.set_cursor(if over_input {
+                            CursorShape::Text
+                        } else {
+                            CursorShape::Default
+                        });
+                    }
+                    WindowEvent::CursorLeave => {
+                        needs_redraw |= family_pane.hover(-1, -1);
+                        needs_redraw |= style_pane.hover(-1, -1);
+                    }
+                    // Pasting focuses the input the text lands in
+                    WindowEvent::PrimaryPaste(..) => {
+                        let in_input = |input: &TextInput| {
+                            point_in_rect(
+                                mouse_x,
+                                mouse_y,
+                                input.x(),
+                                input.y(),
+                                input.width(),
+                                input.height(),
+                            )
+                        };
+                        let index = if in_input(&search_input) {
+                            Some(0)
+                        } else if in_input(&size_input) {
+                            Some(1)
+                        } else {
+                            None
+                        };
+                        if index.is_some() {
+                            focus.focus(
+                                index,
+                                &mut focus_order(
+                                    &mut search_input,
+                                    &mut size_input,
+                                    &mut smaller_button,
+                                    &mut larger_button,
+                                    &mut ok_button,
+                                    &mut cancel_button,
+                                ),
+                            );
+                        }
+                    }
+                    WindowEvent::ButtonPress(MouseButton::Left, _) => {
+                        // A click away from the widgets leaves the keys to
+                        // the panes
+                        let mut widgets = focus_order(
+                            &mut search_input,
+                            &mut size_input,
+                            &mut smaller_button,
+                            &mut larger_button,
+                            &mut ok_button,
+                            &mut cancel_button,
+                        );
+                        if !widgets.iter().any(|w| {
+                            point_in_rect(mouse_x, mouse_y, w.x(), w.y(), w.width(), w.height())
+                        }) {
+                            focus.focus(None, &mut widgets);
+                        }
+
+                        if let Some(row) = family_pane.row_at(mouse_x, mouse_y) {
+                            family_pane.select(Some(row));
+                            styles_active = false;
+                            reload_preview = true;
+                        } else if let Some(row) = style_pane.row_at(mouse_x, mouse_y) {
+                            style_pane.select(Some(row));
+                            styles_active = true;
+                            reload_preview = true;
+                        }
+                        needs_redraw = true;
+                    }
+                    WindowEvent::Scroll(direction) => {
+                        let rows = match direction {
+                            ScrollDirection::Up => -3,
+                            ScrollDirection::Down => 3,
+                            _ => 0,
+                        };
+                        for pane in [&mut family_pane, &mut style_pane] {
+                            if pane.contains(mouse_x, mouse_y) {
+                                pane.scroll(rows);
+                                needs_redraw = true;
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+
+                // A size left half typed goes back to the one in use
+                if size_was_focused && !size_input.has_focus() {
+                    size_input.set_text(&size.to_string());
+                }
+
+                needs_redraw |= search_input.process_event(&event);
+                needs_redraw |= size_input.process_event(&event);
+                for button in [
+                    &mut smaller_button,
+                    &mut larger_button,
+                    &mut ok_button,
+                    &mut cancel_button,
+                ] {
+                    needs_redraw |= button.process_event(&event);
+                }
+                // Enter is handled above for every focus
+                search_input.was_submitted();
+                size_input.was_submitted();
+
+                // Filter families by name, keeping the selected one if it matches
+                if search_input.text() != old_search {
+                    let query = search_input.text().to_lowercase();
+                    shown = (0..families.len())
+                        .filter(|&i| families[i].name.to_lowercase().contains(&query))
+                        .collect();
+                    let keep = shown.iter().position(|&i| i == family);
+                    family_pane.set_items(
+                        shown.iter().map(|&i| families[i].name.clone()).collect(),
+                        keep.or((!shown.is_empty()).then_some(0)),
+                    );
+                    styles_active = false;
+                    reload_preview = true;
+                }
+
+                // Typed sizes apply as soon as they are valid
+                if size_input.text() != old_size
+                    && let Ok(typed) = size_input.text().trim().parse::<u32>()
+                    && (MIN_SIZE..=MAX_SIZE).contains(&typed)
+                {
+                    size = typed;
+                    reload_preview = true;
+                }
+
+                let step = if smaller_button.was_clicked() {
+                    -1
+                } else if larger_button.was_clicked() {
+                    1
+                } else {
+                    0
+                };
+                if step != 0 {
+                    size = size.saturating_add_signed(step).clamp(MIN_SIZE, MAX_SIZE);
+                    size_input.set_text(&size.to_string());
+                    reload_preview = true;
+                }
+
+                if cancel_button.was_clicked() {
+                    return Ok(FontSelectResult::Cancelled);
+                }
+                accept |= ok_button.was_clicked();
+
+                next = window.poll_for_event()?;
+            }
+
+            if reload_preview {
+                // A new family keeps the current style name when it has one
+                if let Some(&picked) = family_pane.selected.and_then(|row| shown.get(row))
+                    && picked != family
+                {
+                    let style_name = families[family].faces[style].style.clone();
+                    family = picked;
+                    style = families[family]
+                        .faces
+                        .iter()
+                        .position(|face| face.style == style_name)
+                        .unwrap_or(0);
+                    style_pane.set_items(style_names(&families, family), Some(style));
+                }
+                style = style_pane.selected.unwrap_or(style);
+                preview_font = load_face(&families, family, style, size, scale);
+                needs_redraw = true;
+            }
+
+            if accept {
+                return Ok(FontSelectResult::Selected {
+                    family: families[family].name.clone(),
+                    style: families[family].faces[style].style.clone(),
+                    size,
+                });
+            }
+
+            if needs_redraw {
+                draw(
+                    &mut canvas,
+                    &family_pane,
+                    &style_pane,
+                    styles_active,
+                    &search_input,
+                    &size_input,
+                    [&smaller_button, &larger_button, &ok_button, &cancel_button],
+                    &preview_font,
+                );
+                window.set_contents(&canvas)?;
+            }
+        }
+    }
+}
+
+/// The dialog's widgets in Tab order.
+fn focus_order<'a>(
+    search_input: &'a mut TextInput,
+    size_input: &'a mut TextInput,
+    smaller_button: &'a mut Button,
+    larger_button: &'a mut Button,
+    ok_button: &'a mut Button,
+    cancel_button: &'a mut Button,
+) -> [&'a mut dyn Widget; 6] {
+    [
+        search_input,
+        size_input,
+        smaller_button,
+        larger_button,
+        ok_button,
+        cancel_button,
+    ]
+}
+
+impl Default for FontSelectBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A font family and its styles.
+struct Family {
+    name: String,
+    faces: Vec<Face>,
+}
+
+/// One style of a family and where to load it from.
+struct Face {
+    style: String,
+    file: PathBuf,
+    /// Face index within the file (for font collections).
+    index: u32,
+}
+
+/// Lists scalable system fonts with `fc-list`, families sorted by name and
+/// regular styles first. Falls back to the built-in font without fontconfig.
+fn list_system_fonts() -> Vec<Family> {
+    let output = Command::new("fc-list")
+        .args([
+            "--format",
+            "%{family[0]}\t%{style[0]}\t%{index}\t%{file}\n",
+            ":scalable=true",
+        ])
+        .output();
+    let stdout = output.map(|o| o.stdout).unwrap_or_default();
+
+    let mut families: Vec<Family> = Vec::new();
+    for line in String::from_utf8_lossy(&stdout).lines() {
+        let mut fields = line.splitn(4, '\t');
+        let (Some(name), Some(style), Some(index), Some(file)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if name.is_empty() || style.is_empty() {
+            continue;
+        }
+        let face = Face {
+            style: style.to_string(),
+            file: PathBuf::from(file),
+            // The high bits number named instances of variable fonts
+            index: index.parse::<u32>().unwrap_or(0) & 0xffff,
+        };
+        match families.iter_mut().find(|f| f.name == name) {
+            Some(family) => {
+                if !family.faces.iter().any(|f| f.style == face.style) {
+                    family.faces.push(face);
+                }
+            }
+            None => {
+                families.push(Family {
+                    name: name.to_string(),
+                    faces: vec![face],
+                })
+            }
+        }
+    }
+
+    if families.is_empty() {
+        families.push(Family {
+            name: "Cantarell".to_string(),
+            faces: vec![Face {
+                style: "Regular".to_string(),
+                file: PathBuf::new(),
+                index: 0,
+            }],
+        });
+    }
+
+    families.sort_by_key(|f| f.name.to_lowercase());
+    for family in &mut families {
+        family.faces.sort_by(|a, b| {
+            (style_rank(&a.style), &a.style).cmp(&(style_rank(&b.style), &b.style))
+        });
+    }
+    families
+}
+
+/// Orders the common styles first; 0 is the family's regular face.
+fn style_rank(style: &str) -> u8 {
+    match style {
+        "Regular" | "Book" | "Normal" | "Roman" => 0,
+        "Italic" | "Oblique" => 1,
+        "Bold" => 2,
+        "Bold Italic" | "Bold Oblique" => 3,
+        _ => 4,
+    }
+}
+
+fn style_names(families: &[Family], family: usize) -> Vec<String> {
+    families[family]
+        .faces
+        .iter()
+        .map(|f| f.style.clone())
+        .collect()
+}
+
+/// Returns fontconfig's default sans-serif font as "Family Style".
+fn default_font_name() -> String {
+    Command::new("fc-match")
+        .args(["--format", "%{family[0]} %{style[0]}", "sans-serif"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+        .unwrap_or_default()
+}
+
+/// Splits a "Family Style Size" name into family and style indices and a
+/// size, matching the longest known family name. Unknown parts fall back to
+/// the first family, its regular style and the default size.
+fn parse_font_name(name: &str, families: &[Family]) -> (usize, usize, u32) {
+    let name = name.trim();
+    let (rest, size) = match name.rsplit_once(' ') {
+        Some((rest, last)) if last.parse::<f32>().is_ok() => {
+            let size = last.parse::<f32>().unwrap_or(0.0).round() as u32;
+            (rest, size.clamp(MIN_SIZE, MAX_SIZE))
+        }
+        _ => (name, DEFAULT_SIZE),
+    };
+
+    let lower = rest.to_lowercase();
+    let family = families
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| {
+            let family = f.name.to_lowercase();
+            lower == family || lower.starts_with(&format!("{family} "))
+        })
+        .max_by_key(|(_, f)| f.name.len())
+        .map(|(i, _)| i);
+    let Some(family) = family else {
+        return (0, 0, size);
+    };
+
+    let style_name = rest[families[family].name.len()..].trim();
+    let style = families[family]
+        .faces
+        .iter()
+        .position(|f| f.style.eq_ignore_ascii_case(style_name))
+        .unwrap_or(0);
+    (family, style, size)
+}
+
+/// Loads a face for the preview at `size` points, falling back to the
+/// built-in font if the file can't be read.
+fn load_face(families: &[Family], family: usize, style: usize, size: u32, scale: f32) -> Font {
+    let face = &families[family].faces[style];
+    let px = size as f32 * 96.0 / 72.0 * scale;
+    Font::load_file(&face.file, face.index, px).unwrap_or_else(|| Font::load_with_size(px))
+}
@@ -1,12 +1,16 @@
 //! Forms dialog implementation for multiple input fields.
 
 use crate::{
-    backend::{CursorShape, Window, WindowEvent, create_window},
+    backend::{CursorShape, Window, WindowEvent},
     error::Error,
     render::{Canvas, Font},
     ui::{
-        Colors,
-        widgets::{Widget, button::Button, text_input::TextInput},
+        Theme, WindowPlacement, create_window,
+        strings::tr,
+        widgets::{
+            Widget, button::Button, focus::FocusManager, label::Label, point_in_rect,
+            spin_button::SpinButton, text_input::TextInput,
+        },
     },
 };
 
@@ -24,6 +28,13 @@ pub enum FormField {
     Entry(String),
     /// Password field (hidden text).
     Password(String),
+    /// Number field, stepped between a minimum and a maximum.
+    Spin {
+        label: String,
+        min: i32,
+        max: i32,
+        step: i32,
+    },
 }
 
 impl FormField {
@@ -31,6 +42,9 @@ impl FormField {
         match self {
             FormField::Entry(label) => label,
             FormField::Password(label) => label,
+            FormField::Spin {
+                label, ..
+            } => label,
         }
     }
 
@@ -41,6 +55,7 @@ impl FormField {
 
 /// Forms dialog result.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FormsResult {
     /// User entered values and clicked OK.
     Values(Vec<String>),
@@ -68,7 +83,8 @@ pub struct FormsBuilder {
     separator: String,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    colors: Option<Theme>,
+    pub(super) placing: WindowPlacement,
 }
 
 impl FormsBuilder {
@@ -81,6 +97,7 @@ impl FormsBuilder {
             width: None,
             height: None,
             colors: None,
+            placing: WindowPlacement::default(),
         }
     }
 
@@ -106,14 +123,26 @@ impl FormsBuilder {
         self
     }
 
+    /// Add a number field from `min` to `max`, starting at `min`, which
+    /// its arrows move by `step`.
+    pub fn add_spin(mut self, label: &str, min: i32, max: i32, step: i32) -> Self {
+        self.fields.push(FormField::Spin {
+            label: label.to_string(),
+            min,
+            max,
+            step,
+        });
+        self
+    }
+
     /// Set the output separator (default: "|").
     pub fn separator(mut self, sep: &str) -> Self {
         self.separator = sep.to_string();
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
         self
     }
 
@@ -127,40 +156,63 @@ impl FormsBuilder {
         self
     }
 
+    /// Checks the number fields' ranges and steps, as `show` does before
+    /// showing the dialog.
+    pub fn validate(&self) -> Result<(), Error> {
+        for field in &self.fields {
+            if let FormField::Spin {
+                label,
+                min,
+                max,
+                step,
+            } = field
+            {
+                if min > max {
+                    return Err(Error::InvalidConfig(format!(
+                        "field \"{label}\": minimum {min} is above maximum {max}"
+                    )));
+                }
+                if *step < 1 {
+                    return Err(Error::InvalidConfig(format!(
+                        "field \"{label}\": step {step} is not positive"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn show(self) -> Result<FormsResult, Error> {
+        self.validate()?;
         if self.fields.is_empty() {
             return Ok(FormsResult::Values(Vec::new()));
         }
 
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
 
         // First pass: calculate LOGICAL dimensions using scale 1.0
-        let temp_font = Font::load(1.0);
-        let temp_ok = Button::new("OK", &temp_font, 1.0);
-        let temp_cancel = Button::new("Cancel", &temp_font, 1.0);
-        let temp_prompt_height = if !self.text.is_empty() {
-            temp_font
-                .render(&self.text)
-                .with_max_width(BASE_INPUT_WIDTH as f32)
-                .finish()
-                .height()
-        } else {
-            0
-        };
+        let temp_font = colors.font(1.0);
+        let temp_ok = Button::new(&tr("OK"), &temp_font, colors, 1.0);
+        let temp_cancel = Button::new(&tr("Cancel"), &temp_font, colors, 1.0);
+        let mut temp_prompt = Label::new(&self.text).with_wrap(true);
+        temp_prompt.layout(&temp_font, colors.text, Some(BASE_INPUT_WIDTH));
+        let temp_prompt_height = temp_prompt.height();
 
         let logical_buttons_width = temp_ok.width() + temp_cancel.width() + 10;
         let logical_content_width =
             (BASE_LABEL_WIDTH + BASE_INPUT_WIDTH + 10).max(logical_buttons_width);
-        let calc_width = (logical_content_width + BASE_PADDING * 2).max(BASE_MIN_WIDTH);
+        let calc_width =
+            (logical_content_width + colors.space(BASE_PADDING) * 2).max(BASE_MIN_WIDTH);
 
         // Height: padding + text + fields + buttons + padding
-        let fields_height = self.fields.len() as u32 * (BASE_FIELD_HEIGHT + BASE_FIELD_SPACING);
-        let calc_height = BASE_PADDING * 2
+        let fields_height =
+            self.fields.len() as u32 * (BASE_FIELD_HEIGHT + colors.space(BASE_FIELD_SPACING));
+        let calc_height = colors.space(BASE_PADDING) * 2
             + temp_prompt_height
             + (if temp_prompt_height > 0 { 16 } else { 0 })
             + fields_height
             + 16
-            + 32; // Button area
+            + colors.control_height; // Button area
 
         drop(temp_font);
         drop(temp_ok);
@@ -171,23 +223,23 @@ impl FormsBuilder {
         let logical_height = self.height.unwrap_or(calc_height) as u16;
 
         // Create window with LOGICAL dimensions
-        let mut window = create_window(logical_width, logical_height)?;
-        window.set_title(if self.title.is_empty() {
-            "Forms"
+        let mut window = create_window(logical_width, logical_height, colors, &self.placing)?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Forms")
         } else {
-            &self.title
+            self.title.as_str().into()
         })?;
 
         // Get the actual scale factor from the window (compositor scale)
         let scale = window.scale_factor();
 
         // Now create everything at PHYSICAL scale
-        let font = Font::load(scale);
+        let font = colors.font(scale);
 
         // Scale dimensions for physical rendering
-        let padding = (BASE_PADDING as f32 * scale) as u32;
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
         let field_height = (BASE_FIELD_HEIGHT as f32 * scale) as u32;
-        let field_spacing = (BASE_FIELD_SPACING as f32 * scale) as u32;
+        let field_spacing = (colors.space(BASE_FIELD_SPACING) as f32 * scale) as u32;
         let label_width = (BASE_LABEL_WIDTH as f32 * scale) as u32;
         let input_width = (BASE_INPUT_WIDTH as f32 * scale) as u32;
 
@@ -196,55 +248,68 @@ impl FormsBuilder {
         let physical_height = (logical_height as f32 * scale) as u32;
 
         // Create buttons at physical scale
-        let mut ok_button = Button::new("OK", &font, scale);
-        let mut cancel_button = Button::new("Cancel", &font, scale);
+        let mut ok_button = Button::new(&tr("OK"), &font, colors, scale);
+        let mut cancel_button = Button::new(&tr("Cancel"), &font, colors, scale);
 
         // Render prompt text at physical scale (wrapped to fit)
-        let prompt_canvas = if !self.text.is_empty() {
-            Some(
-                font.render(&self.text)
-                    .with_color(colors.text)
-                    .with_max_width(input_width as f32)
-                    .finish(),
-            )
-        } else {
-            None
-        };
-        let prompt_height = prompt_canvas.as_ref().map(|c| c.height()).unwrap_or(0);
+        let mut prompt = Label::new(&self.text).with_wrap(true);
+        prompt.layout(&font, colors.text, Some(input_width));
 
-        // Create text inputs for each field
-        let mut inputs: Vec<TextInput> = self
+        // Create inputs for each field
+        let mut inputs: Vec<Input> = self
             .fields
             .iter()
-            .map(|field| TextInput::new(input_width).with_password(field.is_password()))
+            .map(|field| {
+                match *field {
+                    FormField::Spin {
+                        min,
+                        max,
+                        step,
+                        ..
+                    } => Input::Spin(SpinButton::new(min, max, min, &font, scale).with_step(step)),
+                    _ => {
+                        Input::Text(TextInput::new(input_width).with_password(field.is_password()))
+                    }
+                }
+            })
             .collect();
 
         // Set first input as focused
-        if !inputs.is_empty() {
-            inputs[0].set_focus(true);
-        }
-        let mut focused_index = 0usize;
+        let mut focus = FocusManager::new();
+        focus.focus(
+            Some(0),
+            &mut focus_order(&mut inputs, &mut ok_button, &mut cancel_button),
+        );
 
         // Layout calculation
         let mut y = padding as i32;
-        let prompt_y = y;
-        if prompt_height > 0 {
-            y += prompt_height as i32 + (16.0 * scale) as i32;
+        prompt.set_position(padding as i32, y);
+        if prompt.height() > 0 {
+            y += prompt.height() as i32 + (16.0 * scale) as i32;
         }
 
         // Position inputs
         let label_x = padding as i32;
         let input_x = padding as i32 + label_width as i32 + (10.0 * scale) as i32;
-        let mut field_positions: Vec<i32> = Vec::new();
+        let mut labels: Vec<Label> = Vec::with_capacity(inputs.len());
 
-        for (i, input) in inputs.iter_mut().enumerate() {
+        for (i, (field, input)) in self.fields.iter().zip(inputs.iter_mut()).enumerate() {
             let field_y = y + (i as u32 * (field_height + field_spacing)) as i32;
-            field_positions.push(field_y);
             input.set_position(input_x, field_y);
+
+            // Label vertically centered with its input, wrapped if needed
+            let mut label = Label::new(field.label()).with_wrap(true);
+            label.layout(&font, colors.text, Some(label_width));
+            label.set_position(
+                label_x,
+                field_y + (field_height as i32 - label.height() as i32) / 2,
+            );
+            labels.push(label);
         }
 
         // Button positions (right-aligned)
-        let button_y = physical_height as i32 - padding as i32 - (32.0 * scale) as i32;
+        let button_y =
+            physical_height as i32 - padding as i32 - (colors.control_height as f32 * scale) as i32;
         let mut button_x = physical_width as i32 - padding as i32;
         button_x -= cancel_button.width() as i32;
         cancel_button.set_position(button_x, button_y);
@@ -260,52 +325,32 @@ impl FormsBuilder {
 
         // Draw function
         let draw = |canvas: &mut Canvas,
-                    colors: &Colors,
+                    colors: &Theme,
                     font: &Font,
-                    prompt_canvas: &Option<Canvas>,
-                    fields: &[FormField],
-                    inputs: &[TextInput],
+                    prompt: &Label,
+                    labels: &[Label],
+                    inputs: &[Input],
                     ok_button: &Button,
                     cancel_button: &Button,
-                    // Layout params
-                    padding: u32,
-                    label_x: i32,
-                    field_positions: &[i32],
-                    field_height: u32,
-                    prompt_y: i32,
                     scale: f32| {
             let width = canvas.width() as f32;
             let height = canvas.height() as f32;
-            let radius = 8.0 * scale;
 
             canvas.fill_dialog_bg(
                 width,
                 height,
                 colors.window_bg,
                 colors.window_border,
-                colors.window_shadow,
-                radius,
+                colors.window_radius * scale,
+                colors.border_width,
             );
 
             // Draw prompt
-            if let Some(prompt) = prompt_canvas {
-                canvas.draw_canvas(prompt, padding as i32, prompt_y);
-            }
+            prompt.draw(canvas, colors);
 
             // Draw fields
-            for (i, (field, input)) in fields.iter().zip(inputs.iter()).enumerate() {
-                let field_y = field_positions[i];
-
-                // Draw label (vertically centered with input, wrapped if needed)
-                let label_canvas = font
-                    .render(field.label())
-                    .with_color(colors.text)
-                    .with_max_width(label_width as f32)
-                    .finish();
-                let label_y = field_y + (field_height as i32 - label_canvas.height() as i32) / 2;
-                canvas.draw_canvas(&label_canvas, label_x, label_y);
-
-                // Draw input
+            for (label, input) in labels.iter().zip(inputs.iter()) {
+                label.draw(canvas, colors);
                 input.draw_to(canvas, colors, font);
             }
 
@@ -319,16 +364,11 @@ impl FormsBuilder {
             &mut canvas,
             colors,
             &font,
-            &prompt_canvas,
-            &self.fields,
+            &prompt,
+            &labels,
             &inputs,
             &ok_button,
             &cancel_button,
-            padding,
-            label_x,
-            &field_positions,
-            field_height,
-            prompt_y,
             scale,
         );
         window.set_contents(&canvas)?;
@@ -336,7 +376,10 @@ impl FormsBuilder {
 
         // Event loop
         loop {
-            let event = window.wait_for_event()?;
+            let event = focus.process_event(
+                window.wait_for_event()?,
+                &mut focus_order(&mut inputs, &mut ok_button, &mut cancel_button),
+            );
             let mut needs_redraw = false;
 
             match &event {
@@ -369,60 +412,35 @@ impl FormsBuilder {
                         CursorShape::Default
                     });
                 }
-                WindowEvent::ButtonPress(crate::backend::MouseButton::Left, _) => {
-                    // Check if clicking on any input field
-                    for (i, input) in inputs.iter().enumerate() {
-                        let ix = input.x();
-                        let iy = input.y();
-                        let iw = input.width();
-                        let ih = input.height();
-
-                        if cursor_x >= ix
-                            && cursor_x < ix + iw as i32
-                            && cursor_y >= iy
-                            && cursor_y < iy + ih as i32
-                        {
-                            if i != focused_index {
-                                inputs[focused_index].set_focus(false);
-                                focused_index = i;
-                                inputs[focused_index].set_focus(true);
-                                needs_redraw = true;
-                            }
-                            break;
-                        }
+                WindowEvent::ButtonPress(..) => needs_redraw = true,
+                WindowEvent::PrimaryPaste(..) => {
+                    // Pasting into a field focuses it
+                    if let Some(i) = inputs.iter().position(|input| {
+                        point_in_rect(
+                            cursor_x,
+                            cursor_y,
+                            input.x(),
+                            input.y(),
+                            input.width(),
+                            input.height(),
+                        )
+                    }) && focus.focused() != Some(i)
+                    {
+                        focus.focus(
+                            Some(i),
+                            &mut focus_order(&mut inputs, &mut ok_button, &mut cancel_button),
+                        );
+                        needs_redraw = true;
                     }
                 }
                 WindowEvent::KeyPress(key_event) => {
-                    const KEY_TAB: u32 = 0xff09;
                     const KEY_RETURN: u32 = 0xff0d;
                     const KEY_ESCAPE: u32 = 0xff1b;
-                    const KEY_ISO_LEFT_TAB: u32 = 0xfe20; // Shift+Tab
 
                     match key_event.keysym {
-                        KEY_TAB => {
-                            // Move to next field
-                            inputs[focused_index].set_focus(false);
-                            focused_index = (focused_index + 1) % inputs.len();
-                            inputs[focused_index].set_focus(true);
-                            needs_redraw = true;
-                        }
-                        KEY_ISO_LEFT_TAB => {
-                            // Move to previous field (Shift+Tab)
-                            inputs[focused_index].set_focus(false);
-                            focused_index = if focused_index == 0 {
-                                inputs.len() - 1
-                            } else {
-                                focused_index - 1
-                            };
-                            inputs[focused_index].set_focus(true);
-                            needs_redraw = true;
-                        }
                         KEY_RETURN => {
                             // Submit form
-                            let values: Vec<String> = inputs
-                                .iter()
-                                .map(|input| input.text().to_string())
-                                .collect();
+                            let values: Vec<String> = inputs.iter().map(Input::value).collect();
                             return Ok(FormsResult::Values(values));
                         }
                         KEY_ESCAPE => {
@@ -434,17 +452,16 @@ impl FormsBuilder {
                 _ => {}
             }
 
-            // Process input events for focused field
-            if inputs[focused_index].process_event(&event) {
-                needs_redraw = true;
+            // Process input events; only the focused field takes keys
+            let mut submitted = false;
+            for input in &mut inputs {
+                needs_redraw |= input.process_event(&event);
+                submitted |= input.was_submitted();
             }
 
             // Check for submission via input
-            if inputs[focused_index].was_submitted() {
-                let values: Vec<String> = inputs
-                    .iter()
-                    .map(|input| input.text().to_string())
-                    .collect();
+            if submitted {
+                let values: Vec<String> = inputs.iter().map(Input::value).collect();
                 return Ok(FormsResult::Values(values));
             }
 
@@ -453,10 +470,7 @@ impl FormsBuilder {
             needs_redraw |= cancel_button.process_event(&event);
 
             if ok_button.was_clicked() {
-                let values: Vec<String> = inputs
-                    .iter()
-                    .map(|input| input.text().to_string())
-                    .collect();
+                let values: Vec<String> = inputs.iter().map(Input::value).collect();
                 return Ok(FormsResult::Values(values));
             }
             if cancel_button.was_clicked() {
@@ -465,17 +479,21 @@ impl FormsBuilder {
 
             // Batch process pending events
             while let Some(ev) = window.poll_for_event()? {
+                let ev = focus.process_event(
+                    ev,
+                    &mut focus_order(&mut inputs, &mut ok_button, &mut cancel_button),
+                );
                 match &ev {
                     WindowEvent::CloseRequested => return Ok(FormsResult::Closed),
                     _ => {
-                        if inputs[focused_index].process_event(&ev) {
-                            needs_redraw = true;
+                        needs_redraw |= matches!(ev, WindowEvent::RedrawRequested);
+                        let mut submitted = false;
+                        for input in &mut inputs {
+                            needs_redraw |= input.process_event(&ev);
+                            submitted |= input.was_submitted();
                         }
-                        if inputs[focused_index].was_submitted() {
-                            let values: Vec<String> = inputs
-                                .iter()
-                                .map(|input| input.text().to_string())
-                                .collect();
+                        if submitted {
+                            let values: Vec<String> = inputs.iter().map(Input::value).collect();
                             return Ok(FormsResult::Values(values));
                         }
                         needs_redraw |= ok_button.process_event(&ev);
@@ -489,16 +507,11 @@ impl FormsBuilder {
                     &mut canvas,
                     colors,
                     &font,
-                    &prompt_canvas,
-                    &self.fields,
+                    &prompt,
+                    &labels,
                     &inputs,
                     &ok_button,
                     &cancel_button,
-                    padding,
-                    label_x,
-                    &field_positions,
-                    field_height,
-                    prompt_y,
                     scale,
                 );
                 window.set_contents(&canvas)?;
@@ -507,6 +520,102 @@ impl FormsBuilder {
     }
 }
 
+/// A field's input.
+enum Input {
+    Text(TextInput),
+    Spin(SpinButton),
+}
+
+impl Input {
+    /// The field's value as printed.
+    fn value(&self) -> String {
+        match self {
+            Input::Text(input) => input.text().to_string(),
+            Input::Spin(spin) => spin.value().to_string(),
+        }
+    }
+
+    /// Returns true if Enter was pressed in a text field.
+    fn was_submitted(&mut self) -> bool {
+        match self {
+            Input::Text(input) => input.was_submitted(),
+            Input::Spin(_) => false,
+        }
+    }
+
+    fn draw_to(&self, canvas: &mut Canvas, colors: &Theme, font: &Font) {
+        match self {
+            Input::Text(input) => input.draw_to(canvas, colors, font),
+            Input::Spin(spin) => spin.draw_to(canvas, colors, font),
+        }
+    }
+
+    fn widget(&self) -> &dyn Widget {
+        match self {
+            Input::Text(input) => input,
+            Input::Spin(spin) => spin,
+        }
+    }
+
+    fn widget_mut(&mut self) -> &mut dyn Widget {
+        match self {
+            Input::Text(input) => input,
+            Input::Spin(spin) => spin,
+        }
+    }
+}
+
+impl Widget for Input {
+    fn width(&self) -> u32 {
+        self.widget().width()
+    }
+
+    fn height(&self) -> u32 {
+        self.widget().height()
+    }
+
+    fn x(&self) -> i32 {
+        self.widget().x()
+    }
+
+    fn y(&self) -> i32 {
+        self.widget().y()
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.widget_mut().set_position(x, y);
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        self.widget_mut().process_event(event)
+    }
+
+    fn draw(&self, _canvas: &mut Canvas, _colors: &Theme) {
+        // Use draw_to instead for font access
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focus(&mut self, focused: bool) {
+        self.widget_mut().set_focus(focused);
+    }
+}
+
+/// The dialog's widgets in Tab order: the fields, then the buttons.
+fn focus_order<'a>(
+    inputs: &'a mut [Input],
+    ok_button: &'a mut Button,
+    cancel_button: &'a mut Button,
+) -> Vec<&'a mut dyn Widget> {
+    inputs
+        .iter_mut()
+        .map(|input| input as &mut dyn Widget)
+        .chain([ok_button as &mut dyn Widget, cancel_button])
+        .collect()
+}
+
 impl Default for FormsBuilder {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,161 @@
+//! Dialogs shown on a thread of their own, followed through a handle.
+
+use std::{
+    cell::RefCell,
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    backend::WindowEvent,
+    error::Error,
+    ui::{DialogResult, progress::ProgressResult},
+};
+
+/// How long a spawned dialog waits for events before checking its handle
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// What a handle asks of its dialog, passed on by the dialog's window.
+#[derive(Default)]
+pub(crate) struct Remote {
+    closed: AtomicBool,
+    text: Mutex<Option<String>>,
+}
+
+impl Remote {
+    /// Returns the event for what the handle asked since the last call:
+    /// `CloseRequested` from then on once closed, or the new text.
+    pub fn take_event(&self) -> Option<WindowEvent> {
+        if self.is_closed() {
+            return Some(WindowEvent::CloseRequested);
+        }
+        self.text
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+            .map(WindowEvent::SetText)
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+    }
+
+    fn set_text(&self, text: &str) {
+        *self.text.lock().unwrap_or_else(PoisonError::into_inner) = Some(text.to_string());
+    }
+}
+
+thread_local! {
+    static REMOTE: RefCell<Option<Arc<Remote>>> = const { RefCell::new(None) };
+}
+
+/// Returns the remote of the dialogs shown on this thread, if they have one.
+pub(crate) fn remote() -> Option<Arc<Remote>> {
+    REMOTE.with_borrow(Clone::clone)
+}
+
+/// Runs `f` with `remote` as the remote of the dialogs it shows on this
+/// thread, putting back the one before afterwards.
+pub(crate) fn with_remote<R>(remote: Arc<Remote>, f: impl FnOnce() -> R) -> R {
+    let previous = REMOTE.replace(Some(remote));
+    let result = f();
+    REMOTE.set(previous);
+    result
+}
+
+/// A dialog shown on a thread of its own by a builder's `spawn`, so the
+/// application can carry on and dismiss it when it is done with it.
+///
+/// ```no_run
+/// let handle = zenity_rs::info("Indexing files...").spawn();
+/// // ... the work the message is about ...
+/// handle.close();
+/// handle.wait()?;
+/// # Ok::<(), zenity_rs::Error>(())
+/// ```
+///
+/// Dropping the handle leaves the dialog shown. Dialogs drawn in the
+/// terminal, and file dialogs shown by the desktop portal, can't be closed
+/// or changed through it.
+pub struct DialogHandle<T> {
+    thread: Option<JoinHandle<Result<T, Error>>>,
+    remote: Arc<Remote>,
+}
+
+impl<T: Send + 'static> DialogHandle<T> {
+    pub(crate) fn spawn(show: impl FnOnce() -> Result<T, Error> + Send + 'static) -> Self {
+        let remote = Arc::new(Remote::default());
+        let thread = {
+            let remote = remote.clone();
+            thread::spawn(move || {
+                REMOTE.set(Some(remote));
+                show()
+            })
+        };
+        Self {
+            thread: Some(thread),
+            remote,
+        }
+    }
+}
+
+impl<T> DialogHandle<T> {
+    /// Returns the dialog's result if it has been dismissed, without
+    /// waiting. `None` while it is shown, and after the result was
+    /// returned once.
+    pub fn try_result(&mut self) -> Option<Result<T, Error>> {
+        if !self.thread.as_ref()?.is_finished() {
+            return None;
+        }
+        self.thread.take().map(join)
+    }
+
+    /// Waits for the dialog to be dismissed and returns its result.
+    ///
+    /// # Panics
+    ///
+    /// If [`try_result`](Self::try_result) already returned it.
+    pub fn wait(mut self) -> Result<T, Error> {
+        join(
+            self.thread
+                .take()
+                .expect("dialog result was already taken by try_result"),
+        )
+    }
+
+    /// Closes the dialog as if its window was closed, so it reports being
+    /// closed unless it was dismissed first.
+    pub fn close(&self) {
+        self.remote.close();
+    }
+}
+
+impl DialogHandle<DialogResult> {
+    /// Replaces the message's text. It is fitted in the space the first
+    /// text was given.
+    pub fn set_text(&self, text: &str) {
+        self.remote.set_text(text);
+    }
+}
+
+impl DialogHandle<ProgressResult> {
+    /// Replaces the status text above the bar.
+    pub fn set_text(&self, text: &str) {
+        self.remote.set_text(text);
+    }
+}
+
+/// Waits for a dialog's thread, passing on a panic in it to the caller.
+fn join<T>(thread: JoinHandle<Result<T, Error>>) -> Result<T, Error> {
+    thread
+        .join()
+        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+}
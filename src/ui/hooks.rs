@@ -0,0 +1,44 @@
+//! Closures a builder calls back as its dialog is used.
+
+/// Called with what changed
+type OnChange<T> = Box<dyn FnMut(&T) + Send>;
+
+/// The `on_change`, `on_button` and `on_cancel` closures given to a
+/// builder. `T` is what `on_change` is handed.
+pub(crate) struct Hooks<T: ?Sized = ()> {
+    pub change: Option<OnChange<T>>,
+    pub button: Option<Box<dyn FnMut(usize) + Send>>,
+    pub cancel: Option<Box<dyn FnMut() + Send>>,
+}
+
+impl<T: ?Sized> Hooks<T> {
+    /// Passes on a change the user made in the dialog.
+    pub fn changed(&mut self, value: &T) {
+        if let Some(change) = &mut self.change {
+            change(value);
+        }
+    }
+
+    /// Passes on how the dialog was dismissed: the button it was
+    /// dismissed with, if any, and whether that cancelled it.
+    pub fn dismissed(&mut self, button: Option<usize>, cancelled: bool) {
+        if let (Some(on_button), Some(idx)) = (&mut self.button, button) {
+            on_button(idx);
+        }
+        if let Some(cancel) = &mut self.cancel
+            && cancelled
+        {
+            cancel();
+        }
+    }
+}
+
+impl<T: ?Sized> Default for Hooks<T> {
+    fn default() -> Self {
+        Self {
+            change: None,
+            button: None,
+            cancel: None,
+        }
+    }
+}
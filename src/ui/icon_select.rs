@@ -0,0 +1,642 @@
+//! Icon picker dialog showing the icons of the current icon theme.
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    backend::{CursorShape, MouseButton, ScrollDirection, Window, WindowEvent},
+    error::Error,
+    render::{Canvas, Font, Rgba, rgb},
+    ui::{
+        Theme, WindowPlacement, create_window,
+        icon_theme::{self, ThemeIcon},
+        strings::tr,
+        thumbnails::Thumbnails,
+        widgets::{
+            Widget,
+            button::Button,
+            focus::FocusManager,
+            label::{Ellipsize, Label},
+            point_in_rect,
+            text_input::TextInput,
+        },
+    },
+};
+
+const BASE_WINDOW_WIDTH: u32 = 640;
+const BASE_WINDOW_HEIGHT: u32 = 520;
+const BASE_PADDING: u32 = 16;
+const BASE_GAP: u32 = 10;
+const BASE_CELL_WIDTH: u32 = 100;
+const BASE_CELL_HEIGHT: u32 = 84;
+const BASE_ICON_SIZE: u32 = 48;
+const BASE_LABEL_SIZE: f32 = 13.0;
+const ICON_CACHE_SIZE: usize = 512;
+
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+const KEY_ESCAPE: u32 = 0xff1b;
+const KEY_RETURN: u32 = 0xff0d;
+const KEY_KP_ENTER: u32 = 0xff8d;
+const KEY_LEFT: u32 = 0xff51;
+const KEY_UP: u32 = 0xff52;
+const KEY_RIGHT: u32 = 0xff53;
+const KEY_DOWN: u32 = 0xff54;
+const KEY_PAGE_UP: u32 = 0xff55;
+const KEY_PAGE_DOWN: u32 = 0xff56;
+
+/// Icon picker dialog result.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IconSelectResult {
+    /// User picked an icon; contains its theme name (e.g. "folder-music").
+    Selected(String),
+    /// User cancelled.
+    Cancelled,
+    /// Dialog was closed.
+    Closed,
+}
+
+impl IconSelectResult {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            IconSelectResult::Selected(_) => 0,
+            IconSelectResult::Cancelled => 1,
+            IconSelectResult::Closed => 255,
+        }
+    }
+}
+
+/// Icon picker dialog builder.
+pub struct IconSelectBuilder {
+    title: String,
+    text: String,
+    icon_name: String,
+    width: Option<u32>,
+    height: Option<u32>,
+    colors: Option<Theme>,
+    pub(super) placing: WindowPlacement,
+}
+
+impl IconSelectBuilder {
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            text: String::new(),
+            icon_name: String::new(),
+            width: None,
+            height: None,
+            colors: None,
+            placing: WindowPlacement::default(),
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    /// Initially selected icon name.
+    pub fn icon_name(mut self, icon_name: &str) -> Self {
+        self.icon_name = icon_name.to_string();
+        self
+    }
+
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
+        self
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn show(self) -> Result<IconSelectResult, Error> {
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
+
+        let logical_width = self.width.unwrap_or(BASE_WINDOW_WIDTH);
+        let logical_height = self.height.unwrap_or(BASE_WINDOW_HEIGHT);
+
+        let mut window = create_window(
+            logical_width as u16,
+            logical_height as u16,
+            colors,
+            &self.placing,
+        )?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Select Icon")
+        } else {
+            self.title.as_str().into()
+        })?;
+
+        // Get the actual scale factor from the window (compositor scale)
+        let scale = window.scale_factor();
+        let physical_width = (logical_width as f32 * scale) as u32;
+        let physical_height = (logical_height as f32 * scale) as u32;
+
+        // Now create everything at PHYSICAL scale
+        let font = colors.font(scale);
+        let label_font = Font::load_with_size(BASE_LABEL_SIZE * scale);
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
+        let gap = (colors.space(BASE_GAP) as f32 * scale) as u32;
+        let icon_size = (BASE_ICON_SIZE as f32 * scale) as u32;
+        let content_w = physical_width - padding * 2;
+
+        let icons = icon_theme::list_icons(icon_size);
+        let mut icon_images = Thumbnails::new(icon_size, icon_size, ICON_CACHE_SIZE);
+
+        // Layout in physical coordinates, top to bottom
+        let mut y = padding as i32;
+        let mut prompt = Label::new(&self.text).with_wrap(true);
+        prompt.layout(&font, colors.text, Some(content_w));
+        prompt.set_position(padding as i32, y);
+        if !self.text.is_empty() {
+            y += prompt.height() as i32 + gap as i32;
+        }
+
+        let mut search_input = TextInput::new(content_w).with_placeholder(&tr("Search icons..."));
+        search_input.set_position(padding as i32, y);
+        y += search_input.height() as i32 + gap as i32;
+
+        let mut ok_button = Button::new(&tr("OK"), &font, colors, scale);
+        let mut cancel_button = Button::new(&tr("Cancel"), &font, colors, scale);
+        let button_y = (physical_height - padding) as i32 - ok_button.height() as i32;
+        let mut bx = physical_width as i32 - padding as i32;
+        bx -= cancel_button.width() as i32;
+        cancel_button.set_position(bx, button_y);
+        bx -= gap as i32 + ok_button.width() as i32;
+        ok_button.set_position(bx, button_y);
+        ok_button.set_default(true);
+
+        // Typing goes to the search from the start
+        let mut focus = FocusManager::new();
+        focus.focus(
+            Some(0),
+            &mut [&mut search_input, &mut ok_button, &mut cancel_button],
+        );
+
+        let mut grid = Grid {
+            x: padding as i32,
+            y,
+            w: content_w,
+            h: (button_y - gap as i32 - y).max(1) as u32,
+            cell_w: (BASE_CELL_WIDTH as f32 * scale) as u32,
+            cell_h: (BASE_CELL_HEIGHT as f32 * scale) as u32,
+            offset: 0,
+        };
+
+        // Icons matching the search, as indices into `icons`
+        let mut shown: Vec<usize> = (0..icons.len()).collect();
+        let mut selected = icons.iter().position(|icon| icon.name == self.icon_name);
+        if let Some(sel) = selected {
+            grid.scroll_into_view(sel);
+        }
+        let mut hovered: Option<usize> = None;
+        let mut last_click: Option<(usize, Instant)> = None;
+
+        let mut canvas = Canvas::new(physical_width, physical_height);
+
+        let draw = |canvas: &mut Canvas,
+                    grid: &Grid,
+                    shown: &[usize],
+                    selected: Option<usize>,
+                    hovered: Option<usize>,
+                    icon_images: &mut Thumbnails,
+                    search_input: &TextInput,
+                    ok_button: &Button,
+                    cancel_button: &Button| {
+            let width = canvas.width() as f32;
+            let height = canvas.height() as f32;
+            canvas.fill_dialog_bg(
+                width,
+                height,
+                colors.window_bg,
+                colors.window_border,
+                colors.window_radius * scale,
+                colors.border_width,
+            );
+
+            prompt.draw(canvas, colors);
+            search_input.draw_to(canvas, colors, &font);
+
+            // Grid, clipped to its box
+            let radius = 4.0 * scale;
+            let mut area = Canvas::new(grid.w, grid.h);
+            area.fill_rounded_rect(
+                0.0,
+                0.0,
+                grid.w as f32,
+                grid.h as f32,
+                radius,
+                colors.input_bg,
+            );
+
+            if shown.is_empty() {
+                let text = if icons.is_empty() {
+                    tr("No icon theme found")
+                } else {
+                    tr("No matching icons")
+                };
+                let tc = font.render(&text).with_color(colors.input_border).finish();
+                area.draw_canvas(
+                    &tc,
+                    (grid.w as i32 - tc.width() as i32) / 2,
+                    (grid.h as i32 - tc.height() as i32) / 2,
+                );
+            }
+
+            let columns = grid.columns();
+            let first = grid.offset * columns;
+            let last = shown.len().min(first + (grid.visible_rows() + 1) * columns);
+            for (pos, &ii) in shown.iter().enumerate().take(last).skip(first) {
+                let (cx, cy) = grid.cell_origin(pos);
+                let is_selected = selected == Some(ii);
+                if is_selected || hovered == Some(ii) {
+                    area.fill_rounded_rect(
+                        cx as f32 + 2.0 * scale,
+                        cy as f32 + 2.0 * scale,
+                        grid.cell_w as f32 - 4.0 * scale,
+                        grid.cell_h as f32 - 4.0 * scale,
+                        radius,
+                        if is_selected {
+                            colors.input_border_focused
+                        } else {
+                            darken(colors.input_bg, 0.06)
+                        },
+                    );
+                }
+
+                // Icon, centered above its name
+                let icon = &icons[ii];
+                let ix = cx + (grid.cell_w - icon_size) as i32 / 2;
+                let iy = cy + (6.0 * scale) as i32;
+                match icon_images.get(&icon.path) {
+                    Some(image) => {
+                        let image = &image.large;
+                        area.draw_canvas(
+                            image,
+                            ix + (icon_size as i32 - image.width() as i32) / 2,
+                            iy + (icon_size as i32 - image.height() as i32) / 2,
+                        );
+                    }
+                    None => {
+                        if Thumbnails::is_image(&file_name(icon)) {
+                            icon_images.request(&icon.path);
+                        }
+                        draw_placeholder(&mut area, ix, iy, icon_size, colors, scale);
+                    }
+                }
+
+                let color = if is_selected {
+                    colors.selection_text
+                } else {
+                    colors.text
+                };
+                let mut label = Label::new(&icon.name).with_ellipsize(Ellipsize::End);
+                label.layout(&label_font, color, Some(grid.cell_w - (8.0 * scale) as u32));
+                label.set_position(
+                    cx + (grid.cell_w as i32 - label.width() as i32) / 2,
+                    iy + icon_size as i32 + (6.0 * scale) as i32,
+                );
+                label.draw(&mut area, colors);
+            }
+
+            // Scrollbar thumb
+            let max = grid.max_scroll(shown.len());
+            if max > 0 {
+                let total = grid.rows(shown.len()) as f32;
+                let thumb_h =
+                    (grid.visible_rows() as f32 / total * grid.h as f32).max(20.0 * scale);
+                let thumb_y = grid.offset as f32 / max as f32 * (grid.h as f32 - thumb_h);
+                area.fill_rounded_rect(
+                    grid.w as f32 - 8.0 * scale,
+                    thumb_y,
+                    6.0 * scale,
+                    thumb_h,
+                    3.0 * scale,
+                    colors.input_border,
+                );
+            }
+
+            canvas.draw_canvas(&area, grid.x, grid.y);
+            canvas.stroke_rounded_rect(
+                grid.x as f32,
+                grid.y as f32,
+                grid.w as f32,
+                grid.h as f32,
+                radius,
+                colors.input_border,
+                1.0,
+            );
+
+            // Name of the selected icon, left of the buttons
+            if let Some(sel) = selected {
+                let name = font
+                    .render(&icons[sel].name)
+                    .with_color(colors.text)
+                    .finish();
+                let ny = ok_button.y() + (ok_button.height() as i32 - name.height() as i32) / 2;
+                canvas.draw_canvas(&name, padding as i32, ny);
+            }
+
+            ok_button.draw_to(canvas, colors, &font);
+            cancel_button.draw_to(canvas, colors, &font);
+        };
+
+        draw(
+            &mut canvas,
+            &grid,
+            &shown,
+            selected,
+            hovered,
+            &mut icon_images,
+            &search_input,
+            &ok_button,
+            &cancel_button,
+        );
+        window.set_contents(&canvas)?;
+        window.show()?;
+
+        let mut mouse_x = 0i32;
+        let mut mouse_y = 0i32;
+
+        // Event loop; each pass handles every pending event, then redraws once
+        loop {
+            // Poll while icons are decoding so they appear as they arrive
+            let first = if icon_images.is_busy() {
+                match window.poll_for_event()? {
+                    Some(event) => event,
+                    None => {
+                        std::thread::sleep(Duration::from_millis(16));
+                        if !icon_images.receive() {
+                            continue;
+                        }
+                        WindowEvent::RedrawRequested
+                    }
+                }
+            } else {
+                window.wait_for_event()?
+            };
+            let mut next = Some(first);
+            let mut needs_redraw = false;
+            let mut accept = false;
+
+            while let Some(event) = next.take() {
+                let event = focus.process_event(
+                    event,
+                    &mut [&mut search_input, &mut ok_button, &mut cancel_button],
+                );
+                let old_search = search_input.text().to_string();
+
+                match &event {
+                    WindowEvent::CloseRequested => return Ok(IconSelectResult::Closed),
+                    WindowEvent::RedrawRequested => needs_redraw = true,
+                    WindowEvent::KeyPress(key) => {
+                        match key.keysym {
+                            KEY_ESCAPE => return Ok(IconSelectResult::Cancelled),
+                            KEY_RETURN | KEY_KP_ENTER => accept = true,
+                            KEY_LEFT | KEY_RIGHT | KEY_UP | KEY_DOWN | KEY_PAGE_UP
+                            | KEY_PAGE_DOWN
+                                if !shown.is_empty() =>
+                            {
+                                // Left/Right belong to the search text unless it's empty
+                                let in_text = matches!(key.keysym, KEY_LEFT | KEY_RIGHT)
+                                    && !search_input.text().is_empty();
+                                if !in_text {
+                                    let columns = grid.columns() as isize;
+                                    let page = grid.visible_rows() as isize * columns;
+                                    let delta = match key.keysym {
+                                        KEY_LEFT => -1,
+                                        KEY_RIGHT => 1,
+                                        KEY_UP => -columns,
+                                        KEY_DOWN => columns,
+                                        KEY_PAGE_UP => -page,
+                                        _ => page,
+                                    };
+                                    let pos = selected
+                                        .and_then(|sel| shown.iter().position(|&i| i == sel))
+                                        .map_or(0, |pos| {
+                                            pos.saturating_add_signed(delta).min(shown.len() - 1)
+                                        });
+                                    selected = Some(shown[pos]);
+                                    grid.scroll_into_view(pos);
+                                    needs_redraw = true;
+                                }
+                            }
+                            _ => {}
+                        }
+                    }
+                    WindowEvent::CursorMove(pos) => {
+                        mouse_x = pos.x as i32;
+                        mouse_y = pos.y as i32;
+                        let now = grid
+                            .position_at(mouse_x, mouse_y, shown.len())
+                            .map(|pos| shown[pos]);
+                        if now != hovered {
+                            hovered = now;
+                            needs_redraw = true;
+                        }
+                        let over_input = point_in_rect(
+                            mouse_x,
+                            mouse_y,
+                            search_input.x(),
+                            search_input.y(),
+                            search_input.width(),
+                            search_input.height(),
+                        );
+                        let _ = window.set_cursor(if over_input {
+                            CursorShape::Text
+                        } else {
+                            CursorShape::Default
+                        });
+                    }
+                    WindowEvent::CursorLeave => {
+                        needs_redraw |= hovered.take().is_some();
+                    }
+                    WindowEvent::ButtonPress(MouseButton::Left, _) => {
+                        if let Some(pos) = grid.position_at(mouse_x, mouse_y, shown.len()) {
+                            let ii = shown[pos];
+                            let double_click = last_click.is_some_and(|(prev, time)| {
+                                prev == ii && time.elapsed() <= DOUBLE_CLICK_TIME
+                            });
+                            last_click = (!double_click).then(|| (ii, Instant::now()));
+                            selected = Some(ii);
+                            accept |= double_click;
+                            needs_redraw = true;
+                        }
+                    }
+                    WindowEvent::Scroll(direction) if grid.contains(mouse_x, mouse_y) => {
+                        let max = grid.max_scroll(shown.len());
+                        grid.offset = match direction {
+                            ScrollDirection::Up => grid.offset.saturating_sub(1),
+                            ScrollDirection::Down => (grid.offset + 1).min(max),
+                            _ => grid.offset,
+                        };
+                        needs_redraw = true;
+                    }
+                    _ => {}
+                }
+
+                needs_redraw |= search_input.process_event(&event);
+                needs_redraw |= ok_button.process_event(&event);
+                needs_redraw |= cancel_button.process_event(&event);
+                // Enter is handled above
+                search_input.was_submitted();
+
+                if search_input.text() != old_search {
+                    let query = search_input.text().to_lowercase();
+                    shown = (0..icons.len())
+                        .filter(|&i| icons[i].name.to_lowercase().contains(&query))
+                        .collect();
+                    grid.offset = 0;
+                    hovered = None;
+                    needs_redraw = true;
+                }
+
+                if cancel_button.was_clicked() {
+                    return Ok(IconSelectResult::Cancelled);
+                }
+                accept |= ok_button.was_clicked();
+
+                next = window.poll_for_event()?;
+            }
+
+            if accept && let Some(sel) = selected {
+                return Ok(IconSelectResult::Selected(icons[sel].name.clone()));
+            }
+
+            if needs_redraw {
+                icon_images.receive();
+                draw(
+                    &mut canvas,
+                    &grid,
+                    &shown,
+                    selected,
+                    hovered,
+                    &mut icon_images,
+                    &search_input,
+                    &ok_button,
+                    &cancel_button,
+                );
+                window.set_contents(&canvas)?;
+            }
+        }
+    }
+}
+
+impl Default for IconSelectBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Placement of the icon cells; `offset` is the first visible row.
+struct Grid {
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32,
+    cell_w: u32,
+    cell_h: u32,
+    offset: usize,
+}
+
+impl Grid {
+    fn columns(&self) -> usize {
+        (self.w / self.cell_w).max(1) as usize
+    }
+
+    fn visible_rows(&self) -> usize {
+        (self.h / self.cell_h).max(1) as usize
+    }
+
+    fn rows(&self, count: usize) -> usize {
+        count.div_ceil(self.columns())
+    }
+
+    fn max_scroll(&self, count: usize) -> usize {
+        self.rows(count).saturating_sub(self.visible_rows())
+    }
+
+    /// Top-left corner of the cell at list position `pos`, relative to the grid.
+    fn cell_origin(&self, pos: usize) -> (i32, i32) {
+        let columns = self.columns();
+        // Spread spare width evenly so the grid is centered
+        let margin = (self.w - columns as u32 * self.cell_w) / 2;
+        let col = (pos % columns) as u32;
+        let row = (pos / columns) as i32 - self.offset as i32;
+        (
+            (margin + col * self.cell_w) as i32,
+            row * self.cell_h as i32,
+        )
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        point_in_rect(x, y, self.x, self.y, self.w, self.h)
+    }
+
+    /// List position of the cell under a window point.
+    fn position_at(&self, x: i32, y: i32, count: usize) -> Option<usize> {
+        if !self.contains(x, y) {
+            return None;
+        }
+        let columns = self.columns();
+        let margin = (self.w - columns as u32 * self.cell_w) as i32 / 2;
+        let rel_x = x - self.x - margin;
+        if rel_x < 0 || rel_x >= (columns as u32 * self.cell_w) as i32 {
+            return None;
+        }
+        let col = rel_x as usize / self.cell_w as usize;
+        let row = self.offset + (y - self.y) as usize / self.cell_h as usize;
+        let pos = row * columns + col;
+        (pos < count).then_some(pos)
+    }
+
+    fn scroll_into_view(&mut self, pos: usize) {
+        let row = pos / self.columns();
+        if row < self.offset {
+            self.offset = row;
+        } else if row >= self.offset + self.visible_rows() {
+            self.offset = row + 1 - self.visible_rows();
+        }
+    }
+}
+
+fn file_name(icon: &ThemeIcon) -> String {
+    icon.path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Stand-in for icons we can't decode: an outlined rounded square.
+fn draw_placeholder(canvas: &mut Canvas, x: i32, y: i32, size: u32, colors: &Theme, scale: f32) {
+    let inset = size as f32 * 0.15;
+    canvas.stroke_rounded_rect(
+        x as f32 + inset,
+        y as f32 + inset,
+        size as f32 - inset * 2.0,
+        size as f32 - inset * 2.0,
+        4.0 * scale,
+        colors.input_border,
+        (1.5 * scale).max(1.0),
+    );
+}
+
+fn darken(color: Rgba, amount: f32) -> Rgba {
+    rgb(
+        (color.r as f32 * (1.0 - amount)) as u8,
+        (color.g as f32 * (1.0 - amount)) as u8,
+        (color.b as f32 * (1.0 - amount)) as u8,
+    )
+}
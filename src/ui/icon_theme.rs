@@ -0,0 +1,347 @@
+//! Freedesktop icon themes: finding the user's theme, the themes it inherits
+//! from, and the icons they provide.
+//!
+//! See the Icon Theme Specification for the directory layout and
+//! `index.theme` format.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::ui::thumbnails::Thumbnails;
+
+/// Icon file extensions, in the specification's order of preference.
+const ICON_EXTENSIONS: [&str; 3] = ["png", "svg", "xpm"];
+
+/// A theme named in an `index.theme`, with every base directory holding
+/// part of it.
+pub(crate) struct Theme {
+    pub name: String,
+    roots: Vec<PathBuf>,
+    inherits: Vec<String>,
+    dirs: Vec<ThemeDir>,
+}
+
+/// A subdirectory of a theme, e.g. `48x48/apps`.
+struct ThemeDir {
+    path: String,
+    size: u32,
+    scale: u32,
+    kind: DirKind,
+}
+
+enum DirKind {
+    Fixed,
+    Scalable { min: u32, max: u32 },
+    Threshold(u32),
+}
+
+/// An icon name and the file chosen to display it.
+pub(crate) struct ThemeIcon {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl Theme {
+    /// Reads a theme's `index.theme` from the first base directory that has
+    /// one. Returns `None` for unknown themes.
+    pub fn load(name: &str) -> Option<Theme> {
+        let roots: Vec<PathBuf> = base_dirs()
+            .into_iter()
+            .map(|base| base.join(name))
+            .filter(|root| root.is_dir())
+            .collect();
+        let index = roots
+            .iter()
+            .find_map(|root| fs::read_to_string(root.join("index.theme")).ok())?;
+
+        let sections = parse_ini(&index);
+        let main = sections.get("Icon Theme")?;
+        let inherits = main
+            .get("Inherits")
+            .map(|list| list.split(',').map(|s| s.trim().to_string()).collect())
+            .unwrap_or_default();
+
+        let dir_names = [main.get("Directories"), main.get("ScaledDirectories")];
+        let dirs = dir_names
+            .into_iter()
+            .flatten()
+            .flat_map(|list| list.split(','))
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .filter_map(|path| {
+                let keys = sections.get(path)?;
+                let number = |key: &str| keys.get(key).and_then(|v| v.parse::<u32>().ok());
+                let size = number("Size")?;
+                let kind = match keys.get("Type").map(String::as_str) {
+                    Some("Fixed") => DirKind::Fixed,
+                    Some("Scalable") => {
+                        DirKind::Scalable {
+                            min: number("MinSize").unwrap_or(size),
+                            max: number("MaxSize").unwrap_or(size),
+                        }
+                    }
+                    _ => DirKind::Threshold(number("Threshold").unwrap_or(2)),
+                };
+                Some(ThemeDir {
+                    path: path.to_string(),
+                    size,
+                    scale: number("Scale").unwrap_or(1),
+                    kind,
+                })
+            })
+            .collect();
+
+        Some(Theme {
+            name: name.to_string(),
+            roots,
+            inherits,
+            dirs,
+        })
+    }
+
+    /// Finds `name` in this theme alone: in a directory made for `size` at
+    /// `scale` if there is one, otherwise in the closest. Files we can
+    /// decode win over closer ones we can't.
+    fn lookup(&self, name: &str, size: u32, scale: u32) -> Option<PathBuf> {
+        let mut best: Option<(PathBuf, (bool, u32, bool))> = None;
+        for dir in &self.dirs {
+            let distance = dir.distance(size, scale);
+            for root in &self.roots {
+                let Some(path) = find_file(&root.join(&dir.path), name) else {
+                    continue;
+                };
+                let rank = (!is_decodable(&path), distance, dir.scale != scale);
+                if best.as_ref().is_none_or(|(_, best)| rank < *best) {
+                    best = Some((path, rank));
+                }
+            }
+        }
+        best.map(|(path, _)| path)
+    }
+}
+
+impl ThemeDir {
+    /// How far this directory's icons are from `size` at `scale`, per the
+    /// specification's DirectorySizeDistance.
+    fn distance(&self, size: u32, scale: u32) -> u32 {
+        let wanted = size * scale;
+        let (low, high) = match self.kind {
+            DirKind::Fixed => (self.size, self.size),
+            DirKind::Scalable {
+                min,
+                max,
+            } => (min, max),
+            DirKind::Threshold(threshold) => {
+                (self.size.saturating_sub(threshold), self.size + threshold)
+            }
+        };
+        if wanted < low * self.scale {
+            low * self.scale - wanted
+        } else {
+            wanted.saturating_sub(high * self.scale)
+        }
+    }
+}
+
+/// Directories that may hold icon themes, most important first.
+pub(crate) fn base_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join(".icons"));
+    }
+    if let Some(data) = dirs::data_dir() {
+        dirs.push(data.join("icons"));
+    }
+    let data_dirs = std::env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    for dir in data_dirs.split(':').filter(|d| !d.is_empty()) {
+        let dir = Path::new(dir).join("icons");
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Returns the user's icon theme name from GTK settings, falling back to
+/// `hicolor`.
+pub(crate) fn current_theme_name() -> String {
+    let from_settings = ["gtk-3.0", "gtk-4.0"].iter().find_map(|gtk| {
+        let ini = fs::read_to_string(dirs::config_dir()?.join(gtk).join("settings.ini")).ok()?;
+        let settings = parse_ini(&ini);
+        settings
+            .get("Settings")?
+            .get("gtk-icon-theme-name")
+            .cloned()
+    });
+    if let Some(name) = from_settings {
+        return name;
+    }
+
+    if let Ok(output) = std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "icon-theme"])
+        .output()
+    {
+        let name = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .trim_matches('\'')
+            .to_string();
+        if output.status.success() && !name.is_empty() {
+            return name;
+        }
+    }
+
+    "hicolor".to_string()
+}
+
+/// Loads the current theme followed by the themes it inherits from
+/// (depth first) and finally `hicolor`, skipping unknown themes.
+pub(crate) fn theme_chain() -> Vec<Theme> {
+    let mut chain: Vec<Theme> = Vec::new();
+    let mut pending = vec![current_theme_name()];
+    while let Some(name) = pending.pop() {
+        if chain.iter().any(|t| t.name == name) {
+            continue;
+        }
+        if let Some(theme) = Theme::load(&name) {
+            pending.extend(theme.inherits.iter().rev().cloned());
+            chain.push(theme);
+        }
+    }
+    if !chain.iter().any(|t| t.name == "hicolor")
+        && let Some(hicolor) = Theme::load("hicolor")
+    {
+        chain.push(hicolor);
+    }
+    chain
+}
+
+/// Lists every icon name in the current theme chain and `/usr/share/pixmaps`,
+/// sorted by name. Each name maps to the file closest to `size` pixels in the
+/// first theme that has it, preferring files we can decode.
+pub(crate) fn list_icons(size: u32) -> Vec<ThemeIcon> {
+    // Name -> (path, whether we can't decode it, size distance)
+    let mut found: HashMap<String, (PathBuf, bool, u32)> = HashMap::new();
+
+    for theme in theme_chain() {
+        let mut in_theme: HashMap<String, (PathBuf, bool, u32)> = HashMap::new();
+        for dir in &theme.dirs {
+            let distance = dir.distance(size, 1);
+            for root in &theme.roots {
+                for (name, path) in icon_files(&root.join(&dir.path)) {
+                    let undecodable = !is_decodable(&path);
+                    let better = in_theme
+                        .get(&name)
+                        .is_none_or(|&(_, u, d)| (undecodable, distance) < (u, d));
+                    if better {
+                        in_theme.insert(name, (path, undecodable, distance));
+                    }
+                }
+            }
+        }
+        // Earlier themes take precedence over the ones they inherit from
+        for (name, icon) in in_theme {
+            found.entry(name).or_insert(icon);
+        }
+    }
+
+    for (name, path) in icon_files(Path::new("/usr/share/pixmaps")) {
+        let undecodable = !is_decodable(&path);
+        found.entry(name).or_insert((path, undecodable, u32::MAX));
+    }
+
+    let mut icons: Vec<ThemeIcon> = found
+        .into_iter()
+        .map(|(name, (path, _, _))| {
+            ThemeIcon {
+                name,
+                path,
+            }
+        })
+        .collect();
+    icons.sort_by(|a, b| a.name.cmp(&b.name));
+    icons
+}
+
+/// Finds the file for icon `name` closest to `size` logical pixels at
+/// `scale`, looking through the current theme chain and then
+/// `/usr/share/pixmaps`. Names not found are retried with their last
+/// dash-separated part removed, so `network-wireless-signal-good` can fall
+/// back to `network-wireless`.
+pub(crate) fn lookup_icon(name: &str, size: u32, scale: u32) -> Option<PathBuf> {
+    let chain = theme_chain();
+    let mut name = name;
+    loop {
+        if let Some(path) = chain
+            .iter()
+            .find_map(|theme| theme.lookup(name, size, scale))
+            .or_else(|| find_file(Path::new("/usr/share/pixmaps"), name))
+        {
+            return Some(path);
+        }
+        name = &name[..name.rfind('-')?];
+    }
+}
+
+/// Returns the file for icon `name` directly in `dir`, trying the icon
+/// extensions in order of preference and skipping ones we can't decode
+/// if there is another.
+fn find_file(dir: &Path, name: &str) -> Option<PathBuf> {
+    let mut files = ICON_EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(format!("{name}.{ext}")))
+        .filter(|path| path.is_file());
+    let first = files.next()?;
+    if is_decodable(&first) {
+        return Some(first);
+    }
+    files.find(|path| is_decodable(path)).or(Some(first))
+}
+
+/// Icon files directly in `dir`, as (icon name, path) pairs.
+fn icon_files(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let (stem, ext) = path.file_name()?.to_str()?.rsplit_once('.')?;
+            // "name.symbolic.png" files are GTK's pre-rendered symbolic icons
+            (ICON_EXTENSIONS.contains(&ext) && !stem.ends_with(".symbolic"))
+                .then(|| (stem.to_string(), path.clone()))
+        })
+        .collect()
+}
+
+fn is_decodable(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(Thumbnails::is_image)
+}
+
+/// Parses an INI-style file into sections of key/value pairs.
+fn parse_ini(text: &str) -> HashMap<String, HashMap<String, String>> {
+    let mut sections: HashMap<String, HashMap<String, String>> = HashMap::new();
+    let mut current = String::new();
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('#') || line.starts_with(';') || line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = name.to_string();
+        } else if let Some((key, value)) = line.split_once('=') {
+            sections
+                .entry(current.clone())
+                .or_default()
+                .insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    sections
+}
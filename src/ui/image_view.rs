@@ -0,0 +1,546 @@
+//! Image viewer dialog implementation.
+//!
+//! Shows a PNG, JPEG, WebP or SVG file for confirmation. The image starts
+//! fitted to the window; scrolling zooms around the pointer and dragging pans.
+
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "svg")]
+use crate::render::svg::Svg;
+use crate::{
+    backend::{MouseButton, ScrollDirection, Window, WindowEvent},
+    error::Error,
+    render::{Align, Canvas},
+    ui::{
+        Theme, WindowPlacement, create_window,
+        strings::{tr, tr_args},
+        widgets::{Widget, button::Button, focus::FocusManager, label::Label, point_in_rect},
+    },
+};
+
+const BASE_PADDING: u32 = 16;
+const BASE_GAP: u32 = 10;
+const MIN_WINDOW_WIDTH: u32 = 360;
+const MIN_WINDOW_HEIGHT: u32 = 280;
+const MAX_WINDOW_WIDTH: u32 = 1000;
+const MAX_WINDOW_HEIGHT: u32 = 760;
+/// Room for the buttons and caption when sizing the window to the image.
+const BASE_CHROME_HEIGHT: u32 = 90;
+
+const MIN_ZOOM: f32 = 0.05;
+const MAX_ZOOM: f32 = 32.0;
+const ZOOM_STEP: f32 = 1.25;
+
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+
+const KEY_ESCAPE: u32 = 0xff1b;
+const KEY_RETURN: u32 = 0xff0d;
+const KEY_KP_ENTER: u32 = 0xff8d;
+const KEY_PLUS: u32 = 0x2b;
+const KEY_EQUAL: u32 = 0x3d;
+const KEY_MINUS: u32 = 0x2d;
+const KEY_KP_ADD: u32 = 0xffab;
+const KEY_KP_SUBTRACT: u32 = 0xffad;
+const KEY_0: u32 = 0x30;
+const KEY_1: u32 = 0x31;
+
+/// Image viewer dialog result.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ImageResult {
+    /// User clicked OK.
+    Ok,
+    /// User cancelled.
+    Cancelled,
+    /// Dialog was closed.
+    Closed,
+}
+
+impl ImageResult {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ImageResult::Ok => 0,
+            ImageResult::Cancelled => 1,
+            ImageResult::Closed => 255,
+        }
+    }
+}
+
+/// Image viewer dialog builder.
+pub struct ImageBuilder {
+    title: String,
+    text: String,
+    filename: PathBuf,
+    width: Option<u32>,
+    height: Option<u32>,
+    colors: Option<Theme>,
+    pub(super) placing: WindowPlacement,
+}
+
+impl ImageBuilder {
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            text: String::new(),
+            filename: PathBuf::new(),
+            width: None,
+            height: None,
+            colors: None,
+            placing: WindowPlacement::default(),
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Caption shown under the image.
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    /// The image file to show.
+    pub fn filename(mut self, filename: &str) -> Self {
+        self.filename = PathBuf::from(filename);
+        self
+    }
+
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
+        self
+    }
+
+    /// Window width. Defaults to the image's width, within limits.
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Window height. Defaults to the image's height, within limits.
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn show(self) -> Result<ImageResult, Error> {
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
+        let picture = Picture::load(&self.filename).ok_or_else(|| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("cannot display image {}", self.filename.display()),
+            ))
+        })?;
+        let (image_w, image_h) = picture.size();
+
+        let logical_width = self.width.unwrap_or_else(|| {
+            (image_w as u32 + colors.space(BASE_PADDING) * 2)
+                .clamp(MIN_WINDOW_WIDTH, MAX_WINDOW_WIDTH)
+        });
+        let logical_height = self.height.unwrap_or_else(|| {
+            (image_h as u32 + BASE_CHROME_HEIGHT).clamp(MIN_WINDOW_HEIGHT, MAX_WINDOW_HEIGHT)
+        });
+
+        let mut window = create_window(
+            logical_width as u16,
+            logical_height as u16,
+            colors,
+            &self.placing,
+        )?;
+        let default_title = self
+            .filename
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        window.set_title(if self.title.is_empty() {
+            &default_title
+        } else {
+            &self.title
+        })?;
+
+        // Get the actual scale factor from the window (compositor scale)
+        let scale = window.scale_factor();
+        let physical_width = (logical_width as f32 * scale) as u32;
+        let physical_height = (logical_height as f32 * scale) as u32;
+
+        // Now create everything at PHYSICAL scale
+        let font = colors.font(scale);
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
+        let gap = (colors.space(BASE_GAP) as f32 * scale) as u32;
+        let content_w = physical_width - padding * 2;
+
+        let mut ok_button = Button::new(&tr("OK"), &font, colors, scale);
+        let mut cancel_button = Button::new(&tr("Cancel"), &font, colors, scale);
+        let button_y = (physical_height - padding) as i32 - ok_button.height() as i32;
+        let mut bx = physical_width as i32 - padding as i32;
+        bx -= cancel_button.width() as i32;
+        cancel_button.set_position(bx, button_y);
+        bx -= gap as i32 + ok_button.width() as i32;
+        ok_button.set_position(bx, button_y);
+        ok_button.set_default(true);
+
+        // Caption sits between the image and the buttons
+        let mut caption = Label::new(&self.text)
+            .with_wrap(true)
+            .with_align(Align::Center);
+        caption.layout(&font, colors.text, Some(content_w));
+        let mut view_bottom = button_y - gap as i32;
+        if !self.text.is_empty() {
+            let caption_y = view_bottom - caption.height() as i32;
+            caption.set_position(
+                padding as i32 + (content_w as i32 - caption.width() as i32) / 2,
+                caption_y,
+            );
+            view_bottom = caption_y - gap as i32;
+        }
+
+        let view = Viewport {
+            x: padding as i32,
+            y: padding as i32,
+            w: content_w,
+            h: (view_bottom - padding as i32).max(1) as u32,
+        };
+        let mut state = ViewState::fitted(&view, image_w, image_h, picture.max_fit_zoom());
+        let mut cache: Option<(f32, Canvas)> = None;
+
+        let mut canvas = Canvas::new(physical_width, physical_height);
+
+        let draw = |canvas: &mut Canvas,
+                    state: &ViewState,
+                    cache: &mut Option<(f32, Canvas)>,
+                    ok_button: &Button,
+                    cancel_button: &Button| {
+            let width = canvas.width() as f32;
+            let height = canvas.height() as f32;
+            canvas.fill_dialog_bg(
+                width,
+                height,
+                colors.window_bg,
+                colors.window_border,
+                colors.window_radius * scale,
+                colors.border_width,
+            );
+
+            // Image, clipped to the viewport
+            let radius = 4.0 * scale;
+            let mut area = Canvas::new(view.w, view.h);
+            area.fill_rounded_rect(
+                0.0,
+                0.0,
+                view.w as f32,
+                view.h as f32,
+                radius,
+                colors.input_bg,
+            );
+            picture.draw(&mut area, state.pan_x, state.pan_y, state.zoom, cache);
+            canvas.draw_canvas(&area, view.x, view.y);
+            canvas.stroke_rounded_rect(
+                view.x as f32,
+                view.y as f32,
+                view.w as f32,
+                view.h as f32,
+                radius,
+                colors.input_border,
+                1.0,
+            );
+
+            caption.draw(canvas, colors);
+
+            let zoom_label = if state.fit {
+                tr_args("Fit ({})", &[&format!("{:.0}%", state.zoom * 100.0)])
+            } else {
+                format!("{:.0}%", state.zoom * 100.0)
+            };
+            let label = font.render(&zoom_label).with_color(colors.text).finish();
+            let ly = button_y + (ok_button.height() as i32 - label.height() as i32) / 2;
+            canvas.draw_canvas(&label, padding as i32, ly);
+
+            ok_button.draw_to(canvas, colors, &font);
+            cancel_button.draw_to(canvas, colors, &font);
+        };
+
+        draw(&mut canvas, &state, &mut cache, &ok_button, &cancel_button);
+        window.set_contents(&canvas)?;
+        window.show()?;
+
+        let mut mouse_x = 0i32;
+        let mut mouse_y = 0i32;
+        // Pointer position and pan offset when a drag started
+        let mut drag: Option<(i32, i32, f32, f32)> = None;
+        let mut last_click: Option<Instant> = None;
+        let mut focus = FocusManager::new();
+
+        // Event loop; each pass handles every pending event, then redraws once
+        loop {
+            let mut next = Some(window.wait_for_event()?);
+            let mut needs_redraw = false;
+
+            while let Some(event) = next.take() {
+                let event = focus.process_event(event, &mut [&mut ok_button, &mut cancel_button]);
+                let old_state = state;
+                let view_center = (view.w as f32 / 2.0, view.h as f32 / 2.0);
+
+                match &event {
+                    WindowEvent::CloseRequested => return Ok(ImageResult::Closed),
+                    WindowEvent::RedrawRequested => needs_redraw = true,
+                    WindowEvent::KeyPress(key) => {
+                        match key.keysym {
+                            KEY_ESCAPE => return Ok(ImageResult::Cancelled),
+                            KEY_RETURN | KEY_KP_ENTER => return Ok(ImageResult::Ok),
+                            KEY_PLUS | KEY_EQUAL | KEY_KP_ADD => {
+                                state.zoom_at(&view, image_w, image_h, ZOOM_STEP, view_center);
+                            }
+                            KEY_MINUS | KEY_KP_SUBTRACT => {
+                                state.zoom_at(
+                                    &view,
+                                    image_w,
+                                    image_h,
+                                    1.0 / ZOOM_STEP,
+                                    view_center,
+                                );
+                            }
+                            KEY_0 => {
+                                state = ViewState::fitted(
+                                    &view,
+                                    image_w,
+                                    image_h,
+                                    picture.max_fit_zoom(),
+                                );
+                            }
+                            KEY_1 => {
+                                let factor = 1.0 / state.zoom;
+                                state.zoom_at(&view, image_w, image_h, factor, view_center);
+                            }
+                            _ => {}
+                        }
+                    }
+                    WindowEvent::CursorMove(pos) => {
+                        mouse_x = pos.x as i32;
+                        mouse_y = pos.y as i32;
+                        if let Some((start_x, start_y, pan_x, pan_y)) = drag {
+                            state.pan_x = pan_x + (mouse_x - start_x) as f32;
+                            state.pan_y = pan_y + (mouse_y - start_y) as f32;
+                            state.clamp_pan(&view, image_w, image_h);
+                        }
+                    }
+                    WindowEvent::ButtonPress(MouseButton::Left, _)
+                        if view.contains(mouse_x, mouse_y) =>
+                    {
+                        // Double-click switches between fitted and actual size
+                        if last_click.is_some_and(|time| time.elapsed() <= DOUBLE_CLICK_TIME) {
+                            last_click = None;
+                            if state.fit {
+                                let at = view.local(mouse_x, mouse_y);
+                                state.zoom_at(&view, image_w, image_h, 1.0 / state.zoom, at);
+                            } else {
+                                state = ViewState::fitted(
+                                    &view,
+                                    image_w,
+                                    image_h,
+                                    picture.max_fit_zoom(),
+                                );
+                            }
+                        } else {
+                            last_click = Some(Instant::now());
+                            drag = Some((mouse_x, mouse_y, state.pan_x, state.pan_y));
+                        }
+                    }
+                    WindowEvent::ButtonRelease(MouseButton::Left, _) => drag = None,
+                    WindowEvent::Scroll(direction) if view.contains(mouse_x, mouse_y) => {
+                        let factor = match direction {
+                            ScrollDirection::Up => ZOOM_STEP,
+                            ScrollDirection::Down => 1.0 / ZOOM_STEP,
+                            _ => 1.0,
+                        };
+                        let at = view.local(mouse_x, mouse_y);
+                        state.zoom_at(&view, image_w, image_h, factor, at);
+                    }
+                    _ => {}
+                }
+                needs_redraw |= state != old_state;
+
+                needs_redraw |= ok_button.process_event(&event);
+                needs_redraw |= cancel_button.process_event(&event);
+                if ok_button.was_clicked() {
+                    return Ok(ImageResult::Ok);
+                }
+                if cancel_button.was_clicked() {
+                    return Ok(ImageResult::Cancelled);
+                }
+
+                next = window.poll_for_event()?;
+            }
+
+            if needs_redraw {
+                draw(&mut canvas, &state, &mut cache, &ok_button, &cancel_button);
+                window.set_contents(&canvas)?;
+            }
+        }
+    }
+}
+
+impl Default for ImageBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A decoded image.
+enum Picture {
+    #[cfg_attr(not(feature = "images"), allow(dead_code))]
+    Raster(Canvas),
+    #[cfg(feature = "svg")]
+    Vector(Box<Svg>),
+}
+
+impl Picture {
+    #[cfg_attr(not(any(feature = "images", feature = "svg")), allow(unused_variables))]
+    fn load(path: &Path) -> Option<Self> {
+        #[cfg(feature = "svg")]
+        if path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(Svg::is_svg)
+        {
+            return Svg::load(path).map(|svg| Picture::Vector(Box::new(svg)));
+        }
+        #[cfg(feature = "images")]
+        if let Some(canvas) = crate::render::image::load_image(path) {
+            return Some(Picture::Raster(canvas));
+        }
+        None
+    }
+
+    fn size(&self) -> (f32, f32) {
+        match self {
+            Picture::Raster(canvas) => (canvas.width() as f32, canvas.height() as f32),
+            #[cfg(feature = "svg")]
+            Picture::Vector(svg) => svg.size(),
+        }
+    }
+
+    /// Largest zoom used when fitting to the window: bitmaps are not enlarged
+    /// beyond their real size, but drawings may grow to fill the window.
+    fn max_fit_zoom(&self) -> f32 {
+        match self {
+            Picture::Raster(_) => 1.0,
+            #[cfg(feature = "svg")]
+            Picture::Vector(_) => MAX_ZOOM,
+        }
+    }
+
+    /// Draws the image at `zoom` with its top-left corner at (`x`, `y`).
+    /// Shrunk bitmaps are averaged once per zoom level and kept in `cache`.
+    fn draw(
+        &self,
+        canvas: &mut Canvas,
+        x: f32,
+        y: f32,
+        zoom: f32,
+        cache: &mut Option<(f32, Canvas)>,
+    ) {
+        match self {
+            Picture::Raster(image) if zoom < 1.0 => {
+                if cache.as_ref().is_none_or(|(cached, _)| *cached != zoom) {
+                    let w = (image.width() as f32 * zoom).round() as u32;
+                    let h = (image.height() as f32 * zoom).round() as u32;
+                    *cache = Some((zoom, image.scaled_to_fit(w.max(1), h.max(1))));
+                }
+                if let Some((_, scaled)) = cache {
+                    canvas.draw_canvas(scaled, x.round() as i32, y.round() as i32);
+                }
+            }
+            Picture::Raster(image) => canvas.draw_canvas_scaled(image, x.round(), y.round(), zoom),
+            #[cfg(feature = "svg")]
+            Picture::Vector(svg) => svg.render_to(canvas, x, y, zoom),
+        }
+    }
+}
+
+/// The area of the window the image is shown in.
+struct Viewport {
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32,
+}
+
+impl Viewport {
+    fn contains(&self, x: i32, y: i32) -> bool {
+        point_in_rect(x, y, self.x, self.y, self.w, self.h)
+    }
+
+    /// Converts window coordinates to viewport coordinates.
+    fn local(&self, x: i32, y: i32) -> (f32, f32) {
+        ((x - self.x) as f32, (y - self.y) as f32)
+    }
+}
+
+/// Zoom level and where the image's top-left corner sits in the viewport.
+#[derive(Clone, Copy, PartialEq)]
+struct ViewState {
+    zoom: f32,
+    pan_x: f32,
+    pan_y: f32,
+    /// Whether the zoom was chosen to fit the window.
+    fit: bool,
+}
+
+impl ViewState {
+    /// Shows the whole image centered, zoomed to fill the viewport up to
+    /// `max_zoom`.
+    fn fitted(view: &Viewport, image_w: f32, image_h: f32, max_zoom: f32) -> Self {
+        let zoom = (view.w as f32 / image_w)
+            .min(view.h as f32 / image_h)
+            .min(max_zoom);
+        let mut state = Self {
+            zoom,
+            pan_x: 0.0,
+            pan_y: 0.0,
+            fit: true,
+        };
+        state.clamp_pan(view, image_w, image_h);
+        state
+    }
+
+    /// Multiplies the zoom by `factor`, keeping the image point under `at`
+    /// (in viewport coordinates) in place.
+    fn zoom_at(
+        &mut self,
+        view: &Viewport,
+        image_w: f32,
+        image_h: f32,
+        factor: f32,
+        at: (f32, f32),
+    ) {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        if zoom == self.zoom {
+            return;
+        }
+        let ratio = zoom / self.zoom;
+        self.pan_x = at.0 - (at.0 - self.pan_x) * ratio;
+        self.pan_y = at.1 - (at.1 - self.pan_y) * ratio;
+        self.zoom = zoom;
+        self.fit = false;
+        self.clamp_pan(view, image_w, image_h);
+    }
+
+    /// Centers the image along axes where it fits and otherwise keeps the
+    /// viewport covered.
+    fn clamp_pan(&mut self, view: &Viewport, image_w: f32, image_h: f32) {
+        let clamp_axis = |pan: f32, image: f32, view: f32| {
+            let scaled = image * self.zoom;
+            if scaled <= view {
+                ((view - scaled) / 2.0).round()
+            } else {
+                pan.clamp(view - scaled, 0.0)
+            }
+        };
+        self.pan_x = clamp_axis(self.pan_x, image_w, view.w as f32);
+        self.pan_y = clamp_axis(self.pan_y, image_h, view.h as f32);
+    }
+}
@@ -0,0 +1,67 @@
+//! The directory each application last picked a file from, so the file
+//! dialog can reopen there.
+//!
+//! Stored in `~/.cache/zenity-rs/last-dirs` with one entry per line: the
+//! application key, a tab, and the directory.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Returns the last directory recorded for `key`, if it still exists.
+pub(crate) fn load(key: &str) -> Option<PathBuf> {
+    let key = sanitize(key);
+    read_entries()
+        .into_iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, dir)| PathBuf::from(dir))
+        .filter(|dir| dir.is_dir())
+}
+
+/// Records `dir` as the last directory for `key`.
+pub(crate) fn save(key: &str, dir: &Path) -> io::Result<()> {
+    let file = file().ok_or_else(|| io::Error::other("no cache directory"))?;
+    let key = sanitize(key);
+    let dir = dir.to_string_lossy();
+    if dir.contains('\n') {
+        return Ok(());
+    }
+
+    // Most recently used first
+    let mut lines = vec![format!("{key}\t{dir}")];
+    lines.extend(
+        read_entries()
+            .into_iter()
+            .filter(|(k, _)| *k != key)
+            .map(|(k, d)| format!("{k}\t{d}")),
+    );
+
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    fs::write(file, text)
+}
+
+fn file() -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join("zenity-rs/last-dirs"))
+}
+
+fn read_entries() -> Vec<(String, String)> {
+    file()
+        .and_then(|f| fs::read_to_string(f).ok())
+        .map(|text| {
+            text.lines()
+                .filter_map(|line| line.split_once('\t'))
+                .map(|(k, d)| (k.to_string(), d.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Keys are stored on one line before a tab, so neither may appear in them.
+fn sanitize(key: &str) -> String {
+    key.replace(['\t', '\n', '\r'], " ")
+}
@@ -1,12 +1,21 @@
 //! List selection dialog implementation.
 
 use crate::{
-    backend::{MouseButton, Window, WindowEvent, create_window},
+    backend::{MouseButton, Window, WindowEvent},
     error::Error,
     render::{Canvas, Font, rgb},
     ui::{
-        Colors,
-        widgets::{Widget, button::Button},
+        Theme, WindowPlacement, create_window,
+        hooks::Hooks,
+        strings::tr,
+        widgets::{
+            Widget,
+            button::Button,
+            checkbox,
+            focus::FocusManager,
+            label::{Ellipsize, Label},
+            scroll_area::ScrollArea,
+        },
     },
 };
 
@@ -20,6 +29,7 @@ const BASE_MAX_HEIGHT: u32 = 450;
 
 /// List dialog result.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ListResult {
     /// User selected item(s). Contains the values from the first column.
     Selected(Vec<String>),
@@ -60,9 +70,12 @@ pub struct ListBuilder {
     rows: Vec<Vec<String>>,
     mode: ListMode,
     hidden_columns: Vec<usize>,
+    no_markup: bool,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    colors: Option<Theme>,
+    hooks: Hooks<[String]>,
+    pub(super) placing: WindowPlacement,
 }
 
 impl ListBuilder {
@@ -74,9 +87,12 @@ impl ListBuilder {
             rows: Vec::new(),
             mode: ListMode::Single,
             hidden_columns: Vec::new(),
+            no_markup: false,
             width: None,
             height: None,
             colors: None,
+            hooks: Hooks::default(),
+            placing: WindowPlacement::default(),
         }
     }
 
@@ -126,8 +142,8 @@ impl ListBuilder {
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
         self
     }
 
@@ -150,8 +166,85 @@ impl ListBuilder {
         self
     }
 
-    pub fn show(self) -> Result<ListResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+    /// Show cells as plain text rather than Pango markup.
+    pub fn no_markup(mut self, no_markup: bool) -> Self {
+        self.no_markup = no_markup;
+        self
+    }
+
+    /// Calls `f` with the first column of the selected rows each time the
+    /// user changes the selection.
+    pub fn on_change(mut self, f: impl FnMut(&[String]) + Send + 'static) -> Self {
+        self.hooks.change = Some(Box::new(f));
+        self
+    }
+
+    /// Calls `f` with the button the dialog is dismissed with: 0 for OK or
+    /// Enter, 1 for Cancel or Escape.
+    pub fn on_button(mut self, f: impl FnMut(usize) + Send + 'static) -> Self {
+        self.hooks.button = Some(Box::new(f));
+        self
+    }
+
+    /// Calls `f` when the dialog is cancelled or closed, or OK is pressed
+    /// with nothing selected.
+    pub fn on_cancel(mut self, f: impl FnMut() + Send + 'static) -> Self {
+        self.hooks.cancel = Some(Box::new(f));
+        self
+    }
+
+    /// Checks that there are columns and that every row and hidden column
+    /// fits them, as `show` does before showing the dialog. In checklist
+    /// and radiolist mode the first column is the one with the boxes.
+    pub fn validate(&self) -> Result<(), Error> {
+        let columns = self.columns.len();
+        let boxed = matches!(self.mode, ListMode::Checklist | ListMode::Radiolist);
+        if columns == 0 {
+            return Err(Error::InvalidConfig("list has no columns".to_string()));
+        }
+        if boxed && columns < 2 {
+            return Err(Error::InvalidConfig(
+                "a checklist or radiolist needs a column for its boxes and another for its values"
+                    .to_string(),
+            ));
+        }
+        if let Some((i, row)) = self
+            .rows
+            .iter()
+            .enumerate()
+            .find(|(_, row)| row.len() != columns)
+        {
+            return Err(Error::InvalidConfig(format!(
+                "row {} has {} value{}, but there are {columns} columns",
+                i + 1,
+                row.len(),
+                if row.len() == 1 { "" } else { "s" }
+            )));
+        }
+        if let Some(col) = self.hidden_columns.iter().find(|&&col| col >= columns) {
+            return Err(Error::InvalidConfig(format!(
+                "column {} can't be hidden, there are {columns} columns",
+                col + 1
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn show(mut self) -> Result<ListResult, Error> {
+        self.validate()?;
+        let mut hooks = std::mem::take(&mut self.hooks);
+        let result = self.run(&mut hooks)?;
+        let button = match result {
+            ListResult::Selected(_) => Some(0),
+            ListResult::Cancelled => Some(1),
+            ListResult::Closed => None,
+        };
+        hooks.dismissed(button, button != Some(0));
+        Ok(result)
+    }
+
+    fn run(&self, hooks: &mut Hooks<[String]>) -> Result<ListResult, Error> {
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
 
         // Process rows - for checklist/radiolist, first column is TRUE/FALSE
         let (rows, mut selected): (Vec<Vec<String>>, Vec<bool>) = match self.mode {
@@ -229,6 +322,11 @@ impl ListBuilder {
             })
             .collect();
 
+        #[cfg(feature = "tui")]
+        if crate::tui::enabled() {
+            return self.show_tui(hooks, &columns, &display_rows, &rows, selected);
+        }
+
         let num_cols = columns.len().max(1);
         let num_rows = rows.len();
 
@@ -236,7 +334,7 @@ impl ListBuilder {
         let logical_column_gap = 16u32;
 
         // First pass: calculate LOGICAL dimensions using scale 1.0
-        let temp_font = Font::load(1.0);
+        let temp_font = colors.font(1.0);
 
         // Calculate logical column widths (only for visible columns)
         let mut logical_col_widths: Vec<u32> = vec![100; num_cols];
@@ -247,7 +345,10 @@ impl ListBuilder {
         for row in &rows {
             for (vi, &orig_i) in visible_col_indices.iter().enumerate() {
                 if let Some(cell) = row.get(orig_i) {
-                    let (w, _) = temp_font.render(cell).measure();
+                    let (w, _) = temp_font
+                        .render(cell)
+                        .with_markup(!self.no_markup)
+                        .measure();
                     logical_col_widths[vi] = logical_col_widths[vi].max(w as u32 + 20);
                 }
             }
@@ -264,8 +365,8 @@ impl ListBuilder {
         let logical_content_width: u32 = logical_col_widths.iter().sum::<u32>()
             + logical_checkbox_col
             + (num_gaps as u32 * logical_column_gap);
-        let calc_width =
-            (logical_content_width + BASE_PADDING * 2).clamp(BASE_MIN_WIDTH, BASE_MAX_WIDTH);
+        let calc_width = (logical_content_width + colors.space(BASE_PADDING) * 2)
+            .clamp(BASE_MIN_WIDTH, BASE_MAX_WIDTH);
 
         // Calculate logical height
         let logical_title_height = if self.title.is_empty() { 0 } else { 32 };
@@ -277,7 +378,7 @@ impl ListBuilder {
         };
         let logical_list_height =
             (num_rows as u32 * BASE_ROW_HEIGHT).clamp(BASE_ROW_HEIGHT * 3, BASE_MAX_HEIGHT - 100);
-        let calc_height = (BASE_PADDING * 2
+        let calc_height = (colors.space(BASE_PADDING) * 2
             + logical_title_height
             + logical_text_height
             + logical_header_height
@@ -290,21 +391,26 @@ impl ListBuilder {
         let logical_height = self.height.unwrap_or(calc_height);
 
         // Create window with LOGICAL dimensions
-        let mut window = create_window(logical_width as u16, logical_height as u16)?;
-        window.set_title(if self.title.is_empty() {
-            "Select"
+        let mut window = create_window(
+            logical_width as u16,
+            logical_height as u16,
+            colors,
+            &self.placing,
+        )?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Select")
         } else {
-            &self.title
+            self.title.as_str().into()
         })?;
 
         // Get the actual scale factor from the window (compositor scale)
         let scale = window.scale_factor();
 
         // Now create everything at PHYSICAL scale
-        let font = Font::load(scale);
+        let font = colors.font(scale);
 
         // Scale dimensions for physical rendering
-        let padding = (BASE_PADDING as f32 * scale) as u32;
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
         let row_height = (BASE_ROW_HEIGHT as f32 * scale) as u32;
         let checkbox_size = (BASE_CHECKBOX_SIZE as f32 * scale) as u32;
 
@@ -321,7 +427,7 @@ impl ListBuilder {
         for row in &display_rows {
             for (i, cell) in row.iter().enumerate() {
                 if i < num_cols {
-                    let (w, _) = font.render(cell).measure();
+                    let (w, _) = font.render(cell).with_markup(!self.no_markup).measure();
                     col_widths[i] = col_widths[i].max(w as u32 + (20.0 * scale) as u32);
                 }
             }
@@ -363,8 +469,8 @@ impl ListBuilder {
             + (num_gaps as u32 * column_gap);
 
         // Create buttons at physical scale
-        let mut ok_button = Button::new("OK", &font, scale);
-        let mut cancel_button = Button::new("Cancel", &font, scale);
+        let mut ok_button = Button::new(&tr("OK"), &font, colors, scale);
+        let mut cancel_button = Button::new(&tr("Cancel"), &font, colors, scale);
 
         // Layout in physical coordinates
         let mut y = padding as i32;
@@ -376,12 +482,10 @@ impl ListBuilder {
             (24.0 * scale + 8.0 * scale) as u32
         };
 
-        // Position text below title (if both present)
-        let text_y = if self.text.is_empty() {
-            y
-        } else {
-            y + title_height as i32
-        };
+        // Position text below title (if both present), shortened to one line
+        let mut prompt = Label::new(&self.text).with_ellipsize(Ellipsize::End);
+        prompt.layout(&font, colors.text, Some(physical_width - padding * 2));
+        prompt.set_position(padding as i32, y + title_height as i32);
 
         // Update y position after both title and text
         if !self.title.is_empty() {
@@ -393,35 +497,61 @@ impl ListBuilder {
 
         let list_x = padding as i32;
         let list_y = y;
-        let list_w = physical_width - padding * 2;
-        let list_h = list_height;
-        let visible_rows = (list_h / row_height) as usize;
-
-        let button_y = (physical_height - padding - (32.0 * scale) as u32) as i32;
-        let mut bx = physical_width as i32 - padding as i32;
-        bx -= cancel_button.width() as i32;
-        cancel_button.set_position(bx, button_y);
-        bx -= (10.0 * scale) as i32 + ok_button.width() as i32;
-        ok_button.set_position(bx, button_y);
+        let mut list_w = physical_width - padding * 2;
+        let mut list_h = list_height;
+        let mut visible_rows = (list_h / row_height) as usize;
+
+        // Button positions (right-aligned)
+        let place_buttons = |ok_button: &mut Button,
+                             cancel_button: &mut Button,
+                             physical_width: u32,
+                             physical_height: u32| {
+            let button_y =
+                (physical_height - padding - (colors.control_height as f32 * scale) as u32) as i32;
+            let mut bx = physical_width as i32 - padding as i32;
+            bx -= cancel_button.width() as i32;
+            cancel_button.set_position(bx, button_y);
+            bx -= (10.0 * scale) as i32 + ok_button.width() as i32;
+            ok_button.set_position(bx, button_y);
+            button_y
+        };
+        let button_y = place_buttons(
+            &mut ok_button,
+            &mut cancel_button,
+            physical_width,
+            physical_height,
+        );
+        // The list takes up any height gained by resizing, keeping this
+        // much space above the buttons
+        let list_bottom_gap = (button_y - list_y - list_h as i32).max(padding as i32);
 
         // Create canvas at PHYSICAL dimensions
         let mut canvas = Canvas::new(physical_width, physical_height);
-        let mut scroll_offset = 0usize;
-        let mut h_scroll_offset = 0u32;
+        // Size from the latest resize, applied before the next redraw
+        let mut new_size: Option<(u32, u32)> = None;
         let mut hovered_row: Option<usize> = None;
         let mut single_selected: Option<usize> = None;
-        let mut h_scroll_mode = false;
 
-        // Track last cursor position for drag scrolling
+        // Track last cursor position for row clicks
         let mut last_cursor_pos: Option<(i32, i32)> = None;
 
-        // Scrollbar thumb dragging state
-        let mut v_thumb_drag = false;
-        let mut h_thumb_drag = false;
-        let mut v_thumb_drag_offset: Option<i32> = None;
-        let mut h_thumb_drag_offset: Option<i32> = None;
-        let mut v_scrollbar_hovered = false;
-        let mut h_scrollbar_hovered = false;
+        // Rows scroll under the column headers
+        let header_height_px = if columns.is_empty() {
+            0
+        } else {
+            row_height + 1
+        };
+        let data_y = list_y + header_height_px as i32;
+        let mut data_visible = if columns.is_empty() {
+            visible_rows
+        } else {
+            visible_rows.saturating_sub(1)
+        };
+        let mut scroll = ScrollArea::new(list_w, list_h, scale);
+        scroll.set_position(list_x, list_y);
+        scroll.set_header(header_height_px);
+        scroll.set_content(rows.len(), data_visible);
+        scroll.set_content_width(total_content_width);
 
         // Create sub-canvas for the list area to enable clipping
         let mut list_canvas = Canvas::new(list_w, list_h);
@@ -429,23 +559,21 @@ impl ListBuilder {
         // Draw function with scaled parameters
         let draw = |canvas: &mut Canvas,
                     list_canvas: &mut Canvas,
-                    colors: &Colors,
+                    colors: &Theme,
                     font: &Font,
                     title: &str,
-                    text: &str,
+                    prompt: &Label,
                     checkbox_column_header: &Option<String>,
                     columns: &[&str],
                     rows: &[Vec<String>],
                     col_widths: &[u32],
                     selected: &[bool],
                     single_selected: Option<usize>,
-                    scroll_offset: usize,
-                    h_scroll_offset: u32,
+                    scroll: &ScrollArea,
                     hovered_row: Option<usize>,
                     mode: ListMode,
                     ok_button: &Button,
                     cancel_button: &Button,
-                    total_content_width: u32,
                     // Scaled parameters
                     padding: u32,
                     row_height: u32,
@@ -456,21 +584,19 @@ impl ListBuilder {
                     list_w: u32,
                     list_h: u32,
                     visible_rows: usize,
-                    text_y: i32,
-                    scale: f32,
-                    v_scrollbar_hovered: bool,
-                    h_scrollbar_hovered: bool| {
+                    scale: f32| {
             let width = canvas.width() as f32;
             let height = canvas.height() as f32;
-            let radius = 8.0 * scale;
+            let scroll_offset = scroll.offset();
+            let h_scroll_offset = scroll.h_offset();
 
             canvas.fill_dialog_bg(
                 width,
                 height,
                 colors.window_bg,
                 colors.window_border,
-                colors.window_shadow,
-                radius,
+                colors.window_radius * scale,
+                colors.border_width,
             );
 
             // Draw title if present
@@ -485,10 +611,7 @@ impl ListBuilder {
             }
 
             // Draw text prompt
-            if !text.is_empty() {
-                let tc = font.render(text).with_color(colors.text).finish();
-                canvas.draw_canvas(&tc, padding as i32, text_y);
-            }
+            prompt.draw(canvas, colors);
 
             // Clear list canvas
             list_canvas.fill(colors.input_bg);
@@ -578,13 +701,14 @@ impl ListBuilder {
                     let checked = selected.get(ri).copied().unwrap_or(false);
 
                     if mode == ListMode::Checklist {
-                        draw_checkbox(
+                        checkbox::draw_box(
                             list_canvas,
                             check_x,
                             check_y,
+                            checkbox_size,
                             checked,
+                            if is_hovered { 1.0 } else { 0.0 },
                             colors,
-                            checkbox_size,
                             scale,
                         );
                     } else {
@@ -613,11 +737,15 @@ impl ListBuilder {
                 for (ci, cell) in row.iter().enumerate() {
                     if ci < col_widths.len() {
                         let text_color = if is_selected {
-                            rgb(255, 255, 255)
+                            colors.selection_text
                         } else {
                             colors.text
                         };
-                        let tc = font.render(cell).with_color(text_color).finish();
+                        let tc = font
+                            .render(cell)
+                            .with_markup(!self.no_markup)
+                            .with_color(text_color)
+                            .finish();
                         list_canvas.draw_canvas(
                             &tc,
                             cx + (8.0 * scale) as i32,
@@ -632,95 +760,6 @@ impl ListBuilder {
                 }
             }
 
-            // Vertical Scrollbar
-            if rows.len() > data_visible {
-                let sb_x = list_w as i32 - (8.0 * scale) as i32;
-                let sb_h = list_h as f32
-                    - if columns.is_empty() {
-                        0.0
-                    } else {
-                        row_height as f32 + 1.0
-                    };
-                let sb_y = data_y_local as f32;
-                let thumb_h =
-                    ((data_visible as f32 / rows.len() as f32 * sb_h).max(20.0 * scale)).min(sb_h);
-                let max_thumb_y = sb_h - thumb_h;
-                let thumb_y = if rows.len() > data_visible {
-                    scroll_offset as f32 / (rows.len() - data_visible) as f32 * max_thumb_y
-                } else {
-                    0.0
-                };
-
-                let v_scrollbar_width = if v_scrollbar_hovered {
-                    12.0 * scale
-                } else {
-                    8.0 * scale
-                };
-
-                list_canvas.fill_rounded_rect(
-                    sb_x as f32,
-                    sb_y,
-                    v_scrollbar_width - 2.0 * scale,
-                    sb_h,
-                    3.0 * scale,
-                    darken(colors.input_bg, 0.05),
-                );
-                list_canvas.fill_rounded_rect(
-                    sb_x as f32,
-                    sb_y + thumb_y,
-                    v_scrollbar_width - 2.0 * scale,
-                    thumb_h,
-                    3.0 * scale,
-                    if v_scrollbar_hovered {
-                        colors.input_border_focused
-                    } else {
-                        colors.input_border
-                    },
-                );
-            }
-
-            // Horizontal Scrollbar
-            if total_content_width > list_w {
-                let h_scrollbar_width = if h_scrollbar_hovered {
-                    12.0 * scale
-                } else {
-                    8.0 * scale
-                };
-                let sb_x = 0.0;
-                let sb_y = list_h as i32 - h_scrollbar_width as i32;
-                let sb_w = list_w as f32;
-                let max_scroll = total_content_width.saturating_sub(list_w);
-                let thumb_w = ((list_w as f32 / total_content_width as f32 * sb_w)
-                    .max(20.0 * scale))
-                .min(sb_w);
-                let thumb_x = if max_scroll > 0 {
-                    h_scroll_offset as f32 / max_scroll as f32 * (sb_w - thumb_w)
-                } else {
-                    0.0
-                };
-
-                list_canvas.fill_rounded_rect(
-                    sb_x,
-                    sb_y as f32,
-                    sb_w,
-                    h_scrollbar_width - 2.0 * scale,
-                    3.0 * scale,
-                    darken(colors.input_bg, 0.05),
-                );
-                list_canvas.fill_rounded_rect(
-                    sb_x + thumb_x,
-                    sb_y as f32,
-                    thumb_w,
-                    h_scrollbar_width - 2.0 * scale,
-                    3.0 * scale,
-                    if h_scrollbar_hovered {
-                        colors.input_border_focused
-                    } else {
-                        colors.input_border
-                    },
-                );
-            }
-
             // Border
             list_canvas.stroke_rounded_rect(
                 0.0,
@@ -734,6 +773,7 @@ impl ListBuilder {
 
             // Draw the list canvas to main canvas
             canvas.draw_canvas(list_canvas, list_x, list_y);
+            scroll.draw(canvas, colors);
 
             // Buttons
             ok_button.draw_to(canvas, colors, font);
@@ -747,20 +787,18 @@ impl ListBuilder {
             colors,
             &font,
             &self.title,
-            &self.text,
+            &prompt,
             &checkbox_column_header,
             &columns,
             &display_rows,
             &col_widths,
             &selected,
             single_selected,
-            scroll_offset,
-            h_scroll_offset,
+            &scroll,
             hovered_row,
             self.mode,
             &ok_button,
             &cancel_button,
-            total_content_width,
             padding,
             row_height,
             checkbox_size,
@@ -770,32 +808,28 @@ impl ListBuilder {
             list_w,
             list_h,
             visible_rows,
-            text_y,
             scale,
-            v_scrollbar_hovered,
-            h_scrollbar_hovered,
         );
         window.set_contents(&canvas)?;
+        window.set_resizable(
+            BASE_MIN_WIDTH.min(logical_width) as u16,
+            BASE_MIN_HEIGHT.min(logical_height) as u16,
+        )?;
         window.show()?;
 
-        let header_height_px = if columns.is_empty() {
-            0
-        } else {
-            row_height + 1
-        };
-        let data_y = list_y + header_height_px as i32;
-        let data_visible = if columns.is_empty() {
-            visible_rows
-        } else {
-            visible_rows.saturating_sub(1)
-        };
+        let mut focus = FocusManager::new();
+        let mut last_selection = selection(&rows, &selected, single_selected, self.mode);
         loop {
-            let event = window.wait_for_event()?;
+            let event = focus.process_event(
+                window.wait_for_event()?,
+                &mut [&mut ok_button, &mut cancel_button],
+            );
             let mut needs_redraw = false;
 
             match &event {
                 WindowEvent::CloseRequested => return Ok(ListResult::Closed),
                 WindowEvent::RedrawRequested => needs_redraw = true,
+                WindowEvent::Resized(w, h) => new_size = Some((*w, *h)),
                 WindowEvent::CursorMove(pos) => {
                     let mx = pos.x as i32;
                     let my = pos.y as i32;
@@ -803,118 +837,19 @@ impl ListBuilder {
                     // Store current cursor position
                     last_cursor_pos = Some((mx, my));
 
-                    // Handle scrollbar thumb dragging
-                    if v_thumb_drag || h_thumb_drag {
-                        let list_mx = mx - list_x;
-                        let list_my = my - list_y;
-
-                        if v_thumb_drag && rows.len() > data_visible {
-                            let sb_h_f32 = list_h as f32
-                                - if columns.is_empty() {
-                                    0.0
-                                } else {
-                                    row_height as f32 + 1.0
-                                };
-                            let sb_h = sb_h_f32 as i32;
-                            let sb_y = if columns.is_empty() {
-                                0
-                            } else {
-                                (row_height + 1) as i32
-                            };
-                            let thumb_h_f32 = ((data_visible as f32 / rows.len() as f32
-                                * sb_h_f32)
-                                .max(20.0 * scale))
-                            .min(sb_h_f32);
-                            let thumb_h = thumb_h_f32 as i32;
-                            let max_thumb_y = sb_h - thumb_h;
-
-                            // Calculate new scroll offset from thumb position
-                            // Use the drag offset to maintain the relative position from where user clicked
-                            let offset = v_thumb_drag_offset.unwrap_or(thumb_h / 2);
-                            // list_my is relative to list canvas, need to adjust for scrollbar position
-                            let thumb_y = (list_my - sb_y - offset).clamp(0, max_thumb_y);
-                            let scroll_ratio = if max_thumb_y > 0 {
-                                thumb_y as f32 / max_thumb_y as f32
-                            } else {
-                                0.0
-                            };
-                            scroll_offset = ((scroll_ratio * (rows.len() - data_visible) as f32)
-                                as usize)
-                                .clamp(0, rows.len().saturating_sub(data_visible));
-                            needs_redraw = true;
-                        }
-
-                        if h_thumb_drag && total_content_width > list_w {
-                            let sb_w_f32 = list_w as f32;
-                            let sb_w = list_w as i32;
-                            let max_scroll_u32 = total_content_width.saturating_sub(list_w);
-                            let max_scroll = (max_scroll_u32 as i32).max(1);
-                            let thumb_w_f32 = ((list_w as f32 / total_content_width as f32
-                                * sb_w_f32)
-                                .max(20.0 * scale))
-                            .min(sb_w_f32);
-                            let thumb_w = thumb_w_f32 as i32;
-                            let max_thumb_x = sb_w - thumb_w;
-
-                            // Calculate new horizontal scroll offset from thumb position
-                            // Use the drag offset to maintain the relative position from where user clicked
-                            let offset = h_thumb_drag_offset.unwrap_or(thumb_w / 2);
-                            let thumb_x = (list_mx - offset).clamp(0, max_thumb_x);
-                            let scroll_ratio = if max_scroll > 0 {
-                                thumb_x as f32 / max_thumb_x as f32
-                            } else {
-                                0.0
-                            };
-                            h_scroll_offset = ((scroll_ratio * max_scroll as f32) as u32)
-                                .clamp(0, max_scroll_u32);
-                            needs_redraw = true;
-                        }
-                    } else {
+                    // Rows under a dragged thumb or the scrollbars aren't hovered
+                    if !scroll.is_dragging() {
                         let old_hovered = hovered_row;
                         hovered_row = None;
 
-                        // Update scrollbar hover states
-                        let v_scrollbar_width = if v_scrollbar_hovered {
-                            12.0 * scale
-                        } else {
-                            8.0 * scale
-                        };
-                        let v_scrollbar_x = list_w as i32 - v_scrollbar_width as i32;
-                        let h_scrollbar_width = if h_scrollbar_hovered {
-                            12.0 * scale
-                        } else {
-                            8.0 * scale
-                        };
-
-                        v_scrollbar_hovered = rows.len() > data_visible
-                            && mx >= list_x + v_scrollbar_x
-                            && mx < list_x + list_w as i32
-                            && my >= list_y
-                            && my < list_y + list_h as i32;
-
-                        h_scrollbar_hovered = total_content_width > list_w
-                            && mx >= list_x
-                            && mx < list_x + list_w as i32
-                            && my >= list_y + list_h as i32 - h_scrollbar_width as i32
-                            && my < list_y + list_h as i32;
-
-                        // Check row hover (only if not over scrollbar)
-                        let effective_v_scrollbar_width =
-                            if v_scrollbar_hovered && rows.len() > data_visible {
-                                12.0 * scale
-                            } else if rows.len() > data_visible {
-                                8.0 * scale
-                            } else {
-                                0.0
-                            };
-
                         if mx >= list_x
-                            && mx < list_x + list_w as i32 - effective_v_scrollbar_width as i32
+                            && mx < list_x + list_w as i32
                             && my >= data_y
                             && my < list_y + list_h as i32
+                            && !scroll.over_bar(mx, my)
                         {
                             let rel_y = (my - data_y) as usize;
-                            let ri = scroll_offset + rel_y / row_height as usize;
+                            let ri = scroll.offset() + rel_y / row_height as usize;
                             if ri < rows.len() {
                                 hovered_row = Some(ri);
                             }
@@ -926,110 +861,9 @@ impl ListBuilder {
                     }
                 }
                 WindowEvent::ButtonPress(MouseButton::Left, mods) => {
-                    let mut clicking_scrollbar = false;
-
-                    // Check if clicking anywhere in scrollbar area (thumb OR track)
-                    if let Some((mx, my)) = last_cursor_pos {
-                        // Check if click is in list area (convert to list canvas coords)
-                        let list_mx = mx - list_x;
-                        let list_my = my - list_y;
-
-                        if list_mx >= 0
-                            && list_mx < list_w as i32
-                            && list_my >= 0
-                            && list_my < list_h as i32
-                        {
-                            // Vertical scrollbar area
-                            if rows.len() > data_visible {
-                                let v_scrollbar_width = if v_scrollbar_hovered {
-                                    12.0 * scale
-                                } else {
-                                    8.0 * scale
-                                };
-                                let sb_x = list_w as i32 - v_scrollbar_width as i32;
-
-                                // Block all clicks in vertical scrollbar area
-                                if list_mx >= sb_x {
-                                    clicking_scrollbar = true;
-
-                                    let sb_h_f32 = list_h as f32
-                                        - if columns.is_empty() {
-                                            0.0
-                                        } else {
-                                            row_height as f32 + 1.0
-                                        };
-                                    let sb_y = if columns.is_empty() {
-                                        0
-                                    } else {
-                                        (row_height + 1) as i32
-                                    };
-                                    let thumb_h_f32 = ((data_visible as f32 / rows.len() as f32
-                                        * sb_h_f32)
-                                        .max(20.0 * scale))
-                                    .min(sb_h_f32);
-                                    let thumb_h = thumb_h_f32 as i32;
-                                    let max_thumb_y = (sb_h_f32 - thumb_h_f32) as i32;
-                                    let thumb_y = if rows.len() > data_visible {
-                                        (scroll_offset as f32 / (rows.len() - data_visible) as f32
-                                            * max_thumb_y as f32)
-                                            as i32
-                                    } else {
-                                        0
-                                    };
-
-                                    // Check if clicking specifically on the thumb for dragging
-                                    if list_my >= sb_y + thumb_y
-                                        && list_my < sb_y + thumb_y + thumb_h
-                                    {
-                                        v_thumb_drag = true;
-                                        v_thumb_drag_offset = Some(list_my - (sb_y + thumb_y));
-                                    }
-                                }
-                            }
-
-                            // Horizontal scrollbar area
-                            if total_content_width > list_w {
-                                let h_scrollbar_width = if h_scrollbar_hovered {
-                                    12.0 * scale
-                                } else {
-                                    8.0 * scale
-                                };
-                                let sb_h = h_scrollbar_width as i32;
-                                let sb_y = list_h as i32 - sb_h;
-
-                                // Block all clicks in horizontal scrollbar area
-                                if list_my >= sb_y {
-                                    clicking_scrollbar = true;
-
-                                    let sb_w_f32 = list_w as f32;
-                                    let sb_w = list_w as i32;
-                                    let max_scroll_u32 = total_content_width.saturating_sub(list_w);
-                                    let max_scroll = (max_scroll_u32 as i32).max(1);
-                                    let thumb_w_f32 =
-                                        ((list_w as f32 / total_content_width as f32 * sb_w_f32)
-                                            .max(20.0 * scale))
-                                        .min(sb_w_f32);
-                                    let thumb_w = thumb_w_f32 as i32;
-                                    let max_thumb_x = sb_w - thumb_w;
-                                    let thumb_x = if max_scroll > 0 {
-                                        (h_scroll_offset as f32 / max_scroll as f32
-                                            * max_thumb_x as f32)
-                                            as i32
-                                    } else {
-                                        0
-                                    };
-
-                                    // Check if clicking specifically on the thumb for dragging
-                                    if list_mx >= thumb_x && list_mx < thumb_x + thumb_w {
-                                        h_thumb_drag = true;
-                                        h_thumb_drag_offset = Some(list_mx - thumb_x);
-                                    }
-                                }
-                            }
-                        }
-                    }
-
-                    // Only process row selection if not clicking on scrollbar
+                    // Clicks on the scrollbars aren't row clicks
+                    let clicking_scrollbar =
+                        last_cursor_pos.is_some_and(|(mx, my)| scroll.over_bar(mx, my));
                     if !clicking_scrollbar {
                         if let Some(ri) = hovered_row {
                             match self.mode {
@@ -1070,92 +904,20 @@ impl ListBuilder {
                         }
                     }
                 }
-                WindowEvent::ButtonRelease(_, _) => {
-                    // End scrollbar thumb dragging
-                    v_thumb_drag = false;
-                    h_thumb_drag = false;
-                    v_thumb_drag_offset = None;
-                    h_thumb_drag_offset = None;
-                }
-                WindowEvent::Scroll(direction) => {
-                    if h_scroll_mode {
-                        // Shift + wheel: horizontal scroll
-                        match direction {
-                            crate::backend::ScrollDirection::Up => {
-                                if total_content_width > list_w {
-                                    h_scroll_offset = h_scroll_offset.saturating_sub(100);
-                                    needs_redraw = true;
-                                }
-                            }
-                            crate::backend::ScrollDirection::Down => {
-                                if total_content_width > list_w {
-                                    let max_scroll = total_content_width.saturating_sub(list_w);
-                                    h_scroll_offset = (h_scroll_offset + 100).min(max_scroll);
-                                    needs_redraw = true;
-                                }
-                            }
-                            _ => {}
-                        }
-                    } else {
-                        // Normal wheel: vertical scroll
-                        match direction {
-                            crate::backend::ScrollDirection::Up => {
-                                if scroll_offset > 0 {
-                                    scroll_offset = scroll_offset.saturating_sub(2);
-                                    needs_redraw = true;
-                                }
-                            }
-                            crate::backend::ScrollDirection::Down => {
-                                if scroll_offset + data_visible < rows.len() {
-                                    scroll_offset = (scroll_offset + 2)
-                                        .min(rows.len().saturating_sub(data_visible));
-                                    needs_redraw = true;
-                                }
-                            }
-                            crate::backend::ScrollDirection::Left => {
-                                if total_content_width > list_w {
-                                    h_scroll_offset = h_scroll_offset.saturating_sub(100);
-                                    needs_redraw = true;
-                                }
-                            }
-                            crate::backend::ScrollDirection::Right => {
-                                if total_content_width > list_w {
-                                    let max_scroll = total_content_width.saturating_sub(list_w);
-                                    h_scroll_offset = (h_scroll_offset + 100).min(max_scroll);
-                                    needs_redraw = true;
-                                }
-                            }
-                        }
-                    }
-                }
                 WindowEvent::KeyPress(key_event) => {
                     const KEY_UP: u32 = 0xff52;
                     const KEY_DOWN: u32 = 0xff54;
-                    const KEY_LEFT: u32 = 0xff51;
-                    const KEY_RIGHT: u32 = 0xff53;
-                    const KEY_LSHIFT: u32 = 0xffe1;
-                    const KEY_RSHIFT: u32 = 0xffe2;
                     const KEY_SPACE: u32 = 0x20;
                     const KEY_RETURN: u32 = 0xff0d;
                     const KEY_ESCAPE: u32 = 0xff1b;
 
-                    // Handle shift for scroll mode
-                    if key_event.keysym == KEY_LSHIFT || key_event.keysym == KEY_RSHIFT {
-                        h_scroll_mode = true;
-                        continue;
-                    } else {
-                        h_scroll_mode = false;
-                    }
-
                     match key_event.keysym {
                         KEY_UP => {
                             if self.mode == ListMode::Single {
                                 if let Some(sel) = single_selected {
                                     if sel > 0 {
                                         single_selected = Some(sel - 1);
-                                        if sel - 1 < scroll_offset {
-                                            scroll_offset = sel - 1;
-                                        }
+                                        scroll.scroll_into_view(sel - 1);
                                         needs_redraw = true;
                                     }
                                 } else if !rows.is_empty() {
@@ -1167,9 +929,7 @@ impl ListBuilder {
                                 if let Some(last) = last_selected {
                                     if last > 0 {
                                         single_selected = Some(last - 1);
-                                        if last - 1 < scroll_offset {
-                                            scroll_offset = last - 1;
-                                        }
+                                        scroll.scroll_into_view(last - 1);
                                         needs_redraw = true;
                                     }
                                 } else if !rows.is_empty() {
@@ -1183,9 +943,7 @@ impl ListBuilder {
                                 if let Some(sel) = single_selected {
                                     if sel + 1 < rows.len() {
                                         single_selected = Some(sel + 1);
-                                        if sel + 1 >= scroll_offset + data_visible {
-                                            scroll_offset = sel + 2 - data_visible;
-                                        }
+                                        scroll.scroll_into_view(sel + 1);
                                         needs_redraw = true;
                                     }
                                 } else if !rows.is_empty() {
@@ -1197,9 +955,7 @@ impl ListBuilder {
                                 if let Some(last) = last_selected {
                                     if last + 1 < rows.len() {
                                         single_selected = Some(last + 1);
-                                        if last + 1 >= scroll_offset + data_visible {
-                                            scroll_offset = last + 2 - data_visible;
-                                        }
+                                        scroll.scroll_into_view(last + 1);
                                         needs_redraw = true;
                                     }
                                 } else if !rows.is_empty() {
@@ -1208,19 +964,6 @@ impl ListBuilder {
                                 }
                             }
                         }
-                        KEY_LEFT => {
-                            if total_content_width > list_w {
-                                h_scroll_offset = h_scroll_offset.saturating_sub(100);
-                                needs_redraw = true;
-                            }
-                        }
-                        KEY_RIGHT => {
-                            if total_content_width > list_w {
-                                let max_scroll = total_content_width.saturating_sub(list_w);
-                                h_scroll_offset = (h_scroll_offset + 100).min(max_scroll);
-                                needs_redraw = true;
-                            }
-                        }
                         KEY_SPACE => {
                             if self.mode == ListMode::Checklist || self.mode == ListMode::Multiple {
                                 if let Some(ri) = hovered_row.or(single_selected) {
@@ -1241,18 +984,10 @@ impl ListBuilder {
                         _ => {}
                     }
                 }
-                WindowEvent::KeyRelease(key_event) => {
-                    const KEY_LSHIFT: u32 = 0xffe1;
-                    const KEY_RSHIFT: u32 = 0xffe2;
-
-                    // Handle shift release for scroll mode
-                    if key_event.keysym == KEY_LSHIFT || key_event.keysym == KEY_RSHIFT {
-                        h_scroll_mode = false;
-                    }
-                }
                 _ => {}
             }
 
+            needs_redraw |= scroll.process_event(&event);
             needs_redraw |= ok_button.process_event(&event);
             needs_redraw |= cancel_button.process_event(&event);
 
@@ -1264,96 +999,49 @@ impl ListBuilder {
             }
 
             while let Some(ev) = window.poll_for_event()? {
+                let ev = focus.process_event(ev, &mut [&mut ok_button, &mut cancel_button]);
                 match &ev {
                     WindowEvent::CloseRequested => {
                         return Ok(ListResult::Closed);
                     }
+                    WindowEvent::RedrawRequested => needs_redraw = true,
+                    WindowEvent::Resized(w, h) => new_size = Some((*w, *h)),
                     WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
                         last_cursor_pos = Some((pos.x as i32, pos.y as i32));
                     }
-                    WindowEvent::ButtonPress(button, _modifiers)
-                        if *button == MouseButton::Left =>
-                    {
-                        if let Some((list_mx, list_my)) = last_cursor_pos {
-                            // Check vertical scrollbar thumb
-                            if rows.len() > data_visible {
-                                let sb_x = list_w as i32 - (8.0 * scale) as i32;
-                                let sb_h_f32 = list_h as f32
-                                    - if columns.is_empty() {
-                                        0.0
-                                    } else {
-                                        row_height as f32 + 1.0
-                                    };
-                                let thumb_h_f32 = ((data_visible as f32 / rows.len() as f32
-                                    * sb_h_f32)
-                                    .max(20.0 * scale))
-                                .min(sb_h_f32);
-                                let thumb_h = thumb_h_f32 as i32;
-                                let max_thumb_y = (sb_h_f32 - thumb_h_f32) as i32;
-                                let thumb_y = if rows.len() > data_visible {
-                                    (scroll_offset as f32 / (rows.len() - data_visible) as f32
-                                        * max_thumb_y as f32)
-                                        as i32
-                                } else {
-                                    0
-                                };
-
-                                if list_mx >= sb_x
-                                    && list_mx < sb_x + (8.0 * scale) as i32
-                                    && list_my >= thumb_y
-                                    && list_my < thumb_y + thumb_h
-                                {
-                                    v_thumb_drag = true;
-                                    v_thumb_drag_offset = Some(list_my - thumb_y);
-                                }
-                            }
-
-                            // Check horizontal scrollbar thumb
-                            if total_content_width > list_w {
-                                let sb_h = (6.0 * scale) as i32;
-                                let sb_y = list_h as i32 - sb_h;
-                                let sb_w_f32 = list_w as f32;
-                                let sb_w = list_w as i32;
-                                let max_scroll_u32 = total_content_width.saturating_sub(list_w);
-                                let max_scroll = (max_scroll_u32 as i32).max(1);
-                                let thumb_w_f32 = ((list_w as f32 / total_content_width as f32
-                                    * sb_w_f32)
-                                    .max(20.0 * scale))
-                                .min(sb_w_f32);
-                                let thumb_w = thumb_w_f32 as i32;
-                                let max_thumb_x = sb_w - thumb_w;
-                                let thumb_x = if max_scroll > 0 {
-                                    (h_scroll_offset as f32 / max_scroll as f32
-                                        * max_thumb_x as f32)
-                                        as i32
-                                } else {
-                                    0
-                                };
-
-                                if list_my >= sb_y
-                                    && list_my < sb_y + sb_h
-                                    && list_mx >= thumb_x
-                                    && list_mx < thumb_x + thumb_w
-                                {
-                                    h_thumb_drag = true;
-                                    h_thumb_drag_offset = Some(list_mx - thumb_x);
-                                }
-                            }
-                        }
-                    }
-                    WindowEvent::ButtonRelease(_, _) => {
-                        v_thumb_drag = false;
-                        h_thumb_drag = false;
-                        v_thumb_drag_offset = None;
-                        h_thumb_drag_offset = None;
-                    }
                     _ => {}
                 }
 
+                needs_redraw |= scroll.process_event(&ev);
                 needs_redraw |= ok_button.process_event(&ev);
                 needs_redraw |= cancel_button.process_event(&ev);
             }
 
+            let current = selection(&rows, &selected, single_selected, self.mode);
+            if current != last_selection {
+                hooks.changed(&current);
+                last_selection = current;
+            }
+
+            // Stretch the list to fill the new size
+            if let Some((width, height)) = new_size.take() {
+                let button_y = place_buttons(&mut ok_button, &mut cancel_button, width, height);
+                list_w = width - padding * 2;
+                prompt.layout(&font, colors.text, Some(list_w));
+                list_h = (button_y - list_bottom_gap - list_y).max(row_height as i32 * 2) as u32;
+                visible_rows = (list_h / row_height) as usize;
+                data_visible = if columns.is_empty() {
+                    visible_rows
+                } else {
+                    visible_rows.saturating_sub(1)
+                };
+                scroll.set_size(list_w, list_h);
+                scroll.set_content(rows.len(), data_visible);
+                canvas = Canvas::new(width, height);
+                list_canvas = Canvas::new(list_w, list_h);
+                needs_redraw = true;
+            }
+
             if needs_redraw {
                 draw(
                     &mut canvas,
@@ -1361,20 +1049,18 @@ impl ListBuilder {
                     colors,
                     &font,
                     &self.title,
-                    &self.text,
+                    &prompt,
                     &checkbox_column_header,
                     &columns,
                     &display_rows,
                     &col_widths,
                     &selected,
                     single_selected,
-                    scroll_offset,
-                    h_scroll_offset,
+                    &scroll,
                     hovered_row,
                     self.mode,
                     &ok_button,
                     &cancel_button,
-                    total_content_width,
                     padding,
                     row_height,
                     checkbox_size,
@@ -1384,15 +1070,132 @@ impl ListBuilder {
                     list_w,
                     list_h,
                     visible_rows,
-                    text_y,
                     scale,
-                    v_scrollbar_hovered,
-                    h_scrollbar_hovered,
                 );
                 window.set_contents(&canvas)?;
             }
         }
     }
+
+    /// Shows the dialog on the terminal instead of in a window.
+    #[cfg(feature = "tui")]
+    fn show_tui(
+        &self,
+        hooks: &mut Hooks<[String]>,
+        columns: &[&str],
+        display_rows: &[Vec<String>],
+        rows: &[Vec<String>],
+        mut selected: Vec<bool>,
+    ) -> Result<ListResult, Error> {
+        use crate::tui::{self, BOLD, Key, REVERSE, Terminal};
+
+        let mut terminal = Terminal::open()?;
+        let mut cursor = 0;
+        let mut scroll = 0;
+        let hint = match self.mode {
+            ListMode::Single => tr("Enter: Select   Esc: Cancel"),
+            _ => tr("Space: Toggle   Enter: OK   Esc: Cancel"),
+        };
+
+        // Each column is as wide as its widest cell
+        let mut widths: Vec<usize> = columns.iter().map(|c| c.chars().count()).collect();
+        for row in display_rows {
+            for (idx, cell) in row.iter().enumerate() {
+                if idx >= widths.len() {
+                    widths.push(0);
+                }
+                widths[idx] = widths[idx].max(cell.chars().count());
+            }
+        }
+        let format_row = |marker: &str, cells: &[&str], width: usize| {
+            let mut line = marker.to_string();
+            for (cell, &cell_width) in cells.iter().zip(&widths) {
+                let room = width.saturating_sub(line.chars().count());
+                if room == 0 {
+                    break;
+                }
+                let cell = tui::fit(cell, cell_width.min(room));
+                line.push_str(&format!("{cell:<cell_width$}  "));
+            }
+            tui::fit(line.trim_end(), width)
+        };
+
+        loop {
+            let (width, height) = terminal.size();
+            let mut lines = tui::header(&self.title, width);
+            if !self.text.is_empty() {
+                lines.extend(tui::wrap(&self.text, width));
+                lines.push(String::new());
+            }
+            if !columns.is_empty() {
+                lines.push(format!("{BOLD}{}", format_row("    ", columns, width)));
+            }
+
+            // Rows fill what the hint below leaves
+            let visible = height.saturating_sub(lines.len() + 2).max(1);
+            if cursor < scroll {
+                scroll = cursor;
+            } else if cursor >= scroll + visible {
+                scroll = cursor + 1 - visible;
+            }
+            for (idx, row) in display_rows.iter().enumerate().skip(scroll).take(visible) {
+                let marker = match (self.mode, selected[idx]) {
+                    (ListMode::Single, _) => "    ",
+                    (ListMode::Radiolist, true) => "(*) ",
+                    (ListMode::Radiolist, false) => "( ) ",
+                    (_, true) => "[x] ",
+                    (_, false) => "[ ] ",
+                };
+                let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+                let line = format_row(marker, &cells, width);
+                if idx == cursor {
+                    lines.push(format!("{REVERSE}{line:<width$}"));
+                } else {
+                    lines.push(line);
+                }
+            }
+            lines.push(String::new());
+            lines.push(tui::fit(&hint, width));
+            terminal.present(&lines)?;
+
+            let last = display_rows.len().saturating_sub(1);
+            match terminal.read_key(None)? {
+                Some(Key::Up) => cursor = cursor.saturating_sub(1),
+                Some(Key::Down) => cursor = (cursor + 1).min(last),
+                Some(Key::PageUp) => cursor = cursor.saturating_sub(visible),
+                Some(Key::PageDown) => cursor = (cursor + visible).min(last),
+                Some(Key::Home) => cursor = 0,
+                Some(Key::End) => cursor = last,
+                Some(Key::Char(' ')) if !display_rows.is_empty() => {
+                    match self.mode {
+                        ListMode::Single => {}
+                        ListMode::Radiolist => {
+                            selected.fill(false);
+                            selected[cursor] = true;
+                        }
+                        ListMode::Checklist | ListMode::Multiple => {
+                            selected[cursor] = !selected[cursor];
+                        }
+                    }
+                    hooks.changed(&selection(rows, &selected, Some(cursor), self.mode));
+                }
+                Some(Key::Enter) => {
+                    // Without a choice made, the highlighted row is the one
+                    // meant, except for checklists where none is a choice
+                    if matches!(self.mode, ListMode::Radiolist | ListMode::Multiple)
+                        && !selected.contains(&true)
+                        && let Some(sel) = selected.get_mut(cursor)
+                    {
+                        *sel = true;
+                    }
+                    return Ok(get_result(rows, &selected, Some(cursor), self.mode));
+                }
+                Some(Key::Escape) => return Ok(ListResult::Cancelled),
+                Some(Key::Interrupt) => return Ok(ListResult::Closed),
+                _ => {}
+            }
+        }
+    }
 }
 
 impl Default for ListBuilder {
@@ -1407,6 +1210,21 @@ fn get_result(
     single_selected: Option<usize>,
     mode: ListMode,
 ) -> ListResult {
+    let result = selection(rows, selected, single_selected, mode);
+    if result.is_empty() {
+        ListResult::Cancelled
+    } else {
+        ListResult::Selected(result)
+    }
+}
+
+/// The first column of the selected rows.
+fn selection(
+    rows: &[Vec<String>],
+    selected: &[bool],
+    single_selected: Option<usize>,
+    mode: ListMode,
+) -> Vec<String> {
     let mut result = Vec::new();
 
     match mode {
@@ -1432,11 +1250,7 @@ fn get_result(
         }
     }
 
-    if result.is_empty() {
-        ListResult::Cancelled
-    } else {
-        ListResult::Selected(result)
-    }
+    result
 }
 
 fn darken(color: crate::render::Rgba, amount: f32) -> crate::render::Rgba {
@@ -1447,54 +1261,12 @@ fn darken(color: crate::render::Rgba, amount: f32) -> crate::render::Rgba {
     )
 }
 
-fn draw_checkbox(
-    canvas: &mut Canvas,
-    x: i32,
-    y: i32,
-    checked: bool,
-    colors: &Colors,
-    checkbox_size: u32,
-    scale: f32,
-) {
-    // Box
-    canvas.fill_rounded_rect(
-        x as f32,
-        y as f32,
-        checkbox_size as f32,
-        checkbox_size as f32,
-        3.0 * scale,
-        colors.input_bg,
-    );
-    canvas.stroke_rounded_rect(
-        x as f32,
-        y as f32,
-        checkbox_size as f32,
-        checkbox_size as f32,
-        3.0 * scale,
-        colors.input_border,
-        1.0,
-    );
-
-    // Check mark
-    if checked {
-        let inset = (3.0 * scale) as i32;
-        canvas.fill_rounded_rect(
-            (x + inset) as f32,
-            (y + inset) as f32,
-            (checkbox_size as i32 - inset * 2) as f32,
-            (checkbox_size as i32 - inset * 2) as f32,
-            2.0 * scale,
-            colors.input_border_focused,
-        );
-    }
-}
-
 fn draw_radio(
     canvas: &mut Canvas,
     x: i32,
     y: i32,
     checked: bool,
-    colors: &Colors,
+    colors: &Theme,
     checkbox_size: u32,
     _scale: f32,
 ) {
@@ -1502,35 +1274,12 @@ fn draw_radio(
     let cy = y as f32 + checkbox_size as f32 / 2.0;
     let r = checkbox_size as f32 / 2.0;
 
-    // Outer circle (using rounded rect as approximation)
-    canvas.fill_rounded_rect(
-        x as f32,
-        y as f32,
-        checkbox_size as f32,
-        checkbox_size as f32,
-        r,
-        colors.input_bg,
-    );
-    canvas.stroke_rounded_rect(
-        x as f32,
-        y as f32,
-        checkbox_size as f32,
-        checkbox_size as f32,
-        r,
-        colors.input_border,
-        1.0,
-    );
+    // Outer circle
+    canvas.fill_circle(cx, cy, r, colors.input_bg);
+    canvas.stroke_circle(cx, cy, r - 0.5, colors.input_border, 1.0);
 
     // Inner dot
     if checked {
-        let inner_r = r * 0.5;
-        canvas.fill_rounded_rect(
-            cx - inner_r,
-            cy - inner_r,
-            inner_r * 2.0,
-            inner_r * 2.0,
-            inner_r,
-            colors.input_border_focused,
-        );
+        canvas.fill_circle(cx, cy, r * 0.5, colors.input_border_focused);
     }
 }
@@ -0,0 +1,488 @@
+//! Login dialog implementation (username, password and "Remember me").
+
+use crate::{
+    backend::{CursorShape, MouseButton, Window, WindowEvent},
+    error::Error,
+    render::{Canvas, rgb},
+    ui::{
+        Theme, WindowPlacement, create_window,
+        strings::tr,
+        widgets::{
+            Widget, button::Button, focus::FocusManager, label::Label, point_in_rect,
+            text_input::TextInput,
+        },
+    },
+};
+
+const BASE_PADDING: u32 = 20;
+const BASE_FIELD_HEIGHT: u32 = 32;
+const BASE_FIELD_SPACING: u32 = 12;
+const BASE_LABEL_WIDTH: u32 = 100;
+const BASE_INPUT_WIDTH: u32 = 250;
+const BASE_CHECKBOX_SIZE: u32 = 16;
+const BASE_CHECKBOX_SPACING: u32 = 8;
+const BASE_BUTTON_SPACING: u32 = 10;
+
+const KEY_RETURN: u32 = 0xff0d;
+const KEY_KP_ENTER: u32 = 0xff8d;
+const KEY_ESCAPE: u32 = 0xff1b;
+
+/// Login dialog result.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LoginResult {
+    /// User clicked OK.
+    Login {
+        username: String,
+        password: String,
+        /// Whether "Remember me" was checked (always false without the checkbox).
+        remember: bool,
+    },
+    /// User cancelled the dialog.
+    Cancelled,
+    /// Dialog was closed.
+    Closed,
+}
+
+impl LoginResult {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            LoginResult::Login {
+                ..
+            } => 0,
+            LoginResult::Cancelled => 1,
+            LoginResult::Closed => 255,
+        }
+    }
+}
+
+/// Login dialog builder.
+pub struct LoginBuilder {
+    title: String,
+    text: String,
+    username: String,
+    username_label: String,
+    password_label: String,
+    remember_label: Option<String>,
+    remember: bool,
+    width: Option<u32>,
+    height: Option<u32>,
+    colors: Option<Theme>,
+    pub(super) placing: WindowPlacement,
+}
+
+impl LoginBuilder {
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            text: String::new(),
+            username: String::new(),
+            username_label: tr("Username").to_string(),
+            password_label: tr("Password").to_string(),
+            remember_label: None,
+            remember: false,
+            width: None,
+            height: None,
+            colors: None,
+            placing: WindowPlacement::default(),
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    /// Prefills the username; the password field then starts focused.
+    pub fn username(mut self, username: &str) -> Self {
+        self.username = username.to_string();
+        self
+    }
+
+    /// Label for the username field (default: "Username").
+    pub fn username_label(mut self, label: &str) -> Self {
+        self.username_label = label.to_string();
+        self
+    }
+
+    /// Label for the password field (default: "Password").
+    pub fn password_label(mut self, label: &str) -> Self {
+        self.password_label = label.to_string();
+        self
+    }
+
+    /// Adds a checkbox with the given label (e.g. "Remember me").
+    pub fn remember(mut self, label: &str) -> Self {
+        self.remember_label = Some(label.to_string());
+        self
+    }
+
+    /// Sets whether the checkbox starts checked.
+    pub fn remember_checked(mut self, checked: bool) -> Self {
+        self.remember = checked;
+        self
+    }
+
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
+        self
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn show(self) -> Result<LoginResult, Error> {
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
+        let content_w = BASE_LABEL_WIDTH + colors.space(BASE_FIELD_SPACING) + BASE_INPUT_WIDTH;
+
+        // Measure the prompt at logical scale to size the window
+        let temp_font = colors.font(1.0);
+        let mut temp_prompt = Label::new(&self.text).with_wrap(true);
+        temp_prompt.layout(&temp_font, colors.text, Some(content_w));
+        let logical_prompt_h = if self.text.is_empty() {
+            0
+        } else {
+            temp_prompt.height() + colors.space(BASE_FIELD_SPACING)
+        };
+        let temp_ok = Button::new(&tr("OK"), &temp_font, colors, 1.0);
+        drop(temp_font);
+        let checkbox_row = if self.remember_label.is_some() {
+            BASE_CHECKBOX_SIZE + colors.space(BASE_FIELD_SPACING)
+        } else {
+            0
+        };
+
+        let logical_width = self
+            .width
+            .unwrap_or(content_w + colors.space(BASE_PADDING) * 2);
+        let logical_height = self.height.unwrap_or(
+            colors.space(BASE_PADDING) * 2
+                + logical_prompt_h
+                + (BASE_FIELD_HEIGHT + colors.space(BASE_FIELD_SPACING)) * 2
+                + checkbox_row
+                + colors.space(BASE_FIELD_SPACING)
+                + temp_ok.height(),
+        );
+
+        let mut window = create_window(
+            logical_width as u16,
+            logical_height as u16,
+            colors,
+            &self.placing,
+        )?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Login")
+        } else {
+            self.title.as_str().into()
+        })?;
+
+        // Get the actual scale factor from the window (compositor scale)
+        let scale = window.scale_factor();
+        let physical_width = (logical_width as f32 * scale) as u32;
+        let physical_height = (logical_height as f32 * scale) as u32;
+
+        // Now create everything at PHYSICAL scale
+        let font = colors.font(scale);
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
+        let field_height = (BASE_FIELD_HEIGHT as f32 * scale) as u32;
+        let field_spacing = (colors.space(BASE_FIELD_SPACING) as f32 * scale) as u32;
+        let label_width = (BASE_LABEL_WIDTH as f32 * scale) as u32;
+        let checkbox_size = (BASE_CHECKBOX_SIZE as f32 * scale) as u32;
+        let checkbox_spacing = (colors.space(BASE_CHECKBOX_SPACING) as f32 * scale) as i32;
+
+        let mut prompt = Label::new(&self.text).with_wrap(true);
+        prompt.layout(&font, colors.text, Some(physical_width - padding * 2));
+
+        let mut y = padding as i32;
+        prompt.set_position(padding as i32, y);
+        if !self.text.is_empty() {
+            y += (prompt.height() + field_spacing) as i32;
+        }
+
+        // Inputs take whatever width is left next to the labels
+        let input_x = padding as i32 + (label_width + field_spacing) as i32;
+        let input_w = (physical_width as i32 - padding as i32 - input_x).max(1) as u32;
+        let mut inputs = [
+            TextInput::new(input_w).with_default_text(&self.username),
+            TextInput::new(input_w).with_password(true),
+        ];
+        let mut labels = [&self.username_label, &self.password_label].map(|label| {
+            let mut label = Label::new(label).with_wrap(true);
+            label.layout(&font, colors.text, Some(label_width));
+            label
+        });
+        for (label, input) in labels.iter_mut().zip(&mut inputs) {
+            input.set_position(input_x, y);
+            label.set_position(
+                padding as i32,
+                y + (field_height as i32 - label.height() as i32) / 2,
+            );
+            y += (field_height + field_spacing) as i32;
+        }
+
+        // The checkbox lines up with the inputs; its label is clickable too
+        let checkbox_x = input_x;
+        let checkbox_y = y;
+        let checkbox_label = self.remember_label.as_ref().map(|label| {
+            let mut label = Label::new(label).with_wrap(true);
+            label.layout(
+                &font,
+                colors.text,
+                Some(input_w.saturating_sub(checkbox_size)),
+            );
+            label.set_position(
+                checkbox_x + checkbox_size as i32 + checkbox_spacing,
+                checkbox_y + (checkbox_size as i32 - label.height() as i32) / 2,
+            );
+            label
+        });
+        let checkbox_row_w = checkbox_label.as_ref().map_or(0, |label| {
+            checkbox_size + checkbox_spacing as u32 + label.width()
+        });
+        let mut remember = self.remember;
+        let mut checkbox_hovered = false;
+        let mut hovered_input: Option<usize> = None;
+
+        let mut ok_button = Button::new(&tr("OK"), &font, colors, scale);
+        let mut cancel_button = Button::new(&tr("Cancel"), &font, colors, scale);
+        let button_y = (physical_height - padding) as i32 - ok_button.height() as i32;
+        let mut button_x = physical_width as i32 - padding as i32;
+        button_x -= cancel_button.width() as i32;
+        cancel_button.set_position(button_x, button_y);
+        button_x -=
+            (colors.space(BASE_BUTTON_SPACING) as f32 * scale) as i32 + ok_button.width() as i32;
+        ok_button.set_position(button_x, button_y);
+        ok_button.set_default(true);
+
+        let mut focus = FocusManager::new();
+        focus.focus(
+            Some(if self.username.is_empty() { 0 } else { 1 }),
+            &mut focus_order(&mut inputs, &mut ok_button, &mut cancel_button),
+        );
+
+        let mut canvas = Canvas::new(physical_width, physical_height);
+
+        let draw = |canvas: &mut Canvas,
+                    inputs: &[TextInput],
+                    remember: bool,
+                    checkbox_hovered: bool,
+                    ok_button: &Button,
+                    cancel_button: &Button| {
+            let width = canvas.width() as f32;
+            let height = canvas.height() as f32;
+            canvas.fill_dialog_bg(
+                width,
+                height,
+                colors.window_bg,
+                colors.window_border,
+                colors.window_radius * scale,
+                colors.border_width,
+            );
+
+            prompt.draw(canvas, colors);
+
+            for (label, input) in labels.iter().zip(inputs) {
+                label.draw(canvas, colors);
+                input.draw_to(canvas, colors, &font);
+            }
+
+            if let Some(label) = &checkbox_label {
+                let bg = if checkbox_hovered {
+                    darken(colors.input_bg, 0.06)
+                } else {
+                    colors.input_bg
+                };
+                canvas.fill_rounded_rect(
+                    checkbox_x as f32,
+                    checkbox_y as f32,
+                    checkbox_size as f32,
+                    checkbox_size as f32,
+                    3.0 * scale,
+                    bg,
+                );
+                canvas.stroke_rounded_rect(
+                    checkbox_x as f32,
+                    checkbox_y as f32,
+                    checkbox_size as f32,
+                    checkbox_size as f32,
+                    3.0 * scale,
+                    colors.input_border,
+                    1.0,
+                );
+                if remember {
+                    let inset = (3.0 * scale) as i32;
+                    canvas.fill_rounded_rect(
+                        (checkbox_x + inset) as f32,
+                        (checkbox_y + inset) as f32,
+                        (checkbox_size as i32 - inset * 2) as f32,
+                        (checkbox_size as i32 - inset * 2) as f32,
+                        2.0 * scale,
+                        colors.input_border_focused,
+                    );
+                }
+                label.draw(canvas, colors);
+            }
+
+            ok_button.draw_to(canvas, colors, &font);
+            cancel_button.draw_to(canvas, colors, &font);
+        };
+
+        draw(
+            &mut canvas,
+            &inputs,
+            remember,
+            checkbox_hovered,
+            &ok_button,
+            &cancel_button,
+        );
+        window.set_contents(&canvas)?;
+        window.show()?;
+
+        // Event loop; each pass handles every pending event, then redraws once
+        loop {
+            let mut next = Some(window.wait_for_event()?);
+            let mut needs_redraw = false;
+            let mut submit = false;
+
+            while let Some(event) = next.take() {
+                let event = focus.process_event(
+                    event,
+                    &mut focus_order(&mut inputs, &mut ok_button, &mut cancel_button),
+                );
+                match &event {
+                    WindowEvent::CloseRequested => return Ok(LoginResult::Closed),
+                    WindowEvent::RedrawRequested => needs_redraw = true,
+                    WindowEvent::CursorMove(pos) | WindowEvent::CursorEnter(pos) => {
+                        let (px, py) = (pos.x as i32, pos.y as i32);
+                        hovered_input = inputs.iter().position(|input| {
+                            point_in_rect(
+                                px,
+                                py,
+                                input.x(),
+                                input.y(),
+                                input.width(),
+                                input.height(),
+                            )
+                        });
+                        let _ = window.set_cursor(if hovered_input.is_some() {
+                            CursorShape::Text
+                        } else {
+                            CursorShape::Default
+                        });
+
+                        let hovered = point_in_rect(
+                            px,
+                            py,
+                            checkbox_x,
+                            checkbox_y,
+                            checkbox_row_w,
+                            checkbox_size,
+                        );
+                        needs_redraw |= hovered != checkbox_hovered;
+                        checkbox_hovered = hovered;
+                    }
+                    WindowEvent::CursorLeave => {
+                        needs_redraw |= checkbox_hovered;
+                        checkbox_hovered = false;
+                        hovered_input = None;
+                    }
+                    WindowEvent::ButtonPress(MouseButton::Left, _) => {
+                        if checkbox_hovered {
+                            remember = !remember;
+                        }
+                        needs_redraw = true;
+                    }
+                    WindowEvent::PrimaryPaste(..) => {
+                        if let Some(i) = hovered_input
+                            && focus.focused() != Some(i)
+                        {
+                            focus.focus(
+                                Some(i),
+                                &mut focus_order(&mut inputs, &mut ok_button, &mut cancel_button),
+                            );
+                        }
+                    }
+                    WindowEvent::KeyPress(key) => {
+                        match key.keysym {
+                            KEY_RETURN | KEY_KP_ENTER => submit = true,
+                            KEY_ESCAPE => return Ok(LoginResult::Cancelled),
+                            _ => {}
+                        }
+                    }
+                    _ => {}
+                }
+
+                for input in &mut inputs {
+                    needs_redraw |= input.process_event(&event);
+                    submit |= input.was_submitted();
+                }
+
+                needs_redraw |= ok_button.process_event(&event);
+                submit |= ok_button.was_clicked();
+                needs_redraw |= cancel_button.process_event(&event);
+                if cancel_button.was_clicked() {
+                    return Ok(LoginResult::Cancelled);
+                }
+
+                next = window.poll_for_event()?;
+            }
+
+            if submit {
+                let [username, password] = &inputs;
+                return Ok(LoginResult::Login {
+                    username: username.text().to_string(),
+                    password: password.text().to_string(),
+                    remember: remember && self.remember_label.is_some(),
+                });
+            }
+
+            if needs_redraw {
+                draw(
+                    &mut canvas,
+                    &inputs,
+                    remember,
+                    checkbox_hovered,
+                    &ok_button,
+                    &cancel_button,
+                );
+                window.set_contents(&canvas)?;
+            }
+        }
+    }
+}
+
+/// The dialog's widgets in Tab order: the fields, then the buttons.
+fn focus_order<'a>(
+    inputs: &'a mut [TextInput; 2],
+    ok_button: &'a mut Button,
+    cancel_button: &'a mut Button,
+) -> [&'a mut dyn Widget; 4] {
+    let [username, password] = inputs;
+    [username, password, ok_button, cancel_button]
+}
+
+impl Default for LoginBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn darken(color: crate::render::Rgba, amount: f32) -> crate::render::Rgba {
+    rgb(
+        (color.r as f32 * (1.0 - amount)) as u8,
+        (color.g as f32 * (1.0 - amount)) as u8,
+        (color.b as f32 * (1.0 - amount)) as u8,
+    )
+}
@@ -3,12 +3,21 @@
 use std::time::{Duration, Instant};
 
 use crate::{
-    backend::{MouseButton, Window, WindowEvent, create_window},
+    backend::{CursorShape, MouseButton, Window, WindowEvent, WindowHandle},
     error::Error,
     render::{Canvas, Font, rgb},
     ui::{
-        ButtonPreset, Colors, DialogResult, Icon,
-        widgets::{Widget, button::Button},
+        ButtonPreset, DialogResult, Icon, Theme, WindowPlacement, create_window,
+        hooks::Hooks,
+        widgets::{
+            Widget,
+            button::Button,
+            focus::FocusManager,
+            label::{Ellipsize, Label},
+            link::Link,
+            point_in_rect,
+            switch::Switch,
+        },
     },
 };
 
@@ -16,7 +25,21 @@ const BASE_ICON_SIZE: u32 = 48;
 const BASE_PADDING: u32 = 20;
 const BASE_BUTTON_SPACING: u32 = 10;
 const BASE_MIN_WIDTH: u32 = 150;
-const BASE_MAX_TEXT_WIDTH: f32 = 150.0;
+const BASE_MIN_TEXT_WIDTH: u32 = 150;
+const BASE_WRAP_WIDTH: u32 = 360;
+const BASE_MAX_WRAP_WIDTH: u32 = 640;
+const BASE_WRAP_WIDTH_STEP: u32 = 40;
+const BASE_MAX_TEXT_HEIGHT: u32 = 400;
+const BASE_CHECKBOX_SIZE: u32 = 16;
+const BASE_CHECKBOX_SPACING: u32 = 8;
+const BASE_SWITCH_SPACING: u32 = 8;
+
+const KEY_RETURN: u32 = 0xff0d;
+const KEY_KP_ENTER: u32 = 0xff8d;
+const KEY_ESCAPE: u32 = 0xff1b;
+
+/// Draws the content given to [`MessageBuilder::custom_content`]
+type DrawContent = Box<dyn Fn(&mut Canvas) + Send>;
 
 /// Message dialog builder.
 pub struct MessageBuilder {
@@ -32,7 +55,15 @@ pub struct MessageBuilder {
     ellipsize: bool,
     switch: bool,
     extra_buttons: Vec<String>,
-    colors: Option<&'static Colors>,
+    checkbox_text: Option<String>,
+    switches: Vec<(String, bool)>,
+    links: Vec<(String, String)>,
+    /// Logical size of the area below the text, and what draws it
+    content: Option<(u32, u32, DrawContent)>,
+    default_button: Option<usize>,
+    colors: Option<Theme>,
+    hooks: Hooks,
+    pub(super) placing: WindowPlacement,
 }
 
 impl MessageBuilder {
@@ -50,7 +81,14 @@ impl MessageBuilder {
             ellipsize: false,
             switch: false,
             extra_buttons: Vec::new(),
+            checkbox_text: None,
+            switches: Vec::new(),
+            links: Vec::new(),
+            content: None,
+            default_button: None,
             colors: None,
+            hooks: Hooks::default(),
+            placing: WindowPlacement::default(),
         }
     }
 
@@ -80,8 +118,8 @@ impl MessageBuilder {
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
         self
     }
 
@@ -120,11 +158,99 @@ impl MessageBuilder {
         self
     }
 
-    pub fn show(self) -> Result<DialogResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+    /// Add a checkbox below the text (e.g., "Do not show this again").
+    /// Its state is reported through [`DialogResult::ButtonWithCheckbox`].
+    pub fn suppress_checkbox(mut self, label: &str) -> Self {
+        self.checkbox_text = Some(label.to_string());
+        self
+    }
+
+    /// Add on/off switches below the text, each a label and whether it
+    /// starts on. Their states are reported through
+    /// [`DialogResult::ButtonWithSwitches`].
+    pub fn switches(mut self, switches: &[(&str, bool)]) -> Self {
+        self.switches
+            .extend(switches.iter().map(|&(label, on)| (label.to_string(), on)));
+        self
+    }
+
+    /// Add a link below the text that opens `uri` when clicked. The URI
+    /// itself is shown if `text` is empty.
+    pub fn link(mut self, text: &str, uri: &str) -> Self {
+        self.links.push((text.to_string(), uri.to_string()));
+        self
+    }
+
+    /// Add an area of `width` by `height` logical pixels below the text,
+    /// drawn by `draw` when the dialog is shown, for a preview of what the
+    /// message is about. The canvas it is given is at the window's scale,
+    /// so it may be bigger; draw relative to its size.
+    pub fn custom_content(
+        mut self,
+        width: u32,
+        height: u32,
+        draw: impl Fn(&mut Canvas) + Send + 'static,
+    ) -> Self {
+        self.content = Some((width, height, Box::new(draw)));
+        self
+    }
+
+    /// Set the button activated by Enter and drawn with an accent outline.
+    /// Indices count preset buttons first, then extra buttons. Defaults to 0.
+    pub fn default_button(mut self, idx: usize) -> Self {
+        self.default_button = Some(idx);
+        self
+    }
+
+    /// Attach the dialog to another window, keeping it modal and on top of
+    /// it. The same as [`center_on_parent`](Self::center_on_parent).
+    pub fn parent(self, parent: WindowHandle) -> Self {
+        self.center_on_parent(parent)
+    }
+
+    /// Calls `f` with the index of the button the dialog is dismissed
+    /// with, counted as in [`DialogResult::Button`].
+    pub fn on_button(mut self, f: impl FnMut(usize) + Send + 'static) -> Self {
+        self.hooks.button = Some(Box::new(f));
+        self
+    }
+
+    /// Calls `f` when the dialog is dismissed with its Cancel or No
+    /// button, Escape, or by closing it.
+    pub fn on_cancel(mut self, f: impl FnMut() + Send + 'static) -> Self {
+        self.hooks.cancel = Some(Box::new(f));
+        self
+    }
+
+    pub fn show(mut self) -> Result<DialogResult, Error> {
+        let mut hooks = std::mem::take(&mut self.hooks);
+        let result = self.run()?;
+        match result {
+            DialogResult::Button(idx)
+            | DialogResult::ButtonWithCheckbox(idx, _)
+            | DialogResult::ButtonWithSwitches(idx, _) => {
+                hooks.dismissed(Some(idx), Some(idx) == self.cancel_index());
+            }
+            DialogResult::Closed => hooks.dismissed(None, true),
+            DialogResult::Timeout => {}
+        }
+        Ok(result)
+    }
+
+    /// The preset's Cancel/No button, which Escape activates.
+    fn cancel_index(&self) -> Option<usize> {
+        if self.switch {
+            None
+        } else {
+            self.buttons.cancel_index()
+        }
+    }
+
+    fn run(&self) -> Result<DialogResult, Error> {
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
 
         // First pass: calculate LOGICAL dimensions using a temporary font at scale 1.0
-        let temp_font = Font::load(1.0);
+        let temp_font = colors.font(1.0);
         let mut labels = self.buttons.labels();
 
         // Apply --switch mode: if switch is true, use only extra buttons
@@ -135,6 +261,17 @@ impl MessageBuilder {
             labels.extend(self.extra_buttons.clone());
         }
 
+        // Enter activates the default button, Escape the preset's Cancel/No button
+        let button_count = labels.len();
+        let default_idx =
+            (button_count > 0).then(|| self.default_button.unwrap_or(0).min(button_count - 1));
+        let cancel_idx = self.cancel_index();
+
+        #[cfg(feature = "tui")]
+        if crate::tui::enabled() {
+            return self.show_tui(&labels, default_idx, cancel_idx);
+        }
+
         // Reverse labels so that when we position them right-to-left,
         // the last buttons (standard Yes/No) appear on the right
         labels.reverse();
@@ -142,15 +279,15 @@ impl MessageBuilder {
         // Calculate logical button widths and determine layout
         let temp_buttons: Vec<Button> = labels
             .iter()
-            .map(|l| Button::new(l, &temp_font, 1.0))
+            .map(|l| Button::new(l, &temp_font, colors, 1.0))
             .collect();
 
         // Calculate total width if all buttons are in one row
         let total_buttons_width: u32 = temp_buttons.iter().map(|b| b.width()).sum::<u32>()
-            + (temp_buttons.len().saturating_sub(1) as u32 * BASE_BUTTON_SPACING);
+            + (temp_buttons.len().saturating_sub(1) as u32 * colors.space(BASE_BUTTON_SPACING));
 
         // Determine button layout: vertical if they don't fit, horizontal if they do
-        let available_width = BASE_MAX_TEXT_WIDTH as u32 + BASE_PADDING * 2;
+        let available_width = BASE_MIN_TEXT_WIDTH + colors.space(BASE_PADDING) * 2;
         let use_vertical_layout = total_buttons_width > available_width || temp_buttons.len() > 3;
 
         let logical_buttons_width = if use_vertical_layout {
@@ -161,23 +298,55 @@ impl MessageBuilder {
         };
 
         let logical_icon_width = if self.icon.is_some() {
-            BASE_ICON_SIZE + BASE_PADDING
+            BASE_ICON_SIZE + colors.space(BASE_PADDING)
         } else {
             0
         };
 
-        // --width specifies text area width, not total window width
-        let text_width = self.width.map(|w| w as f32).unwrap_or(BASE_MAX_TEXT_WIDTH);
+        // --width specifies text area width, not total window width.
+        // Otherwise short text keeps its natural width, and long text wraps at a
+        // comfortable width that widens before the window grows too tall.
+        let (natural_width, _) = temp_font
+            .render(&self.text)
+            .with_markup(!self.no_markup)
+            .measure();
+        let natural_width = natural_width.ceil() as u32;
+        let text_width = if let Some(w) = self.width {
+            w as f32
+        } else if self.no_wrap {
+            natural_width.max(BASE_MIN_TEXT_WIDTH) as f32
+        } else if self.ellipsize {
+            // Long lines are shortened rather than wrapped
+            natural_width.clamp(BASE_MIN_TEXT_WIDTH, BASE_WRAP_WIDTH) as f32
+        } else {
+            let mut wrap_width = natural_width.clamp(BASE_MIN_TEXT_WIDTH, BASE_WRAP_WIDTH);
+            while wrap_width < natural_width.min(BASE_MAX_WRAP_WIDTH) {
+                let (_, wrapped_height) = temp_font
+                    .render(&self.text)
+                    .with_markup(!self.no_markup)
+                    .with_max_width(wrap_width as f32)
+                    .measure();
+                if wrapped_height <= BASE_MAX_TEXT_HEIGHT as f32 {
+                    break;
+                }
+                wrap_width += BASE_WRAP_WIDTH_STEP;
+            }
+            wrap_width as f32
+        };
 
         // Calculate logical text size with/without wrapping
-        let temp_text = if self.no_wrap {
-            temp_font.render(&self.text).finish()
-        } else {
-            temp_font
-                .render(&self.text)
-                .with_max_width(text_width)
-                .finish()
+        let new_label = |text: &str| {
+            Label::new(text)
+                .with_markup(!self.no_markup)
+                .with_wrap(!self.no_wrap)
+                .with_ellipsize(if self.ellipsize {
+                    Ellipsize::End
+                } else {
+                    Ellipsize::None
+                })
         };
+        let mut temp_text = new_label(&self.text);
+        temp_text.layout(&temp_font, colors.text, Some(text_width as u32));
 
         // Use specified text_width for window sizing
         // When no_wrap is true, width is treated as minimum, content can expand beyond it
@@ -189,62 +358,130 @@ impl MessageBuilder {
                 // Use specified width for wrapping
                 text_width as u32
             };
-        let logical_inner_width = logical_content_width.max(logical_buttons_width);
-        let calc_width = (logical_inner_width + BASE_PADDING * 2).max(BASE_MIN_WIDTH);
-        let logical_text_height = temp_text.height().max(BASE_ICON_SIZE);
+        // Checkbox row sits below the text, aligned with it
+        let logical_checkbox_width = self
+            .checkbox_text
+            .as_ref()
+            .map(|label| {
+                let (label_w, _) = temp_font.render(label).measure();
+                logical_icon_width
+                    + BASE_CHECKBOX_SIZE
+                    + colors.space(BASE_CHECKBOX_SPACING)
+                    + label_w as u32
+            })
+            .unwrap_or(0);
+        let checkbox_row_height = if self.checkbox_text.is_some() {
+            BASE_CHECKBOX_SIZE + colors.space(BASE_PADDING)
+        } else {
+            0
+        };
+        // Switches are stacked between the text and the checkbox
+        let temp_switches: Vec<Switch> = self
+            .switches
+            .iter()
+            .map(|(label, _)| Switch::new(label, &temp_font, 1.0))
+            .collect();
+        let logical_switches_width = temp_switches
+            .iter()
+            .map(|switch| logical_icon_width + switch.width())
+            .max()
+            .unwrap_or(0);
+        let logical_switches_height = stack_height(&temp_switches, 1.0, colors)
+            + if temp_switches.is_empty() {
+                0
+            } else {
+                colors.space(BASE_PADDING)
+            };
+        // Links are stacked between the text and the switches
+        let temp_links: Vec<Link> = self
+            .links
+            .iter()
+            .map(|(text, uri)| Link::new(text, uri, &temp_font, 1.0))
+            .collect();
+        let logical_links_width = temp_links
+            .iter()
+            .map(|link| logical_icon_width + link.width())
+            .max()
+            .unwrap_or(0);
+        let logical_links_height = stack_height(&temp_links, 1.0, colors)
+            + if temp_links.is_empty() {
+                0
+            } else {
+                colors.space(BASE_PADDING)
+            };
+
+        // Own content is stacked between the text and the links
+        let (logical_own_width, logical_own_height) =
+            self.content.as_ref().map_or((0, 0), |(width, height, _)| {
+                (
+                    logical_icon_width + width,
+                    height + colors.space(BASE_PADDING),
+                )
+            });
+
+        let logical_inner_width = logical_content_width
+            .max(logical_buttons_width)
+            .max(logical_checkbox_width)
+            .max(logical_switches_width)
+            .max(logical_links_width)
+            .max(logical_own_width);
+        let calc_width = (logical_inner_width + colors.space(BASE_PADDING) * 2).max(BASE_MIN_WIDTH);
+        // Grow with the text up to a maximum; longer text is clipped
+        let logical_text_height = temp_text
+            .height()
+            .clamp(BASE_ICON_SIZE, BASE_MAX_TEXT_HEIGHT);
         let button_area_height = if use_vertical_layout {
-            temp_buttons.len() as u32 * 32
-                + (temp_buttons.len().saturating_sub(1) as u32 * BASE_BUTTON_SPACING)
+            temp_buttons.len() as u32 * colors.control_height
+                + (temp_buttons.len().saturating_sub(1) as u32 * colors.space(BASE_BUTTON_SPACING))
         } else {
-            32
+            colors.control_height
         };
-        let calc_height = BASE_PADDING * 3 + logical_text_height + button_area_height;
+        let calc_height = colors.space(BASE_PADDING) * 3
+            + logical_text_height
+            + logical_switches_height
+            + logical_links_height
+            + logical_own_height
+            + checkbox_row_height
+            + button_area_height;
 
         let logical_width = calc_width as u16;
         let logical_height = self.height.unwrap_or(calc_height) as u16;
 
         // Create window with LOGICAL dimensions - window will handle physical scaling
-        let mut window = create_window(logical_width, logical_height)?;
+        let mut window = create_window(logical_width, logical_height, colors, &self.placing)?;
         window.set_title(&self.title)?;
 
         // Get the actual scale factor from the window (compositor scale)
         let scale = window.scale_factor();
 
         // Now create everything at PHYSICAL scale
-        let font = Font::load(scale);
+        let font = colors.font(scale);
 
         // Scale dimensions for physical rendering
-        let padding = (BASE_PADDING as f32 * scale) as u32;
-        let button_spacing = (BASE_BUTTON_SPACING as f32 * scale) as u32;
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
+        let button_spacing = (colors.space(BASE_BUTTON_SPACING) as f32 * scale) as u32;
         let max_text_width = text_width * scale;
-        let button_height = (32.0 * scale) as u32;
+        let button_height = (colors.control_height as f32 * scale) as u32;
 
         // Create buttons at physical scale
         let mut buttons: Vec<Button> = labels
             .iter()
-            .map(|l| Button::new(l, &font, scale))
+            .map(|l| Button::new(l, &font, colors, scale))
             .collect();
+        if let Some(idx) = default_idx {
+            buttons[button_count - 1 - idx].set_default(true);
+        }
 
         // Calculate physical dimensions
         let physical_width = (logical_width as f32 * scale) as u32;
         let physical_height = (logical_height as f32 * scale) as u32;
 
-        // Pre-render text to get actual height
-        let text_canvas = if self.no_wrap {
-            font.render(&self.text).with_color(colors.text).finish()
-        } else {
-            font.render(&self.text)
-                .with_color(colors.text)
-                .with_max_width(max_text_width)
-                .finish()
-        };
-
         // Position buttons
         let mut button_positions = Vec::with_capacity(buttons.len());
 
         if use_vertical_layout {
             // Vertical layout: stack buttons vertically, full width
-            for idx in 0..buttons.len() {
+            for (idx, button) in buttons.iter_mut().enumerate() {
                 let button_y = physical_height as i32
                     - padding as i32
                     - button_height as i32
@@ -255,7 +492,7 @@ impl MessageBuilder {
                 let button_width = physical_width as i32 - 2 * padding as i32;
 
                 // Update button width and position
-                buttons[idx].set_width(button_width as u32);
+                button.set_width(button_width as u32);
                 button_positions.push((button_x, button_y));
             }
         } else {
@@ -275,29 +512,156 @@ impl MessageBuilder {
             button.set_position(button_positions[idx].0, button_positions[idx].1);
         }
 
+        // Checkbox sits above the topmost button row, aligned with the text
+        let checkbox_size = (BASE_CHECKBOX_SIZE as f32 * scale) as u32;
+        let checkbox_spacing = (colors.space(BASE_CHECKBOX_SPACING) as f32 * scale) as i32;
+        let checkbox_x = if self.icon.is_some() {
+            padding as i32 + ((BASE_ICON_SIZE as f32 * scale) as u32 + padding) as i32
+        } else {
+            padding as i32
+        };
+        let buttons_top = button_positions
+            .iter()
+            .map(|&(_, y)| y)
+            .min()
+            .unwrap_or(physical_height as i32 - padding as i32);
+        let checkbox_y = buttons_top - padding as i32 - checkbox_size as i32;
+
+        // Switches sit above the checkbox, their pills lined up at the right
+        let mut switches: Vec<Switch> = self
+            .switches
+            .iter()
+            .map(|(label, on)| Switch::new(label, &font, scale).with_on(*on))
+            .collect();
+        let switches_width = switches.iter().map(Switch::width).max().unwrap_or(0);
+        let switch_spacing = (colors.space(BASE_SWITCH_SPACING) as f32 * scale) as i32;
+        let switches_bottom = if self.checkbox_text.is_some() {
+            checkbox_y
+        } else {
+            buttons_top
+        } - padding as i32;
+        let switches_top = switches_bottom - stack_height(&switches, scale, colors) as i32;
+        let mut switch_y = switches_top;
+        for switch in &mut switches {
+            switch.set_width(switches_width);
+            switch.set_position(checkbox_x, switch_y);
+            switch_y += switch.height() as i32 + switch_spacing;
+        }
+
+        // Links sit above the switches
+        let mut links: Vec<Link> = self
+            .links
+            .iter()
+            .map(|(text, uri)| Link::new(text, uri, &font, scale))
+            .collect();
+        let links_bottom = if !switches.is_empty() {
+            switches_top
+        } else if self.checkbox_text.is_some() {
+            checkbox_y
+        } else {
+            buttons_top
+        } - padding as i32;
+        let links_top = links_bottom - stack_height(&links, scale, colors) as i32;
+        let mut link_y = links_top;
+        for link in &mut links {
+            link.set_position(checkbox_x, link_y);
+            link_y += link.height() as i32 + switch_spacing;
+        }
+
+        // Own content sits above the links, drawn once at physical size
+        let rows_top = if !links.is_empty() {
+            links_top
+        } else if !switches.is_empty() {
+            switches_top
+        } else if self.checkbox_text.is_some() {
+            checkbox_y
+        } else {
+            buttons_top
+        };
+        let content = self.content.as_ref().map(|(width, height, draw)| {
+            let mut area = Canvas::new(
+                ((*width as f32 * scale) as u32).max(1),
+                ((*height as f32 * scale) as u32).max(1),
+            );
+            draw(&mut area);
+            let y = rows_top - padding as i32 - area.height() as i32;
+            (area, (checkbox_x, y))
+        });
+
+        // Text is clipped to the space above the content/link/switch/
+        // checkbox/button rows
+        let text_area_bottom = content.as_ref().map_or(rows_top, |(_, (_, y))| *y) - padding as i32;
+        // Centered across the text area, and level with the icon
+        let icon_size = (BASE_ICON_SIZE as f32 * scale) as i32;
+        let lay_out_text = |text: &str| {
+            let mut label = new_label(text);
+            label.layout(&font, colors.text, Some(max_text_width as u32));
+            label.set_max_height((text_area_bottom - padding as i32).max(0) as u32);
+            label.set_position(
+                checkbox_x + ((max_text_width - label.width() as f32) / 2.0).max(0.0) as i32,
+                padding as i32 + ((icon_size - label.height() as i32) / 2).max(0),
+            );
+            label
+        };
+        let mut text_label = lay_out_text(&self.text);
+        let checkbox_row_width = self
+            .checkbox_text
+            .as_ref()
+            .map(|label| {
+                let (label_w, _) = font.render(label).measure();
+                checkbox_size as i32 + checkbox_spacing + label_w as i32
+            })
+            .unwrap_or(0);
+        let mut checkbox_checked = false;
+        let mut checkbox_hovered = false;
+
         // Create canvas at PHYSICAL dimensions
         let mut canvas = Canvas::new(physical_width, physical_height);
 
         // Clone icon for multiple uses
         let icon = self.icon.clone();
+        // Icons from image files and themes are drawn once, at physical size
+        let icon_image = icon
+            .as_ref()
+            .and_then(|icon| icon.file(BASE_ICON_SIZE, scale))
+            .and_then(|path| {
+                let size = (BASE_ICON_SIZE as f32 * scale) as u32;
+                let mut image = Canvas::new(size, size);
+                image.draw_image(&path, 0, 0, size, size).then_some(image)
+            });
+
+        // Results use natural (unreversed) button order
+        let make_result = |idx: usize, checked: bool, switches: &[Switch]| {
+            result(
+                idx,
+                self.checkbox_text.is_some().then_some(checked),
+                (!switches.is_empty()).then(|| switches.iter().map(Switch::is_on).collect()),
+            )
+        };
 
         // Initial draw
         draw_dialog(
             &mut canvas,
             colors,
             &font,
-            &self.text,
+            &text_label,
             icon.clone(),
+            icon_image.as_ref(),
             &buttons,
-            text_canvas.height(),
-            max_text_width,
-            self.no_wrap,
+            &switches,
+            &links,
+            content.as_ref(),
+            self.checkbox_text.as_deref(),
+            checkbox_checked,
+            checkbox_hovered,
+            (checkbox_x, checkbox_y),
             scale,
         );
         window.set_contents(&canvas)?;
         window.show()?;
 
         // Event loop
+        let mut focus = FocusManager::new();
         let mut dragging = false;
         let deadline = self
             .timeout
@@ -323,45 +687,93 @@ impl MessageBuilder {
             } else {
                 window.wait_for_event()?
             };
+            let event = focus.process_event(
+                event,
+                &mut focus_order(&mut links, &mut switches, &mut buttons),
+            );
+
+            let mut needs_redraw = false;
 
             match &event {
                 WindowEvent::CloseRequested => {
                     return Ok(DialogResult::Closed);
                 }
                 WindowEvent::RedrawRequested => {
-                    draw_dialog(
-                        &mut canvas,
-                        colors,
-                        &font,
-                        &self.text,
-                        icon.clone(),
-                        &buttons,
-                        text_canvas.height(),
-                        max_text_width,
-                        self.no_wrap,
-                        scale,
-                    );
-                    window.set_contents(&canvas)?;
+                    needs_redraw = true;
+                }
+                WindowEvent::SetText(text) => {
+                    text_label = lay_out_text(text);
+                    needs_redraw = true;
+                }
+                WindowEvent::KeyPress(key_event) => {
+                    if let Some(result) = key_result(key_event.keysym, default_idx, cancel_idx) {
+                        return Ok(result.map_or(DialogResult::Closed, |idx| {
+                            make_result(idx, checkbox_checked, &switches)
+                        }));
+                    }
+                }
+                WindowEvent::ButtonPress(MouseButton::Left, _) if checkbox_hovered => {
+                    checkbox_checked = !checkbox_checked;
+                    needs_redraw = true;
                 }
                 WindowEvent::ButtonPress(MouseButton::Left, _) => {
                     dragging = true;
                 }
                 WindowEvent::ButtonRelease(MouseButton::Left, _) => {
-                    if dragging {
+                    dragging = false;
+                }
+                _ => {}
+            }
+
+            if self.checkbox_text.is_some() {
+                needs_redraw |= process_checkbox_event(
+                    &event,
+                    &mut checkbox_checked,
+                    &mut checkbox_hovered,
+                    checkbox_x,
+                    checkbox_y,
+                    checkbox_row_width,
+                    checkbox_size,
+                );
+            }
+
+            // A click on a switch or link acts on it rather than moving the
+            // window
+            for switch in &mut switches {
+                if switch.process_event(&event) {
+                    needs_redraw = true;
+                    if let WindowEvent::ButtonPress(..) = event {
                         dragging = false;
                     }
                 }
-                _ => {}
+            }
+            for link in &mut links {
+                if link.process_event(&event) {
+                    needs_redraw = true;
+                    if let WindowEvent::ButtonPress(..) = event {
+                        dragging = false;
+                    }
+                }
+            }
+            if let WindowEvent::CursorMove(_) = &event {
+                let _ = window.set_cursor(if links.iter().any(Link::is_hovered) {
+                    CursorShape::Pointer
+                } else {
+                    CursorShape::Default
+                });
             }
 
             // Process events for buttons
-            let mut needs_redraw = false;
             for (i, button) in buttons.iter_mut().enumerate() {
                 if button.process_event(&event) {
                     needs_redraw = true;
                 }
                 if button.was_clicked() {
-                    return Ok(DialogResult::Button(i));
+                    return Ok(make_result(
+                        button_count - 1 - i,
+                        checkbox_checked,
+                        &switches,
+                    ));
                 }
             }
 
@@ -375,17 +787,66 @@ impl MessageBuilder {
 
             // Batch process pending events
             while let Some(event) = window.poll_for_event()? {
+                let event = focus.process_event(
+                    event,
+                    &mut focus_order(&mut links, &mut switches, &mut buttons),
+                );
                 match &event {
                     WindowEvent::CloseRequested => {
                         return Ok(DialogResult::Closed);
                     }
+                    WindowEvent::ButtonPress(MouseButton::Left, _) if checkbox_hovered => {
+                        checkbox_checked = !checkbox_checked;
+                        needs_redraw = true;
+                    }
+                    WindowEvent::SetText(text) => {
+                        text_label = lay_out_text(text);
+                        needs_redraw = true;
+                    }
                     _ => {
+                        needs_redraw |= matches!(event, WindowEvent::RedrawRequested);
+                        if let WindowEvent::KeyPress(key_event) = &event
+                            && let Some(result) =
+                                key_result(key_event.keysym, default_idx, cancel_idx)
+                        {
+                            return Ok(result.map_or(DialogResult::Closed, |idx| {
+                                make_result(idx, checkbox_checked, &switches)
+                            }));
+                        }
+                        if self.checkbox_text.is_some() {
+                            needs_redraw |= process_checkbox_event(
+                                &event,
+                                &mut checkbox_checked,
+                                &mut checkbox_hovered,
+                                checkbox_x,
+                                checkbox_y,
+                                checkbox_row_width,
+                                checkbox_size,
+                            );
+                        }
+                        for switch in &mut switches {
+                            needs_redraw |= switch.process_event(&event);
+                        }
+                        for link in &mut links {
+                            needs_redraw |= link.process_event(&event);
+                        }
+                        if let WindowEvent::CursorMove(_) = &event {
+                            let _ = window.set_cursor(if links.iter().any(Link::is_hovered) {
+                                CursorShape::Pointer
+                            } else {
+                                CursorShape::Default
+                            });
+                        }
                         for (i, button) in buttons.iter_mut().enumerate() {
                             if button.process_event(&event) {
                                 needs_redraw = true;
                             }
                             if button.was_clicked() {
-                                return Ok(DialogResult::Button(i));
+                                return Ok(make_result(
+                                    button_count - 1 - i,
+                                    checkbox_checked,
+                                    &switches,
+                                ));
                             }
                         }
                     }
@@ -397,39 +858,228 @@ impl MessageBuilder {
                     &mut canvas,
                     colors,
                     &font,
-                    &self.text,
+                    &text_label,
                     icon.clone(),
+                    icon_image.as_ref(),
                     &buttons,
-                    text_canvas.height(),
-                    max_text_width,
-                    self.no_wrap,
+                    &switches,
+                    &links,
+                    content.as_ref(),
+                    self.checkbox_text.as_deref(),
+                    checkbox_checked,
+                    checkbox_hovered,
+                    (checkbox_x, checkbox_y),
                     scale,
                 );
                 window.set_contents(&canvas)?;
             }
         }
     }
+
+    /// Shows the dialog on the terminal instead of in a window.
+    #[cfg(feature = "tui")]
+    fn show_tui(
+        &self,
+        labels: &[String],
+        default_idx: Option<usize>,
+        cancel_idx: Option<usize>,
+    ) -> Result<DialogResult, Error> {
+        use crate::tui::{self, Key, Terminal};
+
+        // Markup can't be shown in the terminal, only its text
+        let text = if self.no_markup {
+            None
+        } else {
+            crate::render::markup::parse(&self.text)
+        }
+        .map_or_else(|| self.text.clone(), |styled| styled.text);
+
+        let mut terminal = Terminal::open()?;
+        let deadline = self
+            .timeout
+            .map(|secs| Instant::now() + Duration::from_secs(secs as u64));
+        let button_count = labels.len();
+        let mut focused = default_idx;
+        let mut checked = false;
+        let mut switches: Vec<bool> = self.switches.iter().map(|&(_, on)| on).collect();
+        let make_result = |idx: usize, checked: bool, switches: &[bool]| {
+            result(
+                idx,
+                self.checkbox_text.is_some().then_some(checked),
+                (!switches.is_empty()).then(|| switches.to_vec()),
+            )
+        };
+
+        loop {
+            let (width, _) = terminal.size();
+            let mut lines = tui::header(&self.title, width);
+            lines.extend(tui::wrap(&text, width));
+            lines.push(String::new());
+            // Links can't be followed here, so their URIs are spelled out
+            for (text, uri) in &self.links {
+                let line = if text.is_empty() {
+                    uri.clone()
+                } else {
+                    format!("{text}: {uri}")
+                };
+                lines.push(tui::fit(&line, width));
+            }
+            if !self.links.is_empty() {
+                lines.push(String::new());
+            }
+            // Switches are toggled with their number keys
+            for (i, ((label, _), on)) in self.switches.iter().zip(&switches).enumerate() {
+                let mark = if *on { "on " } else { "off" };
+                lines.push(tui::fit(&format!("{}. [{mark}] {label}", i + 1), width));
+            }
+            if !switches.is_empty() {
+                lines.push(String::new());
+            }
+            if let Some(label) = &self.checkbox_text {
+                let mark = if checked { 'x' } else { ' ' };
+                lines.push(tui::fit(&format!("[{mark}] {label}"), width));
+                lines.push(String::new());
+            }
+            lines.push(tui::buttons(labels, focused));
+            terminal.present(&lines)?;
+
+            let timeout =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            let Some(key) = terminal.read_key(timeout)? else {
+                return Ok(DialogResult::Timeout);
+            };
+            match key {
+                Key::Left | Key::BackTab if button_count > 0 => {
+                    focused =
+                        Some(focused.map_or(0, |idx| (idx + button_count - 1) % button_count));
+                }
+                Key::Right | Key::Tab if button_count > 0 => {
+                    focused = Some(focused.map_or(0, |idx| (idx + 1) % button_count));
+                }
+                Key::Char(' ') if self.checkbox_text.is_some() => checked = !checked,
+                Key::Char(c @ '1'..='9') => {
+                    if let Some(on) = switches.get_mut(c as usize - '1' as usize) {
+                        *on = !*on;
+                    }
+                }
+                Key::Enter => {
+                    if let Some(idx) = focused {
+                        return Ok(make_result(idx, checked, &switches));
+                    }
+                }
+                Key::Escape => {
+                    return Ok(cancel_idx.map_or(DialogResult::Closed, |idx| {
+                        make_result(idx, checked, &switches)
+                    }));
+                }
+                Key::Interrupt => return Ok(DialogResult::Closed),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Maps Enter/Escape to the button they activate.
+/// Returns `Some(None)` when Escape should close the dialog without a button.
+fn key_result(
+    keysym: u32,
+    default_idx: Option<usize>,
+    cancel_idx: Option<usize>,
+) -> Option<Option<usize>> {
+    match keysym {
+        KEY_RETURN | KEY_KP_ENTER => default_idx.map(Some),
+        KEY_ESCAPE => Some(cancel_idx),
+        _ => None,
+    }
+}
+
+/// The result for a button, along with the checkbox's state and the
+/// switches' ones when they were shown.
+fn result(idx: usize, checked: Option<bool>, switches: Option<Vec<bool>>) -> DialogResult {
+    match (switches, checked) {
+        (Some(mut switches), checked) => {
+            switches.extend(checked);
+            DialogResult::ButtonWithSwitches(idx, switches)
+        }
+        (None, Some(checked)) => DialogResult::ButtonWithCheckbox(idx, checked),
+        (None, None) => DialogResult::Button(idx),
+    }
+}
+
+/// Height of switches or links stacked one above another.
+fn stack_height(widgets: &[impl Widget], scale: f32, colors: &Theme) -> u32 {
+    let spacing = (colors.space(BASE_SWITCH_SPACING) as f32 * scale) as u32;
+    widgets.iter().map(Widget::height).sum::<u32>()
+        + widgets.len().saturating_sub(1) as u32 * spacing
+}
+
+/// The dialog's widgets in Tab order: the links, the switches, then the
+/// buttons.
+fn focus_order<'a>(
+    links: &'a mut [Link],
+    switches: &'a mut [Switch],
+    buttons: &'a mut [Button],
+) -> Vec<&'a mut dyn Widget> {
+    links
+        .iter_mut()
+        .map(|link| link as &mut dyn Widget)
+        .chain(switches.iter_mut().map(|switch| switch as &mut dyn Widget))
+        .chain(buttons.iter_mut().map(|button| button as &mut dyn Widget))
+        .collect()
+}
+
+/// Updates checkbox hover state and handles Space toggling.
+/// Returns true if a redraw is needed.
+fn process_checkbox_event(
+    event: &WindowEvent,
+    checked: &mut bool,
+    hovered: &mut bool,
+    x: i32,
+    y: i32,
+    row_width: i32,
+    size: u32,
+) -> bool {
+    match event {
+        WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
+            let was_hovered = *hovered;
+            *hovered = point_in_rect(pos.x as i32, pos.y as i32, x, y, row_width as u32, size);
+            was_hovered != *hovered
+        }
+        WindowEvent::CursorLeave => {
+            let was_hovered = *hovered;
+            *hovered = false;
+            was_hovered
+        }
+        WindowEvent::TextInput(' ') => {
+            *checked = !*checked;
+            true
+        }
+        _ => false,
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
 fn draw_dialog(
     canvas: &mut Canvas,
-    colors: &Colors,
+    colors: &Theme,
     font: &Font,
-    text: &str,
+    text: &Label,
     icon: Option<Icon>,
+    icon_image: Option<&Canvas>,
     buttons: &[Button],
-    text_height: u32,
-    max_text_width: f32,
-    no_wrap: bool,
+    switches: &[Switch],
+    links: &[Link],
+    content: Option<&(Canvas, (i32, i32))>,
+    checkbox_text: Option<&str>,
+    checkbox_checked: bool,
+    checkbox_hovered: bool,
+    (checkbox_x, checkbox_y): (i32, i32),
     scale: f32,
 ) {
     // Scale dimensions
-    let icon_size = (BASE_ICON_SIZE as f32 * scale) as u32;
-    let padding = (BASE_PADDING as f32 * scale) as u32;
+    let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
     let width = canvas.width() as f32;
     let height = canvas.height() as f32;
-    let radius = 8.0 * scale;
 
     // Draw dialog background with shadow and border
     canvas.fill_dialog_bg(
@@ -437,34 +1087,86 @@ fn draw_dialog(
         height,
         colors.window_bg,
         colors.window_border,
-        colors.window_shadow,
-        radius,
+        colors.window_radius * scale,
+        colors.border_width,
     );
 
-    let mut x = padding as i32;
-    let y = padding as i32;
+    let (x, y) = (padding as i32, padding as i32);
 
     // Draw icon
     if let Some(icon) = icon {
-        draw_icon(canvas, x, y, icon, scale);
-        x += (icon_size + padding) as i32;
+        match icon_image {
+            Some(image) => canvas.draw_canvas(image, x, y),
+            None => draw_icon(canvas, x, y, icon, scale),
+        }
     }
 
     // Draw text
-    let text_canvas = if no_wrap {
-        font.render(text).with_color(colors.text).finish()
-    } else {
-        font.render(text)
-            .with_color(colors.text)
-            .with_max_width(max_text_width)
-            .finish()
-    };
+    text.draw(canvas, colors);
+
+    if let Some((area, (x, y))) = content {
+        canvas.draw_canvas(area, *x, *y);
+    }
+
+    for link in links {
+        link.draw_to(canvas, colors, font);
+    }
+
+    for switch in switches {
+        switch.draw_to(canvas, colors, font);
+    }
+
+    // Draw checkbox
+    if let Some(label) = checkbox_text {
+        let size = (BASE_CHECKBOX_SIZE as f32 * scale) as u32;
+        let cb_bg = if checkbox_hovered {
+            colors.input_bg_focused
+        } else {
+            colors.input_bg
+        };
+        let cb_border = if checkbox_hovered {
+            colors.input_border_focused
+        } else {
+            colors.input_border
+        };
+        canvas.fill_rounded_rect(
+            checkbox_x as f32,
+            checkbox_y as f32,
+            size as f32,
+            size as f32,
+            3.0 * scale,
+            cb_bg,
+        );
+        canvas.stroke_rounded_rect(
+            checkbox_x as f32,
+            checkbox_y as f32,
+            size as f32,
+            size as f32,
+            3.0 * scale,
+            cb_border,
+            1.0,
+        );
 
-    // Center text horizontally within text area
-    let text_x = x + ((max_text_width - text_canvas.width() as f32) / 2.0).max(0.0) as i32;
-    // Center text vertically with icon
-    let text_y = y + (icon_size as i32 - text_height as i32) / 2;
-    canvas.draw_canvas(&text_canvas, text_x, text_y.max(y));
+        // Check mark
+        if checkbox_checked {
+            let inset = (3.0 * scale) as i32;
+            canvas.fill_rounded_rect(
+                (checkbox_x + inset) as f32,
+                (checkbox_y + inset) as f32,
+                (size as i32 - inset * 2) as f32,
+                (size as i32 - inset * 2) as f32,
+                2.0 * scale,
+                colors.input_border_focused,
+            );
+        }
+
+        // Label, vertically centered on the box
+        let label_canvas = font.render(label).with_color(colors.text).finish();
+        let label_x =
+            checkbox_x + size as i32 + (colors.space(BASE_CHECKBOX_SPACING) as f32 * scale) as i32;
+        let label_y = checkbox_y + (size as i32 - label_canvas.height() as i32) / 2;
+        canvas.draw_canvas(&label_canvas, label_x, label_y);
+    }
 
     // Draw buttons
     for button in buttons {
@@ -490,23 +1192,7 @@ fn draw_icon(canvas: &mut Canvas, x: i32, y: i32, icon: Icon, scale: f32) {
 
     match shape {
         IconShape::Circle => {
-            // Draw filled circle
-            for dy in 0..icon_size {
-                for dx in 0..icon_size {
-                    let px = x as f32 + dx as f32 + 0.5;
-                    let py = y as f32 + dy as f32 + 0.5;
-                    let dist = ((px - cx).powi(2) + (py - cy).powi(2)).sqrt();
-                    if dist <= r {
-                        canvas.fill_rect(
-                            x as f32 + dx as f32,
-                            y as f32 + dy as f32,
-                            1.0,
-                            1.0,
-                            color,
-                        );
-                    }
-                }
-            }
+            canvas.fill_circle(cx, cy, r, color);
         }
         IconShape::Triangle => {
             // Draw triangle (warning sign)
@@ -1,21 +1,96 @@
 //! UI components and dialog implementations.
 
+pub(crate) mod accent;
+pub(crate) mod animation;
+pub(crate) mod appearance;
+pub(crate) mod bookmarks;
 pub(crate) mod calendar;
+pub(crate) mod cancel;
+pub(crate) mod chooser;
+pub(crate) mod collate;
+pub(crate) mod color;
+pub(crate) mod countdown;
+pub mod custom;
+pub(crate) mod dialog;
 pub(crate) mod entry;
 pub(crate) mod file_select;
+pub(crate) mod font_select;
 pub(crate) mod forms;
+pub(crate) mod handle;
+pub(crate) mod hooks;
+pub(crate) mod icon_select;
+pub(crate) mod icon_theme;
+pub(crate) mod image_view;
+pub(crate) mod last_dir;
 pub(crate) mod list;
+pub(crate) mod login;
 pub(crate) mod message;
 pub(crate) mod progress;
+pub(crate) mod qr;
+pub(crate) mod recent;
+pub(crate) mod recent_colors;
 pub(crate) mod scale;
+pub(crate) mod session;
+pub(crate) mod strings;
 pub(crate) mod text_info;
+pub(crate) mod theme_file;
+pub(crate) mod thumbnails;
+pub(crate) mod tray;
 pub(crate) mod widgets;
 
-use crate::render::{Rgba, rgb};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+pub use strings::set_string;
+pub use theme_file::ThemeError;
+
+pub use crate::backend::{Monitor, WindowHandle};
+use crate::{
+    backend::{self, Window},
+    error::Error,
+    render::{Canvas, Font, Rgba, rgb},
+    ui::{strings::tr, widgets::titlebar::DecoratedWindow},
+};
 
-/// Color theme for dialogs.
+/// Look of dialogs: their colors, and the shapes, spacing and text size
+/// they are laid out with.
+///
+/// Variants are made from a preset, e.g. a compact light theme:
+///
+/// ```
+/// use zenity_rs::{THEME_LIGHT, Theme};
+///
+/// static COMPACT: Theme = Theme {
+///     spacing: 0.75,
+///     control_height: 26,
+///     font_size: 15.0,
+///     ..THEME_LIGHT
+/// };
+/// ```
+///
+/// Builders take themes by reference or by value, so they can also be
+/// made at runtime, e.g. from an application's own settings:
+///
+/// ```no_run
+/// use std::sync::Arc;
+///
+/// use zenity_rs::{MessageBuilder, THEME_DARK, Theme, rgb};
+///
+/// let theme = Arc::new(Theme {
+///     progress_fill: rgb(230, 97, 0),
+///     font_size: 16.0,
+///     ..THEME_DARK
+/// });
+/// MessageBuilder::new().colors(theme.clone()).show()?;
+/// MessageBuilder::new().colors(&*theme).show()?;
+/// # Ok::<(), zenity_rs::Error>(())
+/// ```
 #[derive(Debug, Clone, Copy)]
-pub struct Colors {
+pub struct Theme {
+    /// Background of dialogs; below full alpha they show what is behind
+    /// them, blurred by compositors that support it
     pub window_bg: Rgba,
     pub text: Rgba,
     pub button: Rgba,
@@ -23,20 +98,89 @@ pub struct Colors {
     pub button_pressed: Rgba,
     pub button_outline: Rgba,
     pub button_text: Rgba,
+    /// How much lighter the top of a button is than its bottom, 0 for flat
+    pub button_gradient: f32,
+    pub button_shadow: Rgba,
+    /// How far button shadows spread, in logical pixels; 0 for none
+    pub elevation: f32,
     pub input_bg: Rgba,
     pub input_bg_focused: Rgba,
     pub input_border: Rgba,
+    /// Border of the focused input, and background of selections
     pub input_border_focused: Rgba,
+    /// Text drawn on [`input_border_focused`](Self::input_border_focused)
+    pub selection_text: Rgba,
     pub input_placeholder: Rgba,
+    /// Inner shadow along the top edge of text inputs
+    pub input_inset: Rgba,
+    /// Ring drawn around the control with keyboard focus
+    pub focus_ring: Rgba,
     pub progress_bg: Rgba,
     pub progress_fill: Rgba,
     pub progress_border: Rgba,
     pub window_border: Rgba,
     pub window_shadow: Rgba,
+    /// Corner radius of dialog windows, in logical pixels
+    pub window_radius: f32,
+    /// Corner radius of buttons, inputs and progress bars, in logical
+    /// pixels
+    pub control_radius: f32,
+    /// Width of window and control outlines, in pixels
+    pub border_width: f32,
+    /// Scales the padding around and the gaps between controls: 1.0 is
+    /// the default, less is more compact, more suits touch screens
+    pub spacing: f32,
+    /// Height of buttons, in logical pixels
+    pub control_height: u32,
+    /// Size of dialog text, in logical pixels
+    pub font_size: f32,
+}
+
+/// Former name of [`Theme`], from when it only held colors.
+pub type Colors = Theme;
+
+impl AsRef<Theme> for Theme {
+    fn as_ref(&self) -> &Theme {
+        self
+    }
+}
+
+impl Theme {
+    /// Returns `base` logical pixels of padding or gap, scaled by
+    /// [`spacing`](Self::spacing).
+    pub(crate) fn space(&self, base: u32) -> u32 {
+        (base as f32 * self.spacing).round() as u32
+    }
+
+    /// Returns this theme with `accent` for focused borders, selections,
+    /// focus rings and progress.
+    pub fn with_accent(self, accent: Rgba) -> Self {
+        Self {
+            input_border_focused: accent,
+            focus_ring: accent.with_alpha(128),
+            progress_fill: accent,
+            ..self
+        }
+    }
+
+    /// Returns this theme with the fields named in `style` changed, e.g.
+    /// `button_text:#fff,window_bg:#202020`: `key:value` pairs separated
+    /// by commas or semicolons, with colors as in theme files and the
+    /// rest as numbers.
+    pub fn with_style(mut self, style: &str) -> Result<Self, Error> {
+        theme_file::apply_style(&mut self, style).map_err(Error::InvalidStyle)?;
+        Ok(self)
+    }
+
+    /// Loads the dialog font at this theme's size for the given scale
+    /// factor.
+    pub(crate) fn font(&self, scale: f32) -> Font {
+        Font::load_with_size(self.font_size * scale)
+    }
 }
 
-/// Light theme colors.
-pub static THEME_LIGHT: Colors = Colors {
+/// Light theme.
+pub static THEME_LIGHT: Theme = Theme {
     window_bg: rgb(250, 250, 250),
     text: rgb(30, 30, 30),
     button: rgb(230, 230, 230),
@@ -44,20 +188,32 @@ pub static THEME_LIGHT: Colors = Colors {
     button_pressed: rgb(200, 200, 200),
     button_outline: rgb(180, 180, 180),
     button_text: rgb(30, 30, 30),
+    button_gradient: 0.04,
+    button_shadow: Rgba::new(0, 0, 0, 30),
+    elevation: 1.0,
     input_bg: rgb(255, 255, 255),
     input_bg_focused: rgb(255, 255, 255),
     input_border: rgb(200, 200, 200),
     input_border_focused: rgb(100, 150, 200),
+    selection_text: rgb(255, 255, 255),
     input_placeholder: rgb(150, 150, 150),
+    input_inset: Rgba::new(0, 0, 0, 20),
+    focus_ring: Rgba::new(100, 150, 200, 128),
     progress_bg: rgb(230, 230, 230),
     progress_fill: rgb(70, 140, 220),
     progress_border: rgb(200, 200, 200),
     window_border: rgb(180, 180, 180),
     window_shadow: Rgba::new(0, 0, 0, 50),
+    window_radius: 8.0,
+    control_radius: 5.0,
+    border_width: 1.0,
+    spacing: 1.0,
+    control_height: 32,
+    font_size: 18.0,
 };
 
-/// Dark theme colors.
-pub static THEME_DARK: Colors = Colors {
+/// Dark theme.
+pub static THEME_DARK: Theme = Theme {
     window_bg: rgb(45, 45, 45),
     text: rgb(230, 230, 230),
     button: rgb(70, 70, 70),
@@ -65,22 +221,123 @@ pub static THEME_DARK: Colors = Colors {
     button_pressed: rgb(60, 60, 60),
     button_outline: rgb(100, 100, 100),
     button_text: rgb(230, 230, 230),
+    button_gradient: 0.04,
+    button_shadow: Rgba::new(0, 0, 0, 60),
+    elevation: 1.0,
     input_bg: rgb(60, 60, 60),
     input_bg_focused: rgb(65, 65, 65),
     input_border: rgb(90, 90, 90),
     input_border_focused: rgb(100, 150, 200),
+    selection_text: rgb(255, 255, 255),
     input_placeholder: rgb(120, 120, 120),
+    input_inset: Rgba::new(0, 0, 0, 50),
+    focus_ring: Rgba::new(100, 150, 200, 128),
     progress_bg: rgb(60, 60, 60),
     progress_fill: rgb(70, 140, 220),
     progress_border: rgb(90, 90, 90),
     window_border: rgb(70, 70, 70),
     window_shadow: Rgba::new(0, 0, 0, 80),
+    window_radius: 8.0,
+    control_radius: 5.0,
+    border_width: 1.0,
+    spacing: 1.0,
+    control_height: 32,
+    font_size: 18.0,
 };
 
-/// Detect the current system theme.
+/// High-contrast theme: white on black, with yellow focus and selections
+/// and thicker outlines.
+pub static THEME_HIGH_CONTRAST: Theme = Theme {
+    window_bg: rgb(0, 0, 0),
+    text: rgb(255, 255, 255),
+    button: rgb(0, 0, 0),
+    button_hover: rgb(50, 50, 50),
+    button_pressed: rgb(90, 90, 90),
+    button_outline: rgb(255, 255, 255),
+    button_text: rgb(255, 255, 255),
+    button_gradient: 0.0,
+    button_shadow: Rgba::new(0, 0, 0, 0),
+    elevation: 0.0,
+    input_bg: rgb(0, 0, 0),
+    input_bg_focused: rgb(0, 0, 0),
+    input_border: rgb(255, 255, 255),
+    input_border_focused: rgb(255, 255, 0),
+    selection_text: rgb(0, 0, 0),
+    input_placeholder: rgb(190, 190, 190),
+    input_inset: Rgba::new(0, 0, 0, 0),
+    focus_ring: rgb(255, 255, 0),
+    progress_bg: rgb(0, 0, 0),
+    progress_fill: rgb(255, 255, 0),
+    progress_border: rgb(255, 255, 255),
+    window_border: rgb(255, 255, 255),
+    window_shadow: Rgba::new(0, 0, 0, 80),
+    window_radius: 8.0,
+    control_radius: 5.0,
+    border_width: 2.0,
+    spacing: 1.0,
+    control_height: 32,
+    font_size: 18.0,
+};
+
+/// Detect the current system theme, in the desktop's accent color if it
+/// has one, or high contrast if the desktop asks for it.
 /// Returns dark theme if detection fails.
-pub fn detect_theme() -> &'static Colors {
-    // Try to detect theme from environment
+///
+/// The desktop's preferences are followed as they change, so this can
+/// return a different theme later on.
+pub fn detect_theme() -> Theme {
+    if prefers_high_contrast() {
+        return THEME_HIGH_CONTRAST;
+    }
+    let theme = *detect_scheme();
+    match detect_accent() {
+        Some(accent) => theme.with_accent(accent),
+        None => theme,
+    }
+}
+
+static THEME: Mutex<Option<Theme>> = Mutex::new(None);
+
+/// Sets the theme of dialogs shown from now on that are not given one
+/// with `colors`, or goes back to the detected one with `None`.
+pub fn set_theme(theme: Option<Theme>) {
+    *THEME.lock().unwrap_or_else(PoisonError::into_inner) = theme;
+}
+
+/// Returns the theme set with [`set_theme`], or else the detected one.
+pub(crate) fn theme() -> Theme {
+    THEME
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .unwrap_or_else(detect_theme)
+}
+
+/// Reads a theme from a `theme.toml` file.
+///
+/// The file sets [`Theme`] fields by name, colors as `"#rrggbb"` or
+/// `"#rrggbbaa"` and the rest as numbers; `base = "light"` or `"dark"`
+/// picks the preset the others start from, otherwise the detected theme.
+/// Every line that could not be used is listed in
+/// [`Error::InvalidTheme`].
+pub fn load_theme(path: &Path) -> Result<Theme, Error> {
+    theme_file::load(path)
+}
+
+/// Returns the desktop's accent color, if it has one.
+pub fn detect_accent() -> Option<Rgba> {
+    static CONFIGURED: OnceLock<Option<Rgba>> = OnceLock::new();
+    accent::from_portal().or_else(|| *CONFIGURED.get_or_init(accent::from_config))
+}
+
+/// Whether the desktop's accessibility settings ask for high contrast.
+fn prefers_high_contrast() -> bool {
+    std::env::var("GTK_THEME").is_ok_and(|theme| theme.to_lowercase().contains("highcontrast"))
+        || appearance::current().is_some_and(|appearance| appearance.high_contrast)
+}
+
+/// Picks the light or dark preset, whichever the desktop prefers.
+fn detect_scheme() -> &'static Theme {
+    // A theme forced on GTK apps is followed here too
     if let Ok(theme) = std::env::var("GTK_THEME") {
         if theme.to_lowercase().contains("dark") {
             return &THEME_DARK;
@@ -88,22 +345,215 @@ pub fn detect_theme() -> &'static Colors {
         return &THEME_LIGHT;
     }
 
-    // Try gsettings
-    if let Ok(output) = std::process::Command::new("gsettings")
-        .args(["get", "org.gnome.desktop.interface", "color-scheme"])
-        .output()
-    {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        if stdout.contains("dark") {
-            return &THEME_DARK;
-        }
-        if stdout.contains("light") || stdout.contains("default") {
-            return &THEME_LIGHT;
+    match appearance::current().map(|appearance| appearance.color_scheme) {
+        Some(1) => &THEME_DARK,
+        Some(_) => &THEME_LIGHT,
+        // Default to dark
+        None => &THEME_DARK,
+    }
+}
+
+/// Where dialog windows appear on screen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Placement {
+    /// Wherever the window manager puts them.
+    #[default]
+    Default,
+    /// Centered on the screen.
+    Center,
+    /// Centered on the mouse pointer, kept on screen.
+    Mouse,
+    /// With the top-left corner at the given screen position.
+    At { x: i32, y: i32 },
+}
+
+static PLACEMENT: Mutex<Placement> = Mutex::new(Placement::Default);
+
+/// Where a builder was asked to put its dialog, over what was set for
+/// all dialogs.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct WindowPlacement {
+    pub placement: Option<Placement>,
+    /// Window the dialog is attached to and centered on
+    pub parent: Option<WindowHandle>,
+}
+
+/// Sets where dialogs shown from now on are placed.
+///
+/// Only X11 lets clients position their windows; on Wayland the
+/// compositor always decides.
+pub fn set_placement(placement: Placement) {
+    *PLACEMENT.lock().unwrap_or_else(PoisonError::into_inner) = placement;
+}
+
+static MONITOR: Mutex<Monitor> = Mutex::new(Monitor::Default);
+
+/// Sets the monitor dialogs shown from now on appear on.
+///
+/// On X11 this moves the dialog there; Wayland compositors pick the
+/// output themselves, so only its scale factor is used until they tell
+/// us which one the dialog is on.
+pub fn on_output(monitor: Monitor) {
+    *MONITOR.lock().unwrap_or_else(PoisonError::into_inner) = monitor;
+}
+
+static PARENT: Mutex<Option<WindowHandle>> = Mutex::new(None);
+
+/// Sets the window that dialogs shown from now on are attached to, or
+/// detaches them with `None`.
+///
+/// An attached dialog is modal for its parent and stays above it; an
+/// explicit [`Placement`] still overrides where it appears.
+pub fn set_parent(parent: Option<WindowHandle>) {
+    *PARENT.lock().unwrap_or_else(PoisonError::into_inner) = parent;
+}
+
+/// Returns the window set with [`set_parent`].
+pub(crate) fn parent() -> Option<WindowHandle> {
+    PARENT
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+static WINDOW_ICON: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Logical size of the window icon given to the window manager.
+const WINDOW_ICON_SIZE: u32 = 64;
+
+/// Sets the image file (SVG with the `svg` feature, or PNG, JPEG and WebP
+/// with the `images` feature) shown as the icon of dialogs from now on, or goes back to
+/// the desktop's default with `None`.
+pub fn set_window_icon(path: Option<PathBuf>) {
+    *WINDOW_ICON.lock().unwrap_or_else(PoisonError::into_inner) = path;
+}
+
+/// Sets the font family (e.g. `"Noto Sans"`) text in dialogs is drawn in
+/// from now on, or goes back to fontconfig's sans-serif font with `None`.
+///
+/// Characters the font lacks are drawn in other fonts fontconfig suggests.
+pub fn set_font(family: Option<&str>) {
+    crate::render::set_family(family.map(str::to_string));
+}
+
+/// Turns animations such as fading in and hover transitions on or off
+/// for dialogs shown from now on. They are also off when the desktop asks
+/// for reduced motion.
+pub fn set_animations(enabled: bool) {
+    animation::set_enabled(enabled);
+}
+
+/// Creates a dialog window of the given logical size, with a title bar
+/// drawn by us when the compositor doesn't decorate windows.
+pub(crate) fn create_window(
+    width: u16,
+    height: u16,
+    colors: &Theme,
+    placing: &WindowPlacement,
+) -> Result<DecoratedWindow, Error> {
+    let monitor = *MONITOR.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut window = DecoratedWindow::new(
+        backend::create_window(width, height, monitor, colors.window_bg.a < 255)?,
+        width,
+        height,
+        colors,
+    )?;
+
+    if let Some(parent) = placing.parent.clone().or_else(parent) {
+        window.set_parent(&parent)?;
+    }
+
+    let icon_path = WINDOW_ICON
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    // Rendered for the window's scale so it stays sharp on HiDPI screens
+    let icon_size = (WINDOW_ICON_SIZE as f32 * window.scale_factor()).ceil() as u32;
+    if let Some(icon) = icon_path.and_then(|path| Canvas::load_image(&path, icon_size, icon_size)) {
+        window.set_icon(&icon)?;
+    }
+
+    let (width, height) = (width as i32, height as i32);
+    // A dialog given its own parent stays centered on it, unless it was
+    // also given a placement
+    let placement = match (placing.placement, &placing.parent) {
+        (Some(placement), _) => Some(placement),
+        (None, Some(_)) => None,
+        (None, None) => Some(*PLACEMENT.lock().unwrap_or_else(PoisonError::into_inner)),
+    };
+    let center = |(mx, my, mw, mh): (i32, i32, u32, u32)| {
+        (mx + (mw as i32 - width) / 2, my + (mh as i32 - height) / 2)
+    };
+    let position = match placement {
+        None => None,
+        // A chosen monitor is only reached by placing the window on it
+        Some(Placement::Default) if monitor == Monitor::Default => None,
+        Some(Placement::Default | Placement::Center) => window.monitor_area().map(center),
+        Some(Placement::Mouse) => {
+            window.pointer_position().zip(window.monitor_area()).map(
+                |((px, py), (mx, my, mw, mh))| {
+                    (
+                        (px - width / 2).clamp(mx, mx + (mw as i32 - width).max(0)),
+                        (py - height / 2).clamp(my, my + (mh as i32 - height).max(0)),
+                    )
+                },
+            )
         }
+        Some(Placement::At {
+            x,
+            y,
+        }) => Some((x, y)),
+    };
+    if let Some((x, y)) = position {
+        window.set_position(x, y)?;
     }
 
-    // Default to dark
-    &THEME_DARK
+    Ok(window)
+}
+
+/// Runs a dialog's `show` drawn at `scale` in memory rather than on the
+/// display, returning what it first drew as PNG.
+pub(crate) fn render_to_image<T>(
+    scale: f32,
+    show: impl FnOnce() -> Result<T, Error>,
+) -> Result<Vec<u8>, Error> {
+    let (result, frame) = backend::headless::render(scale, show);
+    result?;
+    // Dialogs that never drew, like a form without fields or a file
+    // chooser left to the portal, have nothing to render
+    let frame = frame.ok_or(Error::BackendUnavailable {
+        tried: Vec::new(),
+    })?;
+    frame
+        .pixmap
+        .encode_png()
+        .map_err(|e| Error::Io(std::io::Error::other(e)))
+}
+
+/// Runs a dialog's blocking `show` on the runtime's threads for blocking
+/// work, resolving with what it returns. Tokio's are used when both
+/// runtimes are enabled.
+#[cfg(any(feature = "tokio", feature = "async-std"))]
+pub(crate) async fn show_blocking<T: Send + 'static>(
+    show: impl FnOnce() -> Result<T, Error> + Send + 'static,
+) -> Result<T, Error> {
+    #[cfg(feature = "tokio")]
+    {
+        match tokio::task::spawn_blocking(show).await {
+            Ok(result) => result,
+            // A panic in the dialog is the caller's, as with `show`
+            Err(err) => {
+                match err.try_into_panic() {
+                    Ok(panic) => std::panic::resume_unwind(panic),
+                    Err(err) => Err(Error::Io(std::io::Error::other(err))),
+                }
+            }
+        }
+    }
+    #[cfg(not(feature = "tokio"))]
+    {
+        async_std::task::spawn_blocking(show).await
+    }
 }
 
 /// Icon types for message dialogs.
@@ -127,6 +577,19 @@ impl Icon {
             other => Some(Icon::Custom(other.to_string())),
         }
     }
+
+    /// Returns the image file for a custom icon: the path it gives, or the
+    /// icon theme's file for an icon name, closest to `size` logical pixels
+    /// at `scale`.
+    pub(crate) fn file(&self, size: u32, scale: f32) -> Option<PathBuf> {
+        let Icon::Custom(name) = self else {
+            return None;
+        };
+        if name.contains('/') {
+            return Some(PathBuf::from(name));
+        }
+        icon_theme::lookup_icon(name, size, scale.ceil() as u32)
+    }
 }
 
 /// Button presets for message dialogs.
@@ -144,34 +607,63 @@ pub enum ButtonPreset {
 impl ButtonPreset {
     pub fn labels(&self) -> Vec<String> {
         match self {
-            ButtonPreset::Ok => vec!["OK".to_string()],
-            ButtonPreset::OkCancel => vec!["OK".to_string(), "Cancel".to_string()],
-            ButtonPreset::YesNo => vec!["Yes".to_string(), "No".to_string()],
+            ButtonPreset::Ok => vec![tr("OK").to_string()],
+            ButtonPreset::OkCancel => vec![tr("OK").to_string(), tr("Cancel").to_string()],
+            ButtonPreset::YesNo => vec![tr("Yes").to_string(), tr("No").to_string()],
             ButtonPreset::YesNoCancel => {
-                vec!["Yes".to_string(), "No".to_string(), "Cancel".to_string()]
+                vec![
+                    tr("Yes").to_string(),
+                    tr("No").to_string(),
+                    tr("Cancel").to_string(),
+                ]
             }
-            ButtonPreset::Close => vec!["Close".to_string()],
+            ButtonPreset::Close => vec![tr("Close").to_string()],
             ButtonPreset::Empty => vec![],
             ButtonPreset::Custom(labels) => labels.clone(),
         }
     }
+
+    /// Index of the button triggered by Escape, if the preset has one.
+    pub fn cancel_index(&self) -> Option<usize> {
+        match self {
+            ButtonPreset::OkCancel | ButtonPreset::YesNo => Some(1),
+            ButtonPreset::YesNoCancel => Some(2),
+            // Custom labels follow the OK/Cancel layout
+            ButtonPreset::Custom(labels) if labels.len() > 1 => Some(labels.len() - 1),
+            _ => None,
+        }
+    }
 }
 
 /// Dialog result indicating which button was pressed.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DialogResult {
     Button(usize),
+    /// A button was pressed while a checkbox was shown; carries its checked state.
+    ButtonWithCheckbox(usize, bool),
+    /// A button was pressed while switches were shown; carries whether each
+    /// is on, in order, followed by the checkbox's state if one was shown.
+    ButtonWithSwitches(usize, Vec<bool>),
     Closed,
     Timeout,
 }
 
 impl DialogResult {
-    pub fn exit_code(self) -> i32 {
+    pub fn exit_code(&self) -> i32 {
         match self {
-            DialogResult::Button(0) => 0,
-            DialogResult::Button(1) => 1,
-            DialogResult::Button(2) => 2,
-            DialogResult::Button(_) => 3, // Additional buttons
+            DialogResult::Button(0)
+            | DialogResult::ButtonWithCheckbox(0, _)
+            | DialogResult::ButtonWithSwitches(0, _) => 0,
+            DialogResult::Button(1)
+            | DialogResult::ButtonWithCheckbox(1, _)
+            | DialogResult::ButtonWithSwitches(1, _) => 1,
+            DialogResult::Button(2)
+            | DialogResult::ButtonWithCheckbox(2, _)
+            | DialogResult::ButtonWithSwitches(2, _) => 2,
+            DialogResult::Button(_)
+            | DialogResult::ButtonWithCheckbox(..)
+            | DialogResult::ButtonWithSwitches(..) => 3, // Additional buttons
             DialogResult::Timeout => 5,
             DialogResult::Closed => 255,
         }
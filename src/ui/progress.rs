@@ -4,29 +4,36 @@ use std::{
     io::{BufRead, BufReader},
     sync::mpsc::{self, TryRecvError},
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 #[cfg(unix)]
 use libc::{SIGTERM, getppid, kill};
 
 use crate::{
-    backend::{Window, WindowEvent, create_window},
+    backend::{Window, WindowEvent},
     error::Error,
     render::{Canvas, Font},
     ui::{
-        Colors,
-        widgets::{Widget, button::Button, progress_bar::ProgressBar},
+        Theme, WindowPlacement, create_window,
+        strings::tr,
+        widgets::{
+            Widget, button::Button, focus::FocusManager, progress_bar::ProgressBar,
+            spinner::Spinner,
+        },
     },
 };
 
 const BASE_PADDING: u32 = 20;
 const BASE_BAR_WIDTH: u32 = 300;
 const BASE_TEXT_HEIGHT: u32 = 20;
-const BASE_BUTTON_HEIGHT: u32 = 32;
+const BASE_SPINNER_SIZE: u32 = 16;
+
+const KEY_ESCAPE: u32 = 0xff1b;
 
 /// Progress dialog result.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ProgressResult {
     /// Progress completed (reached 100% or stdin closed).
     Completed,
@@ -66,7 +73,8 @@ pub struct ProgressBuilder {
     show_time_remaining: bool,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    colors: Option<Theme>,
+    pub(super) placing: WindowPlacement,
 }
 
 impl ProgressBuilder {
@@ -83,6 +91,7 @@ impl ProgressBuilder {
             width: None,
             height: None,
             colors: None,
+            placing: WindowPlacement::default(),
         }
     }
 
@@ -97,7 +106,7 @@ impl ProgressBuilder {
     }
 
     pub fn percentage(mut self, percentage: u32) -> Self {
-        self.percentage = percentage.min(100);
+        self.percentage = percentage;
         self
     }
 
@@ -116,8 +125,8 @@ impl ProgressBuilder {
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
         self
     }
 
@@ -141,23 +150,41 @@ impl ProgressBuilder {
         self
     }
 
+    /// Checks the percentage given, as `show` does before showing the
+    /// dialog.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.percentage > 100 {
+            return Err(Error::InvalidConfig(format!(
+                "percentage {} is over 100",
+                self.percentage
+            )));
+        }
+        Ok(())
+    }
+
     pub fn show(self) -> Result<ProgressResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+        self.validate()?;
+        #[cfg(feature = "tui")]
+        if crate::tui::enabled() {
+            return self.show_tui();
+        }
+
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
 
         // First pass: calculate LOGICAL dimensions using scale 1.0
-        let temp_font = Font::load(1.0);
-        let temp_button = Button::new("Cancel", &temp_font, 1.0);
+        let temp_font = colors.font(1.0);
+        let temp_button = Button::new(&tr("Cancel"), &temp_font, colors, 1.0);
         let temp_bar = ProgressBar::new(BASE_BAR_WIDTH, 1.0);
 
-        let calc_width = BASE_BAR_WIDTH + BASE_PADDING * 2;
+        let calc_width = BASE_BAR_WIDTH + colors.space(BASE_PADDING) * 2;
         let time_remaining_height = if self.show_time_remaining { 24 } else { 0 };
-        let calc_height = BASE_PADDING * 3
+        let calc_height = colors.space(BASE_PADDING) * 3
             + BASE_TEXT_HEIGHT
             + time_remaining_height
             + 10
             + temp_bar.height()
             + 10
-            + BASE_BUTTON_HEIGHT;
+            + colors.control_height;
         drop(temp_font);
         drop(temp_button);
 
@@ -166,26 +193,26 @@ impl ProgressBuilder {
         let logical_height = self.height.unwrap_or(calc_height) as u16;
 
         // Create window with LOGICAL dimensions
-        let mut window = create_window(logical_width, logical_height)?;
-        window.set_title(if self.title.is_empty() {
-            "Progress"
+        let mut window = create_window(logical_width, logical_height, colors, &self.placing)?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Progress")
         } else {
-            &self.title
+            self.title.as_str().into()
         })?;
 
         // Get the actual scale factor from the window (compositor scale)
         let scale = window.scale_factor();
 
         // Now create everything at PHYSICAL scale
-        let font = Font::load(scale);
+        let font = colors.font(scale);
         let mut cancel_button = if self.no_cancel {
             None
         } else {
-            Some(Button::new("Cancel", &font, scale))
+            Some(Button::new(&tr("Cancel"), &font, colors, scale))
         };
 
         // Scale dimensions for physical rendering
-        let padding = (BASE_PADDING as f32 * scale) as u32;
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
         let bar_width = (BASE_BAR_WIDTH as f32 * scale) as u32;
         let text_height = (BASE_TEXT_HEIGHT as f32 * scale) as u32;
 
@@ -204,7 +231,7 @@ impl ProgressBuilder {
         let mut status_text = self.text.clone();
 
         // Time remaining calculation
-        let start_time = std::time::Instant::now();
+        let start_time = Instant::now();
         let mut time_remaining_text = String::new();
 
         // Position elements in physical coordinates
@@ -213,6 +240,13 @@ impl ProgressBuilder {
         let bar_y = text_y + text_height as i32 + 10 + time_remaining_offset;
         progress_bar.set_position(padding as i32, bar_y);
 
+        // Spinner at the end of the status line while pulsating
+        let mut spinner = Spinner::new((BASE_SPINNER_SIZE as f32 * scale) as u32, scale);
+        spinner.set_position(
+            padding as i32 + bar_width as i32 - spinner.size() as i32,
+            text_y + (text_height as i32 - spinner.size() as i32) / 2,
+        );
+
         let button_y = bar_y + progress_bar.height() as i32 + (10.0 * scale) as i32;
         if let Some(ref mut cancel_button) = cancel_button {
             let button_x = physical_width as i32 - padding as i32 - cancel_button.width() as i32;
@@ -222,47 +256,16 @@ impl ProgressBuilder {
         // Create canvas at PHYSICAL dimensions
         let mut canvas = Canvas::new(physical_width, physical_height);
 
-        // Start stdin reader thread
-        let (tx, rx) = mpsc::channel();
-        thread::spawn(move || {
-            let stdin = std::io::stdin();
-            let reader = BufReader::new(stdin.lock());
-
-            for line in reader.lines() {
-                let line = match line {
-                    Ok(l) => l,
-                    Err(_) => break,
-                };
-
-                let trimmed = line.trim();
-
-                if let Some(text) = trimmed.strip_prefix('#') {
-                    // Status text update
-                    let text = text.trim().to_string();
-                    if tx.send(StdinMessage::Text(text)).is_err() {
-                        break;
-                    }
-                } else if trimmed.eq_ignore_ascii_case("pulsate") {
-                    if tx.send(StdinMessage::Pulsate).is_err() {
-                        break;
-                    }
-                } else if let Ok(num) = trimmed.parse::<u32>() {
-                    if tx.send(StdinMessage::Progress(num.min(100))).is_err() {
-                        break;
-                    }
-                }
-            }
-
-            let _ = tx.send(StdinMessage::Done);
-        });
+        let rx = read_stdin();
 
         // Draw function
         let draw = |canvas: &mut Canvas,
-                    colors: &Colors,
+                    colors: &Theme,
                     font: &Font,
                     status_text: &str,
                     time_remaining_text: &str,
                     progress_bar: &ProgressBar,
+                    spinner: &Spinner,
                     cancel_button: &Option<Button>,
                     padding: u32,
                     text_y: i32,
@@ -270,15 +273,14 @@ impl ProgressBuilder {
                     scale: f32| {
             let width = canvas.width() as f32;
             let height = canvas.height() as f32;
-            let radius = 8.0 * scale;
 
             canvas.fill_dialog_bg(
                 width,
                 height,
                 colors.window_bg,
                 colors.window_border,
-                colors.window_shadow,
-                radius,
+                colors.window_radius * scale,
+                colors.border_width,
             );
 
             // Draw status text
@@ -303,6 +305,9 @@ impl ProgressBuilder {
 
             // Draw progress bar
             progress_bar.draw(canvas, colors);
+            if progress_bar.is_pulsating() {
+                spinner.draw(canvas, colors);
+            }
 
             // Draw cancel button
             if let Some(button) = cancel_button {
@@ -310,21 +315,6 @@ impl ProgressBuilder {
             }
         };
 
-        let format_time_remaining = |seconds: f64| -> String {
-            if seconds < 60.0 {
-                format!("{:.0}s remaining", seconds)
-            } else if seconds < 3600.0 {
-                let mins = (seconds / 60.0).floor();
-                let secs = seconds % 60.0;
-                format!("{:.0}m {:.0}s remaining", mins, secs)
-            } else {
-                let hours = (seconds / 3600.0).floor();
-                let mins = ((seconds % 3600.0) / 60.0).floor();
-                let secs = seconds % 60.0;
-                format!("{:.0}h {:.0}m {:.0}s remaining", hours, mins, secs)
-            }
-        };
-
         // Initial draw
         draw(
             &mut canvas,
@@ -333,6 +323,7 @@ impl ProgressBuilder {
             &status_text,
             &time_remaining_text,
             &progress_bar,
+            &spinner,
             &cancel_button,
             padding,
             text_y,
@@ -343,6 +334,7 @@ impl ProgressBuilder {
         window.show()?;
 
         let auto_close = self.auto_close;
+        let mut focus = FocusManager::new();
 
         // Event loop with timeout for animation
         loop {
@@ -354,11 +346,7 @@ impl ProgressBuilder {
                     Ok(StdinMessage::Progress(p)) => {
                         progress_bar.set_percentage(p);
                         if self.show_time_remaining && !self.pulsate && p > 0 {
-                            let elapsed = start_time.elapsed().as_secs_f64();
-                            let progress_fraction = p as f64 / 100.0;
-                            let estimated_total = elapsed / progress_fraction;
-                            let remaining = (estimated_total - elapsed).max(0.0);
-                            time_remaining_text = format_time_remaining(remaining);
+                            time_remaining_text = time_remaining(start_time, p);
                         }
                         needs_redraw = true;
                         if p >= 100 && auto_close {
@@ -390,45 +378,29 @@ impl ProgressBuilder {
                 }
             }
 
-            // Poll for window events (non-blocking if pulsating)
-            let event = if progress_bar.is_pulsating() {
-                // Use short timeout for animation
-                match window.poll_for_event()? {
-                    Some(e) => Some(e),
-                    None => {
-                        // Tick animation and redraw
-                        progress_bar.tick();
-                        draw(
-                            &mut canvas,
-                            colors,
-                            &font,
-                            &status_text,
-                            &time_remaining_text,
-                            &progress_bar,
-                            &cancel_button,
-                            padding,
-                            text_y,
-                            self.show_time_remaining,
-                            scale,
-                        );
-                        window.set_contents(&canvas)?;
-                        std::thread::sleep(Duration::from_millis(16));
-                        continue;
-                    }
-                }
-            } else {
-                // Poll with short sleep to check stdin
-                window.poll_for_event()?
-            };
+            // Poll so stdin keeps being read; a pulsating bar asks for
+            // frames, which arrive as redraws
+            let event = window.poll_for_event()?;
 
             if let Some(event) = event {
+                let event = match &mut cancel_button {
+                    Some(cancel_button) => focus.process_event(event, &mut [cancel_button]),
+                    None => event,
+                };
                 match &event {
                     WindowEvent::CloseRequested => {
                         return Ok(ProgressResult::Closed);
                     }
+                    WindowEvent::KeyPress(key_event) if key_event.keysym == KEY_ESCAPE => {
+                        return Ok(ProgressResult::Closed);
+                    }
                     WindowEvent::RedrawRequested => {
                         needs_redraw = true;
                     }
+                    WindowEvent::SetText(text) => {
+                        status_text = text.clone();
+                        needs_redraw = true;
+                    }
                     _ => {}
                 }
 
@@ -457,6 +429,7 @@ impl ProgressBuilder {
                     &status_text,
                     &time_remaining_text,
                     &progress_bar,
+                    &spinner,
                     &cancel_button,
                     padding,
                     text_y,
@@ -466,9 +439,118 @@ impl ProgressBuilder {
                 window.set_contents(&canvas)?;
             }
 
-            // Short sleep to prevent CPU spinning when idle
-            if !needs_redraw && !progress_bar.is_pulsating() {
-                std::thread::sleep(Duration::from_millis(50));
+            // Short sleep to prevent CPU spinning when idle, short enough
+            // not to hold up animation frames
+            if !needs_redraw {
+                let idle = if progress_bar.is_pulsating() { 8 } else { 50 };
+                std::thread::sleep(Duration::from_millis(idle));
+            }
+        }
+    }
+
+    /// Shows the dialog on the terminal instead of in a window.
+    #[cfg(feature = "tui")]
+    fn show_tui(&self) -> Result<ProgressResult, Error> {
+        use crate::tui::{self, Key, Terminal};
+
+        let mut terminal = Terminal::open()?;
+        let rx = read_stdin();
+        let start_time = Instant::now();
+        let mut percentage = self.percentage;
+        let mut pulsating = self.pulsate;
+        let mut status_text = self.text.clone();
+        let mut time_remaining_text = String::new();
+        let mut finished = false;
+        let mut tick = 0usize;
+
+        loop {
+            loop {
+                match rx.try_recv() {
+                    Ok(StdinMessage::Progress(p)) => {
+                        percentage = p;
+                        if self.show_time_remaining && !self.pulsate && p > 0 {
+                            time_remaining_text = time_remaining(start_time, p);
+                        }
+                        if p >= 100 && self.auto_close {
+                            return Ok(ProgressResult::Completed);
+                        }
+                    }
+                    Ok(StdinMessage::Text(t)) => status_text = t,
+                    Ok(StdinMessage::Pulsate) => pulsating = true,
+                    Ok(StdinMessage::Done) | Err(TryRecvError::Disconnected) => {
+                        if self.auto_close {
+                            return Ok(ProgressResult::Completed);
+                        }
+                        finished = true;
+                        break;
+                    }
+                    Err(TryRecvError::Empty) => break,
+                }
+            }
+
+            let (width, _) = terminal.size();
+            let mut lines = tui::header(&self.title, width);
+            if !status_text.is_empty() {
+                lines.extend(tui::wrap(&status_text, width));
+            }
+            if self.show_time_remaining && !time_remaining_text.is_empty() {
+                lines.push(tui::fit(&time_remaining_text, width));
+            }
+            lines.push(String::new());
+
+            // A block bouncing back and forth while pulsating
+            let bar_width = width.saturating_sub(7).max(1);
+            let pulsing = pulsating && !finished;
+            let bar: String = if pulsing {
+                let block = (bar_width / 5).max(1);
+                let travel = bar_width - block;
+                let pos = if travel == 0 {
+                    0
+                } else {
+                    let step = tick % (2 * travel);
+                    step.min(2 * travel - step)
+                };
+                (0..bar_width)
+                    .map(|i| {
+                        if (pos..pos + block).contains(&i) {
+                            '█'
+                        } else {
+                            '░'
+                        }
+                    })
+                    .collect()
+            } else {
+                let filled = bar_width * percentage as usize / 100;
+                "█".repeat(filled) + &"░".repeat(bar_width - filled)
+            };
+            if pulsing {
+                lines.push(bar);
+            } else {
+                lines.push(format!("{bar} {percentage:>3}%"));
+            }
+            lines.push(String::new());
+            let hint = match (finished, self.no_cancel) {
+                (true, _) => tr("Enter: Close"),
+                (false, false) => tr("Esc: Cancel"),
+                (false, true) => "".into(),
+            };
+            lines.push(tui::fit(&hint, width));
+            terminal.present(&lines)?;
+
+            tick += 1;
+            match terminal.read_key(Some(Duration::from_millis(100)))? {
+                Some(Key::Enter) if finished => return Ok(ProgressResult::Completed),
+                Some(Key::Escape) if !self.no_cancel => {
+                    if self.auto_kill {
+                        #[cfg(unix)]
+                        unsafe {
+                            kill(getppid(), SIGTERM);
+                        }
+                    }
+                    return Ok(ProgressResult::Cancelled);
+                }
+                Some(Key::Interrupt) => return Ok(ProgressResult::Closed),
+                _ => {}
             }
         }
     }
@@ -479,3 +561,64 @@ impl Default for ProgressBuilder {
         Self::new()
     }
 }
+
+/// Reads progress updates from stdin on a separate thread.
+fn read_stdin() -> mpsc::Receiver<StdinMessage> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let stdin = std::io::stdin();
+        let reader = BufReader::new(stdin.lock());
+
+        for line in reader.lines() {
+            let line = match line {
+                Ok(l) => l,
+                Err(_) => break,
+            };
+
+            let trimmed = line.trim();
+
+            if let Some(text) = trimmed.strip_prefix('#') {
+                // Status text update
+                let text = text.trim().to_string();
+                if tx.send(StdinMessage::Text(text)).is_err() {
+                    break;
+                }
+            } else if trimmed.eq_ignore_ascii_case("pulsate") {
+                if tx.send(StdinMessage::Pulsate).is_err() {
+                    break;
+                }
+            } else if let Ok(num) = trimmed.parse::<u32>() {
+                if tx.send(StdinMessage::Progress(num.min(100))).is_err() {
+                    break;
+                }
+            }
+        }
+
+        let _ = tx.send(StdinMessage::Done);
+    });
+    rx
+}
+
+/// Estimates the time left from the time taken so far.
+fn time_remaining(start_time: Instant, percentage: u32) -> String {
+    let elapsed = start_time.elapsed().as_secs_f64();
+    let progress_fraction = percentage as f64 / 100.0;
+    let estimated_total = elapsed / progress_fraction;
+    let remaining = (estimated_total - elapsed).max(0.0);
+    format_time_remaining(remaining)
+}
+
+fn format_time_remaining(seconds: f64) -> String {
+    if seconds < 60.0 {
+        format!("{:.0}s remaining", seconds)
+    } else if seconds < 3600.0 {
+        let mins = (seconds / 60.0).floor();
+        let secs = seconds % 60.0;
+        format!("{:.0}m {:.0}s remaining", mins, secs)
+    } else {
+        let hours = (seconds / 3600.0).floor();
+        let mins = ((seconds % 3600.0) / 60.0).floor();
+        let secs = seconds % 60.0;
+        format!("{:.0}h {:.0}m {:.0}s remaining", hours, mins, secs)
+    }
+}
@@ -0,0 +1,261 @@
+//! QR code dialog implementation.
+
+use qrcodegen::{QrCode, QrCodeEcc};
+
+use crate::{
+    backend::{Window, WindowEvent},
+    error::Error,
+    render::{Align, Canvas, rgb},
+    ui::{
+        Theme, WindowPlacement, create_window,
+        strings::tr,
+        widgets::{Widget, button::Button, focus::FocusManager, label::Label},
+    },
+};
+
+const BASE_PADDING: u32 = 16;
+const BASE_GAP: u32 = 10;
+/// Preferred size of one module (QR "pixel") in logical pixels.
+const BASE_MODULE_SIZE: u32 = 6;
+const MIN_CODE_SIZE: u32 = 200;
+const MAX_CODE_SIZE: u32 = 480;
+/// Room for the button row when sizing the window to the code.
+const BASE_BUTTON_ROW: u32 = 32;
+/// Light border around the code, in modules, as scanners expect.
+const QUIET_ZONE: u32 = 4;
+
+const KEY_ESCAPE: u32 = 0xff1b;
+const KEY_RETURN: u32 = 0xff0d;
+const KEY_KP_ENTER: u32 = 0xff8d;
+
+/// QR code dialog result.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum QrResult {
+    /// User clicked OK.
+    Ok,
+    /// User pressed Escape.
+    Cancelled,
+    /// Dialog was closed.
+    Closed,
+}
+
+impl QrResult {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            QrResult::Ok => 0,
+            QrResult::Cancelled => 1,
+            QrResult::Closed => 255,
+        }
+    }
+}
+
+/// QR code dialog builder.
+pub struct QrBuilder {
+    title: String,
+    text: String,
+    caption: String,
+    width: Option<u32>,
+    height: Option<u32>,
+    colors: Option<Theme>,
+    pub(super) placing: WindowPlacement,
+}
+
+impl QrBuilder {
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            text: String::new(),
+            caption: String::new(),
+            width: None,
+            height: None,
+            colors: None,
+            placing: WindowPlacement::default(),
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// The text to encode.
+    pub fn text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
+    /// Caption shown under the code (e.g. "Scan to join the network").
+    pub fn caption(mut self, caption: &str) -> Self {
+        self.caption = caption.to_string();
+        self
+    }
+
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
+        self
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn show(self) -> Result<QrResult, Error> {
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
+        let code = QrCode::encode_text(&self.text, QrCodeEcc::Medium).map_err(|_| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "text is too long for a QR code",
+            ))
+        })?;
+        let modules = code.size() as u32 + QUIET_ZONE * 2;
+
+        // Size the window so each module gets a few pixels
+        let code_side = (modules * BASE_MODULE_SIZE).clamp(MIN_CODE_SIZE, MAX_CODE_SIZE);
+        let logical_width = self
+            .width
+            .unwrap_or(code_side + colors.space(BASE_PADDING) * 2);
+        let logical_height = self.height.unwrap_or(
+            code_side + colors.space(BASE_PADDING) * 2 + colors.space(BASE_GAP) + BASE_BUTTON_ROW,
+        );
+
+        let mut window = create_window(
+            logical_width as u16,
+            logical_height as u16,
+            colors,
+            &self.placing,
+        )?;
+        window.set_title(&if self.title.is_empty() {
+            tr("QR Code")
+        } else {
+            self.title.as_str().into()
+        })?;
+
+        // Get the actual scale factor from the window (compositor scale)
+        let scale = window.scale_factor();
+        let physical_width = (logical_width as f32 * scale) as u32;
+        let physical_height = (logical_height as f32 * scale) as u32;
+
+        // Now create everything at PHYSICAL scale
+        let font = colors.font(scale);
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
+        let gap = (colors.space(BASE_GAP) as f32 * scale) as u32;
+        let content_w = physical_width - padding * 2;
+
+        let mut ok_button = Button::new(&tr("OK"), &font, colors, scale);
+        let button_y = (physical_height - padding) as i32 - ok_button.height() as i32;
+        ok_button.set_position(
+            physical_width as i32 - padding as i32 - ok_button.width() as i32,
+            button_y,
+        );
+        ok_button.set_default(true);
+
+        let mut caption = Label::new(&self.caption)
+            .with_wrap(true)
+            .with_align(Align::Center);
+        caption.layout(&font, colors.text, Some(content_w));
+        let mut code_bottom = button_y - gap as i32;
+        if !self.caption.is_empty() {
+            let caption_y = code_bottom - caption.height() as i32;
+            caption.set_position(
+                padding as i32 + (content_w as i32 - caption.width() as i32) / 2,
+                caption_y,
+            );
+            code_bottom = caption_y - gap as i32;
+        }
+
+        // Whole pixels per module keep the edges sharp
+        let available = (code_bottom - padding as i32).max(0) as u32;
+        let module = (content_w.min(available) / modules).max(1);
+        let side = module * modules;
+        let code_x = (physical_width as i32 - side as i32) / 2;
+        let code_y = padding as i32 + (available as i32 - side as i32).max(0) / 2;
+
+        let mut qr = Canvas::new(side, side);
+        qr.fill(rgb(255, 255, 255));
+        for y in 0..code.size() {
+            for x in 0..code.size() {
+                if code.get_module(x, y) {
+                    qr.fill_rect(
+                        ((x as u32 + QUIET_ZONE) * module) as f32,
+                        ((y as u32 + QUIET_ZONE) * module) as f32,
+                        module as f32,
+                        module as f32,
+                        rgb(0, 0, 0),
+                    );
+                }
+            }
+        }
+
+        let mut canvas = Canvas::new(physical_width, physical_height);
+
+        let draw = |canvas: &mut Canvas, ok_button: &Button| {
+            let width = canvas.width() as f32;
+            let height = canvas.height() as f32;
+            canvas.fill_dialog_bg(
+                width,
+                height,
+                colors.window_bg,
+                colors.window_border,
+                colors.window_radius * scale,
+                colors.border_width,
+            );
+
+            canvas.draw_canvas(&qr, code_x, code_y);
+            caption.draw(canvas, colors);
+            ok_button.draw_to(canvas, colors, &font);
+        };
+
+        draw(&mut canvas, &ok_button);
+        window.set_contents(&canvas)?;
+        window.show()?;
+
+        let mut focus = FocusManager::new();
+
+        // Event loop; each pass handles every pending event, then redraws once
+        loop {
+            let mut next = Some(window.wait_for_event()?);
+            let mut needs_redraw = false;
+
+            while let Some(event) = next.take() {
+                let event = focus.process_event(event, &mut [&mut ok_button]);
+                match &event {
+                    WindowEvent::CloseRequested => return Ok(QrResult::Closed),
+                    WindowEvent::RedrawRequested => needs_redraw = true,
+                    WindowEvent::KeyPress(key) => {
+                        match key.keysym {
+                            KEY_ESCAPE => return Ok(QrResult::Cancelled),
+                            KEY_RETURN | KEY_KP_ENTER => return Ok(QrResult::Ok),
+                            _ => {}
+                        }
+                    }
+                    _ => {}
+                }
+
+                needs_redraw |= ok_button.process_event(&event);
+                if ok_button.was_clicked() {
+                    return Ok(QrResult::Ok);
+                }
+
+                next = window.poll_for_event()?;
+            }
+
+            if needs_redraw {
+                draw(&mut canvas, &ok_button);
+                window.set_contents(&canvas)?;
+            }
+        }
+    }
+}
+
+impl Default for QrBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
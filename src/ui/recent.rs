@@ -0,0 +1,56 @@
+//! Recently used files, as recorded by desktop applications in
+//! `~/.local/share/recently-used.xbel`.
+
+use std::{fs, path::PathBuf};
+
+use crate::ui::bookmarks::uri_to_path;
+
+/// Returns the recently used local files that still exist, most recent first.
+pub(crate) fn load() -> Vec<PathBuf> {
+    let Some(file) = dirs::data_dir().map(|d| d.join("recently-used.xbel")) else {
+        return Vec::new();
+    };
+    let Ok(xml) = fs::read_to_string(file) else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<(String, PathBuf)> = Vec::new();
+    for tag in xml.split("<bookmark ").skip(1) {
+        let Some(path) = attribute(tag, "href").and_then(|href| uri_to_path(&href)) else {
+            continue;
+        };
+        // ISO 8601 timestamps, so they order as strings
+        let used = ["modified", "visited"]
+            .iter()
+            .filter_map(|name| attribute(tag, name))
+            .max()
+            .unwrap_or_default();
+        if path.is_file() {
+            entries.push((used, path));
+        }
+    }
+
+    entries.sort_by(|a, b| b.0.cmp(&a.0));
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for (_, path) in entries {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Extracts and unescapes an attribute value from the start of a tag.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let tag = &tag[..tag.find('>')?];
+    let start = tag.find(&format!("{name}=\""))? + name.len() + 2;
+    let value = &tag[start..start + tag[start..].find('"')?];
+    Some(
+        value
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
@@ -0,0 +1,45 @@
+//! Colors recently chosen in the color dialog.
+//!
+//! Stored in `~/.cache/zenity-rs/recent-colors` as one `#rrggbb` per line,
+//! most recent first.
+
+use std::{fs, io, path::PathBuf};
+
+use crate::ui::color::parse_color;
+
+/// How many colors are remembered; one row in the dialog.
+const MAX_RECENT: usize = 9;
+
+/// Returns the recent colors, most recent first.
+pub(crate) fn load() -> Vec<[u8; 3]> {
+    file()
+        .and_then(|f| fs::read_to_string(f).ok())
+        .map(|text| {
+            text.lines()
+                .filter_map(parse_color)
+                .take(MAX_RECENT)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Moves `color` to the front of the recent colors.
+pub(crate) fn save(color: [u8; 3]) -> io::Result<()> {
+    let file = file().ok_or_else(|| io::Error::other("no cache directory"))?;
+    let mut colors = vec![color];
+    colors.extend(load().into_iter().filter(|c| *c != color));
+    colors.truncate(MAX_RECENT);
+
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let text: String = colors
+        .iter()
+        .map(|[r, g, b]| format!("#{r:02x}{g:02x}{b:02x}\n"))
+        .collect();
+    fs::write(file, text)
+}
+
+fn file() -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join("zenity-rs/recent-colors"))
+}
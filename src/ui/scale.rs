@@ -1,12 +1,16 @@
 //! Scale dialog implementation for selecting a numeric value with a slider.
 
 use crate::{
-    backend::{MouseButton, Window, WindowEvent, create_window},
+    backend::{MouseButton, Window, WindowEvent},
     error::Error,
     render::{Canvas, Font},
     ui::{
-        Colors,
-        widgets::{Widget, button::Button},
+        Theme, WindowPlacement, create_window,
+        hooks::Hooks,
+        strings::tr,
+        widgets::{
+            Widget, button::Button, focus::FocusManager, label::Label, spin_button::SpinButton,
+        },
     },
 };
 
@@ -18,6 +22,7 @@ const BASE_MIN_WIDTH: u32 = 350;
 
 /// Scale dialog result.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ScaleResult {
     /// User selected a value and clicked OK.
     Value(i32),
@@ -41,14 +46,16 @@ impl ScaleResult {
 pub struct ScaleBuilder {
     title: String,
     text: String,
-    value: i32,
+    value: Option<i32>,
     min_value: i32,
     max_value: i32,
     step: i32,
     hide_value: bool,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    colors: Option<Theme>,
+    hooks: Hooks<i32>,
+    pub(super) placing: WindowPlacement,
 }
 
 impl ScaleBuilder {
@@ -56,7 +63,7 @@ impl ScaleBuilder {
         Self {
             title: String::new(),
             text: String::new(),
-            value: 0,
+            value: None,
             min_value: 0,
             max_value: 100,
             step: 1,
@@ -64,6 +71,8 @@ impl ScaleBuilder {
             width: None,
             height: None,
             colors: None,
+            hooks: Hooks::default(),
+            placing: WindowPlacement::default(),
         }
     }
 
@@ -77,9 +86,9 @@ impl ScaleBuilder {
         self
     }
 
-    /// Set the initial value.
+    /// Set the initial value (default: the minimum value).
     pub fn value(mut self, value: i32) -> Self {
-        self.value = value;
+        self.value = Some(value);
         self
     }
 
@@ -97,7 +106,7 @@ impl ScaleBuilder {
 
     /// Set the step increment (default: 1).
     pub fn step(mut self, step: i32) -> Self {
-        self.step = step.max(1);
+        self.step = step;
         self
     }
 
@@ -107,8 +116,8 @@ impl ScaleBuilder {
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
         self
     }
 
@@ -122,34 +131,92 @@ impl ScaleBuilder {
         self
     }
 
-    pub fn show(self) -> Result<ScaleResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+    /// Calls `f` with the value each time the user moves it.
+    pub fn on_change(mut self, mut f: impl FnMut(i32) + Send + 'static) -> Self {
+        self.hooks.change = Some(Box::new(move |&value| f(value)));
+        self
+    }
+
+    /// Calls `f` with the button the dialog is dismissed with: 0 for OK or
+    /// Enter, 1 for Cancel or Escape.
+    pub fn on_button(mut self, f: impl FnMut(usize) + Send + 'static) -> Self {
+        self.hooks.button = Some(Box::new(f));
+        self
+    }
+
+    /// Calls `f` when the dialog is cancelled or closed.
+    pub fn on_cancel(mut self, f: impl FnMut() + Send + 'static) -> Self {
+        self.hooks.cancel = Some(Box::new(f));
+        self
+    }
+
+    /// Checks the range, step and value given, as `show` does before
+    /// showing the dialog.
+    pub fn validate(&self) -> Result<(), Error> {
+        let (min, max) = (self.min_value, self.max_value);
+        if min >= max {
+            return Err(Error::InvalidConfig(format!(
+                "maximum value {max} is not above minimum value {min}"
+            )));
+        }
+        if self.step < 1 {
+            return Err(Error::InvalidConfig(format!(
+                "step {} is not positive",
+                self.step
+            )));
+        }
+        let value = self.value.unwrap_or(min);
+        if !(min..=max).contains(&value) {
+            return Err(Error::InvalidConfig(format!(
+                "value {value} is not between {min} and {max}"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn show(mut self) -> Result<ScaleResult, Error> {
+        self.validate()?;
+        let mut hooks = std::mem::take(&mut self.hooks);
+        let result = self.run(&mut hooks)?;
+        let button = match result {
+            ScaleResult::Value(_) => Some(0),
+            ScaleResult::Cancelled => Some(1),
+            ScaleResult::Closed => None,
+        };
+        hooks.dismissed(button, button != Some(0));
+        Ok(result)
+    }
+
+    fn run(&self, hooks: &mut Hooks<i32>) -> Result<ScaleResult, Error> {
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
 
         // Clamp initial value to range
-        let mut value = self.value.clamp(self.min_value, self.max_value);
+        let mut value = self
+            .value
+            .unwrap_or(self.min_value)
+            .clamp(self.min_value, self.max_value);
 
         // First pass: calculate LOGICAL dimensions using scale 1.0
-        let temp_font = Font::load(1.0);
-        let temp_ok = Button::new("OK", &temp_font, 1.0);
-        let temp_cancel = Button::new("Cancel", &temp_font, 1.0);
-        let temp_prompt_height = if !self.text.is_empty() {
-            temp_font.render(&self.text).finish().height()
-        } else {
-            0
-        };
+        let temp_font = colors.font(1.0);
+        let temp_ok = Button::new(&tr("OK"), &temp_font, colors, 1.0);
+        let temp_cancel = Button::new(&tr("Cancel"), &temp_font, colors, 1.0);
+        let mut temp_prompt = Label::new(&self.text);
+        temp_prompt.layout(&temp_font, colors.text, None);
+        let temp_prompt_height = temp_prompt.height();
 
         let logical_buttons_width = temp_ok.width() + temp_cancel.width() + 10;
         let logical_content_width = BASE_SLIDER_WIDTH.max(logical_buttons_width);
-        let calc_width = (logical_content_width + BASE_PADDING * 2).max(BASE_MIN_WIDTH);
+        let calc_width =
+            (logical_content_width + colors.space(BASE_PADDING) * 2).max(BASE_MIN_WIDTH);
 
-        // Height: padding + text + slider area + value display + buttons + padding
-        let value_display_height = if self.hide_value { 0 } else { 24 };
-        let calc_height = BASE_PADDING * 2
+        // Height: padding + text + slider area + value entry + buttons + padding
+        let value_display_height = if self.hide_value { 0 } else { 40 };
+        let calc_height = colors.space(BASE_PADDING) * 2
             + temp_prompt_height
             + (if temp_prompt_height > 0 { 16 } else { 0 })
             + BASE_THUMB_SIZE + 16  // Slider area with some margin
             + value_display_height
-            + 32 + 16; // Buttons
+            + colors.control_height + 16; // Buttons
 
         drop(temp_font);
         drop(temp_ok);
@@ -160,21 +227,21 @@ impl ScaleBuilder {
         let logical_height = self.height.unwrap_or(calc_height) as u16;
 
         // Create window with LOGICAL dimensions
-        let mut window = create_window(logical_width, logical_height)?;
-        window.set_title(if self.title.is_empty() {
-            "Scale"
+        let mut window = create_window(logical_width, logical_height, colors, &self.placing)?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Scale")
         } else {
-            &self.title
+            self.title.as_str().into()
         })?;
 
         // Get the actual scale factor from the window (compositor scale)
         let scale = window.scale_factor();
 
         // Now create everything at PHYSICAL scale
-        let font = Font::load(scale);
+        let font = colors.font(scale);
 
         // Scale dimensions for physical rendering
-        let padding = (BASE_PADDING as f32 * scale) as u32;
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
         let slider_height = (BASE_SLIDER_HEIGHT as f32 * scale) as u32;
         let thumb_size = (BASE_THUMB_SIZE as f32 * scale) as u32;
         let slider_width = (BASE_SLIDER_WIDTH as f32 * scale) as u32;
@@ -184,22 +251,18 @@ impl ScaleBuilder {
         let physical_height = (logical_height as f32 * scale) as u32;
 
         // Create buttons at physical scale
-        let mut ok_button = Button::new("OK", &font, scale);
-        let mut cancel_button = Button::new("Cancel", &font, scale);
+        let mut ok_button = Button::new(&tr("OK"), &font, colors, scale);
+        let mut cancel_button = Button::new(&tr("Cancel"), &font, colors, scale);
 
         // Render prompt text at physical scale
-        let prompt_canvas = if !self.text.is_empty() {
-            Some(font.render(&self.text).with_color(colors.text).finish())
-        } else {
-            None
-        };
-        let prompt_height = prompt_canvas.as_ref().map(|c| c.height()).unwrap_or(0);
+        let mut prompt = Label::new(&self.text);
+        prompt.layout(&font, colors.text, None);
 
         // Layout calculation
         let mut y = padding as i32;
-        let prompt_y = y;
-        if prompt_height > 0 {
-            y += prompt_height as i32 + (16.0 * scale) as i32;
+        prompt.set_position(padding as i32, y);
+        if prompt.height() > 0 {
+            y += prompt.height() as i32 + (16.0 * scale) as i32;
         }
 
         // Slider position (centered horizontally)
@@ -208,8 +271,17 @@ impl ScaleBuilder {
         let thumb_y = y;
         y += thumb_size as i32 + (16.0 * scale) as i32;
 
+        // Entry for typing the exact value, centered under the slider
+        let mut spin = (!self.hide_value).then(|| {
+            let mut spin = SpinButton::new(self.min_value, self.max_value, value, &font, scale)
+                .with_step(self.step);
+            spin.set_position((physical_width - spin.width()) as i32 / 2, y);
+            spin
+        });
+
         // Button positions (right-aligned)
-        let button_y = physical_height as i32 - padding as i32 - (32.0 * scale) as i32;
+        let button_y =
+            physical_height as i32 - padding as i32 - (colors.control_height as f32 * scale) as i32;
         let mut button_x = physical_width as i32 - padding as i32;
         button_x -= cancel_button.width() as i32;
         cancel_button.set_position(button_x, button_y);
@@ -258,45 +330,38 @@ impl ScaleBuilder {
 
         // Draw function
         let draw = |canvas: &mut Canvas,
-                    colors: &Colors,
+                    colors: &Theme,
                     font: &Font,
-                    prompt_canvas: &Option<Canvas>,
+                    prompt: &Label,
                     value: i32,
                     thumb_hovered: bool,
                     dragging: bool,
                     ok_button: &Button,
                     cancel_button: &Button,
-                    hide_value: bool,
+                    spin: Option<&SpinButton>,
                     // Layout params
-                    padding: u32,
                     slider_x: i32,
                     slider_y: i32,
                     slider_width: u32,
                     slider_height: u32,
                     thumb_y: i32,
                     thumb_size: u32,
-                    value_y: i32,
-                    prompt_y: i32,
-                    physical_width: u32,
                     scale: f32,
                     value_to_thumb_x: &dyn Fn(i32) -> i32| {
             let width = canvas.width() as f32;
             let height = canvas.height() as f32;
-            let radius = 8.0 * scale;
 
             canvas.fill_dialog_bg(
                 width,
                 height,
                 colors.window_bg,
                 colors.window_border,
-                colors.window_shadow,
-                radius,
+                colors.window_radius * scale,
+                colors.border_width,
             );
 
             // Draw prompt
-            if let Some(prompt) = prompt_canvas {
-                canvas.draw_canvas(prompt, padding as i32, prompt_y);
-            }
+            prompt.draw(canvas, colors);
 
             // Draw slider track background
             canvas.fill_rounded_rect(
@@ -341,30 +406,19 @@ impl ScaleBuilder {
             } else {
                 colors.button
             };
-            canvas.fill_rounded_rect(
-                thumb_x as f32,
-                thumb_y as f32,
-                thumb_size as f32,
-                thumb_size as f32,
-                thumb_size as f32 / 2.0,
-                thumb_color,
-            );
-            canvas.stroke_rounded_rect(
-                thumb_x as f32,
-                thumb_y as f32,
-                thumb_size as f32,
-                thumb_size as f32,
-                thumb_size as f32 / 2.0,
+            let thumb_r = thumb_size as f32 / 2.0;
+            let (thumb_cx, thumb_cy) = (thumb_x as f32 + thumb_r, thumb_y as f32 + thumb_r);
+            canvas.fill_circle(thumb_cx, thumb_cy, thumb_r, thumb_color);
+            canvas.stroke_circle(
+                thumb_cx,
+                thumb_cy,
+                thumb_r - 0.5,
                 colors.button_outline,
-                1.0,
+                colors.border_width,
             );
 
-            // Draw value display
-            if !hide_value {
-                let value_text = value.to_string();
-                let value_canvas = font.render(&value_text).with_color(colors.text).finish();
-                let value_x = (physical_width - value_canvas.width()) as i32 / 2;
-                canvas.draw_canvas(&value_canvas, value_x, value_y);
+            if let Some(spin) = spin {
+                spin.draw_to(canvas, colors, font);
             }
 
             // Draw buttons
@@ -377,23 +431,19 @@ impl ScaleBuilder {
             &mut canvas,
             colors,
             &font,
-            &prompt_canvas,
+            &prompt,
             value,
             thumb_hovered,
             dragging,
             &ok_button,
             &cancel_button,
-            self.hide_value,
-            padding,
+            spin.as_ref(),
             slider_x,
             slider_y,
             slider_width,
             slider_height,
             thumb_y,
             thumb_size,
-            y,
-            prompt_y,
-            physical_width,
             scale,
             &value_to_thumb_x,
         );
@@ -401,9 +451,16 @@ impl ScaleBuilder {
         window.show()?;
 
         // Event loop
+        let mut focus = FocusManager::new();
+        let mut last_value = value;
         loop {
-            let event = window.wait_for_event()?;
+            let event = focus.process_event(
+                window.wait_for_event()?,
+                &mut focus_order(&mut spin, &mut ok_button, &mut cancel_button),
+            );
             let mut needs_redraw = false;
+            // Keys edit the value's text while its entry has focus
+            let typing = spin.as_ref().is_some_and(SpinButton::has_focus);
 
             match &event {
                 WindowEvent::CloseRequested => return Ok(ScaleResult::Closed),
@@ -476,27 +533,27 @@ impl ScaleBuilder {
                     const KEY_ESCAPE: u32 = 0xff1b;
 
                     match key_event.keysym {
-                        KEY_LEFT => {
+                        KEY_LEFT if !typing => {
                             let new_value = (value - self.step).max(self.min_value);
                             if new_value != value {
                                 value = new_value;
                                 needs_redraw = true;
                             }
                         }
-                        KEY_RIGHT => {
+                        KEY_RIGHT if !typing => {
                             let new_value = (value + self.step).min(self.max_value);
                             if new_value != value {
                                 value = new_value;
                                 needs_redraw = true;
                             }
                         }
-                        KEY_HOME => {
+                        KEY_HOME if !typing => {
                             if value != self.min_value {
                                 value = self.min_value;
                                 needs_redraw = true;
                             }
                         }
-                        KEY_END => {
+                        KEY_END if !typing => {
                             if value != self.max_value {
                                 value = self.max_value;
                                 needs_redraw = true;
@@ -514,6 +571,9 @@ impl ScaleBuilder {
                 _ => {}
             }
 
+            if let Some(spin) = &mut spin {
+                needs_redraw |= spin.process_event(&event);
+            }
             needs_redraw |= ok_button.process_event(&event);
             needs_redraw |= cancel_button.process_event(&event);
 
@@ -526,8 +586,13 @@ impl ScaleBuilder {
 
             // Batch process pending events
             while let Some(ev) = window.poll_for_event()? {
+                let ev = focus.process_event(
+                    ev,
+                    &mut focus_order(&mut spin, &mut ok_button, &mut cancel_button),
+                );
                 match &ev {
                     WindowEvent::CloseRequested => return Ok(ScaleResult::Closed),
+                    WindowEvent::RedrawRequested => needs_redraw = true,
                     WindowEvent::CursorMove(pos) if dragging => {
                         let new_value = x_to_value(pos.x as i32);
                         if new_value != value {
@@ -535,40 +600,51 @@ impl ScaleBuilder {
                             needs_redraw = true;
                         }
                     }
-                    WindowEvent::ButtonRelease(MouseButton::Left, _) => {
-                        if dragging {
-                            dragging = false;
-                            needs_redraw = true;
-                        }
+                    WindowEvent::ButtonRelease(MouseButton::Left, _) if dragging => {
+                        dragging = false;
+                        needs_redraw = true;
                     }
                     _ => {}
                 }
+                if let Some(spin) = &mut spin {
+                    needs_redraw |= spin.process_event(&ev);
+                }
                 needs_redraw |= ok_button.process_event(&ev);
                 needs_redraw |= cancel_button.process_event(&ev);
             }
 
+            // Keep the slider and the entry showing the same value
+            if let Some(spin) = &mut spin {
+                if spin.was_changed() {
+                    value = spin.value();
+                } else if spin.value() != value {
+                    spin.set_value(value);
+                }
+            }
+
+            if value != last_value {
+                last_value = value;
+                hooks.changed(&value);
+            }
+
             if needs_redraw {
                 draw(
                     &mut canvas,
                     colors,
                     &font,
-                    &prompt_canvas,
+                    &prompt,
                     value,
                     thumb_hovered,
                     dragging,
                     &ok_button,
                     &cancel_button,
-                    self.hide_value,
-                    padding,
+                    spin.as_ref(),
                     slider_x,
                     slider_y,
                     slider_width,
                     slider_height,
                     thumb_y,
                     thumb_size,
-                    y,
-                    prompt_y,
-                    physical_width,
                     scale,
                     &value_to_thumb_x,
                 );
@@ -578,6 +654,19 @@ impl ScaleBuilder {
     }
 }
 
+/// The dialog's widgets in Tab order: the value's entry if shown, then
+/// the buttons.
+fn focus_order<'a>(
+    spin: &'a mut Option<SpinButton>,
+    ok_button: &'a mut Button,
+    cancel_button: &'a mut Button,
+) -> Vec<&'a mut dyn Widget> {
+    spin.iter_mut()
+        .map(|spin| spin as &mut dyn Widget)
+        .chain([ok_button as &mut dyn Widget, cancel_button])
+        .collect()
+}
+
 impl Default for ScaleBuilder {
     fn default() -> Self {
         Self::new()
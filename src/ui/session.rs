@@ -0,0 +1,48 @@
+//! One display connection shared by dialogs shown one after another.
+
+use std::marker::PhantomData;
+
+use crate::backend::{self, AnyConnection};
+
+/// Keeps the connection to the display server that the first dialog
+/// shown on this thread makes open while the session lives, and shows the
+/// dialogs after it on the same one rather than connecting for each.
+///
+/// ```no_run
+/// let _session = zenity_rs::Session::new();
+/// let name = zenity_rs::entry().text("Name:").show()?;
+/// zenity_rs::info("Saved").show()?;
+/// # Ok::<(), zenity_rs::Error>(())
+/// ```
+///
+/// Dialogs spawned on threads of their own connect on their own.
+pub struct Session {
+    /// Sharing on this thread before the session, put back when it ends
+    previous: Option<Option<AnyConnection>>,
+    /// The session belongs to the thread it was started on
+    _thread: PhantomData<*const ()>,
+}
+
+impl Session {
+    /// Starts a session on this thread. Nothing connects until a dialog
+    /// is shown.
+    pub fn new() -> Self {
+        Self {
+            previous: backend::share_connection(Some(None)),
+            _thread: PhantomData,
+        }
+    }
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Session {
+    /// Stops sharing; the connection closes once no dialog uses it.
+    fn drop(&mut self) {
+        backend::share_connection(self.previous.take());
+    }
+}
@@ -0,0 +1,244 @@
+//! Translations of the text dialogs draw themselves, like button labels,
+//! headings and hints.
+//!
+//! Texts are looked up by their English wording, gettext-style, in the
+//! catalog for the user's language: a `zenity-rs.mo` file installed for
+//! it, or else one of the catalogs built in. Strings set with
+//! [`set_string`] are used over either.
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    env,
+    fmt::Display,
+    fs, mem,
+    path::PathBuf,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+/// Catalogs built in, as gettext `.po` files, by language
+const BUILT_IN: [(&str, &str); 3] = [
+    ("de", include_str!("../../assets/locale/de.po")),
+    ("es", include_str!("../../assets/locale/es.po")),
+    ("fr", include_str!("../../assets/locale/fr.po")),
+];
+
+/// Where `<language>/LC_MESSAGES/zenity-rs.mo` catalogs are installed,
+/// after `$ZENITY_RS_LOCALEDIR`
+const LOCALE_DIRS: [&str; 2] = ["/usr/local/share/locale", "/usr/share/locale"];
+
+/// Magic number opening a `.mo` file, as read in its own byte order
+const MO_MAGIC: u32 = 0x9504_12de;
+
+/// Strings set with [`set_string`], by the text they replace
+static OVERRIDES: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Replaces a text dialogs draw themselves with `translation` from now on,
+/// or goes back to the catalog's with `None`.
+///
+/// Texts are named by their English wording, mnemonic underscore
+/// included, like `"OK"`, `"_Cancel"` or `"PLACES"`. Where a number or
+/// name is filled in, it stands as `{}`: `"{} items"`.
+///
+/// ```
+/// zenity_rs::set_string("OK", Some("Got it"));
+/// zenity_rs::set_string("{} items", Some("{} files"));
+/// ```
+pub fn set_string(text: &str, translation: Option<&str>) {
+    let mut overrides = OVERRIDES.lock().unwrap_or_else(PoisonError::into_inner);
+    match translation {
+        Some(translation) => overrides.insert(text.to_string(), translation.to_string()),
+        None => overrides.remove(text),
+    };
+}
+
+/// Returns `text` in the user's language. Only strings set with
+/// [`set_string`] are copied; the catalog's are borrowed.
+pub(crate) fn tr(text: &'static str) -> Cow<'static, str> {
+    if let Some(translation) = OVERRIDES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(text)
+    {
+        return Cow::Owned(translation.clone());
+    }
+    Cow::Borrowed(catalog().get(text).map_or(text, String::as_str))
+}
+
+/// Returns `text` in the user's language with each `{}` in it filled in
+/// with the next of `args`.
+pub(crate) fn tr_args(text: &'static str, args: &[&dyn Display]) -> String {
+    let translation = tr(text);
+    let mut parts = translation.split("{}");
+    let mut out = parts.next().unwrap_or_default().to_string();
+    let mut args = args.iter();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+/// Returns the catalog for the user's language, loaded on first use.
+fn catalog() -> &'static HashMap<String, String> {
+    static CATALOG: OnceLock<HashMap<String, String>> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        languages()
+            .iter()
+            .find_map(|language| load_catalog(language))
+            .unwrap_or_default()
+    })
+}
+
+/// Loads the installed catalog for `language`, or else the built-in one.
+fn load_catalog(language: &str) -> Option<HashMap<String, String>> {
+    let dirs = env::var_os("ZENITY_RS_LOCALEDIR")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain(LOCALE_DIRS.map(PathBuf::from));
+    for dir in dirs {
+        let file = dir.join(language).join("LC_MESSAGES/zenity-rs.mo");
+        if let Some(catalog) = fs::read(file).ok().and_then(|data| parse_mo(&data)) {
+            return Some(catalog);
+        }
+    }
+    BUILT_IN
+        .iter()
+        .find(|(name, _)| *name == language)
+        .map(|(_, po)| parse_po(po))
+}
+
+/// The user's languages by preference, as gettext picks them from the
+/// environment, each followed by its base language: `pt_BR.UTF-8` gives
+/// `pt_BR`, then `pt`. Empty for the untranslated `C` locale.
+fn languages() -> Vec<String> {
+    let var = |name| env::var(name).ok().filter(|value| !value.is_empty());
+    let Some(locale) = var("LC_ALL")
+        .or_else(|| var("LC_MESSAGES"))
+        .or_else(|| var("LANG"))
+    else {
+        return Vec::new();
+    };
+    let name = |entry: &str| {
+        entry
+            .split(['.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    };
+    if matches!(name(&locale).as_str(), "C" | "POSIX") {
+        return Vec::new();
+    }
+
+    // LANGUAGE lists fallbacks, but only once a locale is set
+    let mut languages = Vec::new();
+    for entry in var("LANGUAGE").unwrap_or(locale).split(':').map(name) {
+        let base = entry.split('_').next().unwrap_or_default().to_string();
+        for language in [entry, base] {
+            if !language.is_empty() && !languages.contains(&language) {
+                languages.push(language);
+            }
+        }
+    }
+    languages
+}
+
+/// Reads a compiled gettext catalog. Plural forms keep their singular.
+fn parse_mo(data: &[u8]) -> Option<HashMap<String, String>> {
+    let word = |offset: usize, swap: bool| {
+        let bytes = data.get(offset..offset + 4)?.try_into().ok()?;
+        let value = u32::from_le_bytes(bytes);
+        Some(if swap { value.swap_bytes() } else { value } as usize)
+    };
+    let swap = match word(0, false)? as u32 {
+        MO_MAGIC => false,
+        magic if magic.swap_bytes() == MO_MAGIC => true,
+        _ => return None,
+    };
+    let (count, originals, translations) = (word(8, swap)?, word(12, swap)?, word(16, swap)?);
+
+    // Each table entry is a string's length and then its offset
+    let string = |table: usize, i: usize| {
+        let len = word(table + i * 8, swap)?;
+        let offset = word(table + i * 8 + 4, swap)?;
+        let bytes = data.get(offset..offset.checked_add(len)?)?;
+        let text = std::str::from_utf8(bytes).ok()?;
+        Some(text.split('\0').next().unwrap_or_default().to_string())
+    };
+    let mut catalog = HashMap::new();
+    for i in 0..count {
+        let (original, translation) = (string(originals, i)?, string(translations, i)?);
+        // The empty original holds the catalog's header
+        if !original.is_empty() && !translation.is_empty() {
+            catalog.insert(original, translation);
+        }
+    }
+    Some(catalog)
+}
+
+/// Reads a gettext `.po` catalog, leaving out untranslated texts.
+fn parse_po(po: &str) -> HashMap<String, String> {
+    let mut catalog = HashMap::new();
+    let (mut original, mut translation) = (String::new(), String::new());
+    let mut reading_translation = false;
+    // Each entry ends where the next begins, the last one at a stand-in
+    for line in po.lines().map(str::trim).chain(["msgid \"\""]) {
+        let quoted = if let Some(rest) = line.strip_prefix("msgid ") {
+            let entry = (mem::take(&mut original), mem::take(&mut translation));
+            if !entry.0.is_empty() && !entry.1.is_empty() {
+                catalog.insert(entry.0, entry.1);
+            }
+            reading_translation = false;
+            rest
+        } else if let Some(rest) = line.strip_prefix("msgstr ") {
+            reading_translation = true;
+            rest
+        } else if line.starts_with('"') {
+            line
+        } else {
+            continue;
+        };
+        let target = if reading_translation {
+            &mut translation
+        } else {
+            &mut original
+        };
+        let quoted = quoted.strip_prefix('"').unwrap_or(quoted);
+        unescape(quoted.strip_suffix('"').unwrap_or(quoted), target);
+    }
+    catalog
+}
+
+/// Appends a `.po` string's text, escapes resolved, to `out`.
+fn unescape(text: &str, out: &mut String) {
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_strings_replace_and_restore() {
+        set_string("Select Font", Some("Pick a typeface"));
+        set_string("Select Font", Some("Pick a font"));
+        assert_eq!(tr("Select Font"), "Pick a font");
+
+        set_string("Select Font", None);
+        assert_ne!(tr("Select Font"), "Pick a font");
+    }
+}
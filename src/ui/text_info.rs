@@ -1,14 +1,21 @@
 //! Text info dialog implementation for displaying text from files or stdin.
 
-use std::io::Read;
+use std::{io::Read, path::PathBuf};
 
 use crate::{
-    backend::{Window, WindowEvent, create_window},
+    backend::{CursorShape, Window, WindowEvent},
     error::Error,
-    render::{Canvas, Font, rgb},
+    render::{
+        Canvas, Font,
+        markup::{self, StyledText},
+    },
     ui::{
-        Colors,
-        widgets::{Widget, button::Button},
+        Theme, WindowPlacement, create_window,
+        strings::tr,
+        widgets::{
+            Widget, button::Button, checkbox::Checkbox, focus::FocusManager, link::Link,
+            scroll_area::ScrollArea,
+        },
     },
 };
 
@@ -22,6 +29,7 @@ const BASE_DEFAULT_HEIGHT: u32 = 400;
 
 /// Text info dialog result.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextInfoResult {
     /// User clicked OK. Contains whether checkbox was checked (if present).
     Ok { checkbox_checked: bool },
@@ -54,9 +62,12 @@ pub struct TextInfoBuilder {
     title: String,
     filename: Option<String>,
     checkbox_text: Option<String>,
+    link: Option<(String, String)>,
+    markup: bool,
     width: Option<u32>,
     height: Option<u32>,
-    colors: Option<&'static Colors>,
+    colors: Option<Theme>,
+    pub(super) placing: WindowPlacement,
 }
 
 impl TextInfoBuilder {
@@ -65,9 +76,12 @@ impl TextInfoBuilder {
             title: String::new(),
             filename: None,
             checkbox_text: None,
+            link: None,
+            markup: false,
             width: None,
             height: None,
             colors: None,
+            placing: WindowPlacement::default(),
         }
     }
 
@@ -88,8 +102,15 @@ impl TextInfoBuilder {
         self
     }
 
-    pub fn colors(mut self, colors: &'static Colors) -> Self {
-        self.colors = Some(colors);
+    /// Add a link left of the buttons (e.g., to the full license online).
+    /// The URI itself is shown if `text` is empty.
+    pub fn link(mut self, text: &str, uri: &str) -> Self {
+        self.link = Some((text.to_string(), uri.to_string()));
+        self
+    }
+
+    pub fn colors(mut self, colors: impl AsRef<Theme>) -> Self {
+        self.colors = Some(*colors.as_ref());
         self
     }
 
@@ -103,8 +124,15 @@ impl TextInfoBuilder {
         self
     }
 
+    /// Show the text as Pango markup. Off by default, as files are rarely
+    /// written as markup.
+    pub fn markup(mut self, markup: bool) -> Self {
+        self.markup = markup;
+        self
+    }
+
     pub fn show(self) -> Result<TextInfoResult, Error> {
-        let colors = self.colors.unwrap_or_else(|| crate::ui::detect_theme());
+        let colors = &self.colors.unwrap_or_else(crate::ui::theme);
 
         // Read content from file or stdin
         let content = if let Some(ref filename) = self.filename {
@@ -127,160 +155,133 @@ impl TextInfoBuilder {
             .max(BASE_MIN_HEIGHT);
 
         // Create window with LOGICAL dimensions
-        let mut window = create_window(logical_width as u16, logical_height as u16)?;
-        window.set_title(if self.title.is_empty() {
-            "Text"
+        let mut window = create_window(
+            logical_width as u16,
+            logical_height as u16,
+            colors,
+            &self.placing,
+        )?;
+        window.set_title(&if self.title.is_empty() {
+            tr("Text")
         } else {
-            &self.title
+            self.title.as_str().into()
         })?;
 
         // Get the actual scale factor from the window (compositor scale)
         let scale = window.scale_factor();
 
         // Now create everything at PHYSICAL scale
-        let font = Font::load(scale);
+        let font = colors.font(scale);
 
         // Scale dimensions for physical rendering
-        let padding = (BASE_PADDING as f32 * scale) as u32;
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
         let line_height = (BASE_LINE_HEIGHT as f32 * scale) as u32;
-        let checkbox_size = (BASE_CHECKBOX_SIZE as f32 * scale) as u32;
 
         // Calculate physical dimensions
         let physical_width = (logical_width as f32 * scale) as u32;
         let physical_height = (logical_height as f32 * scale) as u32;
 
         // Create buttons at physical scale
-        let mut ok_button = Button::new("OK", &font, scale);
-        let mut cancel_button = Button::new("Cancel", &font, scale);
+        let mut ok_button = Button::new(&tr("OK"), &font, colors, scale);
+        let mut cancel_button = Button::new(&tr("Cancel"), &font, colors, scale);
 
         // Layout calculation
-        let title_height = if self.title.is_empty() {
-            0
-        } else {
-            line_height + (8.0 * scale) as u32
-        };
-        let button_height = (32.0 * scale) as u32;
-        let checkbox_row_height = if has_checkbox {
-            checkbox_size + (8.0 * scale) as u32
-        } else {
-            0
-        };
-        let button_spacing = (24.0 * scale) as u32;
-        let button_y = (physical_height - padding - button_height) as i32;
-        let checkbox_y = if has_checkbox {
-            button_y - checkbox_row_height as i32 - (8.0 * scale) as i32
-        } else {
-            button_y
-        };
-
-        // Text area bounds (with more spacing below it)
-        let text_area_x = padding as i32;
-        let text_area_y = padding as i32 + title_height as i32;
-        let text_area_w = physical_width - padding * 2;
-        let text_area_bottom = if has_checkbox {
-            checkbox_y as u32 - button_spacing
-        } else {
-            button_y as u32 - button_spacing
-        };
-        let text_area_h = text_area_bottom - padding - (8.0 * scale) as u32;
-
-        // Calculate text wrapping - split content into wrapped lines
-        let max_text_width = text_area_w - (16.0 * scale) as u32; // Account for scrollbar
-        let mut wrapped_lines: Vec<String> = Vec::new();
-
-        for line in content.lines() {
-            if line.is_empty() {
-                wrapped_lines.push(String::new());
-            } else {
-                // Wrap long lines
-                let mut remaining = line;
-                while !remaining.is_empty() {
-                    let (line_w, _) = font.render(remaining).measure();
-                    if line_w as u32 <= max_text_width {
-                        wrapped_lines.push(remaining.to_string());
-                        break;
-                    }
+        let has_title = !self.title.is_empty();
+        let Layout {
+            mut button_y,
+            mut checkbox_y,
+            text_area_x,
+            text_area_y,
+            mut text_area_w,
+            mut text_area_h,
+            mut max_text_width,
+        } = Layout::new(
+            physical_width,
+            physical_height,
+            colors,
+            scale,
+            has_title,
+            has_checkbox,
+        );
 
-                    // Find break point
-                    let mut break_at = remaining.len();
-                    for (i, _) in remaining.char_indices().rev() {
-                        let test = &remaining[..i];
-                        let (w, _) = font.render(test).measure();
-                        if w as u32 <= max_text_width {
-                            // Try to break at word boundary
-                            if let Some(space_pos) = test.rfind(|c: char| c.is_whitespace()) {
-                                break_at = space_pos + 1;
-                            } else {
-                                break_at = i;
-                            }
-                            break;
-                        }
-                    }
+        let mut content = styled_text(content, self.markup);
+        let mut wrapped_lines = wrap_lines(&content, &font, max_text_width);
+        let mut visible_lines = (text_area_h / line_height) as usize;
+        let mut scroll = ScrollArea::new(text_area_w, text_area_h, scale);
+        scroll.set_position(text_area_x, text_area_y);
+        scroll.set_content(wrapped_lines.len(), visible_lines);
 
-                    if break_at == 0 {
-                        break_at = 1; // Ensure progress
-                    }
-
-                    wrapped_lines.push(remaining[..break_at].trim_end().to_string());
-                    remaining = remaining[break_at..].trim_start();
-                }
-            }
+        // Button positions (right-aligned)
+        let place_buttons = |ok_button: &mut Button,
+                             cancel_button: &mut Button,
+                             physical_width: u32,
+                             button_y: i32| {
+            let mut bx = physical_width as i32 - padding as i32;
+            bx -= cancel_button.width() as i32;
+            cancel_button.set_position(bx, button_y);
+            bx -= (10.0 * scale) as i32 + ok_button.width() as i32;
+            ok_button.set_position(bx, button_y);
+        };
+        place_buttons(&mut ok_button, &mut cancel_button, physical_width, button_y);
+
+        // Link on the left of the button row, level with the buttons
+        let mut link = self
+            .link
+            .as_ref()
+            .map(|(text, uri)| Link::new(text, uri, &font, scale));
+        let button_height = ok_button.height() as i32;
+        let place_link = |link: &mut Link, button_y: i32| {
+            let offset = (button_height - link.height() as i32) / 2;
+            link.set_position(padding as i32, button_y + offset);
+        };
+        if let Some(link) = &mut link {
+            place_link(link, button_y);
         }
 
-        let total_lines = wrapped_lines.len();
-        let visible_lines = (text_area_h / line_height) as usize;
-
-        // Button positions (right-aligned)
-        let mut bx = physical_width as i32 - padding as i32;
-        bx -= cancel_button.width() as i32;
-        cancel_button.set_position(bx, button_y);
-        bx -= (10.0 * scale) as i32 + ok_button.width() as i32;
-        ok_button.set_position(bx, button_y);
-
-        // State
-        let mut scroll_offset = 0usize;
-        let mut checkbox_checked = false;
-        let mut checkbox_hovered = false;
-        let mut scrollbar_hovered = false;
+        // Checkbox under the text, toggled by space as well
+        let mut checkbox = self.checkbox_text.as_ref().map(|text| {
+            let mut checkbox = Checkbox::new(text, &font, scale);
+            checkbox.set_position(padding as i32, checkbox_y);
+            checkbox
+        });
 
         // Create canvas at PHYSICAL dimensions
         let mut canvas = Canvas::new(physical_width, physical_height);
+        // Size from the latest resize, applied before the next redraw
+        let mut new_size: Option<(u32, u32)> = None;
+        // File dropped onto the window, shown in place of the text
+        let mut dropped: Option<PathBuf> = None;
 
         // Draw function
         let draw = |canvas: &mut Canvas,
-                    colors: &Colors,
+                    colors: &Theme,
                     font: &Font,
                     title: &str,
-                    wrapped_lines: &[String],
-                    scroll_offset: usize,
+                    wrapped_lines: &[StyledText],
+                    scroll: &ScrollArea,
                     visible_lines: usize,
-                    checkbox_text: &Option<String>,
-                    checkbox_checked: bool,
-                    checkbox_hovered: bool,
+                    checkbox: Option<&Checkbox>,
+                    link: Option<&Link>,
                     ok_button: &Button,
                     cancel_button: &Button,
                     // Scaled parameters
                     padding: u32,
                     line_height: u32,
-                    checkbox_size: u32,
                     text_area_x: i32,
                     text_area_y: i32,
                     text_area_w: u32,
                     text_area_h: u32,
-                    checkbox_y: i32,
-                    scale: f32,
-                    scrollbar_hovered: bool| {
+                    scale: f32| {
             let width = canvas.width() as f32;
             let height = canvas.height() as f32;
-            let radius = 8.0 * scale;
 
             canvas.fill_dialog_bg(
                 width,
                 height,
                 colors.window_bg,
                 colors.window_border,
-                colors.window_shadow,
-                radius,
+                colors.window_radius * scale,
+                colors.border_width,
             );
 
             // Draw title if present
@@ -306,59 +307,25 @@ impl TextInfoBuilder {
 
             // Draw visible lines
             let text_padding = (8.0 * scale) as i32;
-            for (i, line_idx) in
-                (scroll_offset..wrapped_lines.len().min(scroll_offset + visible_lines)).enumerate()
+            let first = scroll.offset();
+            for (i, line) in wrapped_lines
+                .iter()
+                .skip(first)
+                .take(visible_lines)
+                .enumerate()
             {
-                let line = &wrapped_lines[line_idx];
-                if !line.is_empty() {
-                    let tc = font.render(line).with_color(colors.text).finish();
+                if !line.text.is_empty() {
+                    let tc = font
+                        .render(&line.text)
+                        .with_runs(&line.runs)
+                        .with_color(colors.text)
+                        .finish();
                     let y = text_area_y + text_padding + (i as u32 * line_height) as i32;
                     canvas.draw_canvas(&tc, text_area_x + text_padding, y);
                 }
             }
 
-            // Scrollbar
-            if wrapped_lines.len() > visible_lines {
-                let scrollbar_width = if scrollbar_hovered {
-                    12.0 * scale
-                } else {
-                    8.0 * scale
-                };
-                let sb_x = text_area_x + text_area_w as i32 - scrollbar_width as i32;
-                let sb_y = text_area_y as f32 + 4.0 * scale;
-                let sb_h = text_area_h as f32 - 8.0 * scale;
-                let thumb_h =
-                    (visible_lines as f32 / wrapped_lines.len() as f32 * sb_h).max(20.0 * scale);
-                let max_scroll = wrapped_lines.len().saturating_sub(visible_lines);
-                let thumb_y = if max_scroll > 0 {
-                    scroll_offset as f32 / max_scroll as f32 * (sb_h - thumb_h)
-                } else {
-                    0.0
-                };
-
-                // Track
-                canvas.fill_rounded_rect(
-                    sb_x as f32,
-                    sb_y,
-                    scrollbar_width - 2.0 * scale,
-                    sb_h,
-                    3.0 * scale,
-                    darken(colors.input_bg, 0.05),
-                );
-                // Thumb
-                canvas.fill_rounded_rect(
-                    sb_x as f32,
-                    sb_y + thumb_y,
-                    scrollbar_width - 2.0 * scale,
-                    thumb_h,
-                    3.0 * scale,
-                    if scrollbar_hovered {
-                        colors.input_border_focused
-                    } else {
-                        colors.input_border
-                    },
-                );
-            }
+            scroll.draw(canvas, colors);
 
             // Border
             canvas.stroke_rounded_rect(
@@ -372,51 +339,12 @@ impl TextInfoBuilder {
             );
 
             // Checkbox
-            if let Some(cb_text) = checkbox_text {
-                let cb_x = padding as i32;
-                let cb_y = checkbox_y;
-
-                // Checkbox box
-                let cb_bg = if checkbox_hovered {
-                    darken(colors.input_bg, 0.06)
-                } else {
-                    colors.input_bg
-                };
-                canvas.fill_rounded_rect(
-                    cb_x as f32,
-                    cb_y as f32,
-                    checkbox_size as f32,
-                    checkbox_size as f32,
-                    3.0 * scale,
-                    cb_bg,
-                );
-                canvas.stroke_rounded_rect(
-                    cb_x as f32,
-                    cb_y as f32,
-                    checkbox_size as f32,
-                    checkbox_size as f32,
-                    3.0 * scale,
-                    colors.input_border,
-                    1.0,
-                );
-
-                // Check mark
-                if checkbox_checked {
-                    let inset = (3.0 * scale) as i32;
-                    canvas.fill_rounded_rect(
-                        (cb_x + inset) as f32,
-                        (cb_y + inset) as f32,
-                        (checkbox_size as i32 - inset * 2) as f32,
-                        (checkbox_size as i32 - inset * 2) as f32,
-                        2.0 * scale,
-                        colors.input_border_focused,
-                    );
-                }
+            if let Some(checkbox) = checkbox {
+                checkbox.draw_to(canvas, colors, font);
+            }
 
-                // Label
-                let label_x = cb_x + checkbox_size as i32 + (8.0 * scale) as i32;
-                let tc = font.render(cb_text).with_color(colors.text).finish();
-                canvas.draw_canvas(&tc, label_x, cb_y);
+            if let Some(link) = link {
+                link.draw_to(canvas, colors, font);
             }
 
             // Buttons
@@ -424,11 +352,19 @@ impl TextInfoBuilder {
             cancel_button.draw_to(canvas, colors, font);
         };
 
-        // Scrollbar thumb dragging state
-        let mut thumb_drag = false;
-        let mut thumb_drag_offset: Option<i32> = None;
-        let mut last_cursor_pos: Option<(i32, i32)> = None;
-        let mut clicking_scrollbar: bool;
+        // Space toggles the checkbox from the start
+        let mut focus = FocusManager::new();
+        if checkbox.is_some() {
+            focus.focus(
+                Some(0),
+                &mut focus_order(
+                    checkbox.as_mut(),
+                    link.as_mut(),
+                    &mut ok_button,
+                    &mut cancel_button,
+                ),
+            );
+        }
 
         // Initial draw
         draw(
@@ -437,244 +373,60 @@ impl TextInfoBuilder {
             &font,
             &self.title,
             &wrapped_lines,
-            scroll_offset,
+            &scroll,
             visible_lines,
-            &self.checkbox_text,
-            checkbox_checked,
-            checkbox_hovered,
+            checkbox.as_ref(),
+            link.as_ref(),
             &ok_button,
             &cancel_button,
             padding,
             line_height,
-            checkbox_size,
             text_area_x,
             text_area_y,
             text_area_w,
             text_area_h,
-            checkbox_y,
             scale,
-            scrollbar_hovered,
         );
         window.set_contents(&canvas)?;
+        window.set_resizable(BASE_MIN_WIDTH as u16, BASE_MIN_HEIGHT as u16)?;
         window.show()?;
 
         // Event loop
         loop {
-            let event = window.wait_for_event()?;
+            let event = focus.process_event(
+                window.wait_for_event()?,
+                &mut focus_order(
+                    checkbox.as_mut(),
+                    link.as_mut(),
+                    &mut ok_button,
+                    &mut cancel_button,
+                ),
+            );
             let mut needs_redraw = false;
 
             match &event {
                 WindowEvent::CloseRequested => return Ok(TextInfoResult::Closed),
                 WindowEvent::RedrawRequested => needs_redraw = true,
-                WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
-                    let mx = pos.x as i32;
-                    let my = pos.y as i32;
-
-                    // Store current cursor position
-                    last_cursor_pos = Some((mx, my));
-
-                    // Handle scrollbar thumb dragging
-                    if thumb_drag && total_lines > visible_lines {
-                        let text_area_my = my - text_area_y;
-
-                        let sb_y_f32 = 4.0 * scale;
-                        let sb_y = sb_y_f32 as i32;
-                        let sb_h_f32 = text_area_h as f32 - 8.0 * scale;
-                        let sb_h = sb_h_f32 as i32;
-
-                        let max_scroll = total_lines.saturating_sub(visible_lines);
-                        if max_scroll > 0 {
-                            let thumb_h_f32 = (visible_lines as f32 / total_lines as f32
-                                * sb_h_f32)
-                                .max(20.0 * scale);
-                            let thumb_h = thumb_h_f32 as i32;
-                            let max_thumb_y = sb_h - thumb_h;
-
-                            let offset = thumb_drag_offset.unwrap_or(thumb_h / 2);
-                            let thumb_y = (text_area_my - sb_y - offset).clamp(0, max_thumb_y);
-                            let scroll_ratio = if max_thumb_y > 0 {
-                                thumb_y as f32 / max_thumb_y as f32
-                            } else {
-                                0.0
-                            };
-                            scroll_offset =
-                                ((scroll_ratio * max_scroll as f32) as usize).clamp(0, max_scroll);
-                            needs_redraw = true;
-                        }
-                    } else {
-                        // Update scrollbar hover state (always, not just when there's a checkbox)
-                        let scrollbar_width = if scrollbar_hovered {
-                            12.0 * scale
-                        } else {
-                            8.0 * scale
-                        };
-                        let scrollbar_x = text_area_x + text_area_w as i32 - scrollbar_width as i32;
-
-                        scrollbar_hovered = total_lines > visible_lines
-                            && mx >= scrollbar_x
-                            && mx < text_area_x + text_area_w as i32
-                            && my >= text_area_y
-                            && my < text_area_y + text_area_h as i32;
-
-                        if has_checkbox {
-                            // Check if hovering checkbox area (only if not over scrollbar)
-                            let cb_x = padding as i32;
-                            let cb_row_width = checkbox_size as i32 + (8.0 * scale) as i32 + 200; // Approximate label width
-                            let old_hovered = checkbox_hovered;
-                            checkbox_hovered = !scrollbar_hovered
-                                && mx >= cb_x
-                                && mx < cb_x + cb_row_width
-                                && my >= checkbox_y
-                                && my < checkbox_y + checkbox_size as i32;
-
-                            if old_hovered != checkbox_hovered {
-                                needs_redraw = true;
-                            }
-                        }
-                    }
-                }
-                WindowEvent::ButtonPress(crate::backend::MouseButton::Left, _) => {
-                    clicking_scrollbar = false;
-
-                    // Check if clicking anywhere in scrollbar area (thumb OR track)
-                    if let Some((mx, my)) = last_cursor_pos {
-                        if total_lines > visible_lines {
-                            let scrollbar_width = if scrollbar_hovered {
-                                12.0 * scale
-                            } else {
-                                8.0 * scale
-                            };
-                            let scrollbar_x =
-                                text_area_x + text_area_w as i32 - scrollbar_width as i32;
-
-                            // Block all clicks in scrollbar area
-                            if mx >= scrollbar_x
-                                && mx < text_area_x + text_area_w as i32
-                                && my >= text_area_y
-                                && my < text_area_y + text_area_h as i32
-                            {
-                                clicking_scrollbar = true;
-
-                                // Now check if clicking specifically on the thumb for dragging
-                                let text_area_mx = mx - text_area_x;
-                                let text_area_my = my - text_area_y;
-
-                                let sb_x = text_area_w as i32 - scrollbar_width as i32;
-                                let sb_y_f32 = 4.0 * scale;
-                                let sb_y = sb_y_f32 as i32;
-                                let sb_h_f32 = text_area_h as f32 - 8.0 * scale;
-                                let sb_h = sb_h_f32 as i32;
-
-                                let thumb_h_f32 = (visible_lines as f32 / total_lines as f32
-                                    * sb_h_f32)
-                                    .max(20.0 * scale);
-                                let thumb_h = thumb_h_f32 as i32;
-
-                                let max_scroll = total_lines.saturating_sub(visible_lines);
-                                let thumb_y = if max_scroll > 0 {
-                                    let max_thumb_y = sb_h - thumb_h;
-                                    ((scroll_offset as f32 / max_scroll as f32)
-                                        * max_thumb_y as f32)
-                                        as i32
-                                } else {
-                                    0
-                                };
-
-                                if text_area_mx >= sb_x
-                                    && text_area_mx < sb_x + scrollbar_width as i32
-                                    && text_area_my >= sb_y + thumb_y
-                                    && text_area_my < sb_y + thumb_y + thumb_h
-                                {
-                                    thumb_drag = true;
-                                    thumb_drag_offset = Some(text_area_my - (sb_y + thumb_y));
-                                }
-                            }
-                        }
-                    }
-
-                    // Only process checkbox click if not clicking on scrollbar
-                    if !clicking_scrollbar && checkbox_hovered {
-                        checkbox_checked = !checkbox_checked;
-                        needs_redraw = true;
-                    }
-                }
-                WindowEvent::ButtonRelease(_, _) => {
-                    thumb_drag = false;
-                    thumb_drag_offset = None;
-                }
-                WindowEvent::Scroll(direction) => {
-                    match direction {
-                        crate::backend::ScrollDirection::Up => {
-                            if scroll_offset > 0 {
-                                scroll_offset = scroll_offset.saturating_sub(3);
-                                needs_redraw = true;
-                            }
-                        }
-                        crate::backend::ScrollDirection::Down => {
-                            let max_scroll = total_lines.saturating_sub(visible_lines);
-                            if scroll_offset < max_scroll {
-                                scroll_offset = (scroll_offset + 3).min(max_scroll);
-                                needs_redraw = true;
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-                WindowEvent::TextInput(c) => {
-                    // Handle space for checkbox toggle (TextInput is sent for printable chars)
-                    if *c == ' ' && has_checkbox {
-                        checkbox_checked = !checkbox_checked;
-                        needs_redraw = true;
-                    }
-                }
+                WindowEvent::Resized(w, h) => new_size = Some((*w, *h)),
+                WindowEvent::Drop(paths) => dropped = paths.first().cloned(),
                 WindowEvent::KeyPress(key_event) => {
                     const KEY_UP: u32 = 0xff52;
                     const KEY_DOWN: u32 = 0xff54;
-                    const KEY_PAGE_UP: u32 = 0xff55;
-                    const KEY_PAGE_DOWN: u32 = 0xff56;
                     const KEY_HOME: u32 = 0xff50;
                     const KEY_END: u32 = 0xff57;
                     const KEY_RETURN: u32 = 0xff0d;
                     const KEY_ESCAPE: u32 = 0xff1b;
 
-                    let max_scroll = total_lines.saturating_sub(visible_lines);
-
                     match key_event.keysym {
-                        KEY_UP => {
-                            if scroll_offset > 0 {
-                                scroll_offset = scroll_offset.saturating_sub(1);
-                                needs_redraw = true;
-                            }
-                        }
-                        KEY_DOWN => {
-                            if scroll_offset < max_scroll {
-                                scroll_offset = (scroll_offset + 1).min(max_scroll);
-                                needs_redraw = true;
-                            }
-                        }
-                        KEY_PAGE_UP => {
-                            scroll_offset = scroll_offset.saturating_sub(visible_lines);
-                            needs_redraw = true;
-                        }
-                        KEY_PAGE_DOWN => {
-                            scroll_offset = (scroll_offset + visible_lines).min(max_scroll);
-                            needs_redraw = true;
-                        }
-                        KEY_HOME => {
-                            if scroll_offset > 0 {
-                                scroll_offset = 0;
-                                needs_redraw = true;
-                            }
-                        }
-                        KEY_END => {
-                            if scroll_offset < max_scroll {
-                                scroll_offset = max_scroll;
-                                needs_redraw = true;
-                            }
-                        }
+                        KEY_UP => needs_redraw |= scroll.scroll_by(-1),
+                        KEY_DOWN => needs_redraw |= scroll.scroll_by(1),
+                        KEY_HOME => needs_redraw |= scroll.scroll_to(0),
+                        KEY_END => needs_redraw |= scroll.scroll_to(usize::MAX),
                         KEY_RETURN => {
                             return Ok(TextInfoResult::Ok {
-                                checkbox_checked,
+                                checkbox_checked: checkbox
+                                    .as_ref()
+                                    .is_some_and(Checkbox::is_checked),
                             });
                         }
                         KEY_ESCAPE => {
@@ -686,12 +438,26 @@ impl TextInfoBuilder {
                 _ => {}
             }
 
+            needs_redraw |= scroll.process_event(&event);
+            if let Some(checkbox) = &mut checkbox {
+                needs_redraw |= checkbox.process_event(&event);
+            }
+            if let Some(link) = &mut link {
+                needs_redraw |= link.process_event(&event);
+                if let WindowEvent::CursorMove(_) = &event {
+                    let _ = window.set_cursor(if link.is_hovered() {
+                        CursorShape::Pointer
+                    } else {
+                        CursorShape::Default
+                    });
+                }
+            }
             needs_redraw |= ok_button.process_event(&event);
             needs_redraw |= cancel_button.process_event(&event);
 
             if ok_button.was_clicked() {
                 return Ok(TextInfoResult::Ok {
-                    checkbox_checked,
+                    checkbox_checked: checkbox.as_ref().is_some_and(Checkbox::is_checked),
                 });
             }
             if cancel_button.was_clicked() {
@@ -700,61 +466,79 @@ impl TextInfoBuilder {
 
             // Batch process pending events
             while let Some(ev) = window.poll_for_event()? {
+                let ev = focus.process_event(
+                    ev,
+                    &mut focus_order(
+                        checkbox.as_mut(),
+                        link.as_mut(),
+                        &mut ok_button,
+                        &mut cancel_button,
+                    ),
+                );
                 match &ev {
                     WindowEvent::CloseRequested => {
                         return Ok(TextInfoResult::Closed);
                     }
-                    WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) => {
-                        last_cursor_pos = Some((pos.x as i32, pos.y as i32));
-                    }
-                    WindowEvent::ButtonPress(button, _modifiers)
-                        if *button == crate::backend::MouseButton::Left =>
-                    {
-                        if let Some((mx, my)) = last_cursor_pos {
-                            if total_lines > visible_lines {
-                                let sb_x = text_area_w as i32 - (10.0 * scale) as i32;
-                                let sb_y_f32 = 4.0 * scale;
-                                let sb_y = sb_y_f32 as i32;
-                                let sb_h_f32 = text_area_h as f32 - 8.0 * scale;
-                                let sb_h = sb_h_f32 as i32;
-
-                                let thumb_h_f32 = (visible_lines as f32 / total_lines as f32
-                                    * sb_h_f32)
-                                    .max(20.0 * scale);
-                                let thumb_h = thumb_h_f32 as i32;
-
-                                let max_scroll = total_lines.saturating_sub(visible_lines);
-                                let max_thumb_y = sb_h - thumb_h;
-                                let thumb_y = if max_scroll > 0 {
-                                    ((scroll_offset as f32 / max_scroll as f32)
-                                        * max_thumb_y as f32)
-                                        as i32
-                                } else {
-                                    0
-                                };
-
-                                if mx >= text_area_x + sb_x
-                                    && mx < text_area_x + sb_x + (6.0 * scale) as i32
-                                    && my >= text_area_y + sb_y + thumb_y
-                                    && my < text_area_y + sb_y + thumb_y + thumb_h
-                                {
-                                    thumb_drag = true;
-                                    thumb_drag_offset = Some(my - (text_area_y + sb_y + thumb_y));
-                                }
-                            }
-                        }
-                    }
-                    WindowEvent::ButtonRelease(_, _) => {
-                        thumb_drag = false;
-                        thumb_drag_offset = None;
-                    }
+                    WindowEvent::RedrawRequested => needs_redraw = true,
+                    WindowEvent::Resized(w, h) => new_size = Some((*w, *h)),
+                    WindowEvent::Drop(paths) => dropped = paths.first().cloned(),
                     _ => {}
                 }
 
+                needs_redraw |= scroll.process_event(&ev);
+                if let Some(checkbox) = &mut checkbox {
+                    needs_redraw |= checkbox.process_event(&ev);
+                }
+                if let Some(link) = &mut link {
+                    needs_redraw |= link.process_event(&ev);
+                    if let WindowEvent::CursorMove(_) = &ev {
+                        let _ = window.set_cursor(if link.is_hovered() {
+                            CursorShape::Pointer
+                        } else {
+                            CursorShape::Default
+                        });
+                    }
+                }
                 needs_redraw |= ok_button.process_event(&ev);
                 needs_redraw |= cancel_button.process_event(&ev);
             }
 
+            // Files that can't be read as text are ignored
+            if let Some(path) = dropped.take()
+                && let Ok(text) = std::fs::read_to_string(&path)
+            {
+                content = styled_text(text, self.markup);
+                wrapped_lines = wrap_lines(&content, &font, max_text_width);
+                scroll.set_content(wrapped_lines.len(), visible_lines);
+                scroll.scroll_to(0);
+                needs_redraw = true;
+            }
+
+            // Reflow the text and move everything to fit the new size
+            if let Some((width, height)) = new_size.take() {
+                Layout {
+                    button_y,
+                    checkbox_y,
+                    text_area_w,
+                    text_area_h,
+                    max_text_width,
+                    ..
+                } = Layout::new(width, height, colors, scale, has_title, has_checkbox);
+                wrapped_lines = wrap_lines(&content, &font, max_text_width);
+                visible_lines = (text_area_h / line_height) as usize;
+                scroll.set_size(text_area_w, text_area_h);
+                scroll.set_content(wrapped_lines.len(), visible_lines);
+                place_buttons(&mut ok_button, &mut cancel_button, width, button_y);
+                if let Some(checkbox) = &mut checkbox {
+                    checkbox.set_position(padding as i32, checkbox_y);
+                }
+                if let Some(link) = &mut link {
+                    place_link(link, button_y);
+                }
+                canvas = Canvas::new(width, height);
+                needs_redraw = true;
+            }
+
             if needs_redraw {
                 draw(
                     &mut canvas,
@@ -762,23 +546,19 @@ impl TextInfoBuilder {
                     &font,
                     &self.title,
                     &wrapped_lines,
-                    scroll_offset,
+                    &scroll,
                     visible_lines,
-                    &self.checkbox_text,
-                    checkbox_checked,
-                    checkbox_hovered,
+                    checkbox.as_ref(),
+                    link.as_ref(),
                     &ok_button,
                     &cancel_button,
                     padding,
                     line_height,
-                    checkbox_size,
                     text_area_x,
                     text_area_y,
                     text_area_w,
                     text_area_h,
-                    checkbox_y,
                     scale,
-                    scrollbar_hovered,
                 );
                 window.set_contents(&canvas)?;
             }
@@ -786,16 +566,161 @@ impl TextInfoBuilder {
     }
 }
 
+/// Positions that depend on the window size, in physical pixels.
+struct Layout {
+    button_y: i32,
+    checkbox_y: i32,
+    text_area_x: i32,
+    text_area_y: i32,
+    text_area_w: u32,
+    text_area_h: u32,
+    /// Width available to text, leaving room for the scrollbar.
+    max_text_width: u32,
+}
+
+impl Layout {
+    fn new(
+        physical_width: u32,
+        physical_height: u32,
+        colors: &Theme,
+        scale: f32,
+        has_title: bool,
+        has_checkbox: bool,
+    ) -> Self {
+        let padding = (colors.space(BASE_PADDING) as f32 * scale) as u32;
+        let line_height = (BASE_LINE_HEIGHT as f32 * scale) as u32;
+        let checkbox_size = (BASE_CHECKBOX_SIZE as f32 * scale) as u32;
+
+        let title_height = if has_title {
+            line_height + (8.0 * scale) as u32
+        } else {
+            0
+        };
+        let button_height = (colors.control_height as f32 * scale) as u32;
+        let checkbox_row_height = if has_checkbox {
+            checkbox_size + (8.0 * scale) as u32
+        } else {
+            0
+        };
+        let button_spacing = (24.0 * scale) as u32;
+        let button_y = (physical_height - padding - button_height) as i32;
+        let checkbox_y = if has_checkbox {
+            button_y - checkbox_row_height as i32 - (8.0 * scale) as i32
+        } else {
+            button_y
+        };
+
+        // Text area bounds (with more spacing below it)
+        let text_area_x = padding as i32;
+        let text_area_y = padding as i32 + title_height as i32;
+        let text_area_w = physical_width - padding * 2;
+        let text_area_bottom = if has_checkbox {
+            checkbox_y as u32 - button_spacing
+        } else {
+            button_y as u32 - button_spacing
+        };
+        let text_area_h = text_area_bottom - padding - (8.0 * scale) as u32;
+
+        Self {
+            button_y,
+            checkbox_y,
+            text_area_x,
+            text_area_y,
+            text_area_w,
+            text_area_h,
+            max_text_width: text_area_w - (16.0 * scale) as u32,
+        }
+    }
+}
+
+/// Reads `text` as markup if asked to and valid, and as plain text
+/// otherwise.
+fn styled_text(text: String, markup: bool) -> StyledText {
+    markup
+        .then(|| markup::parse(&text))
+        .flatten()
+        .unwrap_or(StyledText {
+            text,
+            runs: Vec::new(),
+        })
+}
+
+/// Splits `content` into lines that fit in `max_width`, breaking at
+/// whitespace where possible.
+fn wrap_lines(content: &StyledText, font: &Font, max_width: u32) -> Vec<StyledText> {
+    let mut wrapped_lines: Vec<StyledText> = Vec::new();
+    // Lines are slices of the content, so their runs are found by offset
+    let offset = |slice: &str| slice.as_ptr() as usize - content.text.as_ptr() as usize;
+    let styled = |slice: &str| {
+        let start = offset(slice);
+        StyledText {
+            text: slice.to_string(),
+            runs: content.runs_in(start..start + slice.len()),
+        }
+    };
+    let width = |slice: &str| {
+        let runs = styled(slice).runs;
+        font.render(slice).with_runs(&runs).measure().0
+    };
+
+    for line in content.text.lines() {
+        if line.is_empty() {
+            wrapped_lines.push(StyledText::default());
+        } else {
+            // Wrap long lines
+            let mut remaining = line;
+            while !remaining.is_empty() {
+                if width(remaining) as u32 <= max_width {
+                    wrapped_lines.push(styled(remaining));
+                    break;
+                }
+
+                // Find break point
+                let mut break_at = remaining.len();
+                for (i, _) in remaining.char_indices().rev() {
+                    let test = &remaining[..i];
+                    if width(test) as u32 <= max_width {
+                        // Try to break at word boundary
+                        if let Some(space_pos) = test.rfind(|c: char| c.is_whitespace()) {
+                            break_at = space_pos + 1;
+                        } else {
+                            break_at = i;
+                        }
+                        break;
+                    }
+                }
+
+                if break_at == 0 {
+                    break_at = 1; // Ensure progress
+                }
+
+                wrapped_lines.push(styled(remaining[..break_at].trim_end()));
+                remaining = remaining[break_at..].trim_start();
+            }
+        }
+    }
+
+    wrapped_lines
+}
+
 impl Default for TextInfoBuilder {
     fn default() -> Self {
         Self::new()
     }
 }
 
-fn darken(color: crate::render::Rgba, amount: f32) -> crate::render::Rgba {
-    rgb(
-        (color.r as f32 * (1.0 - amount)) as u8,
-        (color.g as f32 * (1.0 - amount)) as u8,
-        (color.b as f32 * (1.0 - amount)) as u8,
-    )
+/// The dialog's widgets in Tab order: the checkbox and link if any, then
+/// the buttons.
+fn focus_order<'a>(
+    checkbox: Option<&'a mut Checkbox>,
+    link: Option<&'a mut Link>,
+    ok_button: &'a mut Button,
+    cancel_button: &'a mut Button,
+) -> Vec<&'a mut dyn Widget> {
+    checkbox
+        .into_iter()
+        .map(|checkbox| checkbox as &mut dyn Widget)
+        .chain(link.into_iter().map(|link| link as &mut dyn Widget))
+        .chain([ok_button as &mut dyn Widget, cancel_button])
+        .collect()
 }
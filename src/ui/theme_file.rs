@@ -0,0 +1,271 @@
+//! Themes read from `theme.toml` files.
+//!
+//! A file sets [`Theme`] fields by name, one `key = value` per line:
+//! colors as `"#rrggbb"` or `"#rrggbbaa"` strings, everything else as
+//! numbers. Fields it leaves out come from `base`, which is `"light"`,
+//! `"dark"`, `"high-contrast"` or, by default, whatever the desktop
+//! prefers.
+//!
+//! ```toml
+//! base = "dark"
+//! window_bg = "#1e1e2ee6"
+//! progress_fill = "#89b4fa"
+//! control_radius = 8
+//! font_size = 16.5
+//! ```
+
+use std::{fmt, path::Path};
+
+use crate::{
+    error::Error,
+    render::Rgba,
+    ui::{THEME_DARK, THEME_HIGH_CONTRAST, THEME_LIGHT, Theme, detect_theme},
+};
+
+/// A line of a theme file that could not be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThemeError {
+    /// Line number, counting from 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Reads a theme file; see [`parse`].
+pub(crate) fn load(path: &Path) -> Result<Theme, Error> {
+    let text = std::fs::read_to_string(path)?;
+    parse(&text).map_err(|errors| {
+        Error::InvalidTheme {
+            path: path.to_path_buf(),
+            errors,
+        }
+    })
+}
+
+/// Builds a theme from the text of a theme file, or returns every line
+/// that is wrong with it.
+pub(crate) fn parse(text: &str) -> Result<Theme, Vec<ThemeError>> {
+    let mut errors = Vec::new();
+    let mut entries: Vec<(usize, &str, Value)> = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line_no = index + 1;
+        let mut error = |message: String| {
+            errors.push(ThemeError {
+                line: line_no,
+                message,
+            })
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            error("sections are not supported; put every key at the top level".into());
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            error(format!("expected `key = value`, found `{line}`"));
+            continue;
+        };
+        let key = key.trim();
+        if entries.iter().any(|(_, k, _)| *k == key) {
+            error(format!("`{key}` is set more than once"));
+            continue;
+        }
+        match Value::parse(value) {
+            Ok(value) => entries.push((line_no, key, value)),
+            Err(message) => error(format!("`{key}`: {message}")),
+        }
+    }
+
+    let mut theme = match entries.iter().find(|(_, key, _)| *key == "base") {
+        None => detect_theme(),
+        Some((line, _, value)) => {
+            match value {
+                Value::String(s) if s == "light" => THEME_LIGHT,
+                Value::String(s) if s == "dark" => THEME_DARK,
+                Value::String(s) if s == "high-contrast" => THEME_HIGH_CONTRAST,
+                Value::String(s) if s == "auto" => detect_theme(),
+                _ => {
+                    errors.push(ThemeError {
+                        line: *line,
+                        message:
+                            "`base` must be \"light\", \"dark\", \"high-contrast\" or \"auto\""
+                                .into(),
+                    });
+                    detect_theme()
+                }
+            }
+        }
+    };
+
+    for (line, key, value) in &entries {
+        if *key == "base" {
+            continue;
+        }
+        if let Err(message) = set_field(&mut theme, key, value) {
+            errors.push(ThemeError {
+                line: *line,
+                message: format!("`{key}`: {message}"),
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(theme)
+    } else {
+        errors.sort_by_key(|e| e.line);
+        Err(errors)
+    }
+}
+
+/// Applies overrides written as `key:value` pairs separated by commas or
+/// semicolons, e.g. `button_text:#fff,window_bg:#202020`, to `theme`.
+/// Keys may be written with dashes instead of underscores, as in CSS.
+/// Returns a message for every pair that could not be used.
+pub(crate) fn apply_style(theme: &mut Theme, style: &str) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    for pair in split_style(style) {
+        let pair = pair.trim();
+        if pair.is_empty() {
+            continue;
+        }
+        let Some((key, value)) = pair.split_once([':', '=']) else {
+            errors.push(format!("expected `key:value`, found `{pair}`"));
+            continue;
+        };
+        let key = key.trim().replace('-', "_");
+        let value = value.trim().trim_matches('"');
+        let value = match value.parse::<f32>() {
+            Ok(n) if n.is_finite() => Value::Number(n),
+            _ => Value::String(value.to_string()),
+        };
+        if let Err(message) = set_field(theme, &key, &value) {
+            errors.push(format!("`{key}`: {message}"));
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Splits a style at commas and semicolons outside parentheses, which
+/// keeps `rgb(r,g,b)` colors whole.
+fn split_style(style: &str) -> Vec<&str> {
+    let mut pairs = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in style.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' | ';' if depth <= 0 => {
+                pairs.push(&style[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    pairs.push(&style[start..]);
+    pairs
+}
+
+/// A value on the right of `=`.
+#[derive(Debug)]
+enum Value {
+    String(String),
+    Number(f32),
+}
+
+impl Value {
+    fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        if let Some(rest) = text.strip_prefix('"') {
+            let Some((string, after)) = rest.split_once('"') else {
+                return Err("string is missing its closing quote".into());
+            };
+            let after = after.trim();
+            if !after.is_empty() && !after.starts_with('#') {
+                return Err(format!("unexpected `{after}` after the string"));
+            }
+            return Ok(Value::String(string.to_string()));
+        }
+        // A comment may follow a number
+        let number = text.split('#').next().unwrap_or_default().trim();
+        match number.replace('_', "").parse::<f32>() {
+            Ok(n) if n.is_finite() => Ok(Value::Number(n)),
+            _ if number.is_empty() => Err("missing value".into()),
+            _ => {
+                Err(format!(
+                    "expected a number or a quoted string, found `{number}`"
+                ))
+            }
+        }
+    }
+}
+
+/// Sets the field named `key` from `value`, checking that it makes sense
+/// for that field.
+fn set_field(theme: &mut Theme, key: &str, value: &Value) -> Result<(), String> {
+    let color = |value: &Value| {
+        match value {
+            Value::String(s) => Rgba::parse(s).ok_or_else(|| format!("`{s}` is not a color")),
+            Value::Number(_) => Err("expected a color such as \"#3584e4\"".into()),
+        }
+    };
+    let number = |value: &Value, min: f32, max: f32| {
+        match value {
+            Value::Number(n) if (min..=max).contains(n) => Ok(*n),
+            Value::Number(n) => Err(format!("{n} is outside {min}–{max}")),
+            Value::String(_) => Err("expected a number".into()),
+        }
+    };
+
+    match key {
+        "window_bg" => theme.window_bg = color(value)?,
+        "text" => theme.text = color(value)?,
+        "button" => theme.button = color(value)?,
+        "button_hover" => theme.button_hover = color(value)?,
+        "button_pressed" => theme.button_pressed = color(value)?,
+        "button_outline" => theme.button_outline = color(value)?,
+        "button_text" => theme.button_text = color(value)?,
+        "button_gradient" => theme.button_gradient = number(value, 0.0, 1.0)?,
+        "button_shadow" => theme.button_shadow = color(value)?,
+        "elevation" => theme.elevation = number(value, 0.0, 16.0)?,
+        "input_bg" => theme.input_bg = color(value)?,
+        "input_bg_focused" => theme.input_bg_focused = color(value)?,
+        "input_border" => theme.input_border = color(value)?,
+        "input_border_focused" => theme.input_border_focused = color(value)?,
+        "selection_text" => theme.selection_text = color(value)?,
+        "input_placeholder" => theme.input_placeholder = color(value)?,
+        "input_inset" => theme.input_inset = color(value)?,
+        "focus_ring" => theme.focus_ring = color(value)?,
+        "progress_bg" => theme.progress_bg = color(value)?,
+        "progress_fill" => theme.progress_fill = color(value)?,
+        "progress_border" => theme.progress_border = color(value)?,
+        "window_border" => theme.window_border = color(value)?,
+        "window_shadow" => theme.window_shadow = color(value)?,
+        "window_radius" => theme.window_radius = number(value, 0.0, 32.0)?,
+        "control_radius" => theme.control_radius = number(value, 0.0, 32.0)?,
+        "border_width" => theme.border_width = number(value, 0.0, 8.0)?,
+        "spacing" => theme.spacing = number(value, 0.25, 4.0)?,
+        "control_height" => {
+            let height = number(value, 16.0, 128.0)?;
+            if height.fract() != 0.0 {
+                return Err("expected a whole number of pixels".into());
+            }
+            theme.control_height = height as u32;
+        }
+        "font_size" => theme.font_size = number(value, 6.0, 72.0)?,
+        _ => return Err("unknown key".into()),
+    }
+    Ok(())
+}
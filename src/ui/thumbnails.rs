@@ -0,0 +1,196 @@
+//! Background thumbnail loading for the file dialog.
+//!
+//! Images are decoded on a worker thread and kept in a small LRU cache, so
+//! scrolling through image-heavy directories never blocks on decoding. The
+//! same worker reads the start of text files for the preview pane.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+use crate::render::Canvas;
+
+/// How much of a text file is read for its preview.
+const TEXT_PREVIEW_BYTES: u64 = 4096;
+
+/// A decoded image at list and grid icon sizes.
+pub(crate) struct Thumbnail {
+    pub small: Canvas,
+    pub large: Canvas,
+}
+
+/// What the worker is asked to load for a path.
+enum Job {
+    Image,
+    Text,
+}
+
+/// What the worker loaded for a path.
+enum Loaded {
+    /// `None` records files that failed to decode.
+    Image(Option<Thumbnail>),
+    /// Leading lines; empty for files that aren't text.
+    Text(Vec<String>),
+}
+
+/// Thumbnail cache with a background decoder.
+pub(crate) struct Thumbnails {
+    capacity: usize,
+    cache: HashMap<PathBuf, Loaded>,
+    /// Cache keys from least to most recently used.
+    order: VecDeque<PathBuf>,
+    pending: HashSet<PathBuf>,
+    requests: Sender<(PathBuf, Job)>,
+    results: Receiver<(PathBuf, Loaded)>,
+}
+
+impl Thumbnails {
+    /// Starts the worker thread. Thumbnails are made to fit square boxes of
+    /// `small` and `large` pixels.
+    pub fn new(small: u32, large: u32, capacity: usize) -> Self {
+        let (requests, request_rx) = mpsc::channel::<(PathBuf, Job)>();
+        let (result_tx, results) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (path, job) in request_rx {
+                let loaded = match job {
+                    Job::Image => Loaded::Image(load_thumbnail(&path, small, large)),
+                    Job::Text => Loaded::Text(read_text(&path)),
+                };
+                if result_tx.send((path, loaded)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            capacity,
+            cache: HashMap::new(),
+            order: VecDeque::new(),
+            pending: HashSet::new(),
+            requests,
+            results,
+        }
+    }
+
+    /// Returns true if thumbnails are supported for this file name.
+    pub fn is_image(name: &str) -> bool {
+        crate::render::is_image(name)
+    }
+
+    /// Returns the cached thumbnail for a path, if it has been decoded.
+    pub fn get(&self, path: &Path) -> Option<&Thumbnail> {
+        match self.cache.get(path) {
+            Some(Loaded::Image(thumb)) => thumb.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the leading lines of a text file, once they have been read.
+    pub fn text(&self, path: &Path) -> &[String] {
+        match self.cache.get(path) {
+            Some(Loaded::Text(lines)) => lines,
+            _ => &[],
+        }
+    }
+
+    /// Queues a path for decoding unless it is cached or already queued.
+    pub fn request(&mut self, path: &Path) {
+        self.queue(path, Job::Image);
+    }
+
+    /// Queues the start of a file to be read as text, as [`request`] does
+    /// images.
+    ///
+    /// [`request`]: Self::request
+    pub fn request_text(&mut self, path: &Path) {
+        self.queue(path, Job::Text);
+    }
+
+    fn queue(&mut self, path: &Path, job: Job) {
+        if self.cache.contains_key(path) {
+            self.touch(path);
+        } else if !self.pending.contains(path)
+            && self.requests.send((path.to_path_buf(), job)).is_ok()
+        {
+            self.pending.insert(path.to_path_buf());
+        }
+    }
+
+    /// Returns true while decodes are outstanding.
+    pub fn is_busy(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Collects finished loads. Returns true if any new thumbnail or text
+    /// arrived.
+    pub fn receive(&mut self) -> bool {
+        let mut received = false;
+        while let Ok((path, loaded)) = self.results.try_recv() {
+            self.pending.remove(&path);
+            received |= match &loaded {
+                Loaded::Image(thumb) => thumb.is_some(),
+                Loaded::Text(lines) => !lines.is_empty(),
+            };
+            self.order.push_back(path.clone());
+            self.cache.insert(path, loaded);
+        }
+
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.cache.remove(&oldest);
+            }
+        }
+        received
+    }
+
+    fn touch(&mut self, path: &Path) {
+        if let Some(pos) = self.order.iter().position(|p| p == path) {
+            if let Some(p) = self.order.remove(pos) {
+                self.order.push_back(p);
+            }
+        }
+    }
+}
+
+fn load_thumbnail(path: &Path, small: u32, large: u32) -> Option<Thumbnail> {
+    // Vector images are rendered at each size rather than scaled down
+    if path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(crate::render::is_vector_image)
+    {
+        return Some(Thumbnail {
+            small: Canvas::load_image(path, small, small)?,
+            large: Canvas::load_image(path, large, large)?,
+        });
+    }
+    let large = Canvas::load_image(path, large, large)?;
+    Some(Thumbnail {
+        small: large.scaled_to_fit(small, small),
+        large,
+    })
+}
+
+/// Returns the first lines of a file, or nothing if it doesn't look like text.
+///
+/// Only regular files are read: a FIFO or device could block forever.
+fn read_text(path: &Path) -> Vec<String> {
+    if !fs::metadata(path).is_ok_and(|meta| meta.is_file()) {
+        return Vec::new();
+    }
+    let mut buf = Vec::new();
+    let read = fs::File::open(path).and_then(|f| f.take(TEXT_PREVIEW_BYTES).read_to_end(&mut buf));
+    if read.is_err() || buf.contains(&0) {
+        return Vec::new();
+    }
+    String::from_utf8_lossy(&buf)
+        .lines()
+        .map(|line| line.replace('\t', "    ").chars().take(200).collect())
+        .collect()
+}
@@ -0,0 +1,629 @@
+//! System tray icon implementation.
+//!
+//! The icon is a StatusNotifierItem exported over D-Bus, with its menu
+//! served through the `com.canonical.dbusmenu` interface. It is registered
+//! with the tray's StatusNotifierWatcher whenever one is running, so the icon
+//! appears even if the tray starts after us.
+
+use std::{
+    io::{BufRead, BufReader},
+    sync::mpsc,
+    thread,
+};
+
+use crate::{
+    dbus::{Connection, Message, MessageKind, Value},
+    error::Error,
+    render::Canvas,
+    ui::handle::{self, DialogHandle},
+};
+
+const ITEM_PATH: &str = "/StatusNotifierItem";
+const MENU_PATH: &str = "/MenuBar";
+const ITEM_INTERFACE: &str = "org.kde.StatusNotifierItem";
+const MENU_INTERFACE: &str = "com.canonical.dbusmenu";
+const WATCHER_NAME: &str = "org.kde.StatusNotifierWatcher";
+const WATCHER_PATH: &str = "/StatusNotifierWatcher";
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+
+const DEFAULT_ICON: &str = "dialog-information";
+/// Menu action that exits instead of being reported.
+const QUIT_ACTION: &str = "quit";
+/// How often stdin commands are checked for while listening.
+const LISTEN_POLL_MS: i32 = 100;
+
+const INTROSPECTION: &str = r#"<node>
+  <interface name="org.kde.StatusNotifierItem">
+    <method name="Activate"><arg type="i" direction="in"/><arg type="i" direction="in"/></method>
+    <method name="SecondaryActivate"><arg type="i" direction="in"/><arg type="i" direction="in"/></method>
+    <method name="ContextMenu"><arg type="i" direction="in"/><arg type="i" direction="in"/></method>
+    <method name="Scroll"><arg type="i" direction="in"/><arg type="s" direction="in"/></method>
+    <property name="Category" type="s" access="read"/>
+    <property name="Id" type="s" access="read"/>
+    <property name="Title" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="IconName" type="s" access="read"/>
+    <property name="IconPixmap" type="a(iiay)" access="read"/>
+    <property name="ToolTip" type="(sa(iiay)ss)" access="read"/>
+    <property name="ItemIsMenu" type="b" access="read"/>
+    <property name="Menu" type="o" access="read"/>
+    <signal name="NewIcon"/>
+    <signal name="NewToolTip"/>
+    <signal name="NewStatus"><arg type="s"/></signal>
+  </interface>
+</node>"#;
+
+/// Something the user did with the tray icon.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrayEvent {
+    /// The icon was clicked.
+    Activate,
+    /// A menu item was chosen. Holds the item's action.
+    Menu(String),
+}
+
+/// Why the tray icon went away.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TrayResult {
+    /// The "quit" menu action or command was used.
+    Quit,
+    /// Standard input closed while listening for commands.
+    InputClosed,
+}
+
+impl TrayResult {
+    pub fn exit_code(&self) -> i32 {
+        0
+    }
+}
+
+/// An entry in the tray menu.
+#[derive(Debug, Clone)]
+enum MenuItem {
+    Action { label: String, action: String },
+    Separator,
+}
+
+/// Command read from stdin while listening.
+enum ListenCommand {
+    Icon(String),
+    Tooltip(String),
+    Visible(bool),
+    Menu(String),
+    Quit,
+    Done,
+}
+
+/// System tray icon builder.
+pub struct TrayBuilder {
+    title: String,
+    icon: String,
+    tooltip: String,
+    menu: Vec<MenuItem>,
+    listen: bool,
+}
+
+impl TrayBuilder {
+    pub fn new() -> Self {
+        Self {
+            title: String::new(),
+            icon: String::new(),
+            tooltip: String::new(),
+            menu: Vec::new(),
+            listen: false,
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Icon theme name or image file path.
+    pub fn icon(mut self, icon: &str) -> Self {
+        self.icon = icon.to_string();
+        self
+    }
+
+    pub fn tooltip(mut self, tooltip: &str) -> Self {
+        self.tooltip = tooltip.to_string();
+        self
+    }
+
+    /// Adds a menu item. Choosing it reports `action`; the action "quit"
+    /// removes the icon instead.
+    pub fn menu_item(mut self, label: &str, action: &str) -> Self {
+        self.menu.push(MenuItem::Action {
+            label: label.to_string(),
+            action: action.to_string(),
+        });
+        self
+    }
+
+    pub fn menu_separator(mut self) -> Self {
+        self.menu.push(MenuItem::Separator);
+        self
+    }
+
+    /// Adds menu items from a spec like `"Open!open|Quit!quit"`: items are
+    /// separated by `|` and each label is followed by `!` and its action (the
+    /// label itself if left out). Empty items become separators.
+    pub fn menu(mut self, spec: &str) -> Self {
+        self.menu.extend(parse_menu(spec));
+        self
+    }
+
+    /// Reads commands from stdin while running, one per line: `icon: NAME`,
+    /// `tooltip: TEXT`, `visible: true|false`, `menu: SPEC` and `quit`.
+    pub fn listen(mut self, listen: bool) -> Self {
+        self.listen = listen;
+        self
+    }
+
+    /// Shows the icon on a thread of its own, returning a handle to get its
+    /// result with, or to remove it. `on_event` is called from that thread.
+    pub fn spawn(
+        self,
+        on_event: impl FnMut(TrayEvent) + Send + 'static,
+    ) -> DialogHandle<TrayResult> {
+        DialogHandle::spawn(move || self.show(on_event))
+    }
+
+    /// Like [`show`](Self::show), but waits for the icon to be quit without
+    /// blocking the async runtime. `on_event` is called from another thread.
+    #[cfg(any(feature = "tokio", feature = "async-std"))]
+    pub async fn show_async(
+        self,
+        on_event: impl FnMut(TrayEvent) + Send + 'static,
+    ) -> Result<TrayResult, Error> {
+        crate::ui::show_blocking(move || self.show(on_event)).await
+    }
+
+    /// Shows the icon until it is quit, calling `on_event` for each click and
+    /// menu choice.
+    pub fn show(self, mut on_event: impl FnMut(TrayEvent)) -> Result<TrayResult, Error> {
+        let mut conn = Connection::session()?;
+        let service = format!("org.kde.StatusNotifierItem-{}-1", std::process::id());
+        conn.request_name(&service)?;
+
+        let mut item = Item {
+            title: if self.title.is_empty() {
+                "zenity-rs".to_string()
+            } else {
+                self.title
+            },
+            icon: if self.icon.is_empty() {
+                DEFAULT_ICON.to_string()
+            } else {
+                self.icon
+            },
+            tooltip: self.tooltip,
+            visible: true,
+            menu: self.menu,
+            revision: 1,
+        };
+
+        // Register now if a tray is running, and again whenever one starts
+        conn.add_match(&format!(
+            "type='signal',sender='org.freedesktop.DBus',interface='org.freedesktop.DBus',\
+             member='NameOwnerChanged',arg0='{WATCHER_NAME}'"
+        ))?;
+        let has_watcher = conn
+            .call_bus("NameHasOwner", vec![Value::str(WATCHER_NAME)])?
+            .body
+            .first()
+            == Some(&Value::Bool(true));
+        if has_watcher {
+            register(&mut conn, &service)?;
+        }
+
+        let commands = self.listen.then(|| {
+            let (tx, rx) = mpsc::channel();
+            thread::spawn(move || {
+                let stdin = std::io::stdin();
+                let reader = BufReader::new(stdin.lock());
+                for line in reader.lines() {
+                    let Ok(line) = line else {
+                        break;
+                    };
+                    if let Some(command) = parse_command(&line)
+                        && tx.send(command).is_err()
+                    {
+                        break;
+                    }
+                }
+                let _ = tx.send(ListenCommand::Done);
+            });
+            rx
+        });
+
+        // A spawned icon is removed by closing its handle
+        let remote = handle::remote();
+
+        loop {
+            if remote.as_ref().is_some_and(|remote| remote.is_closed()) {
+                return Ok(TrayResult::Quit);
+            }
+            if let Some(rx) = &commands {
+                while let Ok(command) = rx.try_recv() {
+                    match command {
+                        ListenCommand::Icon(icon) => {
+                            item.icon = icon;
+                            emit(&mut conn, ITEM_PATH, ITEM_INTERFACE, "NewIcon", vec![])?;
+                        }
+                        ListenCommand::Tooltip(tooltip) => {
+                            item.tooltip = tooltip;
+                            emit(&mut conn, ITEM_PATH, ITEM_INTERFACE, "NewToolTip", vec![])?;
+                        }
+                        ListenCommand::Visible(visible) => {
+                            item.visible = visible;
+                            let status = Value::str(item.status());
+                            emit(
+                                &mut conn,
+                                ITEM_PATH,
+                                ITEM_INTERFACE,
+                                "NewStatus",
+                                vec![status],
+                            )?;
+                        }
+                        ListenCommand::Menu(spec) => {
+                            item.menu = parse_menu(&spec);
+                            item.revision += 1;
+                            let body = vec![Value::Uint32(item.revision), Value::Int32(0)];
+                            emit(&mut conn, MENU_PATH, MENU_INTERFACE, "LayoutUpdated", body)?;
+                        }
+                        ListenCommand::Quit => return Ok(TrayResult::Quit),
+                        ListenCommand::Done => return Ok(TrayResult::InputClosed),
+                    }
+                }
+            }
+
+            let timeout = if commands.is_some() || remote.is_some() {
+                LISTEN_POLL_MS
+            } else {
+                -1
+            };
+            let Some(message) = conn.read(timeout)? else {
+                continue;
+            };
+
+            match message.kind {
+                MessageKind::MethodCall => {
+                    let (reply, event) = item.handle_call(&message);
+                    if message.expects_reply() {
+                        conn.send(reply)?;
+                    }
+                    match event {
+                        Some(TrayEvent::Menu(action)) if action == QUIT_ACTION => {
+                            return Ok(TrayResult::Quit);
+                        }
+                        Some(event) => on_event(event),
+                        None => {}
+                    }
+                }
+                MessageKind::Signal
+                    if message.member.as_deref() == Some("NameOwnerChanged")
+                        && message.body.first().and_then(Value::as_str) == Some(WATCHER_NAME)
+                        && message.body.get(2).and_then(Value::as_str) != Some("") =>
+                {
+                    register(&mut conn, &service)?;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Default for TrayBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The exported state of the icon and its menu.
+struct Item {
+    title: String,
+    icon: String,
+    tooltip: String,
+    visible: bool,
+    menu: Vec<MenuItem>,
+    /// Menu layout revision, bumped whenever the menu changes.
+    revision: u32,
+}
+
+impl Item {
+    fn status(&self) -> &'static str {
+        if self.visible { "Active" } else { "Passive" }
+    }
+
+    /// Answers a method call, returning the reply and any user action it
+    /// reports.
+    fn handle_call(&self, call: &Message) -> (Message, Option<TrayEvent>) {
+        let path = call.path.as_deref().unwrap_or("");
+        let interface = call.interface.as_deref().unwrap_or("");
+        let member = call.member.as_deref().unwrap_or("");
+        let arg = |i: usize| call.body.get(i);
+        let reply = |body| Message::method_return(call, body);
+
+        match (path, interface, member) {
+            (_, "org.freedesktop.DBus.Peer", "Ping") => (reply(vec![]), None),
+            (ITEM_PATH, "org.freedesktop.DBus.Introspectable", "Introspect") => {
+                (reply(vec![Value::str(INTROSPECTION)]), None)
+            }
+            (ITEM_PATH | MENU_PATH, PROPERTIES_INTERFACE, "Get") => {
+                let name = arg(1).and_then(Value::as_str).unwrap_or("");
+                let found = self
+                    .properties(path)
+                    .into_iter()
+                    .find(|(key, _)| *key == name);
+                match found {
+                    Some((_, value)) => (reply(vec![Value::variant(value)]), None),
+                    None => {
+                        let error = Message::error(
+                            call,
+                            "org.freedesktop.DBus.Error.UnknownProperty",
+                            &format!("no property {name}"),
+                        );
+                        (error, None)
+                    }
+                }
+            }
+            (ITEM_PATH | MENU_PATH, PROPERTIES_INTERFACE, "GetAll") => {
+                (reply(vec![Value::dict(self.properties(path))]), None)
+            }
+            (ITEM_PATH, _, "Activate") => (reply(vec![]), Some(TrayEvent::Activate)),
+            (ITEM_PATH, _, "SecondaryActivate" | "ContextMenu" | "Scroll") => (reply(vec![]), None),
+            (MENU_PATH, _, "GetLayout") => {
+                (
+                    reply(vec![Value::Uint32(self.revision), self.layout()]),
+                    None,
+                )
+            }
+            (MENU_PATH, _, "GetGroupProperties") => {
+                let ids = arg(0).map(Value::items).unwrap_or(&[]);
+                let entries = ids
+                    .iter()
+                    .filter_map(Value::as_i32)
+                    .filter_map(|id| {
+                        let props = self.menu_properties(id)?;
+                        Some(Value::Struct(vec![Value::Int32(id), Value::dict(props)]))
+                    })
+                    .collect();
+                (
+                    reply(vec![Value::Array("(ia{sv})".to_string(), entries)]),
+                    None,
+                )
+            }
+            (MENU_PATH, _, "GetProperty") => {
+                let id = arg(0).and_then(Value::as_i32).unwrap_or(-1);
+                let name = arg(1).and_then(Value::as_str).unwrap_or("");
+                let value = self
+                    .menu_properties(id)
+                    .and_then(|props| props.into_iter().find(|(key, _)| *key == name));
+                match value {
+                    Some((_, value)) => (reply(vec![Value::variant(value)]), None),
+                    None => {
+                        let error = Message::error(
+                            call,
+                            "org.freedesktop.DBus.Error.InvalidArgs",
+                            "unknown menu property",
+                        );
+                        (error, None)
+                    }
+                }
+            }
+            (MENU_PATH, _, "Event") => {
+                let id = arg(0).and_then(Value::as_i32).unwrap_or(-1);
+                let event_id = arg(1).and_then(Value::as_str).unwrap_or("");
+                (reply(vec![]), self.menu_event(id, event_id))
+            }
+            (MENU_PATH, _, "EventGroup") => {
+                // Only the first click in a group is reported
+                let event = arg(0)
+                    .map(Value::items)
+                    .unwrap_or(&[])
+                    .iter()
+                    .find_map(|e| {
+                        let Value::Struct(fields) = e else {
+                            return None;
+                        };
+                        let id = fields.first().and_then(Value::as_i32)?;
+                        self.menu_event(id, fields.get(1).and_then(Value::as_str)?)
+                    });
+                let id_errors = Value::Array("i".to_string(), vec![]);
+                (reply(vec![id_errors]), event)
+            }
+            (MENU_PATH, _, "AboutToShow") => (reply(vec![Value::Bool(false)]), None),
+            (MENU_PATH, _, "AboutToShowGroup") => {
+                let empty = || Value::Array("i".to_string(), vec![]);
+                (reply(vec![empty(), empty()]), None)
+            }
+            _ => {
+                let error = Message::error(
+                    call,
+                    "org.freedesktop.DBus.Error.UnknownMethod",
+                    &format!("unknown method {interface}.{member}"),
+                );
+                (error, None)
+            }
+        }
+    }
+
+    /// Properties of the object at `path`.
+    fn properties(&self, path: &str) -> Vec<(&'static str, Value)> {
+        if path == MENU_PATH {
+            return vec![
+                ("Version", Value::Uint32(3)),
+                ("TextDirection", Value::str("ltr")),
+                ("Status", Value::str("normal")),
+                ("IconThemePath", Value::Array("s".to_string(), vec![])),
+            ];
+        }
+
+        let pixmap = icon_pixmap(&self.icon);
+        let icon_name = if pixmap.is_some() { "" } else { &self.icon };
+        let pixmap = Value::Array("(iiay)".to_string(), pixmap.into_iter().collect());
+        let menu = if self.menu.is_empty() {
+            "/NO_DBUSMENU"
+        } else {
+            MENU_PATH
+        };
+        vec![
+            ("Category", Value::str("ApplicationStatus")),
+            ("Id", Value::str("zenity-rs")),
+            ("Title", Value::str(&self.title)),
+            ("Status", Value::str(self.status())),
+            ("WindowId", Value::Int32(0)),
+            ("IconName", Value::str(icon_name)),
+            ("IconPixmap", pixmap.clone()),
+            ("OverlayIconName", Value::str("")),
+            ("AttentionIconName", Value::str("")),
+            (
+                "ToolTip",
+                Value::Struct(vec![
+                    Value::str(icon_name),
+                    pixmap,
+                    Value::str(&self.title),
+                    Value::str(&self.tooltip),
+                ]),
+            ),
+            ("ItemIsMenu", Value::Bool(false)),
+            ("Menu", Value::ObjectPath(menu.to_string())),
+        ]
+    }
+
+    /// The menu as a dbusmenu layout: `(ia{sv}av)` for the root and its
+    /// children. Item ids are menu positions plus one; the root is 0.
+    fn layout(&self) -> Value {
+        let children = (1..=self.menu.len() as i32)
+            .filter_map(|id| {
+                let props = self.menu_properties(id)?;
+                Some(Value::variant(Value::Struct(vec![
+                    Value::Int32(id),
+                    Value::dict(props),
+                    Value::Array("v".to_string(), vec![]),
+                ])))
+            })
+            .collect();
+        Value::Struct(vec![
+            Value::Int32(0),
+            Value::dict(vec![("children-display", Value::str("submenu"))]),
+            Value::Array("v".to_string(), children),
+        ])
+    }
+
+    fn menu_properties(&self, id: i32) -> Option<Vec<(&'static str, Value)>> {
+        if id == 0 {
+            return Some(vec![("children-display", Value::str("submenu"))]);
+        }
+        let item = self.menu.get(usize::try_from(id).ok()?.checked_sub(1)?)?;
+        Some(match item {
+            MenuItem::Action {
+                label, ..
+            } => {
+                vec![
+                    ("label", Value::str(label)),
+                    ("enabled", Value::Bool(true)),
+                    ("visible", Value::Bool(true)),
+                ]
+            }
+            MenuItem::Separator => vec![("type", Value::str("separator"))],
+        })
+    }
+
+    fn menu_event(&self, id: i32, event_id: &str) -> Option<TrayEvent> {
+        if event_id != "clicked" {
+            return None;
+        }
+        match self.menu.get(usize::try_from(id).ok()?.checked_sub(1)?)? {
+            MenuItem::Action {
+                action, ..
+            } => Some(TrayEvent::Menu(action.clone())),
+            MenuItem::Separator => None,
+        }
+    }
+}
+
+/// Asks the watcher to show our item. The reply isn't waited for, as the
+/// watcher may query the item before answering.
+fn register(conn: &mut Connection, service: &str) -> Result<(), Error> {
+    conn.send(Message::method_call(
+        WATCHER_NAME,
+        WATCHER_PATH,
+        WATCHER_NAME,
+        "RegisterStatusNotifierItem",
+        vec![Value::str(service)],
+    ))?;
+    Ok(())
+}
+
+fn emit(
+    conn: &mut Connection,
+    path: &str,
+    interface: &str,
+    member: &str,
+    body: Vec<Value>,
+) -> Result<(), Error> {
+    conn.send(Message::signal(path, interface, member, body))?;
+    Ok(())
+}
+
+fn parse_menu(spec: &str) -> Vec<MenuItem> {
+    spec.split('|')
+        .map(|entry| {
+            let (label, action) = entry.split_once('!').unwrap_or((entry, entry));
+            if label.trim().is_empty() {
+                MenuItem::Separator
+            } else {
+                MenuItem::Action {
+                    label: label.to_string(),
+                    action: action.to_string(),
+                }
+            }
+        })
+        .collect()
+}
+
+fn parse_command(line: &str) -> Option<ListenCommand> {
+    let line = line.trim();
+    if line.eq_ignore_ascii_case(QUIT_ACTION) {
+        return Some(ListenCommand::Quit);
+    }
+    let (command, value) = line.split_once(':')?;
+    let value = value.trim().to_string();
+    Some(match command.trim().to_ascii_lowercase().as_str() {
+        "icon" => ListenCommand::Icon(value),
+        "tooltip" => ListenCommand::Tooltip(value),
+        "visible" => ListenCommand::Visible(!value.eq_ignore_ascii_case("false")),
+        "menu" => ListenCommand::Menu(value),
+        _ => return None,
+    })
+}
+
+/// Decodes an icon file into an `(iiay)` pixmap: width, height and ARGB32
+/// pixels in network byte order. Returns `None` for icon names.
+fn icon_pixmap(icon: &str) -> Option<Value> {
+    if !icon.contains('/') {
+        return None;
+    }
+    let image = Canvas::load_image(std::path::Path::new(icon), 64, 64)?;
+    let mut argb = Vec::with_capacity(image.pixmap.data().len());
+    for px in image.pixmap.data().chunks_exact(4) {
+        // Stored premultiplied; the tray expects straight alpha
+        let a = px[3];
+        let unmultiply = |c: u8| {
+            if a == 0 {
+                0
+            } else {
+                (c as u32 * 255 / a as u32) as u8
+            }
+        };
+        argb.extend_from_slice(&[a, unmultiply(px[0]), unmultiply(px[1]), unmultiply(px[2])]);
+    }
+    Some(Value::Struct(vec![
+        Value::Int32(image.width() as i32),
+        Value::Int32(image.height() as i32),
+        Value::Array("y".to_string(), argb.into_iter().map(Value::Byte).collect()),
+    ]))
+}
@@ -0,0 +1,64 @@
+//! Keyboard accelerators for underscore mnemonics in labels ("_Yes", "_Retry").
+
+use crate::backend::{Modifiers, WindowEvent};
+
+/// A label with its mnemonic marker removed.
+pub(crate) struct Mnemonic {
+    /// Label text to display.
+    pub text: String,
+    /// Character index of the underlined character and its lowercase key.
+    pub accel: Option<(usize, char)>,
+}
+
+/// Parses a label, treating the first `_` as a mnemonic marker and `__` as a literal underscore.
+pub(crate) fn parse_mnemonic(label: &str) -> Mnemonic {
+    let mut text = String::with_capacity(label.len());
+    let mut accel = None;
+    let mut chars = label.chars().peekable();
+    let mut idx = 0;
+
+    while let Some(c) = chars.next() {
+        if c == '_' {
+            match chars.next() {
+                Some('_') => text.push('_'),
+                Some(next) => {
+                    if accel.is_none() {
+                        accel = next.to_lowercase().next().map(|key| (idx, key));
+                    }
+                    text.push(next);
+                }
+                None => text.push('_'),
+            }
+        } else {
+            text.push(c);
+        }
+        idx += 1;
+    }
+
+    Mnemonic {
+        text,
+        accel,
+    }
+}
+
+/// Returns the lowercase accelerator key for an Alt+<key> press.
+pub(crate) fn accelerator_key(event: &WindowEvent) -> Option<char> {
+    let WindowEvent::KeyPress(key_event) = event else {
+        return None;
+    };
+    if !key_event.modifiers.contains(Modifiers::ALT)
+        || key_event
+            .modifiers
+            .intersects(Modifiers::CTRL | Modifiers::SUPER)
+    {
+        return None;
+    }
+
+    // Latin-1 keysyms match their code point, Unicode keysyms are offset
+    let code = match key_event.keysym {
+        k @ 0x20..=0xff => k,
+        k @ 0x0100_0000.. => k - 0x0100_0000,
+        _ => return None,
+    };
+    char::from_u32(code).and_then(|c| c.to_lowercase().next())
+}
@@ -1,50 +1,70 @@
 //! Button widget.
 
-use super::{Widget, point_in_rect};
+use super::{
+    Widget,
+    accel::{accelerator_key, parse_mnemonic},
+    point_in_rect,
+};
 use crate::{
     backend::{MouseButton, WindowEvent},
-    render::{Canvas, Font},
-    ui::Colors,
+    render::{Canvas, Font, Rgba, rgb},
+    ui::{Theme, animation::Transition},
 };
 
 /// A clickable button widget.
 pub(crate) struct Button {
     label: String,
+    mnemonic: Option<(usize, char)>,
+    underline_thickness: f32,
     x: i32,
     y: i32,
     width: u32,
     height: u32,
     radius: f32,
+    scale: f32,
     hovered: bool,
+    /// Eases the background between its normal and hover colors
+    hover: Transition,
     pressed: bool,
     clicked: bool,
+    is_default: bool,
+    enabled: bool,
+    focused: bool,
+    tooltip: Option<String>,
 }
 
-const BASE_BUTTON_HEIGHT: u32 = 32;
 const BASE_BUTTON_PADDING: u32 = 24;
-const BASE_BUTTON_RADIUS: f32 = 5.0;
 const BASE_MIN_BUTTON_WIDTH: u32 = 80;
 
 impl Button {
-    pub fn new(label: &str, font: &Font, scale: f32) -> Self {
-        let button_padding = (BASE_BUTTON_PADDING as f32 * scale) as u32;
-        let button_height = (BASE_BUTTON_HEIGHT as f32 * scale) as u32;
+    pub fn new(label: &str, font: &Font, colors: &Theme, scale: f32) -> Self {
+        let button_padding = (colors.space(BASE_BUTTON_PADDING) as f32 * scale) as u32;
+        let button_height = (colors.control_height as f32 * scale) as u32;
         let min_button_width = (BASE_MIN_BUTTON_WIDTH as f32 * scale) as u32;
-        let button_radius = BASE_BUTTON_RADIUS * scale;
+        let button_radius = colors.control_radius * scale;
 
-        let (text_w, _) = font.render(label).measure();
+        let mnemonic = parse_mnemonic(label);
+        let (text_w, _) = font.render(&mnemonic.text).measure();
         let width = (text_w as u32 + button_padding * 2).max(min_button_width);
 
         Self {
-            label: label.to_string(),
+            label: mnemonic.text,
+            mnemonic: mnemonic.accel,
+            underline_thickness: scale.max(1.0).round(),
             x: 0,
             y: 0,
             width,
             height: button_height,
             radius: button_radius,
+            scale,
             hovered: false,
+            hover: Transition::new(0.0),
             pressed: false,
             clicked: false,
+            is_default: false,
+            enabled: true,
+            focused: false,
+            tooltip: None,
         }
     }
 
@@ -55,51 +75,129 @@ impl Button {
         clicked
     }
 
+    /// Marks the button as the dialog default, drawn with an accent outline.
+    pub fn set_default(&mut self, is_default: bool) {
+        self.is_default = is_default;
+    }
+
+    /// Enables or disables the button. A disabled button is drawn faded
+    /// and cannot be clicked, though it still shows its tooltip.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.pressed = false;
+            self.hover.set(0.0);
+        } else if self.hovered {
+            self.hover.set(1.0);
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Sets the text shown in a tooltip while the pointer is over the
+    /// button.
+    pub fn set_tooltip(&mut self, text: Option<&str>) {
+        self.tooltip = text.map(str::to_string);
+    }
+
     /// Set the button width.
     pub fn set_width(&mut self, width: u32) {
         self.width = width;
     }
 
     /// Draws the button to a canvas.
-    pub fn draw_to(&self, canvas: &mut Canvas, colors: &Colors, font: &Font) {
+    pub fn draw_to(&self, canvas: &mut Canvas, colors: &Theme, font: &Font) {
         // Determine button color based on state
         let bg_color = if self.pressed {
             colors.button_pressed
-        } else if self.hovered {
-            colors.button_hover
         } else {
-            colors.button
+            colors.button.mix(colors.button_hover, self.hover.value())
+        };
+        // Faded into the window while disabled
+        let fade = |color: Rgba| {
+            if self.enabled {
+                color
+            } else {
+                color.mix(colors.window_bg, 0.5)
+            }
         };
 
-        // Draw button background
-        canvas.fill_rounded_rect(
+        // Raise the button off the window with a shadow falling down,
+        // leaving a disabled one flat
+        if colors.elevation > 0.0 && self.enabled {
+            canvas.fill_shadow(
+                self.x as f32,
+                self.y as f32 + self.scale,
+                self.width as f32,
+                self.height as f32,
+                self.radius,
+                colors.elevation * self.scale,
+                colors.button_shadow,
+            );
+        }
+
+        // Draw button background, lighter towards the top
+        canvas.fill_rounded_rect_gradient(
             self.x as f32,
             self.y as f32,
             self.width as f32,
             self.height as f32,
             self.radius,
-            bg_color,
+            fade(bg_color.mix(rgb(255, 255, 255), colors.button_gradient)),
+            fade(bg_color),
         );
 
-        // Draw button outline
+        // Draw button outline, highlighted for the default button
+        let (outline_color, outline_width) = if self.is_default {
+            (colors.input_border_focused, colors.border_width * 2.0)
+        } else {
+            (colors.button_outline, colors.border_width)
+        };
         canvas.stroke_rounded_rect(
             self.x as f32,
             self.y as f32,
             self.width as f32,
             self.height as f32,
             self.radius,
-            colors.button_outline,
-            1.0,
+            fade(outline_color),
+            outline_width,
         );
 
+        if self.focused {
+            canvas.stroke_focus_ring(
+                self.x as f32,
+                self.y as f32,
+                self.width as f32,
+                self.height as f32,
+                self.radius,
+                2.0,
+                colors.focus_ring,
+            );
+        }
+
         // Draw button label
         let text_canvas = font
             .render(&self.label)
-            .with_color(colors.button_text)
+            .with_color(fade(colors.button_text))
             .finish();
         let text_x = self.x + (self.width as i32 - text_canvas.width() as i32) / 2;
         let text_y = self.y + (self.height as i32 - text_canvas.height() as i32) / 2;
         canvas.draw_canvas(&text_canvas, text_x, text_y);
+
+        // Underline the mnemonic character
+        if let Some((idx, _)) = self.mnemonic
+            && let Some((char_x, char_w, baseline)) = font.render(&self.label).char_span(idx)
+        {
+            canvas.fill_rect(
+                text_x as f32 + char_x,
+                text_y as f32 + baseline + self.underline_thickness,
+                char_w,
+                self.underline_thickness,
+                fade(colors.button_text),
+            );
+        }
     }
 }
 
@@ -136,14 +234,20 @@ impl Widget for Button {
                     self.width,
                     self.height,
                 );
+                self.hover.set(if self.hovered && self.enabled {
+                    1.0
+                } else {
+                    0.0
+                });
                 true
             }
             WindowEvent::CursorLeave => {
                 self.hovered = false;
+                self.hover.set(0.0);
                 self.pressed = false;
                 true
             }
-            WindowEvent::ButtonPress(MouseButton::Left, _) if self.hovered => {
+            WindowEvent::ButtonPress(MouseButton::Left, _) if self.hovered && self.enabled => {
                 self.pressed = true;
                 true
             }
@@ -154,11 +258,41 @@ impl Widget for Button {
                 self.pressed = false;
                 true
             }
+            WindowEvent::KeyPress(_) if self.enabled => {
+                match self.mnemonic {
+                    Some((_, key)) if accelerator_key(event) == Some(key) => {
+                        self.clicked = true;
+                        true
+                    }
+                    _ => false,
+                }
+            }
             _ => false,
         }
     }
 
-    fn draw(&self, _canvas: &mut Canvas, _colors: &Colors) {
+    fn draw(&self, _canvas: &mut Canvas, _colors: &Theme) {
         // Use draw_to instead for font access
     }
+
+    fn is_focusable(&self) -> bool {
+        self.enabled
+    }
+
+    fn set_focus(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn activate(&mut self) -> bool {
+        self.clicked = true;
+        true
+    }
+
+    fn activates_on_enter(&self) -> bool {
+        true
+    }
+
+    fn tooltip(&self) -> Option<&str> {
+        self.tooltip.as_deref().filter(|_| self.hovered)
+    }
 }
@@ -0,0 +1,226 @@
+//! Checkbox widget.
+
+use super::{Widget, point_in_rect};
+use crate::{
+    backend::{MouseButton, WindowEvent},
+    render::{Canvas, Font},
+    ui::{Theme, animation::Transition},
+};
+
+const BASE_BOX_SIZE: u32 = 16;
+const BASE_LABEL_GAP: u32 = 8;
+
+/// A box with a label, toggled by clicking either or by Space while
+/// focused.
+pub(crate) struct Checkbox {
+    label: String,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    box_size: u32,
+    scale: f32,
+    checked: bool,
+    hovered: bool,
+    /// Eases the box between its normal and hover colors
+    hover: Transition,
+    focused: bool,
+    toggled: bool,
+}
+
+impl Checkbox {
+    pub fn new(label: &str, font: &Font, scale: f32) -> Self {
+        let box_size = (BASE_BOX_SIZE as f32 * scale) as u32;
+        let (width, height) = if label.is_empty() {
+            (box_size, box_size)
+        } else {
+            let (text_w, text_h) = font.render(label).measure();
+            (
+                box_size + (BASE_LABEL_GAP as f32 * scale) as u32 + text_w.ceil() as u32,
+                box_size.max(text_h.ceil() as u32),
+            )
+        };
+
+        Self {
+            label: label.to_string(),
+            x: 0,
+            y: 0,
+            width,
+            height,
+            box_size,
+            scale,
+            checked: false,
+            hovered: false,
+            hover: Transition::new(0.0),
+            focused: false,
+            toggled: false,
+        }
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    /// Returns true if the user toggled the checkbox since the last call.
+    #[allow(dead_code)]
+    pub fn was_toggled(&mut self) -> bool {
+        std::mem::take(&mut self.toggled)
+    }
+
+    fn toggle(&mut self) {
+        self.checked = !self.checked;
+        self.toggled = true;
+    }
+
+    /// Draws the checkbox to a canvas.
+    pub fn draw_to(&self, canvas: &mut Canvas, colors: &Theme, font: &Font) {
+        let box_y = self.y + (self.height as i32 - self.box_size as i32) / 2;
+        draw_box(
+            canvas,
+            self.x,
+            box_y,
+            self.box_size,
+            self.checked,
+            self.hover.value(),
+            colors,
+            self.scale,
+        );
+
+        if !self.label.is_empty() {
+            let text = font.render(&self.label).with_color(colors.text).finish();
+            let text_x =
+                self.x + self.box_size as i32 + (BASE_LABEL_GAP as f32 * self.scale) as i32;
+            let text_y = self.y + (self.height as i32 - text.height() as i32) / 2;
+            canvas.draw_canvas(&text, text_x, text_y);
+        }
+
+        if self.focused {
+            let inset = 2.0 * self.scale;
+            canvas.stroke_focus_ring(
+                self.x as f32 - inset,
+                self.y as f32 - inset,
+                self.width as f32 + inset * 2.0,
+                self.height as f32 + inset * 2.0,
+                colors.control_radius * self.scale / 2.0,
+                2.0,
+                colors.focus_ring,
+            );
+        }
+    }
+}
+
+impl Widget for Checkbox {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorMove(pos) | WindowEvent::CursorEnter(pos) => {
+                let hovered = point_in_rect(
+                    pos.x as i32,
+                    pos.y as i32,
+                    self.x,
+                    self.y,
+                    self.width,
+                    self.height,
+                );
+                let changed = hovered != self.hovered;
+                self.hovered = hovered;
+                self.hover.set(if hovered { 1.0 } else { 0.0 });
+                changed
+            }
+            WindowEvent::CursorLeave => {
+                self.hovered = false;
+                self.hover.set(0.0);
+                true
+            }
+            WindowEvent::ButtonPress(MouseButton::Left, _) if self.hovered => {
+                self.toggle();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn draw(&self, _canvas: &mut Canvas, _colors: &Theme) {
+        // Use draw_to instead for font access
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focus(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn activate(&mut self) -> bool {
+        self.toggle();
+        true
+    }
+}
+
+/// Draws a checkbox's box `size` pixels square, filled in when checked.
+/// `hover` runs from 0 to 1 as the pointer comes over it. Lists use this
+/// for rows toggled by clicking anywhere on them.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn draw_box(
+    canvas: &mut Canvas,
+    x: i32,
+    y: i32,
+    size: u32,
+    checked: bool,
+    hover: f32,
+    colors: &Theme,
+    scale: f32,
+) {
+    let bg = colors.input_bg.mix(colors.button_hover, hover);
+    canvas.fill_rounded_rect(
+        x as f32,
+        y as f32,
+        size as f32,
+        size as f32,
+        3.0 * scale,
+        bg,
+    );
+    canvas.stroke_rounded_rect(
+        x as f32,
+        y as f32,
+        size as f32,
+        size as f32,
+        3.0 * scale,
+        colors.input_border,
+        1.0,
+    );
+
+    // Check mark
+    if checked {
+        let inset = (3.0 * scale) as i32;
+        canvas.fill_rounded_rect(
+            (x + inset) as f32,
+            (y + inset) as f32,
+            (size as i32 - inset * 2) as f32,
+            (size as i32 - inset * 2) as f32,
+            2.0 * scale,
+            colors.input_border_focused,
+        );
+    }
+}
@@ -0,0 +1,545 @@
+//! Combo box: a button showing the chosen item, which opens a list of the
+//! others below it.
+//!
+//! The list is drawn on the dialog's own canvas, over whatever is below
+//! the combo, so dialogs draw it last with [`Combo::draw_popup`] and pass
+//! it events before anything under it while [`Combo::is_open`].
+
+use std::time::{Duration, Instant};
+
+use super::{Widget, point_in_rect};
+use crate::{
+    backend::{MouseButton, ScrollDirection, WindowEvent},
+    render::{Canvas, Font},
+    ui::{Theme, animation::Transition},
+};
+
+const KEY_UP: u32 = 0xff52;
+const KEY_DOWN: u32 = 0xff54;
+const KEY_PAGE_UP: u32 = 0xff55;
+const KEY_PAGE_DOWN: u32 = 0xff56;
+const KEY_HOME: u32 = 0xff50;
+const KEY_END: u32 = 0xff57;
+const KEY_RETURN: u32 = 0xff0d;
+const KEY_KP_ENTER: u32 = 0xff8d;
+const KEY_ESCAPE: u32 = 0xff1b;
+
+const BASE_PADDING: u32 = 10;
+/// Width of the arrow showing the combo opens
+const BASE_ARROW_WIDTH: u32 = 10;
+const BASE_ROW_HEIGHT: u32 = 24;
+/// Rows shown at once before the list scrolls
+const DEFAULT_VISIBLE_ROWS: usize = 8;
+/// Typing within this long of the last key extends the search
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A button choosing one of a list of items.
+pub(crate) struct Combo {
+    items: Vec<String>,
+    /// Width of each item's text
+    widths: Vec<u32>,
+    selected: usize,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    scale: f32,
+    /// Drawn as its label alone and sized to it, for headers and toolbars
+    flat: bool,
+    hovered: bool,
+    hover: Transition,
+    focused: bool,
+    /// Last pointer position, for clicks in the list
+    pointer: (i32, i32),
+    changed: bool,
+    open: bool,
+    /// Row under the pointer, or moved to with the keyboard
+    highlighted: usize,
+    /// First row shown in the list
+    offset: usize,
+    visible_rows: usize,
+    row_height: u32,
+    /// Width of the list, at least that of the combo
+    popup_width: u32,
+    /// Text typed to jump to an item, and when it was last typed
+    typed: String,
+    typed_at: Instant,
+}
+
+impl Combo {
+    /// Creates a combo wide enough for its longest item, with `selected`
+    /// chosen.
+    pub fn new(
+        items: Vec<String>,
+        selected: usize,
+        font: &Font,
+        colors: &Theme,
+        scale: f32,
+    ) -> Self {
+        let mut combo = Self {
+            items: Vec::new(),
+            widths: Vec::new(),
+            selected: 0,
+            x: 0,
+            y: 0,
+            width: 0,
+            height: (colors.control_height as f32 * scale) as u32,
+            scale,
+            flat: false,
+            hovered: false,
+            hover: Transition::new(0.0),
+            focused: false,
+            pointer: (0, 0),
+            changed: false,
+            open: false,
+            highlighted: 0,
+            offset: 0,
+            visible_rows: DEFAULT_VISIBLE_ROWS,
+            row_height: (BASE_ROW_HEIGHT as f32 * scale) as u32,
+            popup_width: 0,
+            typed: String::new(),
+            typed_at: Instant::now(),
+        };
+        combo.set_items(items, selected, font);
+        combo
+    }
+
+    /// Draws the combo as its label alone, sized to the chosen item,
+    /// rather than as a button.
+    pub fn with_flat(mut self) -> Self {
+        self.flat = true;
+        self.fit();
+        self
+    }
+
+    /// Sets how many rows the list shows before it scrolls.
+    pub fn with_visible_rows(mut self, rows: usize) -> Self {
+        self.visible_rows = rows.max(1);
+        self
+    }
+
+    /// Replaces the items, choosing `selected`, and widens the list to
+    /// fit them.
+    pub fn set_items(&mut self, items: Vec<String>, selected: usize, font: &Font) {
+        self.widths = items
+            .iter()
+            .map(|item| font.render(item).measure().0.ceil() as u32)
+            .collect();
+        self.items = items;
+        self.selected = selected.min(self.items.len().saturating_sub(1));
+        self.open = false;
+        self.fit();
+    }
+
+    /// Chooses an item without reporting it as a change.
+    pub fn set_selected(&mut self, selected: usize) {
+        self.selected = selected.min(self.items.len().saturating_sub(1));
+        self.fit();
+    }
+
+    /// Sizes the combo and its list to the items.
+    fn fit(&mut self) {
+        let padding = (BASE_PADDING as f32 * self.scale) as u32;
+        let widest = self.widths.iter().copied().max().unwrap_or(0);
+        self.width = if self.flat {
+            self.widths.get(self.selected).copied().unwrap_or(0) + padding
+        } else {
+            widest + (BASE_ARROW_WIDTH as f32 * self.scale) as u32 + padding * 3
+        };
+        self.popup_width = (widest + padding * 3).max(self.width);
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Text of the chosen item.
+    fn label(&self) -> &str {
+        self.items.get(self.selected).map_or("", String::as_str)
+    }
+
+    /// Returns true if the user chose an item since the last call.
+    pub fn was_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Whether the list is showing, and so should see events first.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Shows the list with the chosen item highlighted.
+    fn open(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
+        self.open = true;
+        self.typed.clear();
+        self.highlight(self.selected);
+        // Put the chosen item in the middle of the list where possible
+        self.offset = self
+            .selected
+            .saturating_sub(self.visible_rows() / 2)
+            .min(self.max_offset());
+    }
+
+    fn close(&mut self) {
+        self.open = false;
+    }
+
+    fn visible_rows(&self) -> usize {
+        self.visible_rows.min(self.items.len()).max(1)
+    }
+
+    fn max_offset(&self) -> usize {
+        self.items.len().saturating_sub(self.visible_rows())
+    }
+
+    /// Top-left corner and size of the list.
+    fn popup_rect(&self) -> (i32, i32, u32, u32) {
+        let gap = (2.0 * self.scale) as i32;
+        (
+            self.x,
+            self.y + self.height as i32 + gap,
+            self.popup_width,
+            self.visible_rows() as u32 * self.row_height,
+        )
+    }
+
+    fn row_at(&self, x: i32, y: i32) -> Option<usize> {
+        let (px, py, pw, ph) = self.popup_rect();
+        if !point_in_rect(x, y, px, py, pw, ph) {
+            return None;
+        }
+        let row = self.offset + ((y - py) as u32 / self.row_height) as usize;
+        (row < self.items.len()).then_some(row)
+    }
+
+    /// Highlights a row and scrolls it into view.
+    fn highlight(&mut self, row: usize) {
+        self.highlighted = row.min(self.items.len().saturating_sub(1));
+        if self.highlighted < self.offset {
+            self.offset = self.highlighted;
+        } else if self.highlighted >= self.offset + self.visible_rows() {
+            self.offset = self.highlighted + 1 - self.visible_rows();
+        }
+    }
+
+    fn choose(&mut self, row: usize) {
+        if row != self.selected {
+            self.selected = row;
+            self.changed = true;
+            self.fit();
+        }
+        self.open = false;
+    }
+
+    /// Whether a name is being typed, so space belongs to it.
+    fn typing(&self) -> bool {
+        !self.typed.is_empty() && self.typed_at.elapsed() <= TYPE_AHEAD_TIMEOUT
+    }
+
+    /// Highlights the next item starting with what was typed, going round
+    /// from the highlighted one when a single letter is typed again.
+    fn type_ahead(&mut self, c: char) {
+        if self.typed_at.elapsed() > TYPE_AHEAD_TIMEOUT {
+            self.typed.clear();
+        }
+        self.typed_at = Instant::now();
+        self.typed.extend(c.to_lowercase());
+
+        let len = self.items.len();
+        let start = if self.typed.chars().count() == 1 {
+            self.highlighted + 1
+        } else {
+            self.highlighted
+        };
+        let found = (0..len)
+            .map(|i| (start + i) % len)
+            .find(|&i| self.items[i].to_lowercase().starts_with(&self.typed));
+        if let Some(row) = found {
+            self.highlight(row);
+        }
+    }
+
+    /// Draws the combo. Its list, if open, is drawn by
+    /// [`draw_popup`](Self::draw_popup).
+    pub fn draw_to(&self, canvas: &mut Canvas, colors: &Theme, font: &Font) {
+        let (x, y, w, h) = (
+            self.x as f32,
+            self.y as f32,
+            self.width as f32,
+            self.height as f32,
+        );
+        let radius = colors.control_radius * self.scale;
+        let padding = (BASE_PADDING as f32 * self.scale) as i32;
+        let hover = self.hover.value();
+
+        let text = font.render(self.label()).with_color(colors.text).finish();
+        let text_y = self.y + (self.height as i32 - text.height() as i32) / 2;
+
+        if self.focused && !self.open {
+            canvas.stroke_focus_ring(x, y, w, h, radius, 2.0, colors.focus_ring);
+        }
+
+        if self.flat {
+            if hover > 0.0 || self.open {
+                let alpha = if self.open { 1.0 } else { hover };
+                canvas.fill_rounded_rect(
+                    x,
+                    y,
+                    w,
+                    h,
+                    radius,
+                    colors.button_hover.with_alpha((alpha * 255.0) as u8),
+                );
+            }
+            let text_x = self.x + (self.width as i32 - text.width() as i32) / 2;
+            canvas.draw_canvas(&text, text_x, text_y);
+            return;
+        }
+
+        let bg = colors.button.mix(colors.button_hover, hover);
+        canvas.fill_rounded_rect(x, y, w, h, radius, bg);
+        canvas.stroke_rounded_rect(
+            x,
+            y,
+            w,
+            h,
+            radius,
+            if self.open {
+                colors.input_border_focused
+            } else {
+                colors.button_outline
+            },
+            colors.border_width,
+        );
+        canvas.draw_canvas(&text, self.x + padding, text_y);
+
+        // Downward arrow at the right end
+        let arrow = BASE_ARROW_WIDTH as f32 * self.scale;
+        let ax = x + w - padding as f32 - arrow;
+        let ay = y + h / 2.0 - arrow / 4.0;
+        let width = (1.5 * self.scale).max(1.0);
+        canvas.stroke_line(
+            ax,
+            ay,
+            ax + arrow / 2.0,
+            ay + arrow / 2.0,
+            colors.text,
+            width,
+        );
+        canvas.stroke_line(
+            ax + arrow / 2.0,
+            ay + arrow / 2.0,
+            ax + arrow,
+            ay,
+            colors.text,
+            width,
+        );
+    }
+
+    /// Draws the open list over everything drawn before it.
+    pub fn draw_popup(&self, canvas: &mut Canvas, colors: &Theme, font: &Font) {
+        if !self.open {
+            return;
+        }
+        let (px, py, pw, ph) = self.popup_rect();
+        let radius = colors.control_radius * self.scale;
+        let padding = (BASE_PADDING as f32 * self.scale) as i32;
+
+        canvas.fill_shadow(
+            px as f32,
+            py as f32 + 2.0 * self.scale,
+            pw as f32,
+            ph as f32,
+            radius,
+            4.0 * self.scale,
+            colors.window_shadow,
+        );
+
+        let mut list = Canvas::new(pw, ph);
+        list.fill_rounded_rect(0.0, 0.0, pw as f32, ph as f32, radius, colors.input_bg);
+
+        let end = self.items.len().min(self.offset + self.visible_rows());
+        for (vi, row) in (self.offset..end).enumerate() {
+            let ry = vi as i32 * self.row_height as i32;
+            let highlighted = row == self.highlighted;
+            if highlighted {
+                let inset = 2.0 * self.scale;
+                list.fill_rounded_rect(
+                    inset,
+                    ry as f32 + inset / 2.0,
+                    pw as f32 - inset * 2.0,
+                    self.row_height as f32 - inset,
+                    radius / 2.0,
+                    colors.input_border_focused,
+                );
+            }
+            let color = if highlighted {
+                colors.selection_text
+            } else if row == self.selected {
+                colors.input_border_focused
+            } else {
+                colors.text
+            };
+            let text = font.render(&self.items[row]).with_color(color).finish();
+            let ty = ry + (self.row_height as i32 - text.height() as i32) / 2;
+            list.draw_canvas(&text, padding, ty);
+        }
+
+        // Scrollbar thumb
+        if self.max_offset() > 0 {
+            let thumb_h = (self.visible_rows() as f32 / self.items.len() as f32 * ph as f32)
+                .max(20.0 * self.scale);
+            let thumb_y = self.offset as f32 / self.max_offset() as f32 * (ph as f32 - thumb_h);
+            list.fill_rounded_rect(
+                pw as f32 - 6.0 * self.scale,
+                thumb_y,
+                4.0 * self.scale,
+                thumb_h,
+                2.0 * self.scale,
+                colors.input_border,
+            );
+        }
+
+        canvas.draw_canvas(&list, px, py);
+        canvas.stroke_rounded_rect(
+            px as f32,
+            py as f32,
+            pw as f32,
+            ph as f32,
+            radius,
+            colors.input_border,
+            colors.border_width,
+        );
+    }
+}
+
+impl Widget for Combo {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorMove(pos) | WindowEvent::CursorEnter(pos) => {
+                let (x, y) = (pos.x as i32, pos.y as i32);
+                self.pointer = (x, y);
+                let hovered = point_in_rect(x, y, self.x, self.y, self.width, self.height);
+                let mut changed = hovered != self.hovered;
+                self.hovered = hovered;
+                self.hover.set(if hovered { 1.0 } else { 0.0 });
+                if self.open
+                    && let Some(row) = self.row_at(x, y)
+                    && row != self.highlighted
+                {
+                    self.highlighted = row;
+                    changed = true;
+                }
+                changed
+            }
+            WindowEvent::CursorLeave => {
+                self.hovered = false;
+                self.hover.set(0.0);
+                true
+            }
+            WindowEvent::ButtonPress(MouseButton::Left, _) => {
+                if self.open {
+                    // A click anywhere closes the list, choosing the row
+                    // under it if any
+                    match self.row_at(self.pointer.0, self.pointer.1) {
+                        Some(row) => self.choose(row),
+                        None => self.close(),
+                    }
+                    true
+                } else if self.hovered {
+                    self.open();
+                    true
+                } else {
+                    false
+                }
+            }
+            WindowEvent::Scroll(direction) if self.open => {
+                let rows = match direction {
+                    ScrollDirection::Up => -1,
+                    ScrollDirection::Down => 1,
+                    _ => return false,
+                };
+                self.offset = self
+                    .offset
+                    .saturating_add_signed(rows)
+                    .min(self.max_offset());
+                true
+            }
+            WindowEvent::KeyPress(key) if self.open => {
+                let page = self.visible_rows().saturating_sub(1).max(1);
+                let last = self.items.len().saturating_sub(1);
+                match key.keysym {
+                    KEY_UP => self.highlight(self.highlighted.saturating_sub(1)),
+                    KEY_DOWN => self.highlight(self.highlighted + 1),
+                    KEY_PAGE_UP => self.highlight(self.highlighted.saturating_sub(page)),
+                    KEY_PAGE_DOWN => self.highlight(self.highlighted + page),
+                    KEY_HOME => self.highlight(0),
+                    KEY_END => self.highlight(last),
+                    KEY_RETURN | KEY_KP_ENTER => self.choose(self.highlighted),
+                    KEY_ESCAPE => self.close(),
+                    _ => return false,
+                }
+                true
+            }
+            WindowEvent::TextInput(c) if self.open && !c.is_control() => {
+                // Space chooses the highlighted item unless part of a name
+                // being typed
+                if *c == ' ' && !self.typing() {
+                    self.choose(self.highlighted);
+                } else {
+                    self.type_ahead(*c);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn draw(&self, _canvas: &mut Canvas, _colors: &Theme) {
+        // Use draw_to instead for font access
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focus(&mut self, focused: bool) {
+        self.focused = focused;
+        if !focused {
+            self.close();
+        }
+    }
+
+    fn activate(&mut self) -> bool {
+        // While open, the list takes Space itself
+        if self.open {
+            return false;
+        }
+        self.open();
+        true
+    }
+}
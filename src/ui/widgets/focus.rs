@@ -0,0 +1,134 @@
+//! Keyboard focus: which widget keys go to, moved along with Tab and
+//! Shift+Tab.
+
+use super::{Widget, point_in_rect};
+use crate::backend::{Modifiers, MouseButton, WindowEvent};
+
+const KEY_TAB: u32 = 0xff09;
+const KEY_ISO_LEFT_TAB: u32 = 0xfe20;
+const KEY_RETURN: u32 = 0xff0d;
+const KEY_KP_ENTER: u32 = 0xff8d;
+
+/// Keyboard focus among a dialog's widgets.
+///
+/// The dialog hands every event to [`process_event`] first, along with its
+/// focusable widgets in Tab order; it must pass the same widgets in the
+/// same order each time. Tab and Shift+Tab move focus, a click focuses the
+/// widget under the pointer, and Space activates the focused widget, as if
+/// it were clicked. Enter does too for buttons; elsewhere it is left for
+/// the dialog's default action. Widgets show their own focus ring.
+///
+/// [`process_event`]: FocusManager::process_event
+pub(crate) struct FocusManager {
+    focused: Option<usize>,
+    pointer: (i32, i32),
+}
+
+impl FocusManager {
+    /// Creates a manager with nothing focused.
+    pub fn new() -> Self {
+        Self {
+            focused: None,
+            pointer: (0, 0),
+        }
+    }
+
+    /// Returns the index of the focused widget.
+    pub fn focused(&self) -> Option<usize> {
+        self.focused
+    }
+
+    /// Moves focus to the widget at `index`, or takes it from all of them.
+    pub fn focus(&mut self, index: Option<usize>, widgets: &mut [&mut dyn Widget]) {
+        self.focused = index.filter(|&i| i < widgets.len());
+        for (i, widget) in widgets.iter_mut().enumerate() {
+            widget.set_focus(self.focused == Some(i));
+        }
+    }
+
+    /// Moves focus on to the next focusable widget, or back to the previous
+    /// one, wrapping round at the ends. Returns false if none can take it.
+    fn cycle(&mut self, backwards: bool, widgets: &mut [&mut dyn Widget]) -> bool {
+        let count = widgets.len();
+        if count == 0 {
+            return false;
+        }
+        // With nothing focused, Tab starts at the first widget and Shift+Tab
+        // at the last
+        let start = match (self.focused, backwards) {
+            (Some(i), _) => i,
+            (None, false) => count - 1,
+            (None, true) => 0,
+        };
+        let next = (1..=count)
+            .map(|step| {
+                if backwards {
+                    (start + count * step - step) % count
+                } else {
+                    (start + step) % count
+                }
+            })
+            .find(|&i| widgets[i].is_focusable());
+        if next.is_some() {
+            self.focus(next, widgets);
+        }
+        next.is_some()
+    }
+
+    /// Handles focus keys and clicks, returning the event for the dialog to
+    /// go on with. Events used up here come back as `RedrawRequested`.
+    pub fn process_event(
+        &mut self,
+        event: WindowEvent,
+        widgets: &mut [&mut dyn Widget],
+    ) -> WindowEvent {
+        // A widget disabled since it was focused gives up focus
+        if let Some(i) = self.focused
+            && !widgets.get(i).is_some_and(|w| w.is_focusable())
+        {
+            self.focus(None, widgets);
+        }
+
+        match &event {
+            WindowEvent::CursorMove(pos) | WindowEvent::CursorEnter(pos) => {
+                self.pointer = (pos.x as i32, pos.y as i32);
+            }
+            WindowEvent::ButtonPress(MouseButton::Left, _) => {
+                let (x, y) = self.pointer;
+                if let Some(i) = widgets.iter().position(|w| {
+                    w.is_focusable() && point_in_rect(x, y, w.x(), w.y(), w.width(), w.height())
+                }) && self.focused != Some(i)
+                {
+                    self.focus(Some(i), widgets);
+                }
+            }
+            WindowEvent::KeyPress(key)
+                if matches!(key.keysym, KEY_TAB | KEY_ISO_LEFT_TAB)
+                    && !key.modifiers.contains(Modifiers::CTRL) =>
+            {
+                let backwards =
+                    key.keysym == KEY_ISO_LEFT_TAB || key.modifiers.contains(Modifiers::SHIFT);
+                if self.cycle(backwards, widgets) {
+                    return WindowEvent::RedrawRequested;
+                }
+            }
+            WindowEvent::KeyPress(key) if matches!(key.keysym, KEY_RETURN | KEY_KP_ENTER) => {
+                if let Some(i) = self.focused
+                    && widgets[i].activates_on_enter()
+                    && widgets[i].activate()
+                {
+                    return WindowEvent::RedrawRequested;
+                }
+            }
+            WindowEvent::TextInput(' ') => {
+                if let Some(i) = self.focused
+                    && widgets[i].activate()
+                {
+                    return WindowEvent::RedrawRequested;
+                }
+            }
+            _ => {}
+        }
+        event
+    }
+}
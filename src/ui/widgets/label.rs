@@ -0,0 +1,240 @@
+//! Text label widget.
+
+use super::Widget;
+use crate::{
+    backend::WindowEvent,
+    render::{
+        Align, Canvas, Font, Rgba,
+        markup::{self, StyledText},
+    },
+    ui::Theme,
+};
+
+const ELLIPSIS: &str = "...";
+
+/// How a line too long for a label is shortened.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Ellipsize {
+    /// Lines wrap instead, or run on when wrapping is off
+    #[default]
+    None,
+    /// The middle of the line gives way, keeping both ends
+    Middle,
+    /// The end of the line gives way
+    End,
+}
+
+/// A block of text, wrapped or shortened to fit a width.
+///
+/// Configure it with the `with_` methods, then [`layout`] it, which
+/// renders the text; the label is then as big as the text it shows, up to
+/// the width given. Lines are aligned with each other; placing the block
+/// is left to the dialog.
+///
+/// [`layout`]: Label::layout
+pub(crate) struct Label {
+    text: String,
+    markup: bool,
+    wrap: bool,
+    align: Align,
+    ellipsize: Ellipsize,
+    x: i32,
+    y: i32,
+    rendered: Option<Canvas>,
+}
+
+impl Label {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            markup: false,
+            wrap: false,
+            align: Align::Start,
+            ellipsize: Ellipsize::None,
+            x: 0,
+            y: 0,
+            rendered: None,
+        }
+    }
+
+    /// Reads the text as Pango-style markup.
+    pub fn with_markup(mut self, markup: bool) -> Self {
+        self.markup = markup;
+        self
+    }
+
+    /// Breaks lines wider than the label at spaces.
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Shortens lines wider than the label with "...", rather than
+    /// wrapping them.
+    pub fn with_ellipsize(mut self, ellipsize: Ellipsize) -> Self {
+        self.ellipsize = ellipsize;
+        self
+    }
+
+    /// Renders the text in `color`, fitting it in `max_width` pixels if
+    /// given. Empty text takes no space.
+    pub fn layout(&mut self, font: &Font, color: Rgba, max_width: Option<u32>) {
+        if self.text.is_empty() {
+            self.rendered = None;
+            return;
+        }
+        let styled = self
+            .markup
+            .then(|| markup::parse(&self.text))
+            .flatten()
+            .unwrap_or_else(|| {
+                StyledText {
+                    text: self.text.clone(),
+                    runs: Vec::new(),
+                }
+            });
+        let (shown, wrap_width) = match max_width {
+            Some(width) if self.ellipsize != Ellipsize::None => {
+                (ellipsize(&styled, font, width as f32, self.ellipsize), None)
+            }
+            Some(width) if self.wrap => (styled, Some(width as f32)),
+            _ => (styled, None),
+        };
+
+        let renderer = font
+            .render(&shown.text)
+            .with_runs(&shown.runs)
+            .with_color(color)
+            .with_align(self.align);
+        self.rendered = Some(match wrap_width {
+            Some(width) => renderer.with_max_width(width).finish(),
+            None => renderer.finish(),
+        });
+    }
+
+    /// Cuts off text below `height` pixels.
+    pub fn set_max_height(&mut self, height: u32) {
+        if let Some(rendered) = &mut self.rendered
+            && rendered.height() > height
+        {
+            let mut clipped = Canvas::new(rendered.width(), height.max(1));
+            clipped.draw_canvas(rendered, 0, 0);
+            *rendered = clipped;
+        }
+    }
+}
+
+impl Widget for Label {
+    fn width(&self) -> u32 {
+        self.rendered.as_ref().map_or(0, Canvas::width)
+    }
+
+    fn height(&self) -> u32 {
+        self.rendered.as_ref().map_or(0, Canvas::height)
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn process_event(&mut self, _event: &WindowEvent) -> bool {
+        false
+    }
+
+    fn draw(&self, canvas: &mut Canvas, _colors: &Theme) {
+        if let Some(rendered) = &self.rendered {
+            canvas.draw_canvas(rendered, self.x, self.y);
+        }
+    }
+}
+
+/// Shortens each line of `text` wider than `max_width` with an ellipsis
+/// where `mode` says, keeping the styles of what is left.
+fn ellipsize(text: &StyledText, font: &Font, max_width: f32, mode: Ellipsize) -> StyledText {
+    let mut shown = StyledText::default();
+    for (i, line) in text.text.split('\n').enumerate() {
+        let start = line.as_ptr() as usize - text.text.as_ptr() as usize;
+        let line = StyledText {
+            text: line.to_string(),
+            runs: text.runs_in(start..start + line.len()),
+        };
+        if i > 0 {
+            shown.text.push('\n');
+        }
+        append(&mut shown, ellipsize_line(line, font, max_width, mode));
+    }
+    shown
+}
+
+/// Shortens one line to the most characters that fit in `max_width` with
+/// the ellipsis.
+fn ellipsize_line(line: StyledText, font: &Font, max_width: f32, mode: Ellipsize) -> StyledText {
+    let width = |text: &StyledText| font.render(&text.text).with_runs(&text.runs).measure().0;
+    if width(&line) <= max_width {
+        return line;
+    }
+
+    // Byte offset of each character, and of the end
+    let bounds: Vec<usize> = line
+        .text
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain([line.text.len()])
+        .collect();
+    let chars = bounds.len() - 1;
+    let shortened = |keep: usize| {
+        let (head, tail) = match mode {
+            Ellipsize::Middle => (keep.div_ceil(2), keep / 2),
+            _ => (keep, 0),
+        };
+        let (head_end, tail_start) = (bounds[head], bounds[chars - tail]);
+        let mut text = StyledText {
+            text: line.text[..head_end].to_string(),
+            runs: line.runs_in(0..head_end),
+        };
+        text.text.push_str(ELLIPSIS);
+        append(
+            &mut text,
+            StyledText {
+                text: line.text[tail_start..].to_string(),
+                runs: line.runs_in(tail_start..line.text.len()),
+            },
+        );
+        text
+    };
+
+    let (mut low, mut high) = (0, chars - 1);
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if width(&shortened(mid)) <= max_width {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    shortened(low)
+}
+
+/// Adds `tail` to the end of `text`, moving its runs along.
+fn append(text: &mut StyledText, tail: StyledText) {
+    let offset = text.text.len();
+    text.text.push_str(&tail.text);
+    text.runs.extend(tail.runs.into_iter().map(|mut run| {
+        run.range = run.range.start + offset..run.range.end + offset;
+        run
+    }));
+}
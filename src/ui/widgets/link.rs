@@ -0,0 +1,161 @@
+//! Hyperlink widget.
+
+use super::{Widget, point_in_rect};
+use crate::{
+    backend::{MouseButton, WindowEvent},
+    render::{Canvas, Font},
+    ui::Theme,
+};
+
+/// Text in the accent color that opens a URI with `xdg-open` when clicked,
+/// or activated with Enter or Space while focused. It is underlined while
+/// hovered or focused.
+///
+/// The dialog shows the pointing hand over it by checking [`is_hovered`].
+///
+/// [`is_hovered`]: Link::is_hovered
+pub(crate) struct Link {
+    text: String,
+    uri: String,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    underline_thickness: f32,
+    scale: f32,
+    hovered: bool,
+    focused: bool,
+}
+
+impl Link {
+    /// Creates a link showing `text`, or the URI itself if `text` is empty.
+    pub fn new(text: &str, uri: &str, font: &Font, scale: f32) -> Self {
+        let text = if text.is_empty() { uri } else { text };
+        let (width, height) = font.render(text).measure();
+        Self {
+            text: text.to_string(),
+            uri: uri.to_string(),
+            x: 0,
+            y: 0,
+            width: width.ceil() as u32,
+            height: height.ceil() as u32,
+            underline_thickness: scale.max(1.0).round(),
+            scale,
+            hovered: false,
+            focused: false,
+        }
+    }
+
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    /// Hands the URI to the desktop's default handler. Failing to start
+    /// `xdg-open` leaves the dialog as it is.
+    fn open(&self) {
+        let _ = std::process::Command::new("xdg-open")
+            .arg(&self.uri)
+            .spawn();
+    }
+
+    /// Draws the link to a canvas.
+    pub fn draw_to(&self, canvas: &mut Canvas, colors: &Theme, font: &Font) {
+        let color = colors.input_border_focused;
+        let renderer = font.render(&self.text);
+        let baseline = renderer.char_span(0).map(|(_, _, baseline)| baseline);
+        canvas.draw_canvas(&renderer.with_color(color).finish(), self.x, self.y);
+
+        if (self.hovered || self.focused)
+            && let Some(baseline) = baseline
+        {
+            canvas.fill_rect(
+                self.x as f32,
+                self.y as f32 + baseline + self.underline_thickness,
+                self.width as f32,
+                self.underline_thickness,
+                color,
+            );
+        }
+
+        if self.focused {
+            let pad = 2.0 * self.scale;
+            canvas.stroke_focus_ring(
+                self.x as f32 - pad,
+                self.y as f32 - pad,
+                self.width as f32 + pad * 2.0,
+                self.height as f32 + pad * 2.0,
+                colors.control_radius * self.scale / 2.0,
+                2.0,
+                colors.focus_ring,
+            );
+        }
+    }
+}
+
+impl Widget for Link {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorMove(pos) | WindowEvent::CursorEnter(pos) => {
+                let hovered = point_in_rect(
+                    pos.x as i32,
+                    pos.y as i32,
+                    self.x,
+                    self.y,
+                    self.width,
+                    self.height,
+                );
+                let changed = hovered != self.hovered;
+                self.hovered = hovered;
+                changed
+            }
+            WindowEvent::CursorLeave => std::mem::take(&mut self.hovered),
+            WindowEvent::ButtonPress(MouseButton::Left, _) if self.hovered => {
+                self.open();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn draw(&self, _canvas: &mut Canvas, _colors: &Theme) {
+        // Use draw_to instead for font access
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focus(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn activate(&mut self) -> bool {
+        self.open();
+        true
+    }
+
+    fn activates_on_enter(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,200 @@
+//! Scrollable list of selectable text rows.
+
+use crate::{
+    render::{Canvas, Font, Rgba, rgb},
+    ui::{Theme, widgets::point_in_rect},
+};
+
+/// A scrollable single-selection column of rows.
+pub(crate) struct ListPane {
+    x: i32,
+    y: i32,
+    w: u32,
+    h: u32,
+    row_height: u32,
+    items: Vec<String>,
+    pub selected: Option<usize>,
+    hovered: Option<usize>,
+    offset: usize,
+    /// X offsets of the tab-separated columns after the first.
+    tab_stops: Vec<u32>,
+}
+
+impl ListPane {
+    pub fn new(x: i32, y: i32, w: u32, h: u32, row_height: u32) -> Self {
+        Self {
+            x,
+            y,
+            w,
+            h,
+            row_height,
+            items: Vec::new(),
+            selected: None,
+            hovered: None,
+            offset: 0,
+            tab_stops: Vec::new(),
+        }
+    }
+
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    pub fn visible_rows(&self) -> usize {
+        (self.h / self.row_height).max(1) as usize
+    }
+
+    fn max_offset(&self) -> usize {
+        self.items.len().saturating_sub(self.visible_rows())
+    }
+
+    /// Sets where the tab-separated columns of each row start, relative to
+    /// the first column.
+    pub fn set_tab_stops(&mut self, stops: Vec<u32>) {
+        self.tab_stops = stops;
+    }
+
+    pub fn set_items(&mut self, items: Vec<String>, selected: Option<usize>) {
+        self.items = items;
+        self.offset = self.offset.min(self.max_offset());
+        self.hovered = None;
+        self.select(selected);
+    }
+
+    /// Selects a row and scrolls it into view.
+    pub fn select(&mut self, row: Option<usize>) {
+        self.selected = row.filter(|&r| r < self.items.len());
+        if let Some(row) = self.selected {
+            if row < self.offset {
+                self.offset = row;
+            } else if row >= self.offset + self.visible_rows() {
+                self.offset = row + 1 - self.visible_rows();
+            }
+        }
+    }
+
+    /// Moves the selection by `rows`, stopping at either end.
+    pub fn step(&mut self, rows: isize) {
+        if self.items.is_empty() {
+            return;
+        }
+        let row = self.selected.map_or(0, |r| {
+            r.saturating_add_signed(rows).min(self.items.len() - 1)
+        });
+        self.select(Some(row));
+    }
+
+    pub fn scroll(&mut self, rows: isize) {
+        self.offset = self
+            .offset
+            .saturating_add_signed(rows)
+            .min(self.max_offset());
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        point_in_rect(x, y, self.x, self.y, self.w, self.h)
+    }
+
+    pub fn row_at(&self, x: i32, y: i32) -> Option<usize> {
+        if !self.contains(x, y) {
+            return None;
+        }
+        let row = self.offset + ((y - self.y) as u32 / self.row_height) as usize;
+        (row < self.items.len()).then_some(row)
+    }
+
+    /// Updates the hovered row. Returns true if it changed.
+    pub fn hover(&mut self, x: i32, y: i32) -> bool {
+        let hovered = self.row_at(x, y);
+        let changed = hovered != self.hovered;
+        self.hovered = hovered;
+        changed
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas, font: &Font, colors: &Theme, active: bool, scale: f32) {
+        let radius = 4.0 * scale;
+        let mut list = Canvas::new(self.w, self.h);
+        list.fill_rounded_rect(
+            0.0,
+            0.0,
+            self.w as f32,
+            self.h as f32,
+            radius,
+            colors.input_bg,
+        );
+
+        let end = self.items.len().min(self.offset + self.visible_rows() + 1);
+        for (vi, row) in (self.offset..end).enumerate() {
+            let ry = (vi as u32 * self.row_height) as f32;
+            let selected = self.selected == Some(row);
+            if selected {
+                list.fill_rect(
+                    1.0,
+                    ry,
+                    (self.w - 2) as f32,
+                    self.row_height as f32,
+                    colors.input_border_focused,
+                );
+            } else if self.hovered == Some(row) {
+                list.fill_rect(
+                    1.0,
+                    ry,
+                    (self.w - 2) as f32,
+                    self.row_height as f32,
+                    darken(colors.input_bg, 0.06),
+                );
+            }
+
+            let color = if selected {
+                colors.selection_text
+            } else {
+                colors.text
+            };
+            let stops = std::iter::once(0).chain(self.tab_stops.iter().copied());
+            for (cell, stop) in self.items[row].split('\t').zip(stops) {
+                let text = font.render(cell).with_color(color).finish();
+                let ty = ry as i32 + (self.row_height as i32 - text.height() as i32) / 2;
+                list.draw_canvas(&text, (8.0 * scale) as i32 + stop as i32, ty);
+            }
+        }
+
+        // Scrollbar thumb
+        if self.max_offset() > 0 {
+            let visible = self.visible_rows() as f32;
+            let thumb_h = (visible / self.items.len() as f32 * self.h as f32).max(20.0 * scale);
+            let thumb_y = self.offset as f32 / self.max_offset() as f32 * (self.h as f32 - thumb_h);
+            list.fill_rounded_rect(
+                self.w as f32 - 8.0 * scale,
+                thumb_y,
+                6.0 * scale,
+                thumb_h,
+                3.0 * scale,
+                colors.input_border,
+            );
+        }
+
+        canvas.draw_canvas(&list, self.x, self.y);
+        canvas.stroke_rounded_rect(
+            self.x as f32,
+            self.y as f32,
+            self.w as f32,
+            self.h as f32,
+            radius,
+            if active {
+                colors.input_border_focused
+            } else {
+                colors.input_border
+            },
+            1.0,
+        );
+    }
+}
+
+fn darken(color: Rgba, amount: f32) -> Rgba {
+    rgb(
+        (color.r as f32 * (1.0 - amount)) as u8,
+        (color.g as f32 * (1.0 - amount)) as u8,
+        (color.b as f32 * (1.0 - amount)) as u8,
+    )
+}
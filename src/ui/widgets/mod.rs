@@ -1,10 +1,23 @@
 //! Reusable UI widgets.
 
+pub(crate) mod accel;
 pub(crate) mod button;
+pub(crate) mod checkbox;
+pub(crate) mod combo;
+pub(crate) mod focus;
+pub(crate) mod label;
+pub(crate) mod link;
+pub(crate) mod list_pane;
 pub(crate) mod progress_bar;
+pub(crate) mod scroll_area;
+pub(crate) mod spin_button;
+pub(crate) mod spinner;
+pub(crate) mod switch;
 pub(crate) mod text_input;
+pub(crate) mod titlebar;
+pub(crate) mod tooltip;
 
-use crate::{backend::WindowEvent, render::Canvas, ui::Colors};
+use crate::{backend::WindowEvent, render::Canvas, ui::Theme};
 
 /// Trait for UI widgets.
 #[allow(dead_code)]
@@ -15,7 +28,34 @@ pub(crate) trait Widget {
     fn y(&self) -> i32;
     fn set_position(&mut self, x: i32, y: i32);
     fn process_event(&mut self, event: &WindowEvent) -> bool;
-    fn draw(&self, canvas: &mut Canvas, colors: &Colors);
+    fn draw(&self, canvas: &mut Canvas, colors: &Theme);
+
+    /// Whether the widget can take keyboard focus; see
+    /// [`focus::FocusManager`].
+    fn is_focusable(&self) -> bool {
+        false
+    }
+
+    /// Gives the widget keyboard focus or takes it away.
+    fn set_focus(&mut self, _focused: bool) {}
+
+    /// Acts on Space while focused, as a click would. Returns false to
+    /// leave the key to the widget's own handling.
+    fn activate(&mut self) -> bool {
+        false
+    }
+
+    /// Whether Enter activates the widget too, rather than being left for
+    /// the dialog's default action.
+    fn activates_on_enter(&self) -> bool {
+        false
+    }
+
+    /// Text for a [`tooltip::Tooltip`] while the pointer is over the
+    /// widget.
+    fn tooltip(&self) -> Option<&str> {
+        None
+    }
 }
 
 /// Check if a point is within a rectangle.
@@ -1,9 +1,15 @@
 //! Progress bar widget.
 
-use crate::{render::Canvas, ui::Colors};
+use std::time::{Duration, Instant};
+
+use crate::{
+    render::Canvas,
+    ui::{Theme, animation},
+};
 
 const BASE_BAR_HEIGHT: u32 = 20;
-const BASE_BAR_RADIUS: f32 = 4.0;
+/// Time the pulse takes to sweep across the bar
+const PULSE_PERIOD: Duration = Duration::from_millis(800);
 
 /// A progress bar widget.
 pub struct ProgressBar {
@@ -11,10 +17,11 @@ pub struct ProgressBar {
     y: i32,
     width: u32,
     height: u32,
-    radius: f32,
+    scale: f32,
     progress: f32, // 0.0 to 1.0
     pulsating: bool,
-    pulse_position: f32, // For pulsating animation
+    /// When the pulse started its first sweep
+    pulse_start: Instant,
 }
 
 impl ProgressBar {
@@ -24,10 +31,10 @@ impl ProgressBar {
             y: 0,
             width,
             height: (BASE_BAR_HEIGHT as f32 * scale) as u32,
-            radius: BASE_BAR_RADIUS * scale,
+            scale,
             progress: 0.0,
             pulsating: false,
-            pulse_position: 0.0,
+            pulse_start: Instant::now(),
         }
     }
 
@@ -46,7 +53,7 @@ impl ProgressBar {
     pub fn set_pulsating(&mut self, pulsating: bool) {
         self.pulsating = pulsating;
         if pulsating {
-            self.pulse_position = 0.0;
+            self.pulse_start = Instant::now();
         }
     }
 
@@ -55,16 +62,6 @@ impl ProgressBar {
         self.pulsating
     }
 
-    /// Advances the pulse animation. Call this periodically.
-    pub fn tick(&mut self) {
-        if self.pulsating {
-            self.pulse_position += 0.02;
-            if self.pulse_position > 1.0 {
-                self.pulse_position = 0.0;
-            }
-        }
-    }
-
     /// Returns the current progress (0.0 to 1.0).
     #[allow(dead_code)]
     pub fn progress(&self) -> f32 {
@@ -86,41 +83,45 @@ impl ProgressBar {
     }
 
     /// Draws the progress bar to a canvas.
-    pub fn draw(&self, canvas: &mut Canvas, colors: &Colors) {
+    pub fn draw(&self, canvas: &mut Canvas, colors: &Theme) {
+        let radius = colors.control_radius * self.scale;
+
         // Draw background (track)
         canvas.fill_rounded_rect(
             self.x as f32,
             self.y as f32,
             self.width as f32,
             self.height as f32,
-            self.radius,
+            radius,
             colors.progress_bg,
         );
 
         // Draw progress fill
         if self.pulsating {
-            // Draw a moving pulse
+            // Draw a pulse sweeping across, for as long as it is drawn
+            let sweeps = self.pulse_start.elapsed().as_secs_f32() / PULSE_PERIOD.as_secs_f32();
+            animation::request_frame();
             let pulse_width = self.width as f32 * 0.3;
             let max_x = self.width as f32 - pulse_width;
-            let pulse_x = self.x as f32 + max_x * self.pulse_position;
+            let pulse_x = self.x as f32 + max_x * sweeps.fract();
 
             canvas.fill_rounded_rect(
                 pulse_x,
                 self.y as f32,
                 pulse_width,
                 self.height as f32,
-                self.radius,
+                radius,
                 colors.progress_fill,
             );
         } else if self.progress > 0.0 {
-            let fill_width = (self.width as f32 * self.progress).max(self.radius * 2.0);
+            let fill_width = (self.width as f32 * self.progress).max(radius * 2.0);
 
             canvas.fill_rounded_rect(
                 self.x as f32,
                 self.y as f32,
                 fill_width,
                 self.height as f32,
-                self.radius,
+                radius,
                 colors.progress_fill,
             );
         }
@@ -131,9 +132,9 @@ impl ProgressBar {
             self.y as f32,
             self.width as f32,
             self.height as f32,
-            self.radius,
+            radius,
             colors.progress_border,
-            1.0,
+            colors.border_width,
         );
     }
 }
@@ -0,0 +1,471 @@
+//! Scrollable area: scroll offsets and the scrollbars that show them.
+
+use super::{Widget, point_in_rect};
+use crate::{
+    backend::{MouseButton, ScrollDirection, WindowEvent},
+    render::{Canvas, rgb},
+    ui::Theme,
+};
+
+/// Scrollbar thickness, and while the pointer is over it
+const BASE_BAR_WIDTH: f32 = 8.0;
+const BASE_BAR_HOVER_WIDTH: f32 = 12.0;
+/// Gap between the ends of a scrollbar and the edges of the area
+const BASE_BAR_INSET: f32 = 4.0;
+const BASE_MIN_THUMB: f32 = 20.0;
+/// Pixels moved per horizontal wheel step or arrow key
+const BASE_H_STEP: f32 = 100.0;
+
+const KEY_LEFT: u32 = 0xff51;
+const KEY_RIGHT: u32 = 0xff53;
+const KEY_PAGE_UP: u32 = 0xff55;
+const KEY_PAGE_DOWN: u32 = 0xff56;
+const KEY_LSHIFT: u32 = 0xffe1;
+const KEY_RSHIFT: u32 = 0xffe2;
+
+/// Which scrollbar's thumb is being dragged, and where on it it was
+/// grabbed.
+#[derive(Clone, Copy)]
+enum Drag {
+    Vertical(i32),
+    Horizontal(i32),
+}
+
+/// The scroll state of an area showing part of its content, with the
+/// scrollbars along its right and bottom edges.
+///
+/// Vertically it counts in whatever the owner lays its content out in —
+/// lines, rows or grid entries — and horizontally in pixels. The owner
+/// draws the visible part of the content itself, from [`offset`] and
+/// [`h_offset`], then draws the area on top for the scrollbars. Dragging
+/// a thumb, clicking a track, the wheel (with Shift for sideways) and Page
+/// Up/Down scroll it, as do Left/Right when the content is too wide.
+///
+/// [`offset`]: ScrollArea::offset
+/// [`h_offset`]: ScrollArea::h_offset
+pub(crate) struct ScrollArea {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    scale: f32,
+    /// Height at the top the vertical scrollbar leaves clear, for column
+    /// headers
+    header: u32,
+    offset: usize,
+    total: usize,
+    page: usize,
+    /// Units per row; offsets stay at the start of a row
+    columns: usize,
+    /// Rows moved per wheel step
+    step: usize,
+    h_offset: u32,
+    content_width: u32,
+    v_hovered: bool,
+    h_hovered: bool,
+    drag: Option<Drag>,
+    pointer: (i32, i32),
+    shift: bool,
+}
+
+impl ScrollArea {
+    pub fn new(width: u32, height: u32, scale: f32) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            width,
+            height,
+            scale,
+            header: 0,
+            offset: 0,
+            total: 0,
+            page: 0,
+            columns: 1,
+            step: 3,
+            h_offset: 0,
+            content_width: 0,
+            v_hovered: false,
+            h_hovered: false,
+            drag: None,
+            pointer: (0, 0),
+            shift: false,
+        }
+    }
+
+    /// Sets the rows moved per wheel step.
+    pub fn set_step(&mut self, step: usize) {
+        self.step = step.max(1);
+    }
+
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+        self.clamp();
+    }
+
+    /// Keeps the vertical scrollbar below a header `height` pixels tall.
+    pub fn set_header(&mut self, height: u32) {
+        self.header = height;
+    }
+
+    /// Sets how much content there is and how much of it shows at once,
+    /// keeping the offset in range.
+    pub fn set_content(&mut self, total: usize, page: usize) {
+        self.total = total;
+        self.page = page;
+        self.clamp();
+    }
+
+    /// Lays the content out in rows of `columns` units, as a grid does.
+    pub fn set_columns(&mut self, columns: usize) {
+        self.columns = columns.max(1);
+        self.clamp();
+    }
+
+    /// Sets the full width of the content, in pixels.
+    pub fn set_content_width(&mut self, width: u32) {
+        self.content_width = width;
+        self.clamp();
+    }
+
+    /// Returns the first unit in view.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns how many pixels of the content are scrolled off to the
+    /// left.
+    pub fn h_offset(&self) -> u32 {
+        self.h_offset
+    }
+
+    /// Largest offset, which shows the last row at the bottom.
+    pub fn max_offset(&self) -> usize {
+        (self.total.div_ceil(self.columns) * self.columns).saturating_sub(self.page)
+    }
+
+    fn max_h_offset(&self) -> u32 {
+        self.content_width.saturating_sub(self.width)
+    }
+
+    fn clamp(&mut self) {
+        self.offset = self.row_start(self.offset.min(self.max_offset()));
+        self.h_offset = self.h_offset.min(self.max_h_offset());
+    }
+
+    fn row_start(&self, offset: usize) -> usize {
+        offset - offset % self.columns
+    }
+
+    /// Scrolls to `offset`, or as near as it goes. Returns true if the
+    /// view moved.
+    pub fn scroll_to(&mut self, offset: usize) -> bool {
+        let old = self.offset;
+        self.offset = offset;
+        self.clamp();
+        self.offset != old
+    }
+
+    /// Scrolls by `rows` rows, up for negative. Returns true if the view
+    /// moved.
+    pub fn scroll_by(&mut self, rows: isize) -> bool {
+        let delta = rows * self.columns as isize;
+        self.scroll_to(self.offset.saturating_add_signed(delta))
+    }
+
+    /// Scrolls just far enough to bring the unit at `pos` into view.
+    /// Returns true if the view moved.
+    pub fn scroll_into_view(&mut self, pos: usize) -> bool {
+        if pos < self.offset {
+            self.scroll_to(self.row_start(pos))
+        } else if pos >= self.offset + self.page {
+            self.scroll_to((self.row_start(pos) + self.columns).saturating_sub(self.page))
+        } else {
+            false
+        }
+    }
+
+    /// Scrolls sideways by `pixels`, left for negative. Returns true if
+    /// the view moved.
+    pub fn scroll_h_by(&mut self, pixels: i32) -> bool {
+        let old = self.h_offset;
+        self.h_offset = self
+            .h_offset
+            .saturating_add_signed(pixels)
+            .min(self.max_h_offset());
+        self.h_offset != old
+    }
+
+    fn has_v_bar(&self) -> bool {
+        self.max_offset() > 0
+    }
+
+    fn has_h_bar(&self) -> bool {
+        self.content_width > self.width
+    }
+
+    fn bar_width(&self, hovered: bool) -> f32 {
+        if hovered {
+            BASE_BAR_HOVER_WIDTH * self.scale
+        } else {
+            BASE_BAR_WIDTH * self.scale
+        }
+    }
+
+    /// Vertical track as (y, height), and the thumb within it as (y,
+    /// height).
+    fn v_track(&self) -> ((f32, f32), (f32, f32)) {
+        let inset = BASE_BAR_INSET * self.scale;
+        let bottom = if self.has_h_bar() {
+            self.bar_width(false)
+        } else {
+            0.0
+        };
+        let track_y = (self.y + self.header as i32) as f32 + inset;
+        let track_h = (self.height.saturating_sub(self.header) as f32 - inset * 2.0 - bottom)
+            .max(BASE_MIN_THUMB * self.scale);
+        let shown = self.page as f32 / (self.max_offset() + self.page).max(1) as f32;
+        let thumb_h = (shown * track_h).clamp(BASE_MIN_THUMB * self.scale, track_h);
+        let ratio = self.offset as f32 / self.max_offset().max(1) as f32;
+        (
+            (track_y, track_h),
+            (track_y + ratio * (track_h - thumb_h), thumb_h),
+        )
+    }
+
+    /// Horizontal track as (x, width), and the thumb within it as (x,
+    /// width).
+    fn h_track(&self) -> ((f32, f32), (f32, f32)) {
+        let inset = BASE_BAR_INSET * self.scale;
+        let right = if self.has_v_bar() {
+            self.bar_width(false)
+        } else {
+            0.0
+        };
+        let track_x = self.x as f32 + inset;
+        let track_w = (self.width as f32 - inset * 2.0 - right).max(BASE_MIN_THUMB * self.scale);
+        let shown = self.width as f32 / self.content_width.max(1) as f32;
+        let thumb_w = (shown * track_w).clamp(BASE_MIN_THUMB * self.scale, track_w);
+        let ratio = self.h_offset as f32 / self.max_h_offset().max(1) as f32;
+        (
+            (track_x, track_w),
+            (track_x + ratio * (track_w - thumb_w), thumb_w),
+        )
+    }
+
+    fn over_v_bar(&self, px: i32, py: i32) -> bool {
+        let w = self.bar_width(self.v_hovered) as u32;
+        self.has_v_bar()
+            && point_in_rect(
+                px,
+                py,
+                self.x + self.width as i32 - w as i32,
+                self.y + self.header as i32,
+                w,
+                self.height.saturating_sub(self.header),
+            )
+    }
+
+    fn over_h_bar(&self, px: i32, py: i32) -> bool {
+        let h = self.bar_width(self.h_hovered) as u32;
+        self.has_h_bar()
+            && point_in_rect(
+                px,
+                py,
+                self.x,
+                self.y + self.height as i32 - h as i32,
+                self.width,
+                h,
+            )
+    }
+
+    /// Whether a point is on a scrollbar, where clicks are the
+    /// scrollbar's rather than the content's.
+    pub fn over_bar(&self, px: i32, py: i32) -> bool {
+        self.over_v_bar(px, py) || self.over_h_bar(px, py)
+    }
+
+    /// Whether a scrollbar thumb is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    /// Moves a dragged thumb to follow the pointer.
+    fn drag_to(&mut self, px: i32, py: i32) -> bool {
+        match self.drag {
+            Some(Drag::Vertical(grab)) => {
+                let ((track_y, track_h), (_, thumb_h)) = self.v_track();
+                let room = (track_h - thumb_h).max(1.0);
+                let ratio = ((py - grab) as f32 - track_y) / room;
+                let offset = (ratio.clamp(0.0, 1.0) * self.max_offset() as f32).round();
+                self.scroll_to(offset as usize)
+            }
+            Some(Drag::Horizontal(grab)) => {
+                let ((track_x, track_w), (_, thumb_w)) = self.h_track();
+                let room = (track_w - thumb_w).max(1.0);
+                let ratio = ((px - grab) as f32 - track_x) / room;
+                let h_offset = (ratio.clamp(0.0, 1.0) * self.max_h_offset() as f32).round();
+                let old = self.h_offset;
+                self.h_offset = h_offset as u32;
+                self.h_offset != old
+            }
+            None => false,
+        }
+    }
+
+    /// Grabs the thumb under the pointer, or pages toward it from the
+    /// track.
+    fn press(&mut self, px: i32, py: i32) -> bool {
+        if self.over_v_bar(px, py) {
+            let (_, (thumb_y, thumb_h)) = self.v_track();
+            let py_f = py as f32;
+            if py_f < thumb_y {
+                return self.page_by(-1);
+            }
+            if py_f >= thumb_y + thumb_h {
+                return self.page_by(1);
+            }
+            self.drag = Some(Drag::Vertical(py - thumb_y as i32));
+            true
+        } else if self.over_h_bar(px, py) {
+            let (_, (thumb_x, thumb_w)) = self.h_track();
+            let px_f = px as f32;
+            if px_f < thumb_x {
+                return self.scroll_h_by(-(self.width as i32));
+            }
+            if px_f >= thumb_x + thumb_w {
+                return self.scroll_h_by(self.width as i32);
+            }
+            self.drag = Some(Drag::Horizontal(px - thumb_x as i32));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Scrolls by whole pages, up for negative. Returns true if the view
+    /// moved.
+    pub fn page_by(&mut self, pages: isize) -> bool {
+        let rows = (self.page / self.columns).max(1) as isize;
+        self.scroll_by(pages * rows)
+    }
+
+    fn h_step(&self) -> i32 {
+        (BASE_H_STEP * self.scale) as i32
+    }
+}
+
+impl Widget for ScrollArea {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorMove(pos) | WindowEvent::CursorEnter(pos) => {
+                let (px, py) = (pos.x as i32, pos.y as i32);
+                self.pointer = (px, py);
+                if self.drag.is_some() {
+                    return self.drag_to(px, py);
+                }
+                let v_hovered = self.over_v_bar(px, py);
+                let h_hovered = !v_hovered && self.over_h_bar(px, py);
+                let changed = v_hovered != self.v_hovered || h_hovered != self.h_hovered;
+                self.v_hovered = v_hovered;
+                self.h_hovered = h_hovered;
+                changed
+            }
+            WindowEvent::CursorLeave if self.drag.is_none() => {
+                let changed = self.v_hovered || self.h_hovered;
+                self.v_hovered = false;
+                self.h_hovered = false;
+                changed
+            }
+            WindowEvent::ButtonPress(MouseButton::Left, _) => {
+                let (px, py) = self.pointer;
+                self.press(px, py)
+            }
+            WindowEvent::ButtonRelease(..) => self.drag.take().is_some(),
+            WindowEvent::Scroll(direction) => {
+                let step = self.step as isize;
+                match (direction, self.shift) {
+                    (ScrollDirection::Up, false) => self.scroll_by(-step),
+                    (ScrollDirection::Down, false) => self.scroll_by(step),
+                    (ScrollDirection::Left, _) | (ScrollDirection::Up, true) => {
+                        self.scroll_h_by(-self.h_step())
+                    }
+                    (ScrollDirection::Right, _) | (ScrollDirection::Down, true) => {
+                        self.scroll_h_by(self.h_step())
+                    }
+                }
+            }
+            WindowEvent::KeyPress(key) => {
+                match key.keysym {
+                    KEY_LSHIFT | KEY_RSHIFT => {
+                        self.shift = true;
+                        false
+                    }
+                    KEY_PAGE_UP => self.page_by(-1),
+                    KEY_PAGE_DOWN => self.page_by(1),
+                    KEY_LEFT if self.has_h_bar() => self.scroll_h_by(-self.h_step()),
+                    KEY_RIGHT if self.has_h_bar() => self.scroll_h_by(self.h_step()),
+                    _ => false,
+                }
+            }
+            WindowEvent::KeyRelease(key) if matches!(key.keysym, KEY_LSHIFT | KEY_RSHIFT) => {
+                self.shift = false;
+                false
+            }
+            _ => false,
+        }
+    }
+
+    fn draw(&self, canvas: &mut Canvas, colors: &Theme) {
+        let track = colors.input_bg.mix(rgb(0, 0, 0), 0.05);
+        let radius = 3.0 * self.scale;
+        let thumb_color = |hovered: bool| {
+            if hovered {
+                colors.input_border_focused
+            } else {
+                colors.input_border
+            }
+        };
+
+        if self.has_v_bar() {
+            let hovered = self.v_hovered || matches!(self.drag, Some(Drag::Vertical(_)));
+            let w = self.bar_width(hovered);
+            let bar_x = (self.x + self.width as i32) as f32 - w;
+            let ((track_y, track_h), (thumb_y, thumb_h)) = self.v_track();
+            let bar_w = w - 2.0 * self.scale;
+            canvas.fill_rounded_rect(bar_x, track_y, bar_w, track_h, radius, track);
+            canvas.fill_rounded_rect(bar_x, thumb_y, bar_w, thumb_h, radius, thumb_color(hovered));
+        }
+
+        if self.has_h_bar() {
+            let hovered = self.h_hovered || matches!(self.drag, Some(Drag::Horizontal(_)));
+            let h = self.bar_width(hovered);
+            let bar_y = (self.y + self.height as i32) as f32 - h;
+            let ((track_x, track_w), (thumb_x, thumb_w)) = self.h_track();
+            let bar_h = h - 2.0 * self.scale;
+            canvas.fill_rounded_rect(track_x, bar_y, track_w, bar_h, radius, track);
+            canvas.fill_rounded_rect(thumb_x, bar_y, thumb_w, bar_h, radius, thumb_color(hovered));
+        }
+    }
+}
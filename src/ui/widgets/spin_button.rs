@@ -0,0 +1,298 @@
+//! Spin button: a number entry with arrows stepping it up and down.
+
+use super::{Widget, point_in_rect, text_input::TextInput};
+use crate::{
+    backend::{MouseButton, ScrollDirection, WindowEvent},
+    render::{Canvas, Font},
+    ui::Theme,
+};
+
+const KEY_UP: u32 = 0xff52;
+const KEY_DOWN: u32 = 0xff54;
+const KEY_PAGE_UP: u32 = 0xff55;
+const KEY_PAGE_DOWN: u32 = 0xff56;
+const KEY_RETURN: u32 = 0xff0d;
+const KEY_KP_ENTER: u32 = 0xff8d;
+
+/// Room either side of the number
+const BASE_PADDING: u32 = 6;
+/// Width of the column holding the arrows
+const BASE_ARROW_WIDTH: u32 = 18;
+/// Steps taken by Page Up and Page Down
+const PAGE_STEPS: i32 = 10;
+
+/// An entry for a whole number between a minimum and a maximum.
+///
+/// The number can be typed, or stepped with the arrows, the mouse wheel
+/// and the Up and Down keys. Typed text takes effect as soon as it reads
+/// as a number in range; anything else is put back when focus leaves or
+/// Enter is pressed.
+pub(crate) struct SpinButton {
+    entry: TextInput,
+    value: i32,
+    min: i32,
+    max: i32,
+    step: i32,
+    scale: f32,
+    pointer: (i32, i32),
+    /// Arrow under the pointer: up if true, down if false
+    hovered_arrow: Option<bool>,
+    changed: bool,
+}
+
+impl SpinButton {
+    /// Creates a spin button wide enough for any number from `min` to
+    /// `max`, showing `value`.
+    pub fn new(min: i32, max: i32, value: i32, font: &Font, scale: f32) -> Self {
+        let max = max.max(min);
+        let digits = [min, max]
+            .iter()
+            .map(|n| font.render(&n.to_string()).measure().0.ceil() as u32)
+            .max()
+            .unwrap_or(0);
+        let width = digits + ((BASE_PADDING * 2 + BASE_ARROW_WIDTH) as f32 * scale) as u32;
+
+        let value = value.clamp(min, max);
+        Self {
+            entry: TextInput::new(width).with_default_text(&value.to_string()),
+            value,
+            min,
+            max,
+            step: 1,
+            scale,
+            pointer: (0, 0),
+            hovered_arrow: None,
+            changed: false,
+        }
+    }
+
+    /// Sets how far the arrows move the number (default: 1).
+    pub fn with_step(mut self, step: i32) -> Self {
+        self.step = step.max(1);
+        self
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    /// Shows `value`, kept in range, without reporting it as a change.
+    pub fn set_value(&mut self, value: i32) {
+        self.value = value.clamp(self.min, self.max);
+        self.entry.set_text(&self.value.to_string());
+    }
+
+    /// Returns true if the user changed the number since the last call.
+    pub fn was_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    pub fn has_focus(&self) -> bool {
+        self.entry.has_focus()
+    }
+
+    /// Moves the number by `steps` steps, stopping at the ends.
+    fn step_by(&mut self, steps: i32) {
+        let value = self
+            .value
+            .saturating_add(self.step.saturating_mul(steps))
+            .clamp(self.min, self.max);
+        if value != self.value {
+            self.changed = true;
+        }
+        self.set_value(value);
+    }
+
+    /// Takes the typed text as the number if it is one in range.
+    fn read_text(&mut self) {
+        if let Ok(value) = self.entry.text().trim().parse::<i32>()
+            && (self.min..=self.max).contains(&value)
+            && value != self.value
+        {
+            self.value = value;
+            self.changed = true;
+        }
+    }
+
+    /// Puts the number back in place of whatever was typed.
+    fn reset_text(&mut self) {
+        if self.entry.text() != self.value.to_string() {
+            self.entry.set_text(&self.value.to_string());
+        }
+    }
+
+    /// Left edge of the arrow column.
+    fn arrows_x(&self) -> i32 {
+        self.x() + self.width() as i32 - (BASE_ARROW_WIDTH as f32 * self.scale) as i32
+    }
+
+    /// Arrow at a point: up if true, down if false.
+    fn arrow_at(&self, x: i32, y: i32) -> Option<bool> {
+        let arrows_x = self.arrows_x();
+        let arrow_w = (self.x() + self.width() as i32 - arrows_x) as u32;
+        point_in_rect(x, y, arrows_x, self.y(), arrow_w, self.height())
+            .then(|| y < self.y() + self.height() as i32 / 2)
+    }
+
+    /// Draws the spin button to a canvas.
+    pub fn draw_to(&self, canvas: &mut Canvas, colors: &Theme, font: &Font) {
+        self.entry.draw_to(canvas, colors, font);
+
+        let ax = self.arrows_x() as f32;
+        let (y, w, h) = (
+            self.y() as f32,
+            (BASE_ARROW_WIDTH as f32 * self.scale).floor(),
+            self.height() as f32,
+        );
+        let inset = 2.0 * self.scale;
+        canvas.stroke_line(ax, y + inset, ax, y + h - inset, colors.input_border, 1.0);
+
+        let size = 8.0 * self.scale;
+        let line = (1.5 * self.scale).max(1.0);
+        let cx = ax + w / 2.0;
+        for up in [true, false] {
+            let top = if up { y } else { y + h / 2.0 };
+            if self.hovered_arrow == Some(up) {
+                canvas.fill_rounded_rect(
+                    ax + inset,
+                    top + inset,
+                    w - inset * 2.0,
+                    h / 2.0 - inset * 2.0,
+                    colors.control_radius * self.scale / 2.0,
+                    colors.button_hover,
+                );
+            }
+            let at_end = if up {
+                self.value >= self.max
+            } else {
+                self.value <= self.min
+            };
+            let color = if at_end {
+                colors.input_placeholder
+            } else {
+                colors.text
+            };
+            // Chevron pointing up or down
+            let cy = top + h / 4.0;
+            let tip = if up { -size / 4.0 } else { size / 4.0 };
+            canvas.stroke_line(cx - size / 2.0, cy - tip, cx, cy + tip, color, line);
+            canvas.stroke_line(cx, cy + tip, cx + size / 2.0, cy - tip, color, line);
+        }
+    }
+}
+
+impl Widget for SpinButton {
+    fn width(&self) -> u32 {
+        self.entry.width()
+    }
+
+    fn height(&self) -> u32 {
+        self.entry.height()
+    }
+
+    fn x(&self) -> i32 {
+        self.entry.x()
+    }
+
+    fn y(&self) -> i32 {
+        self.entry.y()
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.entry.set_position(x, y);
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorMove(pos) | WindowEvent::CursorEnter(pos) => {
+                self.pointer = (pos.x as i32, pos.y as i32);
+                let hovered = self.arrow_at(self.pointer.0, self.pointer.1);
+                let changed = hovered != self.hovered_arrow;
+                self.hovered_arrow = hovered;
+                changed
+            }
+            WindowEvent::CursorLeave => self.hovered_arrow.take().is_some(),
+            WindowEvent::ButtonPress(MouseButton::Left, _) => {
+                match self.arrow_at(self.pointer.0, self.pointer.1) {
+                    Some(up) => {
+                        self.step_by(if up { 1 } else { -1 });
+                        true
+                    }
+                    None => false,
+                }
+            }
+            WindowEvent::Scroll(direction)
+                if point_in_rect(
+                    self.pointer.0,
+                    self.pointer.1,
+                    self.x(),
+                    self.y(),
+                    self.width(),
+                    self.height(),
+                ) =>
+            {
+                match direction {
+                    ScrollDirection::Up => self.step_by(1),
+                    ScrollDirection::Down => self.step_by(-1),
+                    _ => return false,
+                }
+                true
+            }
+            WindowEvent::KeyPress(key) if self.has_focus() => {
+                match key.keysym {
+                    KEY_UP => {
+                        self.step_by(1);
+                        true
+                    }
+                    KEY_DOWN => {
+                        self.step_by(-1);
+                        true
+                    }
+                    KEY_PAGE_UP => {
+                        self.step_by(PAGE_STEPS);
+                        true
+                    }
+                    KEY_PAGE_DOWN => {
+                        self.step_by(-PAGE_STEPS);
+                        true
+                    }
+                    KEY_RETURN | KEY_KP_ENTER => {
+                        self.reset_text();
+                        true
+                    }
+                    _ => {
+                        let edited = self.entry.process_event(event);
+                        self.read_text();
+                        edited
+                    }
+                }
+            }
+            // Only what can be part of a number is typed
+            WindowEvent::TextInput(c) if !(c.is_ascii_digit() || *c == '-' && self.min < 0) => {
+                false
+            }
+            _ => {
+                let edited = self.entry.process_event(event);
+                if edited {
+                    self.read_text();
+                }
+                edited
+            }
+        }
+    }
+
+    fn draw(&self, _canvas: &mut Canvas, _colors: &Theme) {
+        // Use draw_to instead for font access
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focus(&mut self, focused: bool) {
+        self.entry.set_focus(focused);
+        if !focused {
+            self.reset_text();
+        }
+    }
+}
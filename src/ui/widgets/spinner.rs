@@ -0,0 +1,64 @@
+//! Spinner widget, shown while waiting on something of unknown length.
+
+use std::{f32::consts::TAU, time::Instant};
+
+use crate::{
+    render::Canvas,
+    ui::{Theme, animation},
+};
+
+/// Seconds for the arc to go once round
+const TURN_PERIOD: f32 = 1.2;
+/// Seconds for the arc to grow and shrink back
+const STRETCH_PERIOD: f32 = 1.6;
+
+/// An arc chasing itself round a faint ring, for as long as it is drawn.
+pub(crate) struct Spinner {
+    x: i32,
+    y: i32,
+    size: u32,
+    scale: f32,
+    /// When the spinner started turning
+    start: Instant,
+}
+
+impl Spinner {
+    /// Creates a spinner `size` pixels across.
+    pub fn new(size: u32, scale: f32) -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            size,
+            scale,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Draws the spinner to a canvas, asking for the next frame.
+    pub fn draw(&self, canvas: &mut Canvas, colors: &Theme) {
+        let elapsed = self.start.elapsed().as_secs_f32();
+        animation::request_frame();
+
+        let width = (self.size as f32 / 8.0).max(2.0 * self.scale);
+        let radius = (self.size as f32 - width) / 2.0;
+        let cx = self.x as f32 + self.size as f32 / 2.0;
+        let cy = self.y as f32 + self.size as f32 / 2.0;
+
+        canvas.stroke_circle(cx, cy, radius, colors.progress_bg, width);
+
+        // The arc turns steadily while its length swells and eases off
+        let stretch = (1.0 - (elapsed / STRETCH_PERIOD * TAU).cos()) / 2.0;
+        let sweep = TAU * (0.1 + 0.6 * stretch);
+        let start = (elapsed / TURN_PERIOD).fract() * TAU;
+        canvas.fill_arc(cx, cy, radius, width, start, sweep, colors.progress_fill);
+    }
+}
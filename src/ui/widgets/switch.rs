@@ -0,0 +1,201 @@
+//! Toggle switch widget.
+
+use super::{Widget, point_in_rect};
+use crate::{
+    backend::{MouseButton, WindowEvent},
+    render::{Canvas, Font},
+    ui::{Theme, animation::Transition},
+};
+
+const BASE_TRACK_WIDTH: u32 = 36;
+const BASE_TRACK_HEIGHT: u32 = 20;
+const BASE_LABEL_GAP: u32 = 12;
+/// Room between the knob and the edge of the track
+const BASE_KNOB_INSET: f32 = 3.0;
+
+/// A label with an on/off pill after it, toggled by clicking either or by
+/// Space while focused. The knob slides across when it changes.
+pub(crate) struct Switch {
+    label: String,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    track_width: u32,
+    track_height: u32,
+    scale: f32,
+    on: bool,
+    /// Eases the knob between off (0) and on (1)
+    knob: Transition,
+    hovered: bool,
+    /// Eases the track outline between its normal and hover colors
+    hover: Transition,
+    focused: bool,
+}
+
+impl Switch {
+    pub fn new(label: &str, font: &Font, scale: f32) -> Self {
+        let track_width = (BASE_TRACK_WIDTH as f32 * scale) as u32;
+        let track_height = (BASE_TRACK_HEIGHT as f32 * scale) as u32;
+        let (width, height) = if label.is_empty() {
+            (track_width, track_height)
+        } else {
+            let (text_w, text_h) = font.render(label).measure();
+            (
+                text_w.ceil() as u32 + (BASE_LABEL_GAP as f32 * scale) as u32 + track_width,
+                track_height.max(text_h.ceil() as u32),
+            )
+        };
+
+        Self {
+            label: label.to_string(),
+            x: 0,
+            y: 0,
+            width,
+            height,
+            track_width,
+            track_height,
+            scale,
+            on: false,
+            knob: Transition::new(0.0),
+            hovered: false,
+            hover: Transition::new(0.0),
+            focused: false,
+        }
+    }
+
+    /// Starts the switch on, without sliding the knob over.
+    pub fn with_on(mut self, on: bool) -> Self {
+        self.on = on;
+        self.knob = Transition::new(if on { 1.0 } else { 0.0 });
+        self
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    /// Widens the switch, keeping the pill at its right end so that the
+    /// pills of switches stacked in a column line up.
+    pub fn set_width(&mut self, width: u32) {
+        self.width = width.max(self.track_width);
+    }
+
+    fn toggle(&mut self) {
+        self.on = !self.on;
+        self.knob.set(if self.on { 1.0 } else { 0.0 });
+    }
+
+    /// Draws the switch to a canvas.
+    pub fn draw_to(&self, canvas: &mut Canvas, colors: &Theme, font: &Font) {
+        if !self.label.is_empty() {
+            let text = font.render(&self.label).with_color(colors.text).finish();
+            let text_y = self.y + (self.height as i32 - text.height() as i32) / 2;
+            canvas.draw_canvas(&text, self.x, text_y);
+        }
+
+        let (w, h) = (self.track_width as f32, self.track_height as f32);
+        let x = (self.x + self.width as i32 - self.track_width as i32) as f32;
+        let y = (self.y + (self.height as i32 - self.track_height as i32) / 2) as f32;
+        let radius = h / 2.0;
+        let knob = self.knob.value();
+
+        canvas.fill_rounded_rect(
+            x,
+            y,
+            w,
+            h,
+            radius,
+            colors.progress_bg.mix(colors.input_border_focused, knob),
+        );
+        canvas.stroke_rounded_rect(
+            x,
+            y,
+            w,
+            h,
+            radius,
+            colors
+                .input_border
+                .mix(colors.input_border_focused, self.hover.value().max(knob)),
+            colors.border_width,
+        );
+
+        let inset = BASE_KNOB_INSET * self.scale;
+        let knob_r = radius - inset;
+        let knob_x = x + radius + knob * (w - h);
+        canvas.fill_circle(knob_x, y + radius, knob_r, colors.input_bg);
+
+        if self.focused {
+            canvas.stroke_focus_ring(x, y, w, h, radius, 2.0, colors.focus_ring);
+        }
+    }
+}
+
+impl Widget for Switch {
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::CursorMove(pos) | WindowEvent::CursorEnter(pos) => {
+                let hovered = point_in_rect(
+                    pos.x as i32,
+                    pos.y as i32,
+                    self.x,
+                    self.y,
+                    self.width,
+                    self.height,
+                );
+                let changed = hovered != self.hovered;
+                self.hovered = hovered;
+                self.hover.set(if hovered { 1.0 } else { 0.0 });
+                changed
+            }
+            WindowEvent::CursorLeave => {
+                self.hovered = false;
+                self.hover.set(0.0);
+                true
+            }
+            WindowEvent::ButtonPress(MouseButton::Left, _) if self.hovered => {
+                self.toggle();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn draw(&self, _canvas: &mut Canvas, _colors: &Theme) {
+        // Use draw_to instead for font access
+    }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focus(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    fn activate(&mut self) -> bool {
+        self.toggle();
+        true
+    }
+}
@@ -1,14 +1,13 @@
 //! Text input widget for single-line text entry.
 
-use super::Widget;
+use super::{Widget, point_in_rect};
 use crate::{
     backend::{Modifiers, WindowEvent},
     render::{Canvas, Font, Rgba},
-    ui::Colors,
+    ui::Theme,
 };
 
 const INPUT_HEIGHT: u32 = 32;
-const INPUT_RADIUS: f32 = 5.0;
 const INPUT_PADDING: i32 = 8;
 
 // XKB keysym constants
@@ -72,6 +71,12 @@ impl TextInput {
         &self.text
     }
 
+    /// Replaces the text content and moves the cursor to the end.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.cursor_pos = self.char_count();
+    }
+
     /// Returns true if Enter was pressed.
     pub fn was_submitted(&mut self) -> bool {
         let submitted = self.submitted;
@@ -109,6 +114,19 @@ impl TextInput {
         self.cursor_pos += 1;
     }
 
+    /// Inserts text at the cursor position, joining lines with spaces
+    /// since the input holds a single line.
+    pub fn insert_text(&mut self, text: &str) {
+        let text = text.trim_end_matches(['\n', '\r']);
+        for c in text.chars() {
+            match c {
+                '\n' => self.insert_char(' '),
+                c if c.is_control() => {}
+                c => self.insert_char(c),
+            }
+        }
+    }
+
     /// Deletes the character before the cursor (backspace).
     fn delete_before(&mut self) {
         if self.cursor_pos > 0 {
@@ -191,7 +209,7 @@ impl TextInput {
     }
 
     /// Draws the text input to a canvas.
-    pub fn draw_to(&self, canvas: &mut Canvas, colors: &Colors, font: &Font) {
+    pub fn draw_to(&self, canvas: &mut Canvas, colors: &Theme, font: &Font) {
         // Draw background
         let bg_color = if self.focused {
             colors.input_bg_focused
@@ -204,9 +222,19 @@ impl TextInput {
             self.y as f32,
             self.width as f32,
             self.height as f32,
-            INPUT_RADIUS,
+            colors.control_radius,
             bg_color,
         );
+        canvas.fill_inner_shadow(
+            self.x as f32,
+            self.y as f32,
+            self.width as f32,
+            self.height as f32,
+            colors.control_radius,
+            3.0,
+            1.0,
+            colors.input_inset,
+        );
 
         // Draw border
         let border_color = if self.focused {
@@ -220,10 +248,21 @@ impl TextInput {
             self.y as f32,
             self.width as f32,
             self.height as f32,
-            INPUT_RADIUS,
+            colors.control_radius,
             border_color,
-            1.0,
+            colors.border_width,
         );
+        if self.focused {
+            canvas.stroke_focus_ring(
+                self.x as f32,
+                self.y as f32,
+                self.width as f32,
+                self.height as f32,
+                colors.control_radius,
+                2.0,
+                colors.focus_ring,
+            );
+        }
 
         // Draw text or placeholder
         let display = self.display_text();
@@ -285,8 +324,9 @@ impl TextInput {
         }
     }
 
-    pub fn set_focus(&mut self, focused: bool) {
-        self.focused = focused;
+    /// Set the input width.
+    pub fn set_width(&mut self, width: u32) {
+        self.width = width;
     }
 
     pub fn has_focus(&self) -> bool {
@@ -327,6 +367,21 @@ impl Widget for TextInput {
                 self.insert_char(*c);
                 true
             }
+            // Middle-click paste goes to the input under the pointer; dialogs
+            // with several inputs move focus there
+            WindowEvent::PrimaryPaste(pos, text)
+                if point_in_rect(
+                    pos.x as i32,
+                    pos.y as i32,
+                    self.x,
+                    self.y,
+                    self.width,
+                    self.height,
+                ) =>
+            {
+                self.insert_text(text);
+                true
+            }
             WindowEvent::KeyPress(key_event) if self.focused => {
                 self.handle_key(key_event.keysym, key_event.modifiers)
             }
@@ -334,7 +389,15 @@ impl Widget for TextInput {
         }
     }
 
-    fn draw(&self, _canvas: &mut Canvas, _colors: &Colors) {
+    fn draw(&self, _canvas: &mut Canvas, _colors: &Theme) {
         // Use draw_to instead for font access
     }
+
+    fn is_focusable(&self) -> bool {
+        true
+    }
+
+    fn set_focus(&mut self, focused: bool) {
+        self.focused = focused;
+    }
 }
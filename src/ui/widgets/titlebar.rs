@@ -0,0 +1,541 @@
+//! Title bar for compositors that leave decorations to the client.
+//!
+//! Dialogs get it through [`DecoratedWindow`], which draws the bar above
+//! their contents and shifts pointer events so their layouts are unaware
+//! of it. A blurred shadow is drawn in a margin around the whole. The
+//! same window fades dialogs in, asks for frames while anything drawn is
+//! animating, turns middle clicks into primary selection pastes, and
+//! passes on what the handle of a spawned dialog asks of it.
+
+use std::{collections::VecDeque, sync::Arc, time::Instant};
+
+use super::point_in_rect;
+use crate::{
+    backend::{AnyWindow, CursorPos, CursorShape, MouseButton, Window, WindowEvent, WindowHandle},
+    error::Error,
+    render::{Canvas, Damage, Font},
+    ui::{
+        Theme,
+        animation::{self, Transition},
+        handle::{self, Remote},
+    },
+};
+
+const BASE_TITLEBAR_HEIGHT: u32 = 32;
+const BASE_CLOSE_SIZE: u32 = 24;
+const BASE_CLOSE_MARGIN: u32 = 6;
+/// Room around the frame for its shadow, which spreads by the blur and
+/// drops by the offset
+const BASE_SHADOW_MARGIN: u32 = 16;
+const BASE_SHADOW_BLUR: f32 = 12.0;
+const BASE_SHADOW_OFFSET: f32 = 3.0;
+
+/// A title bar with the window title and a close button.
+pub(crate) struct TitleBar {
+    title: String,
+    width: u32,
+    height: u32,
+    scale: f32,
+    close_hovered: bool,
+    close_pressed: bool,
+}
+
+impl TitleBar {
+    pub fn new(scale: f32) -> Self {
+        Self {
+            title: String::new(),
+            width: 0,
+            height: (BASE_TITLEBAR_HEIGHT as f32 * scale) as u32,
+            scale,
+            close_hovered: false,
+            close_pressed: false,
+        }
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.title = title.trim_end_matches('\0').to_string();
+    }
+
+    pub fn set_width(&mut self, width: u32) {
+        self.width = width;
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the close button's bounds.
+    fn close_rect(&self) -> (i32, i32, u32, u32) {
+        let size = (BASE_CLOSE_SIZE as f32 * self.scale) as u32;
+        let margin = (BASE_CLOSE_MARGIN as f32 * self.scale) as i32;
+        let x = self.width as i32 - margin - size as i32;
+        let y = (self.height as i32 - size as i32) / 2;
+        (x, y, size, size)
+    }
+
+    /// Updates the close button's hover state. Returns true if it changed.
+    fn hover(&mut self, x: i32, y: i32) -> bool {
+        let (cx, cy, cw, ch) = self.close_rect();
+        let hovered = point_in_rect(x, y, cx, cy, cw, ch);
+        let changed = hovered != self.close_hovered;
+        self.close_hovered = hovered;
+        changed
+    }
+
+    /// Draws the title bar across the top of a canvas.
+    pub fn draw_to(&self, canvas: &mut Canvas, colors: &Theme, font: &Font) {
+        let title = font.render(&self.title).with_color(colors.text).finish();
+        let title_x = (self.width as i32 - title.width() as i32) / 2;
+        let title_y = (self.height as i32 - title.height() as i32) / 2;
+        canvas.draw_canvas(&title, title_x, title_y);
+
+        let (x, y, size, _) = self.close_rect();
+        let (x, y, size) = (x as f32, y as f32, size as f32);
+        if self.close_hovered {
+            let bg = if self.close_pressed {
+                colors.button_pressed
+            } else {
+                colors.button_hover
+            };
+            canvas.fill_circle(x + size / 2.0, y + size / 2.0, size / 2.0, bg);
+        }
+
+        // The cross
+        let inset = size * 0.35;
+        let width = 1.5 * self.scale;
+        canvas.stroke_line(
+            x + inset,
+            y + inset,
+            x + size - inset,
+            y + size - inset,
+            colors.text,
+            width,
+        );
+        canvas.stroke_line(
+            x + size - inset,
+            y + inset,
+            x + inset,
+            y + size - inset,
+            colors.text,
+            width,
+        );
+    }
+}
+
+/// Title bar state kept by a decorated window.
+struct Decorations {
+    titlebar: TitleBar,
+    font: Font,
+    colors: Theme,
+    /// Shadow margin in physical pixels
+    margin: u32,
+    /// Shadow drawn for the last frame size
+    shadow: Option<Canvas>,
+}
+
+/// A window that draws its own title bar when nothing else does.
+///
+/// The bar sits above the dialog's canvas and the shadow margin around
+/// both; the window is made larger to fit them, and events are reported
+/// relative to the dialog's canvas.
+pub(crate) struct DecoratedWindow {
+    window: AnyWindow,
+    decorations: Option<Decorations>,
+    /// Last pointer position, relative to the frame.
+    pointer: (i32, i32),
+    pending_events: VecDeque<WindowEvent>,
+    /// Fade-in, started when the first contents are shown
+    fade: Option<Transition>,
+    /// Opacity the window was last given
+    opacity: f32,
+    /// Contents last shown, to find what changed in the next
+    shown: Option<Canvas>,
+    /// Handle of the dialog, if it was spawned
+    remote: Option<Arc<Remote>>,
+}
+
+impl DecoratedWindow {
+    pub fn new(
+        mut window: AnyWindow,
+        width: u16,
+        height: u16,
+        colors: &Theme,
+    ) -> Result<Self, Error> {
+        let decorations = if window.needs_decorations() {
+            let margin = BASE_SHADOW_MARGIN as u16;
+            window.set_frame_margin(margin)?;
+            window.set_size(
+                width + 2 * margin,
+                height + BASE_TITLEBAR_HEIGHT as u16 + 2 * margin,
+            )?;
+            let scale = window.scale_factor();
+            Some(Decorations {
+                titlebar: TitleBar::new(scale),
+                font: colors.font(scale),
+                colors: *colors,
+                margin: (BASE_SHADOW_MARGIN as f32 * scale) as u32,
+                shadow: None,
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            window,
+            decorations,
+            pointer: (0, 0),
+            pending_events: VecDeque::new(),
+            fade: None,
+            opacity: 1.0,
+            shown: None,
+            remote: handle::remote(),
+        })
+    }
+
+    /// Returns the title bar height, or 0 without one.
+    fn bar_height(&self) -> i32 {
+        self.decorations
+            .as_ref()
+            .map_or(0, |decorations| decorations.titlebar.height() as i32)
+    }
+
+    /// Shows `contents`, sending the window only what changed since the
+    /// last ones.
+    fn present(&mut self, contents: &Canvas) -> Result<(), Error> {
+        let damage = match &self.shown {
+            Some(shown) => contents.damage_since(shown),
+            None => vec![Damage::whole(contents)],
+        };
+        self.window.set_contents_damaged(contents, &damage)?;
+        match &mut self.shown {
+            Some(shown) => shown.clone_from(contents),
+            None => self.shown = Some(contents.clone()),
+        }
+        Ok(())
+    }
+
+    /// Returns the logical size the title bar and shadow add to the
+    /// dialog's.
+    fn extra_size(&self) -> (u16, u16) {
+        if self.decorations.is_none() {
+            return (0, 0);
+        }
+        let margin = 2 * BASE_SHADOW_MARGIN as u16;
+        (margin, BASE_TITLEBAR_HEIGHT as u16 + margin)
+    }
+
+    /// Returns the shadow margin, or 0 without decorations.
+    fn margin(&self) -> i32 {
+        self.decorations
+            .as_ref()
+            .map_or(0, |decorations| decorations.margin as i32)
+    }
+
+    /// Moves the event into the dialog's coordinates and turns middle
+    /// clicks into pastes. Returns `None` for events the title bar consumed.
+    fn translate(&mut self, event: WindowEvent) -> Result<Option<WindowEvent>, Error> {
+        if let WindowEvent::CursorEnter(pos) | WindowEvent::CursorMove(pos) = &event {
+            let margin = self.margin();
+            self.pointer = (pos.x as i32 - margin, pos.y as i32 - margin);
+        }
+        let Some(event) = self.translate_titlebar(event)? else {
+            return Ok(None);
+        };
+
+        // Dialogs have no use for the middle button, so when there is
+        // something to paste the click becomes the paste
+        if let WindowEvent::ButtonPress(MouseButton::Middle, _) = event
+            && let Some(text) = self.window.primary_selection()?
+        {
+            let pos = CursorPos {
+                x: self.pointer.0 as i16,
+                y: (self.pointer.1 - self.bar_height()) as i16,
+            };
+            return Ok(Some(WindowEvent::PrimaryPaste(pos, text)));
+        }
+        Ok(Some(event))
+    }
+
+    /// Handles title bar interaction and moves the event into the dialog's
+    /// coordinates. Returns `None` for events the title bar consumed.
+    fn translate_titlebar(&mut self, event: WindowEvent) -> Result<Option<WindowEvent>, Error> {
+        let Some(decorations) = &mut self.decorations else {
+            return Ok(Some(event));
+        };
+        let bar = &mut decorations.titlebar;
+        let bar_h = bar.height() as i32;
+        let margin = decorations.margin;
+
+        Ok(Some(match event {
+            WindowEvent::CursorEnter(mut pos) | WindowEvent::CursorMove(mut pos) => {
+                pos.x -= margin as i16;
+                pos.y -= margin as i16;
+                if bar.hover(pos.x as i32, pos.y as i32) {
+                    self.pending_events.push_back(WindowEvent::RedrawRequested);
+                }
+                pos.y -= bar_h as i16;
+                match event {
+                    WindowEvent::CursorEnter(_) => WindowEvent::CursorEnter(pos),
+                    _ => WindowEvent::CursorMove(pos),
+                }
+            }
+            WindowEvent::CursorLeave => {
+                if bar.close_hovered || bar.close_pressed {
+                    bar.close_hovered = false;
+                    bar.close_pressed = false;
+                    self.pending_events.push_back(WindowEvent::RedrawRequested);
+                }
+                event
+            }
+            WindowEvent::ButtonPress(button, _) if self.pointer.1 < bar_h => {
+                if button == MouseButton::Left {
+                    if bar.close_hovered {
+                        bar.close_pressed = true;
+                        return Ok(Some(WindowEvent::RedrawRequested));
+                    }
+                    self.window.start_drag()?;
+                }
+                return Ok(None);
+            }
+            WindowEvent::ButtonRelease(MouseButton::Left, _) if bar.close_pressed => {
+                bar.close_pressed = false;
+                if bar.close_hovered {
+                    return Ok(Some(WindowEvent::CloseRequested));
+                }
+                self.pending_events.push_back(WindowEvent::RedrawRequested);
+                event
+            }
+            WindowEvent::Resized(width, height) => {
+                WindowEvent::Resized(
+                    width.saturating_sub(2 * margin),
+                    height.saturating_sub(bar_h as u32 + 2 * margin),
+                )
+            }
+            event => event,
+        }))
+    }
+}
+
+impl Window for DecoratedWindow {
+    fn set_title(&mut self, title: &str) -> Result<(), Error> {
+        if let Some(decorations) = &mut self.decorations {
+            decorations.titlebar.set_title(title);
+        }
+        self.window.set_title(title)
+    }
+
+    fn set_contents(&mut self, canvas: &Canvas) -> Result<(), Error> {
+        let opacity = self
+            .fade
+            .get_or_insert_with(|| {
+                let mut fade = Transition::new(0.0);
+                fade.set(1.0);
+                fade
+            })
+            .value();
+        if opacity != self.opacity {
+            self.window.set_opacity(opacity)?;
+            self.opacity = opacity;
+        }
+        if animation::take_frame_request() {
+            self.window.request_frame()?;
+        }
+
+        let Some(decorations) = &mut self.decorations else {
+            return self.present(canvas);
+        };
+        let colors = &decorations.colors;
+        let margin = decorations.margin;
+        let bar = &mut decorations.titlebar;
+        bar.set_width(canvas.width());
+        let scale = bar.scale;
+
+        let radius = colors.window_radius * scale;
+        let mut frame = Canvas::new(canvas.width(), canvas.height() + bar.height());
+        frame.fill_dialog_bg(
+            frame.width() as f32,
+            frame.height() as f32,
+            colors.window_bg,
+            colors.window_border,
+            radius,
+            colors.border_width,
+        );
+        bar.draw_to(&mut frame, colors, &decorations.font);
+        // Drawn over the frame's background, a translucent one would show
+        // through twice
+        frame.replace_rounded(canvas, 0, bar.height() as i32, radius);
+
+        // Blurring is slow, so the shadow is only redrawn on resize
+        let (width, height) = (frame.width() + 2 * margin, frame.height() + 2 * margin);
+        let shadow = decorations
+            .shadow
+            .take()
+            .filter(|shadow| (shadow.width(), shadow.height()) == (width, height))
+            .unwrap_or_else(|| {
+                let mut shadow = Canvas::new(width, height);
+                shadow.fill_shadow(
+                    margin as f32,
+                    margin as f32 + BASE_SHADOW_OFFSET * scale,
+                    frame.width() as f32,
+                    frame.height() as f32,
+                    radius,
+                    BASE_SHADOW_BLUR * scale,
+                    colors.window_shadow,
+                );
+                shadow
+            });
+
+        let mut contents = Canvas::new(width, height);
+        contents.draw_canvas(&shadow, 0, 0);
+        // The shadow is only around the frame, not seen through it
+        contents.replace_rounded(&frame, margin as i32, margin as i32, radius);
+        decorations.shadow = Some(shadow);
+        self.present(&contents)
+    }
+
+    fn set_contents_damaged(&mut self, canvas: &Canvas, _damage: &[Damage]) -> Result<(), Error> {
+        // What changed is worked out after adding the decorations
+        self.set_contents(canvas)
+    }
+
+    fn show(&mut self) -> Result<(), Error> {
+        self.window.show()
+    }
+
+    fn wait_for_event(&mut self) -> Result<WindowEvent, Error> {
+        // A spawned dialog waits in turns, checking its handle in between
+        if self.remote.is_some() {
+            loop {
+                if let Some(event) = self.poll_for_event()? {
+                    return Ok(event);
+                }
+                std::thread::sleep(handle::POLL_INTERVAL);
+            }
+        }
+        loop {
+            if let Some(event) = self.pending_events.pop_front() {
+                return Ok(event);
+            }
+            let event = self.window.wait_for_event()?;
+            if let Some(event) = self.translate(event)? {
+                return Ok(event);
+            }
+        }
+    }
+
+    fn poll_for_event(&mut self) -> Result<Option<WindowEvent>, Error> {
+        loop {
+            if let Some(event) = self.pending_events.pop_front() {
+                return Ok(Some(event));
+            }
+            if let Some(event) = self.remote.as_ref().and_then(|remote| remote.take_event()) {
+                return Ok(Some(event));
+            }
+            let Some(event) = self.window.poll_for_event()? else {
+                return Ok(None);
+            };
+            if let Some(event) = self.translate(event)? {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    fn wait_for_event_or(
+        &mut self,
+        fds: &[libc::pollfd],
+        deadline: Option<Instant>,
+    ) -> Result<Option<WindowEvent>, Error> {
+        // A spawned dialog waits in turns, as in wait_for_event
+        if self.remote.is_some() {
+            let mut fds = fds.to_vec();
+            loop {
+                if let Some(event) = self.poll_for_event()? {
+                    return Ok(Some(event));
+                }
+                let turn = Instant::now() + handle::POLL_INTERVAL;
+                let wake = deadline.map_or(turn, |deadline| deadline.min(turn));
+                if crate::backend::poll(&mut fds, Some(wake))
+                    || deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                {
+                    return Ok(None);
+                }
+            }
+        }
+        loop {
+            if let Some(event) = self.pending_events.pop_front() {
+                return Ok(Some(event));
+            }
+            let Some(event) = self.window.wait_for_event_or(fds, deadline)? else {
+                return Ok(None);
+            };
+            if let Some(event) = self.translate(event)? {
+                return Ok(Some(event));
+            }
+        }
+    }
+
+    fn start_drag(&mut self) -> Result<(), Error> {
+        self.window.start_drag()
+    }
+
+    fn scale_factor(&self) -> f32 {
+        self.window.scale_factor()
+    }
+
+    fn set_cursor(&mut self, shape: CursorShape) -> Result<(), Error> {
+        self.window.set_cursor(shape)
+    }
+
+    fn set_resizable(&mut self, min_width: u16, min_height: u16) -> Result<(), Error> {
+        let (extra_w, extra_h) = self.extra_size();
+        self.window
+            .set_resizable(min_width + extra_w, min_height + extra_h)
+    }
+
+    fn set_size(&mut self, width: u16, height: u16) -> Result<(), Error> {
+        let (extra_w, extra_h) = self.extra_size();
+        self.window.set_size(width + extra_w, height + extra_h)
+    }
+
+    fn needs_decorations(&self) -> bool {
+        // Drawn here if needed
+        false
+    }
+
+    fn set_position(&mut self, x: i32, y: i32) -> Result<(), Error> {
+        self.window.set_position(x, y)
+    }
+
+    fn monitor_area(&self) -> Option<(i32, i32, u32, u32)> {
+        self.window.monitor_area()
+    }
+
+    fn pointer_position(&self) -> Option<(i32, i32)> {
+        self.window.pointer_position()
+    }
+
+    fn primary_selection(&mut self) -> Result<Option<String>, Error> {
+        self.window.primary_selection()
+    }
+
+    fn set_parent(&mut self, parent: &WindowHandle) -> Result<(), Error> {
+        self.window.set_parent(parent)
+    }
+
+    fn set_icon(&mut self, icon: &Canvas) -> Result<(), Error> {
+        self.window.set_icon(icon)
+    }
+
+    fn set_frame_margin(&mut self, margin: u16) -> Result<(), Error> {
+        self.window.set_frame_margin(margin)
+    }
+
+    fn request_frame(&mut self) -> Result<(), Error> {
+        self.window.request_frame()
+    }
+
+    fn set_opacity(&mut self, opacity: f32) -> Result<(), Error> {
+        self.opacity = opacity;
+        self.window.set_opacity(opacity)
+    }
+}
@@ -0,0 +1,144 @@
+//! Tooltips: a short note shown near the pointer once it rests on
+//! something.
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    backend::WindowEvent,
+    render::{Canvas, Font},
+    ui::{Theme, animation},
+};
+
+/// How long the pointer must rest on something before its tooltip shows.
+const DELAY: Duration = Duration::from_millis(500);
+
+/// Gap between the pointer and the top of the bubble
+const BASE_POINTER_GAP: f32 = 20.0;
+const BASE_PADDING_X: f32 = 8.0;
+const BASE_PADDING_Y: f32 = 4.0;
+
+/// The tooltip of a dialog. The dialog tells it what the pointer is over
+/// after each batch of events, from widgets' [`Widget::tooltip`] or from
+/// areas it draws itself, and draws it last, on top of everything.
+///
+/// [`Widget::tooltip`]: super::Widget::tooltip
+pub(crate) struct Tooltip {
+    text: Option<String>,
+    /// Pointer position when the text was last set, which the bubble hangs
+    /// below
+    x: i32,
+    y: i32,
+    /// When the pointer came to rest on the text
+    since: Instant,
+    /// Clicks, keys and scrolling hide the tooltip until the pointer moves
+    /// on to something else
+    dismissed: bool,
+    scale: f32,
+}
+
+impl Tooltip {
+    pub fn new(scale: f32) -> Self {
+        Self {
+            text: None,
+            x: 0,
+            y: 0,
+            since: Instant::now(),
+            dismissed: false,
+            scale,
+        }
+    }
+
+    /// Points the tooltip at `text` for the pointer at `(x, y)`, or hides
+    /// it for `None`. The delay starts over whenever the text changes.
+    /// Returns true if a redraw is needed.
+    pub fn set(&mut self, text: Option<&str>, x: i32, y: i32) -> bool {
+        if text == self.text.as_deref() {
+            // Follow the pointer until the bubble shows, then stay put
+            if !self.is_showing() {
+                self.x = x;
+                self.y = y;
+            }
+            return false;
+        }
+        let was_showing = self.is_showing();
+        self.text = text.map(str::to_string);
+        self.x = x;
+        self.y = y;
+        self.since = Instant::now();
+        self.dismissed = false;
+        // A new text needs drawing to start waiting out the delay
+        was_showing || self.text.is_some()
+    }
+
+    /// Hides the tooltip on input that shows the user has moved on.
+    /// Returns true if a redraw is needed.
+    pub fn process_event(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::ButtonPress(..)
+            | WindowEvent::KeyPress(_)
+            | WindowEvent::TextInput(_)
+            | WindowEvent::Scroll(_) => {
+                let was_showing = self.is_showing();
+                self.dismissed = self.text.is_some();
+                was_showing
+            }
+            WindowEvent::CursorLeave => self.set(None, self.x, self.y),
+            _ => false,
+        }
+    }
+
+    fn is_showing(&self) -> bool {
+        self.text.is_some() && !self.dismissed && self.since.elapsed() >= DELAY
+    }
+
+    /// Draws the bubble if the pointer has rested long enough, asking for
+    /// frames until then.
+    pub fn draw_to(&self, canvas: &mut Canvas, colors: &Theme, font: &Font) {
+        let Some(text) = &self.text else {
+            return;
+        };
+        if self.dismissed {
+            return;
+        }
+        if !self.is_showing() {
+            animation::request_frame();
+            return;
+        }
+
+        // Dark on light themes and light on dark ones, to stand apart from
+        // the window
+        let bg = colors.text.with_alpha(240);
+        let label = font
+            .render(text)
+            .with_color(colors.window_bg.with_alpha(255))
+            .finish();
+        let pad_x = (BASE_PADDING_X * self.scale) as i32;
+        let pad_y = (BASE_PADDING_Y * self.scale) as i32;
+        let w = label.width() as i32 + pad_x * 2;
+        let h = label.height() as i32 + pad_y * 2;
+
+        // Below the pointer, or above it near the bottom of the window
+        let canvas_w = canvas.width() as i32;
+        let canvas_h = canvas.height() as i32;
+        let x = self.x.min(canvas_w - w).max(0);
+        let below = self.y + (BASE_POINTER_GAP * self.scale) as i32;
+        let y = if below + h <= canvas_h {
+            below
+        } else {
+            (self.y - h - (4.0 * self.scale) as i32).max(0)
+        };
+
+        let radius = colors.control_radius * self.scale / 2.0;
+        canvas.fill_shadow(
+            x as f32,
+            y as f32 + self.scale,
+            w as f32,
+            h as f32,
+            radius,
+            4.0 * self.scale,
+            colors.window_shadow,
+        );
+        canvas.fill_rounded_rect(x as f32, y as f32, w as f32, h as f32, radius, bg);
+        canvas.draw_canvas(&label, x + pad_x, y + pad_y);
+    }
+}
@@ -0,0 +1,176 @@
+//! Dialogs driven through the headless backend with scripted input. Run
+//! with `cargo test --features test-backend`.
+
+use zenity_rs::{
+    DialogResult, EntryResult, Headless, ListBuilder, ListMode, ListResult, ScaleResult, TestEvent,
+};
+
+fn fruit() -> ListBuilder {
+    ListBuilder::new()
+        .title("Pick")
+        .text("Fruit:")
+        .column("Name")
+        .row(vec!["Apple".into()])
+        .row(vec!["Banana".into()])
+        .row(vec!["Cherry".into()])
+        .width(360)
+        .height(300)
+}
+
+#[test]
+fn entry_returns_typed_text_on_enter() {
+    let mut headless = Headless::new([TestEvent::Type("Ada".into()), TestEvent::ENTER]);
+    let result = headless
+        .run(|| zenity_rs::entry().text("Name:").show())
+        .unwrap();
+    assert!(matches!(&result, EntryResult::Text(name) if name == "Ada"));
+    assert_eq!(result.exit_code(), 0);
+}
+
+#[test]
+fn entry_edits_before_enter() {
+    let mut headless = Headless::new([
+        TestEvent::Type("Adx".into()),
+        TestEvent::BACKSPACE,
+        TestEvent::Type("a".into()),
+        TestEvent::ENTER,
+    ]);
+    let result = headless
+        .run(|| zenity_rs::entry().entry_text("").show())
+        .unwrap();
+    assert!(matches!(&result, EntryResult::Text(name) if name == "Ada"));
+}
+
+#[test]
+fn entry_is_cancelled_by_escape() {
+    let mut headless = Headless::new([TestEvent::Type("Ada".into()), TestEvent::ESCAPE]);
+    let result = headless.run(|| zenity_rs::entry().show()).unwrap();
+    assert!(matches!(result, EntryResult::Cancelled));
+    assert_eq!(result.exit_code(), 1);
+}
+
+#[test]
+fn entry_is_closed_when_the_script_runs_out() {
+    let mut headless = Headless::new([TestEvent::Type("Ada".into())]);
+    let result = headless.run(|| zenity_rs::entry().show()).unwrap();
+    assert!(matches!(result, EntryResult::Closed));
+    assert_eq!(result.exit_code(), 255);
+}
+
+#[test]
+fn list_selects_with_arrow_keys() {
+    let mut headless = Headless::new([TestEvent::DOWN, TestEvent::DOWN, TestEvent::ENTER]);
+    let result = headless.run(|| fruit().show()).unwrap();
+    assert!(
+        matches!(&result, ListResult::Selected(rows) if rows == &["Banana"]),
+        "{result:?}"
+    );
+    assert_eq!(result.exit_code(), 0);
+}
+
+#[test]
+fn list_selects_a_clicked_row() {
+    let mut headless = Headless::new([TestEvent::Click(60, 151), TestEvent::ENTER]);
+    let result = headless.run(|| fruit().show()).unwrap();
+    assert!(
+        matches!(&result, ListResult::Selected(rows) if rows == &["Banana"]),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn checklist_returns_every_checked_row() {
+    // Click the boxes of the first two rows
+    let mut headless = Headless::new([
+        TestEvent::Click(32, 58),
+        TestEvent::Click(32, 86),
+        TestEvent::ENTER,
+    ]);
+    let result = headless
+        .run(|| {
+            ListBuilder::new()
+                .mode(ListMode::Checklist)
+                .column("")
+                .column("Name")
+                .row(vec!["FALSE".into(), "Apple".into()])
+                .row(vec!["FALSE".into(), "Banana".into()])
+                .row(vec!["FALSE".into(), "Cherry".into()])
+                .width(360)
+                .height(300)
+                .show()
+        })
+        .unwrap();
+    assert!(
+        matches!(&result, ListResult::Selected(rows) if rows == &["Apple", "Banana"]),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn list_is_cancelled_by_escape() {
+    let mut headless = Headless::new([TestEvent::DOWN, TestEvent::ESCAPE]);
+    let result = headless.run(|| fruit().show()).unwrap();
+    assert!(matches!(result, ListResult::Cancelled), "{result:?}");
+    assert_eq!(result.exit_code(), 1);
+}
+
+#[test]
+fn scale_starts_at_its_minimum_without_a_value() {
+    let mut headless = Headless::new([TestEvent::ENTER]);
+    let result = headless
+        .run(|| zenity_rs::scale().min_value(10).max_value(20).show())
+        .unwrap();
+    assert!(matches!(result, ScaleResult::Value(10)), "{result:?}");
+
+    let mut headless = Headless::new([TestEvent::RIGHT, TestEvent::ENTER]);
+    let result = headless
+        .run(|| zenity_rs::scale().min_value(10).show())
+        .unwrap();
+    assert!(matches!(result, ScaleResult::Value(11)), "{result:?}");
+}
+
+#[test]
+fn scale_rejects_a_value_out_of_range() {
+    let result = zenity_rs::scale().min_value(10).value(5).validate();
+    assert!(matches!(result, Err(zenity_rs::Error::InvalidConfig(_))));
+}
+
+#[test]
+fn question_answers_with_its_buttons() {
+    let question = || zenity_rs::question("Apply the update now?").show();
+
+    let mut headless = Headless::new([TestEvent::ENTER]);
+    let yes = headless.run(question).unwrap();
+    assert_eq!(yes, DialogResult::Button(0));
+    assert_eq!(yes.exit_code(), 0);
+
+    let mut headless = Headless::new([TestEvent::ESCAPE]);
+    let no = headless.run(question).unwrap();
+    assert_eq!(no.exit_code(), 1);
+
+    // No comes first along the bottom, so Tab reaches it first
+    let mut headless = Headless::new([TestEvent::TAB, TestEvent::SPACE]);
+    let no = headless.run(question).unwrap();
+    assert_eq!(no, DialogResult::Button(1));
+}
+
+#[test]
+fn info_is_dismissed_with_ok() {
+    let mut headless = Headless::new([TestEvent::ENTER]);
+    let result = headless.run(|| zenity_rs::info("Done.").show()).unwrap();
+    assert_eq!(result, DialogResult::Button(0));
+
+    let mut headless = Headless::new([TestEvent::Close]);
+    let result = headless.run(|| zenity_rs::info("Done.").show()).unwrap();
+    assert_eq!(result, DialogResult::Closed);
+    assert_eq!(result.exit_code(), 255);
+}
+
+#[test]
+fn dialogs_draw_their_last_frame() {
+    let mut headless = Headless::new([TestEvent::ENTER]).scale(2.0);
+    headless.run(|| zenity_rs::info("Done.").show()).unwrap();
+    let frame = headless.last_frame().unwrap();
+    assert!(frame.width() > 0 && frame.height() > 0);
+    assert!(frame.pixel(frame.width() / 2, frame.height() / 2).is_some());
+}